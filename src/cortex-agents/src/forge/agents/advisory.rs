@@ -0,0 +1,1090 @@
+//! Offline dependency advisory scanning.
+//!
+//! Loads advisories from a local mirror of an advisory database and matches
+//! them against the packages pinned in a project's lockfiles. Two database
+//! formats are understood, and may be mixed in one directory tree:
+//!
+//! - **RustSec advisory-db**: `crates/<name>/RUSTSEC-*.md` files with a TOML
+//!   front matter block (older `.toml` advisories are read too)
+//! - **OSV**: JSON records as exported by osv.dev, one per file or as an array
+//!
+//! Supported lockfiles are `Cargo.lock`, `package-lock.json`, `poetry.lock`
+//! and `go.sum`.
+
+use serde::Deserialize;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use super::AgentError;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// Lockfiles that are scanned for pinned packages.
+pub const LOCKFILES: &[&str] = &["Cargo.lock", "package-lock.json", "poetry.lock", "go.sum"];
+
+/// Directories never searched for lockfiles.
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor", ".git"];
+
+/// Maximum directory depth searched for lockfiles.
+const MAX_LOCKFILE_DEPTH: usize = 8;
+
+// ============================================================================
+// Packages
+// ============================================================================
+
+/// Package ecosystem, named as in OSV.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Ecosystem {
+    /// Rust crates from crates.io.
+    CratesIo,
+    /// npm packages.
+    Npm,
+    /// Python packages from PyPI.
+    PyPI,
+    /// Go modules.
+    Go,
+}
+
+impl Ecosystem {
+    /// Parse an OSV ecosystem name.
+    pub fn from_osv(name: &str) -> Option<Self> {
+        // Some ecosystems carry a suffix such as "Debian:11"
+        match name.split(':').next()? {
+            "crates.io" => Some(Self::CratesIo),
+            "npm" => Some(Self::Npm),
+            "PyPI" => Some(Self::PyPI),
+            "Go" => Some(Self::Go),
+            _ => None,
+        }
+    }
+
+    /// OSV name of the ecosystem.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::CratesIo => "crates.io",
+            Self::Npm => "npm",
+            Self::PyPI => "PyPI",
+            Self::Go => "Go",
+        }
+    }
+
+    /// Normalize a package name for lookups.
+    fn normalize_name(&self, name: &str) -> String {
+        match self {
+            // PEP 503: case-insensitive, runs of -_. are equivalent
+            Self::PyPI => {
+                let mut normalized = String::with_capacity(name.len());
+                for c in name.chars() {
+                    if matches!(c, '-' | '_' | '.') {
+                        if !normalized.ends_with('-') {
+                            normalized.push('-');
+                        }
+                    } else {
+                        normalized.push(c.to_ascii_lowercase());
+                    }
+                }
+                normalized
+            }
+            _ => name.to_string(),
+        }
+    }
+}
+
+/// A package version pinned by a lockfile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LockedPackage {
+    /// Package ecosystem.
+    pub ecosystem: Ecosystem,
+    /// Package name.
+    pub name: String,
+    /// Pinned version.
+    pub version: String,
+    /// Lockfile the package was found in.
+    pub lockfile: PathBuf,
+}
+
+/// Find lockfiles under `root` and return every package they pin.
+pub async fn scan_lockfiles(root: &Path) -> Result<Vec<LockedPackage>, AgentError> {
+    let mut packages = Vec::new();
+    let mut dirs = vec![(root.to_path_buf(), 0)];
+
+    while let Some((dir, depth)) = dirs.pop() {
+        let mut entries = match fs::read_dir(&dir).await {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if entry.file_type().await?.is_dir() {
+                if depth < MAX_LOCKFILE_DEPTH
+                    && !name.starts_with('.')
+                    && !SKIPPED_DIRS.contains(&name.as_str())
+                {
+                    dirs.push((path, depth + 1));
+                }
+                continue;
+            }
+
+            if !LOCKFILES.contains(&name.as_str()) {
+                continue;
+            }
+            let content = fs::read_to_string(&path).await?;
+            let pinned = match parse_lockfile(&name, &content) {
+                Ok(pinned) => pinned,
+                Err(e) => {
+                    tracing::warn!("Skipping unreadable lockfile {}: {}", path.display(), e);
+                    continue;
+                }
+            };
+            packages.extend(
+                pinned
+                    .into_iter()
+                    .map(|(ecosystem, name, version)| LockedPackage {
+                        ecosystem,
+                        name,
+                        version,
+                        lockfile: path.clone(),
+                    }),
+            );
+        }
+    }
+
+    Ok(packages)
+}
+
+/// Parse a lockfile by file name into `(ecosystem, name, version)` entries.
+pub fn parse_lockfile(
+    file_name: &str,
+    content: &str,
+) -> Result<Vec<(Ecosystem, String, String)>, AgentError> {
+    let (ecosystem, pinned) = match file_name {
+        "Cargo.lock" => (Ecosystem::CratesIo, parse_cargo_lock(content)?),
+        "package-lock.json" => (Ecosystem::Npm, parse_package_lock(content)?),
+        "poetry.lock" => (Ecosystem::PyPI, parse_poetry_lock(content)?),
+        "go.sum" => (Ecosystem::Go, parse_go_sum(content)),
+        other => {
+            return Err(AgentError::Config(format!(
+                "Unsupported lockfile: {}",
+                other
+            )))
+        }
+    };
+    Ok(pinned
+        .into_iter()
+        .map(|(name, version)| (ecosystem, name, version))
+        .collect())
+}
+
+#[derive(Deserialize)]
+struct TomlLock {
+    #[serde(default)]
+    package: Vec<TomlLockPackage>,
+}
+
+#[derive(Deserialize)]
+struct TomlLockPackage {
+    name: String,
+    version: String,
+    #[serde(default)]
+    source: Option<String>,
+}
+
+/// Registry packages in a `Cargo.lock`.
+fn parse_cargo_lock(content: &str) -> Result<Vec<(String, String)>, AgentError> {
+    let lock: TomlLock = toml::from_str(content).map_err(|e| AgentError::Config(e.to_string()))?;
+    Ok(lock
+        .package
+        .into_iter()
+        // Workspace members and path dependencies have no source
+        .filter(|p| p.source.is_some())
+        .map(|p| (p.name, p.version))
+        .collect())
+}
+
+/// Packages in a `poetry.lock`.
+fn parse_poetry_lock(content: &str) -> Result<Vec<(String, String)>, AgentError> {
+    let lock: TomlLock = toml::from_str(content).map_err(|e| AgentError::Config(e.to_string()))?;
+    Ok(lock
+        .package
+        .into_iter()
+        .map(|p| (p.name, p.version))
+        .collect())
+}
+
+/// Packages in a `package-lock.json` (lockfile versions 1 to 3).
+fn parse_package_lock(content: &str) -> Result<Vec<(String, String)>, AgentError> {
+    let lock: serde_json::Value = serde_json::from_str(content)?;
+    let mut packages = Vec::new();
+
+    if let Some(entries) = lock.get("packages").and_then(|p| p.as_object()) {
+        // v2/v3: keys are install paths such as "node_modules/a/node_modules/b"
+        for (path, entry) in entries {
+            let Some((_, name)) = path.rsplit_once("node_modules/") else {
+                continue;
+            };
+            if entry.get("link").and_then(|l| l.as_bool()) == Some(true) {
+                continue;
+            }
+            if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+                packages.push((name.to_string(), version.to_string()));
+            }
+        }
+    } else if let Some(deps) = lock.get("dependencies") {
+        // v1: nested dependency tree
+        collect_v1_dependencies(deps, &mut packages);
+    }
+
+    packages.sort();
+    packages.dedup();
+    Ok(packages)
+}
+
+fn collect_v1_dependencies(deps: &serde_json::Value, packages: &mut Vec<(String, String)>) {
+    let Some(deps) = deps.as_object() else {
+        return;
+    };
+    for (name, entry) in deps {
+        if let Some(version) = entry.get("version").and_then(|v| v.as_str()) {
+            packages.push((name.clone(), version.to_string()));
+        }
+        if let Some(nested) = entry.get("dependencies") {
+            collect_v1_dependencies(nested, packages);
+        }
+    }
+}
+
+/// Modules in a `go.sum`.
+fn parse_go_sum(content: &str) -> Vec<(String, String)> {
+    let mut modules: Vec<(String, String)> = content
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            let module = parts.next()?;
+            let version = parts.next()?.trim_end_matches("/go.mod");
+            Some((module.to_string(), version.to_string()))
+        })
+        .collect();
+    modules.sort();
+    modules.dedup();
+    modules
+}
+
+// ============================================================================
+// Advisories
+// ============================================================================
+
+/// A boundary in an OSV range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Event {
+    Introduced(String),
+    Fixed(String),
+    LastAffected(String),
+}
+
+impl Event {
+    fn version(&self) -> &str {
+        match self {
+            Self::Introduced(v) | Self::Fixed(v) | Self::LastAffected(v) => v,
+        }
+    }
+}
+
+/// Which versions of a package an advisory affects.
+#[derive(Debug, Clone)]
+enum Affected {
+    /// RustSec: every version not matched by a patched or unaffected requirement.
+    Requirements(Vec<semver::VersionReq>),
+    /// OSV: versions inside any range, or listed explicitly.
+    Ranges {
+        ranges: Vec<Vec<Event>>,
+        versions: Vec<String>,
+    },
+}
+
+/// A security advisory for one package.
+#[derive(Debug, Clone)]
+pub struct Advisory {
+    /// Advisory ID, e.g. `RUSTSEC-2022-0013` or `GHSA-...`.
+    pub id: String,
+    /// Other IDs for the same issue, e.g. CVEs.
+    pub aliases: Vec<String>,
+    /// One-line summary.
+    pub summary: String,
+    /// Affected package ecosystem.
+    pub ecosystem: Ecosystem,
+    /// Affected package name.
+    pub package: String,
+    /// Versions or requirements that fix the issue.
+    pub fixed: Vec<String>,
+    /// Informational kind (e.g. `unmaintained`) for non-vulnerability notices.
+    pub informational: Option<String>,
+    affected: Affected,
+}
+
+impl Advisory {
+    /// Whether `version` of the package is affected.
+    pub fn affects(&self, version: &str) -> bool {
+        match &self.affected {
+            Affected::Requirements(safe) => match parse_semver(version) {
+                Some(v) => !safe.iter().any(|req| req.matches(&v)),
+                None => false,
+            },
+            Affected::Ranges { ranges, versions } => {
+                versions.iter().any(|v| v == version)
+                    || ranges
+                        .iter()
+                        .any(|events| in_range(self.ecosystem, events, version))
+            }
+        }
+    }
+}
+
+/// Evaluate OSV range events for a version.
+fn in_range(ecosystem: Ecosystem, events: &[Event], version: &str) -> bool {
+    let mut sorted: Vec<&Event> = events.iter().collect();
+    sorted.sort_by(|a, b| compare_event_versions(ecosystem, a.version(), b.version()));
+
+    let mut affected = false;
+    for event in sorted {
+        match event {
+            Event::Introduced(v) => {
+                if v == "0" || compare_versions(ecosystem, version, v) != Some(Ordering::Less) {
+                    affected = true;
+                }
+            }
+            Event::Fixed(v) => {
+                if compare_versions(ecosystem, version, v) != Some(Ordering::Less) {
+                    affected = false;
+                }
+            }
+            Event::LastAffected(v) => {
+                if compare_versions(ecosystem, version, v) == Some(Ordering::Greater) {
+                    affected = false;
+                }
+            }
+        }
+    }
+    affected
+}
+
+fn compare_event_versions(ecosystem: Ecosystem, a: &str, b: &str) -> Ordering {
+    match (a, b) {
+        ("0", "0") => Ordering::Equal,
+        ("0", _) => Ordering::Less,
+        (_, "0") => Ordering::Greater,
+        _ => compare_versions(ecosystem, a, b).unwrap_or(Ordering::Equal),
+    }
+}
+
+/// A local advisory database, indexed by package.
+#[derive(Debug, Default)]
+pub struct AdvisoryDatabase {
+    advisories: HashMap<(Ecosystem, String), Vec<Advisory>>,
+}
+
+impl AdvisoryDatabase {
+    /// Load every advisory under `path`, which may be a directory or a single file.
+    pub async fn load(path: &Path) -> Result<Self, AgentError> {
+        if !path.exists() {
+            return Err(AgentError::Config(format!(
+                "Advisory database not found: {}",
+                path.display()
+            )));
+        }
+
+        let mut db = Self::default();
+        let mut pending = vec![path.to_path_buf()];
+        while let Some(path) = pending.pop() {
+            if path.is_dir() {
+                let mut entries = fs::read_dir(&path).await?;
+                while let Some(entry) = entries.next_entry().await? {
+                    if !entry.file_name().to_string_lossy().starts_with('.') {
+                        pending.push(entry.path());
+                    }
+                }
+                continue;
+            }
+
+            let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+            if !matches!(extension, "md" | "toml" | "json") {
+                continue;
+            }
+            let content = fs::read_to_string(&path).await?;
+            match parse_advisories(extension, &content) {
+                Ok(advisories) => advisories.into_iter().for_each(|a| db.insert(a)),
+                Err(e) => tracing::debug!("Skipping {}: {}", path.display(), e),
+            }
+        }
+
+        Ok(db)
+    }
+
+    /// Parse advisories from in-memory files, given as `(extension, content)` pairs.
+    pub fn from_sources<'a>(
+        sources: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Result<Self, AgentError> {
+        let mut db = Self::default();
+        for (extension, content) in sources {
+            parse_advisories(extension, content)?
+                .into_iter()
+                .for_each(|a| db.insert(a));
+        }
+        Ok(db)
+    }
+
+    fn insert(&mut self, advisory: Advisory) {
+        let key = (
+            advisory.ecosystem,
+            advisory.ecosystem.normalize_name(&advisory.package),
+        );
+        let entries = self.advisories.entry(key).or_default();
+        // The same advisory may be mirrored in both formats
+        if !entries.iter().any(|a| a.id == advisory.id) {
+            entries.push(advisory);
+        }
+    }
+
+    /// Number of advisories loaded.
+    pub fn len(&self) -> usize {
+        self.advisories.values().map(Vec::len).sum()
+    }
+
+    /// Whether no advisories were loaded.
+    pub fn is_empty(&self) -> bool {
+        self.advisories.is_empty()
+    }
+
+    /// Advisories affecting a locked package.
+    pub fn matching(&self, package: &LockedPackage) -> Vec<&Advisory> {
+        let key = (
+            package.ecosystem,
+            package.ecosystem.normalize_name(&package.name),
+        );
+        self.advisories
+            .get(&key)
+            .map(|advisories| {
+                advisories
+                    .iter()
+                    .filter(|a| a.affects(&package.version))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Parse the advisories in one database file.
+fn parse_advisories(extension: &str, content: &str) -> Result<Vec<Advisory>, AgentError> {
+    match extension {
+        "md" => {
+            let (front_matter, body) = split_front_matter(content).ok_or_else(|| {
+                AgentError::Config("Missing TOML front matter in advisory".to_string())
+            })?;
+            parse_rustsec(front_matter, markdown_title(body)).map(|a| a.into_iter().collect())
+        }
+        "toml" => parse_rustsec(content, None).map(|a| a.into_iter().collect()),
+        "json" => {
+            let value: serde_json::Value = serde_json::from_str(content)?;
+            let records: Vec<OsvRecord> = if value.is_array() {
+                serde_json::from_value(value)?
+            } else {
+                vec![serde_json::from_value(value)?]
+            };
+            Ok(records
+                .into_iter()
+                .flat_map(OsvRecord::into_advisories)
+                .collect())
+        }
+        other => Err(AgentError::Config(format!(
+            "Unsupported advisory format: {}",
+            other
+        ))),
+    }
+}
+
+/// Split a RustSec Markdown advisory into its TOML block and the Markdown body.
+fn split_front_matter(content: &str) -> Option<(&str, &str)> {
+    let rest = content.trim_start().strip_prefix("```toml")?;
+    let end = rest.find("\n```")?;
+    Some((&rest[..end], &rest[end + 4..]))
+}
+
+/// First Markdown heading, used as the advisory summary.
+fn markdown_title(body: &str) -> Option<String> {
+    body.lines()
+        .find_map(|line| line.strip_prefix("# "))
+        .map(|title| title.trim().to_string())
+}
+
+#[derive(Deserialize)]
+struct RustSecFile {
+    advisory: RustSecAdvisory,
+    #[serde(default)]
+    versions: RustSecVersions,
+}
+
+#[derive(Deserialize)]
+struct RustSecAdvisory {
+    id: String,
+    package: String,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    informational: Option<String>,
+    #[serde(default)]
+    withdrawn: Option<toml::Value>,
+}
+
+#[derive(Deserialize, Default)]
+struct RustSecVersions {
+    #[serde(default)]
+    patched: Vec<String>,
+    #[serde(default)]
+    unaffected: Vec<String>,
+}
+
+/// Parse a RustSec advisory; withdrawn advisories yield `None`.
+fn parse_rustsec(
+    front_matter: &str,
+    title: Option<String>,
+) -> Result<Option<Advisory>, AgentError> {
+    let file: RustSecFile =
+        toml::from_str(front_matter).map_err(|e| AgentError::Config(e.to_string()))?;
+    if file.advisory.withdrawn.is_some() {
+        return Ok(None);
+    }
+
+    let safe = file
+        .versions
+        .patched
+        .iter()
+        .chain(&file.versions.unaffected)
+        .map(|req| {
+            semver::VersionReq::parse(req).map_err(|e| {
+                AgentError::Config(format!(
+                    "{}: invalid requirement '{}': {}",
+                    file.advisory.id, req, e
+                ))
+            })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Some(Advisory {
+        summary: file.advisory.title.or(title).unwrap_or_default(),
+        id: file.advisory.id,
+        aliases: file.advisory.aliases,
+        ecosystem: Ecosystem::CratesIo,
+        package: file.advisory.package,
+        fixed: file.versions.patched,
+        informational: file.advisory.informational,
+        affected: Affected::Requirements(safe),
+    }))
+}
+
+#[derive(Deserialize)]
+struct OsvRecord {
+    id: String,
+    #[serde(default)]
+    summary: Option<String>,
+    #[serde(default)]
+    details: Option<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    #[serde(default)]
+    withdrawn: Option<String>,
+    #[serde(default)]
+    affected: Vec<OsvAffected>,
+}
+
+#[derive(Deserialize)]
+struct OsvAffected {
+    #[serde(default)]
+    package: Option<OsvPackage>,
+    #[serde(default)]
+    ranges: Vec<OsvRange>,
+    #[serde(default)]
+    versions: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct OsvPackage {
+    ecosystem: String,
+    name: String,
+}
+
+#[derive(Deserialize)]
+struct OsvRange {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    events: Vec<OsvEvent>,
+}
+
+#[derive(Deserialize)]
+struct OsvEvent {
+    #[serde(default)]
+    introduced: Option<String>,
+    #[serde(default)]
+    fixed: Option<String>,
+    #[serde(default)]
+    last_affected: Option<String>,
+}
+
+impl OsvRecord {
+    /// One advisory per affected package in a supported ecosystem.
+    fn into_advisories(self) -> Vec<Advisory> {
+        if self.withdrawn.is_some() {
+            return Vec::new();
+        }
+
+        let summary = self
+            .summary
+            .or_else(|| {
+                self.details
+                    .as_deref()
+                    .and_then(|d| d.lines().next())
+                    .map(String::from)
+            })
+            .unwrap_or_default();
+
+        self.affected
+            .into_iter()
+            .filter_map(|affected| {
+                let package = affected.package?;
+                let ecosystem = Ecosystem::from_osv(&package.ecosystem)?;
+
+                // Git commit ranges can't be matched against lockfile versions
+                let ranges: Vec<Vec<Event>> = affected
+                    .ranges
+                    .into_iter()
+                    .filter(|r| r.kind != "GIT")
+                    .map(|r| {
+                        r.events
+                            .into_iter()
+                            .filter_map(OsvEvent::into_event)
+                            .collect()
+                    })
+                    .collect();
+                let fixed = ranges
+                    .iter()
+                    .flatten()
+                    .filter_map(|e| match e {
+                        Event::Fixed(v) => Some(v.clone()),
+                        _ => None,
+                    })
+                    .collect();
+
+                Some(Advisory {
+                    id: self.id.clone(),
+                    aliases: self.aliases.clone(),
+                    summary: summary.clone(),
+                    ecosystem,
+                    package: package.name,
+                    fixed,
+                    informational: None,
+                    affected: Affected::Ranges {
+                        ranges,
+                        versions: affected.versions,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+impl OsvEvent {
+    fn into_event(self) -> Option<Event> {
+        self.introduced
+            .map(Event::Introduced)
+            .or(self.fixed.map(Event::Fixed))
+            .or(self.last_affected.map(Event::LastAffected))
+    }
+}
+
+// ============================================================================
+// Version Comparison
+// ============================================================================
+
+/// Compare two versions using the ecosystem's ordering.
+///
+/// Crates, npm and Go use semver; PyPI uses PEP 440. Returns `None` if either
+/// version can't be parsed.
+pub fn compare_versions(ecosystem: Ecosystem, a: &str, b: &str) -> Option<Ordering> {
+    match ecosystem {
+        Ecosystem::PyPI => Some(Pep440::parse(a)?.cmp(&Pep440::parse(b)?)),
+        _ => Some(parse_semver(a)?.cmp_precedence(&parse_semver(b)?)),
+    }
+}
+
+/// Parse a semver version, tolerating Go's `v` prefix and missing components.
+fn parse_semver(version: &str) -> Option<semver::Version> {
+    let version = version.trim();
+    let version = version.strip_prefix('v').unwrap_or(version);
+    let version = version.trim_end_matches("+incompatible");
+    if let Ok(v) = semver::Version::parse(version) {
+        return Some(v);
+    }
+
+    // Pad "1" and "1.2" to three components
+    let (core, rest) = match version.find(['-', '+']) {
+        Some(i) => version.split_at(i),
+        None => (version, ""),
+    };
+    let mut parts: Vec<&str> = core.split('.').collect();
+    if parts.is_empty() || parts.len() > 3 {
+        return None;
+    }
+    parts.resize(3, "0");
+    semver::Version::parse(&format!("{}{}", parts.join("."), rest)).ok()
+}
+
+/// A PEP 440 version, ordered as specified.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Pep440 {
+    epoch: u64,
+    /// Release segments without trailing zeros.
+    release: Vec<u64>,
+    /// (phase, number): dev-only 0, alpha 1, beta 2, rc 3, final 4.
+    pre: (u8, u64),
+    /// (has post, number).
+    post: (u8, u64),
+    /// (not dev, number), so dev releases sort first.
+    dev: (u8, u64),
+}
+
+impl Pep440 {
+    fn parse(version: &str) -> Option<Self> {
+        let version = version.trim().to_ascii_lowercase();
+        let version = version.strip_prefix('v').unwrap_or(&version);
+        // Local versions (+local) don't affect advisory matching
+        let version = version.split('+').next()?;
+
+        let (epoch, rest) = match version.split_once('!') {
+            Some((epoch, rest)) => (epoch.parse().ok()?, rest),
+            None => (0, version),
+        };
+
+        let release_end = rest
+            .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+            .unwrap_or(rest.len());
+        let mut release: Vec<u64> = rest[..release_end]
+            .trim_end_matches('.')
+            .split('.')
+            .map(|part| part.parse().ok())
+            .collect::<Option<_>>()?;
+        while release.len() > 1 && release.last() == Some(&0) {
+            release.pop();
+        }
+
+        let mut pre = None;
+        let mut post = None;
+        let mut dev = None;
+        let mut suffix = &rest[release_end..];
+        while !suffix.is_empty() {
+            suffix = suffix.trim_start_matches(['.', '-', '_']);
+            let label_end = suffix
+                .find(|c: char| !c.is_ascii_alphabetic())
+                .unwrap_or(suffix.len());
+            let (label, after) = suffix.split_at(label_end);
+            let after = after.trim_start_matches(['.', '-', '_']);
+            let number_end = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            let number = after[..number_end].parse().unwrap_or(0);
+            suffix = &after[number_end..];
+
+            match label {
+                "a" | "alpha" => pre = Some((1, number)),
+                "b" | "beta" => pre = Some((2, number)),
+                "rc" | "c" | "pre" | "preview" => pre = Some((3, number)),
+                "post" | "rev" | "r" => post = Some(number),
+                // "1.0-1" is an implicit post release
+                "" if number_end > 0 => post = Some(number),
+                "dev" => dev = Some(number),
+                _ => return None,
+            }
+        }
+
+        let pre = match (pre, post, dev) {
+            (Some(pre), _, _) => pre,
+            (None, None, Some(_)) => (0, 0),
+            _ => (4, 0),
+        };
+        Some(Self {
+            epoch,
+            release,
+            pre,
+            post: post.map_or((0, 0), |n| (1, n)),
+            dev: dev.map_or((1, 0), |n| (0, n)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RUSTSEC_REGEX: &str = r#"```toml
+[advisory]
+id = "RUSTSEC-2022-0013"
+package = "regex"
+date = "2022-03-08"
+aliases = ["CVE-2022-24713", "GHSA-m5pq-gvj9-9vr8"]
+
+[versions]
+patched = [">= 1.5.5"]
+```
+
+# Regexes with large repetitions on empty sub-expressions take a very long time to parse
+"#;
+
+    const OSV_LODASH: &str = r#"{
+  "id": "GHSA-jf85-cpcp-j695",
+  "summary": "Prototype Pollution in lodash",
+  "aliases": ["CVE-2019-10744"],
+  "affected": [{
+    "package": {"ecosystem": "npm", "name": "lodash"},
+    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "4.17.12"}]}]
+  }]
+}"#;
+
+    #[test]
+    fn test_parse_lockfiles() {
+        let cargo = r#"
+version = 3
+
+[[package]]
+name = "my-app"
+version = "0.1.0"
+
+[[package]]
+name = "regex"
+version = "1.5.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#;
+        let pinned = parse_lockfile("Cargo.lock", cargo).unwrap();
+        assert_eq!(
+            pinned,
+            vec![(
+                Ecosystem::CratesIo,
+                "regex".to_string(),
+                "1.5.4".to_string()
+            )]
+        );
+
+        let npm_v3 = r#"{"lockfileVersion": 3, "packages": {
+            "": {"name": "app", "version": "1.0.0"},
+            "node_modules/lodash": {"version": "4.17.11"},
+            "node_modules/a/node_modules/@scope/b": {"version": "2.0.0"},
+            "node_modules/local": {"link": true}
+        }}"#;
+        let pinned = parse_package_lock(npm_v3).unwrap();
+        assert_eq!(
+            pinned,
+            vec![
+                ("@scope/b".to_string(), "2.0.0".to_string()),
+                ("lodash".to_string(), "4.17.11".to_string()),
+            ]
+        );
+
+        let npm_v1 = r#"{"lockfileVersion": 1, "dependencies": {
+            "a": {"version": "1.0.0", "dependencies": {"b": {"version": "0.5.0"}}}
+        }}"#;
+        assert_eq!(parse_package_lock(npm_v1).unwrap().len(), 2);
+
+        let poetry = r#"
+[[package]]
+name = "Django"
+version = "3.2.1"
+
+[metadata]
+lock-version = "2.0"
+"#;
+        assert_eq!(
+            parse_poetry_lock(poetry).unwrap(),
+            vec![("Django".to_string(), "3.2.1".to_string())]
+        );
+
+        let go_sum = "golang.org/x/text v0.3.7 h1:abc=\n\
+                      golang.org/x/text v0.3.7/go.mod h1:def=\n";
+        assert_eq!(
+            parse_go_sum(go_sum),
+            vec![("golang.org/x/text".to_string(), "v0.3.7".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_rustsec_advisory() {
+        let db = AdvisoryDatabase::from_sources([("md", RUSTSEC_REGEX)]).unwrap();
+        assert_eq!(db.len(), 1);
+
+        let package = |version: &str| LockedPackage {
+            ecosystem: Ecosystem::CratesIo,
+            name: "regex".to_string(),
+            version: version.to_string(),
+            lockfile: PathBuf::from("Cargo.lock"),
+        };
+        let matches = db.matching(&package("1.5.4"));
+        assert_eq!(matches.len(), 1);
+        assert_eq!(matches[0].id, "RUSTSEC-2022-0013");
+        assert_eq!(matches[0].fixed, vec![">= 1.5.5"]);
+        assert!(matches[0]
+            .summary
+            .starts_with("Regexes with large repetitions"));
+
+        assert!(db.matching(&package("1.5.5")).is_empty());
+        assert!(db.matching(&package("1.10.0")).is_empty());
+    }
+
+    #[test]
+    fn test_rustsec_unaffected_and_withdrawn() {
+        let advisory = r#"
+[advisory]
+id = "RUSTSEC-2020-0001"
+package = "example"
+
+[versions]
+patched = [">= 0.3.1, < 0.4.0", ">= 0.4.2"]
+unaffected = ["< 0.2.0"]
+"#;
+        let db = AdvisoryDatabase::from_sources([("toml", advisory)]).unwrap();
+        let advisory = &db.advisories[&(Ecosystem::CratesIo, "example".to_string())][0];
+        assert!(!advisory.affects("0.1.9"));
+        assert!(advisory.affects("0.2.0"));
+        assert!(advisory.affects("0.3.0"));
+        assert!(!advisory.affects("0.3.5"));
+        assert!(advisory.affects("0.4.1"));
+        assert!(!advisory.affects("0.4.2"));
+
+        let withdrawn = r#"
+[advisory]
+id = "RUSTSEC-2020-0002"
+package = "example"
+withdrawn = "2020-02-01"
+"#;
+        let db = AdvisoryDatabase::from_sources([("toml", withdrawn)]).unwrap();
+        assert!(db.is_empty());
+    }
+
+    #[test]
+    fn test_osv_ranges() {
+        let record = r#"[{
+  "id": "OSV-1",
+  "affected": [{
+    "package": {"ecosystem": "crates.io", "name": "example"},
+    "ranges": [
+      {"type": "SEMVER", "events": [{"introduced": "1.0.0"}, {"fixed": "1.2.0"}, {"introduced": "2.0.0"}, {"last_affected": "2.1.3"}]},
+      {"type": "GIT", "events": [{"introduced": "abc123"}]}
+    ],
+    "versions": ["0.9.1"]
+  }]
+}]"#;
+        let db = AdvisoryDatabase::from_sources([("json", record)]).unwrap();
+        let advisory = &db.advisories[&(Ecosystem::CratesIo, "example".to_string())][0];
+        assert!(!advisory.affects("0.9.0"));
+        assert!(advisory.affects("0.9.1"));
+        assert!(advisory.affects("1.0.0"));
+        assert!(advisory.affects("1.1.9"));
+        assert!(!advisory.affects("1.2.0"));
+        assert!(!advisory.affects("1.10.0"));
+        assert!(advisory.affects("2.1.3"));
+        assert!(!advisory.affects("2.1.4"));
+        assert_eq!(advisory.fixed, vec!["1.2.0"]);
+    }
+
+    #[test]
+    fn test_duplicate_advisories_are_merged() {
+        let osv_regex = r#"{
+  "id": "RUSTSEC-2022-0013",
+  "affected": [{
+    "package": {"ecosystem": "crates.io", "name": "regex"},
+    "ranges": [{"type": "SEMVER", "events": [{"introduced": "0.0.0-0"}, {"fixed": "1.5.5"}]}]
+  }]
+}"#;
+        let db = AdvisoryDatabase::from_sources([
+            ("md", RUSTSEC_REGEX),
+            ("json", osv_regex),
+            ("json", OSV_LODASH),
+        ])
+        .unwrap();
+        assert_eq!(db.len(), 2);
+    }
+
+    #[test]
+    fn test_pypi_names_and_versions() {
+        let record = r#"{
+  "id": "PYSEC-2021-1",
+  "affected": [{
+    "package": {"ecosystem": "PyPI", "name": "Django"},
+    "ranges": [{"type": "ECOSYSTEM", "events": [{"introduced": "3.2"}, {"fixed": "3.2.2"}]}]
+  }]
+}"#;
+        let db = AdvisoryDatabase::from_sources([("json", record)]).unwrap();
+        let package = |name: &str, version: &str| LockedPackage {
+            ecosystem: Ecosystem::PyPI,
+            name: name.to_string(),
+            version: version.to_string(),
+            lockfile: PathBuf::from("poetry.lock"),
+        };
+        assert_eq!(db.matching(&package("django", "3.2.1")).len(), 1);
+        assert_eq!(db.matching(&package("Django", "3.2.2rc1")).len(), 1);
+        assert!(db.matching(&package("django", "3.2.2")).is_empty());
+        assert!(db.matching(&package("django", "3.2rc1")).is_empty());
+
+        let cmp = |a, b| compare_versions(Ecosystem::PyPI, a, b);
+        assert_eq!(cmp("1.0", "1.0.0"), Some(Ordering::Equal));
+        assert_eq!(cmp("1.0.dev1", "1.0a1"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0a1", "1.0b1"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0rc1", "1.0"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0", "1.0.post1"), Some(Ordering::Less));
+        assert_eq!(cmp("1.0-1", "1.0.post1"), Some(Ordering::Equal));
+        assert_eq!(cmp("1!0.5", "2.0"), Some(Ordering::Greater));
+        assert_eq!(cmp("1.0.nonsense", "1.0"), None);
+    }
+
+    #[test]
+    fn test_go_versions() {
+        let cmp = |a, b| compare_versions(Ecosystem::Go, a, b);
+        assert_eq!(cmp("v0.3.7", "0.3.8"), Some(Ordering::Less));
+        assert_eq!(cmp("v2.0.0+incompatible", "2.0.0"), Some(Ordering::Equal));
+        assert_eq!(
+            cmp("v0.0.0-20210220033148-5ea612d1eb83", "0.0.0"),
+            Some(Ordering::Less)
+        );
+        assert_eq!(cmp("1.2", "1.2.0"), Some(Ordering::Equal));
+    }
+
+    #[tokio::test]
+    async fn test_scan_lockfiles() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("web/node_modules/x")).unwrap();
+        std::fs::write(
+            dir.path().join("web/package-lock.json"),
+            r#"{"packages": {"node_modules/lodash": {"version": "4.17.11"}}}"#,
+        )
+        .unwrap();
+        // Lockfiles of installed packages are not part of the project
+        std::fs::write(
+            dir.path().join("web/node_modules/x/package-lock.json"),
+            r#"{"packages": {"node_modules/left-pad": {"version": "1.0.0"}}}"#,
+        )
+        .unwrap();
+
+        let packages = scan_lockfiles(dir.path()).await.unwrap();
+        assert_eq!(packages.len(), 1);
+        assert_eq!(packages[0].name, "lodash");
+        assert_eq!(packages[0].ecosystem, Ecosystem::Npm);
+        assert_eq!(
+            packages[0].lockfile,
+            dir.path().join("web/package-lock.json")
+        );
+    }
+}
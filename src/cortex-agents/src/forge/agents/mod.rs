@@ -20,12 +20,14 @@
 //! let result = agent.validate(&ctx).await?;
 //! ```
 
+pub mod advisory;
 pub mod aggregator;
 pub mod dynamic;
 pub mod quality;
 pub mod security;
 pub mod utils;
 
+pub use advisory::{AdvisoryDatabase, Ecosystem, LockedPackage};
 pub use aggregator::AggregatorAgent;
 pub use dynamic::{create_agent_from_toml, create_agent_from_toml_str, DynamicAgent};
 pub use quality::QualityAgent;
@@ -2,17 +2,20 @@
 //!
 //! Performs security-focused code analysis including:
 //! - Detection of hardcoded secrets and API keys
-//! - Dependency vulnerability auditing
+//! - Dependency vulnerability auditing against a local RustSec or OSV
+//!   advisory database (`advisory_db` setting)
 //! - Unsafe code block analysis
 //! - Input validation pattern checking
 
 use async_trait::async_trait;
-use std::path::Path;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
 use std::time::Instant;
 use tokio::fs;
 use tokio::io::AsyncBufReadExt;
 
 use super::{
+    advisory::{scan_lockfiles, AdvisoryDatabase},
     utils::{collect_source_files, truncate_line},
     AgentError, Finding, RuleInfo, Severity, ValidationAgent, ValidationContext, ValidationResult,
 };
@@ -57,7 +60,7 @@ const ENCODED_SECRET_PATTERNS: &[&str] = &[
     "ghr_", // GitHub refresh token
 ];
 
-/// Known vulnerable crate patterns (simplified - in production would use advisory database).
+/// Known vulnerable crate patterns, used when no advisory database is configured.
 const VULNERABLE_CRATES: &[(&str, &str, &str)] = &[
     ("chrono", "<0.4.20", "RUSTSEC-2020-0159: Potential segfault"),
     (
@@ -79,6 +82,10 @@ const VULNERABLE_CRATES: &[(&str, &str, &str)] = &[
 /// - `dependencies_audit`: Known vulnerable dependencies
 /// - `unsafe_code`: Unsafe blocks without justification
 /// - `input_validation`: Basic input validation patterns
+///
+/// Set the `advisory_db` setting to the path of a local RustSec advisory-db
+/// or OSV mirror to audit `Cargo.lock`, `package-lock.json`, `poetry.lock` and
+/// `go.sum` against it. Relative paths resolve against the project root.
 #[derive(Debug, Clone)]
 pub struct SecurityAgent {
     /// Agent identifier.
@@ -210,6 +217,14 @@ impl SecurityAgent {
         }
 
         let severity = ctx.config.get_severity(rule_id, Severity::Warning);
+
+        if let Some(db_path) = ctx.config.get_setting::<PathBuf>("advisory_db") {
+            let db_path = ctx.project_path.join(db_path);
+            return self
+                .check_against_advisory_db(ctx, &db_path, rule_id, severity, findings)
+                .await;
+        }
+
         let cargo_lock = ctx.project_path.join("Cargo.lock");
 
         if !cargo_lock.exists() {
@@ -239,6 +254,78 @@ impl SecurityAgent {
         Ok(())
     }
 
+    /// Audit every lockfile in the project against a local advisory database.
+    async fn check_against_advisory_db(
+        &self,
+        ctx: &ValidationContext,
+        db_path: &Path,
+        rule_id: &str,
+        severity: Severity,
+        findings: &mut Vec<Finding>,
+    ) -> Result<(), AgentError> {
+        let db = AdvisoryDatabase::load(db_path).await?;
+        if db.is_empty() {
+            tracing::warn!("No advisories found in {}", db_path.display());
+        }
+
+        let mut reported = HashSet::new();
+        for package in scan_lockfiles(&ctx.project_path).await? {
+            for advisory in db.matching(&package) {
+                if !reported.insert((
+                    package.lockfile.clone(),
+                    package.name.clone(),
+                    package.version.clone(),
+                    advisory.id.clone(),
+                )) {
+                    continue;
+                }
+
+                let mut ids = vec![advisory.id.clone()];
+                ids.extend(advisory.aliases.iter().cloned());
+                let kind = match &advisory.informational {
+                    Some(kind) => format!("{} dependency", capitalize(kind)),
+                    None => "Vulnerable dependency".to_string(),
+                };
+                let mut message = format!(
+                    "{}: {} {} ({})",
+                    kind,
+                    package.name,
+                    package.version,
+                    ids.join(", ")
+                );
+                if !advisory.summary.is_empty() {
+                    message.push_str(": ");
+                    message.push_str(&advisory.summary);
+                }
+                let suggestion = if advisory.fixed.is_empty() {
+                    format!(
+                        "No fixed version of {} is available; consider replacing it",
+                        package.name
+                    )
+                } else {
+                    format!(
+                        "Update {} to a fixed version: {}",
+                        package.name,
+                        advisory.fixed.join(", ")
+                    )
+                };
+                let severity = if advisory.informational.is_some() {
+                    Severity::Info
+                } else {
+                    severity
+                };
+
+                findings.push(
+                    Finding::new(rule_id, severity, message)
+                        .at_file(&package.lockfile)
+                        .with_suggestion(suggestion),
+                );
+            }
+        }
+
+        Ok(())
+    }
+
     /// Check for unsafe code blocks without safety documentation.
     async fn check_unsafe_code(
         &self,
@@ -439,7 +526,7 @@ impl ValidationAgent for SecurityAgent {
             RuleInfo::new(
                 "dependencies_audit",
                 "Dependencies Audit",
-                "Check for known vulnerable dependencies in Cargo.lock",
+                "Check lockfiles for dependencies with known advisories",
             )
             .with_severity(Severity::Warning),
             RuleInfo::new(
@@ -581,6 +668,15 @@ fn parse_cargo_lock(content: &str) -> Vec<(String, String)> {
     deps
 }
 
+/// Uppercase the first letter of an advisory kind such as `unmaintained`.
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
 /// Check if a version matches a vulnerable version range.
 /// Uses semantic version comparison for accurate results.
 fn version_matches_vulnerable(version: &str, vulnerable_pattern: &str) -> bool {
@@ -696,6 +792,91 @@ version = "1.20.0"
         assert_eq!(compare_semver("1.0.0", "1.0"), Some(Ordering::Equal));
     }
 
+    #[tokio::test]
+    async fn test_dependencies_audit_with_advisory_db() {
+        let project = tempfile::tempdir().unwrap();
+        let advisories = project.path().join("advisory-db/crates/regex");
+        std::fs::create_dir_all(&advisories).unwrap();
+        std::fs::write(
+            advisories.join("RUSTSEC-2022-0013.md"),
+            r#"```toml
+[advisory]
+id = "RUSTSEC-2022-0013"
+package = "regex"
+aliases = ["CVE-2022-24713"]
+
+[versions]
+patched = [">= 1.5.5"]
+```
+
+# Regexes with large repetitions on empty sub-expressions take a very long time to parse
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            project.path().join("advisory-db/GHSA-jf85-cpcp-j695.json"),
+            r#"{"id": "GHSA-jf85-cpcp-j695", "summary": "Prototype Pollution in lodash",
+                "affected": [{"package": {"ecosystem": "npm", "name": "lodash"},
+                "ranges": [{"type": "SEMVER", "events": [{"introduced": "0"}, {"fixed": "4.17.12"}]}]}]}"#,
+        )
+        .unwrap();
+        std::fs::write(
+            project.path().join("Cargo.lock"),
+            r#"
+[[package]]
+name = "regex"
+version = "1.5.4"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+
+[[package]]
+name = "serde"
+version = "1.0.123"
+source = "registry+https://github.com/rust-lang/crates.io-index"
+"#,
+        )
+        .unwrap();
+        std::fs::write(
+            project.path().join("package-lock.json"),
+            r#"{"lockfileVersion": 3, "packages": {"node_modules/lodash": {"version": "4.17.11"}}}"#,
+        )
+        .unwrap();
+
+        let mut ctx = ValidationContext::new(project.path());
+        ctx.config.set_setting("advisory_db", "advisory-db");
+
+        let mut findings = Vec::new();
+        SecurityAgent::new()
+            .check_dependencies_audit(&ctx, &mut findings)
+            .await
+            .unwrap();
+        findings.sort_by(|a, b| a.message.cmp(&b.message));
+
+        assert_eq!(findings.len(), 2);
+        assert!(findings[0]
+            .message
+            .starts_with("Vulnerable dependency: lodash 4.17.11 (GHSA-jf85-cpcp-j695)"));
+        assert!(findings[1]
+            .message
+            .starts_with("Vulnerable dependency: regex 1.5.4 (RUSTSEC-2022-0013, CVE-2022-24713)"));
+        assert_eq!(
+            findings[1].suggestion.as_deref(),
+            Some("Update regex to a fixed version: >= 1.5.5")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dependencies_audit_missing_advisory_db() {
+        let project = tempfile::tempdir().unwrap();
+        let mut ctx = ValidationContext::new(project.path());
+        ctx.config.set_setting("advisory_db", "missing");
+
+        let mut findings = Vec::new();
+        let result = SecurityAgent::new()
+            .check_dependencies_audit(&ctx, &mut findings)
+            .await;
+        assert!(matches!(result, Err(AgentError::Config(_))));
+    }
+
     #[tokio::test]
     async fn test_security_agent_rules() {
         let agent = SecurityAgent::new();
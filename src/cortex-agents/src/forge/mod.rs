@@ -11,6 +11,7 @@
 //! - **AggregatorAgent**: Combines results from all agents into a final report
 //! - **ForgeOrchestrator**: DAG-based orchestration for agent execution
 //! - **ForgeConfig**: TOML-based configuration system
+//! - **ValidationReport**: SARIF and JUnit export, with baseline suppression
 //!
 //! # Example
 //!
@@ -64,6 +65,7 @@ pub mod agents;
 pub mod config;
 pub mod orchestrator;
 pub mod protocol;
+pub mod report;
 
 // Re-export commonly used types at the forge module level
 pub use agents::{
@@ -87,6 +89,9 @@ pub use orchestrator::{
     OrchestratorResult,
 };
 
+// Re-export report exporters
+pub use report::{AgentReport, Baseline, BaselineEntry, ValidationReport};
+
 use std::path::Path;
 
 /// Load all agents from a configuration directory.
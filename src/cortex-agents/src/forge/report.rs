@@ -0,0 +1,831 @@
+//! Report exporters for Forge validation results.
+//!
+//! Converts agent results into formats understood by CI systems:
+//!
+//! - **SARIF 2.1.0** for code-scanning annotations, with one run per agent
+//!   and the agent's [`RuleInfo`] list as the tool's rules
+//! - **JUnit XML** for test dashboards, with one test suite per agent and one
+//!   test case per rule
+//!
+//! A [`Baseline`] records the findings that already exist in a project. Once
+//! applied to a report, those findings are marked as suppressed: SARIF keeps
+//! them with an external suppression, JUnit and [`ValidationReport::is_success`]
+//! ignore them, so only new findings fail the build.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use cortex_agents::forge::{Baseline, SecurityAgent, ValidationAgent, ValidationContext, ValidationReport};
+//!
+//! let agent = SecurityAgent::new();
+//! let result = agent.validate(&ValidationContext::new(".")).await?;
+//!
+//! let mut report = ValidationReport::new(".");
+//! report.add_agent(&agent, result);
+//! report.apply_baseline(&Baseline::load(".cortex/forge/baseline.json").await?);
+//!
+//! std::fs::write("forge.sarif", report.to_sarif_string()?)?;
+//! std::fs::write("forge.xml", report.to_junit())?;
+//! ```
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use super::agents::{
+    AgentError, Finding, RuleInfo, Severity, ValidationAgent, ValidationResult, ValidationStatus,
+};
+use super::protocol;
+
+// ============================================================================
+// Constants
+// ============================================================================
+
+/// SARIF schema referenced by exported logs.
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Name of the SARIF tool component.
+const TOOL_NAME: &str = "cortex-forge";
+
+/// Current baseline file format version.
+const BASELINE_VERSION: u32 = 1;
+
+// ============================================================================
+// ValidationReport
+// ============================================================================
+
+/// Results of one agent, with the rules it declares.
+#[derive(Debug, Clone)]
+pub struct AgentReport {
+    /// Agent identifier.
+    pub agent_id: String,
+    /// Human-readable agent name.
+    pub agent_name: String,
+    /// Rules the agent checks.
+    pub rules: Vec<RuleInfo>,
+    /// The agent's validation result.
+    pub result: ValidationResult,
+    /// Whether each finding is suppressed by a baseline, by index.
+    suppressed: Vec<bool>,
+}
+
+impl AgentReport {
+    /// Whether the finding at `index` is suppressed by a baseline.
+    pub fn is_suppressed(&self, index: usize) -> bool {
+        self.suppressed.get(index).copied().unwrap_or(false)
+    }
+
+    /// Findings not suppressed by a baseline.
+    pub fn new_findings(&self) -> impl Iterator<Item = &Finding> {
+        self.result
+            .findings
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !self.is_suppressed(*i))
+            .map(|(_, f)| f)
+    }
+
+    /// Rule info for a rule ID, if the agent declares it.
+    fn rule(&self, rule_id: &str) -> Option<&RuleInfo> {
+        self.rules.iter().find(|r| r.id == rule_id)
+    }
+
+    /// Declared rules followed by any rule IDs only seen in findings.
+    fn all_rule_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = self.rules.iter().map(|r| r.id.clone()).collect();
+        for finding in &self.result.findings {
+            if !ids.contains(&finding.rule_id) {
+                ids.push(finding.rule_id.clone());
+            }
+        }
+        ids
+    }
+}
+
+/// Validation results from several agents, ready for export.
+#[derive(Debug, Clone)]
+pub struct ValidationReport {
+    /// Project root; finding paths are reported relative to it.
+    pub project_root: PathBuf,
+    /// Per-agent results, in the order they were added.
+    pub agents: Vec<AgentReport>,
+}
+
+impl ValidationReport {
+    /// Create an empty report for a project.
+    pub fn new(project_root: impl Into<PathBuf>) -> Self {
+        Self {
+            project_root: project_root.into(),
+            agents: Vec::new(),
+        }
+    }
+
+    /// Add an agent's result, taking rule metadata from the agent.
+    pub fn add_agent(&mut self, agent: &dyn ValidationAgent, result: ValidationResult) {
+        self.add_result(agent.name(), agent.rules(), result);
+    }
+
+    /// Add a result with explicit agent name and rules.
+    pub fn add_result(
+        &mut self,
+        agent_name: impl Into<String>,
+        rules: Vec<RuleInfo>,
+        result: ValidationResult,
+    ) {
+        self.agents.push(AgentReport {
+            agent_id: result.agent_id.clone(),
+            agent_name: agent_name.into(),
+            rules,
+            suppressed: vec![false; result.findings.len()],
+            result,
+        });
+    }
+
+    /// Build a report from an orchestrator response.
+    ///
+    /// Orchestrator findings without a rule ID are reported under the
+    /// agent's ID.
+    pub fn from_forge_response(
+        project_root: impl Into<PathBuf>,
+        response: &protocol::ForgeResponse,
+    ) -> Self {
+        let mut report = Self::new(project_root);
+        for result in &response.results {
+            let rules = result
+                .rules_applied
+                .iter()
+                .map(|r| RuleInfo::new(&r.id, &r.name, "").enabled_by_default(r.enabled))
+                .collect();
+
+            let mut converted = ValidationResult::new(&result.agent_id);
+            for finding in &result.findings {
+                converted.add_finding(convert_finding(&result.agent_id, finding));
+            }
+            if converted.status == ValidationStatus::Passed && !result.status.is_pass() {
+                converted.status = match result.status {
+                    protocol::ValidationStatus::Fail => ValidationStatus::Failed,
+                    _ => ValidationStatus::PassedWithWarnings,
+                };
+            }
+            converted.timestamp = result.timestamp;
+
+            report.add_result(&result.agent_id, rules, converted);
+        }
+        report
+    }
+
+    /// Mark findings recorded in `baseline` as suppressed.
+    ///
+    /// Each baseline entry suppresses at most one finding, so a new copy of
+    /// an existing issue is still reported. Returns the number suppressed.
+    pub fn apply_baseline(&mut self, baseline: &Baseline) -> usize {
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for entry in &baseline.findings {
+            *remaining.entry(entry.fingerprint.as_str()).or_default() += 1;
+        }
+
+        let mut suppressed = 0;
+        for i in 0..self.agents.len() {
+            // Findings may have been added to the result after it was reported
+            let len = self.agents[i].result.findings.len();
+            self.agents[i].suppressed.resize(len, false);
+            for j in 0..self.agents[i].result.findings.len() {
+                let fingerprint = self.fingerprint(&self.agents[i], j);
+                if let Some(count) = remaining.get_mut(fingerprint.as_str()) {
+                    if *count > 0 {
+                        *count -= 1;
+                        self.agents[i].suppressed[j] = true;
+                        suppressed += 1;
+                    }
+                }
+            }
+        }
+        suppressed
+    }
+
+    /// Whether no unsuppressed finding is an error or critical.
+    pub fn is_success(&self) -> bool {
+        self.agents
+            .iter()
+            .flat_map(|a| a.new_findings())
+            .all(|f| !is_failure(f.severity))
+    }
+
+    /// Number of findings not suppressed by a baseline.
+    pub fn new_finding_count(&self) -> usize {
+        self.agents.iter().map(|a| a.new_findings().count()).sum()
+    }
+
+    /// Path of a finding relative to the project root, with `/` separators.
+    fn relative_path(&self, file: &Path) -> String {
+        let relative = file.strip_prefix(&self.project_root).unwrap_or(file);
+        relative.to_string_lossy().replace('\\', "/")
+    }
+
+    /// Stable identity of a finding, independent of its line number.
+    fn fingerprint(&self, agent: &AgentReport, index: usize) -> String {
+        let finding = &agent.result.findings[index];
+        let file = finding
+            .file
+            .as_deref()
+            .map(|f| self.relative_path(f))
+            .unwrap_or_default();
+        let snippet = finding.snippet.as_deref().unwrap_or("").trim();
+        let key = [
+            agent.agent_id.as_str(),
+            &finding.rule_id,
+            &file,
+            &finding.message,
+            snippet,
+        ]
+        .join("\0");
+        format!("{:016x}", fnv1a(key.as_bytes()))
+    }
+
+    // ========================================================================
+    // SARIF
+    // ========================================================================
+
+    /// Export as a SARIF 2.1.0 log.
+    pub fn to_sarif(&self) -> Value {
+        let runs: Vec<Value> = self
+            .agents
+            .iter()
+            .map(|agent| self.sarif_run(agent))
+            .collect();
+
+        json!({
+            "$schema": SARIF_SCHEMA,
+            "version": "2.1.0",
+            "runs": runs,
+        })
+    }
+
+    /// Export as a pretty-printed SARIF 2.1.0 document.
+    pub fn to_sarif_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(&self.to_sarif())
+    }
+
+    fn sarif_run(&self, agent: &AgentReport) -> Value {
+        let rule_ids = agent.all_rule_ids();
+        let rules: Vec<Value> = rule_ids
+            .iter()
+            .map(|id| match agent.rule(id) {
+                Some(rule) => {
+                    let mut descriptor = json!({
+                        "id": rule.id,
+                        "name": rule.name,
+                        "defaultConfiguration": {
+                            "level": sarif_level(rule.default_severity),
+                            "enabled": rule.enabled_by_default,
+                        },
+                    });
+                    if !rule.description.is_empty() {
+                        descriptor["shortDescription"] = json!({ "text": rule.description });
+                    }
+                    descriptor
+                }
+                None => json!({ "id": id }),
+            })
+            .collect();
+
+        let results: Vec<Value> = agent
+            .result
+            .findings
+            .iter()
+            .enumerate()
+            .map(|(i, finding)| {
+                let mut result = json!({
+                    "ruleId": finding.rule_id,
+                    "ruleIndex": rule_ids.iter().position(|id| *id == finding.rule_id),
+                    "level": sarif_level(finding.severity),
+                    "message": { "text": finding.message },
+                    "partialFingerprints": { "cortexForge/v1": self.fingerprint(agent, i) },
+                    "properties": { "severity": finding.severity.name() },
+                });
+
+                if let Some(file) = &finding.file {
+                    let mut region = serde_json::Map::new();
+                    if let Some(line) = finding.line {
+                        region.insert("startLine".to_string(), json!(line));
+                        if let Some(column) = finding.column {
+                            region.insert("startColumn".to_string(), json!(column));
+                        }
+                    }
+                    if let Some(snippet) = &finding.snippet {
+                        region.insert("snippet".to_string(), json!({ "text": snippet }));
+                    }
+                    let mut physical = json!({
+                        "artifactLocation": {
+                            "uri": self.relative_path(file),
+                            "uriBaseId": "%SRCROOT%",
+                        },
+                    });
+                    if !region.is_empty() {
+                        physical["region"] = Value::Object(region);
+                    }
+                    result["locations"] = json!([{ "physicalLocation": physical }]);
+                }
+                if let Some(suggestion) = &finding.suggestion {
+                    result["properties"]["suggestion"] = json!(suggestion);
+                }
+                if agent.is_suppressed(i) {
+                    result["suppressions"] = json!([{
+                        "kind": "external",
+                        "justification": "Present in baseline",
+                    }]);
+                }
+                result
+            })
+            .collect();
+
+        json!({
+            "tool": {
+                "driver": {
+                    "name": TOOL_NAME,
+                    "fullName": agent.agent_name,
+                    "informationUri": "https://github.com/CortexLM/cortex",
+                    "rules": rules,
+                    "properties": { "agentId": agent.agent_id },
+                },
+            },
+            "originalUriBaseIds": {
+                "%SRCROOT%": { "uri": file_uri(&self.project_root) },
+            },
+            "results": results,
+        })
+    }
+
+    // ========================================================================
+    // JUnit
+    // ========================================================================
+
+    /// Export as JUnit XML.
+    ///
+    /// Each rule is a test case that fails when it has unsuppressed error or
+    /// critical findings. Warnings and infos are listed in `system-out`.
+    pub fn to_junit(&self) -> String {
+        let mut suites = String::new();
+        let mut total_tests = 0;
+        let mut total_failures = 0;
+        let mut total_time = 0.0;
+
+        for agent in &self.agents {
+            let time = agent.result.duration_ms as f64 / 1000.0;
+            let mut cases = String::new();
+            let mut failures = 0;
+            let rule_ids = agent.all_rule_ids();
+
+            for rule_id in &rule_ids {
+                let findings: Vec<&Finding> = agent
+                    .new_findings()
+                    .filter(|f| &f.rule_id == rule_id)
+                    .collect();
+                let name = agent
+                    .rule(rule_id)
+                    .map(|r| r.name.as_str())
+                    .unwrap_or(rule_id);
+
+                cases.push_str(&format!(
+                    "    <testcase classname=\"forge.{}\" name=\"{}\"",
+                    xml_escape(&agent.agent_id),
+                    xml_escape(rule_id)
+                ));
+                if findings.is_empty() {
+                    cases.push_str("/>\n");
+                    continue;
+                }
+                cases.push_str(">\n");
+
+                let (failing, other): (Vec<&Finding>, Vec<&Finding>) =
+                    findings.iter().partition(|f| is_failure(f.severity));
+                if let Some(worst) = failing.iter().map(|f| f.severity).max() {
+                    failures += 1;
+                    cases.push_str(&format!(
+                        "      <failure type=\"{}\" message=\"{}: {} finding(s)\">{}</failure>\n",
+                        worst.name(),
+                        xml_escape(name),
+                        failing.len(),
+                        xml_escape(&self.junit_details(&failing))
+                    ));
+                }
+                if !other.is_empty() {
+                    cases.push_str(&format!(
+                        "      <system-out>{}</system-out>\n",
+                        xml_escape(&self.junit_details(&other))
+                    ));
+                }
+                cases.push_str("    </testcase>\n");
+            }
+
+            suites.push_str(&format!(
+                "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"0\" time=\"{:.3}\" timestamp=\"{}\">\n{}  </testsuite>\n",
+                xml_escape(&agent.agent_name),
+                rule_ids.len(),
+                failures,
+                time,
+                agent.result.timestamp.format("%Y-%m-%dT%H:%M:%S"),
+                cases
+            ));
+            total_tests += rule_ids.len();
+            total_failures += failures;
+            total_time += time;
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" time=\"{:.3}\">\n{}</testsuites>\n",
+            TOOL_NAME, total_tests, total_failures, total_time, suites
+        )
+    }
+
+    /// One line per finding: location, severity and message.
+    fn junit_details(&self, findings: &[&Finding]) -> String {
+        findings
+            .iter()
+            .map(|f| {
+                let mut line = String::new();
+                if let Some(file) = &f.file {
+                    line.push_str(&self.relative_path(file));
+                    if let Some(l) = f.line {
+                        line.push_str(&format!(":{}", l));
+                    }
+                    line.push_str(": ");
+                }
+                line.push_str(&format!("[{}] {}", f.severity.name(), f.message));
+                line
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// ============================================================================
+// Baseline
+// ============================================================================
+
+/// A finding recorded in a baseline.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// Stable fingerprint of the finding.
+    pub fingerprint: String,
+    /// Agent that reported the finding.
+    pub agent_id: String,
+    /// Rule that reported the finding.
+    pub rule_id: String,
+    /// File of the finding, relative to the project root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub file: Option<String>,
+    /// Finding message, kept for readability of the baseline file.
+    pub message: String,
+}
+
+/// Findings accepted as pre-existing.
+///
+/// Fingerprints cover the agent, rule, file, message and snippet but not the
+/// line number, so findings stay suppressed when surrounding code moves.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Baseline format version.
+    pub version: u32,
+    /// Recorded findings.
+    pub findings: Vec<BaselineEntry>,
+}
+
+impl Baseline {
+    /// Record every finding in a report, suppressed or not.
+    pub fn from_report(report: &ValidationReport) -> Self {
+        let mut findings = Vec::new();
+        for agent in &report.agents {
+            for (i, finding) in agent.result.findings.iter().enumerate() {
+                findings.push(BaselineEntry {
+                    fingerprint: report.fingerprint(agent, i),
+                    agent_id: agent.agent_id.clone(),
+                    rule_id: finding.rule_id.clone(),
+                    file: finding.file.as_deref().map(|f| report.relative_path(f)),
+                    message: finding.message.clone(),
+                });
+            }
+        }
+        findings.sort_by(|a, b| {
+            (&a.file, &a.rule_id, &a.fingerprint).cmp(&(&b.file, &b.rule_id, &b.fingerprint))
+        });
+
+        Self {
+            version: BASELINE_VERSION,
+            findings,
+        }
+    }
+
+    /// Load a baseline file.
+    pub async fn load(path: impl AsRef<Path>) -> Result<Self, AgentError> {
+        let content = tokio::fs::read_to_string(path.as_ref()).await?;
+        let baseline: Self = serde_json::from_str(&content)?;
+        if baseline.version > BASELINE_VERSION {
+            return Err(AgentError::Config(format!(
+                "Unsupported baseline version {} in {}",
+                baseline.version,
+                path.as_ref().display()
+            )));
+        }
+        Ok(baseline)
+    }
+
+    /// Write the baseline file, creating parent directories.
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<(), AgentError> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let content = serde_json::to_string_pretty(self)?;
+        tokio::fs::write(path, content + "\n").await?;
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
+/// Whether a severity fails the build.
+fn is_failure(severity: Severity) -> bool {
+    matches!(severity, Severity::Error | Severity::Critical)
+}
+
+/// SARIF result level for a severity.
+fn sarif_level(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Critical | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "note",
+    }
+}
+
+/// Convert an orchestrator finding to an agent finding.
+fn convert_finding(agent_id: &str, finding: &protocol::Finding) -> Finding {
+    let severity = match finding.severity {
+        protocol::Severity::Info => Severity::Info,
+        protocol::Severity::Warning => Severity::Warning,
+        protocol::Severity::Error => Severity::Error,
+        protocol::Severity::Critical => Severity::Critical,
+    };
+    let rule_id = finding.rule_id.as_deref().unwrap_or(agent_id);
+
+    let mut converted = Finding::new(rule_id, severity, &finding.message);
+    if let Some(location) = &finding.location {
+        converted.file = Some(PathBuf::from(&location.file));
+        converted.line = location.line;
+        converted.column = location.column;
+    }
+    converted.suggestion = finding.suggestion.clone();
+    converted
+}
+
+/// `file://` URI for a directory, with a trailing slash as SARIF requires.
+///
+/// Relative directories (such as the default `.`) are made absolute first,
+/// since URIs have no notion of a working directory.
+fn file_uri(dir: &Path) -> String {
+    let dir = dir
+        .canonicalize()
+        .or_else(|_| std::path::absolute(dir))
+        .unwrap_or_else(|_| dir.to_path_buf());
+    let path = dir.to_string_lossy().replace('\\', "/");
+    // Windows canonical paths carry a verbatim prefix
+    let path = path.strip_prefix("//?/").unwrap_or(&path);
+    let path = path.trim_end_matches('/');
+    if path.starts_with('/') {
+        format!("file://{}/", path)
+    } else {
+        format!("file:///{}/", path)
+    }
+}
+
+/// Escape text for XML attributes and content.
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Control characters other than whitespace are invalid in XML 1.0
+            c if c.is_control() && !matches!(c, '\n' | '\r' | '\t') => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 64-bit FNV-1a hash, stable across platforms and Rust releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_report() -> ValidationReport {
+        let mut result = ValidationResult::new("security");
+        result.add_finding(
+            Finding::new("secrets_exposed", Severity::Critical, "Hardcoded API key")
+                .at_file("/project/src/config.rs")
+                .at_line(12)
+                .at_column(5)
+                .with_snippet("let api_key = \"sk-123\";")
+                .with_suggestion("Use an environment variable"),
+        );
+        result.add_finding(
+            Finding::new(
+                "unsafe_code",
+                Severity::Warning,
+                "Undocumented unsafe block",
+            )
+            .at_file("/project/src/ffi.rs")
+            .at_line(40),
+        );
+        result.duration_ms = 1500;
+
+        let rules = vec![
+            RuleInfo::new("secrets_exposed", "Secrets Exposed", "Hardcoded secrets")
+                .with_severity(Severity::Critical),
+            RuleInfo::new("unsafe_code", "Unsafe Code", "Undocumented unsafe"),
+            RuleInfo::new("input_validation", "Input Validation", "Injection points")
+                .with_severity(Severity::Info),
+        ];
+
+        let mut report = ValidationReport::new("/project");
+        report.add_result("Security Agent", rules, result);
+        report
+    }
+
+    #[test]
+    fn test_sarif_export() {
+        let sarif = sample_report().to_sarif();
+        assert_eq!(sarif["version"], "2.1.0");
+
+        let run = &sarif["runs"][0];
+        assert_eq!(run["tool"]["driver"]["fullName"], "Security Agent");
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        assert_eq!(run["tool"]["driver"]["rules"][0]["id"], "secrets_exposed");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][0]["defaultConfiguration"]["level"],
+            "error"
+        );
+
+        let result = &run["results"][0];
+        assert_eq!(result["ruleId"], "secrets_exposed");
+        assert_eq!(result["ruleIndex"], 0);
+        assert_eq!(result["level"], "error");
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/config.rs");
+        assert_eq!(location["region"]["startLine"], 12);
+        assert_eq!(location["region"]["startColumn"], 5);
+        assert_eq!(
+            result["properties"]["suggestion"],
+            "Use an environment variable"
+        );
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(run["results"][1]["ruleIndex"], 1);
+        assert_eq!(
+            run["originalUriBaseIds"]["%SRCROOT%"]["uri"],
+            "file:///project/"
+        );
+    }
+
+    #[test]
+    fn test_sarif_base_uri_for_relative_root() {
+        let mut report = sample_report();
+        report.project_root = PathBuf::from(".");
+        let sarif = report.to_sarif();
+        let uri = sarif["runs"][0]["originalUriBaseIds"]["%SRCROOT%"]["uri"]
+            .as_str()
+            .unwrap();
+
+        let cwd = std::env::current_dir().unwrap().canonicalize().unwrap();
+        assert_eq!(uri, file_uri(&cwd));
+        assert!(uri.starts_with("file:///"), "{uri}");
+        assert!(uri.ends_with('/') && !uri.contains("/./"), "{uri}");
+    }
+
+    #[test]
+    fn test_junit_export() {
+        let junit = sample_report().to_junit();
+        assert!(junit.starts_with("<?xml"));
+        assert!(junit.contains("<testsuites name=\"cortex-forge\" tests=\"3\" failures=\"1\""));
+        assert!(junit.contains(
+            "<testsuite name=\"Security Agent\" tests=\"3\" failures=\"1\" errors=\"0\" skipped=\"0\" time=\"1.500\""
+        ));
+        assert!(junit.contains("<failure type=\"Critical\" message=\"Secrets Exposed: 1 finding(s)\">src/config.rs:12: [Critical] Hardcoded API key</failure>"));
+        assert!(junit.contains(
+            "<system-out>src/ffi.rs:40: [Warning] Undocumented unsafe block</system-out>"
+        ));
+        assert!(
+            junit.contains("<testcase classname=\"forge.security\" name=\"input_validation\"/>")
+        );
+    }
+
+    #[test]
+    fn test_baseline_suppresses_existing_findings() {
+        let baseline = Baseline::from_report(&sample_report());
+        assert_eq!(baseline.findings.len(), 2);
+        assert_eq!(baseline.findings[0].file.as_deref(), Some("src/config.rs"));
+
+        // The same finding on a different line is still suppressed
+        let mut report = sample_report();
+        report.agents[0].result.findings[0].line = Some(20);
+        report.agents[0].result.add_finding(
+            Finding::new("secrets_exposed", Severity::Error, "Hardcoded password")
+                .at_file("/project/src/db.rs"),
+        );
+
+        assert!(!report.is_success());
+        assert_eq!(report.apply_baseline(&baseline), 2);
+        assert_eq!(report.new_finding_count(), 1);
+        assert!(!report.is_success());
+
+        let sarif = report.to_sarif();
+        assert_eq!(
+            sarif["runs"][0]["results"][0]["suppressions"][0]["kind"],
+            "external"
+        );
+        assert!(sarif["runs"][0]["results"][2].get("suppressions").is_none());
+
+        let junit = report.to_junit();
+        assert!(junit.contains("src/db.rs: [Error] Hardcoded password</failure>"));
+        assert!(!junit.contains("Hardcoded API key"));
+    }
+
+    #[test]
+    fn test_baseline_counts_duplicates() {
+        let mut result = ValidationResult::new("quality");
+        for _ in 0..2 {
+            result.add_finding(
+                Finding::new("todo", Severity::Error, "TODO left in code").at_file("/p/a.rs"),
+            );
+        }
+        let mut report = ValidationReport::new("/p");
+        report.add_result("Quality", Vec::new(), result.clone());
+        let mut baseline = Baseline::from_report(&report);
+        baseline.findings.pop();
+
+        let mut report = ValidationReport::new("/p");
+        report.add_result("Quality", Vec::new(), result);
+        assert_eq!(report.apply_baseline(&baseline), 1);
+        assert!(!report.is_success());
+    }
+
+    #[tokio::test]
+    async fn test_baseline_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("forge/baseline.json");
+        let baseline = Baseline::from_report(&sample_report());
+        baseline.save(&path).await.unwrap();
+        assert_eq!(Baseline::load(&path).await.unwrap(), baseline);
+
+        std::fs::write(&path, r#"{"version": 99, "findings": []}"#).unwrap();
+        assert!(matches!(
+            Baseline::load(&path).await,
+            Err(AgentError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_from_forge_response() {
+        let finding = protocol::Finding::error("Missing license header")
+            .with_location(protocol::Location::at_line("src/lib.rs", 1))
+            .with_rule("license");
+        let mut result = protocol::ValidationResult::fail("lint", vec![finding]);
+        result.rules_applied = vec![protocol::RuleInfo::new("license", "License Header")];
+        let response = protocol::ForgeResponse::new(vec![result], 10);
+
+        let report = ValidationReport::from_forge_response("/project", &response);
+        assert_eq!(report.agents.len(), 1);
+        assert_eq!(report.agents[0].result.status, ValidationStatus::Failed);
+        assert!(!report.is_success());
+
+        let sarif = report.to_sarif();
+        let result = &sarif["runs"][0]["results"][0];
+        assert_eq!(result["ruleId"], "license");
+        assert_eq!(
+            result["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "src/lib.rs"
+        );
+    }
+
+    #[test]
+    fn test_xml_escape() {
+        assert_eq!(
+            xml_escape("a < b && \"c\"\u{1}"),
+            "a &lt; b &amp;&amp; &quot;c&quot;"
+        );
+    }
+}
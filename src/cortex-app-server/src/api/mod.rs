@@ -88,6 +88,10 @@ pub fn routes() -> Router<Arc<AppState>> {
             "/stored-sessions",
             get(stored_sessions::list_stored_sessions),
         )
+        .route(
+            "/stored-sessions/search",
+            get(stored_sessions::search_stored_sessions),
+        )
         .route(
             "/stored-sessions/:id",
             get(stored_sessions::get_stored_session),
@@ -100,9 +104,16 @@ pub fn routes() -> Router<Arc<AppState>> {
             "/stored-sessions/:id/history",
             get(stored_sessions::get_session_history),
         )
-        // Terminals (background processes)
+        // Terminals (background shells and interactive PTYs)
         .route("/terminals", get(terminals::list_terminals))
+        .route("/terminals", post(terminals::create_terminal))
+        .route("/terminals/:id", get(terminals::get_terminal))
+        .route("/terminals/:id", delete(terminals::delete_terminal))
         .route("/terminals/:id/logs", get(terminals::get_terminal_logs))
+        .route("/terminals/:id/input", post(terminals::send_terminal_input))
+        .route("/terminals/:id/wait", post(terminals::wait_terminal))
+        .route("/terminals/:id/screen", get(terminals::get_terminal_screen))
+        .route("/terminals/:id/resize", post(terminals::resize_terminal))
         // Search
         .route("/search", get(search::search_project))
         // Git
@@ -4,13 +4,15 @@ use std::sync::Arc;
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
 };
 
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 use crate::storage::{StoredMessage, StoredSession};
 
+use super::types::{StoredSessionSearchQuery, StoredSessionSearchResult};
+
 /// List all stored sessions.
 pub async fn list_stored_sessions(
     State(state): State<Arc<AppState>>,
@@ -61,3 +63,22 @@ pub async fn get_session_history(
         .map_err(|e| AppError::Internal(format!("Failed to read history: {}", e)))?;
     Ok(Json(messages))
 }
+
+/// Search stored session history, best matches first.
+pub async fn search_stored_sessions(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<StoredSessionSearchQuery>,
+) -> AppResult<Json<Vec<StoredSessionSearchResult>>> {
+    let storage = state.cli_sessions.storage();
+    let hits = storage
+        .search(&query.q, query.limit)
+        .map_err(|e| AppError::Internal(format!("Failed to search sessions: {}", e)))?;
+    let results = hits
+        .into_iter()
+        .map(|hit| StoredSessionSearchResult {
+            session: storage.load_session(&hit.session_id).ok(),
+            hit,
+        })
+        .collect();
+    Ok(Json(results))
+}
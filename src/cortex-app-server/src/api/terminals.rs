@@ -1,39 +1,261 @@
 //! Terminal management endpoints.
 //!
-//! NOTE: Terminal functionality has been removed. These endpoints are stubs
-//! for API compatibility.
+//! Terminals are shared with the agent's terminal tools through the engine's
+//! global terminal manager. Background terminals run a shell with line-based
+//! logs; PTY terminals run a program behind a terminal emulator and also
+//! support keystrokes, waiting for output and reading the rendered screen.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use axum::{
     Json,
     extract::{Path, Query, State},
 };
+use cortex_engine::terminal::{
+    ScreenSnapshot, TerminalInfo, TerminalKind, TerminalStatus, WaitFor, global_manager,
+};
+use regex::Regex;
 
 use crate::error::{AppError, AppResult};
 use crate::state::AppState;
 
-use super::types::{TerminalLogEntry, TerminalLogsQuery, TerminalResponse};
+use super::path_security::validate_path_safe;
+use super::types::{
+    CreateTerminalRequest, TerminalInputRequest, TerminalLogEntry, TerminalLogsQuery,
+    TerminalResizeRequest, TerminalResponse, TerminalWaitRequest, TerminalWaitResponse,
+};
+
+/// Longest wait a client may request.
+const MAX_WAIT_TIMEOUT_MS: u64 = 600_000;
+
+/// Default quiet period for a wait without a pattern.
+const DEFAULT_IDLE_MS: u64 = 500;
+
+impl From<TerminalInfo> for TerminalResponse {
+    fn from(info: TerminalInfo) -> Self {
+        Self {
+            id: info.id,
+            name: info.name,
+            cwd: info.cwd,
+            status: match info.status {
+                TerminalStatus::Running => "running",
+                TerminalStatus::Stopped => "stopped",
+                TerminalStatus::Error => "error",
+            }
+            .to_string(),
+            created_at: info.created_at,
+            exit_code: info.exit_code,
+            kind: match info.kind {
+                TerminalKind::Background => "background",
+                TerminalKind::Pty => "pty",
+            }
+            .to_string(),
+            command: info.command,
+        }
+    }
+}
+
+/// Map a terminal manager error to an API error.
+fn terminal_error(message: String) -> AppError {
+    if message.starts_with("Terminal not found") {
+        AppError::NotFound(message)
+    } else {
+        AppError::BadRequest(message)
+    }
+}
 
 /// List all terminals.
-/// NOTE: Terminal functionality has been removed. Returns empty list.
 pub async fn list_terminals(
     State(_state): State<Arc<AppState>>,
 ) -> AppResult<Json<Vec<TerminalResponse>>> {
-    // Terminal functionality removed - return empty list
-    Ok(Json(Vec::new()))
+    let terminals = global_manager().list_terminals().await;
+    Ok(Json(terminals.into_iter().map(Into::into).collect()))
+}
+
+/// Create a terminal.
+pub async fn create_terminal(
+    State(_state): State<Arc<AppState>>,
+    Json(req): Json<CreateTerminalRequest>,
+) -> AppResult<Json<TerminalResponse>> {
+    let cwd = match req.cwd {
+        Some(cwd) => {
+            validate_path_safe(std::path::Path::new(&cwd)).map_err(AppError::BadRequest)?
+        }
+        None => std::env::current_dir()
+            .map_err(|e| AppError::Internal(format!("Failed to get current directory: {}", e)))?,
+    };
+    if !cwd.is_dir() {
+        return Err(AppError::BadRequest(format!(
+            "Not a directory: {}",
+            cwd.display()
+        )));
+    }
+    let cwd = cwd.display().to_string();
+    let manager = global_manager();
+
+    let info = match req.kind.as_deref().unwrap_or("pty") {
+        "pty" => {
+            let name = req
+                .name
+                .or_else(|| req.command.first().cloned())
+                .unwrap_or_else(|| "shell".to_string());
+            manager
+                .create_pty_terminal(
+                    name,
+                    cwd,
+                    req.command,
+                    None,
+                    req.cols
+                        .unwrap_or(cortex_utils_pty::DEFAULT_COLS)
+                        .clamp(20, 500),
+                    req.rows
+                        .unwrap_or(cortex_utils_pty::DEFAULT_ROWS)
+                        .clamp(5, 200),
+                )
+                .await
+        }
+        "background" => {
+            if !req.command.is_empty() {
+                return Err(AppError::BadRequest(
+                    "Background terminals run a shell; send commands as input".to_string(),
+                ));
+            }
+            let name = req.name.unwrap_or_else(|| "shell".to_string());
+            manager.create_terminal(name, cwd).await
+        }
+        other => {
+            return Err(AppError::BadRequest(format!(
+                "Unknown terminal kind '{}' (expected pty or background)",
+                other
+            )));
+        }
+    }
+    .map_err(AppError::Internal)?;
+
+    Ok(Json(info.into()))
+}
+
+/// Get a terminal.
+pub async fn get_terminal(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> AppResult<Json<TerminalResponse>> {
+    global_manager()
+        .get_terminal(&id)
+        .await
+        .map(|info| Json(info.into()))
+        .ok_or_else(|| AppError::NotFound(format!("Terminal not found: {}", id)))
+}
+
+/// Kill and remove a terminal.
+pub async fn delete_terminal(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    let manager = global_manager();
+    let running = manager
+        .get_terminal(&id)
+        .await
+        .is_some_and(|info| info.status == TerminalStatus::Running);
+    if running {
+        manager.kill_terminal(&id).await.map_err(terminal_error)?;
+    }
+    manager.remove_terminal(&id).await.map_err(terminal_error)?;
+    Ok(Json(serde_json::json!({ "deleted": true, "id": id })))
 }
 
-/// Get terminal logs.
-/// NOTE: Terminal functionality has been removed. Always returns not found.
+/// Get terminal logs. For PTY terminals these are the scrollback and screen
+/// lines.
 pub async fn get_terminal_logs(
     State(_state): State<Arc<AppState>>,
     Path(id): Path<String>,
-    Query(_query): Query<TerminalLogsQuery>,
+    Query(query): Query<TerminalLogsQuery>,
 ) -> AppResult<Json<Vec<TerminalLogEntry>>> {
-    // Terminal functionality removed
-    Err(AppError::NotFound(format!(
-        "Terminal not found: {}. Terminal functionality has been removed.",
-        id
-    )))
+    let logs = global_manager()
+        .get_logs(&id, Some(query.tail))
+        .await
+        .map_err(terminal_error)?;
+    Ok(Json(
+        logs.into_iter()
+            .map(|line| TerminalLogEntry {
+                timestamp: line.timestamp,
+                content: line.content,
+                stream: line.stream,
+            })
+            .collect(),
+    ))
+}
+
+/// Send input to a terminal.
+pub async fn send_terminal_input(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<TerminalInputRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    let manager = global_manager();
+    match (req.keys, req.command) {
+        (Some(keys), None) => manager.send_keys(&id, &keys).await,
+        (None, Some(command)) => manager.run_command(&id, &command).await,
+        _ => {
+            return Err(AppError::BadRequest(
+                "Specify exactly one of keys or command".to_string(),
+            ));
+        }
+    }
+    .map_err(terminal_error)?;
+    Ok(Json(serde_json::json!({ "sent": true })))
+}
+
+/// Wait for output matching a pattern, or for quiet, in a PTY terminal.
+pub async fn wait_terminal(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<TerminalWaitRequest>,
+) -> AppResult<Json<TerminalWaitResponse>> {
+    let condition = match (req.pattern, req.idle_ms) {
+        (Some(_), Some(_)) => {
+            return Err(AppError::BadRequest(
+                "Specify either pattern or idle_ms, not both".to_string(),
+            ));
+        }
+        (Some(pattern), None) => WaitFor::Pattern(
+            Regex::new(&pattern)
+                .map_err(|e| AppError::BadRequest(format!("Invalid pattern: {}", e)))?,
+        ),
+        (None, idle_ms) => WaitFor::Idle(Duration::from_millis(idle_ms.unwrap_or(DEFAULT_IDLE_MS))),
+    };
+    let timeout = Duration::from_millis(req.timeout_ms.min(MAX_WAIT_TIMEOUT_MS));
+
+    let manager = global_manager();
+    let outcome = manager
+        .wait_for(&id, &condition, timeout)
+        .await
+        .map_err(terminal_error)?;
+    let screen = manager.screen(&id).await.map_err(terminal_error)?;
+    Ok(Json(TerminalWaitResponse { outcome, screen }))
+}
+
+/// Get the rendered screen of a PTY terminal.
+pub async fn get_terminal_screen(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> AppResult<Json<ScreenSnapshot>> {
+    let screen = global_manager().screen(&id).await.map_err(terminal_error)?;
+    Ok(Json(screen))
+}
+
+/// Resize a PTY terminal.
+pub async fn resize_terminal(
+    State(_state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+    Json(req): Json<TerminalResizeRequest>,
+) -> AppResult<Json<ScreenSnapshot>> {
+    let manager = global_manager();
+    manager
+        .resize_terminal(&id, req.cols.clamp(20, 500), req.rows.clamp(5, 200))
+        .await
+        .map_err(terminal_error)?;
+    let screen = manager.screen(&id).await.map_err(terminal_error)?;
+    Ok(Json(screen))
 }
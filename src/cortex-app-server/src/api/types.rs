@@ -1,7 +1,11 @@
 //! API request and response types.
 
+use cortex_engine::terminal::{ScreenSnapshot, WaitOutcome};
+use cortex_storage::SearchHit;
 use serde::{Deserialize, Serialize};
 
+use crate::storage::StoredSession;
+
 // ============================================================================
 // Health and Metrics
 // ============================================================================
@@ -460,6 +464,72 @@ pub struct TerminalResponse {
     pub status: String,
     pub created_at: u64,
     pub exit_code: Option<i32>,
+    /// "background" or "pty".
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+/// Request to create a terminal.
+#[derive(Debug, Deserialize)]
+pub struct CreateTerminalRequest {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub cwd: Option<String>,
+    /// "pty" (default) or "background".
+    #[serde(default)]
+    pub kind: Option<String>,
+    /// Program and arguments for a PTY terminal (default: the user's shell).
+    #[serde(default)]
+    pub command: Vec<String>,
+    #[serde(default)]
+    pub cols: Option<u16>,
+    #[serde(default)]
+    pub rows: Option<u16>,
+}
+
+/// Keystrokes for a PTY terminal, or a command line for a background one.
+#[derive(Debug, Deserialize)]
+pub struct TerminalInputRequest {
+    /// Keystrokes in key notation, e.g. `ls<Enter>` or `<C-c>`.
+    #[serde(default)]
+    pub keys: Option<String>,
+    /// A command line, run as if typed followed by Enter.
+    #[serde(default)]
+    pub command: Option<String>,
+}
+
+/// Request to wait for a PTY terminal.
+#[derive(Debug, Deserialize)]
+pub struct TerminalWaitRequest {
+    /// Regex to wait for in output received since the last input.
+    #[serde(default)]
+    pub pattern: Option<String>,
+    /// Without a pattern, milliseconds without output to wait for.
+    #[serde(default)]
+    pub idle_ms: Option<u64>,
+    #[serde(default = "default_terminal_wait_timeout")]
+    pub timeout_ms: u64,
+}
+
+fn default_terminal_wait_timeout() -> u64 {
+    30_000
+}
+
+/// Result of waiting for a PTY terminal.
+#[derive(Debug, Serialize)]
+pub struct TerminalWaitResponse {
+    #[serde(flatten)]
+    pub outcome: WaitOutcome,
+    pub screen: ScreenSnapshot,
+}
+
+/// Request to resize a PTY terminal.
+#[derive(Debug, Deserialize)]
+pub struct TerminalResizeRequest {
+    pub cols: u16,
+    pub rows: u16,
 }
 
 /// Terminal log entry.
@@ -481,6 +551,26 @@ fn default_tail() -> usize {
     1000
 }
 
+// ============================================================================
+// Stored session search
+// ============================================================================
+
+/// Query params for searching stored session history.
+#[derive(Debug, Deserialize)]
+pub struct StoredSessionSearchQuery {
+    pub q: String,
+    #[serde(default = "default_limit")]
+    pub limit: usize,
+}
+
+/// A stored session matching a history search.
+#[derive(Debug, Serialize)]
+pub struct StoredSessionSearchResult {
+    pub session: Option<StoredSession>,
+    #[serde(flatten)]
+    pub hit: SearchHit,
+}
+
 // ============================================================================
 // Search
 // ============================================================================
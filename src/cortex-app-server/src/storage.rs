@@ -8,6 +8,7 @@ use std::fs;
 use std::io::{BufRead, BufReader, BufWriter};
 use std::path::{Path, PathBuf};
 
+use cortex_storage::{SearchHit, SearchIndex};
 use fs2::FileExt;
 use serde::{Deserialize, Serialize};
 use tracing::{debug, info, warn};
@@ -45,12 +46,36 @@ pub struct StoredToolCall {
     pub duration_ms: Option<u64>,
 }
 
+impl From<&StoredMessage> for cortex_storage::StoredMessage {
+    fn from(message: &StoredMessage) -> Self {
+        Self {
+            id: message.id.clone(),
+            role: message.role.clone(),
+            content: message.content.clone(),
+            timestamp: message.timestamp,
+            tool_calls: message
+                .tool_calls
+                .iter()
+                .map(|call| cortex_storage::StoredToolCall {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    input: call.input.clone(),
+                    output: call.output.clone(),
+                    success: call.success,
+                    duration_ms: call.duration_ms,
+                })
+                .collect(),
+        }
+    }
+}
+
 /// Session storage manager.
 pub struct SessionStorage {
     #[allow(dead_code)]
     base_dir: PathBuf,
     sessions_dir: PathBuf,
     history_dir: PathBuf,
+    index: SearchIndex,
 }
 
 impl SessionStorage {
@@ -59,6 +84,7 @@ impl SessionStorage {
         let base_dir = base_dir.as_ref().to_path_buf();
         let sessions_dir = base_dir.join("sessions");
         let history_dir = base_dir.join("history");
+        let index = SearchIndex::new(base_dir.join("index"));
 
         fs::create_dir_all(&sessions_dir)?;
         fs::create_dir_all(&history_dir)?;
@@ -69,6 +95,7 @@ impl SessionStorage {
             base_dir,
             sessions_dir,
             history_dir,
+            index,
         })
     }
 
@@ -133,6 +160,9 @@ impl SessionStorage {
         if history_path.exists() {
             fs::remove_file(&history_path)?;
         }
+        if let Err(e) = self.index.remove_session(id) {
+            warn!("Failed to remove session {} from search index: {}", id, e);
+        }
 
         info!("Deleted session {}", id);
         Ok(())
@@ -188,6 +218,7 @@ impl SessionStorage {
         file.sync_all()?;
 
         file.unlock()?;
+        self.index_message(session_id, message);
 
         debug!("Appended message to session {} history", session_id);
         Ok(())
@@ -235,6 +266,65 @@ impl SessionStorage {
         Ok(())
     }
 
+    /// Search message contents, tool names, paths and commands across all
+    /// sessions, returning the best matching sessions with snippets.
+    pub fn search(&self, query: &str, limit: usize) -> std::io::Result<Vec<SearchHit>> {
+        if !self.index.exists() {
+            self.rebuild_index()?;
+        }
+        let hits = self
+            .index
+            .search(query, limit)
+            .map_err(std::io::Error::other)?;
+        hits.into_iter()
+            .map(|hit| {
+                let history = self.read_history(&hit.session_id)?;
+                let messages: Vec<_> = history.iter().map(Into::into).collect();
+                Ok(hit.with_snippet(&messages))
+            })
+            .collect()
+    }
+
+    /// Rebuild the search index from all stored history.
+    pub fn rebuild_index(&self) -> std::io::Result<usize> {
+        let mut histories = Vec::new();
+        for entry in fs::read_dir(&self.history_dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "jsonl")
+                && let Some(id) = path.file_stem().and_then(|s| s.to_str())
+            {
+                let messages: Vec<cortex_storage::StoredMessage> =
+                    self.read_history(id)?.iter().map(Into::into).collect();
+                histories.push((id.to_string(), messages));
+            }
+        }
+        self.index
+            .rebuild(
+                histories
+                    .iter()
+                    .map(|(id, messages)| (id.as_str(), messages.as_slice())),
+            )
+            .map_err(std::io::Error::other)
+    }
+
+    /// Add an appended message to the search index, building it first if
+    /// needed. Failures are logged; the history file is the source of truth.
+    fn index_message(&self, session_id: &str, message: &StoredMessage) {
+        let result = if self.index.exists() {
+            self.index
+                .add_message(session_id, &message.into())
+                .map_err(std::io::Error::other)
+        } else {
+            self.rebuild_index().map(|_| ())
+        };
+        if let Err(e) = result {
+            warn!(
+                "Failed to update search index for session {}: {}",
+                session_id, e
+            );
+        }
+    }
+
     fn session_path(&self, id: &str) -> PathBuf {
         self.sessions_dir.join(format!("{}.json", id))
     }
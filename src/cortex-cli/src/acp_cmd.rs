@@ -74,11 +74,7 @@ impl AcpCli {
         }
 
         // Build configuration
-        let mut config = cortex_engine::Config::default();
-
-        if let Some(cwd) = &self.cwd {
-            config.cwd = cwd.clone();
-        }
+        let mut config = crate::utils::load_config(self.cwd.clone()).await?;
 
         if let Some(model) = &self.model {
             // Resolve model alias (e.g., "sonnet" -> "anthropic/claude-sonnet-4-20250514")
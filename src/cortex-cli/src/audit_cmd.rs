@@ -0,0 +1,287 @@
+//! Audit command for the tamper-evident log of agent actions.
+//!
+//! Provides:
+//! - `cortex audit verify` - Check that no entry was altered, reordered or removed
+//! - `cortex audit query` - List recorded actions, optionally filtered
+//! - `cortex audit export` - Write recorded actions as JSONL, JSON or CSV
+
+use anyhow::{Context, Result, bail};
+use chrono::{DateTime, NaiveDate, Utc};
+use clap::Parser;
+use std::path::PathBuf;
+
+use cortex_engine::audit::{AuditEntry, AuditKind, AuditLog, AuditQuery, ExportFormat, export};
+use cortex_engine::config::find_cortex_home;
+
+/// Audit CLI command.
+#[derive(Debug, Parser)]
+pub struct AuditCli {
+    /// Audit log to read (default: ~/.cortex/audit/audit.jsonl)
+    #[arg(long, global = true, value_name = "PATH")]
+    pub log: Option<PathBuf>,
+
+    #[command(subcommand)]
+    pub subcommand: AuditSubcommand,
+}
+
+/// Audit subcommands.
+#[derive(Debug, clap::Subcommand)]
+pub enum AuditSubcommand {
+    /// Verify the hash chain of the audit log
+    Verify(AuditVerifyArgs),
+
+    /// List recorded actions
+    Query(AuditQueryArgs),
+
+    /// Export recorded actions
+    Export(AuditExportArgs),
+}
+
+/// Arguments for audit verify command.
+#[derive(Debug, Parser)]
+pub struct AuditVerifyArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Filters shared by query and export.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct AuditFilterArgs {
+    /// Only actions of this kind (approval, denial, command, file_write,
+    /// network_fetch, mcp_call); repeatable
+    #[arg(long = "kind", short = 'k', value_name = "KIND")]
+    pub kinds: Vec<AuditKind>,
+
+    /// Only actions of this session
+    #[arg(long, short = 's')]
+    pub session: Option<String>,
+
+    /// Only actions recorded at or after this time (RFC 3339 or YYYY-MM-DD)
+    #[arg(long, value_parser = parse_time)]
+    pub since: Option<DateTime<Utc>>,
+
+    /// Only actions recorded before this time (RFC 3339 or YYYY-MM-DD)
+    #[arg(long, value_parser = parse_time)]
+    pub until: Option<DateTime<Utc>>,
+
+    /// Only actions whose source, target or outcome contains this text
+    #[arg(long)]
+    pub grep: Option<String>,
+}
+
+impl AuditFilterArgs {
+    fn to_query(&self, limit: Option<usize>) -> AuditQuery {
+        AuditQuery {
+            kinds: self.kinds.clone(),
+            session: self.session.clone(),
+            since: self.since,
+            until: self.until,
+            text: self.grep.clone(),
+            limit,
+        }
+    }
+}
+
+/// Arguments for audit query command.
+#[derive(Debug, Parser)]
+pub struct AuditQueryArgs {
+    #[command(flatten)]
+    pub filter: AuditFilterArgs,
+
+    /// Show at most this many of the most recent matching actions
+    #[arg(long, short = 'n', default_value_t = 50)]
+    pub limit: usize,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for audit export command.
+#[derive(Debug, Parser)]
+pub struct AuditExportArgs {
+    #[command(flatten)]
+    pub filter: AuditFilterArgs,
+
+    /// Output format: jsonl, json or csv
+    #[arg(long, short = 'f', default_value = "jsonl")]
+    pub format: ExportFormat,
+
+    /// Write to this file instead of stdout
+    #[arg(long, short = 'o')]
+    pub output: Option<PathBuf>,
+}
+
+impl AuditCli {
+    /// Run the audit command.
+    pub async fn run(self) -> Result<()> {
+        let log = match self.log {
+            Some(path) => AuditLog::new(path),
+            None => {
+                let home = find_cortex_home().context("Failed to find cortex home")?;
+                AuditLog::new(AuditLog::default_path(&home))
+            }
+        };
+
+        match self.subcommand {
+            AuditSubcommand::Verify(args) => run_verify(&log, args),
+            AuditSubcommand::Query(args) => run_query(&log, args),
+            AuditSubcommand::Export(args) => run_export(&log, args),
+        }
+    }
+}
+
+fn run_verify(log: &AuditLog, args: AuditVerifyArgs) -> Result<()> {
+    let verification = log.verify()?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&verification)?);
+    } else {
+        println!("Audit log: {}", log.path().display());
+        match &verification.broken {
+            None => {
+                println!("Chain intact: {} entries", verification.entries);
+                if let Some(head) = &verification.head {
+                    println!("Head hash:    {head}");
+                }
+            }
+            Some(broken) => {
+                println!(
+                    "Chain BROKEN at line {}{}: {}",
+                    broken.line,
+                    broken
+                        .seq
+                        .map(|seq| format!(" (seq {seq})"))
+                        .unwrap_or_default(),
+                    broken.reason
+                );
+                println!("{} entries verified before the break", verification.entries);
+            }
+        }
+    }
+
+    if let Some(broken) = verification.broken {
+        bail!("Audit log chain is broken at line {}", broken.line);
+    }
+    Ok(())
+}
+
+fn run_query(log: &AuditLog, args: AuditQueryArgs) -> Result<()> {
+    let entries = args.filter.to_query(Some(args.limit)).apply(log.entries()?);
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No matching actions in {}.", log.path().display());
+        return Ok(());
+    }
+    for entry in &entries {
+        println!("{}", format_entry(entry));
+    }
+    Ok(())
+}
+
+fn run_export(log: &AuditLog, args: AuditExportArgs) -> Result<()> {
+    let entries = args.filter.to_query(None).apply(log.entries()?);
+    let rendered = export(&entries, args.format)?;
+
+    match args.output {
+        Some(path) => {
+            std::fs::write(&path, rendered)
+                .with_context(|| format!("Failed to write {}", path.display()))?;
+            eprintln!("Exported {} entries to {}", entries.len(), path.display());
+        }
+        None => print!("{rendered}"),
+    }
+    Ok(())
+}
+
+/// Render an entry as one line: sequence, time, kind, source, target and outcome.
+fn format_entry(entry: &AuditEntry) -> String {
+    format!(
+        "{:>6}  {}  {:<13} {:<14} {}  [{}]",
+        entry.seq,
+        entry.timestamp.format("%Y-%m-%d %H:%M:%S"),
+        entry.kind.as_str(),
+        entry.source,
+        entry.target,
+        entry.outcome
+    )
+}
+
+/// Parse an RFC 3339 timestamp or a date (midnight UTC).
+fn parse_time(value: &str) -> std::result::Result<DateTime<Utc>, String> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(value) {
+        return Ok(time.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default().and_utc())
+        .map_err(|_| format!("Invalid time '{value}' (expected RFC 3339 or YYYY-MM-DD)"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_query_filters() {
+        let cli = AuditCli::try_parse_from([
+            "audit",
+            "query",
+            "--kind",
+            "command",
+            "-k",
+            "file-write",
+            "--since",
+            "2026-01-02",
+            "--log",
+            "/tmp/audit.jsonl",
+        ])
+        .expect("should parse");
+        assert_eq!(cli.log, Some(PathBuf::from("/tmp/audit.jsonl")));
+        let AuditSubcommand::Query(args) = cli.subcommand else {
+            panic!("Expected Query subcommand");
+        };
+        assert_eq!(
+            args.filter.kinds,
+            vec![AuditKind::Command, AuditKind::FileWrite]
+        );
+        assert_eq!(
+            args.filter.since.unwrap().to_rfc3339(),
+            "2026-01-02T00:00:00+00:00"
+        );
+        assert_eq!(args.limit, 50);
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let time = parse_time("2026-03-04T05:06:07+02:00").unwrap();
+        assert_eq!(time.to_rfc3339(), "2026-03-04T03:06:07+00:00");
+        assert!(parse_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_verify_fails_on_broken_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::new(&path);
+        let event = cortex_engine::audit::AuditEvent::new(
+            AuditKind::FileWrite,
+            "Create",
+            "src/lib.rs",
+            "ok",
+        );
+        log.append(None, event.clone()).unwrap();
+        log.append(None, event).unwrap();
+        assert!(run_verify(&log, AuditVerifyArgs { json: true }).is_ok());
+
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replacen("src/lib.rs", "README.md", 1)).unwrap();
+        let err = run_verify(&log, AuditVerifyArgs { json: true }).unwrap_err();
+        assert!(err.to_string().contains("line 1"));
+    }
+}
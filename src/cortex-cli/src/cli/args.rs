@@ -9,6 +9,7 @@ use super::styles::{AFTER_HELP, BEFORE_HELP, HELP_TEMPLATE, categories, get_styl
 use crate::acp_cmd::AcpCli;
 use crate::agent_cmd::AgentCli;
 use crate::alias_cmd::AliasCli;
+use crate::audit_cmd::AuditCli;
 use crate::cache_cmd::CacheCli;
 use crate::compact_cmd::CompactCli;
 use crate::dag_cmd::DagCli;
@@ -21,8 +22,10 @@ use crate::import_cmd::ImportCommand;
 use crate::lock_cmd::LockCli;
 use crate::logs_cmd::LogsCli;
 use crate::mcp_cmd::McpCli;
+use crate::mcp_server_cmd::McpServerCli;
 use crate::models_cmd::ModelsCli;
 use crate::plugin_cmd::PluginCli;
+use crate::policy_cmd::PolicyCli;
 use crate::pr_cmd::PrCli;
 use crate::run_cmd::RunCli;
 use crate::scrape_cmd::ScrapeCommand;
@@ -342,10 +345,10 @@ pub enum Commands {
     #[command(next_help_heading = categories::EXTENSION)]
     Mcp(McpCli),
 
-    /// Run the MCP server (stdio transport)
-    #[command(display_order = 32, hide = true)]
+    /// Run Cortex as an MCP server (stdio transport)
+    #[command(display_order = 32)]
     #[command(next_help_heading = categories::EXTENSION)]
-    McpServer,
+    McpServer(McpServerCli),
 
     /// Start ACP server for IDE integration (e.g., Zed)
     #[command(display_order = 33)]
@@ -375,6 +378,16 @@ pub enum Commands {
     #[command(next_help_heading = categories::CONFIG)]
     Init(InitCommand),
 
+    /// Inspect the managed policy and explain command decisions
+    #[command(display_order = 44)]
+    #[command(next_help_heading = categories::CONFIG)]
+    Policy(PolicyCli),
+
+    /// Verify, query and export the audit log of agent actions
+    #[command(display_order = 45)]
+    #[command(next_help_heading = categories::CONFIG)]
+    Audit(AuditCli),
+
     // ========================================================================
     // 🛠️ Utilities (order 50-59)
     // ========================================================================
@@ -825,7 +838,7 @@ pub struct HistoryCommand {
 /// History subcommands.
 #[derive(Subcommand)]
 pub enum HistorySubcommand {
-    /// Search history for a pattern
+    /// Search session messages, tool calls and commands
     Search(HistorySearchArgs),
     /// Clear history (requires confirmation)
     Clear(HistoryClearArgs),
@@ -834,7 +847,7 @@ pub enum HistorySubcommand {
 /// Arguments for history search command.
 #[derive(Args)]
 pub struct HistorySearchArgs {
-    /// Pattern to search for in prompts
+    /// Words to search for in messages, tool names, file paths and commands
     pub pattern: String,
 
     /// Maximum number of results
@@ -2019,6 +2032,20 @@ mod tests {
         assert!(matches!(cli.command, Some(Commands::Feedback(_))));
     }
 
+    #[test]
+    fn test_policy_explain_subcommand() {
+        let cli = Cli::try_parse_from(["cortex", "policy", "explain", "git", "push"])
+            .expect("should parse policy explain");
+        assert!(matches!(cli.command, Some(Commands::Policy(_))));
+    }
+
+    #[test]
+    fn test_audit_verify_subcommand() {
+        let cli =
+            Cli::try_parse_from(["cortex", "audit", "verify"]).expect("should parse audit verify");
+        assert!(matches!(cli.command, Some(Commands::Audit(_))));
+    }
+
     #[test]
     fn test_lock_alias_protect() {
         let cli = Cli::try_parse_from(["cortex", "protect"])
@@ -32,11 +32,7 @@ pub async fn dispatch_command(cli: Cli) -> Result<()> {
         }
         Some(Commands::Mcp(mcp_cli)) => mcp_cli.run().await,
         Some(Commands::Agent(agent_cli)) => agent_cli.run().await,
-        Some(Commands::McpServer) => {
-            bail!(
-                "MCP server mode is not yet implemented. Use 'cortex mcp' for MCP server management."
-            );
-        }
+        Some(Commands::McpServer(mcp_server_cli)) => mcp_server_cli.run().await,
         Some(Commands::Completion(completion_cli)) => handle_completion(completion_cli),
         Some(Commands::Sandbox(sandbox_args)) => handle_sandbox(sandbox_args).await,
         Some(Commands::Resume(resume_cli)) => run_resume(resume_cli).await,
@@ -46,6 +42,8 @@ pub async fn dispatch_command(cli: Cli) -> Result<()> {
         Some(Commands::Delete(delete_cli)) => run_delete(delete_cli).await,
         Some(Commands::Config(config_cli)) => show_config(config_cli).await,
         Some(Commands::Features(features_cli)) => handle_features(features_cli).await,
+        Some(Commands::Policy(policy_cli)) => policy_cli.run().await,
+        Some(Commands::Audit(audit_cli)) => audit_cli.run().await,
         Some(Commands::Serve(serve_cli)) => run_serve(serve_cli).await,
         Some(Commands::Models(models_cli)) => models_cli.run().await,
         Some(Commands::Upgrade(upgrade_cli)) => upgrade_cli.run().await,
@@ -96,7 +94,18 @@ async fn run_tui(args: InteractiveArgs) -> Result<()> {
         );
     }
 
-    let mut config = cortex_engine::Config::default();
+    // Apply working directory override if specified
+    if let Some(ref cwd) = args.cwd {
+        let cwd_path = if cwd.is_absolute() {
+            cwd.clone()
+        } else {
+            std::env::current_dir()?.join(cwd)
+        };
+        std::env::set_current_dir(&cwd_path)?;
+    }
+
+    // Build config from the user's config.toml in the working directory
+    let mut config = crate::utils::load_config(None).await?;
 
     // Apply model override if specified
     if let Some(ref model) = args.model {
@@ -109,15 +118,18 @@ async fn run_tui(args: InteractiveArgs) -> Result<()> {
         config.model = resolve_model_alias(model).to_string();
     }
 
-    // Apply working directory override if specified
-    if let Some(ref cwd) = args.cwd {
-        let cwd_path = if cwd.is_absolute() {
-            cwd.clone()
-        } else {
-            std::env::current_dir()?.join(cwd)
-        };
-        std::env::set_current_dir(&cwd_path)?;
-        config.cwd = cwd_path;
+    // --oss: drive the session with a local LM Studio server
+    if args.oss {
+        let model = args
+            .model
+            .as_deref()
+            .map(resolve_model_alias)
+            .unwrap_or(cortex_lmstudio::DEFAULT_OSS_MODEL)
+            .to_string();
+        let base_url = std::env::var("LMSTUDIO_BASE_URL").ok();
+        cortex_lmstudio::ensure_oss_ready(base_url.as_deref(), Some(&model)).await?;
+        config.model_provider_id = cortex_lmstudio::LMSTUDIO_PROVIDER_ID.to_string();
+        config.model = model;
     }
 
     // Initialize custom command registry
@@ -601,7 +613,7 @@ pub async fn run_resume(resume_cli: ResumeCommand) -> Result<()> {
         );
     }
 
-    let config = cortex_engine::Config::default();
+    let config = crate::utils::load_config(None).await?;
 
     let id_str = match (resume_cli.session_id, resume_cli.last, resume_cli.pick) {
         // Support "last" as SESSION_ID as documented in help text (Issue #3646)
@@ -968,8 +980,7 @@ pub async fn run_servers(servers_cli: ServersCommand) -> Result<()> {
 pub async fn run_history(history_cli: HistoryCommand) -> Result<()> {
     match history_cli.action {
         Some(HistorySubcommand::Search(args)) => {
-            println!("Searching history for: {}", args.pattern);
-            println!("(History search not yet implemented)");
+            search_history(&args.pattern, args.limit, args.json)?;
         }
         Some(HistorySubcommand::Clear(args)) => {
             if !args.yes {
@@ -994,6 +1005,66 @@ pub async fn run_history(history_cli: HistoryCommand) -> Result<()> {
     Ok(())
 }
 
+/// Search session history and print ranked matches with snippets.
+fn search_history(pattern: &str, limit: usize, json: bool) -> Result<()> {
+    let storage = cortex_storage::SessionStorage::new()?;
+    let hits = storage.search_history_sync(pattern, limit)?;
+    let titles: Vec<Option<String>> = hits
+        .iter()
+        .map(|hit| {
+            storage
+                .get_session_sync(&hit.session_id)
+                .ok()
+                .and_then(|s| s.title)
+        })
+        .collect();
+
+    if json {
+        let json_hits: Vec<serde_json::Value> = hits
+            .iter()
+            .zip(&titles)
+            .map(|(hit, title)| {
+                serde_json::json!({
+                    "session_id": hit.session_id,
+                    "title": title,
+                    "message_id": hit.message_id,
+                    "role": hit.role,
+                    "timestamp": hit.timestamp,
+                    "score": hit.score,
+                    "matches": hit.matches,
+                    "snippet": hit.snippet,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&json_hits)?);
+        return Ok(());
+    }
+
+    if hits.is_empty() {
+        print_info(&format!("No sessions match \"{pattern}\"."));
+        return Ok(());
+    }
+
+    for (hit, title) in hits.iter().zip(&titles) {
+        let date = chrono::DateTime::from_timestamp(hit.timestamp, 0)
+            .map(|d| d.format("%Y-%m-%d %H:%M").to_string())
+            .unwrap_or_default();
+        println!(
+            "{:<12} {:<17} {:>3} match(es)  {}",
+            &hit.session_id[..8.min(hit.session_id.len())],
+            date,
+            hit.matches,
+            title.as_deref().unwrap_or("(untitled)"),
+        );
+        if let Some(snippet) = &hit.snippet {
+            println!("    {}: {}", hit.role, snippet);
+        }
+    }
+
+    println!("\nTotal: {} session(s)", hits.len());
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use clap_complete::Shell;
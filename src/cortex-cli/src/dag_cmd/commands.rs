@@ -1,7 +1,7 @@
 //! Command handlers for DAG operations.
 
 use anyhow::{Result, bail};
-use cortex_agents::task::{DagHydrator, DagStore, TaskStatus};
+use cortex_agents::task::{DagHydrator, DagStore, SessionHydrator, TaskStatus};
 use std::collections::HashMap;
 use std::io::{self, Write};
 
@@ -11,12 +11,15 @@ use super::args::{
     DagCreateArgs, DagDeleteArgs, DagGraphArgs, DagListArgs, DagResumeArgs, DagRunArgs,
     DagStatusArgs, DagValidateArgs,
 };
+use super::executor::TaskExecutor;
 use super::helpers::{
-    convert_specs, get_dag_store_path, load_spec, print_ascii_graph, print_dag_summary,
-    print_dot_graph, print_execution_order, print_execution_summary, print_mermaid_graph,
+    check_agents, convert_specs, get_dag_store_path, has_agent_tasks, load_spec, print_ascii_graph,
+    print_dag_summary, print_dot_graph, print_execution_order, print_execution_summary,
+    print_mermaid_graph,
 };
 use super::scheduler::DagScheduler;
 use super::types::{DagOutputFormat, ExecutionStrategy};
+use super::worktree::WorktreeManager;
 
 /// Create a DAG from specification.
 pub async fn run_create(args: DagCreateArgs) -> Result<()> {
@@ -77,6 +80,7 @@ pub async fn run_execute(args: DagRunArgs) -> Result<()> {
     }
 
     let spec = load_spec(&args.file)?;
+    check_agents(&spec).await?;
     let specs = convert_specs(&spec);
 
     let hydrator = if args.infer_deps {
@@ -131,7 +135,16 @@ pub async fn run_execute(args: DagRunArgs) -> Result<()> {
         println!();
     }
 
-    let scheduler = DagScheduler::new(
+    let id = args.id.clone().unwrap_or_else(|| {
+        let now = chrono::Utc::now();
+        format!(
+            "dag-{}-{}",
+            spec.name.as_deref().unwrap_or("run"),
+            now.format("%Y%m%d-%H%M%S")
+        )
+    });
+
+    let mut scheduler = DagScheduler::new(
         dag.clone(),
         args.max_concurrent,
         args.timeout,
@@ -140,6 +153,19 @@ pub async fn run_execute(args: DagRunArgs) -> Result<()> {
         args.quiet,
     );
 
+    // Agent tasks run in their own worktrees
+    if has_agent_tasks(&dag) {
+        let worktrees = WorktreeManager::open(&std::env::current_dir()?, &id).await?;
+        worktrees.ensure_clean().await?;
+        scheduler = scheduler
+            .with_executor(TaskExecutor::new(args.timeout, args.verbose).with_worktrees(worktrees));
+    }
+
+    // Save progress as tasks finish so the run can be resumed
+    if args.save {
+        scheduler = scheduler.with_store(DagStore::new(get_dag_store_path()?), &id);
+    }
+
     let stats = match args.strategy {
         ExecutionStrategy::Parallel => scheduler.execute().await?,
         ExecutionStrategy::Sequential => scheduler.execute_sequential().await?,
@@ -148,14 +174,6 @@ pub async fn run_execute(args: DagRunArgs) -> Result<()> {
 
     // Save if requested
     if args.save {
-        let id = args.id.unwrap_or_else(|| {
-            let now = chrono::Utc::now();
-            format!(
-                "dag-{}-{}",
-                spec.name.as_deref().unwrap_or("run"),
-                now.format("%Y%m%d-%H%M%S")
-            )
-        });
         let store_path = get_dag_store_path()?;
         let store = DagStore::new(&store_path);
         let dag = scheduler.dag.read().await;
@@ -353,6 +371,7 @@ pub async fn run_list(args: DagListArgs) -> Result<()> {
 /// Validate a DAG specification.
 pub async fn run_validate(args: DagValidateArgs) -> Result<()> {
     let spec = load_spec(&args.file)?;
+    check_agents(&spec).await?;
     let specs = convert_specs(&spec);
 
     // Check for cycle detection and other issues
@@ -450,12 +469,15 @@ pub async fn run_resume(args: DagResumeArgs) -> Result<()> {
     let store_path = get_dag_store_path()?;
     let store = DagStore::new(&store_path);
 
-    let dag = store.load(&args.id).await.map_err(|e| match e {
-        cortex_agents::task::PersistenceError::NotFound(_) => {
-            anyhow::anyhow!("DAG '{}' not found", args.id)
-        }
-        e => anyhow::anyhow!("Failed to load DAG: {}", e),
-    })?;
+    if !store.exists(&args.id) {
+        bail!("DAG '{}' not found", args.id);
+    }
+
+    // Tasks that were running when the last run stopped start over
+    let dag = SessionHydrator::new(DagStore::new(&store_path))
+        .restore_session(&args.id, None)
+        .await
+        .map_err(|e| anyhow::anyhow!("Failed to load DAG: {}", e))?;
 
     if dag.is_complete() {
         print_info("DAG has already completed");
@@ -473,14 +495,23 @@ pub async fn run_resume(args: DagResumeArgs) -> Result<()> {
     ));
     println!();
 
-    let scheduler = DagScheduler::new(
-        dag,
+    let mut scheduler = DagScheduler::new(
+        dag.clone(),
         args.max_concurrent,
         args.timeout,
         args.failure_mode,
         true,
         false,
-    );
+    )
+    .with_store(DagStore::new(&store_path), &args.id);
+
+    // Interrupted agent tasks get a fresh worktree under the same name
+    if has_agent_tasks(&dag) {
+        let worktrees = WorktreeManager::open(&std::env::current_dir()?, &args.id).await?;
+        worktrees.ensure_clean().await?;
+        scheduler = scheduler
+            .with_executor(TaskExecutor::new(args.timeout, true).with_worktrees(worktrees));
+    }
 
     let stats = scheduler.execute().await?;
 
@@ -1,17 +1,30 @@
 //! Task executor for running DAG tasks.
+//!
+//! Tasks either run a shell `command`, or hand a `prompt` to a named `agent`
+//! from the agent registry. Agent tasks run in their own git worktree and are
+//! merged back when they finish.
 
 use anyhow::{Context, Result, bail};
 use cortex_agents::task::{Task, TaskStatus};
+use cortex_agents::{AgentInfo, AgentRegistry};
+use cortex_engine::{Session, SessionHandle};
+use cortex_protocol::{
+    AskForApproval, EventMsg, Op, ReviewDecision, SandboxPolicy, Submission, UserInput,
+};
+use std::path::Path;
+use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use crate::styled_output::print_info;
 
 use super::types::TaskExecutionResult;
+use super::worktree::{MergeOutcome, WorktreeManager};
 
 /// Task executor that runs the actual task commands.
 pub struct TaskExecutor {
     timeout: Duration,
     verbose: bool,
+    worktrees: Option<Arc<WorktreeManager>>,
 }
 
 impl TaskExecutor {
@@ -19,9 +32,16 @@ impl TaskExecutor {
         Self {
             timeout: Duration::from_secs(timeout_secs),
             verbose,
+            worktrees: None,
         }
     }
 
+    /// Run agent tasks in worktrees managed by `worktrees`.
+    pub fn with_worktrees(mut self, worktrees: WorktreeManager) -> Self {
+        self.worktrees = Some(Arc::new(worktrees));
+        self
+    }
+
     /// Execute a single task.
     pub async fn execute(&self, task: &Task) -> TaskExecutionResult {
         let start = Instant::now();
@@ -45,20 +65,21 @@ impl TaskExecutor {
             }
         }
 
+        let agent = task.metadata.get("agent").and_then(|v| v.as_str());
+
         // If there's a command, execute it
-        let (status, output, error) = if let Some(cmd) = command {
-            match self.run_command(&cmd).await {
-                Ok(output) => (TaskStatus::Completed, Some(output), None),
-                Err(e) => (TaskStatus::Failed, None, Some(e.to_string())),
-            }
+        let result = if let Some(cmd) = command {
+            self.run_command(&cmd).await
+        } else if let Some(agent) = agent {
+            self.run_agent_task(task, agent).await
         } else {
-            // Simulated task execution (no command)
-            // In a real system, this would delegate to an agent or external executor
-            (
-                TaskStatus::Completed,
-                Some(format!("Task '{}' completed (no command)", task.name)),
-                None,
-            )
+            // Nothing to run: the task only groups its dependencies
+            Ok(format!("Task '{}' completed (no command)", task.name))
+        };
+
+        let (status, output, error) = match result {
+            Ok(output) => (TaskStatus::Completed, Some(output), None),
+            Err(e) => (TaskStatus::Failed, None, Some(format!("{e:#}"))),
         };
 
         TaskExecutionResult {
@@ -98,4 +119,143 @@ impl TaskExecutor {
             Err(_) => bail!("Task timed out after {:?}", timeout_duration),
         }
     }
+
+    /// Run an agent task in its own worktree and merge the result back.
+    async fn run_agent_task(&self, task: &Task, agent_name: &str) -> Result<String> {
+        let Some(worktrees) = &self.worktrees else {
+            bail!("Agent tasks need a git repository");
+        };
+        let agent = AgentRegistry::with_defaults()
+            .await
+            .get(agent_name)
+            .await
+            .with_context(|| format!("Unknown agent '{agent_name}'"))?;
+        let prompt = task
+            .metadata
+            .get("prompt")
+            .and_then(|v| v.as_str())
+            .unwrap_or(&task.description);
+
+        let worktree = worktrees.create(&task.name).await?;
+        if self.verbose {
+            print_info(&format!(
+                "Task '{}' running agent '{}' in {}",
+                task.name,
+                agent.name,
+                worktree.path.display()
+            ));
+        }
+
+        let message = run_agent(&agent, prompt, &worktree.path, self.timeout)
+            .await
+            .with_context(|| {
+                format!("Agent failed; worktree kept at {}", worktree.path.display())
+            })?;
+
+        let commit_message = format!("{}\n\n{}", task.name, prompt);
+        match worktrees.merge(&worktree, &commit_message).await? {
+            MergeOutcome::Merged(commit) => Ok(format!(
+                "{message}\n\nMerged {} as {commit}",
+                worktree.branch
+            )),
+            MergeOutcome::NoChanges => Ok(message),
+            MergeOutcome::Conflict(files) => bail!(
+                "Merging {} conflicts in {}; resolve with `git merge {}`",
+                worktree.branch,
+                files.join(", "),
+                worktree.branch
+            ),
+        }
+    }
+}
+
+/// Run one headless agent turn in `cwd` and return its final message.
+///
+/// The session has stopped by the time this returns, so nothing is still
+/// writing to `cwd`, even when the turn timed out.
+async fn run_agent(
+    agent: &AgentInfo,
+    prompt: &str,
+    cwd: &Path,
+    timeout: Duration,
+) -> Result<String> {
+    let mut config = crate::utils::load_config(Some(cwd.to_path_buf())).await?;
+    config.current_agent = Some(agent.name.clone());
+    // Nobody is around to approve; the worktree is the sandbox
+    config.approval_policy = AskForApproval::Never;
+    config.sandbox_policy = SandboxPolicy::WorkspaceWrite {
+        writable_roots: vec![cwd.to_path_buf()],
+        network_access: false,
+        exclude_tmpdir_env_var: false,
+        exclude_slash_tmp: false,
+    };
+    if let Some(model) = &agent.model {
+        config.model = cortex_common::resolve_model_alias(model).to_string();
+    }
+    if agent.temperature.is_some() {
+        config.temperature = agent.temperature;
+    }
+    config.user_instructions = agent.prompt.clone();
+
+    let (mut session, handle) = Session::new(config)?;
+    let session_task = tokio::spawn(async move { session.run().await });
+
+    let outcome = match tokio::time::timeout(timeout, agent_turn(&handle, prompt)).await {
+        Ok(outcome) => {
+            let _ = handle
+                .submission_tx
+                .send(Submission {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    op: Op::Shutdown,
+                })
+                .await;
+            outcome
+        }
+        Err(_) => {
+            // Dropping the session kills the commands it started
+            session_task.abort();
+            Err(anyhow::anyhow!("Task timed out after {timeout:?}"))
+        }
+    };
+    let _ = session_task.await;
+
+    outcome
+}
+
+/// Submit `prompt` and follow the session's events until the turn completes.
+async fn agent_turn(handle: &SessionHandle, prompt: &str) -> Result<String> {
+    handle
+        .submission_tx
+        .send(Submission {
+            id: uuid::Uuid::new_v4().to_string(),
+            op: Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: prompt.to_string(),
+                }],
+            },
+        })
+        .await?;
+
+    let mut outcome = Ok(String::new());
+    while let Ok(event) = handle.event_rx.recv().await {
+        match event.msg {
+            EventMsg::AgentMessage(message) => outcome = Ok(message.message),
+            EventMsg::Error(error) => outcome = Err(anyhow::anyhow!(error.message)),
+            EventMsg::ExecApprovalRequest(request) => {
+                handle
+                    .submission_tx
+                    .send(Submission {
+                        id: uuid::Uuid::new_v4().to_string(),
+                        op: Op::ExecApproval {
+                            id: request.call_id,
+                            decision: ReviewDecision::Denied,
+                        },
+                    })
+                    .await?;
+            }
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+    outcome
 }
@@ -37,6 +37,15 @@ pub fn load_spec(path: &PathBuf) -> Result<DagSpecInput> {
                 task.name
             );
         }
+        if task.command.is_some() && task.agent.is_some() {
+            anyhow::bail!(
+                "Task '{}' sets both 'command' and 'agent'; use one or the other.",
+                task.name
+            );
+        }
+        if task.prompt.is_some() && task.agent.is_none() {
+            anyhow::bail!("Task '{}' has a 'prompt' but no 'agent'.", task.name);
+        }
     }
 
     Ok(spec)
@@ -65,6 +74,14 @@ pub fn convert_specs(input: &DagSpecInput) -> Vec<TaskSpec> {
                 spec = spec.with_metadata("command", serde_json::json!(cmd));
             }
 
+            // Store agent and prompt in metadata
+            if let Some(agent) = &t.agent {
+                spec = spec.with_metadata("agent", serde_json::json!(agent));
+            }
+            if let Some(prompt) = &t.prompt {
+                spec = spec.with_metadata("prompt", serde_json::json!(prompt));
+            }
+
             for (key, value) in &t.metadata {
                 spec = spec.with_metadata(key, value.clone());
             }
@@ -74,6 +91,29 @@ pub fn convert_specs(input: &DagSpecInput) -> Vec<TaskSpec> {
         .collect()
 }
 
+/// Whether any task in the DAG runs an agent.
+pub fn has_agent_tasks(dag: &TaskDag) -> bool {
+    dag.all_tasks().any(|t| t.metadata.contains_key("agent"))
+}
+
+/// Check that every agent named in the spec is registered.
+pub async fn check_agents(spec: &DagSpecInput) -> Result<()> {
+    let registry = cortex_agents::AgentRegistry::with_defaults().await;
+    for task in &spec.tasks {
+        if let Some(agent) = &task.agent
+            && !registry.exists(agent).await
+        {
+            anyhow::bail!(
+                "Task '{}' uses unknown agent '{}'. Available agents: {}",
+                task.name,
+                agent,
+                registry.names().await.join(", ")
+            );
+        }
+    }
+    Ok(())
+}
+
 /// Print a summary of the DAG.
 pub fn print_dag_summary(dag: &TaskDag) {
     println!("Tasks:");
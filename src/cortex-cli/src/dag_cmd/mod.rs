@@ -12,6 +12,8 @@
 //! - **Failure Propagation**: Skip dependent tasks on failure
 //! - **Progress Tracking**: Real-time status updates
 //! - **Persistence**: Save and resume DAG execution
+//! - **Agent Tasks**: Run registry agents in isolated git worktrees and merge
+//!   their changes back in dependency order
 //!
 //! # Usage
 //!
@@ -28,6 +30,24 @@
 //! # List all DAGs
 //! cortex dag list
 //! ```
+//!
+//! # Agent Tasks
+//!
+//! A task can name an agent and a prompt instead of a command. Each agent task
+//! gets its own worktree; its changes are committed there and merged into the
+//! current branch before any dependent task starts. Conflicting merges fail
+//! the task and keep its branch for manual resolution.
+//!
+//! ```yaml
+//! tasks:
+//!   - name: api
+//!     agent: build
+//!     prompt: Add a /health endpoint
+//!   - name: docs
+//!     agent: build
+//!     prompt: Document the /health endpoint
+//!     depends_on: [api]
+//! ```
 
 mod args;
 mod commands;
@@ -35,6 +55,7 @@ mod executor;
 mod helpers;
 mod scheduler;
 mod types;
+mod worktree;
 
 #[cfg(test)]
 mod tests;
@@ -56,6 +77,7 @@ pub use types::{
 pub use executor::TaskExecutor;
 pub use helpers::{convert_specs, get_dag_store_path, load_spec};
 pub use scheduler::DagScheduler;
+pub use worktree::{MergeOutcome, WorktreeManager};
 
 /// Run the DAG CLI command.
 pub async fn run(cli: DagCli) -> Result<()> {
@@ -1,7 +1,7 @@
 //! DAG scheduler for coordinating task execution.
 
 use anyhow::Result;
-use cortex_agents::task::{Task, TaskDag, TaskStatus};
+use cortex_agents::task::{DagStore, Task, TaskDag, TaskStatus};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use tokio::sync::{Mutex, RwLock, Semaphore};
@@ -19,6 +19,7 @@ pub struct DagScheduler {
     failure_mode: FailureMode,
     stats: Arc<Mutex<DagExecutionStats>>,
     quiet: bool,
+    store: Option<(DagStore, String)>,
 }
 
 impl DagScheduler {
@@ -41,6 +42,30 @@ impl DagScheduler {
                 ..Default::default()
             })),
             quiet,
+            store: None,
+        }
+    }
+
+    /// Use a custom task executor.
+    pub fn with_executor(mut self, executor: TaskExecutor) -> Self {
+        self.executor = Arc::new(executor);
+        self
+    }
+
+    /// Save the DAG to `store` under `id` after every finished task, so an
+    /// interrupted run can be resumed.
+    pub fn with_store(mut self, store: DagStore, id: impl Into<String>) -> Self {
+        self.store = Some((store, id.into()));
+        self
+    }
+
+    /// Save the current DAG state if a store is configured.
+    async fn persist(&self) {
+        if let Some((store, id)) = &self.store {
+            let dag = self.dag.read().await;
+            if let Err(e) = store.save(id, &dag).await {
+                tracing::warn!("Failed to save DAG '{}': {}", id, e);
+            }
         }
     }
 
@@ -212,6 +237,7 @@ impl DagScheduler {
 
     /// Handle a task result and determine if execution should stop.
     async fn handle_task_result(&self, result: TaskExecutionResult) -> Result<bool> {
+        self.persist().await;
         match result.status {
             TaskStatus::Failed => match self.failure_mode {
                 FailureMode::FailFast => Ok(true),
@@ -326,6 +352,8 @@ impl DagScheduler {
                 }
                 stats.task_results.push(result);
             }
+
+            self.persist().await;
         }
 
         // Finalize stats
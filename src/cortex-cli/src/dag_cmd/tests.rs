@@ -41,6 +41,8 @@ fn test_convert_specs() {
                 name: "a".to_string(),
                 description: "Task A".to_string(),
                 command: Some("echo A".to_string()),
+                agent: None,
+                prompt: None,
                 depends_on: vec![],
                 affected_files: vec![],
                 priority: 10,
@@ -51,6 +53,8 @@ fn test_convert_specs() {
                 name: "b".to_string(),
                 description: "Task B".to_string(),
                 command: None,
+                agent: Some("build".to_string()),
+                prompt: Some("Update file.txt".to_string()),
                 depends_on: vec!["a".to_string()],
                 affected_files: vec!["file.txt".to_string()],
                 priority: 5,
@@ -64,6 +68,8 @@ fn test_convert_specs() {
     assert_eq!(specs.len(), 2);
     assert_eq!(specs[0].priority, 10);
     assert_eq!(specs[1].depends_on, vec!["a"]);
+    assert_eq!(specs[1].metadata["agent"], "build");
+    assert_eq!(specs[1].metadata["prompt"], "Update file.txt");
 }
 
 #[test]
@@ -56,6 +56,12 @@ pub struct TaskSpecInput {
     /// Command to execute (optional).
     #[serde(default)]
     pub command: Option<String>,
+    /// Agent to run instead of a command (optional).
+    #[serde(default)]
+    pub agent: Option<String>,
+    /// Prompt for the agent (defaults to the description).
+    #[serde(default)]
+    pub prompt: Option<String>,
     /// Task dependencies (names of tasks).
     #[serde(default)]
     pub depends_on: Vec<String>,
@@ -0,0 +1,310 @@
+//! Git worktrees for agent tasks.
+//!
+//! Each agent task runs in its own worktree on a branch named after the DAG
+//! and task, so parallel agents never share a checkout. When a task finishes,
+//! its branch is merged back into the branch checked out in the main worktree.
+//! Merges are serialized, and dependents only start after their dependencies
+//! have been merged, so they always build on their dependencies' changes.
+//!
+//! Names are derived from the DAG ID and task name, which lets a resumed run
+//! find and replace worktrees left behind by an interrupted one.
+
+use anyhow::{Context, Result, bail};
+use std::path::{Path, PathBuf};
+use tokio::process::Command;
+use tokio::sync::Mutex;
+
+/// Result of merging a task branch back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MergeOutcome {
+    /// The branch was merged with the given commit.
+    Merged(String),
+    /// The task made no changes.
+    NoChanges,
+    /// The merge conflicted and was aborted.
+    Conflict(Vec<String>),
+}
+
+/// A worktree created for a task.
+#[derive(Debug, Clone)]
+pub struct TaskWorktree {
+    /// Checkout directory.
+    pub path: PathBuf,
+    /// Branch checked out in the worktree.
+    pub branch: String,
+}
+
+/// Creates, merges and removes task worktrees for one DAG run.
+pub struct WorktreeManager {
+    /// Top level of the main worktree.
+    repo_root: PathBuf,
+    /// Directory holding this run's worktrees.
+    base_dir: PathBuf,
+    /// DAG ID used in branch names.
+    dag_id: String,
+    /// Serializes operations that touch the main worktree.
+    lock: Mutex<()>,
+}
+
+impl WorktreeManager {
+    /// Open the repository containing `dir` for the DAG `dag_id`.
+    pub async fn open(dir: &Path, dag_id: &str) -> Result<Self> {
+        let repo_root = git(dir, &["rev-parse", "--show-toplevel"])
+            .await
+            .context("Agent tasks must run inside a git repository")?;
+        let repo_root = PathBuf::from(repo_root);
+        let common_dir = git(&repo_root, &["rev-parse", "--git-common-dir"]).await?;
+        let dag_id = slug(dag_id);
+
+        Ok(Self {
+            base_dir: repo_root.join(common_dir).join("cortex-dag").join(&dag_id),
+            repo_root,
+            dag_id,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Fail if the main worktree has uncommitted changes to tracked files.
+    pub async fn ensure_clean(&self) -> Result<()> {
+        let status = git(
+            &self.repo_root,
+            &["status", "--porcelain", "--untracked-files=no"],
+        )
+        .await?;
+        if !status.is_empty() {
+            bail!(
+                "Agent task results are merged into the current branch; commit or stash changes in {} first",
+                self.repo_root.display()
+            );
+        }
+        Ok(())
+    }
+
+    /// Branch used for a task.
+    pub fn branch_name(&self, task_name: &str) -> String {
+        format!("cortex/dag/{}/{}", self.dag_id, slug(task_name))
+    }
+
+    /// Create a fresh worktree for a task from the current branch.
+    ///
+    /// A worktree left behind by an earlier run of the same task is replaced.
+    pub async fn create(&self, task_name: &str) -> Result<TaskWorktree> {
+        let _guard = self.lock.lock().await;
+
+        let worktree = TaskWorktree {
+            path: self.base_dir.join(slug(task_name)),
+            branch: self.branch_name(task_name),
+        };
+        self.discard(&worktree).await;
+
+        tokio::fs::create_dir_all(&self.base_dir).await?;
+        let path = worktree.path.to_string_lossy();
+        git(
+            &self.repo_root,
+            &["worktree", "add", "-b", &worktree.branch, &path, "HEAD"],
+        )
+        .await
+        .with_context(|| format!("Failed to create worktree for task '{task_name}'"))?;
+
+        Ok(worktree)
+    }
+
+    /// Commit everything in the worktree and merge its branch into the current branch.
+    ///
+    /// Worktrees are removed after a successful merge. On conflict the merge is
+    /// aborted and the worktree and branch are kept for manual resolution.
+    pub async fn merge(&self, worktree: &TaskWorktree, message: &str) -> Result<MergeOutcome> {
+        git(&worktree.path, &["add", "-A"]).await?;
+        let staged = git(&worktree.path, &["diff", "--cached", "--name-only"]).await?;
+        if !staged.is_empty() {
+            git(&worktree.path, &["commit", "--quiet", "-m", message]).await?;
+        }
+
+        let _guard = self.lock.lock().await;
+
+        let ahead = git(
+            &self.repo_root,
+            &["rev-list", "--count", &format!("HEAD..{}", worktree.branch)],
+        )
+        .await?;
+        if ahead == "0" {
+            self.discard(worktree).await;
+            return Ok(MergeOutcome::NoChanges);
+        }
+
+        let merge_message = format!("Merge {}", worktree.branch);
+        let merged = git(
+            &self.repo_root,
+            &["merge", "--no-ff", "-m", &merge_message, &worktree.branch],
+        )
+        .await;
+
+        if let Err(e) = merged {
+            let conflicts = git(&self.repo_root, &["diff", "--name-only", "--diff-filter=U"])
+                .await
+                .unwrap_or_default();
+            // Leave the main worktree as it was before the merge
+            let _ = git(&self.repo_root, &["merge", "--abort"]).await;
+            if conflicts.is_empty() {
+                return Err(e);
+            }
+            return Ok(MergeOutcome::Conflict(
+                conflicts.lines().map(String::from).collect(),
+            ));
+        }
+
+        let commit = git(&self.repo_root, &["rev-parse", "--short", "HEAD"]).await?;
+        self.discard(worktree).await;
+        Ok(MergeOutcome::Merged(commit))
+    }
+
+    /// Remove a worktree and its branch, ignoring ones that don't exist.
+    async fn discard(&self, worktree: &TaskWorktree) {
+        let path = worktree.path.to_string_lossy();
+        let _ = git(&self.repo_root, &["worktree", "remove", "--force", &path]).await;
+        let _ = git(&self.repo_root, &["worktree", "prune"]).await;
+        let _ = git(&self.repo_root, &["branch", "-D", &worktree.branch]).await;
+    }
+}
+
+/// Run git in `dir` and return its trimmed stdout.
+async fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .await
+        .context("Failed to run git")?;
+
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Make a name safe for branch and directory names.
+fn slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "task".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn init_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        for args in [
+            &["init", "--quiet", "-b", "main"][..],
+            &["config", "user.name", "Test"],
+            &["config", "user.email", "test@example.com"],
+        ] {
+            git(dir.path(), args).await.unwrap();
+        }
+        std::fs::write(dir.path().join("shared.txt"), "base\n").unwrap();
+        git(dir.path(), &["add", "."]).await.unwrap();
+        git(dir.path(), &["commit", "--quiet", "-m", "init"])
+            .await
+            .unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_slug() {
+        assert_eq!(slug("Build API"), "build-api");
+        assert_eq!(slug("fix/bug #2"), "fix-bug--2");
+        assert_eq!(slug("***"), "task");
+    }
+
+    #[tokio::test]
+    async fn test_merge_task_changes() {
+        let repo = init_repo().await;
+        let manager = WorktreeManager::open(repo.path(), "dag-1").await.unwrap();
+        manager.ensure_clean().await.unwrap();
+
+        let first = manager.create("first").await.unwrap();
+        let second = manager.create("second").await.unwrap();
+        assert_eq!(first.branch, "cortex/dag/dag-1/first");
+        std::fs::write(first.path.join("a.txt"), "a\n").unwrap();
+        std::fs::write(second.path.join("b.txt"), "b\n").unwrap();
+
+        let outcome = manager.merge(&first, "first").await.unwrap();
+        assert!(matches!(outcome, MergeOutcome::Merged(_)));
+        let outcome = manager.merge(&second, "second").await.unwrap();
+        assert!(matches!(outcome, MergeOutcome::Merged(_)));
+
+        assert!(repo.path().join("a.txt").exists());
+        assert!(repo.path().join("b.txt").exists());
+        assert!(!first.path.exists());
+        let branches = git(repo.path(), &["branch", "--list", "cortex/*"])
+            .await
+            .unwrap();
+        assert!(branches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_merge_without_changes() {
+        let repo = init_repo().await;
+        let manager = WorktreeManager::open(repo.path(), "dag-1").await.unwrap();
+
+        let worktree = manager.create("noop").await.unwrap();
+        let outcome = manager.merge(&worktree, "noop").await.unwrap();
+        assert_eq!(outcome, MergeOutcome::NoChanges);
+        assert!(!worktree.path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_merge_conflict_is_reported() {
+        let repo = init_repo().await;
+        let manager = WorktreeManager::open(repo.path(), "dag-1").await.unwrap();
+
+        let left = manager.create("left").await.unwrap();
+        let right = manager.create("right").await.unwrap();
+        std::fs::write(left.path.join("shared.txt"), "left\n").unwrap();
+        std::fs::write(right.path.join("shared.txt"), "right\n").unwrap();
+
+        manager.merge(&left, "left").await.unwrap();
+        let outcome = manager.merge(&right, "right").await.unwrap();
+        assert_eq!(
+            outcome,
+            MergeOutcome::Conflict(vec!["shared.txt".to_string()])
+        );
+
+        // The main worktree is untouched and the branch is kept
+        manager.ensure_clean().await.unwrap();
+        let content = std::fs::read_to_string(repo.path().join("shared.txt")).unwrap();
+        assert_eq!(content, "left\n");
+        assert!(right.path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_create_replaces_stale_worktree() {
+        let repo = init_repo().await;
+        let manager = WorktreeManager::open(repo.path(), "dag-1").await.unwrap();
+
+        let stale = manager.create("task").await.unwrap();
+        std::fs::write(stale.path.join("partial.txt"), "partial\n").unwrap();
+
+        let fresh = manager.create("task").await.unwrap();
+        assert_eq!(fresh.path, stale.path);
+        assert!(!fresh.path.join("partial.txt").exists());
+    }
+}
@@ -252,15 +252,14 @@ impl ExecCli {
             ExecOutputFormat::StreamJson | ExecOutputFormat::Debug
         );
 
-        // Build configuration
-        let mut config = cortex_engine::Config::default();
-
-        // Apply working directory
+        // Resolve working directory
         let cwd = self
             .cwd
             .clone()
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-        config.cwd = cwd.clone();
+
+        // Build configuration from the user's config.toml
+        let mut config = crate::utils::load_config(Some(cwd.clone())).await?;
 
         // Apply model if specified
         if let Some(ref model) = self.model {
@@ -522,7 +521,10 @@ impl ExecCli {
                 }
                 EventMsg::ApplyPatchApprovalRequest(p) => {
                     if is_text && self.verbose {
-                        eprintln!("\x1b[1;33m[PATCH]\x1b[0m Patch approval requested: {}", p.id);
+                        eprintln!(
+                            "\x1b[1;33m[PATCH]\x1b[0m Patch approval requested: {}",
+                            p.call_id
+                        );
                     }
                 }
                 EventMsg::ElicitationRequest(e) => {
@@ -710,15 +712,14 @@ impl ExecCli {
         initial_prompt: String,
         autonomy: Option<AutonomyLevel>,
     ) -> Result<()> {
-        // Build configuration
-        let mut config = cortex_engine::Config::default();
-
-        // Apply working directory
+        // Resolve working directory
         let cwd = self
             .cwd
             .clone()
             .unwrap_or_else(|| std::env::current_dir().unwrap_or_else(|_| PathBuf::from(".")));
-        config.cwd = cwd.clone();
+
+        // Build configuration from the user's config.toml
+        let mut config = crate::utils::load_config(Some(cwd.clone())).await?;
 
         // Apply model if specified
         if let Some(ref model) = self.model {
@@ -1,6 +1,7 @@
 //! Session export command for Cortex CLI.
 //!
-//! Exports a session to a portable JSON format that can be shared or imported.
+//! Exports a session to a portable JSON format that can be shared or imported,
+//! or to a standalone HTML transcript for review.
 
 use anyhow::{Context, Result, bail};
 use clap::Parser;
@@ -8,8 +9,8 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 use cortex_engine::list_sessions;
-use cortex_engine::rollout::get_rollout_path;
 use cortex_engine::rollout::reader::{RolloutItem, get_session_meta, read_rollout};
+use cortex_engine::rollout::{get_rollout_path, render_html};
 use cortex_protocol::{ConversationId, EventMsg};
 
 /// Export format for sessions.
@@ -22,6 +23,8 @@ pub enum ExportFormat {
     Yaml,
     /// CSV format (simplified, messages only)
     Csv,
+    /// Self-contained HTML transcript with tool calls, diffs and approvals
+    Html,
 }
 
 /// Export a session to JSON format.
@@ -35,7 +38,7 @@ pub struct ExportCommand {
     #[arg(short, long, value_name = "FILE")]
     pub output: Option<PathBuf>,
 
-    /// Output format (json, yaml, csv)
+    /// Output format (json, yaml, csv, html)
     #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
     pub format: ExportFormat,
 
@@ -223,6 +226,7 @@ impl ExportCommand {
                 }
                 csv_output
             }
+            ExportFormat::Html => render_html(&entries),
         };
 
         // Write to output
@@ -466,4 +470,11 @@ mod tests {
         assert_eq!(refs.len(), 1);
         assert!(refs.contains(&"explore".to_string()));
     }
+
+    #[test]
+    fn test_html_format_flag() {
+        let cmd =
+            ExportCommand::try_parse_from(["export", "abcd1234", "--format", "html"]).unwrap();
+        assert_eq!(cmd.format, ExportFormat::Html);
+    }
 }
@@ -0,0 +1,676 @@
+//! Conversion of session transcripts written by other agent CLIs.
+//!
+//! Supported sources:
+//! - Claude Code project transcripts (`~/.claude/projects/<project>/<session>.jsonl`)
+//! - Codex rollouts (`~/.codex/sessions/YYYY/MM/DD/rollout-*.jsonl`), both the
+//!   current `{type, payload}` lines and the older bare response items
+//!
+//! Transcripts are converted into `cortex-storage` messages, with tool calls
+//! renamed onto Cortex's tools where there is an equivalent.
+
+use std::collections::HashMap;
+
+use anyhow::{Result, bail};
+use chrono::DateTime;
+use cortex_storage::{StoredMessage, StoredToolCall};
+use serde_json::{Value, json};
+
+use super::ImportFormat;
+
+/// A session read from another agent's transcript.
+#[derive(Debug, Clone)]
+pub struct ForeignSession {
+    /// Agent that wrote the transcript.
+    pub source: ImportFormat,
+    /// Session ID in the source agent.
+    pub id: Option<String>,
+    /// Working directory of the session.
+    pub cwd: Option<String>,
+    /// Model used, if recorded.
+    pub model: Option<String>,
+    /// Start of the session (Unix seconds).
+    pub started_at: Option<i64>,
+    /// Conversation with tool calls attached to the assistant messages.
+    pub messages: Vec<StoredMessage>,
+}
+
+impl ForeignSession {
+    fn new(source: ImportFormat) -> Self {
+        Self {
+            source,
+            id: None,
+            cwd: None,
+            model: None,
+            started_at: None,
+            messages: Vec::new(),
+        }
+    }
+
+    /// Title from the first user message.
+    pub fn title(&self) -> Option<String> {
+        let first = self.messages.iter().find(|m| m.role == "user")?;
+        let line = first.content.lines().find(|l| !l.trim().is_empty())?.trim();
+        Some(if line.chars().count() > 80 {
+            format!("{}...", line.chars().take(77).collect::<String>())
+        } else {
+            line.to_string()
+        })
+    }
+
+    /// Number of tool calls across all messages.
+    pub fn tool_call_count(&self) -> usize {
+        self.messages.iter().map(|m| m.tool_calls.len()).sum()
+    }
+}
+
+/// Builds the message list, attaching tool calls and their results.
+struct Builder {
+    session: ForeignSession,
+    /// Position of each pending tool call, by call ID.
+    calls: HashMap<String, (usize, usize)>,
+}
+
+impl Builder {
+    fn new(source: ImportFormat) -> Self {
+        Self {
+            session: ForeignSession::new(source),
+            calls: HashMap::new(),
+        }
+    }
+
+    fn note_time(&mut self, timestamp: Option<i64>) {
+        if self.session.started_at.is_none() {
+            self.session.started_at = timestamp;
+        }
+    }
+
+    fn user(&mut self, text: &str, timestamp: Option<i64>) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let mut message = StoredMessage::user(text);
+        if let Some(ts) = timestamp {
+            message.timestamp = ts;
+        }
+        self.session.messages.push(message);
+    }
+
+    /// The assistant message currently being built, started if needed.
+    fn assistant(&mut self, timestamp: Option<i64>) -> &mut StoredMessage {
+        if self
+            .session
+            .messages
+            .last()
+            .is_none_or(|m| m.role != "assistant")
+        {
+            let mut message = StoredMessage::assistant("");
+            if let Some(ts) = timestamp {
+                message.timestamp = ts;
+            }
+            self.session.messages.push(message);
+        }
+        self.session.messages.last_mut().expect("just pushed")
+    }
+
+    fn assistant_text(&mut self, text: &str, timestamp: Option<i64>) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let message = self.assistant(timestamp);
+        if !message.content.is_empty() {
+            message.content.push_str("\n\n");
+        }
+        message.content.push_str(text);
+    }
+
+    fn tool_call(&mut self, id: &str, name: &str, input: Value, timestamp: Option<i64>) {
+        let message = self.assistant(timestamp);
+        message.tool_calls.push(StoredToolCall {
+            id: id.to_string(),
+            name: name.to_string(),
+            input,
+            output: None,
+            success: false,
+            duration_ms: None,
+        });
+        let position = (
+            self.session.messages.len() - 1,
+            self.session
+                .messages
+                .last()
+                .map_or(0, |m| m.tool_calls.len() - 1),
+        );
+        self.calls.insert(id.to_string(), position);
+    }
+
+    fn tool_result(&mut self, id: &str, output: String, success: bool, duration_ms: Option<u64>) {
+        let Some((message, call)) = self.calls.get(id).copied() else {
+            return;
+        };
+        let call = &mut self.session.messages[message].tool_calls[call];
+        call.output = Some(output);
+        call.success = success;
+        call.duration_ms = duration_ms;
+    }
+
+    fn finish(mut self) -> Result<ForeignSession> {
+        self.session
+            .messages
+            .retain(|m| !m.content.is_empty() || !m.tool_calls.is_empty());
+        if self.session.messages.is_empty() {
+            bail!(
+                "No messages found in {} transcript",
+                self.session.source.label()
+            );
+        }
+        Ok(self.session)
+    }
+}
+
+/// Guess the format of an import source from its content.
+pub fn detect_format(content: &str) -> ImportFormat {
+    if serde_json::from_str::<Value>(content)
+        .is_ok_and(|v| v.get("session").is_some() && v.get("messages").is_some())
+    {
+        return ImportFormat::Cortex;
+    }
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()).take(20) {
+        let Ok(value) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let kind = value.get("type").and_then(Value::as_str);
+        if value.get("sessionId").is_some() && kind.is_some() {
+            return ImportFormat::ClaudeCode;
+        }
+        if value.get("payload").is_some()
+            && matches!(
+                kind,
+                Some("session_meta" | "response_item" | "turn_context" | "event_msg")
+            )
+        {
+            return ImportFormat::Codex;
+        }
+        if kind == Some("message") && value.get("role").is_some()
+            || value.get("instructions").is_some() && value.get("id").is_some()
+        {
+            return ImportFormat::Codex;
+        }
+    }
+    ImportFormat::Cortex
+}
+
+/// Parse a Claude Code project transcript.
+pub fn parse_claude_code(content: &str) -> Result<ForeignSession> {
+    let mut builder = Builder::new(ImportFormat::ClaudeCode);
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        // Subagent transcripts and injected meta messages are not part of the conversation
+        if entry.get("isSidechain").and_then(Value::as_bool) == Some(true)
+            || entry.get("isMeta").and_then(Value::as_bool) == Some(true)
+        {
+            continue;
+        }
+        let timestamp = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(unix_seconds);
+        if builder.session.id.is_none() {
+            builder.session.id = str_field(&entry, "sessionId");
+        }
+        if builder.session.cwd.is_none() {
+            builder.session.cwd = str_field(&entry, "cwd");
+        }
+
+        let Some(message) = entry.get("message") else {
+            continue;
+        };
+        builder.note_time(timestamp);
+        let blocks = content_blocks(message.get("content"));
+
+        match entry.get("type").and_then(Value::as_str) {
+            Some("user") => {
+                let mut text = Vec::new();
+                for block in &blocks {
+                    match block.get("type").and_then(Value::as_str) {
+                        Some("text") => text.extend(str_field(block, "text")),
+                        Some("tool_result") => {
+                            let id = str_field(block, "tool_use_id").unwrap_or_default();
+                            let output = content_blocks(block.get("content"))
+                                .iter()
+                                .filter_map(|b| str_field(b, "text"))
+                                .collect::<Vec<_>>()
+                                .join("\n");
+                            let failed =
+                                block.get("is_error").and_then(Value::as_bool) == Some(true);
+                            builder.tool_result(&id, output, !failed, None);
+                        }
+                        _ => {}
+                    }
+                }
+                builder.user(&text.join("\n"), timestamp);
+            }
+            Some("assistant") => {
+                if let Some(model) = str_field(message, "model").filter(|m| m != "<synthetic>") {
+                    builder.session.model = Some(model);
+                }
+                for block in &blocks {
+                    match block.get("type").and_then(Value::as_str) {
+                        Some("text") => builder.assistant_text(
+                            &str_field(block, "text").unwrap_or_default(),
+                            timestamp,
+                        ),
+                        Some("tool_use") => {
+                            let id = str_field(block, "id").unwrap_or_default();
+                            let name = str_field(block, "name").unwrap_or_default();
+                            let input = block.get("input").cloned().unwrap_or(Value::Null);
+                            let (name, input) = map_claude_code_tool(&name, input);
+                            builder.tool_call(&id, &name, input, timestamp);
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    builder.finish()
+}
+
+/// Parse a Codex rollout.
+pub fn parse_codex(content: &str) -> Result<ForeignSession> {
+    let mut builder = Builder::new(ImportFormat::Codex);
+
+    for line in content.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<Value>(line) else {
+            continue;
+        };
+        let timestamp = entry
+            .get("timestamp")
+            .and_then(Value::as_str)
+            .and_then(unix_seconds);
+
+        // Current rollouts wrap every line as {timestamp, type, payload}; older
+        // ones start with a bare metadata line followed by bare response items.
+        let (kind, item) = match entry.get("payload") {
+            Some(payload) => (entry.get("type").and_then(Value::as_str), payload),
+            None if entry.get("instructions").is_some() || entry.get("record_type").is_some() => {
+                (Some("session_meta"), &entry)
+            }
+            None => (Some("response_item"), &entry),
+        };
+
+        match kind {
+            Some("session_meta") => {
+                if builder.session.id.is_none() {
+                    builder.session.id = str_field(item, "id");
+                }
+                if builder.session.cwd.is_none() {
+                    builder.session.cwd = str_field(item, "cwd");
+                }
+                builder.note_time(
+                    item.get("timestamp")
+                        .and_then(Value::as_str)
+                        .and_then(unix_seconds),
+                );
+            }
+            Some("turn_context") => {
+                builder.session.model = str_field(item, "model").or(builder.session.model.take());
+                if builder.session.cwd.is_none() {
+                    builder.session.cwd = str_field(item, "cwd");
+                }
+            }
+            Some("response_item") => {
+                builder.note_time(timestamp);
+                codex_response_item(&mut builder, item, timestamp);
+            }
+            // Event messages repeat what the response items already hold
+            _ => {}
+        }
+    }
+    builder.finish()
+}
+
+fn codex_response_item(builder: &mut Builder, item: &Value, timestamp: Option<i64>) {
+    match item.get("type").and_then(Value::as_str) {
+        Some("message") => {
+            let text = content_blocks(item.get("content"))
+                .iter()
+                .filter_map(|b| str_field(b, "text"))
+                .collect::<Vec<_>>()
+                .join("\n");
+            match item.get("role").and_then(Value::as_str) {
+                // Codex injects its environment and AGENTS.md as user messages
+                Some("user")
+                    if !text.trim_start().starts_with("<environment_context>")
+                        && !text.trim_start().starts_with("<user_instructions>") =>
+                {
+                    builder.user(&text, timestamp)
+                }
+                Some("assistant") => builder.assistant_text(&text, timestamp),
+                _ => {}
+            }
+        }
+        Some("function_call") => {
+            let id = str_field(item, "call_id").unwrap_or_default();
+            let name = str_field(item, "name").unwrap_or_default();
+            // Arguments are a JSON document encoded as a string
+            let input = match item.get("arguments") {
+                Some(Value::String(raw)) => {
+                    serde_json::from_str(raw).unwrap_or_else(|_| Value::String(raw.clone()))
+                }
+                Some(other) => other.clone(),
+                None => Value::Null,
+            };
+            let (name, input) = map_codex_tool(&name, input);
+            builder.tool_call(&id, &name, input, timestamp);
+        }
+        Some("custom_tool_call") => {
+            let id = str_field(item, "call_id").unwrap_or_default();
+            let name = str_field(item, "name").unwrap_or_default();
+            let input = json!({ "input": item.get("input").cloned().unwrap_or(Value::Null) });
+            let (name, input) = map_codex_tool(&name, input);
+            builder.tool_call(&id, &name, input, timestamp);
+        }
+        Some("local_shell_call") => {
+            let id = str_field(item, "call_id")
+                .or_else(|| str_field(item, "id"))
+                .unwrap_or_default();
+            let action = item.get("action").cloned().unwrap_or(Value::Null);
+            let (name, input) = map_codex_tool("shell", action);
+            builder.tool_call(&id, &name, input, timestamp);
+        }
+        Some("function_call_output" | "custom_tool_call_output") => {
+            let id = str_field(item, "call_id").unwrap_or_default();
+            let (output, success, duration_ms) = codex_tool_output(item.get("output"));
+            builder.tool_result(&id, output, success, duration_ms);
+        }
+        _ => {}
+    }
+}
+
+/// Output, success and duration of a Codex tool result.
+///
+/// Shell results are a JSON string of `{output, metadata: {exit_code, duration_seconds}}`;
+/// other tools return plain text.
+fn codex_tool_output(output: Option<&Value>) -> (String, bool, Option<u64>) {
+    let raw = match output {
+        Some(Value::String(s)) => s.clone(),
+        Some(Value::Object(o)) => o
+            .get("content")
+            .and_then(Value::as_str)
+            .map(String::from)
+            .unwrap_or_else(|| Value::Object(o.clone()).to_string()),
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    let Ok(parsed) = serde_json::from_str::<Value>(&raw) else {
+        return (raw, true, None);
+    };
+    let Some(text) = str_field(&parsed, "output") else {
+        return (raw, true, None);
+    };
+    let metadata = parsed.get("metadata");
+    let exit_code = metadata
+        .and_then(|m| m.get("exit_code"))
+        .and_then(Value::as_i64);
+    let duration_ms = metadata
+        .and_then(|m| m.get("duration_seconds"))
+        .and_then(Value::as_f64)
+        .map(|s| (s * 1000.0) as u64);
+    (text, exit_code.is_none_or(|code| code == 0), duration_ms)
+}
+
+/// Rename a Claude Code tool call onto the matching Cortex tool.
+fn map_claude_code_tool(name: &str, input: Value) -> (String, Value) {
+    let arg = |key: &str| input.get(key).cloned().unwrap_or(Value::Null);
+    let mapped = match name {
+        "Bash" => {
+            return (
+                "Execute".to_string(),
+                json!({ "command": ["bash", "-c", arg("command")] }),
+            );
+        }
+        "Read" => json!({
+            "file_path": arg("file_path"),
+            "offset": arg("offset"),
+            "limit": arg("limit"),
+        }),
+        "Write" => {
+            return (
+                "Create".to_string(),
+                json!({ "file_path": arg("file_path"), "content": arg("content") }),
+            );
+        }
+        "Edit" => json!({
+            "file_path": arg("file_path"),
+            "old_str": arg("old_string"),
+            "new_str": arg("new_string"),
+            "change_all": input.get("replace_all").and_then(Value::as_bool).unwrap_or(false),
+        }),
+        "MultiEdit" => {
+            let edits: Vec<Value> = input
+                .get("edits")
+                .and_then(Value::as_array)
+                .map(|edits| {
+                    edits
+                        .iter()
+                        .map(|edit| {
+                            json!({
+                                "file_path": arg("file_path"),
+                                "old_str": edit.get("old_string").cloned().unwrap_or(Value::Null),
+                                "new_str": edit.get("new_string").cloned().unwrap_or(Value::Null),
+                                "change_all": edit
+                                    .get("replace_all")
+                                    .and_then(Value::as_bool)
+                                    .unwrap_or(false),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            json!({ "edits": edits })
+        }
+        "Glob" => json!({
+            "patterns": [arg("pattern")],
+            "directory": arg("path"),
+        }),
+        "Grep" => json!({
+            "pattern": arg("pattern"),
+            "path": arg("path"),
+            "glob_pattern": arg("glob"),
+            "case_insensitive": input.get("-i").and_then(Value::as_bool).unwrap_or(false),
+        }),
+        "LS" => json!({ "directory_path": arg("path") }),
+        "WebFetch" => return ("FetchUrl".to_string(), json!({ "url": arg("url") })),
+        "WebSearch" => json!({ "query": arg("query") }),
+        "TodoWrite" => json!({ "todos": todos(input.get("todos"), "content") }),
+        "Task" => json!({
+            "agent": arg("subagent_type"),
+            "task": arg("prompt"),
+            "context": arg("description"),
+        }),
+        _ => return (name.to_string(), input),
+    };
+    (name.to_string(), without_nulls(mapped))
+}
+
+/// Rename a Codex tool call onto the matching Cortex tool.
+fn map_codex_tool(name: &str, input: Value) -> (String, Value) {
+    match name {
+        "shell" | "container.exec" | "local_shell" => {
+            let command = input.get("command").cloned().unwrap_or(Value::Null);
+            let command = match command {
+                Value::String(line) => json!(["bash", "-c", line]),
+                other => other,
+            };
+            (
+                "Execute".to_string(),
+                without_nulls(json!({
+                    "command": command,
+                    "workdir": input.get("workdir").or_else(|| input.get("working_directory")).cloned(),
+                })),
+            )
+        }
+        "apply_patch" => {
+            let patch = input
+                .get("input")
+                .or_else(|| input.get("patch"))
+                .cloned()
+                .unwrap_or(Value::Null);
+            ("ApplyPatch".to_string(), json!({ "patch": patch }))
+        }
+        "update_plan" => (
+            "TodoWrite".to_string(),
+            json!({ "todos": todos(input.get("plan"), "step") }),
+        ),
+        "web_search" => ("WebSearch".to_string(), input),
+        _ => (name.to_string(), input),
+    }
+}
+
+/// Convert a todo or plan list into `TodoWrite` items.
+fn todos(items: Option<&Value>, text_key: &str) -> Vec<Value> {
+    items
+        .and_then(Value::as_array)
+        .map(|items| {
+            items
+                .iter()
+                .enumerate()
+                .map(|(i, item)| {
+                    json!({
+                        "id": item
+                            .get("id")
+                            .and_then(Value::as_str)
+                            .map(String::from)
+                            .unwrap_or_else(|| (i + 1).to_string()),
+                        "content": item.get(text_key).cloned().unwrap_or(Value::Null),
+                        "status": item.get("status").cloned().unwrap_or_else(|| json!("pending")),
+                        "priority": item.get("priority").cloned().unwrap_or_else(|| json!("medium")),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Content as a list of blocks; plain strings become a single text block.
+fn content_blocks(content: Option<&Value>) -> Vec<Value> {
+    match content {
+        Some(Value::String(text)) => vec![json!({ "type": "text", "text": text })],
+        Some(Value::Array(blocks)) => blocks.clone(),
+        _ => Vec::new(),
+    }
+}
+
+fn without_nulls(value: Value) -> Value {
+    match value {
+        Value::Object(map) => {
+            Value::Object(map.into_iter().filter(|(_, v)| !v.is_null()).collect())
+        }
+        other => other,
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(Value::as_str).map(String::from)
+}
+
+fn unix_seconds(timestamp: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .ok()
+        .map(|t| t.timestamp())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CLAUDE_CODE: &str = r#"{"type":"summary","summary":"Fix tests","leafUuid":"x"}
+{"type":"user","sessionId":"cc-1","cwd":"/repo","isSidechain":false,"timestamp":"2025-06-01T10:00:00.000Z","message":{"role":"user","content":"Fix the failing test"}}
+{"type":"assistant","sessionId":"cc-1","cwd":"/repo","timestamp":"2025-06-01T10:00:02.000Z","message":{"role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"text","text":"Running the tests."}]}}
+{"type":"assistant","sessionId":"cc-1","cwd":"/repo","timestamp":"2025-06-01T10:00:03.000Z","message":{"role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"toolu_1","name":"Bash","input":{"command":"cargo test","description":"Run tests"}}]}}
+{"type":"user","sessionId":"cc-1","cwd":"/repo","timestamp":"2025-06-01T10:00:09.000Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_1","content":"1 failed","is_error":true}]}}
+{"type":"assistant","sessionId":"cc-1","cwd":"/repo","timestamp":"2025-06-01T10:00:10.000Z","message":{"role":"assistant","model":"claude-sonnet-4-20250514","content":[{"type":"tool_use","id":"toolu_2","name":"Edit","input":{"file_path":"src/lib.rs","old_string":"a","new_string":"b"}}]}}
+{"type":"user","sessionId":"cc-1","cwd":"/repo","isSidechain":true,"timestamp":"2025-06-01T10:00:11.000Z","message":{"role":"user","content":"subagent prompt"}}
+{"type":"user","sessionId":"cc-1","cwd":"/repo","timestamp":"2025-06-01T10:00:12.000Z","message":{"role":"user","content":[{"type":"tool_result","tool_use_id":"toolu_2","content":[{"type":"text","text":"Edited"}]}]}}"#;
+
+    const CODEX: &str = r#"{"timestamp":"2025-06-01T10:00:00.000Z","type":"session_meta","payload":{"id":"cx-1","timestamp":"2025-06-01T10:00:00.000Z","cwd":"/repo","originator":"codex_cli_rs","cli_version":"0.30.0"}}
+{"timestamp":"2025-06-01T10:00:00.100Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"<environment_context>\n  <cwd>/repo</cwd>\n</environment_context>"}]}}
+{"timestamp":"2025-06-01T10:00:00.200Z","type":"turn_context","payload":{"cwd":"/repo","model":"gpt-5-codex"}}
+{"timestamp":"2025-06-01T10:00:01.000Z","type":"response_item","payload":{"type":"message","role":"user","content":[{"type":"input_text","text":"List the files"}]}}
+{"timestamp":"2025-06-01T10:00:01.000Z","type":"event_msg","payload":{"type":"user_message","message":"List the files"}}
+{"timestamp":"2025-06-01T10:00:02.000Z","type":"response_item","payload":{"type":"function_call","name":"shell","arguments":"{\"command\":[\"ls\",\"-la\"],\"workdir\":\"/repo\"}","call_id":"call_1"}}
+{"timestamp":"2025-06-01T10:00:03.000Z","type":"response_item","payload":{"type":"function_call_output","call_id":"call_1","output":"{\"output\":\"Cargo.toml\\nsrc\\n\",\"metadata\":{\"exit_code\":0,\"duration_seconds\":0.25}}"}}
+{"timestamp":"2025-06-01T10:00:04.000Z","type":"response_item","payload":{"type":"message","role":"assistant","content":[{"type":"output_text","text":"There are two entries."}]}}"#;
+
+    #[test]
+    fn test_detect_format() {
+        assert_eq!(detect_format(CLAUDE_CODE), ImportFormat::ClaudeCode);
+        assert_eq!(detect_format(CODEX), ImportFormat::Codex);
+        assert_eq!(
+            detect_format(r#"{"type":"message","role":"user","content":[]}"#),
+            ImportFormat::Codex
+        );
+        assert_eq!(
+            detect_format(r#"{"version":1,"session":{},"messages":[]}"#),
+            ImportFormat::Cortex
+        );
+    }
+
+    #[test]
+    fn test_parse_claude_code() {
+        let session = parse_claude_code(CLAUDE_CODE).unwrap();
+        assert_eq!(session.id.as_deref(), Some("cc-1"));
+        assert_eq!(session.cwd.as_deref(), Some("/repo"));
+        assert_eq!(session.model.as_deref(), Some("claude-sonnet-4-20250514"));
+        assert_eq!(session.title().as_deref(), Some("Fix the failing test"));
+
+        // Tool-result-only user lines do not become messages
+        assert_eq!(session.messages.len(), 2);
+        let assistant = &session.messages[1];
+        assert_eq!(assistant.content, "Running the tests.");
+        assert_eq!(assistant.tool_calls.len(), 2);
+
+        let bash = &assistant.tool_calls[0];
+        assert_eq!(bash.name, "Execute");
+        assert_eq!(bash.input["command"], json!(["bash", "-c", "cargo test"]));
+        assert_eq!(bash.output.as_deref(), Some("1 failed"));
+        assert!(!bash.success);
+
+        let edit = &assistant.tool_calls[1];
+        assert_eq!(edit.name, "Edit");
+        assert_eq!(edit.input["old_str"], "a");
+        assert_eq!(edit.input["new_str"], "b");
+        assert!(edit.success);
+    }
+
+    #[test]
+    fn test_parse_codex() {
+        let session = parse_codex(CODEX).unwrap();
+        assert_eq!(session.id.as_deref(), Some("cx-1"));
+        assert_eq!(session.model.as_deref(), Some("gpt-5-codex"));
+
+        // The environment context and duplicate event_msg are skipped
+        assert_eq!(session.messages.len(), 2);
+        assert_eq!(session.messages[0].content, "List the files");
+
+        let assistant = &session.messages[1];
+        assert_eq!(assistant.content, "There are two entries.");
+        let shell = &assistant.tool_calls[0];
+        assert_eq!(shell.name, "Execute");
+        assert_eq!(shell.input["command"], json!(["ls", "-la"]));
+        assert_eq!(shell.output.as_deref(), Some("Cargo.toml\nsrc\n"));
+        assert_eq!(shell.duration_ms, Some(250));
+        assert!(shell.success);
+    }
+
+    #[test]
+    fn test_empty_transcript() {
+        assert!(parse_codex("").is_err());
+        assert!(parse_claude_code("{\"type\":\"summary\"}").is_err());
+    }
+}
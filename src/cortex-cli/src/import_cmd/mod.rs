@@ -1,6 +1,9 @@
 //! Session import command for Cortex CLI.
 //!
-//! Imports a session from a portable JSON format (exported or shared).
+//! Imports a session from a portable JSON format (exported or shared), or
+//! converts a transcript written by another agent CLI (see [`foreign`]).
+
+mod foreign;
 
 use anyhow::{Context, Result, bail};
 use clap::Parser;
@@ -11,23 +14,64 @@ use crate::styled_output::{print_info, print_success, print_warning};
 use cortex_engine::rollout::recorder::{RolloutRecorder, SessionMeta};
 use cortex_engine::rollout::{SESSIONS_SUBDIR, get_rollout_path};
 use cortex_protocol::{
-    AgentMessageEvent, ConversationId, Event, EventMsg, ExecCommandEndEvent, ExecCommandSource,
-    ParsedCommand, UserMessageEvent,
+    AgentMessageEvent, ConversationId, Event, EventMsg, ExecCommandBeginEvent, ExecCommandEndEvent,
+    ExecCommandSource, ParsedCommand, UserMessageEvent,
 };
+use cortex_storage::{SessionStorage, StoredMessage, StoredSession, StoredToolCall};
 
 use crate::agent_cmd::load_all_agents;
 use crate::export_cmd::{ExportMessage, SessionExport};
+use foreign::{ForeignSession, detect_format, parse_claude_code, parse_codex};
 
 /// Maximum depth for processing messages to prevent stack overflow from deeply nested structures.
 const MAX_PROCESSING_DEPTH: usize = 10000;
 
-/// Import a session from JSON format.
+/// Format of the session being imported.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ImportFormat {
+    /// Detect the format from the content (default)
+    #[default]
+    Auto,
+    /// Cortex portable JSON (from `cortex export`)
+    Cortex,
+    /// Claude Code project transcript (JSONL)
+    ClaudeCode,
+    /// Codex rollout (JSONL)
+    Codex,
+}
+
+impl ImportFormat {
+    /// Human-readable name of the agent that wrote the session.
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Auto => "auto-detected",
+            Self::Cortex => "Cortex",
+            Self::ClaudeCode => "Claude Code",
+            Self::Codex => "Codex",
+        }
+    }
+
+    /// Tag given to sessions imported from this format.
+    fn tag(self) -> &'static str {
+        match self {
+            Self::Auto | Self::Cortex => "cortex",
+            Self::ClaudeCode => "claude-code",
+            Self::Codex => "codex",
+        }
+    }
+}
+
+/// Import a session from JSON format or another agent's transcript.
 #[derive(Debug, Parser)]
 pub struct ImportCommand {
-    /// Path to the JSON file to import, URL to fetch, or "-" for stdin
+    /// Path to the file to import, URL to fetch, or "-" for stdin
     #[arg(value_name = "FILE_OR_URL")]
     pub source: String,
 
+    /// Format of the source (cortex, claude-code, codex); detected when omitted
+    #[arg(long = "from", value_enum, default_value_t = ImportFormat::Auto)]
+    pub format: ImportFormat,
+
     /// Force import even if session already exists
     #[arg(short, long, default_value_t = false)]
     pub force: bool,
@@ -69,6 +113,23 @@ impl ImportCommand {
             (content, false)
         };
 
+        let format = match self.format {
+            ImportFormat::Auto => detect_format(&json_content),
+            format => format,
+        };
+        let session = match format {
+            ImportFormat::ClaudeCode => Some(parse_claude_code(&json_content)?),
+            ImportFormat::Codex => Some(parse_codex(&json_content)?),
+            ImportFormat::Auto | ImportFormat::Cortex => None,
+        };
+        if let Some(session) = session {
+            let conversation_id = import_foreign_session(&cortex_home, &session)?;
+            if self.resume {
+                resume_session(conversation_id).await?;
+            }
+            return Ok(());
+        }
+
         // Parse the export with helpful error messages
         let export: SessionExport = serde_json::from_str(&json_content).map_err(|e| {
             // Create a helpful error message with content preview
@@ -217,23 +278,222 @@ impl ImportCommand {
         println!("\nTo resume: cortex resume {new_conversation_id}");
 
         if self.resume {
-            // Launch resume
-            print_info("Resuming session...");
-            let config = cortex_engine::Config::default();
+            resume_session(new_conversation_id).await?;
+        }
 
-            #[cfg(feature = "cortex-tui")]
-            {
-                cortex_tui::resume(config, new_conversation_id).await?;
-            }
+        Ok(())
+    }
+}
 
-            #[cfg(not(feature = "cortex-tui"))]
-            {
-                compile_error!("The 'cortex-tui' feature must be enabled");
-            }
+/// Launch the TUI on an imported session.
+async fn resume_session(conversation_id: ConversationId) -> Result<()> {
+    print_info("Resuming session...");
+    let config = crate::utils::load_config(None).await?;
+
+    #[cfg(feature = "cortex-tui")]
+    {
+        cortex_tui::resume(config, conversation_id).await?;
+    }
+
+    #[cfg(not(feature = "cortex-tui"))]
+    {
+        compile_error!("The 'cortex-tui' feature must be enabled");
+    }
+
+    Ok(())
+}
+
+/// Store a session converted from another agent as a new Cortex session.
+///
+/// The rollout is what `cortex resume` replays. Resume only restores user and
+/// assistant messages, so each assistant message also lists the tool calls it
+/// made; the calls themselves are recorded as exec events for exports.
+fn import_foreign_session(cortex_home: &Path, session: &ForeignSession) -> Result<ConversationId> {
+    let conversation_id = ConversationId::new();
+    std::fs::create_dir_all(cortex_home.join(SESSIONS_SUBDIR))?;
+
+    let cwd = session
+        .cwd
+        .as_ref()
+        .map(PathBuf::from)
+        .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
+    let model = session
+        .model
+        .clone()
+        .unwrap_or_else(|| "unknown".to_string());
+    let started_at = session
+        .started_at
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .unwrap_or_else(chrono::Utc::now);
+
+    let mut recorder = RolloutRecorder::new(&cortex_home.to_path_buf(), conversation_id)?;
+    recorder.init()?;
+    recorder.record_meta(&SessionMeta {
+        id: conversation_id,
+        parent_id: None,
+        fork_point: None,
+        timestamp: started_at.to_rfc3339(),
+        cwd: cwd.clone(),
+        model: model.clone(),
+        cli_version: env!("CARGO_PKG_VERSION").to_string(),
+        instructions: None,
+    })?;
+
+    let mut turn_id = 0u64;
+    for message in &session.messages {
+        if message.role == "user" {
+            turn_id += 1;
+        }
+        for event in stored_message_to_events(message, turn_id, &cwd) {
+            recorder.record_event(&Event {
+                id: turn_id.to_string(),
+                msg: event,
+            })?;
         }
+    }
+    recorder.flush()?;
+
+    // Keep the conversation in session storage under the same ID
+    let mut stored = StoredSession::with_id(
+        conversation_id.to_string(),
+        model,
+        cwd.display().to_string(),
+    );
+    stored.created_at = started_at.timestamp();
+    stored.updated_at = session
+        .messages
+        .last()
+        .map_or(stored.created_at, |m| m.timestamp);
+    stored.title = session.title();
+    stored.tags = vec!["imported".to_string(), session.source.tag().to_string()];
+    let storage = SessionStorage::new()?;
+    storage.init_sync()?;
+    storage.save_session_sync(&stored)?;
+    for message in &session.messages {
+        storage.append_message_sync(&stored.id, message)?;
+    }
 
-        Ok(())
+    print_success(&format!(
+        "Imported {} session as: {conversation_id}",
+        session.source.label()
+    ));
+    if let Some(id) = &session.id {
+        println!("  Original ID: {id}");
+    }
+    if let Some(title) = &stored.title {
+        println!("  Title: {title}");
     }
+    println!(
+        "  Messages: {} ({} tool calls)",
+        session.messages.len(),
+        session.tool_call_count()
+    );
+    println!("\nTo resume: cortex resume {conversation_id}");
+
+    Ok(conversation_id)
+}
+
+/// Convert a stored message into rollout events.
+fn stored_message_to_events(message: &StoredMessage, turn_id: u64, cwd: &Path) -> Vec<EventMsg> {
+    if message.role == "user" {
+        return vec![EventMsg::UserMessage(UserMessageEvent {
+            id: None,
+            parent_id: None,
+            message: message.content.clone(),
+            images: None,
+        })];
+    }
+
+    let mut text = message.content.clone();
+    if !message.tool_calls.is_empty() {
+        if !text.is_empty() {
+            text.push_str("\n\n");
+        }
+        text.push_str("[Tool calls]");
+        for call in &message.tool_calls {
+            text.push_str(&format!(
+                "\n- {} {}{}",
+                call.name,
+                tool_call_subject(call),
+                if call.success { "" } else { " (failed)" }
+            ));
+        }
+    }
+
+    let mut events = vec![EventMsg::AgentMessage(AgentMessageEvent {
+        id: None,
+        parent_id: None,
+        message: text,
+        finish_reason: None,
+    })];
+    for call in &message.tool_calls {
+        let command = match call.input.get("command").and_then(|c| c.as_array()) {
+            Some(parts) if call.name == "Execute" => parts
+                .iter()
+                .map(|p| p.as_str().map_or_else(|| p.to_string(), String::from))
+                .collect(),
+            _ => vec![call.name.clone()],
+        };
+        let parsed_cmd = vec![ParsedCommand {
+            program: command.first().cloned().unwrap_or_default(),
+            args: command.iter().skip(1).cloned().collect(),
+        }];
+        let output = call.output.clone().unwrap_or_default();
+        events.push(EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+            call_id: call.id.clone(),
+            turn_id: turn_id.to_string(),
+            command: command.clone(),
+            cwd: cwd.to_path_buf(),
+            parsed_cmd: parsed_cmd.clone(),
+            source: ExecCommandSource::Agent,
+            interaction_input: None,
+            tool_name: Some(call.name.clone()),
+            tool_arguments: Some(call.input.clone()),
+        }));
+        events.push(EventMsg::ExecCommandEnd(Box::new(ExecCommandEndEvent {
+            call_id: call.id.clone(),
+            turn_id: turn_id.to_string(),
+            command,
+            cwd: cwd.to_path_buf(),
+            parsed_cmd,
+            source: ExecCommandSource::Agent,
+            interaction_input: None,
+            stdout: output.clone(),
+            stderr: String::new(),
+            aggregated_output: output.clone(),
+            exit_code: if call.success { 0 } else { 1 },
+            duration_ms: call.duration_ms.unwrap_or(0),
+            formatted_output: output,
+            metadata: None,
+        })));
+    }
+    events
+}
+
+/// What a tool call acted on, for the summary in the assistant message.
+fn tool_call_subject(call: &StoredToolCall) -> String {
+    let input = &call.input;
+    if let Some(parts) = input.get("command").and_then(|c| c.as_array()) {
+        let parts: Vec<&str> = parts.iter().filter_map(|p| p.as_str()).collect();
+        // Show the script rather than the `bash -c` wrapper
+        let command = match parts.as_slice() {
+            ["bash" | "sh", "-c" | "-lc", script] => script.to_string(),
+            _ => parts.join(" "),
+        };
+        return format!("`{}`", command.lines().next().unwrap_or_default());
+    }
+    [
+        "file_path",
+        "directory_path",
+        "path",
+        "url",
+        "query",
+        "pattern",
+    ]
+    .iter()
+    .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+    .map(String::from)
+    .unwrap_or_default()
 }
 
 /// Fetch content from a URL.
@@ -549,6 +809,7 @@ mod tests {
     async fn test_import_empty_source_validation() {
         let cmd = ImportCommand {
             source: String::new(),
+            format: ImportFormat::Auto,
             force: false,
             resume: false,
         };
@@ -563,6 +824,7 @@ mod tests {
     async fn test_import_whitespace_source_validation() {
         let cmd = ImportCommand {
             source: "   ".to_string(),
+            format: ImportFormat::Auto,
             force: false,
             resume: false,
         };
@@ -690,4 +952,37 @@ mod tests {
         assert!(!missing.contains(&"build".to_string()));
         assert!(!missing.contains(&"plan".to_string()));
     }
+
+    #[test]
+    fn test_stored_message_to_events_with_tool_calls() {
+        let message =
+            StoredMessage::assistant("Running the tests.").with_tool_call(StoredToolCall {
+                id: "call_1".to_string(),
+                name: "Execute".to_string(),
+                input: serde_json::json!({"command": ["bash", "-c", "cargo test"]}),
+                output: Some("1 failed".to_string()),
+                success: false,
+                duration_ms: Some(900),
+            });
+
+        let events = stored_message_to_events(&message, 1, Path::new("/repo"));
+        assert_eq!(events.len(), 3);
+        let EventMsg::AgentMessage(agent) = &events[0] else {
+            panic!("expected an agent message");
+        };
+        assert!(
+            agent
+                .message
+                .ends_with("[Tool calls]\n- Execute `cargo test` (failed)")
+        );
+        let EventMsg::ExecCommandBegin(begin) = &events[1] else {
+            panic!("expected the exec begin event");
+        };
+        assert_eq!(begin.command, vec!["bash", "-c", "cargo test"]);
+        let EventMsg::ExecCommandEnd(end) = &events[2] else {
+            panic!("expected the exec end event");
+        };
+        assert_eq!(end.exit_code, 1);
+        assert_eq!(end.formatted_output, "1 failed");
+    }
 }
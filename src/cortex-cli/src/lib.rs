@@ -9,6 +9,7 @@
 //! - GitHub integration commands
 //! - Login management
 //! - MCP commands
+//! - MCP server (stdio)
 //! - Models listing
 //! - PR checkout commands
 //! - Run command (non-interactive execution)
@@ -261,6 +262,7 @@ pub fn restore_terminal() {
 pub mod acp_cmd;
 pub mod agent_cmd;
 pub mod alias_cmd;
+pub mod audit_cmd;
 pub mod cache_cmd;
 pub mod compact_cmd;
 pub mod completion_setup;
@@ -276,8 +278,10 @@ pub mod lock_cmd;
 pub mod login;
 pub mod logs_cmd;
 pub mod mcp_cmd;
+pub mod mcp_server_cmd;
 pub mod models_cmd;
 pub mod plugin_cmd;
+pub mod policy_cmd;
 pub mod pr_cmd;
 pub mod run_cmd;
 pub mod scrape_cmd;
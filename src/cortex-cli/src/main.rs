@@ -176,9 +176,13 @@ async fn main() -> Result<()> {
             log_level.as_filter_str().to_string()
         };
 
-        tracing_subscriber::fmt()
-            .with_env_filter(&filter_str)
-            .init();
+        let subscriber = tracing_subscriber::fmt().with_env_filter(&filter_str);
+        if matches!(&cli.command, Some(Commands::McpServer(_))) {
+            // stdout carries the MCP JSON-RPC stream
+            subscriber.with_writer(std::io::stderr).init();
+        } else {
+            subscriber.init();
+        }
     }
 
     // Background update check (non-blocking)
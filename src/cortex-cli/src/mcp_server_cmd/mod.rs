@@ -0,0 +1,135 @@
+//! MCP server command.
+//!
+//! Runs Cortex as a Model Context Protocol server over stdio so that other
+//! agents and editors can use it:
+//! - Tools: the engine file, search, edit, shell and web tools, gated by the
+//!   autonomy level and permission rules (see [`policy`])
+//! - Resources: session transcripts and skills (see [`resources`])
+//! - Prompts: custom commands (see [`prompts`])
+//!
+//! stdout carries the JSON-RPC stream, so nothing else may be printed there.
+
+mod policy;
+mod prompts;
+mod resources;
+mod tools;
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use cortex_engine::PermissionManager;
+use cortex_engine::tools::ToolRegistry;
+use cortex_mcp_server::McpServerBuilder;
+
+use crate::exec_cmd::AutonomyLevel;
+use policy::ToolPolicy;
+use prompts::CommandPrompts;
+use resources::CortexResources;
+
+/// MCP server CLI command.
+#[derive(Debug, Parser)]
+#[command(about = "Run Cortex as an MCP server over stdio")]
+pub struct McpServerCli {
+    /// Working directory for tools, project skills and project commands.
+    #[arg(long = "cwd", short = 'C', value_name = "DIR")]
+    pub cwd: Option<PathBuf>,
+
+    /// Autonomy level for tool calls (default: read-only).
+    /// Same levels as `cortex exec --auto`.
+    #[arg(long = "auto", value_enum)]
+    pub autonomy: Option<AutonomyLevel>,
+
+    /// Tools to expose (whitelist). Can be specified multiple times.
+    #[arg(long = "allow-tool", action = clap::ArgAction::Append)]
+    pub allow_tools: Vec<String>,
+
+    /// Tools to hide (blacklist). Can be specified multiple times.
+    #[arg(long = "deny-tool", action = clap::ArgAction::Append)]
+    pub deny_tools: Vec<String>,
+}
+
+impl McpServerCli {
+    /// Run the MCP server until stdin is closed.
+    pub async fn run(self) -> Result<()> {
+        // Built-in tools resolve relative paths against the process directory.
+        if let Some(cwd) = &self.cwd {
+            std::env::set_current_dir(cwd)
+                .with_context(|| format!("Failed to change directory to {}", cwd.display()))?;
+        }
+
+        // Permission rules come from the user's config.toml and project config
+        let config = crate::utils::load_config(None).await?;
+        let cwd = config.cwd.clone();
+        let autonomy = self.autonomy.unwrap_or_default();
+
+        let permissions = PermissionManager::new();
+        if let Err(e) = permissions.init().await {
+            tracing::warn!("Failed to load stored permissions: {}", e);
+        }
+        permissions.load_from_config(&config.permission).await;
+
+        let policy = Arc::new(ToolPolicy::new(cwd.clone(), autonomy, permissions));
+        let tools = tools::registry_tools(
+            Arc::new(ToolRegistry::new()),
+            policy,
+            &self.allow_tools,
+            &self.deny_tools,
+        );
+
+        let server = McpServerBuilder::new("cortex", env!("CARGO_PKG_VERSION"))
+            .with_tools_capability()
+            .resource_provider(Arc::new(CortexResources::new(
+                config.cortex_home.clone(),
+                cwd.clone(),
+            )))
+            .prompt_provider(Arc::new(CommandPrompts::new(&config.cortex_home, &cwd)))
+            .instructions(format!(
+                "Cortex tools operate on {} in {} mode. Calls outside that mode are refused.",
+                cwd.display(),
+                autonomy
+            ))
+            .build()?;
+        server.register_tools(tools).await;
+
+        tracing::info!(cwd = %cwd.display(), %autonomy, "Starting Cortex MCP server");
+        server.run_stdio().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mcp_server_cli_defaults() {
+        let cli = McpServerCli::try_parse_from(["mcp-server"]).expect("should parse with no args");
+
+        assert!(cli.cwd.is_none());
+        assert!(cli.autonomy.is_none());
+        assert!(cli.allow_tools.is_empty());
+        assert!(cli.deny_tools.is_empty());
+    }
+
+    #[test]
+    fn test_mcp_server_cli_options() {
+        let cli = McpServerCli::try_parse_from([
+            "mcp-server",
+            "-C",
+            "/work",
+            "--auto",
+            "medium",
+            "--allow-tool",
+            "Read",
+            "--deny-tool",
+            "Execute",
+        ])
+        .unwrap();
+
+        assert_eq!(cli.cwd, Some(PathBuf::from("/work")));
+        assert_eq!(cli.autonomy, Some(AutonomyLevel::Medium));
+        assert_eq!(cli.allow_tools, vec!["Read"]);
+        assert_eq!(cli.deny_tools, vec!["Execute"]);
+    }
+}
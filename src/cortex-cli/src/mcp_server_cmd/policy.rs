@@ -0,0 +1,419 @@
+//! Permission gating for tools exposed over MCP.
+//!
+//! An MCP client cannot answer approval prompts, so every call is decided up
+//! front: the permission manager can veto a call outright, and otherwise the
+//! autonomy level (and the sandbox policy derived from it) decides, exactly as
+//! it does for `cortex exec`.
+
+use std::path::{Path, PathBuf};
+
+use cortex_engine::safety::{RiskLevel, analyze_command};
+use cortex_engine::{PermissionContext, PermissionManager, PermissionResponse};
+use cortex_protocol::SandboxPolicy;
+use serde_json::Value;
+
+use crate::exec_cmd::AutonomyLevel;
+
+/// What a tool is able to do, which decides how its calls are checked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ToolKind {
+    /// Reads files or searches the workspace.
+    Read,
+    /// Creates or modifies files.
+    Write,
+    /// Runs a shell command.
+    Shell,
+    /// Talks to the network.
+    Network,
+}
+
+impl ToolKind {
+    /// Classify a registry tool, or `None` if it is not exposed over MCP.
+    ///
+    /// Session-bound tools (todos, plans, subagents, questions) only make
+    /// sense inside a Cortex conversation and are left out.
+    pub(super) fn of(tool: &str) -> Option<Self> {
+        match tool {
+            "Read" | "LS" | "Glob" | "Grep" | "SearchFiles" => Some(Self::Read),
+            "Create" | "Edit" | "MultiEdit" | "ApplyPatch" => Some(Self::Write),
+            "Execute" => Some(Self::Shell),
+            "FetchUrl" | "WebFetch" | "WebSearch" => Some(Self::Network),
+            _ => None,
+        }
+    }
+
+    /// Tool name used for permission rules and `[permission]` config.
+    fn permission_key(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Write => "edit",
+            Self::Shell => "bash",
+            Self::Network => "webfetch",
+        }
+    }
+}
+
+/// Decides whether an MCP tool call may run.
+pub(super) struct ToolPolicy {
+    cwd: PathBuf,
+    autonomy: AutonomyLevel,
+    sandbox: SandboxPolicy,
+    permissions: PermissionManager,
+}
+
+impl ToolPolicy {
+    /// Create a policy for the given working directory and autonomy level.
+    pub(super) fn new(
+        cwd: PathBuf,
+        autonomy: AutonomyLevel,
+        permissions: PermissionManager,
+    ) -> Self {
+        let sandbox = autonomy.to_sandbox_policy(&cwd);
+        Self {
+            cwd,
+            autonomy,
+            sandbox,
+            permissions,
+        }
+    }
+
+    /// Working directory tools run in.
+    pub(super) fn cwd(&self) -> &Path {
+        &self.cwd
+    }
+
+    /// Sandbox policy handed to tool handlers.
+    pub(super) fn sandbox_policy(&self) -> &SandboxPolicy {
+        &self.sandbox
+    }
+
+    /// Check a tool call, returning the reason when it is refused.
+    pub(super) async fn check(&self, tool: &str, arguments: &Value) -> Result<(), String> {
+        let Some(kind) = ToolKind::of(tool) else {
+            return Err(format!("Tool '{tool}' is not available over MCP"));
+        };
+
+        match kind {
+            ToolKind::Read => {
+                for path in target_paths(tool, arguments) {
+                    let path = self.resolve(&path);
+                    self.check_permission(
+                        kind,
+                        &path.display().to_string(),
+                        PermissionContext::for_file(&path),
+                    )
+                    .await?;
+                }
+                Ok(())
+            }
+            ToolKind::Write => self.check_write(tool, arguments).await,
+            ToolKind::Shell => self.check_shell(arguments).await,
+            ToolKind::Network => {
+                if !self.sandbox.has_full_network_access() {
+                    return Err(format!(
+                        "network access is disabled in {} mode",
+                        self.autonomy
+                    ));
+                }
+                let target = ["url", "query"]
+                    .iter()
+                    .find_map(|key| arguments.get(*key).and_then(Value::as_str))
+                    .unwrap_or_default();
+                self.check_permission(
+                    kind,
+                    target,
+                    PermissionContext::new().with_description(target),
+                )
+                .await
+            }
+        }
+    }
+
+    async fn check_write(&self, tool: &str, arguments: &Value) -> Result<(), String> {
+        if tool == "ApplyPatch" && arguments.get("dry_run").and_then(Value::as_bool) == Some(true) {
+            return Ok(());
+        }
+        if matches!(self.sandbox, SandboxPolicy::ReadOnly) {
+            return Err(format!(
+                "file modifications are not allowed in {} mode",
+                self.autonomy
+            ));
+        }
+
+        let paths = target_paths(tool, arguments);
+        if paths.is_empty() {
+            return Err("no target file given".to_string());
+        }
+
+        let roots = self.sandbox.get_writable_roots_with_cwd(&self.cwd);
+        for path in paths {
+            let path = self.resolve(&path);
+            if !self.sandbox.has_full_disk_write_access()
+                && !roots.iter().any(|root| root.is_path_writable(&path))
+            {
+                return Err(format!(
+                    "{} is outside the writable roots of the sandbox",
+                    path.display()
+                ));
+            }
+            self.check_permission(
+                ToolKind::Write,
+                &path.display().to_string(),
+                PermissionContext::for_file(&path),
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    async fn check_shell(&self, arguments: &Value) -> Result<(), String> {
+        let command: Vec<String> = match arguments.get("command") {
+            Some(Value::Array(parts)) => parts
+                .iter()
+                .filter_map(|p| p.as_str().map(str::to_string))
+                .collect(),
+            Some(Value::String(command)) => {
+                command.split_whitespace().map(str::to_string).collect()
+            }
+            _ => Vec::new(),
+        };
+        if command.is_empty() {
+            return Err("no command given".to_string());
+        }
+
+        let command_str = command.join(" ");
+        let analysis = analyze_command(&command, &self.cwd);
+        if analysis.risk_level == RiskLevel::Critical {
+            return Err(format!("{command_str}: {}", analysis.reason));
+        }
+
+        self.check_permission(
+            ToolKind::Shell,
+            &command_str,
+            PermissionContext::for_command(&command_str),
+        )
+        .await?;
+
+        let risk = match analysis.risk_level {
+            RiskLevel::Safe => "low",
+            RiskLevel::Medium => "medium",
+            RiskLevel::High | RiskLevel::Critical => "high",
+        };
+        if !self.autonomy.allows_risk(risk, &command_str) {
+            return Err(format!(
+                "command '{command_str}' (risk: {risk}) is not allowed in {} mode",
+                self.autonomy
+            ));
+        }
+        Ok(())
+    }
+
+    /// Refuse the call if a permission rule denies it.
+    ///
+    /// `Allow` and `Ask` both fall through to the autonomy checks: rules can
+    /// only narrow what the autonomy level permits, never widen it.
+    async fn check_permission(
+        &self,
+        kind: ToolKind,
+        action: &str,
+        context: PermissionContext,
+    ) -> Result<(), String> {
+        let response = self
+            .permissions
+            .request_permission(kind.permission_key(), action, &context)
+            .await;
+        if response == PermissionResponse::Deny {
+            return Err(format!("{action} is denied by permission rules"));
+        }
+        Ok(())
+    }
+
+    fn resolve(&self, path: &str) -> PathBuf {
+        let path = Path::new(path);
+        if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.cwd.join(path)
+        }
+    }
+}
+
+/// Files a tool call reads or writes, as given in its arguments.
+fn target_paths(tool: &str, arguments: &Value) -> Vec<String> {
+    let str_arg =
+        |value: &Value, key: &str| value.get(key).and_then(Value::as_str).map(str::to_string);
+
+    match tool {
+        "MultiEdit" => arguments
+            .get("edits")
+            .and_then(Value::as_array)
+            .map(|edits| {
+                edits
+                    .iter()
+                    .filter_map(|e| str_arg(e, "file_path"))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        "ApplyPatch" => arguments
+            .get("patch")
+            .and_then(Value::as_str)
+            .map(patch_paths)
+            .unwrap_or_default(),
+        "LS" => str_arg(arguments, "directory_path").into_iter().collect(),
+        _ => ["file_path", "path"]
+            .iter()
+            .find_map(|key| str_arg(arguments, key))
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// Files touched by a unified diff, from its `---`/`+++` headers.
+fn patch_paths(patch: &str) -> Vec<String> {
+    let mut paths: Vec<String> = Vec::new();
+    for line in patch.lines() {
+        let Some(header) = line
+            .strip_prefix("--- ")
+            .or_else(|| line.strip_prefix("+++ "))
+        else {
+            continue;
+        };
+        // Drop trailing timestamps ("path\t2024-01-01 ...").
+        let path = header.split('\t').next().unwrap_or(header).trim();
+        if path == "/dev/null" || path.is_empty() {
+            continue;
+        }
+        let path = path
+            .strip_prefix("a/")
+            .or_else(|| path.strip_prefix("b/"))
+            .unwrap_or(path);
+        if !paths.iter().any(|p| p == path) {
+            paths.push(path.to_string());
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn policy(cwd: &Path, autonomy: AutonomyLevel) -> ToolPolicy {
+        // Persisted grants are never loaded because `init` is not called.
+        ToolPolicy::new(cwd.to_path_buf(), autonomy, PermissionManager::new())
+    }
+
+    #[test]
+    fn test_tool_kind_classification() {
+        assert_eq!(ToolKind::of("Read"), Some(ToolKind::Read));
+        assert_eq!(ToolKind::of("ApplyPatch"), Some(ToolKind::Write));
+        assert_eq!(ToolKind::of("Execute"), Some(ToolKind::Shell));
+        assert_eq!(ToolKind::of("WebSearch"), Some(ToolKind::Network));
+        assert_eq!(ToolKind::of("TodoWrite"), None);
+        assert_eq!(ToolKind::of("Task"), None);
+    }
+
+    #[test]
+    fn test_patch_paths() {
+        let patch = "--- a/src/lib.rs\n+++ b/src/lib.rs\n@@ -1 +1 @@\n-a\n+b\n\
+                     --- /dev/null\n+++ b/new.txt\t2024-01-01\n@@ -0,0 +1 @@\n+x\n";
+        assert_eq!(patch_paths(patch), vec!["src/lib.rs", "new.txt"]);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_allows_reads_and_safe_commands() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = policy(dir.path(), AutonomyLevel::ReadOnly);
+
+        assert!(
+            policy
+                .check("Read", &json!({"file_path": "a.txt"}))
+                .await
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check("Execute", &json!({"command": ["ls", "-la"]}))
+                .await
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check("Execute", &json!({"command": ["cargo", "build"]}))
+                .await
+                .is_err()
+        );
+        assert!(
+            policy
+                .check(
+                    "Edit",
+                    &json!({"file_path": "a.txt", "old_str": "a", "new_str": "b"})
+                )
+                .await
+                .is_err()
+        );
+        assert!(
+            policy
+                .check("FetchUrl", &json!({"url": "https://example.com"}))
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_writes_limited_to_workspace() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = policy(dir.path(), AutonomyLevel::Low);
+
+        assert!(
+            policy
+                .check("Create", &json!({"file_path": "notes.md", "content": ""}))
+                .await
+                .is_ok()
+        );
+        let err = policy
+            .check(
+                "Create",
+                &json!({"file_path": "/etc/cortex-test", "content": ""}),
+            )
+            .await
+            .unwrap_err();
+        assert!(err.contains("outside the writable roots"));
+
+        let patch = "--- a/../../outside.txt\n+++ b/../../outside.txt\n";
+        assert!(
+            policy
+                .check("ApplyPatch", &json!({"patch": patch}))
+                .await
+                .is_err()
+        );
+        assert!(
+            policy
+                .check("ApplyPatch", &json!({"patch": patch, "dry_run": true}))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dangerous_commands_always_denied() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = policy(dir.path(), AutonomyLevel::High);
+
+        assert!(
+            policy
+                .check("Execute", &json!({"command": ["cargo", "test"]}))
+                .await
+                .is_ok()
+        );
+        assert!(
+            policy
+                .check("Execute", &json!({"command": ["rm", "-rf", "/"]}))
+                .await
+                .is_err()
+        );
+        assert!(policy.check("Execute", &json!({})).await.is_err());
+        assert!(policy.check("TodoWrite", &json!({})).await.is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! Custom commands exposed as MCP prompts.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use async_trait::async_trait;
+use cortex_engine::custom_command::list_variables;
+use cortex_engine::{CustomCommand, CustomCommandRegistry, TemplateContext, expand_template};
+use cortex_mcp_server::PromptProvider;
+use cortex_mcp_types::{Content, GetPromptResult, Prompt, PromptArgument, PromptMessage};
+
+/// Template variables filled in by Cortex rather than by the caller.
+const BUILTIN_VARIABLES: &[&str] = &[
+    "input",
+    "selection",
+    "clipboard",
+    "cwd",
+    "date",
+    "time",
+    "datetime",
+];
+
+/// Prompt provider backed by the custom command registry.
+pub(super) struct CommandPrompts {
+    cortex_home: PathBuf,
+    cwd: PathBuf,
+}
+
+impl CommandPrompts {
+    pub(super) fn new(cortex_home: &Path, cwd: &Path) -> Self {
+        Self {
+            cortex_home: cortex_home.to_path_buf(),
+            cwd: cwd.to_path_buf(),
+        }
+    }
+
+    async fn registry(&self) -> Result<CustomCommandRegistry> {
+        let registry = CustomCommandRegistry::new(&self.cortex_home, Some(&self.cwd));
+        registry.scan().await?;
+        Ok(registry)
+    }
+}
+
+#[async_trait]
+impl PromptProvider for CommandPrompts {
+    async fn list(&self) -> Result<Vec<Prompt>> {
+        let mut commands = self.registry().await?.list().await;
+        commands.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(commands.iter().map(prompt_for).collect())
+    }
+
+    async fn get(
+        &self,
+        name: &str,
+        arguments: Option<HashMap<String, String>>,
+    ) -> Result<GetPromptResult> {
+        let command = self
+            .registry()
+            .await?
+            .get(name)
+            .await
+            .ok_or_else(|| anyhow!("Prompt not found: {name}"))?;
+        let arguments = arguments.unwrap_or_default();
+
+        let mut result = GetPromptResult::new(vec![PromptMessage::user(Content::text(render(
+            &command, &arguments, &self.cwd,
+        )))]);
+        result.description = Some(command.description);
+        Ok(result)
+    }
+}
+
+/// Describe a custom command as a prompt.
+fn prompt_for(command: &CustomCommand) -> Prompt {
+    let mut prompt = Prompt::new(&command.name)
+        .with_description(&command.description)
+        .argument(PromptArgument::new("input").with_description("Text substituted for {{input}}"));
+    for variable in custom_variables(&command.template) {
+        let description = format!("Value substituted for {{{{{variable}}}}}");
+        prompt = prompt.argument(PromptArgument::required(variable).with_description(description));
+    }
+    prompt
+}
+
+/// Template variables the caller must supply.
+fn custom_variables(template: &str) -> Vec<String> {
+    let mut variables: Vec<String> = Vec::new();
+    for variable in list_variables(template) {
+        if BUILTIN_VARIABLES.contains(&variable.as_str())
+            || variable.starts_with("file:")
+            || variable.starts_with("env:")
+            || variables.contains(&variable)
+        {
+            continue;
+        }
+        variables.push(variable);
+    }
+    variables
+}
+
+/// Expand a command template with caller-supplied arguments.
+///
+/// Arguments are substituted only after `{{file:...}}` and `{{env:...}}`
+/// expansion, so a client cannot smuggle those directives in to read files
+/// or environment variables the command author did not reference.
+fn render(command: &CustomCommand, arguments: &HashMap<String, String>, cwd: &Path) -> String {
+    let placeholder = |name: &str| format!("\u{0}cortex-arg:{name}\u{0}");
+
+    let mut names: Vec<String> = custom_variables(&command.template);
+    names.push("input".to_string());
+
+    let mut ctx = TemplateContext::new(placeholder("input")).with_cwd(cwd.display().to_string());
+    for name in &names {
+        if name != "input" {
+            ctx = ctx.with_var(name.as_str(), placeholder(name));
+        }
+    }
+
+    let mut rendered = expand_template(&command.template, &ctx);
+    for name in &names {
+        let value = arguments.get(name).map(String::as_str).unwrap_or_default();
+        rendered = rendered.replace(&placeholder(name), value);
+    }
+    rendered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(template: &str) -> CustomCommand {
+        CustomCommand::new("review", "Review code", template)
+    }
+
+    #[test]
+    fn test_prompt_arguments() {
+        let prompt = prompt_for(&command(
+            "Review {{input}} focusing on {{focus}} in {{cwd}} ({{focus}})",
+        ));
+        let args = prompt.arguments.unwrap();
+        let names: Vec<&str> = args.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["input", "focus"]);
+        assert_eq!(args[1].required, Some(true));
+    }
+
+    #[test]
+    fn test_render_substitutes_arguments() {
+        let args = HashMap::from([
+            ("input".to_string(), "src/main.rs".to_string()),
+            ("focus".to_string(), "errors".to_string()),
+        ]);
+        let rendered = render(
+            &command("Review {{input}} for {{focus}} in {{cwd}}"),
+            &args,
+            Path::new("/work"),
+        );
+        assert_eq!(rendered, "Review src/main.rs for errors in /work");
+    }
+
+    #[test]
+    fn test_render_does_not_expand_directives_in_arguments() {
+        let args = HashMap::from([(
+            "input".to_string(),
+            "{{env:HOME}} {{file:/etc/hostname}}".to_string(),
+        )]);
+        let rendered = render(&command("Review {{input}}"), &args, Path::new("/work"));
+        assert_eq!(rendered, "Review {{env:HOME}} {{file:/etc/hostname}}");
+    }
+
+    #[tokio::test]
+    async fn test_prompts_from_command_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let commands_dir = dir.path().join(".cortex").join("commands");
+        std::fs::create_dir_all(&commands_dir).unwrap();
+        std::fs::write(
+            commands_dir.join("explain.md"),
+            "---\nname: explain\ndescription: Explain code\n---\n\nExplain {{input}}\n",
+        )
+        .unwrap();
+
+        let prompts = CommandPrompts::new(&dir.path().join("home"), dir.path());
+        let listed = prompts.list().await.unwrap();
+        assert!(listed.iter().any(|p| p.name == "explain"));
+
+        let args = HashMap::from([("input".to_string(), "lib.rs".to_string())]);
+        let result = prompts.get("explain", Some(args)).await.unwrap();
+        assert_eq!(result.description.as_deref(), Some("Explain code"));
+        match &result.messages[0].content {
+            Content::Text { text } => assert_eq!(text.trim(), "Explain lib.rs"),
+            other => panic!("unexpected content: {other:?}"),
+        }
+        assert!(prompts.get("missing", None).await.is_err());
+    }
+}
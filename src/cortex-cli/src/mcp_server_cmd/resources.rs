@@ -0,0 +1,213 @@
+//! Sessions and skills exposed as MCP resources.
+//!
+//! - `cortex://sessions/<id>`: Markdown transcript of a session recorded in
+//!   the current project
+//! - `cortex://skills/<name>`: instructions of a personal or project skill
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use cortex_engine::rollout::get_rollout_path;
+use cortex_engine::rollout::reader::{RolloutItem, get_session_meta, read_rollout};
+use cortex_engine::{SkillRegistry, list_sessions};
+use cortex_mcp_server::ResourceProvider;
+use cortex_mcp_types::{Resource, ResourceContent};
+use cortex_protocol::{ConversationId, EventMsg};
+
+const SESSIONS_PREFIX: &str = "cortex://sessions/";
+const SKILLS_PREFIX: &str = "cortex://skills/";
+const MARKDOWN: &str = "text/markdown";
+
+/// Resource provider for Cortex sessions and skills.
+pub(super) struct CortexResources {
+    cortex_home: PathBuf,
+    cwd: PathBuf,
+}
+
+impl CortexResources {
+    pub(super) fn new(cortex_home: PathBuf, cwd: PathBuf) -> Self {
+        Self { cortex_home, cwd }
+    }
+
+    fn skills(&self) -> SkillRegistry {
+        SkillRegistry::new(&self.cortex_home, Some(&self.cwd))
+    }
+
+    fn read_session(&self, id: &str) -> Result<String> {
+        // Parsing as a conversation ID also rules out path traversal.
+        let conversation_id: ConversationId = id
+            .parse()
+            .map_err(|_| anyhow!("Invalid session ID: {id}"))?;
+        let path = get_rollout_path(&self.cortex_home, &conversation_id);
+        if !path.exists() {
+            bail!("Session not found: {id}");
+        }
+        let entries = read_rollout(&path)
+            .with_context(|| format!("Failed to read session: {}", path.display()))?;
+
+        let mut transcript = format!("# Session {id}\n");
+        if let Some(meta) = get_session_meta(&entries) {
+            transcript.push_str(&format!(
+                "\n- Started: {}\n- Directory: {}\n",
+                meta.timestamp, meta.cwd
+            ));
+            if let Some(model) = &meta.model {
+                transcript.push_str(&format!("- Model: {model}\n"));
+            }
+        }
+        for entry in &entries {
+            let (role, text) = match &entry.item {
+                RolloutItem::EventMsg(EventMsg::UserMessage(msg)) => ("User", &msg.message),
+                RolloutItem::EventMsg(EventMsg::AgentMessage(msg)) => ("Assistant", &msg.message),
+                _ => continue,
+            };
+            transcript.push_str(&format!("\n## {role}\n\n{}\n", text.trim_end()));
+        }
+        Ok(transcript)
+    }
+}
+
+#[async_trait]
+impl ResourceProvider for CortexResources {
+    async fn list(&self) -> Result<Vec<Resource>> {
+        let mut resources = Vec::new();
+
+        for session in list_sessions(&self.cortex_home)?
+            .into_iter()
+            .filter(|s| s.cwd == self.cwd)
+        {
+            let mut description = format!(
+                "{} messages, started {}",
+                session.message_count, session.timestamp
+            );
+            if let Some(model) = &session.model {
+                description.push_str(&format!(" with {model}"));
+            }
+            resources.push(
+                Resource::new(
+                    format!("{SESSIONS_PREFIX}{}", session.id),
+                    format!("Session {}", session.id),
+                )
+                .with_description(description)
+                .with_mime_type(MARKDOWN),
+            );
+        }
+
+        let mut skills = self.skills().scan().await?;
+        skills.sort_by(|a, b| a.metadata.name.cmp(&b.metadata.name));
+        for skill in skills {
+            resources.push(
+                Resource::new(
+                    format!("{SKILLS_PREFIX}{}", skill.metadata.name),
+                    &skill.metadata.name,
+                )
+                .with_description(&skill.metadata.description)
+                .with_mime_type(MARKDOWN),
+            );
+        }
+
+        Ok(resources)
+    }
+
+    async fn read(&self, uri: &str) -> Result<ResourceContent> {
+        let text = if let Some(id) = uri.strip_prefix(SESSIONS_PREFIX) {
+            self.read_session(id)?
+        } else if let Some(name) = uri.strip_prefix(SKILLS_PREFIX) {
+            let registry = self.skills();
+            registry.scan().await?;
+            registry
+                .get(name)
+                .await
+                .ok_or_else(|| anyhow!("Skill not found: {name}"))?
+                .get_instructions()
+        } else {
+            bail!("Resource not found: {uri}");
+        };
+
+        let mut content = ResourceContent::text(uri, text);
+        content.mime_type = Some(MARKDOWN.to_string());
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SESSION_ID: &str = "0196d6a5-3f7c-7a31-9e52-6d2b9c1e4f10";
+
+    fn setup() -> (tempfile::TempDir, CortexResources) {
+        let dir = tempfile::tempdir().unwrap();
+        let home = dir.path().join("home");
+        let project = dir.path().join("project");
+
+        let skill_dir = project.join(".cortex").join("skills").join("reviewer");
+        std::fs::create_dir_all(&skill_dir).unwrap();
+        std::fs::write(
+            skill_dir.join("SKILL.md"),
+            "---\nname: reviewer\ndescription: Review code changes\n---\n\nCheck every diff twice.\n",
+        )
+        .unwrap();
+
+        let sessions = home.join("sessions");
+        std::fs::create_dir_all(&sessions).unwrap();
+        let rollout = [
+            format!(
+                r#"{{"timestamp":"2024-01-01T00:00:00Z","type":"session_meta","payload":{{"id":"{SESSION_ID}","timestamp":"2024-01-01T00:00:00Z","cwd":"{}"}}}}"#,
+                project.display()
+            ),
+            r#"{"timestamp":"2024-01-01T00:00:01Z","type":"event_msg","payload":{"type":"user_message","message":"What does main do?"}}"#.to_string(),
+            r#"{"timestamp":"2024-01-01T00:00:02Z","type":"event_msg","payload":{"type":"agent_message","message":"It starts the server."}}"#.to_string(),
+        ];
+        std::fs::write(
+            sessions.join(format!("{SESSION_ID}.jsonl")),
+            rollout.join("\n"),
+        )
+        .unwrap();
+
+        let resources = CortexResources::new(home, project);
+        (dir, resources)
+    }
+
+    #[tokio::test]
+    async fn test_list_sessions_and_skills() {
+        let (_dir, resources) = setup();
+        let uris: Vec<String> = resources
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|r| r.uri)
+            .collect();
+
+        assert!(uris.contains(&format!("cortex://sessions/{SESSION_ID}")));
+        assert!(uris.contains(&"cortex://skills/reviewer".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_read_resources() {
+        let (_dir, resources) = setup();
+
+        let session = resources
+            .read(&format!("cortex://sessions/{SESSION_ID}"))
+            .await
+            .unwrap();
+        let text = session.text.unwrap();
+        assert!(text.contains("## User\n\nWhat does main do?"));
+        assert!(text.contains("## Assistant\n\nIt starts the server."));
+
+        let skill = resources.read("cortex://skills/reviewer").await.unwrap();
+        assert!(skill.text.unwrap().contains("Check every diff twice."));
+        assert_eq!(skill.mime_type.as_deref(), Some("text/markdown"));
+
+        assert!(
+            resources
+                .read("cortex://sessions/../../etc/passwd")
+                .await
+                .is_err()
+        );
+        assert!(resources.read("cortex://skills/missing").await.is_err());
+        assert!(resources.read("file:///etc/passwd").await.is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Engine tools exposed as MCP tools.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cortex_engine::tools::{ToolContext, ToolDefinition, ToolRegistry};
+use cortex_mcp_server::ToolHandler;
+use cortex_mcp_types::{CallToolResult, Tool, ToolInputSchema};
+use serde_json::Value;
+
+use super::policy::{ToolKind, ToolPolicy};
+
+/// An engine registry tool served over MCP.
+pub(super) struct RegistryTool {
+    registry: Arc<ToolRegistry>,
+    definition: ToolDefinition,
+    policy: Arc<ToolPolicy>,
+}
+
+#[async_trait]
+impl ToolHandler for RegistryTool {
+    fn tool(&self) -> Tool {
+        Tool::new(&self.definition.name, &self.definition.description)
+            .with_schema(input_schema(&self.definition.parameters))
+    }
+
+    async fn execute(&self, arguments: Value) -> anyhow::Result<CallToolResult> {
+        let name = &self.definition.name;
+        if let Err(reason) = self.policy.check(name, &arguments).await {
+            tracing::info!(tool = %name, %reason, "MCP tool call denied");
+            return Ok(CallToolResult::error(format!(
+                "Permission denied: {reason}"
+            )));
+        }
+
+        let context = ToolContext::new(self.policy.cwd().to_path_buf())
+            .with_sandbox_policy(self.policy.sandbox_policy().clone())
+            .with_auto_approve(true);
+
+        Ok(
+            match self
+                .registry
+                .execute_with_context(name, arguments, context)
+                .await
+            {
+                Ok(result) if result.success => CallToolResult::text(result.output),
+                Ok(result) => CallToolResult::error(result.error.unwrap_or(result.output)),
+                Err(e) => CallToolResult::error(e.to_string()),
+            },
+        )
+    }
+}
+
+/// Build handlers for the registry tools that are exposed over MCP.
+///
+/// `allow` restricts the set to the named tools when non-empty; `deny`
+/// removes tools from it.
+pub(super) fn registry_tools(
+    registry: Arc<ToolRegistry>,
+    policy: Arc<ToolPolicy>,
+    allow: &[String],
+    deny: &[String],
+) -> Vec<Arc<dyn ToolHandler>> {
+    let mut definitions: Vec<ToolDefinition> = registry
+        .get_definitions()
+        .into_iter()
+        .filter(|d| ToolKind::of(&d.name).is_some())
+        .filter(|d| allow.is_empty() || allow.iter().any(|a| a == &d.name))
+        .filter(|d| !deny.iter().any(|n| n == &d.name))
+        .collect();
+    definitions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    definitions
+        .into_iter()
+        .map(|definition| {
+            Arc::new(RegistryTool {
+                registry: registry.clone(),
+                definition,
+                policy: policy.clone(),
+            }) as Arc<dyn ToolHandler>
+        })
+        .collect()
+}
+
+/// Convert an engine JSON schema into an MCP input schema.
+///
+/// Falls back to an open object schema when the engine schema uses
+/// constructs the MCP types cannot represent.
+fn input_schema(parameters: &Value) -> ToolInputSchema {
+    serde_json::from_value(parameters.clone()).unwrap_or_else(|e| {
+        tracing::debug!(error = %e, "Falling back to an open input schema");
+        ToolInputSchema::object()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::exec_cmd::AutonomyLevel;
+    use cortex_engine::PermissionManager;
+    use serde_json::json;
+
+    fn tools(dir: &std::path::Path, autonomy: AutonomyLevel) -> Vec<Arc<dyn ToolHandler>> {
+        let policy = Arc::new(ToolPolicy::new(
+            dir.to_path_buf(),
+            autonomy,
+            PermissionManager::new(),
+        ));
+        registry_tools(Arc::new(ToolRegistry::new()), policy, &[], &[])
+    }
+
+    fn find(tools: &[Arc<dyn ToolHandler>], name: &str) -> Arc<dyn ToolHandler> {
+        tools
+            .iter()
+            .find(|t| t.tool().name == name)
+            .cloned()
+            .unwrap_or_else(|| panic!("tool {name} not exposed"))
+    }
+
+    #[test]
+    fn test_exposed_tools() {
+        let dir = tempfile::tempdir().unwrap();
+        let names: Vec<String> = tools(dir.path(), AutonomyLevel::ReadOnly)
+            .iter()
+            .map(|t| t.tool().name)
+            .collect();
+
+        for name in ["Read", "Grep", "Glob", "Edit", "Execute", "ApplyPatch"] {
+            assert!(names.iter().any(|n| n == name), "missing {name}");
+        }
+        assert!(!names.iter().any(|n| n == "TodoWrite" || n == "Task"));
+
+        let read = find(&tools(dir.path(), AutonomyLevel::ReadOnly), "Read").tool();
+        assert_eq!(
+            read.input_schema.required,
+            Some(vec!["file_path".to_string()])
+        );
+        assert!(read.input_schema.properties.unwrap().contains_key("offset"));
+    }
+
+    #[test]
+    fn test_allow_and_deny_lists() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = Arc::new(ToolPolicy::new(
+            dir.path().to_path_buf(),
+            AutonomyLevel::ReadOnly,
+            PermissionManager::new(),
+        ));
+        let registry = Arc::new(ToolRegistry::new());
+
+        let allowed = registry_tools(
+            registry.clone(),
+            policy.clone(),
+            &["Read".to_string(), "Execute".to_string()],
+            &["Execute".to_string()],
+        );
+        let names: Vec<String> = allowed.iter().map(|t| t.tool().name).collect();
+        assert_eq!(names, vec!["Read"]);
+    }
+
+    #[tokio::test]
+    async fn test_execute_respects_policy() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("hello.txt");
+        std::fs::write(&file, "hello from mcp\n").unwrap();
+
+        let read_only = tools(dir.path(), AutonomyLevel::ReadOnly);
+        let result = find(&read_only, "Read")
+            .execute(json!({"file_path": file.to_string_lossy()}))
+            .await
+            .unwrap();
+        assert!(!result.is_error());
+
+        let result = find(&read_only, "Create")
+            .execute(
+                json!({"file_path": dir.path().join("new.txt").to_string_lossy(), "content": "x"}),
+            )
+            .await
+            .unwrap();
+        assert!(result.is_error());
+        assert!(!dir.path().join("new.txt").exists());
+
+        let low = tools(dir.path(), AutonomyLevel::Low);
+        let result = find(&low, "Create")
+            .execute(
+                json!({"file_path": dir.path().join("new.txt").to_string_lossy(), "content": "x"}),
+            )
+            .await
+            .unwrap();
+        assert!(!result.is_error(), "{result:?}");
+        assert!(dir.path().join("new.txt").exists());
+    }
+}
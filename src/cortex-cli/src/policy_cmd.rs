@@ -0,0 +1,183 @@
+//! Policy command for inspecting the managed policy layer.
+//!
+//! Provides:
+//! - `cortex policy show` - Show the managed policy installed on this machine
+//! - `cortex policy explain <command>` - Show which layer decides a command
+
+use anyhow::{Context, Result};
+use clap::Parser;
+use std::path::PathBuf;
+
+use cortex_engine::managed_policy::{
+    MANAGED_POLICY_PATH, ManagedPolicy, PolicyExplanation, PolicySources, explain_command,
+};
+
+/// Policy CLI command.
+#[derive(Debug, Parser)]
+pub struct PolicyCli {
+    #[command(subcommand)]
+    pub subcommand: PolicySubcommand,
+}
+
+/// Policy subcommands.
+#[derive(Debug, clap::Subcommand)]
+pub enum PolicySubcommand {
+    /// Show the managed policy installed on this machine
+    Show(PolicyShowArgs),
+
+    /// Show which policy layer decides whether a command may run
+    Explain(PolicyExplainArgs),
+}
+
+/// Arguments for policy show command.
+#[derive(Debug, Parser)]
+pub struct PolicyShowArgs {
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// Arguments for policy explain command.
+#[derive(Debug, Parser)]
+pub struct PolicyExplainArgs {
+    /// Working directory the command would run in (default: current directory)
+    #[arg(long, short = 'C')]
+    pub cwd: Option<PathBuf>,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// The command to explain
+    #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+    pub command: Vec<String>,
+}
+
+impl PolicyCli {
+    /// Run the policy command.
+    pub async fn run(self) -> Result<()> {
+        match self.subcommand {
+            PolicySubcommand::Show(args) => run_show(args),
+            PolicySubcommand::Explain(args) => run_explain(args).await,
+        }
+    }
+}
+
+fn run_show(args: PolicyShowArgs) -> Result<()> {
+    let policy = ManagedPolicy::system()?;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(policy)?);
+        return Ok(());
+    }
+
+    if !policy.is_installed() {
+        println!("No managed policy installed ({MANAGED_POLICY_PATH}).");
+        return Ok(());
+    }
+
+    println!("Managed policy: {MANAGED_POLICY_PATH}");
+    println!("{}", "=".repeat(50));
+    let locked = toml::to_string_pretty(policy)?;
+    if locked.trim().is_empty() {
+        println!("(no settings locked)");
+    } else {
+        print!("{locked}");
+    }
+    Ok(())
+}
+
+async fn run_explain(args: PolicyExplainArgs) -> Result<()> {
+    let cwd = match args.cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir().context("Failed to get current directory")?,
+    };
+    let sources = PolicySources::discover(&cwd)?;
+    let explanation = explain_command(&args.command, &sources).await;
+
+    if args.json {
+        println!("{}", serde_json::to_string_pretty(&explanation)?);
+    } else {
+        print!("{}", format_explanation(&explanation));
+    }
+    Ok(())
+}
+
+/// Render an explanation as a table of layers, marking the deciding one.
+fn format_explanation(explanation: &PolicyExplanation) -> String {
+    let mut output = format!("Command: {}\n\n", explanation.command);
+
+    for verdict in &explanation.layers {
+        let marker = if verdict.layer == explanation.decided_by {
+            "→"
+        } else {
+            " "
+        };
+        let decision = verdict
+            .decision
+            .map(|d| d.to_string())
+            .unwrap_or_else(|| "-".to_string());
+        output.push_str(&format!(
+            "{marker} {:<9} {:<6} {}\n",
+            verdict.layer.name(),
+            decision,
+            verdict.reason
+        ));
+        if let Some(source) = &verdict.source {
+            output.push_str(&format!("  {:<9} {:<6} {}\n", "", "", source.display()));
+        }
+    }
+
+    output.push_str(&format!(
+        "\nDecision: {} (decided by the {} layer)\n",
+        explanation.decision,
+        explanation.decided_by.name()
+    ));
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_engine::config::PermissionLevel;
+    use cortex_engine::managed_policy::{LayerVerdict, PolicyLayer};
+
+    #[test]
+    fn test_parse_explain_keeps_command_flags() {
+        let cli = PolicyCli::try_parse_from(["policy", "explain", "--json", "rm", "-rf", "build"])
+            .expect("should parse");
+        let PolicySubcommand::Explain(args) = cli.subcommand else {
+            panic!("Expected Explain subcommand");
+        };
+        assert!(args.json);
+        assert_eq!(args.command, vec!["rm", "-rf", "build"]);
+    }
+
+    #[test]
+    fn test_format_explanation_marks_deciding_layer() {
+        let explanation = PolicyExplanation {
+            command: "curl example.com".to_string(),
+            layers: vec![
+                LayerVerdict {
+                    layer: PolicyLayer::Managed,
+                    source: Some(PathBuf::from("/etc/cortex/managed.toml")),
+                    decision: Some(PermissionLevel::Deny),
+                    reason: "matches denied_commands pattern 'curl *'".to_string(),
+                },
+                LayerVerdict {
+                    layer: PolicyLayer::User,
+                    source: None,
+                    decision: Some(PermissionLevel::Allow),
+                    reason: "[permission.bash] pattern 'curl *'".to_string(),
+                },
+            ],
+            decided_by: PolicyLayer::Managed,
+            decision: PermissionLevel::Deny,
+        };
+
+        let output = format_explanation(&explanation);
+        assert!(output.contains("→ managed   deny"));
+        assert!(output.contains("  user      allow"));
+        assert!(output.contains("Decision: deny (decided by the managed layer)"));
+    }
+}
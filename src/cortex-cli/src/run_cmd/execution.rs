@@ -220,7 +220,7 @@ impl RunCli {
         }
 
         // Create or resume session
-        let mut config = cortex_engine::Config::default();
+        let mut config = crate::utils::load_config(self.cwd.clone()).await?;
 
         // Set agent if provided
         if let Some(ref agent_name) = self.agent {
@@ -56,18 +56,6 @@ impl ShellCli {
             );
         }
 
-        // Build config
-        let mut config = cortex_engine::Config::default();
-
-        // Apply model override
-        if let Some(ref model) = self.model {
-            use cortex_common::resolve_model_alias;
-            if model.trim().is_empty() {
-                bail!("Model name cannot be empty.");
-            }
-            config.model = resolve_model_alias(model).to_string();
-        }
-
         // Apply working directory override
         if let Some(ref cwd) = self.cwd {
             let cwd_path = if cwd.is_absolute() {
@@ -76,7 +64,18 @@ impl ShellCli {
                 std::env::current_dir()?.join(cwd)
             };
             std::env::set_current_dir(&cwd_path)?;
-            config.cwd = cwd_path;
+        }
+
+        // Build config from the user's config.toml in the working directory
+        let mut config = crate::utils::load_config(None).await?;
+
+        // Apply model override
+        if let Some(ref model) = self.model {
+            use cortex_common::resolve_model_alias;
+            if model.trim().is_empty() {
+                bail!("Model name cannot be empty.");
+            }
+            config.model = resolve_model_alias(model).to_string();
         }
 
         // Get initial prompt if provided
@@ -1,16 +1,16 @@
 //! Upgrade command - check for and install updates.
 //!
-//! Uses the Cortex Software Distribution API at software.cortex.foundation
-//! to check for updates and download new versions.
+//! Uses the Cortex Software Distribution API at software.cortex.foundation,
+//! or a self-hosted mirror, to check for updates and download new versions.
+//! Downloads are checked against pinned release signing keys, and the replaced
+//! binary is kept for `cortex upgrade --rollback`.
 
 use anyhow::{Context, Result, bail};
 use clap::Parser;
 use cortex_engine::create_default_client;
 use std::io::{Write, stdout};
 
-use cortex_update::{
-    ReleaseChannel, SOFTWARE_URL, UpdateConfig, UpdateInfo, UpdateManager, UpdateOutcome,
-};
+use cortex_update::{ReleaseChannel, UpdateConfig, UpdateInfo, UpdateManager, UpdateOutcome};
 
 /// Current CLI version from this binary's Cargo.toml
 const CLI_VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -51,6 +51,15 @@ pub struct UpgradeCli {
     /// Use custom software distribution URL
     #[arg(long, hide = true)]
     pub url: Option<String>,
+
+    /// Self-hosted update channel: a local directory or URL serving release
+    /// manifests (overrides the mirror in ~/.cortex/update.json)
+    #[arg(long, value_name = "DIR|URL")]
+    pub mirror: Option<String>,
+
+    /// Restore the binary replaced by the last upgrade
+    #[arg(long, conflicts_with_all = ["version", "check", "changelog", "force", "pre"])]
+    pub rollback: bool,
 }
 
 impl UpgradeCli {
@@ -59,10 +68,6 @@ impl UpgradeCli {
         println!("Cortex CLI Upgrade");
         println!("{}", "=".repeat(40));
         println!("Current version: v{}", CLI_VERSION);
-        println!(
-            "Update server: {}",
-            self.url.as_deref().unwrap_or(SOFTWARE_URL)
-        );
 
         // Parse channel (--pre is shorthand for --channel beta)
         let channel = if self.pre {
@@ -87,11 +92,20 @@ impl UpgradeCli {
         if let Some(url) = &self.url {
             config.custom_url = Some(url.clone());
         }
+        if let Some(mirror) = &self.mirror {
+            config.mirror = Some(mirror.clone());
+        }
 
         // Create update manager
-        let manager =
+        let mut manager =
             UpdateManager::with_config(config).context("Failed to initialize update manager")?;
 
+        if self.rollback {
+            return perform_rollback(&mut manager, self.yes).await;
+        }
+
+        println!("Update server: {}", manager.update_source());
+
         // Check for specific version or latest
         let update_info = if let Some(ref version) = self.version {
             println!("\nChecking version {}...", version);
@@ -129,7 +143,7 @@ impl UpgradeCli {
                 }
                 Err(e) => {
                     eprintln!("Failed to check for updates: {}", e);
-                    eprintln!("\nTip: Check {} manually.", SOFTWARE_URL);
+                    eprintln!("\nTip: Check {} manually.", manager.update_source());
                     return Ok(());
                 }
             }
@@ -216,26 +230,48 @@ impl UpgradeCli {
 
 /// Check for a specific version
 async fn check_specific_version(manager: &UpdateManager, version: &str) -> Result<UpdateInfo> {
-    let client = cortex_update::CortexSoftwareClient::new();
-    let release = client
-        .get_release(version)
+    manager
+        .get_version(version)
         .await
-        .context(format!("Version {} not found", version))?;
-
-    let release_clone = release.clone();
-    let asset = release_clone
-        .asset_for_current_platform()
-        .ok_or_else(|| anyhow::anyhow!("No download available for this platform"))?;
-
-    Ok(UpdateInfo {
-        current_version: CLI_VERSION.to_string(),
-        latest_version: release.version,
-        channel: release.channel,
-        changelog_url: release.changelog_url,
-        release_notes: release.release_notes,
-        asset: asset.clone(),
-        install_method: manager.install_method(),
-    })
+        .context(format!("Version {} not available", version))
+}
+
+/// Restore the binary replaced by the last upgrade
+async fn perform_rollback(manager: &mut UpdateManager, yes: bool) -> Result<()> {
+    let Some(previous) = manager.previous_version() else {
+        bail!(
+            "No previous version to roll back to. Only upgrades installed by `cortex upgrade` can be rolled back."
+        );
+    };
+
+    println!(
+        "\n↶ Rollback: v{} → v{} (replaced {})",
+        CLI_VERSION,
+        previous.version,
+        previous.replaced_at.format("%Y-%m-%d %H:%M UTC")
+    );
+
+    if !yes {
+        print!("\nProceed with rollback? [y/N] ");
+        stdout().flush()?;
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        if !input.trim().eq_ignore_ascii_case("y") {
+            println!("Rollback cancelled.");
+            return Ok(());
+        }
+    }
+
+    if let UpdateOutcome::RolledBack { from, to } =
+        manager.rollback().await.context("Rollback failed")?
+    {
+        println!("\n✓ Rolled back from v{} to v{}.", from, to);
+        println!(
+            "  v{} will not be offered again until you run `cortex upgrade {}`.",
+            from, from
+        );
+    }
+    Ok(())
 }
 
 /// Perform the actual upgrade
@@ -258,14 +294,14 @@ async fn perform_upgrade(manager: &UpdateManager, info: &UpdateInfo) -> Result<(
 
     println!("\n  Downloaded successfully");
 
-    // Verify checksum
-    print!("Verifying checksum... ");
+    // Verify checksum and signature
+    print!("Verifying checksum and signature... ");
     stdout().flush()?;
     let mut download = download;
     manager
-        .verify(&mut download, &info.asset.sha256)
+        .verify(&mut download, info)
         .await
-        .context("Checksum verification failed")?;
+        .context("Verification failed")?;
     println!("✓");
 
     // Install
@@ -287,6 +323,12 @@ async fn perform_upgrade(manager: &UpdateManager, info: &UpdateInfo) -> Result<(
         }
         _ => {}
     }
+    if manager.previous_version().is_some() {
+        println!(
+            "  Run `cortex upgrade --rollback` to go back to v{}.",
+            info.current_version
+        );
+    }
 
     Ok(())
 }
@@ -492,4 +534,14 @@ mod tests {
         assert_eq!(semver_compare("1.0.0", "2.0.0"), -1);
         assert_eq!(semver_compare("v1.0.0", "1.0.0"), 0);
     }
+
+    #[test]
+    fn test_parse_rollback_and_mirror() {
+        let cli = UpgradeCli::try_parse_from(["upgrade", "--rollback", "-y"]).unwrap();
+        assert!(cli.rollback && cli.yes);
+        assert!(UpgradeCli::try_parse_from(["upgrade", "--rollback", "1.2.0"]).is_err());
+
+        let cli = UpgradeCli::try_parse_from(["upgrade", "--mirror", "/srv/cortex"]).unwrap();
+        assert_eq!(cli.mirror.as_deref(), Some("/srv/cortex"));
+    }
 }
@@ -0,0 +1,23 @@
+//! Configuration loading for the Cortex CLI.
+//!
+//! Commands that start an agent session load the user's config.toml (merged with
+//! any project config) instead of starting from `Config::default()`, so settings
+//! such as `[providers]`, `[network]` and `[permission]` take effect.
+
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use cortex_engine::{Config, ConfigOverrides};
+
+/// Load the user's configuration, resolving project config from `cwd`.
+///
+/// When `cwd` is `None` the current working directory is used. Any managed
+/// policy installed on the machine is applied to the result.
+pub async fn load_config(cwd: Option<PathBuf>) -> Result<Config> {
+    Config::load(ConfigOverrides {
+        cwd,
+        ..Default::default()
+    })
+    .await
+    .context("Failed to load configuration")
+}
@@ -5,6 +5,7 @@
 //! - URL validation and sanitization
 //! - File validation and security checks
 //! - Clipboard operations
+//! - Configuration loading
 //! - Path validation utilities
 //! - Terminal color detection and safe output
 //! - Model name validation and resolution
@@ -20,6 +21,7 @@
 //! - **Modularity**: Each utility module is focused on a single concern
 
 pub mod clipboard;
+pub mod config;
 pub mod file;
 pub mod mime;
 pub mod model;
@@ -33,6 +35,9 @@ pub mod validation;
 // Re-export clipboard operations
 pub use clipboard::{copy_to_clipboard, read_clipboard};
 
+// Re-export config loading
+pub use config::load_config;
+
 // Re-export file utilities
 pub use file::{
     FileAttachment, MAX_ATTACHMENT_SIZE, MAX_TOTAL_ATTACHMENT_SIZE, process_file_attachments,
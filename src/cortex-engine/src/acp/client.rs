@@ -0,0 +1,536 @@
+//! Agent-to-client requests.
+//!
+//! ACP is bidirectional: besides answering the editor's requests, the agent
+//! asks the editor for permission, reads and writes files through its buffers
+//! and runs commands in its terminals. [`AcpClient`] sends those requests over
+//! the server's transport and matches the responses back up; [`AcpBridge`]
+//! exposes them to the engine's tools for one session.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use tokio::sync::{broadcast, oneshot};
+use tracing::{debug, warn};
+
+use crate::acp::handler::AcpNotificationEvent;
+use crate::acp::protocol::{AcpRequest, AcpRequestId, AcpResponse, methods};
+use crate::acp::types::*;
+use crate::exec::ExecOutput;
+use crate::tools::ClientBridge;
+
+/// How long to wait for the client to answer a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// How long to wait for the user to answer a permission request.
+const PERMISSION_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+/// Output kept by client terminals (bytes).
+const TERMINAL_OUTPUT_LIMIT: u64 = 1024 * 1024;
+
+/// Requests from the agent to the connected client.
+pub struct AcpClient {
+    /// Capabilities the client advertised in `initialize`.
+    capabilities: RwLock<ClientCapabilities>,
+    /// Next request ID.
+    next_id: AtomicI64,
+    /// Requests waiting for a response.
+    pending: Mutex<HashMap<AcpRequestId, oneshot::Sender<AcpResponse>>>,
+    /// Outgoing requests, written by the transport.
+    outgoing: broadcast::Sender<AcpRequest>,
+}
+
+impl AcpClient {
+    /// Create a new client with no capabilities.
+    pub fn new() -> Self {
+        let (outgoing, _) = broadcast::channel(256);
+        Self {
+            capabilities: RwLock::new(ClientCapabilities::default()),
+            next_id: AtomicI64::new(1),
+            pending: Mutex::new(HashMap::new()),
+            outgoing,
+        }
+    }
+
+    /// Subscribe to outgoing requests (used by transports).
+    pub fn subscribe(&self) -> broadcast::Receiver<AcpRequest> {
+        self.outgoing.subscribe()
+    }
+
+    /// Record the capabilities from `initialize`.
+    pub fn set_capabilities(&self, capabilities: ClientCapabilities) {
+        *self.capabilities.write().unwrap_or_else(|e| e.into_inner()) = capabilities;
+    }
+
+    /// Whether the client serves `fs/read_text_file`.
+    pub fn can_read_files(&self) -> bool {
+        self.fs_capability(|fs| fs.read_text_file)
+    }
+
+    /// Whether the client serves `fs/write_text_file`.
+    pub fn can_write_files(&self) -> bool {
+        self.fs_capability(|fs| fs.write_text_file)
+    }
+
+    /// Whether the client serves `terminal/*`.
+    pub fn has_terminal(&self) -> bool {
+        let capabilities = self.capabilities.read().unwrap_or_else(|e| e.into_inner());
+        capabilities.terminal.unwrap_or(false)
+    }
+
+    fn fs_capability(&self, check: impl Fn(&FileSystemCapability) -> bool) -> bool {
+        let capabilities = self.capabilities.read().unwrap_or_else(|e| e.into_inner());
+        capabilities.fs.as_ref().is_some_and(check)
+    }
+
+    /// Send a request and wait for the client's response.
+    pub async fn request<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: impl Serialize,
+    ) -> Result<R> {
+        self.request_with_timeout(method, params, REQUEST_TIMEOUT)
+            .await
+    }
+
+    /// Send a request and wait up to `timeout` for the client's response.
+    pub async fn request_with_timeout<R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: impl Serialize,
+        timeout: Duration,
+    ) -> Result<R> {
+        let id = AcpRequestId::Number(self.next_id.fetch_add(1, Ordering::SeqCst));
+        let (tx, rx) = oneshot::channel();
+        self.pending_map().insert(id.clone(), tx);
+        // Forget the request however this future ends (answered, timed out or dropped)
+        let _pending = PendingRequest {
+            client: self,
+            id: &id,
+        };
+
+        let request =
+            AcpRequest::new(id.clone(), method).with_params(serde_json::to_value(params)?);
+        debug!("Sending {} to client", method);
+        if self.outgoing.send(request).is_err() {
+            bail!("No ACP client connected");
+        }
+
+        let response = tokio::time::timeout(timeout, rx)
+            .await
+            .map_err(|_| anyhow!("Client did not answer {method} within {timeout:?}"))?
+            .with_context(|| format!("Client never answered {method}"))?;
+        if let Some(error) = response.error {
+            bail!("{method} failed: {} ({})", error.message, error.code);
+        }
+        Ok(serde_json::from_value(
+            response.result.unwrap_or(Value::Null),
+        )?)
+    }
+
+    /// Deliver a response from the client. Returns false if nothing was waiting for it.
+    pub fn handle_response(&self, response: AcpResponse) -> bool {
+        match self.pending_map().remove(&response.id) {
+            Some(tx) => tx.send(response).is_ok(),
+            None => {
+                warn!("Response for unknown request {:?}", response.id);
+                false
+            }
+        }
+    }
+
+    /// Ask the user to approve a tool call.
+    pub async fn request_permission(
+        &self,
+        params: RequestPermissionRequest,
+    ) -> Result<PermissionOutcome> {
+        let response: RequestPermissionResponse = self
+            .request_with_timeout(
+                methods::SESSION_REQUEST_PERMISSION,
+                params,
+                PERMISSION_TIMEOUT,
+            )
+            .await?;
+        Ok(response.outcome)
+    }
+
+    fn pending_map(
+        &self,
+    ) -> std::sync::MutexGuard<'_, HashMap<AcpRequestId, oneshot::Sender<AcpResponse>>> {
+        self.pending.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Removes a request from the pending map when dropped.
+struct PendingRequest<'a> {
+    client: &'a AcpClient,
+    id: &'a AcpRequestId,
+}
+
+impl Drop for PendingRequest<'_> {
+    fn drop(&mut self) {
+        self.client.pending_map().remove(self.id);
+    }
+}
+
+impl Default for AcpClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Client file and terminal access for one session's tools.
+pub struct AcpBridge {
+    /// Shared client connection.
+    client: Arc<AcpClient>,
+    /// Session the requests belong to.
+    session_id: String,
+    /// Notification sender for tool call updates.
+    notification_tx: broadcast::Sender<AcpNotificationEvent>,
+}
+
+impl AcpBridge {
+    /// Create a bridge for a session.
+    pub fn new(
+        client: Arc<AcpClient>,
+        session_id: impl Into<String>,
+        notification_tx: broadcast::Sender<AcpNotificationEvent>,
+    ) -> Self {
+        Self {
+            client,
+            session_id: session_id.into(),
+            notification_tx,
+        }
+    }
+
+    fn terminal(&self, terminal_id: &str) -> TerminalRequest {
+        TerminalRequest {
+            session_id: self.session_id.clone(),
+            terminal_id: terminal_id.to_string(),
+        }
+    }
+
+    /// Embed the terminal in the tool call so the editor shows it live.
+    fn show_terminal(&self, call_id: &str, terminal_id: &str) {
+        let notification = SessionNotification {
+            session_id: self.session_id.clone(),
+            update: SessionUpdate::ToolCallUpdate {
+                tool_call_id: call_id.to_string(),
+                status: ToolStatus::InProgress,
+                content: Some(vec![ToolCallContent::Terminal {
+                    terminal_id: terminal_id.to_string(),
+                }]),
+                raw_output: None,
+            },
+        };
+        let _ = self.notification_tx.send(AcpNotificationEvent {
+            method: methods::SESSION_UPDATE.to_string(),
+            params: serde_json::to_value(notification).unwrap_or(Value::Null),
+        });
+    }
+}
+
+#[async_trait]
+impl ClientBridge for AcpBridge {
+    fn can_read_files(&self) -> bool {
+        self.client.can_read_files()
+    }
+
+    fn can_write_files(&self) -> bool {
+        self.client.can_write_files()
+    }
+
+    fn can_run_commands(&self) -> bool {
+        self.client.has_terminal()
+    }
+
+    async fn read_text_file(&self, path: &Path) -> crate::error::Result<String> {
+        let response: ReadTextFileResponse = self
+            .client
+            .request(
+                methods::FS_READ_TEXT_FILE,
+                ReadTextFileRequest {
+                    session_id: self.session_id.clone(),
+                    path: path.display().to_string(),
+                    line: None,
+                    limit: None,
+                },
+            )
+            .await?;
+        Ok(response.content)
+    }
+
+    async fn write_text_file(&self, path: &Path, content: &str) -> crate::error::Result<()> {
+        let _: Value = self
+            .client
+            .request(
+                methods::FS_WRITE_TEXT_FILE,
+                WriteTextFileRequest {
+                    session_id: self.session_id.clone(),
+                    path: path.display().to_string(),
+                    content: content.to_string(),
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    async fn run_command(
+        &self,
+        call_id: &str,
+        command: &[String],
+        cwd: &Path,
+        env: &HashMap<String, String>,
+        timeout: Duration,
+    ) -> crate::error::Result<ExecOutput> {
+        let start = Instant::now();
+        let (program, args) = command.split_first().context("Empty command")?;
+
+        let created: CreateTerminalResponse = self
+            .client
+            .request(
+                methods::TERMINAL_CREATE,
+                CreateTerminalRequest {
+                    session_id: self.session_id.clone(),
+                    command: program.clone(),
+                    args: args.to_vec(),
+                    env: env
+                        .iter()
+                        .map(|(name, value)| EnvVariable {
+                            name: name.clone(),
+                            value: value.clone(),
+                        })
+                        .collect(),
+                    cwd: Some(cwd.display().to_string()),
+                    output_byte_limit: Some(TERMINAL_OUTPUT_LIMIT),
+                },
+            )
+            .await?;
+        let terminal = self.terminal(&created.terminal_id);
+        self.show_terminal(call_id, &created.terminal_id);
+
+        // The command's own timeout fires first; the request timeout is only a backstop
+        let exited = tokio::time::timeout(
+            timeout,
+            self.client.request_with_timeout::<TerminalExitStatus>(
+                methods::TERMINAL_WAIT_FOR_EXIT,
+                &terminal,
+                timeout + REQUEST_TIMEOUT,
+            ),
+        )
+        .await;
+        let timed_out = exited.is_err();
+        if timed_out {
+            let _: Value = self
+                .client
+                .request(methods::TERMINAL_KILL, &terminal)
+                .await?;
+        }
+
+        let output: TerminalOutputResponse = self
+            .client
+            .request(methods::TERMINAL_OUTPUT, &terminal)
+            .await?;
+        if let Err(e) = self
+            .client
+            .request::<Value>(methods::TERMINAL_RELEASE, &terminal)
+            .await
+        {
+            warn!("Failed to release terminal {}: {e:#}", created.terminal_id);
+        }
+
+        let status = match exited {
+            Ok(status) => status?,
+            Err(_) => output.exit_status.unwrap_or_default(),
+        };
+        Ok(ExecOutput {
+            stdout: output.output.clone(),
+            stderr: String::new(),
+            aggregated: output.output,
+            exit_code: status.exit_code.unwrap_or(-1),
+            duration: start.elapsed(),
+            timed_out,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answer outgoing requests like an editor would.
+    fn spawn_editor(
+        client: Arc<AcpClient>,
+        answer: impl Fn(&AcpRequest) -> AcpResponse + Send + 'static,
+    ) -> tokio::task::JoinHandle<Vec<AcpRequest>> {
+        let mut rx = client.subscribe();
+        tokio::spawn(async move {
+            let mut seen = Vec::new();
+            while let Ok(request) = rx.recv().await {
+                let release = request.method == methods::TERMINAL_RELEASE;
+                client.handle_response(answer(&request));
+                seen.push(request);
+                if release {
+                    break;
+                }
+            }
+            seen
+        })
+    }
+
+    fn capabilities(read: bool, write: bool, terminal: bool) -> ClientCapabilities {
+        ClientCapabilities {
+            fs: Some(FileSystemCapability {
+                read_text_file: read,
+                write_text_file: write,
+            }),
+            terminal: Some(terminal),
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn test_capabilities() {
+        let client = AcpClient::new();
+        assert!(!client.can_read_files());
+        assert!(!client.has_terminal());
+
+        client.set_capabilities(capabilities(true, false, true));
+        assert!(client.can_read_files());
+        assert!(!client.can_write_files());
+        assert!(client.has_terminal());
+    }
+
+    #[tokio::test]
+    async fn test_request_round_trip() {
+        let client = Arc::new(AcpClient::new());
+        spawn_editor(client.clone(), |request| {
+            assert_eq!(request.method, methods::FS_READ_TEXT_FILE);
+            let params = request.params.as_ref().unwrap();
+            assert_eq!(params["sessionId"], "s1");
+            assert_eq!(params["path"], "/repo/main.rs");
+            AcpResponse::success(
+                request.id.clone(),
+                serde_json::json!({"content": "unsaved buffer"}),
+            )
+        });
+
+        let bridge = AcpBridge::new(client, "s1", broadcast::channel(8).0);
+        let content = bridge
+            .read_text_file(Path::new("/repo/main.rs"))
+            .await
+            .unwrap();
+        assert_eq!(content, "unsaved buffer");
+    }
+
+    #[tokio::test]
+    async fn test_request_error() {
+        let client = Arc::new(AcpClient::new());
+        spawn_editor(client.clone(), |request| {
+            AcpResponse::error(
+                request.id.clone(),
+                crate::acp::protocol::AcpError::internal("no such buffer"),
+            )
+        });
+
+        let error = client
+            .request::<Value>(methods::FS_WRITE_TEXT_FILE, serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("no such buffer"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_request_without_transport() {
+        let client = AcpClient::new();
+        let error = client
+            .request::<Value>(methods::TERMINAL_KILL, serde_json::json!({}))
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("No ACP client"));
+        assert!(client.pending_map().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_request_timeout_forgets_request() {
+        let client = AcpClient::new();
+        let _rx = client.subscribe();
+        let error = client
+            .request_with_timeout::<Value>(
+                methods::FS_READ_TEXT_FILE,
+                serde_json::json!({}),
+                Duration::from_millis(10),
+            )
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("did not answer"), "{error}");
+        assert!(client.pending_map().is_empty());
+
+        let dropped = tokio::time::timeout(
+            Duration::from_millis(10),
+            client.request::<Value>(methods::FS_READ_TEXT_FILE, serde_json::json!({})),
+        )
+        .await;
+        assert!(dropped.is_err());
+        assert!(client.pending_map().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_run_command_in_terminal() {
+        let client = Arc::new(AcpClient::new());
+        let editor = spawn_editor(client.clone(), |request| {
+            let result = match request.method.as_str() {
+                methods::TERMINAL_CREATE => serde_json::json!({"terminalId": "t1"}),
+                methods::TERMINAL_WAIT_FOR_EXIT => serde_json::json!({"exitCode": 2}),
+                methods::TERMINAL_OUTPUT => {
+                    serde_json::json!({"output": "boom\n", "truncated": false})
+                }
+                _ => Value::Null,
+            };
+            AcpResponse::success(request.id.clone(), result)
+        });
+
+        let (notification_tx, mut notifications) = broadcast::channel(8);
+        let bridge = AcpBridge::new(client, "s1", notification_tx);
+        let env = HashMap::from([("CI".to_string(), "true".to_string())]);
+        let output = bridge
+            .run_command(
+                "call_1",
+                &["cargo".to_string(), "test".to_string()],
+                Path::new("/repo"),
+                &env,
+                Duration::from_secs(5),
+            )
+            .await
+            .unwrap();
+        assert_eq!(output.exit_code, 2);
+        assert_eq!(output.stdout, "boom\n");
+        assert!(!output.timed_out);
+
+        let requests = editor.await.unwrap();
+        let sent: Vec<&str> = requests.iter().map(|r| r.method.as_str()).collect();
+        assert_eq!(
+            sent,
+            [
+                methods::TERMINAL_CREATE,
+                methods::TERMINAL_WAIT_FOR_EXIT,
+                methods::TERMINAL_OUTPUT,
+                methods::TERMINAL_RELEASE
+            ]
+        );
+        let create = requests[0].params.as_ref().unwrap();
+        assert_eq!(create["command"], "cargo");
+        assert_eq!(create["args"], serde_json::json!(["test"]));
+        assert_eq!(create["env"][0]["name"], "CI");
+
+        let update = notifications.recv().await.unwrap();
+        assert_eq!(update.params["update"]["content"][0]["terminalId"], "t1");
+    }
+}
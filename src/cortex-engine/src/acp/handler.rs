@@ -2,6 +2,7 @@
 //!
 //! This module contains the business logic for handling ACP protocol requests,
 //! including session management, prompt processing, and event forwarding.
+//! Exec approvals are forwarded to the client as `session/request_permission`.
 
 use std::collections::HashMap;
 use std::sync::Arc;
@@ -12,11 +13,14 @@ use serde_json::Value;
 use tokio::sync::{RwLock, broadcast};
 use tracing::{debug, error, info, warn};
 
-use crate::acp::protocol::{AcpError, AcpRequestId, AcpResponse};
+use crate::acp::client::{AcpBridge, AcpClient};
+use crate::acp::protocol::{AcpError, AcpRequestId, AcpResponse, methods};
 use crate::acp::types::*;
 use crate::config::Config;
 use crate::session::{Session, SessionHandle};
-use cortex_protocol::{EventMsg, Op, Submission, UserInput};
+use cortex_protocol::{
+    EventMsg, ExecApprovalRequestEvent, Op, ReviewDecision, Submission, UserInput,
+};
 
 /// Session state tracked by the ACP handler.
 pub struct AcpSessionState {
@@ -24,6 +28,8 @@ pub struct AcpSessionState {
     pub handle: SessionHandle,
     /// Cancel token for this session.
     pub cancel_tx: broadcast::Sender<()>,
+    /// Signalled when a turn finishes.
+    pub turn_tx: broadcast::Sender<StopReason>,
     /// Session metadata.
     pub metadata: SessionMetadata,
 }
@@ -52,6 +58,8 @@ pub struct AcpHandler {
     config: Config,
     /// Notification sender for streaming updates.
     notification_tx: broadcast::Sender<AcpNotificationEvent>,
+    /// Requests to the connected client.
+    client: Arc<AcpClient>,
 }
 
 /// Notification event wrapper.
@@ -71,6 +79,7 @@ impl AcpHandler {
             sessions: Arc::new(RwLock::new(HashMap::new())),
             config,
             notification_tx,
+            client: Arc::new(AcpClient::new()),
         }
     }
 
@@ -79,12 +88,18 @@ impl AcpHandler {
         self.notification_tx.subscribe()
     }
 
+    /// Requests to the connected client.
+    pub fn client(&self) -> &Arc<AcpClient> {
+        &self.client
+    }
+
     /// Handle initialize request.
     pub async fn handle_initialize(&self, params: InitializeRequest) -> Result<InitializeResponse> {
         debug!(
             "Initialize request: version={}, client={}",
             params.protocol_version, params.client_info.name
         );
+        self.client.set_capabilities(params.client_capabilities);
 
         Ok(InitializeResponse {
             protocol_version: PROTOCOL_VERSION,
@@ -119,8 +134,10 @@ impl AcpHandler {
 
         let (mut session, handle) = Session::new(config)?;
         let session_id = handle.conversation_id.to_string();
+        self.attach_client(&mut session, &session_id);
 
         let (cancel_tx, _) = broadcast::channel(1);
+        let (turn_tx, _) = broadcast::channel(16);
 
         let metadata = SessionMetadata {
             session_id: session_id.clone(),
@@ -132,7 +149,8 @@ impl AcpHandler {
 
         let state = AcpSessionState {
             handle: handle.clone(),
-            cancel_tx: cancel_tx.clone(),
+            cancel_tx,
+            turn_tx: turn_tx.clone(),
             metadata: metadata.clone(),
         };
 
@@ -152,32 +170,7 @@ impl AcpHandler {
             }
         });
 
-        // Spawn event forwarder
-        let session_id_clone = session_id.clone();
-        let notification_tx = self.notification_tx.clone();
-        let event_rx = handle.event_rx.clone();
-        let mut cancel_rx = cancel_tx.subscribe();
-
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    result = event_rx.recv() => {
-                        match result {
-                            Ok(event) => {
-                                if let Some(notification) = event_to_notification(&session_id_clone, event.msg) {
-                                    let _ = notification_tx.send(notification);
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                    _ = cancel_rx.recv() => {
-                        debug!("Session {} event forwarder cancelled", session_id_clone);
-                        break;
-                    }
-                }
-            }
-        });
+        self.spawn_event_forwarder(session_id.clone(), &handle, turn_tx);
 
         Ok(NewSessionResponse {
             session_id,
@@ -242,8 +235,10 @@ impl AcpHandler {
         let config = self.config.clone();
         let (mut session, handle) = Session::resume(config, conversation_id)?;
         let session_id = handle.conversation_id.to_string();
+        self.attach_client(&mut session, &session_id);
 
         let (cancel_tx, _) = broadcast::channel(1);
+        let (turn_tx, _) = broadcast::channel(16);
 
         let metadata = SessionMetadata {
             session_id: session_id.clone(),
@@ -255,7 +250,8 @@ impl AcpHandler {
 
         let state = AcpSessionState {
             handle: handle.clone(),
-            cancel_tx: cancel_tx.clone(),
+            cancel_tx,
+            turn_tx: turn_tx.clone(),
             metadata,
         };
 
@@ -271,31 +267,7 @@ impl AcpHandler {
             }
         });
 
-        // Spawn event forwarder
-        let notification_tx = self.notification_tx.clone();
-        let event_rx = handle.event_rx.clone();
-        let session_id_clone = session_id.clone();
-        let mut cancel_rx = cancel_tx.subscribe();
-
-        tokio::spawn(async move {
-            loop {
-                tokio::select! {
-                    result = event_rx.recv() => {
-                        match result {
-                            Ok(event) => {
-                                if let Some(notification) = event_to_notification(&session_id_clone, event.msg) {
-                                    let _ = notification_tx.send(notification);
-                                }
-                            }
-                            Err(_) => break,
-                        }
-                    }
-                    _ = cancel_rx.recv() => {
-                        break;
-                    }
-                }
-            }
-        });
+        self.spawn_event_forwarder(session_id.clone(), &handle, turn_tx);
 
         Ok(LoadSessionResponse {
             session_id,
@@ -334,6 +306,9 @@ impl AcpHandler {
             .get(&params.session_id)
             .context("Session not found")?;
         let handle = state.handle.clone();
+        // Subscribe before submitting so the turn's end can't be missed
+        let mut turn_rx = state.turn_tx.subscribe();
+        let mut cancel_rx = state.cancel_tx.subscribe();
         drop(sessions);
 
         // Convert prompt content to user inputs
@@ -379,19 +354,13 @@ impl AcpHandler {
 
         handle.submission_tx.send(submission).await?;
 
-        // Wait for turn completion
-        let event_rx = handle.event_rx.clone();
-        while let Ok(event) = event_rx.recv().await {
-            match event.msg {
-                EventMsg::TaskComplete(_) => break,
-                EventMsg::Error(_) => break,
-                _ => {}
-            }
-        }
+        // Wait for turn completion (reported by the event forwarder)
+        let stop_reason = tokio::select! {
+            result = turn_rx.recv() => result.unwrap_or(StopReason::EndTurn),
+            _ = cancel_rx.recv() => StopReason::Cancelled,
+        };
 
-        Ok(PromptResponse {
-            stop_reason: StopReason::EndTurn,
-        })
+        Ok(PromptResponse { stop_reason })
     }
 
     /// Handle session/cancel request.
@@ -401,7 +370,7 @@ impl AcpHandler {
             .get(&params.session_id)
             .context("Session not found")?;
 
-        // Signal cancellation
+        // Signal cancellation to the waiting prompt
         let _ = state.cancel_tx.send(());
 
         // Send interrupt submission
@@ -448,6 +417,68 @@ impl AcpHandler {
         })
     }
 
+    /// Route the session's file and shell tools through the client, if it supports any.
+    fn attach_client(&self, session: &mut Session, session_id: &str) {
+        if self.client.can_read_files()
+            || self.client.can_write_files()
+            || self.client.has_terminal()
+        {
+            session.set_client_bridge(Arc::new(AcpBridge::new(
+                self.client.clone(),
+                session_id,
+                self.notification_tx.clone(),
+            )));
+        }
+    }
+
+    /// Forward session events as notifications, answer approvals and report turn ends.
+    ///
+    /// This is the only reader of the session's events.
+    fn spawn_event_forwarder(
+        &self,
+        session_id: String,
+        handle: &SessionHandle,
+        turn_tx: broadcast::Sender<StopReason>,
+    ) {
+        let notification_tx = self.notification_tx.clone();
+        let client = self.client.clone();
+        let event_rx = handle.event_rx.clone();
+        let submission_tx = handle.submission_tx.clone();
+
+        tokio::spawn(async move {
+            while let Ok(event) = event_rx.recv().await {
+                match event.msg {
+                    EventMsg::TaskComplete(_) | EventMsg::Error(_) => {
+                        let _ = turn_tx.send(StopReason::EndTurn);
+                    }
+                    EventMsg::ExecApprovalRequest(request) => {
+                        if let Some(notification) = approval_notification(&session_id, &request) {
+                            let _ = notification_tx.send(notification);
+                        }
+                        let client = client.clone();
+                        let submission_tx = submission_tx.clone();
+                        let session_id = session_id.clone();
+                        tokio::spawn(async move {
+                            let id = request.call_id.clone();
+                            let decision = request_approval(&client, &session_id, request).await;
+                            let submission = Submission {
+                                id: uuid::Uuid::new_v4().to_string(),
+                                op: Op::ExecApproval { id, decision },
+                            };
+                            let _ = submission_tx.send(submission).await;
+                        });
+                    }
+                    msg => {
+                        if let Some(notification) = event_to_notification(&session_id, msg) {
+                            let _ = notification_tx.send(notification);
+                        }
+                    }
+                }
+            }
+            debug!("Session {} event forwarder stopped", session_id);
+        });
+    }
+
     /// Process a JSON-RPC request and return a response.
     pub async fn process_request(
         &self,
@@ -570,6 +601,81 @@ fn event_to_notification(session_id: &str, msg: EventMsg) -> Option<AcpNotificat
     })
 }
 
+/// Permission options offered for exec approvals.
+const PERMISSION_OPTIONS: [(&str, &str, PermissionOptionKind); 3] = [
+    ("allow_once", "Allow", PermissionOptionKind::AllowOnce),
+    (
+        "allow_always",
+        "Always allow",
+        PermissionOptionKind::AllowAlways,
+    ),
+    ("reject_once", "Reject", PermissionOptionKind::RejectOnce),
+];
+
+/// Show the pending command as a tool call before asking for permission.
+fn approval_notification(
+    session_id: &str,
+    request: &ExecApprovalRequestEvent,
+) -> Option<AcpNotificationEvent> {
+    let notification = SessionNotification {
+        session_id: session_id.to_string(),
+        update: SessionUpdate::ToolCall {
+            tool_call_id: request.call_id.clone(),
+            title: request.command.join(" "),
+            kind: ToolKind::Execute,
+            status: ToolStatus::Pending,
+            locations: vec![],
+            raw_input: serde_json::json!({ "command": request.command }),
+        },
+    };
+    Some(AcpNotificationEvent {
+        method: methods::SESSION_UPDATE.to_string(),
+        params: serde_json::to_value(notification).ok()?,
+    })
+}
+
+/// Ask the client to approve a command. Errors count as a rejection.
+async fn request_approval(
+    client: &AcpClient,
+    session_id: &str,
+    request: ExecApprovalRequestEvent,
+) -> ReviewDecision {
+    let params = RequestPermissionRequest {
+        session_id: session_id.to_string(),
+        tool_call: PermissionToolCall {
+            tool_call_id: request.call_id,
+            title: request.command.join(" "),
+            kind: ToolKind::Execute,
+            status: ToolStatus::Pending,
+            raw_input: serde_json::json!({
+                "command": request.command,
+                "cwd": request.cwd,
+            }),
+        },
+        options: PERMISSION_OPTIONS
+            .iter()
+            .map(|(id, name, kind)| PermissionOption {
+                option_id: id.to_string(),
+                name: name.to_string(),
+                kind: *kind,
+            })
+            .collect(),
+    };
+
+    match client.request_permission(params).await {
+        Ok(PermissionOutcome::Selected { option_id }) => match option_id.as_str() {
+            "allow_once" => ReviewDecision::Approved,
+            "allow_always" => ReviewDecision::ApprovedForSession,
+            _ => ReviewDecision::Denied,
+        },
+        Ok(PermissionOutcome::Cancelled) => ReviewDecision::Abort,
+        Err(e) => {
+            warn!("Permission request failed: {e:#}");
+            ReviewDecision::Denied
+        }
+    }
+}
+
 // Additional request/response types for extended protocol
 
 /// Load session request.
@@ -19,12 +19,21 @@
 //!
 //! Session updates are streamed via notifications:
 //! - `session/update` - Contains agent message chunks, tool calls, etc.
+//!
+//! ## Client Requests
+//!
+//! The agent calls back into clients that advertise the capability:
+//! - `session/request_permission` - Approve a command before it runs
+//! - `fs/read_text_file`, `fs/write_text_file` - File access through editor buffers
+//! - `terminal/*` - Run commands in client terminals
 
+pub mod client;
 pub mod handler;
 pub mod protocol;
 pub mod server;
 pub mod types;
 
+pub use client::{AcpBridge, AcpClient};
 pub use handler::{AcpHandler, AcpNotificationEvent, AcpSessionState};
 pub use protocol::{AcpError, AcpNotification, AcpRequest, AcpRequestId, AcpResponse};
 pub use server::AcpServer;
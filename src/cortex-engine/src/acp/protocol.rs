@@ -244,4 +244,23 @@ pub mod methods {
     pub const MODELS_LIST: &str = "models/list";
     /// Get available agents.
     pub const AGENTS_LIST: &str = "agents/list";
+
+    // Agent-to-client requests
+
+    /// Ask the user to approve a tool call.
+    pub const SESSION_REQUEST_PERMISSION: &str = "session/request_permission";
+    /// Read a file, including unsaved editor buffers.
+    pub const FS_READ_TEXT_FILE: &str = "fs/read_text_file";
+    /// Write a file through the editor.
+    pub const FS_WRITE_TEXT_FILE: &str = "fs/write_text_file";
+    /// Start a command in a client terminal.
+    pub const TERMINAL_CREATE: &str = "terminal/create";
+    /// Get a terminal's output so far.
+    pub const TERMINAL_OUTPUT: &str = "terminal/output";
+    /// Wait for a terminal's command to exit.
+    pub const TERMINAL_WAIT_FOR_EXIT: &str = "terminal/wait_for_exit";
+    /// Kill a terminal's command.
+    pub const TERMINAL_KILL: &str = "terminal/kill";
+    /// Release a terminal.
+    pub const TERMINAL_RELEASE: &str = "terminal/release";
 }
@@ -11,7 +11,8 @@ use anyhow::Result;
 use serde::Serialize;
 use serde_json::Value;
 use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tracing::{debug, error, info};
+use tokio::sync::{broadcast, mpsc};
+use tracing::{debug, error, info, warn};
 
 use crate::acp::handler::{AcpHandler, AcpNotificationEvent};
 use crate::acp::protocol::{AcpError, AcpNotification, AcpRequest, AcpRequestId, AcpResponse};
@@ -35,8 +36,9 @@ impl AcpServer {
 
     /// Run the server with stdio transport.
     ///
-    /// This reads JSON-RPC requests from stdin and writes responses to stdout.
-    /// Notifications are also written to stdout.
+    /// This reads JSON-RPC messages from stdin and writes responses to stdout.
+    /// Notifications and agent-to-client requests are also written to stdout;
+    /// the client's responses to those come back on stdin.
     pub async fn run_stdio(&self) -> Result<()> {
         info!("Starting ACP server on stdio transport");
 
@@ -44,9 +46,15 @@ impl AcpServer {
         let mut reader = BufReader::new(stdin);
         let mut line = String::new();
 
-        // Spawn notification forwarder
+        // Single writer so concurrent messages never interleave
+        let (out_tx, out_rx) = mpsc::unbounded_channel::<Vec<u8>>();
+        tokio::spawn(Self::write_stdout_lines(out_rx));
+
+        // Spawn notification and client request forwarders
         let notification_rx = self.handler.subscribe();
-        tokio::spawn(Self::forward_notifications_to_stdio(notification_rx));
+        tokio::spawn(Self::forward_notifications(notification_rx, out_tx.clone()));
+        let client_rx = self.handler.client().subscribe();
+        tokio::spawn(Self::forward_client_requests(client_rx, out_tx.clone()));
 
         while reader.read_line(&mut line).await? > 0 {
             let trimmed = line.trim();
@@ -55,57 +63,109 @@ impl AcpServer {
                 continue;
             }
 
-            debug!("Received request: {}", trimmed);
+            debug!("Received message: {}", trimmed);
 
-            let request: AcpRequest = match serde_json::from_str(trimmed) {
-                Ok(req) => req,
+            let message: Value = match serde_json::from_str(trimmed) {
+                Ok(value) => value,
                 Err(e) => {
                     let err_response = AcpResponse::error(
                         AcpRequestId::Number(0),
                         AcpError::parse_error(e.to_string()),
                     );
-                    Self::write_to_stdout(&err_response).await?;
+                    Self::send_line(&out_tx, &err_response);
                     line.clear();
                     continue;
                 }
             };
 
-            let response = self
-                .handler
-                .process_request(
-                    request.id.clone(),
-                    &request.method,
-                    request.params.unwrap_or(Value::Null),
-                )
-                .await;
+            // Responses to our own requests carry no method
+            if message.get("method").is_none() {
+                match serde_json::from_value::<AcpResponse>(message) {
+                    Ok(response) => {
+                        if !self.handler.client().handle_response(response) {
+                            warn!("Response to unknown request: {}", trimmed);
+                        }
+                    }
+                    Err(e) => warn!("Invalid response from client: {}", e),
+                }
+                line.clear();
+                continue;
+            }
+
+            let request: AcpRequest = match serde_json::from_value(message) {
+                Ok(req) => req,
+                Err(e) => {
+                    let err_response = AcpResponse::error(
+                        AcpRequestId::Number(0),
+                        AcpError::invalid_request(e.to_string()),
+                    );
+                    Self::send_line(&out_tx, &err_response);
+                    line.clear();
+                    continue;
+                }
+            };
 
-            Self::write_to_stdout(&response).await?;
+            // Requests run concurrently: a prompt may wait on client responses
+            let handler = self.handler.clone();
+            let out_tx = out_tx.clone();
+            tokio::spawn(async move {
+                let response = handler
+                    .process_request(
+                        request.id.clone(),
+                        &request.method,
+                        request.params.unwrap_or(Value::Null),
+                    )
+                    .await;
+                Self::send_line(&out_tx, &response);
+            });
             line.clear();
         }
 
         Ok(())
     }
 
-    /// Forward notifications to stdout.
-    async fn forward_notifications_to_stdio(
-        mut rx: tokio::sync::broadcast::Receiver<AcpNotificationEvent>,
+    /// Forward notifications to the output channel.
+    async fn forward_notifications(
+        mut rx: broadcast::Receiver<AcpNotificationEvent>,
+        out_tx: mpsc::UnboundedSender<Vec<u8>>,
     ) {
         while let Ok(event) = rx.recv().await {
             let notification = AcpNotification::new(&event.method).with_params(event.params);
-            if let Err(e) = Self::write_to_stdout(&notification).await {
-                error!("Error writing notification: {}", e);
+            Self::send_line(&out_tx, &notification);
+        }
+    }
+
+    /// Forward agent-to-client requests to the output channel.
+    async fn forward_client_requests(
+        mut rx: broadcast::Receiver<AcpRequest>,
+        out_tx: mpsc::UnboundedSender<Vec<u8>>,
+    ) {
+        while let Ok(request) = rx.recv().await {
+            Self::send_line(&out_tx, &request);
+        }
+    }
+
+    /// Queue a serializable value as one line of JSON.
+    fn send_line<T: Serialize>(out_tx: &mpsc::UnboundedSender<Vec<u8>>, value: &T) {
+        match serde_json::to_vec(value) {
+            Ok(mut json) => {
+                json.push(b'\n');
+                let _ = out_tx.send(json);
             }
+            Err(e) => error!("Error serializing message: {}", e),
         }
     }
 
-    /// Write a serializable value to stdout as JSON.
-    async fn write_to_stdout<T: Serialize>(value: &T) -> Result<()> {
-        let mut json = serde_json::to_vec(value)?;
-        json.push(b'\n');
+    /// Write queued lines to stdout.
+    async fn write_stdout_lines(mut rx: mpsc::UnboundedReceiver<Vec<u8>>) {
         let mut stdout = tokio::io::stdout();
-        stdout.write_all(&json).await?;
-        stdout.flush().await?;
-        Ok(())
+        while let Some(line) = rx.recv().await {
+            if let Err(e) = stdout.write_all(&line).await {
+                error!("Error writing to stdout: {}", e);
+                continue;
+            }
+            let _ = stdout.flush().await;
+        }
     }
 
     /// Run the server with HTTP transport.
@@ -178,6 +238,17 @@ impl AcpServer {
                     })
                     .unwrap_or("");
 
+                // Responses to agent-to-client requests carry no method
+                if let Ok(value) = serde_json::from_str::<Value>(body.trim())
+                    && value.get("method").is_none()
+                    && let Ok(response) = serde_json::from_value::<AcpResponse>(value)
+                {
+                    let accepted = handler.client().handle_response(response);
+                    let body = serde_json::json!({ "accepted": accepted });
+                    Self::send_http_json(&mut stream, 200, &body).await?;
+                    return Ok(());
+                }
+
                 let request: AcpRequest = match serde_json::from_str(body.trim()) {
                     Ok(req) => req,
                     Err(e) => {
@@ -238,10 +309,23 @@ impl AcpServer {
         stream.write_all(headers.as_bytes()).await?;
 
         let mut rx = handler.subscribe();
+        let mut client_rx = handler.client().subscribe();
 
         // Keep connection alive and forward events
         loop {
             tokio::select! {
+                result = client_rx.recv() => {
+                    match result {
+                        Ok(request) => {
+                            let sse_msg = format!("data: {}\n\n", serde_json::to_string(&request)?);
+                            if stream.write_all(sse_msg.as_bytes()).await.is_err() {
+                                break;
+                            }
+                        }
+                        Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                        Err(_) => break,
+                    }
+                }
                 result = rx.recv() => {
                     match result {
                         Ok(event) => {
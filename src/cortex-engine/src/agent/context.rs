@@ -8,6 +8,7 @@ use super::{AgentConfig, AgentEvent};
 use crate::client::ModelClient;
 use crate::client::types::{CompletionRequest, Message, MessageRole};
 use crate::error::Result;
+use crate::tokenizer::TokenCounter;
 use tokio::sync::mpsc;
 
 /// Manages the conversation context for an agent.
@@ -86,15 +87,9 @@ impl AgentContext {
         Ok(())
     }
 
-    /// Rough estimate of tokens (4 characters per token).
+    /// Count tokens with the model's tokenizer.
     fn estimate_tokens(&self, messages: &[Message]) -> u32 {
-        let mut total = 0;
-        for msg in messages {
-            if let Some(text) = msg.content.as_text() {
-                total += (text.len() / 4) as u32;
-            }
-        }
-        total
+        TokenCounter::for_model(&self.config.model).count_conversation(messages)
     }
 
     /// Compact the context by summarizing early messages.
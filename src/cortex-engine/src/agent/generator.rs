@@ -399,6 +399,11 @@ fn validate_agent(mut agent: GeneratedAgent) -> Result<GeneratedAgent> {
         "LspDiagnostics",
         "LspHover",
         "LspSymbols",
+        "LspDefinition",
+        "LspReferences",
+        "LspCallHierarchy",
+        "LspRename",
+        "LspCodeAction",
     ];
 
     agent
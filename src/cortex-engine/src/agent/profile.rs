@@ -189,7 +189,11 @@ impl AgentProfile {
                     perms.insert("Edit".to_string(), ToolPermission::Deny);
                     perms.insert("Create".to_string(), ToolPermission::Deny);
                     perms.insert("ApplyPatch".to_string(), ToolPermission::Deny);
+                    perms.insert("LspRename".to_string(), ToolPermission::Deny);
+                    perms.insert("LspCodeAction".to_string(), ToolPermission::Deny);
                     perms.insert("Execute".to_string(), ToolPermission::Deny);
+                    perms.insert("TerminalOpen".to_string(), ToolPermission::Deny);
+                    perms.insert("TerminalSend".to_string(), ToolPermission::Deny);
                     perms
                 },
                 system_prompt: Some("You are a software architect focused on planning and analysis. You have read-only access to the codebase. Your goal is to analyze requirements, research the codebase, and provide detailed implementation plans without making any changes.".to_string()),
@@ -212,12 +216,19 @@ impl AgentProfile {
                     perms.insert("LS".to_string(), ToolPermission::Allow);
                     perms.insert("Read".to_string(), ToolPermission::Allow);
                     perms.insert("SearchFiles".to_string(), ToolPermission::Allow);
+                    perms.insert("LspDefinition".to_string(), ToolPermission::Allow);
+                    perms.insert("LspReferences".to_string(), ToolPermission::Allow);
+                    perms.insert("LspSymbols".to_string(), ToolPermission::Allow);
 
                     // Deny others for speed and focus
                     perms.insert("Edit".to_string(), ToolPermission::Deny);
                     perms.insert("Create".to_string(), ToolPermission::Deny);
                     perms.insert("ApplyPatch".to_string(), ToolPermission::Deny);
+                    perms.insert("LspRename".to_string(), ToolPermission::Deny);
+                    perms.insert("LspCodeAction".to_string(), ToolPermission::Deny);
                     perms.insert("Execute".to_string(), ToolPermission::Deny);
+                    perms.insert("TerminalOpen".to_string(), ToolPermission::Deny);
+                    perms.insert("TerminalSend".to_string(), ToolPermission::Deny);
                     perms
                 },
                 system_prompt: Some("You are a code exploration specialist. Your goal is to quickly find information and understand the codebase structure using search and navigation tools.".to_string()),
@@ -2,12 +2,21 @@ use crate::error::Result;
 use crate::integrations::LspIntegration;
 use crate::tools::context::ToolContext;
 use crate::tools::handlers::ToolHandler;
+use crate::tools::handlers::workspace_edit::{
+    apply_workspace_edit, display_path, edited_files_result, to_lsp_position,
+};
 use crate::tools::spec::ToolResult;
 use async_trait::async_trait;
 use cortex_lsp::DiagnosticSeverity;
+use cortex_lsp::lsp_types::{
+    CallHierarchyItem, CodeActionOrCommand, Location, Position, Range, Url, WorkspaceEdit,
+};
 use serde::Deserialize;
 use serde_json::Value;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 
 /// Tool for querying LSP diagnostics across the project.
 pub struct LspDiagnosticsTool {
@@ -81,6 +90,70 @@ impl LspDiagnosticsTool {
 
         Ok(ToolResult::success(output))
     }
+
+    /// Run the tool for a single file, waiting up to `timeout` for
+    /// diagnostics that describe its current content.
+    pub async fn run_for_file(
+        &self,
+        lsp: &LspIntegration,
+        path: &Path,
+        cwd: &Path,
+        timeout: Duration,
+        severity: Option<&str>,
+    ) -> Result<ToolResult> {
+        let display = display_path(path, cwd);
+        let (diagnostics, stale) = match lsp.wait_for_diagnostics(path, timeout).await {
+            Ok(Some(diagnostics)) => (diagnostics, false),
+            Ok(None) => (lsp.get_diagnostics(path).await, true),
+            Err(e) => {
+                return Ok(ToolResult::error(format!(
+                    "Failed to get diagnostics for {}: {}",
+                    display, e
+                )));
+            }
+        };
+
+        let diagnostics: Vec<_> = diagnostics
+            .iter()
+            .filter(|d| severity_matches(severity, d.severity))
+            .collect();
+
+        let mut output = if diagnostics.is_empty() {
+            format!("✓ No diagnostics for {}.", display)
+        } else {
+            let mut output = format!("LSP Diagnostics for {}:\n\n", display);
+            for diag in &diagnostics {
+                output.push_str(&format!("  {}\n", diag.format()));
+            }
+            output
+        };
+        if stale {
+            output.push_str(&format!(
+                "\n\nNote: the language server did not report on the latest content within {}ms; these diagnostics may be stale.",
+                timeout.as_millis()
+            ));
+        }
+
+        Ok(ToolResult::success(output))
+    }
+}
+
+/// How long `LspDiagnostics` waits for fresh diagnostics on a file by default.
+const DEFAULT_DIAGNOSTICS_TIMEOUT_MS: u64 = 5_000;
+
+#[derive(Debug, Default, Deserialize)]
+struct DiagnosticsArgs {
+    path: Option<String>,
+    severity: Option<String>,
+    timeout_ms: Option<u64>,
+}
+
+fn severity_matches(filter: Option<&str>, severity: DiagnosticSeverity) -> bool {
+    match filter {
+        Some("error") => severity == DiagnosticSeverity::Error,
+        Some("warning") => severity == DiagnosticSeverity::Warning,
+        _ => true,
+    }
 }
 
 #[async_trait]
@@ -89,15 +162,24 @@ impl ToolHandler for LspDiagnosticsTool {
         "LspDiagnostics"
     }
 
-    async fn execute(&self, _arguments: Value, context: &ToolContext) -> Result<ToolResult> {
-        if let Some(lsp) = &context.lsp {
-            self.run_with_lsp(lsp).await
-        } else if let Some(lsp) = &self.lsp {
-            self.run_with_lsp(lsp).await
-        } else {
-            Ok(ToolResult::error(
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let Some(lsp) = context.lsp.as_ref().or(self.lsp.as_ref()) else {
+            return Ok(ToolResult::error(
                 "LSP integration is not available in the current context.",
-            ))
+            ));
+        };
+
+        let args: DiagnosticsArgs = serde_json::from_value(arguments).unwrap_or_default();
+        match &args.path {
+            Some(path) => {
+                let path = context.resolve_path(path);
+                let timeout = Duration::from_millis(
+                    args.timeout_ms.unwrap_or(DEFAULT_DIAGNOSTICS_TIMEOUT_MS),
+                );
+                self.run_for_file(lsp, &path, &context.cwd, timeout, args.severity.as_deref())
+                    .await
+            }
+            None => self.run_with_lsp(lsp).await,
         }
     }
 }
@@ -161,3 +243,610 @@ impl ToolHandler for LspHoverTool {
         }
     }
 }
+
+/// Maximum number of locations listed in a navigation result.
+const MAX_LOCATIONS: usize = 100;
+
+/// A position in a file, as passed to the navigation tools.
+#[derive(Debug, Deserialize)]
+struct PositionArgs {
+    file: String,
+    line: u32,
+    column: u32,
+}
+
+impl PositionArgs {
+    /// Resolve the file and convert the 1-based position to an LSP position.
+    async fn resolve(
+        &self,
+        context: &ToolContext,
+    ) -> std::result::Result<(String, Position), String> {
+        let path = context.resolve_path(&self.file);
+        let content = tokio::fs::read_to_string(&path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let position = to_lsp_position(&content, self.line, self.column);
+        Ok((path.to_string_lossy().into_owned(), position))
+    }
+}
+
+/// Error reported when the context carries no LSP integration.
+const LSP_UNAVAILABLE: &str = "LSP integration is not available in the current context.";
+
+/// Render locations as `path:line:column  source line`, one per line.
+async fn format_locations(locations: &[Location], cwd: &Path) -> String {
+    let mut files: HashMap<PathBuf, Option<String>> = HashMap::new();
+    let mut output = String::new();
+
+    for location in locations.iter().take(MAX_LOCATIONS) {
+        let Ok(path) = location.uri.to_file_path() else {
+            output.push_str(&format!("{}\n", location.uri));
+            continue;
+        };
+        let line = location.range.start.line as usize;
+        let content = match files.get(&path) {
+            Some(content) => content.clone(),
+            None => {
+                let content = tokio::fs::read_to_string(&path).await.ok();
+                files.insert(path.clone(), content.clone());
+                content
+            }
+        };
+        let text = content
+            .as_deref()
+            .and_then(|c| c.lines().nth(line))
+            .unwrap_or("");
+        let column = text
+            .encode_utf16()
+            .take(location.range.start.character as usize)
+            .collect::<Vec<_>>();
+        let column = String::from_utf16_lossy(&column).chars().count() + 1;
+
+        output.push_str(&format!(
+            "{}:{}:{}  {}\n",
+            display_path(&path, cwd),
+            line + 1,
+            column,
+            text.trim()
+        ));
+    }
+
+    if locations.len() > MAX_LOCATIONS {
+        output.push_str(&format!(
+            "... and {} more\n",
+            locations.len() - MAX_LOCATIONS
+        ));
+    }
+    output
+}
+
+/// Tool for jumping to the definition or implementations of a symbol.
+pub struct LspDefinitionTool;
+
+#[derive(Debug, Deserialize)]
+struct DefinitionArgs {
+    #[serde(flatten)]
+    position: PositionArgs,
+    #[serde(default)]
+    implementation: bool,
+}
+
+impl LspDefinitionTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LspDefinitionTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for LspDefinitionTool {
+    fn name(&self) -> &str {
+        "LspDefinition"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: DefinitionArgs = match serde_json::from_value(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid arguments: {e}"))),
+        };
+        let Some(lsp) = &context.lsp else {
+            return Ok(ToolResult::error(LSP_UNAVAILABLE));
+        };
+        let (path, position) = match args.position.resolve(context).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let (what, result) = if args.implementation {
+            let result = lsp
+                .go_to_implementation(&path, position.line, position.character)
+                .await;
+            ("implementations", result)
+        } else {
+            let result = lsp
+                .go_to_definition(&path, position.line, position.character)
+                .await;
+            ("definition", result)
+        };
+
+        match result {
+            Ok(locations) if locations.is_empty() => Ok(ToolResult::success(format!(
+                "No {what} found at this position."
+            ))),
+            Ok(locations) => Ok(ToolResult::success(
+                format_locations(&locations, &context.cwd).await,
+            )),
+            Err(e) => Ok(ToolResult::error(format!(
+                "LSP {what} lookup failed: {e:#}"
+            ))),
+        }
+    }
+}
+
+/// Tool for finding every reference to a symbol.
+pub struct LspReferencesTool;
+
+impl LspReferencesTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LspReferencesTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for LspReferencesTool {
+    fn name(&self) -> &str {
+        "LspReferences"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: PositionArgs = match serde_json::from_value(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid arguments: {e}"))),
+        };
+        let Some(lsp) = &context.lsp else {
+            return Ok(ToolResult::error(LSP_UNAVAILABLE));
+        };
+        let (path, position) = match args.resolve(context).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        match lsp
+            .find_references(&path, position.line, position.character)
+            .await
+        {
+            Ok(locations) if locations.is_empty() => {
+                Ok(ToolResult::success("No references found at this position."))
+            }
+            Ok(locations) => Ok(ToolResult::success(format!(
+                "{} reference(s):\n{}",
+                locations.len(),
+                format_locations(&locations, &context.cwd).await
+            ))),
+            Err(e) => Ok(ToolResult::error(format!("LSP references failed: {e:#}"))),
+        }
+    }
+}
+
+/// Tool for listing the callers or callees of a function.
+pub struct LspCallHierarchyTool;
+
+#[derive(Debug, Deserialize)]
+struct CallHierarchyArgs {
+    #[serde(flatten)]
+    position: PositionArgs,
+    #[serde(default)]
+    direction: CallDirection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum CallDirection {
+    #[default]
+    Incoming,
+    Outgoing,
+}
+
+impl LspCallHierarchyTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LspCallHierarchyTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for LspCallHierarchyTool {
+    fn name(&self) -> &str {
+        "LspCallHierarchy"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: CallHierarchyArgs = match serde_json::from_value(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid arguments: {e}"))),
+        };
+        let Some(lsp) = &context.lsp else {
+            return Ok(ToolResult::error(LSP_UNAVAILABLE));
+        };
+        let (path, position) = match args.position.resolve(context).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        // Each entry is the related function and where the call happens.
+        let calls: Vec<(CallHierarchyItem, Vec<Location>)> = match args.direction {
+            CallDirection::Incoming => {
+                match lsp
+                    .incoming_calls(&path, position.line, position.character)
+                    .await
+                {
+                    Ok(calls) => calls
+                        .into_iter()
+                        .map(|call| {
+                            let sites = call
+                                .from_ranges
+                                .into_iter()
+                                .map(|range| Location::new(call.from.uri.clone(), range))
+                                .collect();
+                            (call.from, sites)
+                        })
+                        .collect(),
+                    Err(e) => {
+                        return Ok(ToolResult::error(format!(
+                            "LSP incoming calls failed: {e:#}"
+                        )));
+                    }
+                }
+            }
+            CallDirection::Outgoing => {
+                match lsp
+                    .outgoing_calls(&path, position.line, position.character)
+                    .await
+                {
+                    // Outgoing call ranges are in the caller, i.e. this file.
+                    Ok(calls) => calls
+                        .into_iter()
+                        .map(|call| {
+                            let uri = Url::from_file_path(&path).unwrap_or(call.to.uri.clone());
+                            let sites = call
+                                .from_ranges
+                                .into_iter()
+                                .map(|range| Location::new(uri.clone(), range))
+                                .collect();
+                            (call.to, sites)
+                        })
+                        .collect(),
+                    Err(e) => {
+                        return Ok(ToolResult::error(format!(
+                            "LSP outgoing calls failed: {e:#}"
+                        )));
+                    }
+                }
+            }
+        };
+
+        let (label, none) = match args.direction {
+            CallDirection::Incoming => ("Callers", "No callers found."),
+            CallDirection::Outgoing => ("Callees", "No outgoing calls found."),
+        };
+        if calls.is_empty() {
+            return Ok(ToolResult::success(none));
+        }
+
+        let mut output = format!("{label} ({}):\n", calls.len());
+        for (item, sites) in calls.iter().take(MAX_LOCATIONS) {
+            let target = Location::new(item.uri.clone(), item.selection_range);
+            output.push_str(&format!(
+                "\n{} ({:?}) at {}",
+                item.name,
+                item.kind,
+                format_locations(std::slice::from_ref(&target), &context.cwd).await
+            ));
+            for line in format_locations(sites, &context.cwd).await.lines() {
+                output.push_str(&format!("  call: {line}\n"));
+            }
+        }
+        Ok(ToolResult::success(output.trim_end().to_string()))
+    }
+}
+
+/// Tool for renaming a symbol across the workspace.
+///
+/// The language server computes the edit; it is applied through the same
+/// write path as the Patch tool.
+pub struct LspRenameTool;
+
+#[derive(Debug, Deserialize)]
+struct RenameArgs {
+    #[serde(flatten)]
+    position: PositionArgs,
+    new_name: String,
+}
+
+impl LspRenameTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LspRenameTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for LspRenameTool {
+    fn name(&self) -> &str {
+        "LspRename"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: RenameArgs = match serde_json::from_value(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid arguments: {e}"))),
+        };
+        if args.new_name.trim().is_empty() {
+            return Ok(ToolResult::error("new_name must not be empty"));
+        }
+        let Some(lsp) = &context.lsp else {
+            return Ok(ToolResult::error(LSP_UNAVAILABLE));
+        };
+        let (path, position) = match args.position.resolve(context).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let edit = match lsp
+            .rename(&path, position.line, position.character, &args.new_name)
+            .await
+        {
+            Ok(Some(edit)) => edit,
+            Ok(None) => {
+                return Ok(ToolResult::error(
+                    "The language server cannot rename the symbol at this position.",
+                ));
+            }
+            Err(e) => return Ok(ToolResult::error(format!("LSP rename failed: {e:#}"))),
+        };
+
+        match apply_workspace_edit(&edit, context).await {
+            Ok(files) => Ok(edited_files_result(
+                &format!("Renamed to '{}'", args.new_name),
+                &files,
+                &context.cwd,
+            )),
+            Err(e) => Ok(ToolResult::error(format!("Failed to apply rename: {e}"))),
+        }
+    }
+}
+
+/// Tool for listing and applying code actions (quick fixes and refactorings).
+pub struct LspCodeActionTool;
+
+#[derive(Debug, Deserialize)]
+struct CodeActionArgs {
+    #[serde(flatten)]
+    position: PositionArgs,
+    end_line: Option<u32>,
+    end_column: Option<u32>,
+    /// 1-based index or exact title of the action to apply.
+    apply: Option<Value>,
+}
+
+impl LspCodeActionTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for LspCodeActionTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Title, kind and edit of a code action. Bare commands have no edit.
+fn code_action_parts(action: &CodeActionOrCommand) -> (&str, Option<&str>, Option<&WorkspaceEdit>) {
+    match action {
+        CodeActionOrCommand::Command(command) => (&command.title, None, None),
+        CodeActionOrCommand::CodeAction(action) => (
+            &action.title,
+            action.kind.as_ref().map(|k| k.as_str()),
+            action.edit.as_ref(),
+        ),
+    }
+}
+
+/// Find the action selected by a 1-based index or title.
+fn select_code_action<'a>(
+    actions: &'a [CodeActionOrCommand],
+    selector: &Value,
+) -> Option<&'a CodeActionOrCommand> {
+    let index = selector
+        .as_u64()
+        .or_else(|| selector.as_str().and_then(|s| s.trim().parse().ok()));
+    match index {
+        Some(index) => actions.get((index as usize).checked_sub(1)?),
+        None => {
+            let title = selector.as_str()?.trim();
+            actions
+                .iter()
+                .find(|action| code_action_parts(action).0 == title)
+        }
+    }
+}
+
+#[async_trait]
+impl ToolHandler for LspCodeActionTool {
+    fn name(&self) -> &str {
+        "LspCodeAction"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: CodeActionArgs = match serde_json::from_value(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(format!("Invalid arguments: {e}"))),
+        };
+        let Some(lsp) = &context.lsp else {
+            return Ok(ToolResult::error(LSP_UNAVAILABLE));
+        };
+        let (path, start) = match args.position.resolve(context).await {
+            Ok(resolved) => resolved,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let end = match (args.end_line, args.end_column) {
+            (Some(line), Some(column)) => {
+                let end = PositionArgs {
+                    file: args.position.file.clone(),
+                    line,
+                    column,
+                };
+                match end.resolve(context).await {
+                    Ok((_, end)) => end,
+                    Err(e) => return Ok(ToolResult::error(e)),
+                }
+            }
+            _ => start,
+        };
+
+        let actions = match lsp.code_actions(&path, Range::new(start, end)).await {
+            Ok(actions) => actions,
+            Err(e) => return Ok(ToolResult::error(format!("LSP code actions failed: {e:#}"))),
+        };
+        if actions.is_empty() {
+            return Ok(ToolResult::success("No code actions available here."));
+        }
+
+        let Some(selector) = args.apply else {
+            let mut output = format!("{} code action(s):\n", actions.len());
+            for (i, action) in actions.iter().enumerate() {
+                let (title, kind, edit) = code_action_parts(action);
+                output.push_str(&format!("{}. {}", i + 1, title));
+                if let Some(kind) = kind {
+                    output.push_str(&format!(" [{kind}]"));
+                }
+                if edit.is_none() {
+                    output.push_str(" (cannot be applied: no edit)");
+                }
+                output.push('\n');
+            }
+            output.push_str("\nCall again with `apply` set to a number or title to apply one.");
+            return Ok(ToolResult::success(output));
+        };
+
+        let Some(action) = select_code_action(&actions, &selector) else {
+            return Ok(ToolResult::error(format!(
+                "No code action matches {selector}; {} are available.",
+                actions.len()
+            )));
+        };
+        let (title, _, edit) = code_action_parts(action);
+        let Some(edit) = edit else {
+            return Ok(ToolResult::error(format!(
+                "Code action '{title}' only runs a server command and has no edit to apply."
+            )));
+        };
+
+        match apply_workspace_edit(edit, context).await {
+            Ok(files) => Ok(edited_files_result(
+                &format!("Applied '{title}'"),
+                &files,
+                &context.cwd,
+            )),
+            Err(e) => Ok(ToolResult::error(format!(
+                "Failed to apply code action '{title}': {e}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_lsp::lsp_types::{CodeAction, Command};
+    use serde_json::json;
+
+    fn actions() -> Vec<CodeActionOrCommand> {
+        vec![
+            CodeActionOrCommand::Command(Command::new(
+                "Run test".to_string(),
+                "rust-analyzer.runSingle".to_string(),
+                None,
+            )),
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title: "Import `HashMap`".to_string(),
+                edit: Some(WorkspaceEdit::default()),
+                ..Default::default()
+            }),
+        ]
+    }
+
+    #[test]
+    fn test_select_code_action_by_index_and_title() {
+        let actions = actions();
+
+        let by_index = select_code_action(&actions, &json!(2)).unwrap();
+        assert_eq!(code_action_parts(by_index).0, "Import `HashMap`");
+        let by_string_index = select_code_action(&actions, &json!("1")).unwrap();
+        assert_eq!(code_action_parts(by_string_index).0, "Run test");
+        let by_title = select_code_action(&actions, &json!("Import `HashMap`")).unwrap();
+        assert!(code_action_parts(by_title).2.is_some());
+
+        assert!(select_code_action(&actions, &json!(0)).is_none());
+        assert!(select_code_action(&actions, &json!(3)).is_none());
+        assert!(select_code_action(&actions, &json!("Missing")).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_format_locations_shows_source_line() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let file = dir.path().join("lib.rs");
+        std::fs::write(&file, "struct A;\n    fn run() {}\n").unwrap();
+        let location = Location::new(
+            Url::from_file_path(&file).unwrap(),
+            Range::new(Position::new(1, 7), Position::new(1, 10)),
+        );
+
+        let output = format_locations(&[location], dir.path()).await;
+        assert_eq!(output, "lib.rs:2:8  fn run() {}\n");
+    }
+
+    #[test]
+    fn test_severity_filter() {
+        assert!(severity_matches(None, DiagnosticSeverity::Hint));
+        assert!(severity_matches(
+            Some("all"),
+            DiagnosticSeverity::Information
+        ));
+        assert!(severity_matches(Some("error"), DiagnosticSeverity::Error));
+        assert!(!severity_matches(
+            Some("error"),
+            DiagnosticSeverity::Warning
+        ));
+        assert!(severity_matches(
+            Some("warning"),
+            DiagnosticSeverity::Warning
+        ));
+        assert!(!severity_matches(
+            Some("warning"),
+            DiagnosticSeverity::Error
+        ));
+    }
+}
@@ -3,9 +3,16 @@ pub mod lsp;
 pub mod multiedit;
 pub mod patch;
 pub mod search;
+pub mod terminal;
 
 pub use fetch::WebFetchTool;
-pub use lsp::{LspDiagnosticsTool, LspHoverTool};
+pub use lsp::{
+    LspCallHierarchyTool, LspCodeActionTool, LspDefinitionTool, LspDiagnosticsTool, LspHoverTool,
+    LspReferencesTool, LspRenameTool,
+};
 pub use multiedit::MultiEditTool;
 pub use patch::PatchTool;
 pub use search::WebSearchTool;
+pub use terminal::{
+    TerminalCloseTool, TerminalOpenTool, TerminalScreenTool, TerminalSendTool, TerminalWaitTool,
+};
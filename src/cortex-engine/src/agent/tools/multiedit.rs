@@ -134,6 +134,7 @@ impl ToolHandler for MultiEditTool {
                         e
                     )));
                 }
+                context.notify_file_written(path, content).await;
             }
         }
 
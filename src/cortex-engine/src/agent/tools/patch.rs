@@ -1,7 +1,7 @@
 use async_trait::async_trait;
 use serde::Deserialize;
 use serde_json::Value;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 use tokio::fs;
 
 use crate::error::Result;
@@ -45,10 +45,7 @@ impl ToolHandler for PatchTool {
             return Ok(ToolResult::error("Empty patch provided"));
         }
 
-        match self
-            .apply_patch(&args.patch, &context.cwd, args.dry_run)
-            .await
-        {
+        match self.apply_patch(&args.patch, context, args.dry_run).await {
             Ok(report) => Ok(ToolResult::success(report)),
             Err(e) => Ok(ToolResult::error(format!("Failed to apply patch: {e}"))),
         }
@@ -59,7 +56,7 @@ impl PatchTool {
     async fn apply_patch(
         &self,
         patch: &str,
-        cwd: &Path,
+        context: &ToolContext,
         dry_run: bool,
     ) -> std::result::Result<String, String> {
         let file_changes = parse_unified_diff(patch)?;
@@ -73,7 +70,7 @@ impl PatchTool {
         let mut failed_files = Vec::new();
 
         for change in file_changes {
-            match self.apply_file_change(&change, cwd, dry_run).await {
+            match self.apply_file_change(&change, context, dry_run).await {
                 Ok(res) => {
                     report.push(res);
                     if let Some(ref path) = change.new_path {
@@ -117,9 +114,10 @@ impl PatchTool {
     async fn apply_file_change(
         &self,
         change: &FileChange,
-        cwd: &Path,
+        context: &ToolContext,
         dry_run: bool,
     ) -> std::result::Result<String, String> {
+        let cwd = context.cwd.as_path();
         // Handle file deletion
         if change.is_deleted
             && let Some(ref old_path) = change.old_path
@@ -153,9 +151,10 @@ impl PatchTool {
                         .await
                         .map_err(|e| format!("Failed to create directory: {e}"))?;
                 }
-                fs::write(&full_path, content)
+                fs::write(&full_path, &content)
                     .await
                     .map_err(|e| format!("Failed to write {}: {}", target_path.display(), e))?;
+                context.notify_file_written(&full_path, &content).await;
             }
             return Ok(format!("  A {}", target_path.display()));
         }
@@ -192,9 +191,10 @@ impl PatchTool {
         }
 
         if !dry_run {
-            fs::write(&full_path, new_content)
+            fs::write(&full_path, &new_content)
                 .await
                 .map_err(|e| format!("Failed to write {}: {}", target_path.display(), e))?;
+            context.notify_file_written(&full_path, &new_content).await;
         }
 
         Ok(format!(
@@ -0,0 +1,481 @@
+//! Tools for driving interactive programs in PTY terminals.
+//!
+//! `TerminalOpen` starts a program in a pseudo-terminal, `TerminalSend` types
+//! into it, `TerminalWait` waits for output or quiet, `TerminalScreen` reads
+//! the rendered screen and `TerminalClose` ends it. Terminals live in the
+//! global [`TerminalManager`](crate::terminal::TerminalManager), so they
+//! persist across tool calls and are visible to the app server.
+
+use crate::audit::{self, AuditEvent, AuditKind};
+use crate::error::Result;
+use crate::terminal::{ScreenSnapshot, WaitFor, WaitOutcome, global_manager, typed_text};
+use crate::tools::context::ToolContext;
+use crate::tools::handlers::ToolHandler;
+use crate::tools::spec::{ToolMetadata, ToolResult};
+use async_trait::async_trait;
+use regex::Regex;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// Default and maximum time a wait may take.
+const DEFAULT_WAIT_TIMEOUT_MS: u64 = 30_000;
+const MAX_WAIT_TIMEOUT_MS: u64 = 600_000;
+
+/// How long a new terminal is given to draw its first screen.
+const OPEN_SETTLE_MS: u64 = 300;
+const OPEN_TIMEOUT_MS: u64 = 5_000;
+
+/// Default quiet period for `TerminalWait` without a pattern.
+const DEFAULT_IDLE_MS: u64 = 500;
+
+fn parse_args<T: for<'de> Deserialize<'de>>(arguments: Value) -> std::result::Result<T, String> {
+    serde_json::from_value(arguments).map_err(|e| format!("Invalid arguments: {e}"))
+}
+
+/// Render a screen snapshot with a one-line header.
+fn format_screen(terminal_id: &str, snapshot: &ScreenSnapshot, exit_code: Option<i32>) -> String {
+    let state = match exit_code {
+        Some(code) => format!("exited with code {code}"),
+        None => "running".to_string(),
+    };
+    let mut header = format!(
+        "Terminal {terminal_id} ({state}), {}x{}, cursor at line {} column {}",
+        snapshot.cols,
+        snapshot.rows,
+        snapshot.cursor.0 + 1,
+        snapshot.cursor.1 + 1
+    );
+    if snapshot.alternate_screen {
+        header.push_str(", full-screen");
+    }
+    if let Some(title) = snapshot.title.as_deref().filter(|t| !t.is_empty()) {
+        header.push_str(&format!(", title \"{title}\""));
+    }
+    format!("{header}\n```\n{}\n```", snapshot.text())
+}
+
+/// Error result for a command the managed policy denies, after recording
+/// the denial the way `Execute` does.
+async fn managed_denial(command: &[String]) -> Option<ToolResult> {
+    let reason = crate::managed_policy::command_denial(command)?;
+    let event = AuditEvent::new(
+        AuditKind::Denial,
+        "managed-policy",
+        command.join(" "),
+        audit::summarize(&reason),
+    );
+    audit::record(event).await;
+    Some(ToolResult::error(reason))
+}
+
+/// Screen of a terminal, or an error result if it is gone.
+async fn screen_result(terminal_id: &str, preface: Option<String>) -> ToolResult {
+    let manager = global_manager();
+    match manager.screen(terminal_id).await {
+        Ok(snapshot) => {
+            let exit_code = manager
+                .get_terminal(terminal_id)
+                .await
+                .and_then(|info| info.exit_code);
+            let screen = format_screen(terminal_id, &snapshot, exit_code);
+            let output = match preface {
+                Some(preface) => format!("{preface}\n\n{screen}"),
+                None => screen,
+            };
+            ToolResult::success(output).with_metadata(ToolMetadata {
+                duration_ms: 0,
+                exit_code,
+                files_modified: vec![],
+                data: Some(json!({
+                    "terminal_id": terminal_id,
+                    "screen": snapshot,
+                })),
+            })
+        }
+        Err(e) => ToolResult::error(e),
+    }
+}
+
+/// Tool that starts a program in a new PTY terminal.
+pub struct TerminalOpenTool;
+
+#[derive(Debug, Deserialize)]
+struct OpenArgs {
+    #[serde(default)]
+    command: Vec<String>,
+    workdir: Option<String>,
+    name: Option<String>,
+    cols: Option<u16>,
+    rows: Option<u16>,
+}
+
+impl TerminalOpenTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalOpenTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerminalOpenTool {
+    fn name(&self) -> &str {
+        "TerminalOpen"
+    }
+
+    async fn execute(&self, arguments: Value, context: &ToolContext) -> Result<ToolResult> {
+        let args: OpenArgs = match parse_args(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let cwd = match &args.workdir {
+            Some(dir) => context.resolve_path(dir),
+            None => context.cwd.clone(),
+        };
+        let name = args
+            .name
+            .or_else(|| args.command.first().cloned())
+            .unwrap_or_else(|| "shell".to_string());
+        let cols = args
+            .cols
+            .unwrap_or(cortex_utils_pty::DEFAULT_COLS)
+            .clamp(20, 500);
+        let rows = args
+            .rows
+            .unwrap_or(cortex_utils_pty::DEFAULT_ROWS)
+            .clamp(5, 200);
+
+        let command = if args.command.is_empty() {
+            vec![cortex_utils_pty::get_default_shell()]
+        } else {
+            args.command
+        };
+        if let Some(denied) = managed_denial(&command).await {
+            return Ok(denied);
+        }
+
+        // Like `Execute`, the program gets Cortex's environment without
+        // secrets, plus the session's variables such as the network proxy.
+        let mut env = crate::exec::inherited_environment();
+        env.extend(context.env.clone());
+
+        let manager = global_manager();
+        let info = match manager
+            .create_pty_terminal(
+                name,
+                cwd.display().to_string(),
+                command,
+                Some(env),
+                cols,
+                rows,
+            )
+            .await
+        {
+            Ok(info) => info,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        // Let the program draw its first screen before showing it.
+        let _ = manager
+            .wait_for(
+                &info.id,
+                &WaitFor::Idle(Duration::from_millis(OPEN_SETTLE_MS)),
+                Duration::from_millis(OPEN_TIMEOUT_MS),
+            )
+            .await;
+
+        let preface = format!(
+            "Started `{}` in terminal {}. Use TerminalSend, TerminalWait and TerminalScreen with this terminal_id, and TerminalClose when done.",
+            info.command.as_deref().unwrap_or_default(),
+            info.id
+        );
+        Ok(screen_result(&info.id, Some(preface)).await)
+    }
+}
+
+/// Tool that types keystrokes into a PTY terminal.
+pub struct TerminalSendTool;
+
+#[derive(Debug, Deserialize)]
+struct SendArgs {
+    terminal_id: String,
+    keys: String,
+}
+
+impl TerminalSendTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalSendTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerminalSendTool {
+    fn name(&self) -> &str {
+        "TerminalSend"
+    }
+
+    async fn execute(&self, arguments: Value, _context: &ToolContext) -> Result<ToolResult> {
+        let args: SendArgs = match parse_args(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        // Typed text usually lands in a shell, so check it as a script.
+        let script = vec![
+            cortex_utils_pty::get_default_shell(),
+            "-c".to_string(),
+            typed_text(&args.keys),
+        ];
+        if let Some(denied) = managed_denial(&script).await {
+            return Ok(denied);
+        }
+
+        match global_manager()
+            .send_keys(&args.terminal_id, &args.keys)
+            .await
+        {
+            Ok(()) => Ok(ToolResult::success(format!(
+                "Sent keys to terminal {}. Use TerminalWait to wait for the response.",
+                args.terminal_id
+            ))),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+/// Tool that waits for output matching a pattern, or for quiet, in a PTY
+/// terminal.
+pub struct TerminalWaitTool;
+
+#[derive(Debug, Deserialize)]
+struct WaitArgs {
+    terminal_id: String,
+    pattern: Option<String>,
+    idle_ms: Option<u64>,
+    timeout_ms: Option<u64>,
+}
+
+impl TerminalWaitTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalWaitTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerminalWaitTool {
+    fn name(&self) -> &str {
+        "TerminalWait"
+    }
+
+    async fn execute(&self, arguments: Value, _context: &ToolContext) -> Result<ToolResult> {
+        let args: WaitArgs = match parse_args(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let condition = match (&args.pattern, args.idle_ms) {
+            (Some(_), Some(_)) => {
+                return Ok(ToolResult::error(
+                    "Specify either pattern or idle_ms, not both.",
+                ));
+            }
+            (Some(pattern), None) => match Regex::new(pattern) {
+                Ok(regex) => WaitFor::Pattern(regex),
+                Err(e) => return Ok(ToolResult::error(format!("Invalid pattern: {e}"))),
+            },
+            (None, idle_ms) => {
+                WaitFor::Idle(Duration::from_millis(idle_ms.unwrap_or(DEFAULT_IDLE_MS)))
+            }
+        };
+        let timeout = Duration::from_millis(
+            args.timeout_ms
+                .unwrap_or(DEFAULT_WAIT_TIMEOUT_MS)
+                .min(MAX_WAIT_TIMEOUT_MS),
+        );
+
+        let outcome = match global_manager()
+            .wait_for(&args.terminal_id, &condition, timeout)
+            .await
+        {
+            Ok(outcome) => outcome,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+        let preface = match &outcome {
+            WaitOutcome::Matched { text } => format!("Matched: {text:?}"),
+            WaitOutcome::Idle => "Terminal is idle.".to_string(),
+            WaitOutcome::Exited => "The program exited.".to_string(),
+            WaitOutcome::TimedOut => format!("Timed out after {} ms.", timeout.as_millis()),
+        };
+        Ok(screen_result(&args.terminal_id, Some(preface)).await)
+    }
+}
+
+/// Tool that reads the rendered screen, or recent scrollback, of a PTY
+/// terminal.
+pub struct TerminalScreenTool;
+
+#[derive(Debug, Deserialize)]
+struct ScreenArgs {
+    terminal_id: String,
+    scrollback: Option<usize>,
+}
+
+impl TerminalScreenTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalScreenTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerminalScreenTool {
+    fn name(&self) -> &str {
+        "TerminalScreen"
+    }
+
+    async fn execute(&self, arguments: Value, _context: &ToolContext) -> Result<ToolResult> {
+        let args: ScreenArgs = match parse_args(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let Some(lines) = args.scrollback else {
+            return Ok(screen_result(&args.terminal_id, None).await);
+        };
+        match global_manager()
+            .get_logs(&args.terminal_id, Some(lines))
+            .await
+        {
+            Ok(logs) => {
+                let text: Vec<_> = logs.into_iter().map(|l| l.content).collect();
+                Ok(ToolResult::success(format!(
+                    "Last {} lines of terminal {}:\n```\n{}\n```",
+                    text.len(),
+                    args.terminal_id,
+                    text.join("\n")
+                )))
+            }
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+/// Tool that ends a PTY terminal.
+pub struct TerminalCloseTool;
+
+#[derive(Debug, Deserialize)]
+struct CloseArgs {
+    terminal_id: String,
+}
+
+impl TerminalCloseTool {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Default for TerminalCloseTool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait]
+impl ToolHandler for TerminalCloseTool {
+    fn name(&self) -> &str {
+        "TerminalClose"
+    }
+
+    async fn execute(&self, arguments: Value, _context: &ToolContext) -> Result<ToolResult> {
+        let args: CloseArgs = match parse_args(arguments) {
+            Ok(a) => a,
+            Err(e) => return Ok(ToolResult::error(e)),
+        };
+
+        let manager = global_manager();
+        let result = match manager.kill_terminal(&args.terminal_id).await {
+            Ok(()) => manager.remove_terminal(&args.terminal_id).await,
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => Ok(ToolResult::success(format!(
+                "Closed terminal {}.",
+                args.terminal_id
+            ))),
+            Err(e) => Ok(ToolResult::error(e)),
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_terminal_tools_round_trip() {
+        let context = ToolContext::new(std::env::temp_dir());
+        let opened = TerminalOpenTool::new()
+            .execute(
+                json!({ "command": ["sh", "-c", "read line; echo \"got $line\"; sleep 5"] }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(opened.success, "{}", opened.output);
+        let data = opened.metadata.and_then(|m| m.data).unwrap();
+        let terminal_id = data["terminal_id"].as_str().unwrap().to_string();
+
+        let sent = TerminalSendTool::new()
+            .execute(
+                json!({ "terminal_id": terminal_id, "keys": "ping<Enter>" }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(sent.success);
+
+        let waited = TerminalWaitTool::new()
+            .execute(
+                json!({ "terminal_id": terminal_id, "pattern": "got \\w+", "timeout_ms": 10000 }),
+                &context,
+            )
+            .await
+            .unwrap();
+        assert!(waited.output.starts_with("Matched: \"got ping\""));
+        assert!(waited.output.contains("ping\ngot ping"));
+
+        let closed = TerminalCloseTool::new()
+            .execute(json!({ "terminal_id": terminal_id }), &context)
+            .await
+            .unwrap();
+        assert!(closed.success);
+        let gone = TerminalScreenTool::new()
+            .execute(json!({ "terminal_id": terminal_id }), &context)
+            .await
+            .unwrap();
+        assert!(!gone.success);
+    }
+}
@@ -0,0 +1,301 @@
+//! Tamper-evident audit log of agent actions.
+//!
+//! Approvals, denials, commands run, files written, network fetches and MCP
+//! calls are appended to `~/.cortex/audit/audit.jsonl`, one JSON entry per
+//! line. Each entry stores the hash of the entry before it, and its own hash
+//! covers its content and that link, so editing, reordering or removing an
+//! entry breaks the chain from that point on. Cutting entries off the end
+//! leaves a valid chain: keep the head hash reported by `cortex audit verify`
+//! elsewhere to detect that as well.
+//!
+//! Entries are fed by the permission manager, the tool router and the
+//! command runner. Recording never fails the action being recorded; errors
+//! are logged instead.
+
+mod query;
+mod store;
+
+pub use query::{AuditQuery, ExportFormat, export};
+pub use store::{AuditLog, ChainBreak, Verification};
+
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::OnceLock;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::config::find_cortex_home;
+
+/// `prev_hash` of the first entry in a log.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// Kind of action recorded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    /// An action was approved, by the user or a stored permission.
+    Approval,
+    /// An action was refused.
+    Denial,
+    /// A command was run.
+    Command,
+    /// A file was written.
+    FileWrite,
+    /// A URL was fetched or the web was searched.
+    NetworkFetch,
+    /// An MCP tool was called.
+    McpCall,
+}
+
+impl AuditKind {
+    /// All kinds, in declaration order.
+    pub const ALL: [AuditKind; 6] = [
+        Self::Approval,
+        Self::Denial,
+        Self::Command,
+        Self::FileWrite,
+        Self::NetworkFetch,
+        Self::McpCall,
+    ];
+
+    /// Name as written in the log.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Approval => "approval",
+            Self::Denial => "denial",
+            Self::Command => "command",
+            Self::FileWrite => "file_write",
+            Self::NetworkFetch => "network_fetch",
+            Self::McpCall => "mcp_call",
+        }
+    }
+}
+
+impl fmt::Display for AuditKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let normalized = s.to_lowercase().replace('-', "_");
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == normalized)
+            .ok_or_else(|| {
+                let names: Vec<_> = Self::ALL.iter().map(AuditKind::as_str).collect();
+                format!(
+                    "Unknown audit kind '{s}' (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// An action to record.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AuditEvent {
+    /// Kind of action.
+    pub kind: AuditKind,
+    /// Component or tool that acted, e.g. `Execute` or `permission`.
+    pub source: String,
+    /// What was acted on: a command line, file path, URL or MCP tool name.
+    pub target: String,
+    /// How it turned out, e.g. `allowed`, `exit 0` or `error: ...`.
+    pub outcome: String,
+    /// Additional structured details.
+    pub details: Value,
+}
+
+impl AuditEvent {
+    /// Create an event without details.
+    pub fn new(
+        kind: AuditKind,
+        source: impl Into<String>,
+        target: impl Into<String>,
+        outcome: impl Into<String>,
+    ) -> Self {
+        Self {
+            kind,
+            source: source.into(),
+            target: target.into(),
+            outcome: outcome.into(),
+            details: Value::Null,
+        }
+    }
+
+    /// Attach structured details.
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = details;
+        self
+    }
+}
+
+/// A recorded entry, as stored on one line of the log.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AuditEntry {
+    /// Position in the log, starting at 0.
+    pub seq: u64,
+    /// When the entry was recorded.
+    pub timestamp: DateTime<Utc>,
+    /// Session the action belongs to, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub session: Option<String>,
+    /// Kind of action.
+    pub kind: AuditKind,
+    /// Component or tool that acted.
+    pub source: String,
+    /// What was acted on.
+    pub target: String,
+    /// How it turned out.
+    pub outcome: String,
+    /// Additional structured details.
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub details: Value,
+    /// Hash of the previous entry, [`GENESIS_HASH`] for the first one.
+    pub prev_hash: String,
+    /// SHA-256 over this entry with an empty `hash` field.
+    pub hash: String,
+}
+
+impl AuditEntry {
+    /// Build the entry following one with `prev_hash` at position `seq`.
+    pub fn chained(
+        seq: u64,
+        prev_hash: impl Into<String>,
+        session: Option<String>,
+        event: AuditEvent,
+    ) -> Self {
+        let mut entry = Self {
+            seq,
+            timestamp: Utc::now(),
+            session,
+            kind: event.kind,
+            source: event.source,
+            target: event.target,
+            outcome: event.outcome,
+            details: event.details,
+            prev_hash: prev_hash.into(),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+
+    /// Hash the entry's content, including its link to the previous entry.
+    pub fn compute_hash(&self) -> String {
+        let mut unsigned = self.clone();
+        unsigned.hash.clear();
+        // Serializing a struct with string, integer and JSON values cannot fail.
+        let bytes = serde_json::to_vec(&unsigned).unwrap_or_default();
+        Sha256::digest(&bytes)
+            .iter()
+            .map(|b| format!("{b:02x}"))
+            .collect()
+    }
+}
+
+static GLOBAL_LOG: OnceLock<Option<AuditLog>> = OnceLock::new();
+
+tokio::task_local! {
+    static SESSION: String;
+}
+
+/// The audit log under Cortex home, or `None` if it cannot be located.
+pub fn global() -> Option<&'static AuditLog> {
+    GLOBAL_LOG
+        .get_or_init(|| {
+            // Unit tests run the recording paths; keep them out of the
+            // developer's own audit log.
+            if cfg!(test) {
+                return None;
+            }
+            match find_cortex_home() {
+                Ok(home) => Some(AuditLog::new(AuditLog::default_path(&home))),
+                Err(e) => {
+                    warn!("Audit log disabled, cannot locate Cortex home: {}", e);
+                    None
+                }
+            }
+        })
+        .as_ref()
+}
+
+/// Run `future` with actions it records attributed to `session`.
+pub async fn in_session<F: Future>(session: &str, future: F) -> F::Output {
+    SESSION.scope(session.to_string(), future).await
+}
+
+/// Append an event to the global audit log.
+pub async fn record(event: AuditEvent) {
+    let Some(log) = global() else {
+        return;
+    };
+    let session = SESSION
+        .try_with(String::clone)
+        .ok()
+        .filter(|s| !s.is_empty());
+    let kind = event.kind;
+
+    match tokio::task::spawn_blocking(move || log.append(session, event)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => warn!("Failed to record {} in audit log: {}", kind, e),
+        Err(e) => warn!("Failed to record {} in audit log: {}", kind, e),
+    }
+}
+
+/// Shorten free text, such as error messages, for an entry's outcome.
+pub fn summarize(text: &str) -> String {
+    const MAX_CHARS: usize = 200;
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.chars().count() > MAX_CHARS {
+        let truncated: String = first_line.chars().take(MAX_CHARS).collect();
+        format!("{truncated}...")
+    } else {
+        first_line.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kind_round_trip() {
+        for kind in AuditKind::ALL {
+            assert_eq!(kind.as_str().parse::<AuditKind>().unwrap(), kind);
+            let json = serde_json::to_string(&kind).unwrap();
+            assert_eq!(json, format!("\"{kind}\""));
+        }
+        assert_eq!(
+            "file-write".parse::<AuditKind>().unwrap(),
+            AuditKind::FileWrite
+        );
+        assert!("launch".parse::<AuditKind>().is_err());
+    }
+
+    #[test]
+    fn test_hash_covers_content_and_link() {
+        let event = AuditEvent::new(AuditKind::Command, "Execute", "ls", "exit 0");
+        let entry = AuditEntry::chained(0, GENESIS_HASH, None, event);
+        assert_eq!(entry.hash, entry.compute_hash());
+        assert_eq!(entry.hash.len(), 64);
+
+        let mut edited = entry.clone();
+        edited.target = "rm -rf /".to_string();
+        assert_ne!(edited.compute_hash(), entry.hash);
+
+        let mut relinked = entry.clone();
+        relinked.prev_hash = "f".repeat(64);
+        assert_ne!(relinked.compute_hash(), entry.hash);
+    }
+}
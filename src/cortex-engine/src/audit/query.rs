@@ -0,0 +1,217 @@
+//! Filtering and exporting audit entries.
+
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+
+use super::{AuditEntry, AuditKind};
+use crate::error::Result;
+use crate::output::CsvBuilder;
+
+/// Filter over audit entries. Unset fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct AuditQuery {
+    /// Kinds to include; empty includes all.
+    pub kinds: Vec<AuditKind>,
+    /// Only entries of this session.
+    pub session: Option<String>,
+    /// Only entries recorded at or after this time.
+    pub since: Option<DateTime<Utc>>,
+    /// Only entries recorded before this time.
+    pub until: Option<DateTime<Utc>>,
+    /// Only entries whose source, target or outcome contains this text.
+    pub text: Option<String>,
+    /// Keep only the most recent matching entries.
+    pub limit: Option<usize>,
+}
+
+impl AuditQuery {
+    /// Whether an entry passes the filter, ignoring `limit`.
+    pub fn matches(&self, entry: &AuditEntry) -> bool {
+        (self.kinds.is_empty() || self.kinds.contains(&entry.kind))
+            && self
+                .session
+                .as_ref()
+                .is_none_or(|s| entry.session.as_ref() == Some(s))
+            && self.since.is_none_or(|since| entry.timestamp >= since)
+            && self.until.is_none_or(|until| entry.timestamp < until)
+            && self.text.as_ref().is_none_or(|text| {
+                entry.source.contains(text.as_str())
+                    || entry.target.contains(text.as_str())
+                    || entry.outcome.contains(text.as_str())
+            })
+    }
+
+    /// Select the matching entries, oldest first.
+    pub fn apply(&self, entries: Vec<AuditEntry>) -> Vec<AuditEntry> {
+        let mut matching: Vec<_> = entries.into_iter().filter(|e| self.matches(e)).collect();
+        if let Some(limit) = self.limit {
+            let excess = matching.len().saturating_sub(limit);
+            matching.drain(..excess);
+        }
+        matching
+    }
+}
+
+/// Format for exported entries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    /// One JSON entry per line, exactly as stored; the export verifies
+    /// like the log itself when no filter is applied.
+    #[default]
+    Jsonl,
+    /// A pretty-printed JSON array.
+    Json,
+    /// CSV with one column per field; details are JSON-encoded.
+    Csv,
+}
+
+impl FromStr for ExportFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "jsonl" | "ndjson" => Ok(Self::Jsonl),
+            "json" => Ok(Self::Json),
+            "csv" => Ok(Self::Csv),
+            _ => Err(format!(
+                "Unknown export format '{s}' (expected jsonl, json or csv)"
+            )),
+        }
+    }
+}
+
+/// Render entries in an export format.
+pub fn export(entries: &[AuditEntry], format: ExportFormat) -> Result<String> {
+    Ok(match format {
+        ExportFormat::Jsonl => {
+            let mut output = String::new();
+            for entry in entries {
+                output.push_str(&serde_json::to_string(entry)?);
+                output.push('\n');
+            }
+            output
+        }
+        ExportFormat::Json => {
+            let mut output = serde_json::to_string_pretty(entries)?;
+            output.push('\n');
+            output
+        }
+        ExportFormat::Csv => {
+            let mut csv = CsvBuilder::new().headers(vec![
+                "seq",
+                "timestamp",
+                "session",
+                "kind",
+                "source",
+                "target",
+                "outcome",
+                "details",
+                "prev_hash",
+                "hash",
+            ]);
+            for entry in entries {
+                let details = if entry.details.is_null() {
+                    String::new()
+                } else {
+                    entry.details.to_string()
+                };
+                csv = csv.row(vec![
+                    entry.seq.to_string(),
+                    entry.timestamp.to_rfc3339(),
+                    entry.session.clone().unwrap_or_default(),
+                    entry.kind.to_string(),
+                    entry.source.clone(),
+                    entry.target.clone(),
+                    entry.outcome.clone(),
+                    details,
+                    entry.prev_hash.clone(),
+                    entry.hash.clone(),
+                ]);
+            }
+            csv.build()
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::{AuditEvent, GENESIS_HASH};
+
+    fn entries() -> Vec<AuditEntry> {
+        let events = [
+            (AuditKind::Command, "cargo build"),
+            (AuditKind::FileWrite, "src/main.rs"),
+            (AuditKind::Command, "cargo test"),
+            (AuditKind::NetworkFetch, "https://docs.rs"),
+        ];
+        let mut prev_hash = GENESIS_HASH.to_string();
+        events
+            .into_iter()
+            .enumerate()
+            .map(|(seq, (kind, target))| {
+                let session = (seq % 2 == 0).then(|| "s1".to_string());
+                let event = AuditEvent::new(kind, "tool", target, "ok");
+                let entry = AuditEntry::chained(seq as u64, &prev_hash, session, event);
+                prev_hash = entry.hash.clone();
+                entry
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_query_filters_and_keeps_most_recent() {
+        let query = AuditQuery {
+            kinds: vec![AuditKind::Command],
+            ..Default::default()
+        };
+        let targets: Vec<_> = query
+            .apply(entries())
+            .into_iter()
+            .map(|e| e.target)
+            .collect();
+        assert_eq!(targets, vec!["cargo build", "cargo test"]);
+
+        let query = AuditQuery {
+            session: Some("s1".to_string()),
+            text: Some("cargo".to_string()),
+            limit: Some(1),
+            ..Default::default()
+        };
+        let matching = query.apply(entries());
+        assert_eq!(matching.len(), 1);
+        assert_eq!(matching[0].target, "cargo test");
+
+        let query = AuditQuery {
+            until: Some(Utc::now() - chrono::Duration::hours(1)),
+            ..Default::default()
+        };
+        assert!(query.apply(entries()).is_empty());
+    }
+
+    #[test]
+    fn test_export_formats() {
+        let entries = entries();
+
+        let jsonl = export(&entries, ExportFormat::Jsonl).unwrap();
+        let parsed: Vec<AuditEntry> = jsonl
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(parsed, entries);
+
+        let json = export(&entries, ExportFormat::Json).unwrap();
+        let parsed: Vec<AuditEntry> = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, entries);
+
+        let csv = export(&entries[..1], ExportFormat::Csv).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            "seq,timestamp,session,kind,source,target,outcome,details,prev_hash,hash"
+        );
+        assert!(lines.next().unwrap().starts_with("0,"));
+        assert!(csv.contains(",s1,command,tool,cargo build,ok,,"));
+    }
+}
@@ -0,0 +1,311 @@
+//! Audit log file.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use cortex_common::{LockConfig, LockMode, acquire_lock};
+use serde::Serialize;
+
+use super::{AuditEntry, AuditEvent, GENESIS_HASH};
+use crate::error::{CortexError, Result};
+
+/// An append-only, hash-chained audit log file.
+#[derive(Debug, Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+}
+
+/// Where the chain of a log first breaks.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ChainBreak {
+    /// 1-based line number of the offending entry.
+    pub line: usize,
+    /// Sequence number of the entry, if it could be parsed.
+    pub seq: Option<u64>,
+    /// What is wrong with it.
+    pub reason: String,
+}
+
+/// Result of verifying a log.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Verification {
+    /// Path of the log.
+    pub path: PathBuf,
+    /// Number of entries verified before the first break, or in total.
+    pub entries: u64,
+    /// Hash of the last intact entry.
+    pub head: Option<String>,
+    /// The first break in the chain, if any.
+    pub broken: Option<ChainBreak>,
+}
+
+impl Verification {
+    /// Whether every entry is intact and linked to the one before it.
+    pub fn is_intact(&self) -> bool {
+        self.broken.is_none()
+    }
+}
+
+impl AuditLog {
+    /// Open the log at `path`. The file is created on first append.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Default location of the log under Cortex home.
+    pub fn default_path(cortex_home: &Path) -> PathBuf {
+        cortex_home.join("audit").join("audit.jsonl")
+    }
+
+    /// Path of the log file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an event, chained to the current last entry.
+    ///
+    /// Holds an exclusive lock on the file while appending, so several
+    /// Cortex processes can share a log.
+    pub fn append(&self, session: Option<String>, event: AuditEvent) -> Result<AuditEntry> {
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let mut guard = acquire_lock(&self.path, LockMode::Exclusive, &LockConfig::default())
+            .map_err(|e| CortexError::internal(format!("Failed to lock audit log: {e}")))?;
+        let file = guard.file_mut();
+
+        let (seq, prev_hash) = match last_line(file)? {
+            None => (0, GENESIS_HASH.to_string()),
+            Some(line) => {
+                let last: AuditEntry = serde_json::from_str(&line).map_err(|e| {
+                    CortexError::internal(format!(
+                        "Last entry of audit log {} is unreadable ({e}); run `cortex audit verify`",
+                        self.path.display()
+                    ))
+                })?;
+                (last.seq + 1, last.hash)
+            }
+        };
+
+        let entry = AuditEntry::chained(seq, prev_hash, session, event);
+        let mut line = serde_json::to_string(&entry)?;
+        line.push('\n');
+        file.seek(SeekFrom::End(0))?;
+        file.write_all(line.as_bytes())?;
+        file.sync_data()?;
+        Ok(entry)
+    }
+
+    /// Read every entry, oldest first. A missing log has no entries.
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let Some(reader) = self.reader()? else {
+            return Ok(Vec::new());
+        };
+        let mut entries = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|e| {
+                CortexError::Serialization(format!(
+                    "{}:{}: invalid audit entry: {e}",
+                    self.path.display(),
+                    index + 1
+                ))
+            })?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+
+    /// Walk the chain and report the first entry that does not hold up.
+    pub fn verify(&self) -> Result<Verification> {
+        let mut verification = Verification {
+            path: self.path.clone(),
+            entries: 0,
+            head: None,
+            broken: None,
+        };
+        let Some(reader) = self.reader()? else {
+            return Ok(verification);
+        };
+
+        let mut prev_hash = GENESIS_HASH.to_string();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let expected_seq = verification.entries;
+            let broken = |seq, reason: String| ChainBreak {
+                line: index + 1,
+                seq,
+                reason,
+            };
+
+            let entry: AuditEntry = match serde_json::from_str(&line) {
+                Ok(entry) => entry,
+                Err(e) => {
+                    verification.broken = Some(broken(None, format!("unreadable entry: {e}")));
+                    break;
+                }
+            };
+            let problem = if entry.seq != expected_seq {
+                Some(format!("expected seq {expected_seq}, found {}", entry.seq))
+            } else if entry.prev_hash != prev_hash {
+                Some("prev_hash does not match the previous entry".to_string())
+            } else if entry.hash != entry.compute_hash() {
+                Some("hash does not match the entry's content".to_string())
+            } else {
+                None
+            };
+            if let Some(reason) = problem {
+                verification.broken = Some(broken(Some(entry.seq), reason));
+                break;
+            }
+
+            verification.entries += 1;
+            prev_hash = entry.hash;
+            verification.head = Some(prev_hash.clone());
+        }
+        Ok(verification)
+    }
+
+    fn reader(&self) -> Result<Option<BufReader<File>>> {
+        match File::open(&self.path) {
+            Ok(file) => Ok(Some(BufReader::new(file))),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Read the last non-empty line of a file without reading all of it.
+fn last_line(file: &mut File) -> Result<Option<String>> {
+    const CHUNK: u64 = 8 * 1024;
+
+    let len = file.seek(SeekFrom::End(0))?;
+    let mut start = len;
+    let mut tail = Vec::new();
+    loop {
+        let trimmed_len = tail.iter().rposition(|b| *b != b'\n').map_or(0, |i| i + 1);
+        if let Some(newline) = tail[..trimmed_len].iter().rposition(|b| *b == b'\n') {
+            tail.truncate(trimmed_len);
+            tail.drain(..=newline);
+            break;
+        }
+        if start == 0 {
+            tail.truncate(trimmed_len);
+            break;
+        }
+
+        let read_from = start.saturating_sub(CHUNK);
+        let mut chunk = vec![0; (start - read_from) as usize];
+        file.seek(SeekFrom::Start(read_from))?;
+        file.read_exact(&mut chunk)?;
+        chunk.extend_from_slice(&tail);
+        tail = chunk;
+        start = read_from;
+    }
+
+    if tail.is_empty() {
+        return Ok(None);
+    }
+    String::from_utf8(tail)
+        .map(Some)
+        .map_err(|e| CortexError::Serialization(format!("audit log is not UTF-8: {e}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audit::AuditKind;
+
+    fn event(target: &str) -> AuditEvent {
+        AuditEvent::new(AuditKind::Command, "Execute", target, "exit 0")
+    }
+
+    #[test]
+    fn test_append_chains_entries() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = AuditLog::new(dir.path().join("audit/audit.jsonl"));
+
+        let first = log.append(Some("s1".to_string()), event("ls")).unwrap();
+        let second = log.append(None, event("pwd")).unwrap();
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.seq, 1);
+        assert_eq!(second.prev_hash, first.hash);
+
+        let verification = log.verify().unwrap();
+        assert!(verification.is_intact());
+        assert_eq!(verification.entries, 2);
+        assert_eq!(verification.head.as_ref(), Some(&second.hash));
+        assert_eq!(log.entries().unwrap(), vec![first, second.clone()]);
+
+        // Blank lines, e.g. from an editor, are not entries.
+        let path = dir.path().join("audit/audit.jsonl");
+        let content = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(&path, content.replace('\n', "\n\n")).unwrap();
+        let verification = log.verify().unwrap();
+        assert!(verification.is_intact());
+        assert_eq!(verification.entries, 2);
+        assert_eq!(verification.head.as_ref(), Some(&second.hash));
+    }
+
+    #[test]
+    fn test_verify_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::new(&path);
+        for target in ["ls", "curl https://example.com", "pwd"] {
+            log.append(None, event(target)).unwrap();
+        }
+        let original = std::fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = original.lines().collect();
+
+        // Editing an entry invalidates its hash.
+        std::fs::write(&path, original.replace("curl", "echo")).unwrap();
+        let broken = log.verify().unwrap().broken.unwrap();
+        assert_eq!((broken.line, broken.seq), (2, Some(1)));
+        assert!(broken.reason.contains("content"));
+
+        // Removing an entry breaks the sequence.
+        std::fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        let verification = log.verify().unwrap();
+        assert_eq!(verification.entries, 1);
+        assert!(
+            verification
+                .broken
+                .unwrap()
+                .reason
+                .contains("expected seq 1")
+        );
+
+        // Rewriting an entry consistently still breaks the next link.
+        let mut forged: AuditEntry = serde_json::from_str(lines[1]).unwrap();
+        forged.target = "echo".to_string();
+        forged.hash = forged.compute_hash();
+        let forged = serde_json::to_string(&forged).unwrap();
+        std::fs::write(&path, format!("{}\n{forged}\n{}\n", lines[0], lines[2])).unwrap();
+        let broken = log.verify().unwrap().broken.unwrap();
+        assert_eq!(broken.line, 3);
+        assert!(broken.reason.contains("prev_hash"));
+    }
+
+    #[test]
+    fn test_last_line_spans_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("lines");
+        let long = "x".repeat(20_000);
+        std::fs::write(&path, format!("first\n{long}\n\n")).unwrap();
+
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(last_line(&mut file).unwrap(), Some(long));
+
+        std::fs::write(&path, "").unwrap();
+        let mut file = File::open(&path).unwrap();
+        assert_eq!(last_line(&mut file).unwrap(), None);
+    }
+}
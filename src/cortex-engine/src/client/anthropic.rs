@@ -0,0 +1,745 @@
+//! Anthropic Messages Client
+//!
+//! Direct client for the Anthropic Messages API with:
+//! - Streaming SSE responses
+//! - `tool_use` blocks assembled from `input_json_delta` fragments
+//! - Extended thinking surfaced as reasoning events
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{
+    CompletionRequest, CompletionResponse, ContentPart, FinishReason, FunctionCall, Message,
+    MessageContent, MessageRole, ModelCapabilities, ModelClient, ResponseEvent, ResponseStream,
+    TokenUsage, ToolCall, ToolCallEvent, collect_stream, error_from_response,
+};
+use crate::error::{CortexError, Result};
+
+/// Anthropic API version header value.
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Default `max_tokens` when the request does not specify one (the API requires it).
+const DEFAULT_MAX_TOKENS: u32 = 8192;
+
+/// Timeout in seconds for receiving individual SSE chunks during streaming.
+const CHUNK_TIMEOUT_SECS: u64 = 60;
+
+/// Client for the Anthropic Messages API.
+pub struct AnthropicClient {
+    client: Client,
+    provider: String,
+    base_url: String,
+    model: String,
+    capabilities: ModelCapabilities,
+    api_key: Option<String>,
+    headers: HashMap<String, String>,
+}
+
+impl AnthropicClient {
+    /// Create a new client for `base_url` (e.g. "https://api.anthropic.com/v1").
+    pub fn new(provider: impl Into<String>, model: impl Into<String>, base_url: &str) -> Self {
+        let client = crate::api_client::create_streaming_client().unwrap_or_else(|e| {
+            tracing::warn!("Failed to create streaming client: {}, using fallback", e);
+            Client::new()
+        });
+
+        Self {
+            client,
+            provider: provider.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.into(),
+            capabilities: ModelCapabilities {
+                vision: true,
+                tools: true,
+                reasoning: true,
+                context_window: 200_000,
+                max_output_tokens: Some(DEFAULT_MAX_TOKENS),
+            },
+            api_key: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Set the API key sent as `x-api-key`.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Set additional headers sent with every request.
+    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Override the model capabilities.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Use a custom HTTP client (e.g. with a provider-specific timeout).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Build the Messages API request body.
+    fn build_request(&self, request: &CompletionRequest) -> Value {
+        let (system, messages) = convert_messages(&request.messages);
+
+        let model = if request.model.is_empty() {
+            &self.model
+        } else {
+            &request.model
+        };
+
+        let max_tokens = request
+            .max_tokens
+            .or(self.capabilities.max_output_tokens)
+            .unwrap_or(DEFAULT_MAX_TOKENS);
+
+        let mut body = json!({
+            "model": model,
+            "messages": messages,
+            "max_tokens": max_tokens,
+            "stream": true,
+        });
+
+        if !system.is_empty() {
+            body["system"] = json!(system);
+        }
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = Value::Array(
+                request
+                    .tools
+                    .iter()
+                    .map(|t| {
+                        json!({
+                            "name": t.name(),
+                            "description": t.description(),
+                            "input_schema": t.parameters(),
+                        })
+                    })
+                    .collect(),
+            );
+        }
+
+        body
+    }
+}
+
+/// Convert internal messages to Anthropic's `(system, messages)` pair.
+///
+/// System messages are hoisted into the top-level `system` field, tool results
+/// become `tool_result` blocks on a user turn, and consecutive messages with the
+/// same role are merged since the API requires alternating turns.
+fn convert_messages(messages: &[Message]) -> (String, Vec<Value>) {
+    let mut system = Vec::new();
+    let mut out: Vec<(&'static str, Vec<Value>)> = Vec::new();
+
+    for message in messages {
+        let (role, blocks) = match message.role {
+            MessageRole::System => {
+                if let Some(text) = message.content.as_text() {
+                    system.push(text.to_string());
+                }
+                continue;
+            }
+            MessageRole::Tool => {
+                let (tool_use_id, content) = match &message.content {
+                    MessageContent::ToolResult {
+                        tool_call_id,
+                        content,
+                    } => (tool_call_id.clone(), content.clone()),
+                    other => (
+                        message.tool_call_id.clone().unwrap_or_default(),
+                        other.as_text().unwrap_or_default().to_string(),
+                    ),
+                };
+                (
+                    "user",
+                    vec![json!({
+                        "type": "tool_result",
+                        "tool_use_id": tool_use_id,
+                        "content": content,
+                    })],
+                )
+            }
+            MessageRole::User => ("user", content_blocks(&message.content)),
+            MessageRole::Assistant => {
+                let mut blocks = content_blocks(&message.content);
+                for call in message.tool_calls.iter().flatten() {
+                    blocks.push(json!({
+                        "type": "tool_use",
+                        "id": call.id,
+                        "name": call.function.name,
+                        "input": parse_arguments(&call.function.arguments),
+                    }));
+                }
+                if let MessageContent::ToolCalls(calls) = &message.content {
+                    for call in calls {
+                        blocks.push(json!({
+                            "type": "tool_use",
+                            "id": call.id,
+                            "name": call.name,
+                            "input": parse_arguments(&call.arguments),
+                        }));
+                    }
+                }
+                ("assistant", blocks)
+            }
+        };
+
+        if blocks.is_empty() {
+            continue;
+        }
+        match out.last_mut() {
+            Some((last_role, last_blocks)) if *last_role == role => last_blocks.extend(blocks),
+            _ => out.push((role, blocks)),
+        }
+    }
+
+    let messages = out
+        .into_iter()
+        .map(|(role, content)| json!({ "role": role, "content": content }))
+        .collect();
+
+    (system.join("\n\n"), messages)
+}
+
+/// Convert message content to Anthropic content blocks.
+fn content_blocks(content: &MessageContent) -> Vec<Value> {
+    match content {
+        MessageContent::Text(text) if text.is_empty() => Vec::new(),
+        MessageContent::Text(text) => vec![json!({ "type": "text", "text": text })],
+        MessageContent::Parts(parts) => parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text {
+                    text,
+                    cache_control,
+                } => {
+                    let mut block = json!({ "type": "text", "text": text });
+                    if let Some(cache_control) = cache_control {
+                        block["cache_control"] = json!(cache_control);
+                    }
+                    Some(block)
+                }
+                ContentPart::ImageUrl { image_url } => image_block(&image_url.url),
+                ContentPart::Image { url, .. } => image_block(url),
+                ContentPart::Document {
+                    data, mime_type, ..
+                } => Some(json!({
+                    "type": "document",
+                    "source": { "type": "base64", "media_type": mime_type, "data": data },
+                })),
+            })
+            .collect(),
+        MessageContent::ToolResult { content, .. } => {
+            vec![json!({ "type": "text", "text": content })]
+        }
+        MessageContent::ToolCalls(_) => Vec::new(),
+    }
+}
+
+/// Build an image block from a data URL or a remote URL.
+fn image_block(url: &str) -> Option<Value> {
+    if let Some(rest) = url.strip_prefix("data:") {
+        let (media_type, data) = rest.split_once(";base64,")?;
+        Some(json!({
+            "type": "image",
+            "source": { "type": "base64", "media_type": media_type, "data": data },
+        }))
+    } else {
+        Some(json!({ "type": "image", "source": { "type": "url", "url": url } }))
+    }
+}
+
+/// Parse tool call arguments, falling back to an empty object.
+fn parse_arguments(arguments: &str) -> Value {
+    serde_json::from_str(arguments).unwrap_or_else(|_| json!({}))
+}
+
+// =============================================================================
+// MESSAGES STREAM TYPES
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum StreamEvent {
+    MessageStart {
+        message: MessageStart,
+    },
+    ContentBlockStart {
+        index: usize,
+        content_block: ContentBlock,
+    },
+    ContentBlockDelta {
+        index: usize,
+        delta: BlockDelta,
+    },
+    ContentBlockStop {
+        index: usize,
+    },
+    MessageDelta {
+        #[serde(default)]
+        delta: MessageDeltaBody,
+        #[serde(default)]
+        usage: Option<Usage>,
+    },
+    MessageStop,
+    Error {
+        error: ApiError,
+    },
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Deserialize)]
+struct MessageStart {
+    #[serde(default)]
+    usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ContentBlock {
+    ToolUse {
+        id: String,
+        name: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum BlockDelta {
+    TextDelta {
+        text: String,
+    },
+    InputJsonDelta {
+        partial_json: String,
+    },
+    ThinkingDelta {
+        thinking: String,
+    },
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct MessageDeltaBody {
+    #[serde(default)]
+    stop_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Usage {
+    #[serde(default)]
+    input_tokens: Option<i64>,
+    #[serde(default)]
+    output_tokens: Option<i64>,
+    #[serde(default)]
+    cache_creation_input_tokens: Option<i64>,
+    #[serde(default)]
+    cache_read_input_tokens: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    #[serde(rename = "type", default)]
+    error_type: String,
+    message: String,
+}
+
+/// Accumulates Messages API stream events into text, tool calls and usage.
+#[derive(Debug, Default)]
+pub(crate) struct MessagesStreamState {
+    text: String,
+    tool_uses: BTreeMap<usize, PartialToolUse>,
+    tool_calls: Vec<ToolCall>,
+    usage: TokenUsage,
+    finish_reason: Option<FinishReason>,
+    done: bool,
+}
+
+#[derive(Debug, Default)]
+struct PartialToolUse {
+    id: String,
+    name: String,
+    input: String,
+}
+
+impl MessagesStreamState {
+    /// Apply one `data:` payload and return the events it produces.
+    pub(crate) fn apply(&mut self, data: &str) -> Vec<ResponseEvent> {
+        let event = match serde_json::from_str::<StreamEvent>(data) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::debug!("Failed to parse Anthropic event: {} - {}", e, data);
+                return Vec::new();
+            }
+        };
+
+        match event {
+            StreamEvent::MessageStart { message } => {
+                if let Some(usage) = message.usage {
+                    self.apply_usage(&usage);
+                }
+                Vec::new()
+            }
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlock::ToolUse { id, name },
+            } => {
+                self.tool_uses.insert(
+                    index,
+                    PartialToolUse {
+                        id,
+                        name,
+                        input: String::new(),
+                    },
+                );
+                Vec::new()
+            }
+            StreamEvent::ContentBlockStart { .. } => Vec::new(),
+            StreamEvent::ContentBlockDelta { index, delta } => match delta {
+                BlockDelta::TextDelta { text } => {
+                    self.text.push_str(&text);
+                    vec![ResponseEvent::Delta(text)]
+                }
+                BlockDelta::ThinkingDelta { thinking } => vec![ResponseEvent::Reasoning(thinking)],
+                BlockDelta::InputJsonDelta { partial_json } => {
+                    if let Some(tool_use) = self.tool_uses.get_mut(&index) {
+                        tool_use.input.push_str(&partial_json);
+                    }
+                    Vec::new()
+                }
+                BlockDelta::Other => Vec::new(),
+            },
+            StreamEvent::ContentBlockStop { index } => match self.tool_uses.remove(&index) {
+                Some(tool_use) => {
+                    let arguments = if tool_use.input.trim().is_empty() {
+                        "{}".to_string()
+                    } else {
+                        tool_use.input
+                    };
+                    self.tool_calls.push(ToolCall {
+                        id: tool_use.id.clone(),
+                        call_type: "function".to_string(),
+                        function: FunctionCall {
+                            name: tool_use.name.clone(),
+                            arguments: arguments.clone(),
+                        },
+                    });
+                    vec![ResponseEvent::ToolCall(ToolCallEvent {
+                        id: tool_use.id,
+                        name: tool_use.name,
+                        arguments,
+                    })]
+                }
+                None => Vec::new(),
+            },
+            StreamEvent::MessageDelta { delta, usage } => {
+                if let Some(usage) = usage {
+                    self.apply_usage(&usage);
+                }
+                if let Some(reason) = delta.stop_reason {
+                    self.finish_reason = Some(match reason.as_str() {
+                        "max_tokens" => FinishReason::Length,
+                        "tool_use" => FinishReason::ToolCalls,
+                        "refusal" => FinishReason::ContentFilter,
+                        _ => FinishReason::Stop,
+                    });
+                }
+                Vec::new()
+            }
+            StreamEvent::MessageStop => self.finish(),
+            StreamEvent::Error { error } => vec![ResponseEvent::Error(format!(
+                "{}: {}",
+                error.error_type, error.message
+            ))],
+            StreamEvent::Unknown => Vec::new(),
+        }
+    }
+
+    fn apply_usage(&mut self, usage: &Usage) {
+        if let Some(input) = usage.input_tokens {
+            self.usage.input_tokens = input
+                + usage.cache_creation_input_tokens.unwrap_or(0)
+                + usage.cache_read_input_tokens.unwrap_or(0);
+        }
+        if let Some(output) = usage.output_tokens {
+            self.usage.output_tokens = output;
+        }
+        self.usage.total_tokens = self.usage.input_tokens + self.usage.output_tokens;
+    }
+
+    /// Emit the final `Done` event (once).
+    pub(crate) fn finish(&mut self) -> Vec<ResponseEvent> {
+        if self.done {
+            return Vec::new();
+        }
+        self.done = true;
+
+        let tool_calls = std::mem::take(&mut self.tool_calls);
+        vec![ResponseEvent::Done(CompletionResponse {
+            message: Some(Message {
+                role: MessageRole::Assistant,
+                content: MessageContent::Text(std::mem::take(&mut self.text)),
+                tool_call_id: None,
+                tool_calls: (!tool_calls.is_empty()).then(|| tool_calls.clone()),
+            }),
+            usage: self.usage.clone(),
+            finish_reason: self.finish_reason.take().unwrap_or_default(),
+            tool_calls,
+        })]
+    }
+}
+
+// =============================================================================
+// MODEL CLIENT IMPLEMENTATION
+// =============================================================================
+
+#[async_trait]
+impl ModelClient for AnthropicClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        &self.capabilities
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+        let url = format!("{}/messages", self.base_url);
+        let body = self.build_request(&request);
+
+        let mut req = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "text/event-stream")
+            .header("anthropic-version", ANTHROPIC_VERSION);
+        if let Some(key) = &self.api_key {
+            req = req.header("x-api-key", key);
+        }
+        for (name, value) in &self.headers {
+            req = req.header(name, value);
+        }
+
+        tracing::debug!(url = %url, provider = %self.provider, model = %self.model, "Sending messages request");
+
+        let resp = req
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CortexError::from_reqwest_with_proxy_check(e, &url))?;
+
+        if !resp.status().is_success() {
+            return Err(error_from_response(resp, &self.base_url).await);
+        }
+
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(100);
+
+        let stream = resp.bytes_stream().eventsource();
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            let mut state = MessagesStreamState::default();
+            let chunk_timeout = Duration::from_secs(CHUNK_TIMEOUT_SECS);
+
+            loop {
+                let event = match timeout(chunk_timeout, stream.next()).await {
+                    Ok(Some(Ok(event))) => event,
+                    Ok(None) => break,
+                    Ok(Some(Err(e))) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!("Stream error: {}", e),
+                            }))
+                            .await;
+                        return;
+                    }
+                    Err(_) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!(
+                                    "SSE chunk timeout - no data received for {} seconds",
+                                    CHUNK_TIMEOUT_SECS
+                                ),
+                            }))
+                            .await;
+                        return;
+                    }
+                };
+
+                if event.data.is_empty() {
+                    continue;
+                }
+
+                for response_event in state.apply(&event.data) {
+                    let is_error = matches!(response_event, ResponseEvent::Error(_));
+                    if tx.send(Ok(response_event)).await.is_err() || is_error {
+                        return;
+                    }
+                }
+            }
+
+            for response_event in state.finish() {
+                if tx.send(Ok(response_event)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse> {
+        collect_stream(self.complete(request).await?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ToolDefinition;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_convert_messages_hoists_system_and_merges_tool_results() {
+        let mut assistant = Message::assistant("Reading both files");
+        assistant.tool_calls = Some(vec![
+            ToolCall {
+                id: "toolu_1".to_string(),
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "Read".to_string(),
+                    arguments: r#"{"path":"a.rs"}"#.to_string(),
+                },
+            },
+            ToolCall {
+                id: "toolu_2".to_string(),
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: "Read".to_string(),
+                    arguments: r#"{"path":"b.rs"}"#.to_string(),
+                },
+            },
+        ]);
+        let messages = vec![
+            Message::system("be brief"),
+            Message::user("read a and b"),
+            assistant,
+            Message::tool_result("toolu_1", "fn a() {}"),
+            Message::tool_result("toolu_2", "fn b() {}"),
+        ];
+
+        let (system, converted) = convert_messages(&messages);
+        assert_eq!(system, "be brief");
+        assert_eq!(converted.len(), 3);
+        assert_eq!(converted[1]["content"][1]["type"], "tool_use");
+        assert_eq!(converted[1]["content"][1]["input"]["path"], "a.rs");
+        assert_eq!(converted[2]["role"], "user");
+        assert_eq!(converted[2]["content"].as_array().unwrap().len(), 2);
+        assert_eq!(converted[2]["content"][1]["tool_use_id"], "toolu_2");
+    }
+
+    #[test]
+    fn test_stream_state_assembles_tool_use() {
+        let mut state = MessagesStreamState::default();
+        state.apply(
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":20,"output_tokens":1}}}"#,
+        );
+        state.apply(
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+        );
+        let events = state.apply(r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Let me look."}}"#);
+        assert!(matches!(&events[0], ResponseEvent::Delta(t) if t == "Let me look."));
+        state.apply(r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"Grep","input":{}}}"#);
+        state.apply(r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"pattern\":"}}"#);
+        state.apply(r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"\"fn main\"}"}}"#);
+        let events = state.apply(r#"{"type":"content_block_stop","index":1}"#);
+        match &events[0] {
+            ResponseEvent::ToolCall(tc) => {
+                assert_eq!(tc.id, "toolu_1");
+                assert_eq!(tc.arguments, r#"{"pattern":"fn main"}"#);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        state.apply(r#"{"type":"message_delta","delta":{"stop_reason":"tool_use"},"usage":{"output_tokens":15}}"#);
+        let events = state.apply(r#"{"type":"message_stop"}"#);
+        match &events[0] {
+            ResponseEvent::Done(done) => {
+                assert_eq!(done.finish_reason, FinishReason::ToolCalls);
+                assert_eq!(done.usage.input_tokens, 20);
+                assert_eq!(done.usage.output_tokens, 15);
+                assert_eq!(done.tool_calls.len(), 1);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert!(state.finish().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_streaming_completion_against_mock_server() {
+        let server = MockServer::start().await;
+        let body = [
+            r#"{"type":"message_start","message":{"usage":{"input_tokens":4}}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hi"}}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn"},"usage":{"output_tokens":1}}"#,
+            r#"{"type":"message_stop"}"#,
+        ]
+        .iter()
+        .map(|d| format!("event: x\ndata: {}\n\n", d))
+        .collect::<String>();
+
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .and(header("x-api-key", "sk-test"))
+            .and(header("anthropic-version", ANTHROPIC_VERSION))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(body),
+            )
+            .mount(&server)
+            .await;
+
+        let client = AnthropicClient::new("gateway", "claude", &format!("{}/v1", server.uri()))
+            .with_api_key("sk-test");
+        let request = CompletionRequest {
+            messages: vec![Message::system("sys"), Message::user("hello")],
+            tools: vec![ToolDefinition::function(
+                "Read",
+                "Read",
+                json!({"type": "object"}),
+            )],
+            ..Default::default()
+        };
+        let response = client.complete_sync(request).await.unwrap();
+
+        assert_eq!(response.message.unwrap().content.as_text(), Some("Hi"));
+        assert_eq!(response.usage.total_tokens, 5);
+    }
+}
@@ -0,0 +1,513 @@
+//! Record/replay model client for deterministic tests.
+//!
+//! [`CassetteClient`] wraps a [`ModelClient`]. In record mode it forwards
+//! requests to the wrapped client and saves each request, with the events
+//! streamed back, to a cassette file. In replay mode it serves those events
+//! back without touching the network.
+//!
+//! Requests are matched in a normalized form so recordings survive changes
+//! that do not affect the conversation:
+//! - tools are reduced to their sorted names;
+//! - system messages are ignored unless [`CassetteClient::match_system_prompt`]
+//!   is set, since they embed the date, cwd and platform;
+//! - strings registered with [`CassetteClient::redact`] are replaced.
+//!
+//! A request that matches no unused recording fails with a diff against the
+//! next one in order.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::types::{CompletionRequest, CompletionResponse, MessageRole, ModelCapabilities};
+use super::{ModelClient, ResponseEvent, ResponseStream, collect_stream};
+use crate::error::{CortexError, Result};
+
+/// Placeholder for system message content when it is not matched.
+const SYSTEM_PROMPT_PLACEHOLDER: &str = "<system prompt>";
+
+/// Contents of a cassette file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Cassette {
+    /// Model the cassette was recorded with.
+    pub model: String,
+    /// Provider the cassette was recorded with.
+    pub provider: String,
+    /// Capabilities reported by the recorded client.
+    #[serde(default)]
+    pub capabilities: ModelCapabilities,
+    /// Recorded requests, in the order they were made.
+    #[serde(default)]
+    pub interactions: Vec<Interaction>,
+}
+
+/// One recorded request and its response.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Interaction {
+    /// Normalized request.
+    pub request: Value,
+    /// Events streamed back.
+    pub events: Vec<ResponseEvent>,
+}
+
+/// Whether the client records or replays.
+enum Mode {
+    Record(Box<dyn ModelClient>),
+    Replay,
+}
+
+/// Cassette plus which recordings have been served.
+#[derive(Default)]
+struct State {
+    cassette: Cassette,
+    used: Vec<bool>,
+}
+
+/// Model client that records to or replays from a cassette file.
+pub struct CassetteClient {
+    mode: Mode,
+    path: PathBuf,
+    state: Arc<Mutex<State>>,
+    model: String,
+    provider: String,
+    capabilities: ModelCapabilities,
+    match_system_prompt: bool,
+    redactions: Vec<(String, String)>,
+}
+
+impl CassetteClient {
+    /// Record every request made through `inner` to `path`.
+    ///
+    /// The cassette is rewritten after each response stream ends, so a
+    /// stream dropped before its end is not recorded.
+    pub fn record(inner: Box<dyn ModelClient>, path: impl Into<PathBuf>) -> Self {
+        let cassette = Cassette {
+            model: inner.model().to_string(),
+            provider: inner.provider().to_string(),
+            capabilities: inner.capabilities().clone(),
+            interactions: Vec::new(),
+        };
+
+        Self {
+            model: cassette.model.clone(),
+            provider: cassette.provider.clone(),
+            capabilities: cassette.capabilities.clone(),
+            mode: Mode::Record(inner),
+            path: path.into(),
+            state: Arc::new(Mutex::new(State {
+                cassette,
+                used: Vec::new(),
+            })),
+            match_system_prompt: false,
+            redactions: Vec::new(),
+        }
+    }
+
+    /// Replay the cassette at `path`.
+    pub fn replay(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let content = std::fs::read_to_string(&path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                CortexError::NotFound(format!("cassette {} (record it first)", path.display()))
+            } else {
+                CortexError::Io(e)
+            }
+        })?;
+        let cassette: Cassette = serde_json::from_str(&content)?;
+
+        Ok(Self {
+            mode: Mode::Replay,
+            path,
+            model: cassette.model.clone(),
+            provider: cassette.provider.clone(),
+            capabilities: cassette.capabilities.clone(),
+            state: Arc::new(Mutex::new(State {
+                used: vec![false; cassette.interactions.len()],
+                cassette,
+            })),
+            match_system_prompt: false,
+            redactions: Vec::new(),
+        })
+    }
+
+    /// Replace `value` with `placeholder` in requests before matching.
+    ///
+    /// Use it for paths and IDs that differ between runs, such as a
+    /// temporary working directory.
+    pub fn redact(mut self, value: impl Into<String>, placeholder: impl Into<String>) -> Self {
+        let value = value.into();
+        if !value.is_empty() {
+            self.redactions.push((value, placeholder.into()));
+        }
+        self
+    }
+
+    /// Include system message content when matching requests.
+    pub fn match_system_prompt(mut self, enabled: bool) -> Self {
+        self.match_system_prompt = enabled;
+        self
+    }
+
+    /// Cassette file path.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Number of recordings not served yet (always 0 when recording).
+    pub fn remaining(&self) -> usize {
+        self.lock().used.iter().filter(|used| !**used).count()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Normalized form of a request used for matching.
+    fn normalize(&self, request: &CompletionRequest) -> Result<Value> {
+        let mut value = serde_json::to_value(request)?;
+
+        if !self.match_system_prompt
+            && let Some(messages) = value.get_mut("messages").and_then(Value::as_array_mut)
+        {
+            for (message, original) in messages.iter_mut().zip(&request.messages) {
+                if original.role == MessageRole::System {
+                    message["content"] = Value::String(SYSTEM_PROMPT_PLACEHOLDER.to_string());
+                }
+            }
+        }
+
+        let mut tools: Vec<&str> = request.tools.iter().map(|t| t.name()).collect();
+        tools.sort_unstable();
+        value["tools"] = tools.into();
+
+        for (from, to) in &self.redactions {
+            redact(&mut value, from, to);
+        }
+        Ok(value)
+    }
+
+    /// Take the first unused recording matching `request`.
+    fn take_match(&self, request: &Value) -> Result<Vec<ResponseEvent>> {
+        let mut state = self.lock();
+        let State { cassette, used } = &mut *state;
+
+        let matched = cassette
+            .interactions
+            .iter()
+            .zip(used.iter())
+            .position(|(interaction, used)| !used && interaction.request == *request);
+        if let Some(i) = matched {
+            used[i] = true;
+            return Ok(cassette.interactions[i].events.clone());
+        }
+
+        let actual = pretty(request);
+        let message = match used.iter().position(|used| !used) {
+            Some(next) => format!(
+                "request does not match cassette {} (recording #{}):\n{}",
+                self.path.display(),
+                next + 1,
+                similar::TextDiff::from_lines(
+                    &pretty(&cassette.interactions[next].request),
+                    &actual
+                )
+                .unified_diff()
+                .header("recorded", "actual")
+            ),
+            None => format!(
+                "cassette {} has no recordings left for request:\n{}",
+                self.path.display(),
+                actual
+            ),
+        };
+        Err(CortexError::Provider(message))
+    }
+}
+
+#[async_trait]
+impl ModelClient for CassetteClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        &self.capabilities
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+        let normalized = self.normalize(&request)?;
+
+        let inner = match &self.mode {
+            Mode::Replay => {
+                let events = self.take_match(&normalized)?;
+                return Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))));
+            }
+            Mode::Record(inner) => inner,
+        };
+
+        let mut stream = inner.complete(request).await?;
+        let state = self.state.clone();
+        let path = self.path.clone();
+
+        Ok(Box::pin(async_stream::stream! {
+            let mut events = Vec::new();
+            while let Some(item) = stream.next().await {
+                match &item {
+                    Ok(event) => events.push(event.clone()),
+                    Err(e) => events.push(ResponseEvent::Error(e.to_string())),
+                }
+                yield item;
+            }
+
+            let json = {
+                let mut state = state.lock().unwrap_or_else(|e| e.into_inner());
+                state.cassette.interactions.push(Interaction {
+                    request: normalized,
+                    events,
+                });
+                serde_json::to_string_pretty(&state.cassette)
+            };
+            let saved = match json {
+                Ok(json) => save(&path, json).await,
+                Err(e) => Err(e.into()),
+            };
+            if let Err(e) = saved {
+                tracing::warn!("Failed to save cassette {}: {}", path.display(), e);
+            }
+        }))
+    }
+
+    async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse> {
+        collect_stream(self.complete(request).await?).await
+    }
+}
+
+/// Write a cassette, creating its directory.
+async fn save(path: &Path, json: String) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(path, json).await?;
+    Ok(())
+}
+
+/// Replace `from` with `to` in every string in `value`.
+fn redact(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::String(s) if s.contains(from) => *s = s.replace(from, to),
+        Value::Array(items) => items.iter_mut().for_each(|v| redact(v, from, to)),
+        Value::Object(map) => map.values_mut().for_each(|v| redact(v, from, to)),
+        _ => {}
+    }
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_default() + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{Message, ToolCallEvent, ToolDefinition};
+    use serde_json::json;
+
+    /// Client that answers every request with the same text.
+    struct EchoClient {
+        capabilities: ModelCapabilities,
+    }
+
+    #[async_trait]
+    impl ModelClient for EchoClient {
+        fn model(&self) -> &str {
+            "echo-model"
+        }
+
+        fn provider(&self) -> &str {
+            "echo"
+        }
+
+        fn capabilities(&self) -> &ModelCapabilities {
+            &self.capabilities
+        }
+
+        async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+            let last = request
+                .messages
+                .last()
+                .and_then(|m| m.content.as_text())
+                .unwrap_or_default()
+                .to_string();
+            let events = vec![
+                Ok(ResponseEvent::ToolCall(ToolCallEvent {
+                    id: "call_1".to_string(),
+                    name: "Read".to_string(),
+                    arguments: r#"{"file_path":"a.txt"}"#.to_string(),
+                })),
+                Ok(ResponseEvent::Delta(format!("you said {last}"))),
+                Ok(ResponseEvent::Done(CompletionResponse::default())),
+            ];
+            Ok(Box::pin(futures::stream::iter(events)))
+        }
+
+        async fn complete_sync(&self, _request: CompletionRequest) -> Result<CompletionResponse> {
+            unimplemented!()
+        }
+    }
+
+    fn request(system: &str, user: &str, tools: &[&str]) -> CompletionRequest {
+        CompletionRequest {
+            model: "echo-model".to_string(),
+            messages: vec![Message::system(system), Message::user(user)],
+            tools: tools
+                .iter()
+                .map(|name| ToolDefinition::function(*name, "", json!({})))
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    fn recorder(path: &Path) -> CassetteClient {
+        CassetteClient::record(
+            Box::new(EchoClient {
+                capabilities: ModelCapabilities::default(),
+            }),
+            path,
+        )
+        .redact("/tmp/recorded", "<cwd>")
+    }
+
+    async fn record(path: &Path, requests: Vec<CompletionRequest>) {
+        record_with(recorder(path), requests).await;
+    }
+
+    async fn record_with(client: CassetteClient, requests: Vec<CompletionRequest>) {
+        for request in requests {
+            let events: Vec<_> = client.complete(request).await.unwrap().collect().await;
+            assert_eq!(events.len(), 3);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replays_recorded_events() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("cassettes/echo.json");
+        record(
+            &path,
+            vec![request("at /tmp/recorded", "hi", &["Read", "Grep"])],
+        )
+        .await;
+
+        let client = CassetteClient::replay(&path)
+            .unwrap()
+            .redact("/tmp/replayed", "<cwd>");
+        assert_eq!(client.model(), "echo-model");
+        assert_eq!(client.remaining(), 1);
+
+        // System prompt and tool order do not matter
+        let response = client
+            .complete_sync(request("a different prompt", "hi", &["Grep", "Read"]))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.message.unwrap().content.as_text(),
+            Some("you said hi")
+        );
+        assert_eq!(client.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_redacts_volatile_strings() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("redact.json");
+        record(&path, vec![request("", "open /tmp/recorded/a.txt", &[])]).await;
+
+        let client = CassetteClient::replay(&path)
+            .unwrap()
+            .redact("/tmp/replayed", "<cwd>");
+        let stream = client
+            .complete(request("", "open /tmp/replayed/a.txt", &[]))
+            .await
+            .unwrap();
+        let events: Vec<_> = stream.collect().await;
+        assert!(matches!(
+            &events[0],
+            Ok(ResponseEvent::ToolCall(call)) if call.name == "Read"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mismatch_shows_diff() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("mismatch.json");
+        record(&path, vec![request("", "hi", &[])]).await;
+
+        let client = CassetteClient::replay(&path).unwrap();
+        let error = client
+            .complete(request("", "bye", &[]))
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("recording #1"), "{error}");
+        let changed = |sign: char, text: &str| {
+            error
+                .lines()
+                .any(|line| line.starts_with(sign) && line.contains(text))
+        };
+        assert!(changed('-', "\"hi\""), "{error}");
+        assert!(changed('+', "\"bye\""), "{error}");
+
+        // Recordings are served once
+        assert!(client.complete(request("", "hi", &[])).await.is_ok());
+        let error = client
+            .complete(request("", "hi", &[]))
+            .await
+            .err()
+            .unwrap()
+            .to_string();
+        assert!(error.contains("no recordings left"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn test_system_prompt_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("system.json");
+        record_with(
+            recorder(&path).match_system_prompt(true),
+            vec![request("be brief", "hi", &[])],
+        )
+        .await;
+
+        let client = CassetteClient::replay(&path)
+            .unwrap()
+            .match_system_prompt(true);
+        assert!(
+            client
+                .complete(request("be verbose", "hi", &[]))
+                .await
+                .is_err()
+        );
+        assert!(
+            client
+                .complete(request("be brief", "hi", &[]))
+                .await
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_missing_cassette() {
+        let error = CassetteClient::replay("/nonexistent/cassette.json")
+            .err()
+            .unwrap();
+        assert!(error.to_string().contains("record it first"));
+    }
+}
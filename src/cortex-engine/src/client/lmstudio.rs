@@ -0,0 +1,173 @@
+//! LM Studio Client
+//!
+//! Adapter exposing a local LM Studio server as a [`ModelClient`]. Inference
+//! goes through LM Studio's OpenAI-compatible `/v1/chat/completions` endpoint
+//! (streaming, tool call delta assembly, usage), while model discovery and
+//! loading use [`cortex_lmstudio::LMStudioClient`].
+
+use async_trait::async_trait;
+use cortex_lmstudio::{LMSTUDIO_PROVIDER_ID, LMStudioClient as LmStudioServer};
+
+use super::{
+    CompletionRequest, CompletionResponse, ModelCapabilities, ModelClient, OpenAiClient,
+    ResponseStream,
+};
+use crate::error::{CortexError, Result};
+
+/// Client for a local LM Studio server.
+pub struct LmStudioClient {
+    inner: OpenAiClient,
+    server: LmStudioServer,
+}
+
+impl LmStudioClient {
+    /// Create a client for `base_url` (e.g. "http://localhost:1234/v1") without
+    /// checking that the server is running.
+    pub fn new(model: impl Into<String>, base_url: &str) -> Self {
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = if base_url.ends_with("/v1") {
+            base_url.to_string()
+        } else {
+            format!("{}/v1", base_url)
+        };
+
+        Self {
+            inner: OpenAiClient::new(LMSTUDIO_PROVIDER_ID, model, &base_url),
+            server: LmStudioServer::with_base_url(base_url),
+        }
+    }
+
+    /// Create a client and verify the server is reachable.
+    pub async fn connect(model: impl Into<String>, base_url: &str) -> Result<Self> {
+        let client = Self::new(model, base_url);
+        LmStudioServer::new(client.server.base_url())
+            .await
+            .map_err(lmstudio_error)?;
+        Ok(client)
+    }
+
+    /// Override the model capabilities.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.inner = self.inner.with_capabilities(capabilities);
+        self
+    }
+
+    /// List the models available on the server (`GET /v1/models`).
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let models = self.server.fetch_models().await.map_err(lmstudio_error)?;
+        Ok(models.into_iter().map(|m| m.id).collect())
+    }
+
+    /// Ask the server to load the current model into memory.
+    pub async fn load_model(&self) -> Result<()> {
+        self.server
+            .load_model(self.inner.model())
+            .await
+            .map_err(lmstudio_error)
+    }
+}
+
+fn lmstudio_error(e: cortex_lmstudio::LMStudioError) -> CortexError {
+    CortexError::BackendError {
+        message: e.to_string(),
+    }
+}
+
+#[async_trait]
+impl ModelClient for LmStudioClient {
+    fn model(&self) -> &str {
+        self.inner.model()
+    }
+
+    fn provider(&self) -> &str {
+        LMSTUDIO_PROVIDER_ID
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        self.inner.capabilities()
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+        self.inner.complete(request).await
+    }
+
+    async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse> {
+        self.inner.complete_sync(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{FinishReason, Message, ToolDefinition};
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_discovers_models_and_streams_tool_calls() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/models"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "object": "list",
+                "data": [{ "id": "openai/gpt-oss-20b", "object": "model", "owned_by": "organization_owner" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let chunks = [
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","function":{"name":"Glob","arguments":"{\"pattern\""}}]}}]}"#,
+            r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":":\"*.rs\"}"}}]},"finish_reason":"tool_calls"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":40,"completion_tokens":9,"total_tokens":49}}"#,
+        ];
+        let body = chunks
+            .iter()
+            .map(|c| format!("data: {}\n\n", c))
+            .collect::<String>()
+            + "data: [DONE]\n\n";
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(body),
+            )
+            .mount(&server)
+            .await;
+
+        let client = LmStudioClient::connect("openai/gpt-oss-20b", &server.uri())
+            .await
+            .unwrap();
+        assert_eq!(client.provider(), "lmstudio");
+        assert_eq!(
+            client.list_models().await.unwrap(),
+            vec!["openai/gpt-oss-20b"]
+        );
+
+        let request = CompletionRequest {
+            messages: vec![Message::user("list rust files")],
+            tools: vec![ToolDefinition::function("Glob", "Find files", json!({}))],
+            ..Default::default()
+        };
+        let response = client.complete_sync(request).await.unwrap();
+        assert_eq!(response.finish_reason, FinishReason::ToolCalls);
+        assert_eq!(response.tool_calls[0].function.name, "Glob");
+        assert_eq!(
+            response.tool_calls[0].function.arguments,
+            r#"{"pattern":"*.rs"}"#
+        );
+        assert_eq!(response.usage.total_tokens, 49);
+    }
+
+    #[tokio::test]
+    async fn test_connect_fails_when_server_is_down() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+
+        assert!(LmStudioClient::connect("m", &server.uri()).await.is_err());
+    }
+}
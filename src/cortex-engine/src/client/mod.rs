@@ -1,19 +1,33 @@
 //! Cortex Backend Client
 //!
 //! Provides unified interface for the Cortex Backend API.
-//! All LLM requests go through the Cortex backend with OAuth authentication.
+//! By default LLM requests go through the Cortex backend with OAuth authentication;
+//! providers declared in the `[providers]` config table are called directly.
 
+mod anthropic;
+pub mod cassette;
 mod cortex;
+mod lmstudio;
+mod ollama;
+mod openai;
 pub mod types;
 
+pub use anthropic::AnthropicClient;
+pub use cassette::{Cassette, CassetteClient};
 pub use cortex::{CortexClient, CortexModel, PricingInfo};
+pub use lmstudio::LmStudioClient;
+pub use ollama::{DEFAULT_OLLAMA_URL, OLLAMA_PROVIDER_ID, OllamaClient};
+pub use openai::OpenAiClient;
 pub use types::*;
 
 use async_trait::async_trait;
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::pin::Pin;
+use std::time::Duration;
 use tokio_stream::Stream;
 
+use crate::config::{ApiType, Config, CustomProviderConfig};
 use crate::error::{CortexError, Result};
 
 /// Stream type for response events.
@@ -46,6 +60,67 @@ pub trait ModelClient: Send + Sync {
     async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse>;
 }
 
+/// Drain a response stream into a single [`CompletionResponse`].
+pub(crate) async fn collect_stream(mut stream: ResponseStream) -> Result<CompletionResponse> {
+    let mut response = CompletionResponse::default();
+    let mut text = String::new();
+
+    while let Some(event_result) = stream.next().await {
+        match event_result? {
+            ResponseEvent::Delta(delta) => text.push_str(&delta),
+            ResponseEvent::Done(completion) => response = completion,
+            ResponseEvent::Error(err) => return Err(CortexError::BackendError { message: err }),
+            _ => {}
+        }
+    }
+
+    if response.message.is_none() && !text.is_empty() {
+        response.message = Some(Message::assistant(text));
+    }
+
+    Ok(response)
+}
+
+/// Convert a non-success HTTP response from a provider into a [`CortexError`].
+pub(crate) async fn error_from_response(resp: reqwest::Response, base_url: &str) -> CortexError {
+    let status = resp.status();
+    let retry_after = resp
+        .headers()
+        .get("retry-after")
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    let body = resp.text().await.unwrap_or_default();
+
+    let message = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|v| {
+            let error = v.get("error")?;
+            error
+                .get("message")
+                .and_then(|m| m.as_str())
+                .or_else(|| error.as_str())
+                .map(String::from)
+        })
+        .unwrap_or_else(|| {
+            let preview: String = body.chars().take(200).collect();
+            format!("HTTP {} from {}: {}", status, base_url, preview)
+        });
+
+    tracing::error!(status = %status, url = %base_url, body = %body, "Provider request failed");
+
+    match status.as_u16() {
+        401 | 403 => CortexError::AuthenticationError { message },
+        429 => match retry_after {
+            Some(retry_after_secs) => CortexError::RateLimitWithRetryAfter {
+                message,
+                retry_after_secs,
+            },
+            None => CortexError::RateLimit(message),
+        },
+        _ => CortexError::BackendError { message },
+    }
+}
+
 /// Get the Cortex auth token from environment or keyring.
 fn get_auth_token() -> Result<String> {
     // First check environment variable
@@ -64,19 +139,67 @@ fn get_auth_token() -> Result<String> {
     ))
 }
 
+/// Check whether a provider ID refers to a local inference server (LM Studio, Ollama).
+///
+/// Local providers need no authentication.
+pub fn is_local_provider(provider_id: &str) -> bool {
+    provider_id.eq_ignore_ascii_case(cortex_lmstudio::LMSTUDIO_PROVIDER_ID)
+        || provider_id.eq_ignore_ascii_case(OLLAMA_PROVIDER_ID)
+}
+
+/// Create a client for a local inference server, or `None` if `provider_id` is not local.
+///
+/// Without an explicit `base_url`, `LMSTUDIO_BASE_URL` / `OLLAMA_HOST` are honored
+/// before falling back to the default local ports.
+pub fn create_local_client(
+    provider_id: &str,
+    model: &str,
+    base_url: Option<&str>,
+) -> Option<Box<dyn ModelClient>> {
+    if provider_id.eq_ignore_ascii_case(cortex_lmstudio::LMSTUDIO_PROVIDER_ID) {
+        let base_url = base_url
+            .map(String::from)
+            .or_else(|| std::env::var("LMSTUDIO_BASE_URL").ok())
+            .unwrap_or_else(|| cortex_lmstudio::DEFAULT_LMSTUDIO_URL.to_string());
+        return Some(Box::new(LmStudioClient::new(model, &base_url)));
+    }
+
+    if provider_id.eq_ignore_ascii_case(OLLAMA_PROVIDER_ID) {
+        let base_url = base_url
+            .map(String::from)
+            .or_else(|| std::env::var("OLLAMA_HOST").ok())
+            .map(|host| {
+                if host.contains("://") {
+                    host
+                } else {
+                    format!("http://{}", host)
+                }
+            })
+            .unwrap_or_else(|| DEFAULT_OLLAMA_URL.to_string());
+        return Some(Box::new(OllamaClient::new(model, &base_url)));
+    }
+
+    None
+}
+
 /// Create a Cortex backend client.
 ///
-/// All requests go through the Cortex backend with OAuth authentication.
+/// All requests go through the Cortex backend with OAuth authentication,
+/// except for local providers (see [`create_local_client`]).
 ///
 /// # Legacy signature compatibility
-/// The `_provider_id` and `_base_url` parameters are ignored - all requests
-/// go through Cortex backend. The `api_key` parameter is used as the auth token.
+/// For non-local providers the `provider_id` and `base_url` parameters are
+/// ignored. The `api_key` parameter is used as the auth token.
 pub fn create_client(
-    _provider_id: &str,
+    provider_id: &str,
     model: &str,
     api_key: &str,
-    _base_url: Option<&str>,
+    base_url: Option<&str>,
 ) -> Result<Box<dyn ModelClient>> {
+    if let Some(client) = create_local_client(provider_id, model, base_url) {
+        return Ok(client);
+    }
+
     // Use provided api_key as auth token, or try to get from environment/keyring
     let auth_token = if !api_key.is_empty() {
         api_key.to_string()
@@ -107,3 +230,108 @@ pub fn create_client_with_url(
             .with_auth_token(auth_token.to_string()),
     )
 }
+
+/// Create a client that talks directly to a provider from the `[providers]` table.
+///
+/// `api_type` selects the wire protocol: `"anthropic"` uses the Messages API,
+/// `"openai"` and `"openai-compatible"` use Chat Completions. The API key is taken
+/// from `api_key` when non-empty, otherwise from the provider's `api_key_env`.
+/// OpenAI-compatible servers (vLLM, gateways) may run without a key.
+pub fn create_client_for_provider(
+    provider_id: &str,
+    provider: &CustomProviderConfig,
+    model: &str,
+    api_key: Option<&str>,
+) -> Result<Box<dyn ModelClient>> {
+    let api_type = ApiType::parse(&provider.api_type).ok_or_else(|| CortexError::InvalidConfig {
+        field: format!("providers.{}.api_type", provider_id),
+        message: format!(
+            "unknown api_type '{}' (expected \"openai\", \"anthropic\" or \"openai-compatible\")",
+            provider.api_type
+        ),
+    })?;
+
+    if provider.base_url.is_empty() {
+        return Err(CortexError::InvalidConfig {
+            field: format!("providers.{}.base_url", provider_id),
+            message: "base_url is required".to_string(),
+        });
+    }
+
+    let api_key = api_key
+        .filter(|k| !k.is_empty())
+        .map(String::from)
+        .or_else(|| provider.resolve_api_key());
+    if api_key.is_none() && api_type != ApiType::OpenAiCompatible {
+        return Err(CortexError::ApiKeyNotFound {
+            provider: provider_id.to_string(),
+        });
+    }
+
+    let model = if model.is_empty() {
+        provider.default_model.as_deref().unwrap_or_default()
+    } else {
+        model
+    };
+
+    let http_client = crate::api_client::create_client_with_timeout(Duration::from_secs(
+        provider.timeout_seconds,
+    ))?;
+
+    let client: Box<dyn ModelClient> = match api_type {
+        ApiType::Anthropic => {
+            let mut client = AnthropicClient::new(provider_id, model, &provider.base_url)
+                .with_headers(provider.headers.clone())
+                .with_http_client(http_client);
+            if let Some(caps) = provider.model_capabilities(model, client.capabilities()) {
+                client = client.with_capabilities(caps);
+            }
+            if let Some(key) = api_key {
+                client = client.with_api_key(key);
+            }
+            Box::new(client)
+        }
+        ApiType::OpenAi | ApiType::OpenAiCompatible => {
+            let mut client = OpenAiClient::new(provider_id, model, &provider.base_url)
+                .with_headers(provider.headers.clone())
+                .with_http_client(http_client);
+            if let Some(caps) = provider.model_capabilities(model, client.capabilities()) {
+                client = client.with_capabilities(caps);
+            }
+            if let Some(key) = api_key {
+                client = client.with_api_key(key);
+            }
+            Box::new(client)
+        }
+    };
+
+    Ok(client)
+}
+
+/// Create the model client for a loaded [`Config`].
+///
+/// If `model_provider_id` names an entry in the `[providers]` table, the matching
+/// direct client is used. Local providers (`lmstudio`, `ollama`) connect to the
+/// local server. Otherwise requests go through the Cortex backend.
+pub fn create_client_from_config(config: &Config) -> Result<Box<dyn ModelClient>> {
+    if let Some(provider) = config.providers.get(&config.model_provider_id) {
+        return create_client_for_provider(
+            &config.model_provider_id,
+            provider,
+            &config.model,
+            None,
+        );
+    }
+
+    if let Some(client) = create_local_client(&config.model_provider_id, &config.model, None) {
+        return Ok(client);
+    }
+
+    let api_key = crate::auth_token::get_auth_token(None)?;
+    create_client(
+        &config.model_provider_id,
+        &config.model,
+        &api_key,
+        Some(config.model_provider.base_url.as_str()),
+    )
+}
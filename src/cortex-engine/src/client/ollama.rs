@@ -0,0 +1,598 @@
+//! Ollama Native Client
+//!
+//! Client for Ollama's native `/api/chat` endpoint with:
+//! - Streaming NDJSON responses
+//! - Native tool calls (`message.tool_calls` with object arguments)
+//! - Thinking output for reasoning models
+//! - Model discovery from `/api/tags`
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{
+    CompletionRequest, CompletionResponse, ContentPart, FinishReason, FunctionCall, Message,
+    MessageContent, MessageRole, ModelCapabilities, ModelClient, ResponseEvent, ResponseStream,
+    TokenUsage, ToolCall, ToolCallEvent, collect_stream, error_from_response,
+};
+use crate::error::{CortexError, Result};
+
+/// Provider identifier for Ollama.
+pub const OLLAMA_PROVIDER_ID: &str = "ollama";
+
+/// Default Ollama server URL.
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+
+/// Timeout in seconds for receiving individual NDJSON lines during streaming.
+/// Local models can take a while to load before the first token.
+const CHUNK_TIMEOUT_SECS: u64 = 300;
+
+/// Client for a local Ollama server.
+pub struct OllamaClient {
+    client: Client,
+    base_url: String,
+    model: String,
+    capabilities: ModelCapabilities,
+}
+
+impl OllamaClient {
+    /// Create a new client for `base_url` (e.g. "http://localhost:11434").
+    ///
+    /// A trailing `/v1` or `/api` is stripped so the OpenAI-compatible URL
+    /// can be used interchangeably.
+    pub fn new(model: impl Into<String>, base_url: &str) -> Self {
+        let client = crate::api_client::create_streaming_client().unwrap_or_else(|e| {
+            tracing::warn!("Failed to create streaming client: {}, using fallback", e);
+            Client::new()
+        });
+
+        let base_url = base_url.trim_end_matches('/');
+        let base_url = base_url
+            .strip_suffix("/v1")
+            .or_else(|| base_url.strip_suffix("/api"))
+            .unwrap_or(base_url);
+
+        Self {
+            client,
+            base_url: base_url.to_string(),
+            model: model.into(),
+            capabilities: ModelCapabilities {
+                vision: false,
+                tools: true,
+                reasoning: false,
+                context_window: 32_768,
+                max_output_tokens: None,
+            },
+        }
+    }
+
+    /// Override the model capabilities.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Use a custom HTTP client.
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// List the models installed on the server (`GET /api/tags`).
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        #[derive(Deserialize)]
+        struct TagsResponse {
+            #[serde(default)]
+            models: Vec<TagModel>,
+        }
+        #[derive(Deserialize)]
+        struct TagModel {
+            name: String,
+        }
+
+        let url = format!("{}/api/tags", self.base_url);
+        let resp = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| CortexError::from_reqwest_with_proxy_check(e, &url))?;
+
+        if !resp.status().is_success() {
+            return Err(error_from_response(resp, &self.base_url).await);
+        }
+
+        let tags: TagsResponse = resp.json().await.map_err(|e| CortexError::BackendError {
+            message: format!("Invalid /api/tags response: {}", e),
+        })?;
+        Ok(tags.models.into_iter().map(|m| m.name).collect())
+    }
+
+    /// Build the `/api/chat` request body.
+    fn build_request(&self, request: &CompletionRequest) -> Value {
+        let messages: Vec<Value> = request.messages.iter().map(convert_message).collect();
+
+        let model = if request.model.is_empty() {
+            &self.model
+        } else {
+            &request.model
+        };
+
+        let mut options = serde_json::Map::new();
+        if let Some(max_tokens) = request.max_tokens {
+            options.insert("num_predict".to_string(), json!(max_tokens));
+        }
+        if let Some(temperature) = request.temperature {
+            options.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(seed) = request.seed {
+            options.insert("seed".to_string(), json!(seed));
+        }
+
+        let mut body = json!({
+            "model": model,
+            "messages": messages,
+            "stream": true,
+        });
+        if !options.is_empty() {
+            body["options"] = Value::Object(options);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = json!(request.tools);
+        }
+
+        body
+    }
+}
+
+/// Convert an internal message to Ollama's chat message format.
+///
+/// Ollama takes plain string content plus a separate `images` array of raw
+/// base64 data, and tool call arguments as JSON objects rather than strings.
+fn convert_message(message: &Message) -> Value {
+    let role = match message.role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+    };
+
+    let mut images = Vec::new();
+    let content = match &message.content {
+        MessageContent::Text(text) => text.clone(),
+        MessageContent::Parts(parts) => {
+            let mut text = String::new();
+            for part in parts {
+                match part {
+                    ContentPart::Text { text: t, .. } => text.push_str(t),
+                    ContentPart::ImageUrl { image_url } => {
+                        images.extend(image_data(&image_url.url))
+                    }
+                    ContentPart::Image { url, .. } => images.extend(image_data(url)),
+                    ContentPart::Document { data, name, .. } => {
+                        text.push_str(&format!(
+                            "[{}]\n{}",
+                            name.as_deref().unwrap_or("document"),
+                            data
+                        ));
+                    }
+                }
+            }
+            text
+        }
+        MessageContent::ToolResult { content, .. } => content.clone(),
+        MessageContent::ToolCalls(_) => String::new(),
+    };
+
+    let mut value = json!({ "role": role, "content": content });
+    if !images.is_empty() {
+        value["images"] = json!(images);
+    }
+
+    let tool_calls: Vec<Value> = match (&message.tool_calls, &message.content) {
+        (Some(calls), _) => calls
+            .iter()
+            .map(|tc| tool_call_value(&tc.function.name, &tc.function.arguments))
+            .collect(),
+        (None, MessageContent::ToolCalls(calls)) => calls
+            .iter()
+            .map(|tc| tool_call_value(&tc.name, &tc.arguments))
+            .collect(),
+        _ => Vec::new(),
+    };
+    if !tool_calls.is_empty() {
+        value["tool_calls"] = Value::Array(tool_calls);
+    }
+
+    value
+}
+
+fn tool_call_value(name: &str, arguments: &str) -> Value {
+    let arguments: Value = serde_json::from_str(arguments).unwrap_or_else(|_| json!({}));
+    json!({ "function": { "name": name, "arguments": arguments } })
+}
+
+/// Extract raw base64 data from a `data:` URL. Remote URLs are not supported by Ollama.
+fn image_data(url: &str) -> Option<String> {
+    let (_, data) = url.strip_prefix("data:")?.split_once(";base64,")?;
+    Some(data.to_string())
+}
+
+// =============================================================================
+// NDJSON STREAM TYPES
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ChatLine {
+    #[serde(default)]
+    message: Option<LineMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    done_reason: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<i64>,
+    #[serde(default)]
+    eval_count: Option<i64>,
+    #[serde(default)]
+    error: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct LineMessage {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<LineToolCall>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LineToolCall {
+    function: LineFunction,
+}
+
+#[derive(Debug, Deserialize)]
+struct LineFunction {
+    name: String,
+    #[serde(default)]
+    arguments: Value,
+}
+
+/// Accumulates streamed NDJSON lines into text, tool calls and usage.
+#[derive(Debug, Default)]
+pub(crate) struct OllamaStreamState {
+    text: String,
+    tool_calls: Vec<ToolCall>,
+    usage: TokenUsage,
+    finish_reason: Option<FinishReason>,
+}
+
+impl OllamaStreamState {
+    /// Apply one NDJSON line and return the events it produces.
+    ///
+    /// Ollama sends each tool call complete in a single line, so tool calls
+    /// are emitted as soon as they arrive.
+    pub(crate) fn apply(&mut self, line: &str) -> Vec<ResponseEvent> {
+        let chunk = match serde_json::from_str::<ChatLine>(line) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                tracing::debug!("Failed to parse Ollama chat line: {} - {}", e, line);
+                return Vec::new();
+            }
+        };
+
+        if let Some(error) = chunk.error {
+            return vec![ResponseEvent::Error(error)];
+        }
+
+        let mut events = Vec::new();
+
+        if let Some(message) = chunk.message {
+            if let Some(thinking) = message.thinking.filter(|t| !t.is_empty()) {
+                events.push(ResponseEvent::Reasoning(thinking));
+            }
+            if !message.content.is_empty() {
+                self.text.push_str(&message.content);
+                events.push(ResponseEvent::Delta(message.content));
+            }
+            for call in message.tool_calls {
+                let arguments = match call.function.arguments {
+                    Value::String(s) => s,
+                    Value::Null => "{}".to_string(),
+                    other => other.to_string(),
+                };
+                let tool_call = ToolCall {
+                    id: format!("call_{}", self.tool_calls.len()),
+                    call_type: "function".to_string(),
+                    function: FunctionCall {
+                        name: call.function.name,
+                        arguments,
+                    },
+                };
+                events.push(ResponseEvent::ToolCall(ToolCallEvent {
+                    id: tool_call.id.clone(),
+                    name: tool_call.function.name.clone(),
+                    arguments: tool_call.function.arguments.clone(),
+                }));
+                self.tool_calls.push(tool_call);
+            }
+        }
+
+        if chunk.done {
+            let input_tokens = chunk.prompt_eval_count.unwrap_or_default();
+            let output_tokens = chunk.eval_count.unwrap_or_default();
+            self.usage = TokenUsage {
+                input_tokens,
+                output_tokens,
+                total_tokens: input_tokens + output_tokens,
+            };
+            self.finish_reason = Some(match chunk.done_reason.as_deref() {
+                Some("length") => FinishReason::Length,
+                _ if !self.tool_calls.is_empty() => FinishReason::ToolCalls,
+                _ => FinishReason::Stop,
+            });
+        }
+
+        events
+    }
+
+    /// Produce the final `Done` event.
+    pub(crate) fn finish(&mut self) -> ResponseEvent {
+        let tool_calls = std::mem::take(&mut self.tool_calls);
+        let finish_reason = match self.finish_reason.take() {
+            Some(reason) => reason,
+            None if !tool_calls.is_empty() => FinishReason::ToolCalls,
+            None => FinishReason::Stop,
+        };
+
+        ResponseEvent::Done(CompletionResponse {
+            message: Some(Message {
+                role: MessageRole::Assistant,
+                content: MessageContent::Text(std::mem::take(&mut self.text)),
+                tool_call_id: None,
+                tool_calls: (!tool_calls.is_empty()).then(|| tool_calls.clone()),
+            }),
+            usage: self.usage.clone(),
+            finish_reason,
+            tool_calls,
+        })
+    }
+}
+
+// =============================================================================
+// MODEL CLIENT IMPLEMENTATION
+// =============================================================================
+
+#[async_trait]
+impl ModelClient for OllamaClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn provider(&self) -> &str {
+        OLLAMA_PROVIDER_ID
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        &self.capabilities
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+        let url = format!("{}/api/chat", self.base_url);
+        let body = self.build_request(&request);
+
+        tracing::debug!(url = %url, model = %self.model, "Sending Ollama chat request");
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CortexError::from_reqwest_with_proxy_check(e, &url))?;
+
+        if !resp.status().is_success() {
+            return Err(error_from_response(resp, &self.base_url).await);
+        }
+
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(100);
+
+        let stream = resp.bytes_stream();
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            let mut state = OllamaStreamState::default();
+            let mut buffer: Vec<u8> = Vec::new();
+            let chunk_timeout = Duration::from_secs(CHUNK_TIMEOUT_SECS);
+
+            loop {
+                let bytes = match timeout(chunk_timeout, stream.next()).await {
+                    Ok(Some(Ok(bytes))) => bytes,
+                    Ok(None) => break,
+                    Ok(Some(Err(e))) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!("Stream error: {}", e),
+                            }))
+                            .await;
+                        return;
+                    }
+                    Err(_) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!(
+                                    "Stream timeout - no data received for {} seconds",
+                                    CHUNK_TIMEOUT_SECS
+                                ),
+                            }))
+                            .await;
+                        return;
+                    }
+                };
+
+                buffer.extend_from_slice(&bytes);
+                while let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    let line = String::from_utf8_lossy(&line);
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    for event in state.apply(line) {
+                        let is_error = matches!(event, ResponseEvent::Error(_));
+                        if tx.send(Ok(event)).await.is_err() || is_error {
+                            return;
+                        }
+                    }
+                }
+            }
+
+            let rest = String::from_utf8_lossy(&buffer);
+            if !rest.trim().is_empty() {
+                for event in state.apply(rest.trim()) {
+                    let is_error = matches!(event, ResponseEvent::Error(_));
+                    if tx.send(Ok(event)).await.is_err() || is_error {
+                        return;
+                    }
+                }
+            }
+
+            let _ = tx.send(Ok(state.finish())).await;
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse> {
+        collect_stream(self.complete(request).await?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ToolDefinition;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[test]
+    fn test_tool_calls_and_usage_from_lines() {
+        let mut state = OllamaStreamState::default();
+        let events = state.apply(r#"{"message":{"role":"assistant","content":"","tool_calls":[{"function":{"name":"Read","arguments":{"path":"a.rs"}}}]},"done":false}"#);
+        match &events[0] {
+            ResponseEvent::ToolCall(tc) => {
+                assert_eq!(tc.id, "call_0");
+                assert_eq!(tc.name, "Read");
+                assert_eq!(tc.arguments, r#"{"path":"a.rs"}"#);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        state.apply(r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":20,"eval_count":5}"#);
+
+        match state.finish() {
+            ResponseEvent::Done(done) => {
+                assert_eq!(done.finish_reason, FinishReason::ToolCalls);
+                assert_eq!(done.tool_calls.len(), 1);
+                assert_eq!(done.usage.total_tokens, 25);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_convert_message_uses_object_arguments_and_images() {
+        let mut assistant = Message::assistant("");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "call_0".to_string(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: "Read".to_string(),
+                arguments: r#"{"path":"a.rs"}"#.to_string(),
+            },
+        }]);
+        let value = convert_message(&assistant);
+        assert_eq!(
+            value["tool_calls"][0]["function"]["arguments"]["path"],
+            "a.rs"
+        );
+
+        let user = Message {
+            role: MessageRole::User,
+            content: MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: "what is this?".to_string(),
+                    cache_control: None,
+                },
+                ContentPart::Image {
+                    url: "data:image/png;base64,AAAA".to_string(),
+                    detail: None,
+                },
+            ]),
+            tool_call_id: None,
+            tool_calls: None,
+        };
+        let value = convert_message(&user);
+        assert_eq!(value["content"], "what is this?");
+        assert_eq!(value["images"][0], "AAAA");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_chat_against_mock_server() {
+        let server = MockServer::start().await;
+        let body = [
+            r#"{"message":{"role":"assistant","content":"Hel"},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":"lo"},"done":false}"#,
+            r#"{"message":{"role":"assistant","content":""},"done":true,"done_reason":"stop","prompt_eval_count":3,"eval_count":2}"#,
+        ]
+        .join("\n");
+        Mock::given(method("POST"))
+            .and(path("/api/chat"))
+            .and(body_partial_json(
+                json!({ "model": "llama3.2", "stream": true }),
+            ))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "application/x-ndjson")
+                    .set_body_string(body),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::new("llama3.2", &format!("{}/v1", server.uri()));
+        let request = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            tools: vec![ToolDefinition::function("Read", "Read a file", json!({}))],
+            ..Default::default()
+        };
+        let response = client.complete_sync(request).await.unwrap();
+
+        assert_eq!(response.message.unwrap().content.as_text(), Some("Hello"));
+        assert_eq!(response.usage.input_tokens, 3);
+        assert_eq!(response.finish_reason, FinishReason::Stop);
+    }
+
+    #[tokio::test]
+    async fn test_list_models_from_tags() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/tags"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "models": [{ "name": "llama3.2:latest" }, { "name": "qwen2.5-coder:7b" }]
+            })))
+            .mount(&server)
+            .await;
+
+        let client = OllamaClient::new("llama3.2", &server.uri());
+        let models = client.list_models().await.unwrap();
+        assert_eq!(models, vec!["llama3.2:latest", "qwen2.5-coder:7b"]);
+    }
+}
@@ -0,0 +1,616 @@
+//! OpenAI Chat Completions Client
+//!
+//! Direct client for the OpenAI Chat Completions API and compatible servers
+//! (vLLM, LiteLLM, internal gateways, ...) with:
+//! - Streaming SSE responses
+//! - Incremental tool call assembly from `delta.tool_calls`
+//! - Token usage via `stream_options.include_usage`
+
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::sync::mpsc;
+use tokio::time::timeout;
+use tokio_stream::wrappers::ReceiverStream;
+
+use super::{
+    CompletionRequest, CompletionResponse, ContentPart, FinishReason, FunctionCall, Message,
+    MessageContent, MessageRole, ModelCapabilities, ModelClient, ResponseEvent, ResponseStream,
+    TokenUsage, ToolCall, ToolCallEvent, collect_stream, error_from_response,
+};
+use crate::error::{CortexError, Result};
+
+/// Timeout in seconds for receiving individual SSE chunks during streaming.
+const CHUNK_TIMEOUT_SECS: u64 = 60;
+
+/// Client for OpenAI-compatible Chat Completions endpoints.
+pub struct OpenAiClient {
+    client: Client,
+    provider: String,
+    base_url: String,
+    model: String,
+    capabilities: ModelCapabilities,
+    api_key: Option<String>,
+    headers: HashMap<String, String>,
+}
+
+impl OpenAiClient {
+    /// Create a new client for `base_url` (e.g. "https://api.openai.com/v1").
+    pub fn new(provider: impl Into<String>, model: impl Into<String>, base_url: &str) -> Self {
+        let client = crate::api_client::create_streaming_client().unwrap_or_else(|e| {
+            tracing::warn!("Failed to create streaming client: {}, using fallback", e);
+            Client::new()
+        });
+
+        Self {
+            client,
+            provider: provider.into(),
+            base_url: base_url.trim_end_matches('/').to_string(),
+            model: model.into(),
+            capabilities: ModelCapabilities {
+                vision: false,
+                tools: true,
+                reasoning: false,
+                context_window: 128_000,
+                max_output_tokens: None,
+            },
+            api_key: None,
+            headers: HashMap::new(),
+        }
+    }
+
+    /// Set the API key sent as a bearer token.
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Set additional headers sent with every request.
+    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Override the model capabilities.
+    pub fn with_capabilities(mut self, capabilities: ModelCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Use a custom HTTP client (e.g. with a provider-specific timeout).
+    pub fn with_http_client(mut self, client: Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Build the Chat Completions request body.
+    fn build_request(&self, request: &CompletionRequest) -> Value {
+        let messages: Vec<Value> = request.messages.iter().map(convert_message).collect();
+
+        let model = if request.model.is_empty() {
+            &self.model
+        } else {
+            &request.model
+        };
+
+        let mut body = json!({
+            "model": model,
+            "messages": messages,
+            "stream": true,
+            "stream_options": { "include_usage": true },
+        });
+
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(seed) = request.seed {
+            body["seed"] = json!(seed);
+        }
+        if !request.tools.is_empty() {
+            body["tools"] = json!(request.tools);
+            body["tool_choice"] = json!("auto");
+        }
+
+        body
+    }
+}
+
+/// Convert an internal message to the Chat Completions wire format.
+fn convert_message(message: &Message) -> Value {
+    let role = match message.role {
+        MessageRole::System => "system",
+        MessageRole::User => "user",
+        MessageRole::Assistant => "assistant",
+        MessageRole::Tool => "tool",
+    };
+
+    let content = match &message.content {
+        MessageContent::Text(text) => json!(text),
+        MessageContent::Parts(parts) => Value::Array(
+            parts
+                .iter()
+                .map(|part| match part {
+                    ContentPart::Text { text, .. } => json!({ "type": "text", "text": text }),
+                    ContentPart::ImageUrl { image_url } => {
+                        json!({ "type": "image_url", "image_url": image_url })
+                    }
+                    ContentPart::Image { url, detail } => json!({
+                        "type": "image_url",
+                        "image_url": { "url": url, "detail": detail },
+                    }),
+                    ContentPart::Document { data, name, .. } => json!({
+                        "type": "text",
+                        "text": format!("[{}]\n{}", name.as_deref().unwrap_or("document"), data),
+                    }),
+                })
+                .collect(),
+        ),
+        MessageContent::ToolResult { content, .. } => json!(content),
+        MessageContent::ToolCalls(_) => Value::Null,
+    };
+
+    let mut value = json!({ "role": role, "content": content });
+
+    if let Some(id) = message.tool_call_id.as_ref().or(match &message.content {
+        MessageContent::ToolResult { tool_call_id, .. } => Some(tool_call_id),
+        _ => None,
+    }) {
+        value["tool_call_id"] = json!(id);
+    }
+
+    let tool_calls: Vec<Value> = match (&message.tool_calls, &message.content) {
+        (Some(calls), _) => calls.iter().map(|tc| json!(tc)).collect(),
+        (None, MessageContent::ToolCalls(calls)) => calls
+            .iter()
+            .map(|tc| {
+                json!({
+                    "id": tc.id,
+                    "type": "function",
+                    "function": { "name": tc.name, "arguments": tc.arguments },
+                })
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if !tool_calls.is_empty() {
+        value["tool_calls"] = Value::Array(tool_calls);
+        if value["content"].as_str().is_some_and(str::is_empty) {
+            value["content"] = Value::Null;
+        }
+    }
+
+    value
+}
+
+// =============================================================================
+// CHAT COMPLETIONS STREAM TYPES
+// =============================================================================
+
+#[derive(Debug, Deserialize)]
+struct ChatChunk {
+    #[serde(default)]
+    choices: Vec<ChunkChoice>,
+    #[serde(default)]
+    usage: Option<ChunkUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkChoice {
+    #[serde(default)]
+    delta: ChunkDelta,
+    #[serde(default)]
+    finish_reason: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct ChunkDelta {
+    #[serde(default)]
+    content: Option<String>,
+    /// Reasoning text (vLLM, DeepSeek and several gateways).
+    #[serde(default, alias = "reasoning")]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ToolCallDelta {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FunctionDelta {
+    #[serde(default)]
+    name: Option<String>,
+    #[serde(default)]
+    arguments: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChunkUsage {
+    #[serde(default)]
+    prompt_tokens: i64,
+    #[serde(default)]
+    completion_tokens: i64,
+    #[serde(default)]
+    total_tokens: Option<i64>,
+}
+
+/// Accumulates streamed chunks into text, tool calls and usage.
+#[derive(Debug, Default)]
+pub(crate) struct ChatStreamState {
+    text: String,
+    tool_calls: BTreeMap<usize, PartialToolCall>,
+    usage: TokenUsage,
+    finish_reason: Option<FinishReason>,
+}
+
+#[derive(Debug, Default)]
+struct PartialToolCall {
+    id: String,
+    name: String,
+    arguments: String,
+}
+
+impl ChatStreamState {
+    /// Apply one `data:` payload and return the events it produces.
+    pub(crate) fn apply(&mut self, data: &str) -> Vec<ResponseEvent> {
+        let chunk = match serde_json::from_str::<ChatChunk>(data) {
+            Ok(chunk) => chunk,
+            Err(e) => {
+                if let Some(message) = serde_json::from_str::<Value>(data)
+                    .ok()
+                    .and_then(|v| v.get("error")?.get("message")?.as_str().map(String::from))
+                {
+                    return vec![ResponseEvent::Error(message)];
+                }
+                tracing::debug!("Failed to parse chat chunk: {} - {}", e, data);
+                return Vec::new();
+            }
+        };
+
+        let mut events = Vec::new();
+
+        if let Some(usage) = chunk.usage {
+            self.usage = TokenUsage {
+                input_tokens: usage.prompt_tokens,
+                output_tokens: usage.completion_tokens,
+                total_tokens: usage
+                    .total_tokens
+                    .unwrap_or(usage.prompt_tokens + usage.completion_tokens),
+            };
+        }
+
+        for choice in chunk.choices {
+            if let Some(reasoning) = choice.delta.reasoning_content.filter(|r| !r.is_empty()) {
+                events.push(ResponseEvent::Reasoning(reasoning));
+            }
+            if let Some(content) = choice.delta.content.filter(|c| !c.is_empty()) {
+                self.text.push_str(&content);
+                events.push(ResponseEvent::Delta(content));
+            }
+            for delta in choice.delta.tool_calls {
+                let call = self.tool_calls.entry(delta.index).or_default();
+                if let Some(id) = delta.id.filter(|id| !id.is_empty()) {
+                    call.id = id;
+                }
+                if let Some(function) = delta.function {
+                    if let Some(name) = function.name {
+                        call.name.push_str(&name);
+                    }
+                    if let Some(arguments) = function.arguments {
+                        call.arguments.push_str(&arguments);
+                    }
+                }
+            }
+            if let Some(reason) = choice.finish_reason {
+                self.finish_reason = Some(match reason.as_str() {
+                    "length" => FinishReason::Length,
+                    "tool_calls" | "function_call" => FinishReason::ToolCalls,
+                    "content_filter" => FinishReason::ContentFilter,
+                    _ => FinishReason::Stop,
+                });
+            }
+        }
+
+        events
+    }
+
+    /// Flush assembled tool calls and the final `Done` event.
+    pub(crate) fn finish(&mut self) -> Vec<ResponseEvent> {
+        let tool_calls: Vec<ToolCall> = std::mem::take(&mut self.tool_calls)
+            .into_iter()
+            .filter(|(_, call)| !call.name.is_empty())
+            .map(|(index, call)| ToolCall {
+                id: if call.id.is_empty() {
+                    format!("call_{}", index)
+                } else {
+                    call.id
+                },
+                call_type: "function".to_string(),
+                function: FunctionCall {
+                    name: call.name,
+                    arguments: if call.arguments.is_empty() {
+                        "{}".to_string()
+                    } else {
+                        call.arguments
+                    },
+                },
+            })
+            .collect();
+
+        let mut events: Vec<ResponseEvent> = tool_calls
+            .iter()
+            .map(|tc| {
+                ResponseEvent::ToolCall(ToolCallEvent {
+                    id: tc.id.clone(),
+                    name: tc.function.name.clone(),
+                    arguments: tc.function.arguments.clone(),
+                })
+            })
+            .collect();
+
+        let finish_reason = match self.finish_reason.take() {
+            Some(reason) => reason,
+            None if !tool_calls.is_empty() => FinishReason::ToolCalls,
+            None => FinishReason::Stop,
+        };
+
+        events.push(ResponseEvent::Done(CompletionResponse {
+            message: Some(Message {
+                role: MessageRole::Assistant,
+                content: MessageContent::Text(std::mem::take(&mut self.text)),
+                tool_call_id: None,
+                tool_calls: (!tool_calls.is_empty()).then(|| tool_calls.clone()),
+            }),
+            usage: self.usage.clone(),
+            finish_reason,
+            tool_calls,
+        }));
+
+        events
+    }
+}
+
+// =============================================================================
+// MODEL CLIENT IMPLEMENTATION
+// =============================================================================
+
+#[async_trait]
+impl ModelClient for OpenAiClient {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn provider(&self) -> &str {
+        &self.provider
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        &self.capabilities
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> Result<ResponseStream> {
+        let url = format!("{}/chat/completions", self.base_url);
+        let body = self.build_request(&request);
+
+        let mut req = self
+            .client
+            .post(&url)
+            .header("Content-Type", "application/json")
+            .header("Accept", "text/event-stream");
+        if let Some(key) = &self.api_key {
+            req = req.bearer_auth(key);
+        }
+        for (name, value) in &self.headers {
+            req = req.header(name, value);
+        }
+
+        tracing::debug!(url = %url, provider = %self.provider, model = %self.model, "Sending chat completion request");
+
+        let resp = req
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CortexError::from_reqwest_with_proxy_check(e, &url))?;
+
+        if !resp.status().is_success() {
+            return Err(error_from_response(resp, &self.base_url).await);
+        }
+
+        let (tx, rx) = mpsc::channel::<Result<ResponseEvent>>(100);
+
+        let stream = resp.bytes_stream().eventsource();
+        tokio::spawn(async move {
+            let mut stream = std::pin::pin!(stream);
+            let mut state = ChatStreamState::default();
+            let chunk_timeout = Duration::from_secs(CHUNK_TIMEOUT_SECS);
+
+            loop {
+                let event = match timeout(chunk_timeout, stream.next()).await {
+                    Ok(Some(Ok(event))) => event,
+                    Ok(None) => break,
+                    Ok(Some(Err(e))) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!("Stream error: {}", e),
+                            }))
+                            .await;
+                        return;
+                    }
+                    Err(_) => {
+                        let _ = tx
+                            .send(Err(CortexError::BackendError {
+                                message: format!(
+                                    "SSE chunk timeout - no data received for {} seconds",
+                                    CHUNK_TIMEOUT_SECS
+                                ),
+                            }))
+                            .await;
+                        return;
+                    }
+                };
+
+                if event.data == "[DONE]" {
+                    break;
+                }
+                if event.data.is_empty() {
+                    continue;
+                }
+
+                for response_event in state.apply(&event.data) {
+                    let is_error = matches!(response_event, ResponseEvent::Error(_));
+                    if tx.send(Ok(response_event)).await.is_err() || is_error {
+                        return;
+                    }
+                }
+            }
+
+            for response_event in state.finish() {
+                if tx.send(Ok(response_event)).await.is_err() {
+                    return;
+                }
+            }
+        });
+
+        Ok(Box::pin(ReceiverStream::new(rx)))
+    }
+
+    async fn complete_sync(&self, request: CompletionRequest) -> Result<CompletionResponse> {
+        collect_stream(self.complete(request).await?).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ToolDefinition;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn sse(chunks: &[&str]) -> String {
+        chunks
+            .iter()
+            .map(|c| format!("data: {}\n\n", c))
+            .collect::<String>()
+            + "data: [DONE]\n\n"
+    }
+
+    #[test]
+    fn test_tool_call_deltas_are_assembled() {
+        let mut state = ChatStreamState::default();
+        state.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_a","function":{"name":"Read","arguments":""}}]}}]}"#);
+        state.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"path\":"}}]}}]}"#);
+        state.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":1,"id":"call_b","function":{"name":"Glob","arguments":"{}"}}]}}]}"#);
+        state.apply(r#"{"choices":[{"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"a.rs\"}"}}]},"finish_reason":"tool_calls"}]}"#);
+        state.apply(r#"{"choices":[],"usage":{"prompt_tokens":12,"completion_tokens":7}}"#);
+
+        let events = state.finish();
+        assert_eq!(events.len(), 3);
+        match &events[0] {
+            ResponseEvent::ToolCall(tc) => {
+                assert_eq!(tc.id, "call_a");
+                assert_eq!(tc.name, "Read");
+                assert_eq!(tc.arguments, r#"{"path":"a.rs"}"#);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        match &events[2] {
+            ResponseEvent::Done(done) => {
+                assert_eq!(done.finish_reason, FinishReason::ToolCalls);
+                assert_eq!(done.tool_calls.len(), 2);
+                assert_eq!(done.usage.total_tokens, 19);
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_convert_tool_messages() {
+        let mut assistant = Message::assistant("");
+        assistant.tool_calls = Some(vec![ToolCall {
+            id: "call_1".to_string(),
+            call_type: "function".to_string(),
+            function: FunctionCall {
+                name: "Read".to_string(),
+                arguments: "{}".to_string(),
+            },
+        }]);
+        let value = convert_message(&assistant);
+        assert!(value["content"].is_null());
+        assert_eq!(value["tool_calls"][0]["function"]["name"], "Read");
+
+        let value = convert_message(&Message::tool_result("call_1", "ok"));
+        assert_eq!(value["role"], "tool");
+        assert_eq!(value["tool_call_id"], "call_1");
+    }
+
+    #[tokio::test]
+    async fn test_streaming_completion_against_mock_server() {
+        let server = MockServer::start().await;
+        let body = sse(&[
+            r#"{"choices":[{"delta":{"content":"Hel"}}]}"#,
+            r#"{"choices":[{"delta":{"content":"lo"},"finish_reason":"stop"}]}"#,
+            r#"{"choices":[],"usage":{"prompt_tokens":3,"completion_tokens":2,"total_tokens":5}}"#,
+        ]);
+        Mock::given(method("POST"))
+            .and(path("/v1/chat/completions"))
+            .and(header("authorization", "Bearer secret"))
+            .and(header("x-team", "infra"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-type", "text/event-stream")
+                    .set_body_string(body),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OpenAiClient::new("vllm", "qwen", &format!("{}/v1/", server.uri()))
+            .with_api_key("secret")
+            .with_headers(HashMap::from([("x-team".to_string(), "infra".to_string())]));
+
+        let request = CompletionRequest {
+            messages: vec![Message::user("hi")],
+            tools: vec![ToolDefinition::function("Read", "Read a file", json!({}))],
+            ..Default::default()
+        };
+        let response = client.complete_sync(request).await.unwrap();
+
+        assert_eq!(response.message.unwrap().content.as_text(), Some("Hello"));
+        assert_eq!(response.usage.input_tokens, 3);
+        assert_eq!(response.usage.total_tokens, 5);
+    }
+
+    #[tokio::test]
+    async fn test_http_error_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(400)
+                    .set_body_json(json!({ "error": { "message": "model not loaded" } })),
+            )
+            .mount(&server)
+            .await;
+
+        let client = OpenAiClient::new("vllm", "qwen", &server.uri());
+        let err = match client.complete(CompletionRequest::default()).await {
+            Err(e) => e,
+            Ok(_) => panic!("expected error"),
+        };
+        assert!(err.to_string().contains("model not loaded"));
+    }
+}
@@ -273,7 +273,8 @@ pub struct FunctionCall {
 }
 
 /// Response event from streaming.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub enum ResponseEvent {
     /// Text delta.
     Delta(String),
@@ -288,7 +289,7 @@ pub enum ResponseEvent {
 }
 
 /// Tool call event.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ToolCallEvent {
     pub id: String,
     pub name: String,
@@ -296,7 +297,7 @@ pub struct ToolCallEvent {
 }
 
 /// Full completion response.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CompletionResponse {
     /// Generated message.
     pub message: Option<Message>,
@@ -309,7 +310,7 @@ pub struct CompletionResponse {
 }
 
 /// Token usage statistics.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct TokenUsage {
     pub input_tokens: i64,
     pub output_tokens: i64,
@@ -328,7 +329,8 @@ impl TokenUsage {
 }
 
 /// Reason for completion finishing.
-#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FinishReason {
     #[default]
     Stop,
@@ -339,7 +341,7 @@ pub enum FinishReason {
 }
 
 /// Model capabilities.
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ModelCapabilities {
     /// Whether the model supports vision (images).
     pub vision: bool,
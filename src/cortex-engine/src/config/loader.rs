@@ -722,4 +722,31 @@ model = "sync-model"
         assert_eq!(config.model, Some("gpt-4o".to_string()));
         assert_eq!(config.model_provider, Some("openai".to_string()));
     }
+
+    #[test]
+    fn test_custom_provider_selected_from_table() {
+        let config_content = r#"
+model_provider = "vllm"
+
+[providers.vllm]
+name = "Self-hosted vLLM"
+base_url = "http://localhost:8000/v1"
+default_model = "qwen2.5-coder"
+headers = { "X-Team" = "infra" }
+"#;
+        let toml = parse_config_content(config_content, ConfigFormat::Toml).unwrap();
+        let config = crate::config::Config::from_toml(
+            toml,
+            crate::config::ConfigOverrides::default(),
+            PathBuf::from("/tmp/cortex"),
+        );
+
+        assert_eq!(config.model, "qwen2.5-coder");
+        assert_eq!(config.model_provider.base_url, "http://localhost:8000/v1");
+        assert_eq!(
+            config.model_provider.api_type,
+            crate::config::ApiType::OpenAiCompatible
+        );
+        assert_eq!(config.providers["vllm"].headers["X-Team"], "infra");
+    }
 }
@@ -3,7 +3,7 @@
 //! This module provides configuration loading with support for:
 //! - Global configuration from `~/.cortex/config.toml`
 //! - Per-project configuration from `.cortex/config.toml` or `cortex.toml`
-//! - Configuration merging (global → project → CLI args)
+//! - Configuration merging (global → project → CLI args), clamped to the managed policy
 //! - Environment variable overrides (`CORTEX_CONFIG`, `CORTEX_CONFIG_DIR`)
 
 mod config_discovery;
@@ -34,6 +34,7 @@ pub use types::*;
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use cortex_network_proxy::NetworkProxyConfig;
 use cortex_protocol::{AskForApproval, SandboxPolicy};
 
 /// Main configuration struct.
@@ -91,6 +92,10 @@ pub struct Config {
     pub temperature: Option<f32>,
     /// Execution configuration for runtime behavior.
     pub execution: ExecutionConfig,
+    /// Custom providers from the `[providers]` table, keyed by provider ID.
+    pub providers: HashMap<String, CustomProviderConfig>,
+    /// Network policy for tool processes from the `[network_proxy]` table.
+    pub network_proxy: Option<NetworkProxyConfig>,
 }
 
 impl Default for Config {
@@ -122,6 +127,8 @@ impl Default for Config {
             small_model: None, // Auto-detected based on available providers
             temperature: None,
             execution: ExecutionConfig::default(),
+            providers: HashMap::new(),
+            network_proxy: None,
         }
     }
 }
@@ -133,14 +140,21 @@ impl Config {
     /// 1. Loads global config from `~/.cortex/config.toml` (or `CORTEX_CONFIG_DIR`)
     /// 2. Discovers project config (`.cortex/config.toml` or `cortex.toml`)
     /// 3. Merges them (global → project → CLI overrides)
+    /// 4. Clamps the result to the managed policy, which nothing can override
     ///
     /// Environment variables:
     /// - `CORTEX_CONFIG`: Path to a specific config file
     /// - `CORTEX_CONFIG_DIR`: Directory containing config.toml
     /// - `CORTEX_HOME`: Alias for `CORTEX_CONFIG_DIR`
     pub async fn load(overrides: ConfigOverrides) -> std::io::Result<Self> {
-        let cortex_home = find_cortex_home()?;
+        Self::load_with_home(find_cortex_home()?, overrides).await
+    }
 
+    /// Load configuration from an explicit Cortex home directory.
+    pub async fn load_with_home(
+        cortex_home: PathBuf,
+        overrides: ConfigOverrides,
+    ) -> std::io::Result<Self> {
         // Get the working directory from overrides or current dir
         let cwd = overrides
             .cwd
@@ -151,7 +165,9 @@ impl Config {
         // Load merged config (global + project)
         let (toml, _project_config_path) = load_merged_config(&cortex_home, &cwd).await?;
 
-        Ok(Self::from_toml(toml, overrides, cortex_home))
+        let mut config = Self::from_toml(toml, overrides, cortex_home);
+        crate::managed_policy::enforce(&mut config).map_err(std::io::Error::other)?;
+        Ok(config)
     }
 
     /// Load configuration synchronously with optional overrides.
@@ -168,7 +184,9 @@ impl Config {
         // Load merged config (global + project)
         let (toml, _project_config_path) = load_merged_config_sync(&cortex_home, &cwd)?;
 
-        Ok(Self::from_toml(toml, overrides, cortex_home))
+        let mut config = Self::from_toml(toml, overrides, cortex_home);
+        crate::managed_policy::enforce(&mut config).map_err(std::io::Error::other)?;
+        Ok(config)
     }
 
     /// Create config from TOML and overrides.
@@ -178,16 +196,22 @@ impl Config {
             .or_else(|| std::env::current_dir().ok())
             .unwrap_or_default();
 
-        let model = overrides
-            .model
-            .or(toml.model)
-            .unwrap_or_else(|| "claude-opus-4-5-20251101".to_string());
-
         let model_provider_id = overrides
             .model_provider
             .or(toml.model_provider)
             .unwrap_or_else(|| "cortex".to_string());
 
+        // Custom providers may declare their own default model
+        let model = overrides
+            .model
+            .or(toml.model)
+            .or_else(|| {
+                toml.providers
+                    .get(&model_provider_id)
+                    .and_then(|p| p.default_model.clone())
+            })
+            .unwrap_or_else(|| "claude-opus-4-5-20251101".to_string());
+
         let approval_policy = overrides
             .approval_policy
             .or(toml.approval_policy)
@@ -213,10 +237,21 @@ impl Config {
             })
             .unwrap_or_default();
 
+        let model_provider = toml
+            .providers
+            .get(&model_provider_id)
+            .map(|provider| ModelProviderInfo {
+                id: model_provider_id.clone(),
+                name: provider.name.clone(),
+                base_url: provider.base_url.clone(),
+                api_type: ApiType::parse(&provider.api_type).unwrap_or_default(),
+            })
+            .unwrap_or_default();
+
         Self {
             model,
             model_provider_id,
-            model_provider: ModelProviderInfo::default(),
+            model_provider,
             model_context_window: toml.model_context_window,
             model_auto_compact_token_limit: toml.model_auto_compact_token_limit,
             approval_policy,
@@ -240,6 +275,8 @@ impl Config {
             // CLI temperature override takes precedence
             temperature: overrides.temperature,
             execution: toml.execution,
+            providers: toml.providers,
+            network_proxy: toml.network_proxy,
         }
     }
 }
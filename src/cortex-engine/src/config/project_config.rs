@@ -207,6 +207,9 @@ pub fn merge_configs(global: ConfigToml, project: Option<ConfigToml>) -> ConfigT
         } else {
             global.execution
         },
+
+        // Network proxy: project overrides global
+        network_proxy: project.network_proxy.or(global.network_proxy),
     }
 }
 
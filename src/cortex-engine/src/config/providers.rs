@@ -3,6 +3,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::client::ModelCapabilities;
+
 /// Custom provider configuration.
 /// Users can define their own providers in config.toml.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -79,3 +81,44 @@ impl Default for CustomProviderConfig {
         }
     }
 }
+
+impl CustomProviderConfig {
+    /// Resolve the API key from `api_key_env`, falling back to the
+    /// conventional variable for first-party API types.
+    pub fn resolve_api_key(&self) -> Option<String> {
+        let fallback = match self.api_type.as_str() {
+            "openai" => Some("OPENAI_API_KEY"),
+            "anthropic" => Some("ANTHROPIC_API_KEY"),
+            _ => None,
+        };
+        self.api_key_env
+            .as_deref()
+            .or(fallback)
+            .and_then(|var| std::env::var(var).ok())
+            .filter(|key| !key.is_empty())
+    }
+
+    /// Look up a model entry by ID.
+    pub fn model(&self, id: &str) -> Option<&CustomModelConfig> {
+        self.models.iter().find(|m| m.id == id)
+    }
+
+    /// Capabilities for `model`, layered over `defaults`, if the model is declared.
+    pub fn model_capabilities(
+        &self,
+        model: &str,
+        defaults: &ModelCapabilities,
+    ) -> Option<ModelCapabilities> {
+        let entry = self.model(model)?;
+        Some(ModelCapabilities {
+            vision: entry.supports_vision,
+            tools: entry.supports_tools,
+            reasoning: defaults.reasoning,
+            context_window: entry
+                .context_window
+                .map(|w| w.clamp(0, u32::MAX as i64) as u32)
+                .unwrap_or(defaults.context_window),
+            max_output_tokens: defaults.max_output_tokens,
+        })
+    }
+}
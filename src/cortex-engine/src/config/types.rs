@@ -3,6 +3,7 @@
 use std::collections::HashMap;
 use std::path::PathBuf;
 
+use cortex_network_proxy::NetworkProxyConfig;
 use cortex_protocol::AskForApproval;
 use serde::{Deserialize, Serialize};
 
@@ -123,6 +124,8 @@ pub struct ConfigToml {
     /// Execution configuration for runtime behavior.
     #[serde(default)]
     pub execution: ExecutionConfig,
+    /// Network policy for tool processes, enforced by a local proxy.
+    pub network_proxy: Option<NetworkProxyConfig>,
 }
 
 /// Profile configuration - named presets.
@@ -271,6 +274,18 @@ pub enum ApiType {
     OpenAiCompatible,
 }
 
+impl ApiType {
+    /// Parse the `api_type` string used in `[providers]` config entries.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "openai" => Some(Self::OpenAi),
+            "anthropic" => Some(Self::Anthropic),
+            "openai-compatible" | "openai_compatible" => Some(Self::OpenAiCompatible),
+            _ => None,
+        }
+    }
+}
+
 /// Feature flags.
 #[derive(Debug, Clone, Default)]
 pub struct Features {
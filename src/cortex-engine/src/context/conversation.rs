@@ -4,7 +4,8 @@ use std::collections::HashMap;
 
 use serde::Serialize;
 
-use crate::client::types::{Message, MessageContent, MessageRole};
+use crate::client::types::{Message, MessageRole};
+use crate::tokenizer::TokenCounter;
 
 /// A conversation with message history.
 #[derive(Debug, Clone)]
@@ -385,30 +386,7 @@ impl ConversationBuilder {
 
 /// Estimate token count for a message.
 fn estimate_tokens(message: &Message) -> u32 {
-    let text = match &message.content {
-        MessageContent::Text(s) => s.as_str(),
-        MessageContent::Parts(parts) => {
-            // Just count text parts for now
-            return parts
-                .iter()
-                .filter_map(|p| {
-                    match p {
-                        crate::client::types::ContentPart::Text { text, .. } => {
-                            Some(text.len() as u32 / 4)
-                        }
-                        _ => Some(100), // Estimate for images/documents
-                    }
-                })
-                .sum();
-        }
-        MessageContent::ToolResult { content, .. } => content.as_str(),
-        MessageContent::ToolCalls(calls) => {
-            return calls.len() as u32 * 50; // Rough estimate
-        }
-    };
-
-    // Rough estimate: ~4 characters per token
-    (text.len() as u32 / 4) + 4 // +4 for role overhead
+    TokenCounter::default().count_message(message)
 }
 
 #[cfg(test)]
@@ -460,4 +438,3 @@ mod tests {
         assert_eq!(fork.len(), 2);
     }
 }
-
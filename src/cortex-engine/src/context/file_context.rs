@@ -449,8 +449,7 @@ fn detect_language(path: &Path) -> Option<String> {
 
 /// Estimate token count for content.
 fn estimate_tokens(content: &str) -> u32 {
-    // Rough estimate: ~4 characters per token
-    (content.len() as u32 / 4) + 1
+    crate::tokenizer::count_tokens(content) + 1
 }
 
 #[cfg(test)]
@@ -367,7 +367,7 @@ Provide specific, actionable feedback with examples."#;
 
 /// Estimate token count.
 fn estimate_tokens(text: &str) -> u32 {
-    (text.len() as u32 / 4) + 1
+    crate::tokenizer::count_tokens(text) + 1
 }
 
 #[cfg(test)]
@@ -76,6 +76,8 @@ impl ToolsConfig {
                 "Create".to_string(),
                 "Edit".to_string(),
                 "ApplyPatch".to_string(),
+                "LspRename".to_string(),
+                "LspCodeAction".to_string(),
             ],
             "execute" => vec!["Execute".to_string()],
             "web" => vec!["WebSearch".to_string(), "FetchUrl".to_string()],
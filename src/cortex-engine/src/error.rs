@@ -456,6 +456,12 @@ impl From<cortex_protocol::CortexErrorInfo> for CortexError {
     }
 }
 
+impl From<rusqlite::Error> for CortexError {
+    fn from(e: rusqlite::Error) -> Self {
+        Self::Internal(format!("Database error: {}", e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
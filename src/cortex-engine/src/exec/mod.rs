@@ -7,6 +7,7 @@ pub use output::OutputCapture;
 pub use runner::{
     ExecOptions, ExecOutput, OutputChunk, execute_command, execute_command_streaming,
 };
+pub(crate) use runner::{audit_command, inherited_environment};
 
 use std::time::Duration;
 
@@ -1,7 +1,7 @@
 //! Command execution.
 
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::time::{Duration, Instant};
 
@@ -12,6 +12,7 @@ use tokio::sync::mpsc;
 use cortex_protocol::SandboxPolicy;
 
 use super::{DEFAULT_TIMEOUT, MAX_OUTPUT_SIZE};
+use crate::audit::{self, AuditEvent, AuditKind};
 use crate::error::Result;
 
 /// Output chunk from streaming execution
@@ -102,7 +103,16 @@ fn validate_cwd(cwd: &PathBuf) -> Result<()> {
 }
 
 /// Execute a command.
+///
+/// The command and its outcome are recorded in the audit log.
 pub async fn execute_command(command: &[String], options: ExecOptions) -> Result<ExecOutput> {
+    let cwd = options.cwd.clone();
+    let result = run_command(command, options).await;
+    audit_command(command, &cwd, &result).await;
+    result
+}
+
+async fn run_command(command: &[String], options: ExecOptions) -> Result<ExecOutput> {
     if command.is_empty() {
         return Ok(ExecOutput {
             stdout: String::new(),
@@ -236,10 +246,23 @@ fn truncate_output(bytes: &[u8]) -> String {
 /// Execute a command with streaming output.
 /// Sends output chunks via the provided sender as they arrive.
 /// Output is interleaved in the order it is received, preserving stdout/stderr ordering.
+///
+/// The command and its outcome are recorded in the audit log.
 pub async fn execute_command_streaming(
     command: &[String],
     options: ExecOptions,
     chunk_sender: mpsc::Sender<OutputChunk>,
+) -> Result<ExecOutput> {
+    let cwd = options.cwd.clone();
+    let result = run_command_streaming(command, options, chunk_sender).await;
+    audit_command(command, &cwd, &result).await;
+    result
+}
+
+async fn run_command_streaming(
+    command: &[String],
+    options: ExecOptions,
+    chunk_sender: mpsc::Sender<OutputChunk>,
 ) -> Result<ExecOutput> {
     if command.is_empty() {
         return Ok(ExecOutput {
@@ -434,21 +457,38 @@ pub async fn execute_command_streaming(
     }
 }
 
+/// Record a command run, wherever it ran, in the audit log.
+pub(crate) async fn audit_command(command: &[String], cwd: &Path, result: &Result<ExecOutput>) {
+    let (outcome, details) = match result {
+        Ok(output) => (
+            if output.timed_out {
+                "timed out".to_string()
+            } else {
+                format!("exit {}", output.exit_code)
+            },
+            serde_json::json!({
+                "cwd": cwd,
+                "exit_code": output.exit_code,
+                "duration_ms": output.duration.as_millis() as u64,
+            }),
+        ),
+        Err(e) => (
+            format!("error: {}", audit::summarize(&e.to_string())),
+            serde_json::json!({ "cwd": cwd }),
+        ),
+    };
+    let event = AuditEvent::new(AuditKind::Command, "exec", command.join(" "), outcome)
+        .with_details(details);
+    audit::record(event).await;
+}
+
 /// Build a safe environment for command execution.
 /// - Inherits ALL environment variables from parent process
 /// - Excludes variables containing sensitive patterns (KEY, SECRET, TOKEN, etc.)
 /// - Forces non-interactive mode for common tools
 /// - Applies any custom overrides from options.env
 fn build_safe_environment(overrides: &HashMap<String, String>) -> HashMap<String, String> {
-    let mut env: HashMap<String, String> = std::env::vars()
-        .filter(|(key, _)| {
-            // Exclude variables with sensitive patterns (case-insensitive)
-            let key_upper = key.to_uppercase();
-            !SENSITIVE_PATTERNS
-                .iter()
-                .any(|pattern| key_upper.contains(pattern))
-        })
-        .collect();
+    let mut env = inherited_environment();
 
     // Force non-interactive mode for common tools
     // This prevents commands from hanging waiting for user input
@@ -474,6 +514,19 @@ fn build_safe_environment(overrides: &HashMap<String, String>) -> HashMap<String
     env
 }
 
+/// The parent environment without variables that look like secrets.
+pub(crate) fn inherited_environment() -> HashMap<String, String> {
+    std::env::vars()
+        .filter(|(key, _)| {
+            // Exclude variables with sensitive patterns (case-insensitive)
+            let key_upper = key.to_uppercase();
+            !SENSITIVE_PATTERNS
+                .iter()
+                .any(|pattern| key_upper.contains(pattern))
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
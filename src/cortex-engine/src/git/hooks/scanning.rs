@@ -9,6 +9,7 @@ use regex::Regex;
 
 use crate::error::{CortexError, Result};
 use crate::git_info::GitDiff;
+use crate::secrets::secret_patterns;
 
 use super::config::PatternCheck;
 use super::results::{HookIssue, IssueCategory, IssueSeverity};
@@ -18,28 +19,6 @@ use super::utils::should_ignore_path;
 pub async fn scan_secrets(repo_path: &Path, diff: &GitDiff) -> Result<Vec<HookIssue>> {
     let mut issues = Vec::new();
 
-    let secret_patterns: Vec<(&str, &str)> = vec![
-        ("AWS Access Key", r"AKIA[0-9A-Z]{16}"),
-        (
-            "AWS Secret Key",
-            r#"(?i)aws(.{0,20})?['"][0-9a-zA-Z/+]{40}['"]"#,
-        ),
-        ("GitHub Token", r"ghp_[0-9a-zA-Z]{36}"),
-        ("GitLab Token", r"glpat-[0-9a-zA-Z_-]{20}"),
-        (
-            "Generic API Key",
-            r#"(?i)(api[_-]?key|apikey)['"]?\s*[:=]\s*['"][0-9a-zA-Z]{20,}['"]"#,
-        ),
-        (
-            "Private Key",
-            r"-----BEGIN (RSA |DSA |EC )?PRIVATE KEY-----",
-        ),
-        (
-            "Password Assignment",
-            r#"(?i)(password|passwd|pwd)['"]?\s*[:=]\s*['"][^'"]{8,}['"]"#,
-        ),
-    ];
-
     for file in &diff.files {
         // Skip binary files and common non-code files
         if should_ignore_path(&file.path) {
@@ -57,12 +36,10 @@ pub async fn scan_secrets(repo_path: &Path, diff: &GitDiff) -> Result<Vec<HookIs
             Err(_) => continue, // Skip binary files
         };
 
-        for (name, pattern) in &secret_patterns {
-            if let Ok(re) = Regex::new(pattern) {
-                for (line_num, line) in content.lines().enumerate() {
-                    if re.is_match(line) {
-                        issues.push(HookIssue::secret(&file.path, line_num + 1, name));
-                    }
+        for (name, re) in secret_patterns() {
+            for (line_num, line) in content.lines().enumerate() {
+                if re.is_match(line) {
+                    issues.push(HookIssue::secret(&file.path, line_num + 1, name));
                 }
             }
         }
@@ -2,25 +2,26 @@
 //!
 //! Connects the cortex-lsp crate to provide diagnostics in tool results.
 
-use cortex_lsp::{Diagnostic, DiagnosticSeverity, LspManager};
+use cortex_lsp::{Diagnostic, DiagnosticSeverity, LspError, LspManager};
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::debug;
 
-// Type aliases for LSP results (using Value for flexibility)
-pub type Location = Value;
-pub type DocumentSymbol = Value;
-pub type WorkspaceSymbol = Value;
-pub type CallHierarchyItem = Value;
-pub type CallHierarchyIncomingCall = Value;
-pub type CallHierarchyOutgoingCall = Value;
+pub use cortex_lsp::lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, Location, Position,
+    Range, WorkspaceEdit,
+};
+
+// Type aliases for LSP results
+pub type DocumentSymbol = cortex_lsp::lsp_types::SymbolInformation;
+pub type WorkspaceSymbol = cortex_lsp::lsp_types::SymbolInformation;
+pub type CodeAction = cortex_lsp::lsp_types::CodeActionOrCommand;
 pub type CompletionItem = Value;
 pub type SignatureHelp = Value;
-pub type WorkspaceEdit = Value;
-pub type CodeAction = Value;
 
 /// LSP integration for the agent.
 pub struct LspIntegration {
@@ -87,6 +88,36 @@ impl LspIntegration {
         results
     }
 
+    /// Tell the language server a file was written, so its diagnostics
+    /// follow the new content. Failures are logged, never returned: a write
+    /// must not fail because a language server is unhappy.
+    pub async fn did_write(&self, path: &Path, content: &str) {
+        let guard = self.manager.read().await;
+        if let Some(ref manager) = *guard
+            && let Err(e) = manager.did_write(path, content).await
+        {
+            debug!("Failed to sync {} with LSP: {}", path.display(), e);
+        }
+    }
+
+    /// Wait for diagnostics describing the current content of a file.
+    ///
+    /// Returns `None` if the server did not publish fresh diagnostics within
+    /// `timeout`.
+    pub async fn wait_for_diagnostics(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<Option<Vec<Diagnostic>>> {
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        match manager.wait_for_diagnostics(path, timeout).await {
+            Ok(diagnostics) => Ok(Some(diagnostics)),
+            Err(LspError::Timeout) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
     /// Get hover information for a position.
     pub async fn hover(
         &self,
@@ -105,90 +136,108 @@ impl LspIntegration {
         }
     }
 
-    /// Go to definition (stub - not yet implemented in cortex-lsp).
+    /// Go to definition.
     pub async fn go_to_definition(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<Location>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .goto_definition(Path::new(path), line, column)
+            .await?)
     }
 
-    /// Find references (stub - not yet implemented in cortex-lsp).
+    /// Find references, including the declaration.
     pub async fn find_references(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<Location>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .find_references(Path::new(path), line, column, true)
+            .await?)
     }
 
-    /// Get document symbols (stub - not yet implemented in cortex-lsp).
-    pub async fn document_symbols(&self, _path: &str) -> anyhow::Result<Vec<DocumentSymbol>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+    /// Get document symbols, flattened in document order.
+    pub async fn document_symbols(&self, path: &str) -> anyhow::Result<Vec<DocumentSymbol>> {
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager.document_symbols(Path::new(path)).await?)
     }
 
-    /// Search workspace symbols (stub - not yet implemented in cortex-lsp).
-    pub async fn workspace_symbols(&self, _query: &str) -> anyhow::Result<Vec<WorkspaceSymbol>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+    /// Search workspace symbols.
+    ///
+    /// `path` selects the language server; without it every running server is asked.
+    pub async fn workspace_symbols(
+        &self,
+        query: &str,
+        path: Option<&Path>,
+    ) -> anyhow::Result<Vec<WorkspaceSymbol>> {
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager.workspace_symbols(query, path).await?)
     }
 
-    /// Go to implementation (stub - not yet implemented in cortex-lsp).
+    /// Go to implementation.
     pub async fn go_to_implementation(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<Location>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .goto_implementation(Path::new(path), line, column)
+            .await?)
     }
 
-    /// Prepare call hierarchy (stub - not yet implemented in cortex-lsp).
+    /// Prepare call hierarchy.
     pub async fn prepare_call_hierarchy(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<CallHierarchyItem>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .prepare_call_hierarchy(Path::new(path), line, column)
+            .await?)
     }
 
-    /// Get incoming calls (stub - not yet implemented in cortex-lsp).
+    /// Get incoming calls.
     pub async fn incoming_calls(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<CallHierarchyIncomingCall>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .incoming_calls(Path::new(path), line, column)
+            .await?)
     }
 
-    /// Get outgoing calls (stub - not yet implemented in cortex-lsp).
+    /// Get outgoing calls.
     pub async fn outgoing_calls(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
+        path: &str,
+        line: u32,
+        column: u32,
     ) -> anyhow::Result<Vec<CallHierarchyOutgoingCall>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .outgoing_calls(Path::new(path), line, column)
+            .await?)
     }
 
     /// Get completions (stub - not yet implemented in cortex-lsp).
@@ -215,29 +264,32 @@ impl LspIntegration {
         Ok(None)
     }
 
-    /// Rename symbol (stub - not yet implemented in cortex-lsp).
+    /// Compute the edit for renaming a symbol. The edit is not applied.
     pub async fn rename(
         &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
-        _new_name: &str,
-    ) -> anyhow::Result<WorkspaceEdit> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Value::Null)
+        path: &str,
+        line: u32,
+        column: u32,
+        new_name: &str,
+    ) -> anyhow::Result<Option<WorkspaceEdit>> {
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager
+            .rename(Path::new(path), line, column, new_name)
+            .await?)
     }
 
-    /// Get code actions (stub - not yet implemented in cortex-lsp).
-    pub async fn code_actions(
-        &self,
-        _path: &str,
-        _line: u32,
-        _column: u32,
-    ) -> anyhow::Result<Vec<CodeAction>> {
-        // LSP feature not yet implemented - returns empty result
-        // This will be implemented in a future version of cortex-lsp
-        Ok(Vec::new())
+    /// Get code actions for a range.
+    pub async fn code_actions(&self, path: &str, range: Range) -> anyhow::Result<Vec<CodeAction>> {
+        let guard = self.manager.read().await;
+        let manager = Self::running(&guard)?;
+        Ok(manager.code_actions(Path::new(path), range).await?)
+    }
+
+    fn running(manager: &Option<LspManager>) -> anyhow::Result<&LspManager> {
+        manager
+            .as_ref()
+            .ok_or_else(|| anyhow::anyhow!("LSP is not running or not enabled"))
     }
 
     /// Format diagnostics for display in tool results.
@@ -127,8 +127,10 @@ pub mod tools;
 
 // === EXECUTION & SAFETY ===
 pub mod approval;
+pub mod audit;
 pub mod command_executor;
 pub mod exec;
+pub mod managed_policy;
 pub mod permission;
 pub mod safety;
 pub mod sandbox;
@@ -249,6 +251,7 @@ pub use conversation::ConversationManager;
 pub use error::{CortexError, Result};
 pub use message_parts::MessagePartsBuilder;
 pub use safety::{RiskLevel, SafetyAnalysis, analyze_command};
+pub use managed_policy::ManagedPolicy;
 pub use session::{Session, SessionHandle, SessionInfo, list_sessions};
 
 // Auth re-exports
@@ -0,0 +1,362 @@
+//! Explaining how a command is decided.
+//!
+//! Layers are consulted from the top down and the first one with an opinion
+//! decides:
+//!
+//! 1. the managed policy,
+//! 2. permissions stored from earlier prompts (`~/.cortex/permissions.json`),
+//! 3. project `[permission.bash]` patterns,
+//! 4. user `[permission.bash]` patterns,
+//! 5. the built-in risk analysis under the effective approval policy.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use super::ManagedPolicy;
+use crate::config::{
+    ConfigToml, PermissionLevel, find_cortex_home, find_project_config, get_config_path,
+    load_config_sync, load_project_config, merge_configs,
+};
+use crate::permission::{PermissionResponse, PermissionStorage, glob_match};
+use crate::safety::{analyze_command, requires_approval};
+
+/// A layer of policy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PolicyLayer {
+    /// The administrator-installed managed policy.
+    Managed,
+    /// Permissions remembered from earlier prompts.
+    StoredPermissions,
+    /// Project configuration.
+    Project,
+    /// User configuration.
+    User,
+    /// Built-in risk analysis.
+    BuiltIn,
+}
+
+impl PolicyLayer {
+    /// Human-readable name.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Managed => "managed",
+            Self::StoredPermissions => "stored",
+            Self::Project => "project",
+            Self::User => "user",
+            Self::BuiltIn => "built-in",
+        }
+    }
+}
+
+/// What one layer says about a command.
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerVerdict {
+    /// The layer.
+    pub layer: PolicyLayer,
+    /// File backing the layer, if any.
+    pub source: Option<PathBuf>,
+    /// The layer's decision, `None` when it has no opinion.
+    pub decision: Option<PermissionLevel>,
+    /// Why the layer decided as it did.
+    pub reason: String,
+}
+
+/// How a command is decided, layer by layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct PolicyExplanation {
+    /// The command, as matched against patterns.
+    pub command: String,
+    /// Every layer, from highest to lowest precedence.
+    pub layers: Vec<LayerVerdict>,
+    /// Layer that decided.
+    pub decided_by: PolicyLayer,
+    /// Final decision.
+    pub decision: PermissionLevel,
+}
+
+/// Files each policy layer is read from.
+#[derive(Debug, Clone)]
+pub struct PolicySources {
+    /// The managed policy.
+    pub managed: ManagedPolicy,
+    /// Stored permissions file.
+    pub permissions: PathBuf,
+    /// Project configuration file, if one was found.
+    pub project_config: Option<PathBuf>,
+    /// Cortex home, holding the user configuration.
+    pub cortex_home: PathBuf,
+    /// Working directory commands run in.
+    pub cwd: PathBuf,
+}
+
+impl PolicySources {
+    /// Discover the sources that apply to commands run in `cwd`.
+    pub fn discover(cwd: &Path) -> crate::Result<Self> {
+        Ok(Self {
+            managed: ManagedPolicy::system()?.clone(),
+            permissions: PermissionStorage::default_store_path(),
+            project_config: find_project_config(cwd),
+            cortex_home: find_cortex_home()?,
+            cwd: cwd.to_path_buf(),
+        })
+    }
+}
+
+/// Explain how a command would be decided.
+pub async fn explain_command(command: &[String], sources: &PolicySources) -> PolicyExplanation {
+    let command_line = command.join(" ");
+    let user_path = get_config_path(&sources.cortex_home);
+
+    let user = load_config_sync(&sources.cortex_home);
+    let project = sources
+        .project_config
+        .as_deref()
+        .map(|path| load_project_config(path).map_err(|e| e.to_string()));
+
+    let mut layers = vec![
+        managed_verdict(&sources.managed, command),
+        stored_verdict(&sources.permissions, &command_line).await,
+    ];
+    layers.push(match &project {
+        None => LayerVerdict {
+            layer: PolicyLayer::Project,
+            source: None,
+            decision: None,
+            reason: "no project configuration".to_string(),
+        },
+        Some(project) => config_verdict(
+            PolicyLayer::Project,
+            sources.project_config.clone(),
+            project.as_ref().map_err(String::clone),
+            &command_line,
+        ),
+    });
+    layers.push(config_verdict(
+        PolicyLayer::User,
+        Some(user_path),
+        user.as_ref().map_err(|e| e.to_string()),
+        &command_line,
+    ));
+
+    let merged = merge_configs(
+        user.unwrap_or_default(),
+        project.and_then(std::result::Result::ok),
+    );
+    layers.push(built_in_verdict(
+        &sources.managed,
+        &merged,
+        command,
+        &sources.cwd,
+    ));
+
+    let (decided_by, decision) = layers
+        .iter()
+        .find_map(|l| l.decision.map(|d| (l.layer, d)))
+        .unwrap_or((PolicyLayer::BuiltIn, PermissionLevel::Ask));
+
+    PolicyExplanation {
+        command: command_line,
+        layers,
+        decided_by,
+        decision,
+    }
+}
+
+fn managed_verdict(policy: &ManagedPolicy, command: &[String]) -> LayerVerdict {
+    let (decision, reason) = if !policy.is_installed() {
+        (None, "no managed policy installed".to_string())
+    } else if let Some(pattern) = policy.denied_command_pattern(command) {
+        (
+            Some(PermissionLevel::Deny),
+            format!("matches denied_commands pattern '{pattern}'"),
+        )
+    } else {
+        (None, "no denied_commands pattern matches".to_string())
+    };
+    LayerVerdict {
+        layer: PolicyLayer::Managed,
+        source: policy.source.clone(),
+        decision,
+        reason,
+    }
+}
+
+async fn stored_verdict(path: &Path, command: &str) -> LayerVerdict {
+    let storage = PermissionStorage::with_path(path);
+    let (decision, reason) = match storage.load().await {
+        Err(e) => (None, format!("failed to load: {e}")),
+        Ok(()) => match storage
+            .list_for_tool("bash")
+            .await
+            .into_iter()
+            .find(|p| glob_match(&p.pattern, command))
+        {
+            Some(permission) => (
+                Some(match permission.response {
+                    PermissionResponse::Allow => PermissionLevel::Allow,
+                    PermissionResponse::Ask => PermissionLevel::Ask,
+                    PermissionResponse::Deny => PermissionLevel::Deny,
+                }),
+                format!("stored permission for '{}'", permission.pattern),
+            ),
+            None => (None, "no stored permission matches".to_string()),
+        },
+    };
+    LayerVerdict {
+        layer: PolicyLayer::StoredPermissions,
+        source: Some(path.to_path_buf()),
+        decision,
+        reason,
+    }
+}
+
+fn config_verdict(
+    layer: PolicyLayer,
+    source: Option<PathBuf>,
+    config: std::result::Result<&ConfigToml, String>,
+    command: &str,
+) -> LayerVerdict {
+    let (decision, reason) = match config {
+        Err(e) => (None, format!("failed to load: {e}")),
+        Ok(config) => match most_specific_match(&config.permission.bash, command) {
+            Some((pattern, level)) => (
+                Some(level),
+                format!("[permission.bash] pattern '{pattern}'"),
+            ),
+            None => (None, "no [permission.bash] pattern matches".to_string()),
+        },
+    };
+    LayerVerdict {
+        layer,
+        source,
+        decision,
+        reason,
+    }
+}
+
+/// The matching pattern with the fewest wildcards, as the permission
+/// manager orders them.
+fn most_specific_match<'a>(
+    patterns: &'a HashMap<String, PermissionLevel>,
+    command: &str,
+) -> Option<(&'a str, PermissionLevel)> {
+    patterns
+        .iter()
+        .filter(|(pattern, _)| glob_match(pattern, command))
+        .min_by_key(|(pattern, _)| {
+            (
+                pattern.matches(['*', '?']).count(),
+                std::cmp::Reverse(pattern.len()),
+            )
+        })
+        .map(|(pattern, level)| (pattern.as_str(), *level))
+}
+
+fn built_in_verdict(
+    managed: &ManagedPolicy,
+    config: &ConfigToml,
+    command: &[String],
+    cwd: &Path,
+) -> LayerVerdict {
+    let configured = config.approval_policy.unwrap_or_default();
+    let approval_policy = managed.clamp_approval_policy(configured);
+    let analysis = analyze_command(command, cwd);
+
+    let decision = if requires_approval(&analysis, &approval_policy) {
+        PermissionLevel::Ask
+    } else {
+        PermissionLevel::Allow
+    };
+    let mut reason = format!(
+        "{:?} risk ({}) under approval policy '{approval_policy}'",
+        analysis.risk_level, analysis.reason
+    );
+    if approval_policy != configured {
+        reason.push_str(&format!(
+            ", capped from '{configured}' by managed max_autonomy"
+        ));
+    }
+
+    LayerVerdict {
+        layer: PolicyLayer::BuiltIn,
+        source: None,
+        decision: Some(decision),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sources(dir: &Path, managed: &str) -> PolicySources {
+        let mut managed: ManagedPolicy = toml::from_str(managed).unwrap();
+        managed.source = Some(dir.join("managed.toml"));
+        PolicySources {
+            managed,
+            permissions: dir.join("permissions.json"),
+            project_config: Some(dir.join("project.toml")),
+            cortex_home: dir.join("home"),
+            cwd: dir.to_path_buf(),
+        }
+    }
+
+    fn cmd(line: &str) -> Vec<String> {
+        line.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[tokio::test]
+    async fn test_first_layer_with_an_opinion_decides() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("home")).unwrap();
+        std::fs::write(
+            dir.path().join("home/config.toml"),
+            "[permission.bash]\n\"git *\" = \"allow\"\n\"curl *\" = \"allow\"\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("project.toml"),
+            "[permission.bash]\n\"git push *\" = \"ask\"\n",
+        )
+        .unwrap();
+        let sources = sources(dir.path(), r#"denied_commands = ["curl *"]"#);
+
+        // The managed layer cannot be overridden by user config.
+        let explanation = explain_command(&cmd("curl https://example.com"), &sources).await;
+        assert_eq!(explanation.decided_by, PolicyLayer::Managed);
+        assert_eq!(explanation.decision, PermissionLevel::Deny);
+        assert_eq!(explanation.layers.len(), 5);
+
+        let explanation = explain_command(&cmd("git push origin main"), &sources).await;
+        assert_eq!(explanation.decided_by, PolicyLayer::Project);
+        assert_eq!(explanation.decision, PermissionLevel::Ask);
+
+        let explanation = explain_command(&cmd("git status"), &sources).await;
+        assert_eq!(explanation.decided_by, PolicyLayer::User);
+        assert_eq!(explanation.decision, PermissionLevel::Allow);
+
+        let explanation = explain_command(&cmd("ls -la"), &sources).await;
+        assert_eq!(explanation.decided_by, PolicyLayer::BuiltIn);
+    }
+
+    #[tokio::test]
+    async fn test_built_in_layer_reports_autonomy_cap() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("home")).unwrap();
+        std::fs::write(
+            dir.path().join("home/config.toml"),
+            "approval_policy = \"never\"\n",
+        )
+        .unwrap();
+        let sources = sources(dir.path(), r#"max_autonomy = "manual""#);
+
+        let explanation = explain_command(&cmd("rm -rf build"), &sources).await;
+        assert_eq!(explanation.decided_by, PolicyLayer::BuiltIn);
+        assert_eq!(explanation.decision, PermissionLevel::Ask);
+        let built_in = explanation.layers.last().unwrap();
+        assert!(built_in.reason.contains("capped from 'never'"));
+    }
+}
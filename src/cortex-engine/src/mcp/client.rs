@@ -2,29 +2,31 @@
 //!
 //! Supports:
 //! - Stdio transport (local processes)
-//! - HTTP/SSE transport (remote servers)
+//! - HTTP/SSE transport (legacy remote servers)
+//! - Streamable HTTP and WebSocket transports (remote servers, with OAuth)
 //! - Tool listing and execution
 //! - Resource reading
 //! - Prompt retrieval
+//! - Server-initiated requests (sampling, roots, elicitation)
 
-use std::io::{BufRead, BufReader, Write};
-use std::process::{Child, Command, Stdio};
-use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Arc;
 
 use anyhow::{Context, Result, anyhow};
-use serde::Deserialize;
-use serde_json::{Value, json};
-use tokio::sync::{Mutex, RwLock};
-use tracing::{error, info, warn};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{error, info};
 
 use cortex_mcp_types::{
-    CallToolParams, CallToolResult, InitializeParams, InitializeResult, JSONRPC_VERSION,
-    JsonRpcRequest, JsonRpcResponse, ListResourcesResult, ListToolsResult, ReadResourceParams,
-    ReadResourceResult, Resource, Tool, methods,
+    CallToolParams, CallToolResult, ClientCapabilities, InitializeParams, InitializeResult,
+    ReadResourceParams, ReadResourceResult, Resource, Tool,
 };
 
-use cortex_common::create_default_client;
+use cortex_mcp_client::{
+    HttpTransport, NoServerRequests, ReconnectConfig, ServerRequestHandler, StdioTransport,
+    StreamableHttpTransport, Transport, WebSocketTransport,
+};
 
+use super::oauth::StoredTokenProvider;
 use super::{McpServerConfig, TransportType};
 
 /// MCP client connection state.
@@ -48,40 +50,49 @@ pub struct McpClient {
     state: RwLock<ConnectionState>,
     /// Server capabilities (after initialization).
     server_info: RwLock<Option<InitializeResult>>,
-    /// Request ID counter.
-    request_id: AtomicI64,
-    /// Stdio transport (process handle).
-    stdio_process: Mutex<Option<StdioTransport>>,
-    /// HTTP/SSE transport.
-    http_client: reqwest::Client,
+    /// Transport to the server, while connected.
+    transport: RwLock<Option<Arc<dyn Transport>>>,
+    /// Transport to use instead of one built from the configuration.
+    preset_transport: Option<Arc<dyn Transport>>,
     /// Cached tools.
     cached_tools: RwLock<Vec<Tool>>,
     /// Cached resources.
     cached_resources: RwLock<Vec<Resource>>,
-}
-
-/// Stdio transport for local MCP servers.
-struct StdioTransport {
-    child: Child,
-    // We'll use synchronous I/O for simplicity
+    /// Handler for requests initiated by the server.
+    request_handler: Arc<dyn ServerRequestHandler>,
 }
 
 impl McpClient {
     /// Create a new MCP client for the given server configuration.
     pub fn new(config: McpServerConfig) -> Self {
-        let http_client = create_default_client().expect("HTTP client");
         Self {
             config,
             state: RwLock::new(ConnectionState::Disconnected),
             server_info: RwLock::new(None),
-            request_id: AtomicI64::new(1),
-            stdio_process: Mutex::new(None),
-            http_client,
+            transport: RwLock::new(None),
+            preset_transport: None,
             cached_tools: RwLock::new(Vec::new()),
             cached_resources: RwLock::new(Vec::new()),
+            request_handler: Arc::new(NoServerRequests),
         }
     }
 
+    /// Set the handler for sampling, roots and elicitation requests.
+    ///
+    /// Its capabilities are advertised when connecting.
+    pub fn with_request_handler(mut self, handler: Arc<dyn ServerRequestHandler>) -> Self {
+        self.request_handler = handler;
+        self
+    }
+
+    /// Connect through `transport` instead of the configured one.
+    ///
+    /// Used for servers running in the same process.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.preset_transport = Some(transport);
+        self
+    }
+
     /// Get the server name.
     pub fn name(&self) -> &str {
         &self.config.name
@@ -122,10 +133,16 @@ impl McpClient {
             *state = ConnectionState::Connecting;
         }
 
-        let result = match self.config.transport {
-            TransportType::Stdio => self.connect_stdio().await,
-            TransportType::Sse | TransportType::Http => self.connect_http().await,
-            TransportType::WebSocket => Err(anyhow!("WebSocket transport not yet implemented")),
+        let result = match self.preset_transport.clone() {
+            Some(transport) => Ok(transport),
+            None => self.build_transport(),
+        };
+        let result = match result {
+            Ok(transport) => {
+                *self.transport.write().await = Some(transport);
+                self.initialize().await
+            }
+            Err(e) => Err(e),
         };
 
         match result {
@@ -171,23 +188,62 @@ impl McpClient {
         "SHELL",        // Shell information
     ];
 
-    /// Connect using stdio transport.
-    async fn connect_stdio(&self) -> Result<()> {
-        let mut cmd = Command::new(&self.config.command);
-        cmd.args(&self.config.args)
-            .stdin(Stdio::piped())
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
+    /// Build the transport for the configured server.
+    fn build_transport(&self) -> Result<Arc<dyn Transport>> {
+        let reconnect = if self.config.restart_on_failure {
+            ReconnectConfig::new().with_max_attempts(self.config.max_restarts)
+        } else {
+            ReconnectConfig::disabled()
+        };
 
+        let transport: Arc<dyn Transport> = match self.config.transport {
+            TransportType::Stdio => Arc::new(self.stdio_transport().with_reconnect(reconnect)),
+            TransportType::Sse => {
+                let url = self.server_url()?;
+                let auth = Arc::new(StoredTokenProvider::new(&self.config.name, url.as_str()));
+                Arc::new(
+                    HttpTransport::new(url)
+                        .with_auth(auth)
+                        .with_reconnect(reconnect),
+                )
+            }
+            TransportType::Http => {
+                let url = self.server_url()?;
+                let auth = Arc::new(StoredTokenProvider::new(&self.config.name, url.as_str()));
+                Arc::new(
+                    StreamableHttpTransport::new(url)
+                        .with_auth(auth)
+                        .with_reconnect(reconnect)
+                        .with_request_handler(self.request_handler.clone()),
+                )
+            }
+            TransportType::WebSocket => {
+                let url = self.server_url()?;
+                let auth = Arc::new(StoredTokenProvider::new(&self.config.name, url.as_str()));
+                Arc::new(
+                    WebSocketTransport::new(url)
+                        .with_auth(auth)
+                        .with_reconnect(reconnect)
+                        .with_request_handler(self.request_handler.clone()),
+                )
+            }
+        };
+        Ok(transport)
+    }
+
+    /// Build the stdio transport for a local server.
+    fn stdio_transport(&self) -> StdioTransport {
         // Build a filtered environment to prevent leaking sensitive data to MCP servers.
         // Start with a clean environment and selectively add safe variables.
-        cmd.env_clear();
+        let mut transport = StdioTransport::new(&self.config.command, self.config.args.clone())
+            .with_clean_env()
+            .with_request_handler(self.request_handler.clone());
 
         // Add filtered environment variables from the parent process
         for (key, value) in std::env::vars() {
             // Check if explicitly allowed
             if Self::ALLOWED_ENV_VARS.iter().any(|&allowed| key == allowed) {
-                cmd.env(&key, &value);
+                transport = transport.with_env(key, value);
                 continue;
             }
 
@@ -198,68 +254,92 @@ impl McpClient {
                 .any(|pattern| key_upper.contains(pattern));
 
             if !is_sensitive {
-                cmd.env(&key, &value);
+                transport = transport.with_env(key, value);
             }
         }
 
         // Add explicitly configured environment variables (these override filtered ones)
         // Note: Config-specified env vars are trusted since they come from user configuration
         for (key, value) in &self.config.env {
-            cmd.env(key, value);
+            transport = transport.with_env(key, value);
         }
 
         // Set working directory
         if let Some(ref cwd) = self.config.cwd {
-            cmd.current_dir(cwd);
+            transport = transport.with_cwd(cwd.to_string_lossy());
         }
 
-        let child = cmd
-            .spawn()
-            .with_context(|| format!("Failed to spawn MCP server: {}", self.config.command))?;
-
-        *self.stdio_process.lock().await = Some(StdioTransport { child });
-
-        // Send initialize request
-        self.initialize().await?;
-
-        Ok(())
+        transport
     }
 
-    /// Connect using HTTP/SSE transport.
-    async fn connect_http(&self) -> Result<()> {
-        let _url = self
-            .config
-            .sse_url
-            .as_ref()
-            .ok_or_else(|| anyhow!("SSE URL not configured for HTTP transport"))?;
+    /// URL of a remote server.
+    ///
+    /// The legacy SSE transport posts to `sse_post_url` when set.
+    fn server_url(&self) -> Result<url::Url> {
+        let url = match self.config.transport {
+            TransportType::Sse => self
+                .config
+                .sse_post_url
+                .as_ref()
+                .or(self.config.sse_url.as_ref()),
+            _ => self
+                .config
+                .url
+                .as_ref()
+                .or(self.config.sse_post_url.as_ref())
+                .or(self.config.sse_url.as_ref()),
+        }
+        .ok_or_else(|| {
+            anyhow!(
+                "URL not configured for {:?} transport",
+                self.config.transport
+            )
+        })?;
+        url::Url::parse(url).with_context(|| format!("Invalid MCP server URL: {url}"))
+    }
 
-        // Test connection by sending initialize
-        self.initialize().await?;
+    /// Get the transport, if connected.
+    async fn transport(&self) -> Result<Arc<dyn Transport>> {
+        self.transport
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow!("Not connected"))
+    }
 
-        Ok(())
+    /// Capabilities to advertise for the configured transport.
+    ///
+    /// The SSE transport only sees responses to its own POSTs, so it cannot
+    /// serve sampling, roots or elicitation requests and advertises none.
+    fn client_capabilities(&self) -> ClientCapabilities {
+        match self.config.transport {
+            TransportType::Sse => ClientCapabilities::default(),
+            _ => self.request_handler.capabilities(),
+        }
     }
 
     /// Send initialize request.
     async fn initialize(&self) -> Result<InitializeResult> {
-        let params = InitializeParams::default();
+        let params = InitializeParams {
+            capabilities: self.client_capabilities(),
+            ..Default::default()
+        };
 
-        let response: InitializeResult = self
-            .request(methods::INITIALIZE, Some(serde_json::to_value(&params)?))
-            .await?;
+        let transport = self.transport().await?;
+        let response = transport.initialize(params).await?;
 
         *self.server_info.write().await = Some(response.clone());
 
         // Send initialized notification
-        self.notify(methods::INITIALIZED, None).await?;
+        transport.send_initialized().await?;
 
         Ok(response)
     }
 
     /// Disconnect from the server.
     pub async fn disconnect(&self) -> Result<()> {
-        let mut process = self.stdio_process.lock().await;
-        if let Some(mut transport) = process.take() {
-            let _ = transport.child.kill();
+        if let Some(transport) = self.transport.write().await.take() {
+            let _ = transport.close().await;
         }
 
         *self.state.write().await = ConnectionState::Disconnected;
@@ -273,14 +353,14 @@ impl McpClient {
 
     /// Refresh the list of available tools.
     pub async fn refresh_tools(&self) -> Result<Vec<Tool>> {
-        let result: ListToolsResult = self.request(methods::TOOLS_LIST, None).await?;
+        let result = self.transport().await?.list_tools().await?;
         *self.cached_tools.write().await = result.tools.clone();
         Ok(result.tools)
     }
 
     /// Refresh the list of available resources.
     pub async fn refresh_resources(&self) -> Result<Vec<Resource>> {
-        let result: ListResourcesResult = self.request(methods::RESOURCES_LIST, None).await?;
+        let result = self.transport().await?.list_resources().await?;
         *self.cached_resources.write().await = result.resources.clone();
         Ok(result.resources)
     }
@@ -292,8 +372,7 @@ impl McpClient {
             arguments,
         };
 
-        self.request(methods::TOOLS_CALL, Some(serde_json::to_value(&params)?))
-            .await
+        self.transport().await?.call_tool(params).await
     }
 
     /// Read a resource.
@@ -302,180 +381,14 @@ impl McpClient {
             uri: uri.to_string(),
         };
 
-        self.request(
-            methods::RESOURCES_READ,
-            Some(serde_json::to_value(&params)?),
-        )
-        .await
-    }
-
-    /// Send a JSON-RPC request and wait for response.
-    async fn request<T: for<'de> Deserialize<'de>>(
-        &self,
-        method: &str,
-        params: Option<Value>,
-    ) -> Result<T> {
-        let id = self.request_id.fetch_add(1, Ordering::SeqCst);
-
-        let request = JsonRpcRequest {
-            jsonrpc: JSONRPC_VERSION.to_string(),
-            id: id.into(),
-            method: method.to_string(),
-            params,
-        };
-
-        let response = match self.config.transport {
-            TransportType::Stdio => self.send_stdio_request(&request).await?,
-            TransportType::Sse | TransportType::Http => self.send_http_request(&request).await?,
-            TransportType::WebSocket => {
-                return Err(anyhow!("WebSocket transport not yet implemented"));
-            }
-        };
-
-        if let Some(error) = response.error {
-            return Err(anyhow!(
-                "MCP error: {} (code {})",
-                error.message,
-                error.code
-            ));
-        }
-
-        let result = response
-            .result
-            .ok_or_else(|| anyhow!("No result in response"))?;
-        serde_json::from_value(result).context("Failed to parse MCP response")
-    }
-
-    /// Send a JSON-RPC notification (no response expected).
-    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
-        let notification = json!({
-            "jsonrpc": JSONRPC_VERSION,
-            "method": method,
-            "params": params
-        });
-
-        match self.config.transport {
-            TransportType::Stdio => self.send_stdio_notification(&notification).await,
-            TransportType::Sse | TransportType::Http => {
-                self.send_http_notification(&notification).await
-            }
-            TransportType::WebSocket => Err(anyhow!("WebSocket transport not yet implemented")),
-        }
-    }
-
-    /// Send a request via stdio transport.
-    async fn send_stdio_request(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
-        let mut process = self.stdio_process.lock().await;
-        let transport = process.as_mut().ok_or_else(|| anyhow!("Not connected"))?;
-
-        let stdin = transport
-            .child
-            .stdin
-            .as_mut()
-            .ok_or_else(|| anyhow!("No stdin"))?;
-        let stdout = transport
-            .child
-            .stdout
-            .as_mut()
-            .ok_or_else(|| anyhow!("No stdout"))?;
-
-        // Write request
-        let request_json = serde_json::to_string(request)?;
-        writeln!(stdin, "{}", request_json)?;
-        stdin.flush()?;
-
-        // Read response
-        let mut reader = BufReader::new(stdout);
-        let mut line = String::new();
-        reader.read_line(&mut line)?;
-
-        serde_json::from_str(&line).context("Failed to parse JSON-RPC response")
-    }
-
-    /// Send a notification via stdio transport.
-    async fn send_stdio_notification(&self, notification: &Value) -> Result<()> {
-        let mut process = self.stdio_process.lock().await;
-        let transport = process.as_mut().ok_or_else(|| anyhow!("Not connected"))?;
-
-        let stdin = transport
-            .child
-            .stdin
-            .as_mut()
-            .ok_or_else(|| anyhow!("No stdin"))?;
-
-        let json = serde_json::to_string(notification)?;
-        writeln!(stdin, "{}", json)?;
-        stdin.flush()?;
-
-        Ok(())
-    }
-
-    /// Send a request via HTTP transport.
-    async fn send_http_request(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
-        let url = self
-            .config
-            .sse_post_url
-            .as_ref()
-            .or(self.config.sse_url.as_ref())
-            .ok_or_else(|| anyhow!("No HTTP URL configured"))?;
-
-        let response = self
-            .http_client
-            .post(url)
-            .json(request)
-            .send()
-            .await
-            .context("HTTP request failed")?;
-
-        if !response.status().is_success() {
-            return Err(anyhow!("HTTP error: {}", response.status()));
-        }
-
-        response
-            .json()
-            .await
-            .context("Failed to parse JSON-RPC response")
-    }
-
-    /// Send a notification via HTTP transport.
-    async fn send_http_notification(&self, notification: &Value) -> Result<()> {
-        let url = self
-            .config
-            .sse_post_url
-            .as_ref()
-            .or(self.config.sse_url.as_ref())
-            .ok_or_else(|| anyhow!("No HTTP URL configured"))?;
-
-        let response = self
-            .http_client
-            .post(url)
-            .json(notification)
-            .send()
-            .await
-            .context("HTTP notification failed")?;
-
-        if !response.status().is_success() {
-            warn!("HTTP notification returned error: {}", response.status());
-        }
-
-        Ok(())
-    }
-}
-
-impl Drop for McpClient {
-    fn drop(&mut self) {
-        // Kill child process if running
-        if let Ok(mut process) = self.stdio_process.try_lock() {
-            if let Some(mut transport) = process.take() {
-                let _ = transport.child.kill();
-            }
-        }
+        self.transport().await?.read_resource(params).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use serde_json::json;
 
     #[test]
     fn test_connection_state() {
@@ -490,4 +403,82 @@ mod tests {
         assert_eq!(client.name(), "test");
         assert_eq!(client.state().await, ConnectionState::Disconnected);
     }
+
+    #[test]
+    fn test_sse_advertises_no_server_request_capabilities() {
+        struct Roots;
+
+        #[async_trait::async_trait]
+        impl ServerRequestHandler for Roots {
+            fn capabilities(&self) -> ClientCapabilities {
+                ClientCapabilities {
+                    roots: Some(Default::default()),
+                    ..Default::default()
+                }
+            }
+        }
+
+        let http = McpClient::new(McpServerConfig::new_http("remote", "http://localhost"))
+            .with_request_handler(Arc::new(Roots));
+        assert!(http.client_capabilities().roots.is_some());
+
+        let sse = McpClient::new(McpServerConfig::new_sse("remote", "http://localhost"))
+            .with_request_handler(Arc::new(Roots));
+        assert!(sse.client_capabilities().roots.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_connection() {
+        use wiremock::matchers::{body_partial_json, header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "initialize"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("Mcp-Session-Id", "abc")
+                    .set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": {
+                        "protocolVersion": "2024-11-05",
+                        "capabilities": {"tools": {}},
+                        "serverInfo": {"name": "remote", "version": "1"}
+                    }})),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("Mcp-Session-Id", "abc"))
+            .and(body_partial_json(
+                json!({"method": "notifications/initialized"}),
+            ))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("Mcp-Session-Id", "abc"))
+            .and(body_partial_json(json!({"method": "tools/list"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 2,
+                "result": {"tools": [{"name": "search", "inputSchema": {"type": "object"}}]}
+            })))
+            .mount(&server)
+            .await;
+
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "resources/list"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 3,
+                "error": {"code": -32601, "message": "Method not found"}
+            })))
+            .mount(&server)
+            .await;
+
+        let client = McpClient::new(McpServerConfig::new_http("remote", server.uri()));
+        client.connect().await.unwrap();
+
+        assert_eq!(client.state().await, ConnectionState::Connected);
+        assert_eq!(client.tools().await[0].name, "search");
+        client.disconnect().await.unwrap();
+    }
 }
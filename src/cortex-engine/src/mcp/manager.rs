@@ -14,10 +14,12 @@ use serde_json::Value;
 use tokio::sync::{Mutex, RwLock, mpsc};
 use tracing::warn;
 
+use cortex_mcp_client::Transport;
 use cortex_mcp_types::{CallToolResult, Resource, Tool};
 
 use super::McpServerConfig;
 use super::client::{ConnectionState, McpClient};
+use super::server_requests::McpServerRequests;
 
 /// MCP lifecycle event for notifications
 #[derive(Debug, Clone)]
@@ -54,6 +56,8 @@ pub struct McpConnectionManager {
     starting_servers: Mutex<HashSet<String>>,
     /// Event sender for lifecycle notifications
     event_tx: Option<mpsc::UnboundedSender<McpLifecycleEvent>>,
+    /// Session context for answering server-initiated requests
+    server_requests: Option<Arc<McpServerRequests>>,
 }
 
 impl Default for McpConnectionManager {
@@ -70,6 +74,7 @@ impl McpConnectionManager {
             configs: RwLock::new(HashMap::new()),
             starting_servers: Mutex::new(HashSet::new()),
             event_tx: None,
+            server_requests: None,
         }
     }
 
@@ -80,6 +85,7 @@ impl McpConnectionManager {
             configs: RwLock::new(HashMap::new()),
             starting_servers: Mutex::new(HashSet::new()),
             event_tx: Some(tx),
+            server_requests: None,
         }
     }
 
@@ -88,6 +94,12 @@ impl McpConnectionManager {
         self.event_tx = Some(tx);
     }
 
+    /// Answer sampling, roots and elicitation requests of servers added
+    /// from now on using the given session context
+    pub fn set_server_requests(&mut self, server_requests: Arc<McpServerRequests>) {
+        self.server_requests = Some(server_requests);
+    }
+
     /// Helper to send an event
     fn send_event(&self, event: McpLifecycleEvent) {
         if let Some(ref tx) = self.event_tx {
@@ -97,14 +109,30 @@ impl McpConnectionManager {
 
     /// Add a server configuration.
     pub async fn add_server(&self, config: McpServerConfig) {
+        self.insert_client(McpClient::new(config.clone()), config)
+            .await;
+    }
+
+    /// Add a server reached through `transport` rather than its configured
+    /// transport, e.g. a server running in the same process.
+    pub async fn add_server_with_transport(
+        &self,
+        config: McpServerConfig,
+        transport: Arc<dyn Transport>,
+    ) {
+        let client = McpClient::new(config.clone()).with_transport(transport);
+        self.insert_client(client, config).await;
+    }
+
+    /// Register a client without connecting it.
+    async fn insert_client(&self, mut client: McpClient, config: McpServerConfig) {
         let name = config.name.clone();
-        self.configs
-            .write()
-            .await
-            .insert(name.clone(), config.clone());
+        self.configs.write().await.insert(name.clone(), config);
 
-        // Create client but don't connect yet
-        let client = Arc::new(McpClient::new(config));
+        if let Some(ref server_requests) = self.server_requests {
+            client = client.with_request_handler(Arc::new(server_requests.handler(&name)));
+        }
+        let client = Arc::new(client);
         self.clients.write().await.insert(name.clone(), client);
 
         self.send_event(McpLifecycleEvent::ServerAdded { name });
@@ -137,6 +165,14 @@ impl McpConnectionManager {
     /// Connect to a specific server.
     /// Uses a lock to prevent concurrent startup of the same server (race condition fix).
     pub async fn connect(&self, name: &str) -> Result<()> {
+        let policy = crate::managed_policy::ManagedPolicy::system()?;
+        if !policy.allows_mcp_server(name) {
+            return Err(anyhow!(
+                "MCP server '{}' is not allowed by the managed policy",
+                name
+            ));
+        }
+
         // Check if this server is already being started by another request
         {
             let mut starting = self.starting_servers.lock().await;
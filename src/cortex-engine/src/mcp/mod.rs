@@ -1,22 +1,25 @@
 //! MCP (Model Context Protocol) support.
 //!
 //! This module provides:
-//! - MCP client for connecting to servers (stdio, HTTP/SSE)
+//! - MCP client for connecting to servers (stdio, HTTP/SSE, Streamable HTTP,
+//!   WebSocket)
 //! - Connection manager for multiple servers
 //! - OAuth 2.0 authentication support for remote servers
 //! - Tool execution and resource reading
+//! - Answers to server-initiated sampling, roots and elicitation requests
 
 pub mod client;
 pub mod manager;
 pub mod oauth;
 pub mod oauth_callback;
 pub mod registry;
+pub mod server_requests;
 
 // OAuth exports - these are actively used by cortex-cli
 pub use oauth::{
     AuthStatus, OAUTH_CALLBACK_PATH, OAUTH_CALLBACK_PORT, OAuthClientInfo, OAuthClientMetadata,
     OAuthConfig, OAuthEntry, OAuthFlow, OAuthServerMetadata, OAuthStorage, OAuthTokens, Pkce,
-    get_auth_status, has_stored_tokens, remove_auth,
+    StoredTokenProvider, get_auth_status, has_stored_tokens, remove_auth,
 };
 pub use oauth_callback::{
     CallbackResult, OAuthCallbackServer, ensure_valid_tokens, run_oauth_flow,
@@ -26,6 +29,8 @@ pub use oauth_callback::{
 pub use client::{ConnectionState, McpClient};
 pub use manager::{McpConnectionManager, create_qualified_name, parse_qualified_name};
 
+pub use server_requests::{McpRequestHandler, McpServerRequests, McpUserRequest};
+
 // Registry exports
 pub use registry::{
     DEFAULT_CACHE_TTL, HttpConfig, McpRegistryClient, REGISTRY_URL, RegistryInstallConfig,
@@ -97,6 +102,9 @@ pub struct McpServerConfig {
     /// HTTP POST URL for SSE transport (defaults to sse_url if not specified).
     #[serde(skip_serializing_if = "Option::is_none")]
     pub sse_post_url: Option<String>,
+    /// Endpoint URL for the Streamable HTTP and WebSocket transports.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 impl McpServerConfig {
@@ -115,6 +123,7 @@ impl McpServerConfig {
             max_restarts: 3,
             sse_url: None,
             sse_post_url: None,
+            url: None,
         }
     }
 
@@ -133,6 +142,25 @@ impl McpServerConfig {
             max_restarts: 3,
             sse_url: Some(sse_url.into()),
             sse_post_url: None,
+            url: None,
+        }
+    }
+
+    /// Create a new Streamable HTTP server config.
+    pub fn new_http(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            transport: TransportType::Http,
+            url: Some(url.into()),
+            ..Self::new(name, String::new())
+        }
+    }
+
+    /// Create a new WebSocket server config.
+    pub fn new_websocket(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            transport: TransportType::WebSocket,
+            url: Some(url.into()),
+            ..Self::new(name, String::new())
         }
     }
 
@@ -191,7 +219,7 @@ impl McpServerConfig {
 pub enum TransportType {
     /// Standard I/O transport.
     Stdio,
-    /// HTTP/HTTPS transport.
+    /// Streamable HTTP transport.
     Http,
     /// WebSocket transport.
     WebSocket,
@@ -761,6 +761,77 @@ pub async fn remove_auth(mcp_name: &str) -> Result<()> {
     Ok(())
 }
 
+/// Bearer tokens from [`OAuthStorage`] for remote MCP transports.
+///
+/// Expired or rejected tokens are refreshed with the stored refresh token.
+/// The interactive flow is never started from here; servers without stored
+/// tokens are contacted without authorization.
+#[derive(Debug, Clone)]
+pub struct StoredTokenProvider {
+    mcp_name: String,
+    server_url: String,
+    config: OAuthConfig,
+}
+
+impl StoredTokenProvider {
+    /// Create a provider for the tokens stored for this server.
+    pub fn new(mcp_name: impl Into<String>, server_url: impl Into<String>) -> Self {
+        Self {
+            mcp_name: mcp_name.into(),
+            server_url: server_url.into(),
+            config: OAuthConfig::default(),
+        }
+    }
+
+    /// Set the OAuth configuration used for refreshing.
+    pub fn with_config(mut self, config: OAuthConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Refresh the stored tokens, if there is a refresh token.
+    async fn refresh_stored(&self, storage: &mut OAuthStorage) -> Result<Option<String>> {
+        let has_refresh_token = storage
+            .get_for_url(&self.mcp_name, &self.server_url)
+            .and_then(|e| e.tokens.as_ref())
+            .is_some_and(|t| t.refresh_token.is_some());
+        if !has_refresh_token {
+            return Ok(None);
+        }
+
+        let flow =
+            OAuthFlow::new(&self.mcp_name, &self.server_url).with_config(self.config.clone());
+        let metadata = flow.discover_metadata().await?;
+        let tokens = flow.refresh_tokens(&metadata, storage).await?;
+        Ok(Some(tokens.access_token))
+    }
+}
+
+#[async_trait::async_trait]
+impl cortex_mcp_client::AuthProvider for StoredTokenProvider {
+    async fn token(&self) -> anyhow::Result<Option<String>> {
+        let mut storage = OAuthStorage::load().await?;
+        let Some(tokens) = storage
+            .get_for_url(&self.mcp_name, &self.server_url)
+            .and_then(|e| e.tokens.clone())
+        else {
+            return Ok(None);
+        };
+
+        let now = chrono::Utc::now().timestamp();
+        if tokens.expires_at.is_some_and(|exp| exp < now) {
+            debug!(mcp_name = %self.mcp_name, "Stored token expired, refreshing");
+            return Ok(self.refresh_stored(&mut storage).await?);
+        }
+        Ok(Some(tokens.access_token))
+    }
+
+    async fn refresh(&self) -> anyhow::Result<Option<String>> {
+        let mut storage = OAuthStorage::load().await?;
+        Ok(self.refresh_stored(&mut storage).await?)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
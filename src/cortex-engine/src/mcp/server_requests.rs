@@ -0,0 +1,498 @@
+//! Session-side answers to MCP server-initiated requests.
+//!
+//! - `roots/list`: the session workspace directories
+//! - `sampling/createMessage`: forwarded to the session's model client once
+//!   the user approves it
+//! - `elicitation/create`: forwarded to the UI, which asks the user
+//!
+//! Anything needing the user is sent to the UI as an [`McpUserRequest`]
+//! carrying a reply channel. A dropped reply counts as a refusal.
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use cortex_mcp_client::ServerRequestHandler;
+use cortex_mcp_types::{
+    ClientCapabilities, Content, ElicitRequest, ElicitResult, ElicitationCapability, JsonRpcError,
+    ListRootsResult, Role, Root, RootsCapability, SamplingCapability, SamplingRequest,
+    SamplingResult, StopReason, methods,
+};
+use tokio::sync::{mpsc, oneshot};
+use tracing::info;
+
+use crate::client::{
+    CompletionRequest, ContentPart, FinishReason, ImageUrl, Message, MessageContent, MessageRole,
+    ModelClient,
+};
+
+/// Error code MCP uses when the user rejects a request.
+const USER_REJECTED: i32 = -1;
+
+/// A server request that needs the user, delivered to the UI.
+#[derive(Debug)]
+pub enum McpUserRequest {
+    /// Approve (`true`) or reject a sampling request before it reaches the model.
+    Sampling {
+        server: String,
+        request: SamplingRequest,
+        respond: oneshot::Sender<bool>,
+    },
+    /// Ask the user for the data an elicitation request describes.
+    Elicitation {
+        server: String,
+        request: ElicitRequest,
+        respond: oneshot::Sender<ElicitResult>,
+    },
+}
+
+/// Session context shared by the request handlers of all MCP servers.
+pub struct McpServerRequests {
+    /// Workspace directories reported as roots.
+    roots: Vec<PathBuf>,
+    /// Model used for sampling.
+    model: Option<Arc<dyn ModelClient>>,
+    /// Channel to the UI for approvals and elicitation.
+    user_tx: Option<mpsc::UnboundedSender<McpUserRequest>>,
+}
+
+impl McpServerRequests {
+    /// Create a context that only answers `roots/list`.
+    pub fn new(roots: Vec<PathBuf>) -> Self {
+        Self {
+            roots,
+            model: None,
+            user_tx: None,
+        }
+    }
+
+    /// Route sampling through the given model client.
+    pub fn with_model(mut self, model: Arc<dyn ModelClient>) -> Self {
+        self.model = Some(model);
+        self
+    }
+
+    /// Send approvals and elicitation requests to the UI.
+    pub fn with_user_channel(mut self, tx: mpsc::UnboundedSender<McpUserRequest>) -> Self {
+        self.user_tx = Some(tx);
+        self
+    }
+
+    /// Create the request handler for one server.
+    pub fn handler(self: &Arc<Self>, server: impl Into<String>) -> McpRequestHandler {
+        McpRequestHandler {
+            server: server.into(),
+            context: self.clone(),
+        }
+    }
+}
+
+/// Request handler for a single MCP server.
+pub struct McpRequestHandler {
+    server: String,
+    context: Arc<McpServerRequests>,
+}
+
+impl McpRequestHandler {
+    /// Send a request to the UI and wait for its reply.
+    async fn ask_user<T>(
+        &self,
+        method: &str,
+        build: impl FnOnce(oneshot::Sender<T>) -> McpUserRequest,
+    ) -> Result<Option<T>, JsonRpcError> {
+        let tx = self
+            .context
+            .user_tx
+            .as_ref()
+            .ok_or_else(|| JsonRpcError::method_not_found(method))?;
+        let (respond, reply) = oneshot::channel();
+        if tx.send(build(respond)).is_err() {
+            return Ok(None);
+        }
+        Ok(reply.await.ok())
+    }
+}
+
+#[async_trait]
+impl ServerRequestHandler for McpRequestHandler {
+    fn capabilities(&self) -> ClientCapabilities {
+        let interactive = self.context.user_tx.is_some();
+        ClientCapabilities {
+            roots: Some(RootsCapability::default()),
+            sampling: (interactive && self.context.model.is_some())
+                .then(SamplingCapability::default),
+            elicitation: interactive.then(ElicitationCapability::default),
+            ..Default::default()
+        }
+    }
+
+    async fn list_roots(&self) -> Result<ListRootsResult, JsonRpcError> {
+        Ok(ListRootsResult {
+            roots: self
+                .context
+                .roots
+                .iter()
+                .map(|path| root_for(path))
+                .collect(),
+        })
+    }
+
+    async fn create_message(
+        &self,
+        request: SamplingRequest,
+    ) -> Result<SamplingResult, JsonRpcError> {
+        let model = self
+            .context
+            .model
+            .clone()
+            .ok_or_else(|| JsonRpcError::method_not_found(methods::SAMPLING_CREATE_MESSAGE))?;
+
+        let approved = self
+            .ask_user(methods::SAMPLING_CREATE_MESSAGE, |respond| {
+                McpUserRequest::Sampling {
+                    server: self.server.clone(),
+                    request: request.clone(),
+                    respond,
+                }
+            })
+            .await?
+            .unwrap_or(false);
+        if !approved {
+            info!(server = %self.server, "Sampling request rejected");
+            return Err(JsonRpcError::new(
+                USER_REJECTED,
+                "User rejected sampling request",
+            ));
+        }
+
+        let response = model
+            .complete_sync(completion_request(&request, model.model()))
+            .await
+            .map_err(|e| JsonRpcError::internal_error(e.to_string()))?;
+        let text = response
+            .message
+            .as_ref()
+            .and_then(|m| m.content.as_text())
+            .unwrap_or_default()
+            .to_string();
+
+        Ok(SamplingResult {
+            model: model.model().to_string(),
+            stop_reason: match response.finish_reason {
+                FinishReason::Length => Some(StopReason::MaxTokens),
+                FinishReason::Stop => Some(StopReason::EndTurn),
+                _ => None,
+            },
+            role: Role::Assistant,
+            content: Content::text(text),
+        })
+    }
+
+    async fn elicit(&self, request: ElicitRequest) -> Result<ElicitResult, JsonRpcError> {
+        Ok(self
+            .ask_user(methods::ELICITATION_CREATE, |respond| {
+                McpUserRequest::Elicitation {
+                    server: self.server.clone(),
+                    request,
+                    respond,
+                }
+            })
+            .await?
+            .unwrap_or_else(ElicitResult::cancel))
+    }
+}
+
+/// Describe a workspace directory as an MCP root.
+fn root_for(path: &std::path::Path) -> Root {
+    let uri = url::Url::from_directory_path(path)
+        .map(String::from)
+        .unwrap_or_else(|_| format!("file://{}", path.display()));
+    let root = Root::new(uri);
+    match path.file_name() {
+        Some(name) => root.with_name(name.to_string_lossy()),
+        None => root,
+    }
+}
+
+/// Translate a sampling request into a completion request.
+fn completion_request(request: &SamplingRequest, model: &str) -> CompletionRequest {
+    let mut messages = Vec::new();
+    if let Some(system) = &request.system_prompt {
+        messages.push(Message::system(system));
+    }
+    for message in &request.messages {
+        let role = match message.role {
+            Role::User => MessageRole::User,
+            Role::Assistant => MessageRole::Assistant,
+        };
+        let content = match &message.content {
+            Content::Text { text } => MessageContent::Text(text.clone()),
+            Content::Image { data, mime_type } => {
+                MessageContent::Parts(vec![ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: format!("data:{mime_type};base64,{data}"),
+                        detail: None,
+                    },
+                }])
+            }
+            Content::Resource { resource } => {
+                MessageContent::Text(resource.text.clone().unwrap_or_default())
+            }
+        };
+        messages.push(Message {
+            role,
+            content,
+            tool_call_id: None,
+            tool_calls: None,
+        });
+    }
+
+    CompletionRequest {
+        messages,
+        model: model.to_string(),
+        max_tokens: request.max_tokens,
+        temperature: request.temperature.map(|t| t as f32),
+        stream: false,
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::ResponseStream;
+    use crate::client::types::{CompletionResponse, ModelCapabilities};
+    use crate::mcp::{McpClient, McpServerConfig};
+    use cortex_mcp_client::dispatch;
+    use cortex_mcp_types::{JsonRpcRequest, SamplingMessage};
+    use serde_json::json;
+    use std::sync::Mutex;
+
+    /// Model that echoes the last user message and records requests.
+    struct EchoModel {
+        capabilities: ModelCapabilities,
+        requests: Mutex<Vec<CompletionRequest>>,
+    }
+
+    #[async_trait]
+    impl ModelClient for EchoModel {
+        fn model(&self) -> &str {
+            "echo-model"
+        }
+
+        fn provider(&self) -> &str {
+            "test"
+        }
+
+        fn capabilities(&self) -> &ModelCapabilities {
+            &self.capabilities
+        }
+
+        async fn complete(
+            &self,
+            _request: CompletionRequest,
+        ) -> crate::error::Result<ResponseStream> {
+            Err(crate::error::CortexError::Internal(
+                "streaming unsupported".into(),
+            ))
+        }
+
+        async fn complete_sync(
+            &self,
+            request: CompletionRequest,
+        ) -> crate::error::Result<CompletionResponse> {
+            let text = request
+                .messages
+                .last()
+                .and_then(|m| m.content.as_text())
+                .unwrap_or_default()
+                .to_string();
+            self.requests.lock().unwrap().push(request);
+            Ok(CompletionResponse {
+                message: Some(Message::assistant(format!("echo: {text}"))),
+                ..Default::default()
+            })
+        }
+    }
+
+    fn echo_model() -> Arc<EchoModel> {
+        Arc::new(EchoModel {
+            capabilities: ModelCapabilities::default(),
+            requests: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn sampling_request() -> SamplingRequest {
+        SamplingRequest {
+            messages: vec![SamplingMessage {
+                role: Role::User,
+                content: Content::text("summarize"),
+            }],
+            model_preferences: None,
+            system_prompt: Some("Be brief.".to_string()),
+            include_context: None,
+            temperature: Some(0.5),
+            max_tokens: Some(64),
+            stop_sequences: None,
+            metadata: None,
+        }
+    }
+
+    /// Answer user requests from the UI side with fixed decisions.
+    fn spawn_user(approve: bool) -> mpsc::UnboundedSender<McpUserRequest> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            while let Some(request) = rx.recv().await {
+                match request {
+                    McpUserRequest::Sampling { respond, .. } => {
+                        let _ = respond.send(approve);
+                    }
+                    McpUserRequest::Elicitation { respond, .. } => {
+                        let mut content = serde_json::Map::new();
+                        content.insert("name".to_string(), json!("cortex"));
+                        let _ = respond.send(if approve {
+                            ElicitResult::accept(content)
+                        } else {
+                            ElicitResult::decline()
+                        });
+                    }
+                }
+            }
+        });
+        tx
+    }
+
+    #[tokio::test]
+    async fn test_roots_and_capabilities() {
+        let dir = tempfile::tempdir().unwrap();
+        let context = Arc::new(McpServerRequests::new(vec![dir.path().to_path_buf()]));
+        let handler = context.handler("files");
+
+        let capabilities = handler.capabilities();
+        assert!(capabilities.roots.is_some());
+        assert!(capabilities.sampling.is_none());
+        assert!(capabilities.elicitation.is_none());
+
+        let roots = handler.list_roots().await.unwrap().roots;
+        assert_eq!(roots.len(), 1);
+        assert!(roots[0].uri.starts_with("file:///"));
+        assert_eq!(
+            roots[0].name.as_deref(),
+            dir.path().file_name().and_then(|n| n.to_str())
+        );
+
+        let error = handler
+            .create_message(sampling_request())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, cortex_mcp_types::ErrorCode::METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_sampling_requires_approval() {
+        let model = echo_model();
+        let approved = Arc::new(
+            McpServerRequests::new(Vec::new())
+                .with_model(model.clone())
+                .with_user_channel(spawn_user(true)),
+        )
+        .handler("search");
+        assert!(approved.capabilities().sampling.is_some());
+
+        let result = approved.create_message(sampling_request()).await.unwrap();
+        assert_eq!(result.model, "echo-model");
+        assert_eq!(result.content.as_text(), Some("echo: summarize"));
+        assert_eq!(result.stop_reason, Some(StopReason::EndTurn));
+
+        let sent = model.requests.lock().unwrap().remove(0);
+        assert_eq!(sent.messages[0].role, MessageRole::System);
+        assert_eq!(sent.max_tokens, Some(64));
+
+        let rejected = Arc::new(
+            McpServerRequests::new(Vec::new())
+                .with_model(model.clone())
+                .with_user_channel(spawn_user(false)),
+        )
+        .handler("search");
+        let error = rejected
+            .create_message(sampling_request())
+            .await
+            .unwrap_err();
+        assert_eq!(error.code, USER_REJECTED);
+        assert!(model.requests.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_elicitation_goes_to_user() {
+        let handler =
+            Arc::new(McpServerRequests::new(Vec::new()).with_user_channel(spawn_user(true)))
+                .handler("deploy");
+        let response = dispatch(
+            &handler,
+            JsonRpcRequest::new(3, methods::ELICITATION_CREATE).with_params(json!({
+                "message": "Project name?",
+                "requestedSchema": {"type": "object", "properties": {"name": {"type": "string"}}}
+            })),
+        )
+        .await;
+        assert_eq!(
+            response.result.unwrap(),
+            json!({"action": "accept", "content": {"name": "cortex"}})
+        );
+
+        // A UI that goes away cancels the request instead of hanging.
+        let (tx, rx) = mpsc::unbounded_channel();
+        drop(rx);
+        let handler =
+            Arc::new(McpServerRequests::new(Vec::new()).with_user_channel(tx)).handler("deploy");
+        let result = handler
+            .elicit(ElicitRequest::new(
+                "Project name?",
+                json!({"type": "object"}),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result.action, cortex_mcp_types::ElicitAction::Cancel);
+    }
+
+    #[tokio::test]
+    async fn test_stdio_client_answers_roots_during_initialize() {
+        let dir = tempfile::tempdir().unwrap();
+        let log = dir.path().join("log");
+        // Ask for roots before answering initialize, and log what we receive.
+        let script = r#"read init
+echo "$init" >> "$1"
+echo '{"jsonrpc":"2.0","id":"srv-1","method":"roots/list"}'
+read answer
+echo "$answer" >> "$1"
+echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2024-11-05","capabilities":{},"serverInfo":{"name":"test","version":"1.0"}}}'
+read initialized
+read list
+echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[]}}'
+read list
+echo '{"jsonrpc":"2.0","id":3,"result":{"resources":[]}}'
+"#;
+        let config =
+            McpServerConfig::new("roots", "sh").args(["-c", script, "sh", log.to_str().unwrap()]);
+        let context = Arc::new(McpServerRequests::new(vec![dir.path().to_path_buf()]));
+        let client =
+            McpClient::new(config).with_request_handler(Arc::new(context.handler("roots")));
+
+        client.connect().await.unwrap();
+        assert!(client.is_connected().await);
+        client.disconnect().await.unwrap();
+
+        let log = std::fs::read_to_string(&log).unwrap();
+        let mut lines = log.lines();
+        let init: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert!(init["params"]["capabilities"]["roots"].is_object());
+        let answer: serde_json::Value = serde_json::from_str(lines.next().unwrap()).unwrap();
+        assert_eq!(answer["id"], "srv-1");
+        assert!(
+            answer["result"]["roots"][0]["uri"]
+                .as_str()
+                .unwrap()
+                .starts_with("file:///")
+        );
+    }
+}
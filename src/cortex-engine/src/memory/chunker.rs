@@ -0,0 +1,694 @@
+//! Structural code chunking with tree-sitter.
+//!
+//! Parses a source file and produces one chunk per definition (function,
+//! method, class, struct, trait, ...) together with its symbol name, kind,
+//! enclosing scope and byte range. Leading doc comments, attributes,
+//! decorators and `export` keywords are included in the definition's range.
+//!
+//! Containers (impl blocks, classes, traits, modules, interfaces with bodies)
+//! are split into their members; the container itself becomes a skeleton
+//! chunk holding whatever is left once the members are removed (signature,
+//! fields, associated constants). Top-level code outside any definition is
+//! returned as gaps for the caller to chunk by lines.
+
+use std::ops::Range;
+use std::path::Path;
+
+use tree_sitter::{Language, Node, Parser};
+
+/// Skeletons with fewer lines than this hold nothing but the container's
+/// opening line and closing brace, and are not worth a chunk.
+const MIN_SKELETON_LINES: usize = 3;
+
+/// A definition extracted from a syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SymbolChunk {
+    /// Kind of definition ("function", "method", "class", "impl", ...).
+    pub kind: &'static str,
+    /// Symbol name, if the definition has one.
+    pub name: Option<String>,
+    /// Qualified name of the enclosing scope (e.g. `outer::Foo`).
+    pub parent: Option<String>,
+    /// Byte range in the file, including leading doc comments and attributes.
+    pub range: Range<usize>,
+    /// Chunk text. For skeletons this is the container with its members removed.
+    pub content: String,
+    /// Whether this is a container skeleton rather than a contiguous slice.
+    pub skeleton: bool,
+}
+
+/// Definitions and uncovered top-level ranges of a file.
+#[derive(Debug, Default)]
+pub(crate) struct Outline {
+    /// Definitions in file order.
+    pub symbols: Vec<SymbolChunk>,
+    /// Top-level byte ranges not covered by any definition.
+    pub gaps: Vec<Range<usize>>,
+}
+
+/// Grammars supported for structural chunking.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Grammar {
+    Rust,
+    Python,
+    JavaScript,
+    TypeScript,
+    Tsx,
+    Go,
+    Java,
+}
+
+impl Grammar {
+    fn for_file(path: &Path, language: &str) -> Option<Self> {
+        let tsx = path
+            .extension()
+            .is_some_and(|ext| ext.eq_ignore_ascii_case("tsx"));
+        match language {
+            "rust" => Some(Self::Rust),
+            "python" => Some(Self::Python),
+            "javascript" => Some(Self::JavaScript),
+            "typescript" if tsx => Some(Self::Tsx),
+            "typescript" => Some(Self::TypeScript),
+            "go" => Some(Self::Go),
+            "java" => Some(Self::Java),
+            _ => None,
+        }
+    }
+
+    fn language(self) -> Language {
+        match self {
+            Self::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Self::Python => tree_sitter_python::LANGUAGE.into(),
+            Self::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
+            Self::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
+            Self::Tsx => tree_sitter_typescript::LANGUAGE_TSX.into(),
+            Self::Go => tree_sitter_go::LANGUAGE.into(),
+            Self::Java => tree_sitter_java::LANGUAGE.into(),
+        }
+    }
+
+    fn scope_separator(self) -> &'static str {
+        match self {
+            Self::Rust => "::",
+            _ => ".",
+        }
+    }
+}
+
+/// A syntax node recognized as a definition.
+struct Definition<'t> {
+    /// The definition node itself; members of containers are searched below it.
+    node: Node<'t>,
+    /// The node whose range the chunk covers (e.g. a decorated definition).
+    outer: Node<'t>,
+    kind: &'static str,
+    name: Option<String>,
+    container: bool,
+    /// Name members see as their parent (e.g. `Foo` for `impl Display for Foo`).
+    scope_name: Option<String>,
+    /// Parent scope not derived from nesting (e.g. a Go method's receiver).
+    parent_override: Option<String>,
+}
+
+/// Parse `content` and extract its definitions.
+///
+/// Returns `None` when the language is not supported or the file cannot be parsed.
+pub(crate) fn outline(path: &Path, content: &str, language: &str) -> Option<Outline> {
+    let grammar = Grammar::for_file(path, language)?;
+    let mut parser = Parser::new();
+    parser.set_language(&grammar.language()).ok()?;
+    let tree = parser.parse(content, None)?;
+
+    let mut walker = Walker {
+        grammar,
+        src: content,
+        symbols: Vec::new(),
+    };
+    let mut top_level = Vec::new();
+    walker.collect(tree.root_node(), &mut Vec::new(), &mut top_level);
+
+    let mut symbols = walker.symbols;
+    symbols.sort_by_key(|s| (s.range.start, std::cmp::Reverse(s.range.end)));
+
+    Some(Outline {
+        symbols,
+        gaps: subtract(0..content.len(), &top_level),
+    })
+}
+
+struct Walker<'s> {
+    grammar: Grammar,
+    src: &'s str,
+    symbols: Vec<SymbolChunk>,
+}
+
+impl Walker<'_> {
+    /// Find definitions below `node`, recording their ranges in `members`.
+    fn collect(
+        &mut self,
+        node: Node<'_>,
+        scope: &mut Vec<(String, &'static str)>,
+        members: &mut Vec<Range<usize>>,
+    ) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            let Some(def) = self.classify(child) else {
+                // Not a definition; definitions may still be nested inside
+                // (export statements, declaration lists, class bodies, ...).
+                self.collect(child, scope, members);
+                continue;
+            };
+
+            let range = leading_start(def.outer)..def.outer.end_byte();
+            members.push(range.clone());
+
+            let parent = def.parent_override.clone().or_else(|| {
+                (!scope.is_empty()).then(|| {
+                    scope
+                        .iter()
+                        .map(|(name, _)| name.as_str())
+                        .collect::<Vec<_>>()
+                        .join(self.grammar.scope_separator())
+                })
+            });
+            let in_type = scope.last().is_some_and(|(_, kind)| is_type_scope(kind));
+            let kind = if def.kind == "function" && in_type {
+                "method"
+            } else {
+                def.kind
+            };
+
+            if def.container {
+                let scope_name = def
+                    .scope_name
+                    .clone()
+                    .or_else(|| def.name.clone())
+                    .unwrap_or_else(|| "<anonymous>".to_string());
+                let mut inner = Vec::new();
+                scope.push((scope_name, def.kind));
+                self.collect(def.node, scope, &mut inner);
+                scope.pop();
+
+                let content = if inner.is_empty() {
+                    self.src[range.clone()].to_string()
+                } else {
+                    skeleton(self.src, range.clone(), &inner)
+                };
+                let lines = content.lines().filter(|l| !l.trim().is_empty()).count();
+                if inner.is_empty() || lines >= MIN_SKELETON_LINES {
+                    self.symbols.push(SymbolChunk {
+                        kind,
+                        name: def.name,
+                        parent,
+                        range,
+                        content,
+                        skeleton: !inner.is_empty(),
+                    });
+                }
+            } else {
+                self.symbols.push(SymbolChunk {
+                    kind,
+                    name: def.name,
+                    parent,
+                    content: self.src[range.clone()].to_string(),
+                    range,
+                    skeleton: false,
+                });
+            }
+        }
+    }
+
+    fn text(&self, node: Node<'_>) -> String {
+        self.src[node.byte_range()].to_string()
+    }
+
+    fn field_text(&self, node: Node<'_>, field: &str) -> Option<String> {
+        node.child_by_field_name(field).map(|n| self.text(n))
+    }
+
+    /// Recognize `node` as a definition of the current grammar.
+    fn classify<'t>(&self, node: Node<'t>) -> Option<Definition<'t>> {
+        let leaf = |kind: &'static str| (kind, false);
+        let container = |kind: &'static str| (kind, true);
+
+        let (kind, is_container) = match (self.grammar, node.kind()) {
+            (Grammar::Rust, "function_item" | "function_signature_item") => leaf("function"),
+            (Grammar::Rust, "struct_item") => leaf("struct"),
+            (Grammar::Rust, "enum_item") => leaf("enum"),
+            (Grammar::Rust, "union_item") => leaf("union"),
+            (Grammar::Rust, "macro_definition") => leaf("macro"),
+            (Grammar::Rust, "trait_item") => container("trait"),
+            (Grammar::Rust, "mod_item") if node.child_by_field_name("body").is_some() => {
+                container("module")
+            }
+            (Grammar::Rust, "impl_item") => {
+                let ty = self.field_text(node, "type");
+                let name = match (self.field_text(node, "trait"), &ty) {
+                    (Some(tr), Some(ty)) => Some(format!("{} for {}", tr, ty)),
+                    (None, ty) => ty.clone(),
+                    (Some(tr), None) => Some(tr),
+                };
+                return Some(Definition {
+                    node,
+                    outer: node,
+                    kind: "impl",
+                    name,
+                    container: true,
+                    scope_name: ty.map(|t| strip_generics(&t)),
+                    parent_override: None,
+                });
+            }
+
+            (Grammar::Python, "function_definition") => leaf("function"),
+            (Grammar::Python, "class_definition") => container("class"),
+            (Grammar::Python, "decorated_definition") => {
+                let inner = node.child_by_field_name("definition")?;
+                let mut def = self.classify(inner)?;
+                def.outer = node;
+                return Some(def);
+            }
+
+            (
+                Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx,
+                "function_declaration" | "generator_function_declaration",
+            ) => leaf("function"),
+            (
+                Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx,
+                "class_declaration" | "abstract_class_declaration",
+            ) => container("class"),
+            (Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx, "method_definition") => {
+                leaf("method")
+            }
+            (
+                Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx,
+                "lexical_declaration" | "variable_declaration",
+            ) => {
+                // `const handler = async () => { ... }`
+                let mut cursor = node.walk();
+                let declarator = node.named_children(&mut cursor).find(|d| {
+                    d.kind() == "variable_declarator"
+                        && d.child_by_field_name("value")
+                            .is_some_and(|v| is_function_value(v.kind()))
+                })?;
+                return Some(self.definition(
+                    node,
+                    "function",
+                    false,
+                    self.field_text(declarator, "name"),
+                ));
+            }
+            (
+                Grammar::JavaScript | Grammar::TypeScript | Grammar::Tsx,
+                "field_definition" | "public_field_definition",
+            ) => {
+                // Class fields holding arrow functions behave like methods.
+                if !node
+                    .child_by_field_name("value")
+                    .is_some_and(|v| is_function_value(v.kind()))
+                {
+                    return None;
+                }
+                let name = self
+                    .field_text(node, "name")
+                    .or_else(|| self.field_text(node, "property"));
+                return Some(self.definition(node, "method", false, name));
+            }
+            (Grammar::TypeScript | Grammar::Tsx, "interface_declaration") => leaf("interface"),
+            (Grammar::TypeScript | Grammar::Tsx, "type_alias_declaration") => leaf("type"),
+            (Grammar::TypeScript | Grammar::Tsx, "enum_declaration") => leaf("enum"),
+            (Grammar::TypeScript | Grammar::Tsx, "internal_module" | "module")
+                if node.child_by_field_name("body").is_some() =>
+            {
+                container("namespace")
+            }
+
+            (Grammar::Go, "function_declaration") => leaf("function"),
+            (Grammar::Go, "method_declaration") => {
+                let receiver = node
+                    .child_by_field_name("receiver")
+                    .and_then(|r| find_descendant(r, "type_identifier"))
+                    .map(|t| self.text(t));
+                let mut def = self.definition(node, "method", false, self.field_text(node, "name"));
+                def.parent_override = receiver;
+                return Some(def);
+            }
+            (Grammar::Go, "type_declaration") => {
+                // Only ungrouped declarations; `type ( ... )` groups are
+                // searched for their individual specs.
+                let mut cursor = node.walk();
+                let specs: Vec<_> = node
+                    .named_children(&mut cursor)
+                    .filter(|n| matches!(n.kind(), "type_spec" | "type_alias"))
+                    .collect();
+                let [spec] = specs.as_slice() else {
+                    return None;
+                };
+                let mut def = self.classify(*spec)?;
+                def.node = node;
+                def.outer = node;
+                return Some(def);
+            }
+            (Grammar::Go, "type_spec" | "type_alias") => {
+                let kind = match node.child_by_field_name("type").map(|t| t.kind()) {
+                    Some("struct_type") => "struct",
+                    Some("interface_type") => "interface",
+                    _ => "type",
+                };
+                leaf(kind)
+            }
+
+            (Grammar::Java, "class_declaration") => container("class"),
+            (Grammar::Java, "interface_declaration") => container("interface"),
+            (Grammar::Java, "enum_declaration") => container("enum"),
+            (Grammar::Java, "record_declaration") => container("record"),
+            (Grammar::Java, "annotation_type_declaration") => container("annotation"),
+            (Grammar::Java, "method_declaration") => leaf("method"),
+            (Grammar::Java, "constructor_declaration" | "compact_constructor_declaration") => {
+                leaf("constructor")
+            }
+
+            _ => return None,
+        };
+
+        Some(self.definition(node, kind, is_container, self.field_text(node, "name")))
+    }
+
+    fn definition<'t>(
+        &self,
+        node: Node<'t>,
+        kind: &'static str,
+        container: bool,
+        name: Option<String>,
+    ) -> Definition<'t> {
+        // Include the `export` keyword of exported JS/TS declarations.
+        let outer = node
+            .parent()
+            .filter(|p| p.kind() == "export_statement")
+            .unwrap_or(node);
+        Definition {
+            node,
+            outer,
+            kind,
+            name,
+            container,
+            scope_name: None,
+            parent_override: None,
+        }
+    }
+}
+
+/// Scopes whose functions are methods.
+fn is_type_scope(kind: &str) -> bool {
+    matches!(
+        kind,
+        "impl" | "trait" | "class" | "interface" | "enum" | "record"
+    )
+}
+
+fn is_function_value(kind: &str) -> bool {
+    matches!(
+        kind,
+        "arrow_function" | "function_expression" | "function" | "generator_function"
+    )
+}
+
+fn strip_generics(name: &str) -> String {
+    name.split('<').next().unwrap_or(name).trim().to_string()
+}
+
+fn find_descendant<'t>(node: Node<'t>, kind: &str) -> Option<Node<'t>> {
+    if node.kind() == kind {
+        return Some(node);
+    }
+    let mut cursor = node.walk();
+    node.named_children(&mut cursor)
+        .find_map(|child| find_descendant(child, kind))
+}
+
+/// Start of `node` extended over directly preceding doc comments and attributes.
+fn leading_start(node: Node<'_>) -> usize {
+    let mut start = node.start_byte();
+    let mut row = node.start_position().row;
+    let mut prev = node.prev_named_sibling();
+
+    while let Some(sibling) = prev {
+        let decoration = sibling.kind() == "attribute_item" || sibling.kind().contains("comment");
+        // Only attach comments and attributes on the lines right above.
+        if !decoration || sibling.end_position().row + 1 < row {
+            break;
+        }
+        start = sibling.start_byte();
+        row = sibling.start_position().row;
+        prev = sibling.prev_named_sibling();
+    }
+    start
+}
+
+/// `range` with the (sorted, non-overlapping) `holes` removed.
+fn subtract(range: Range<usize>, holes: &[Range<usize>]) -> Vec<Range<usize>> {
+    let mut remaining = Vec::new();
+    let mut cursor = range.start;
+    for hole in holes {
+        if hole.start > cursor {
+            remaining.push(cursor..hole.start.min(range.end));
+        }
+        cursor = cursor.max(hole.end);
+    }
+    if cursor < range.end {
+        remaining.push(cursor..range.end);
+    }
+    remaining
+}
+
+/// Text of `range` without its members, with runs of blank lines collapsed.
+///
+/// Separators left behind by removed members (a field's trailing `;`) count as blank.
+fn skeleton(src: &str, range: Range<usize>, members: &[Range<usize>]) -> String {
+    let text: String = subtract(range, members)
+        .into_iter()
+        .map(|r| &src[r])
+        .collect();
+
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines() {
+        if matches!(line.trim(), ";" | ",") {
+            continue;
+        }
+        let blank = line.trim().is_empty();
+        if blank && lines.last().is_none_or(|l| l.trim().is_empty()) {
+            continue;
+        }
+        lines.push(line.trim_end());
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn symbols(
+        file: &str,
+        language: &str,
+        content: &str,
+    ) -> Vec<(String, Option<String>, Option<String>)> {
+        outline(Path::new(file), content, language)
+            .unwrap()
+            .symbols
+            .into_iter()
+            .map(|s| (s.kind.to_string(), s.name, s.parent))
+            .collect()
+    }
+
+    fn sym(
+        kind: &str,
+        name: &str,
+        parent: Option<&str>,
+    ) -> (String, Option<String>, Option<String>) {
+        (
+            kind.to_string(),
+            Some(name.to_string()),
+            parent.map(str::to_string),
+        )
+    }
+
+    #[test]
+    fn test_rust_impl_methods_and_attributes() {
+        let code = r#"use std::fmt;
+
+/// A point.
+#[derive(Debug)]
+pub struct Point { x: i32 }
+
+impl fmt::Display for Point {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let show = |v: i32| v.to_string();
+        write!(f, "{}", show(self.x))
+    }
+}
+
+mod inner {
+    pub trait Shape {
+        fn area(&self) -> f64;
+    }
+}
+"#;
+        let outline = outline(Path::new("lib.rs"), code, "rust").unwrap();
+        let found: Vec<_> = outline
+            .symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_deref(), s.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("struct", Some("Point"), None),
+                ("method", Some("fmt"), Some("Point")),
+                ("method", Some("area"), Some("inner::Shape")),
+            ]
+        );
+
+        // Doc comments and attributes belong to the definition.
+        let point = &outline.symbols[0];
+        assert!(point.content.starts_with("/// A point.\n#[derive(Debug)]"));
+        assert_eq!(&code[point.range.clone()], point.content);
+        let fmt = &outline.symbols[1];
+        assert!(fmt.content.starts_with("#[inline]"));
+        assert!(fmt.content.contains("let show"));
+
+        // The `use` line is left for line-based chunking.
+        assert_eq!(&code[outline.gaps[0].clone()].trim(), &"use std::fmt;");
+    }
+
+    #[test]
+    fn test_python_decorated_class() {
+        let code = r#"import os
+
+@dataclass
+class Config:
+    """Settings loaded from disk, with defaults for every field."""
+    path: str = "config.toml"
+    verbose: bool = False
+
+    @staticmethod
+    def load(path):
+        def helper():
+            return os.path.join(path, "x")
+        return helper()
+
+async def main():
+    pass
+"#;
+        assert_eq!(
+            symbols("app.py", "python", code),
+            vec![
+                sym("class", "Config", None),
+                sym("method", "load", Some("Config")),
+                sym("function", "main", None),
+            ]
+        );
+
+        let outline = outline(Path::new("app.py"), code, "python").unwrap();
+        let class = &outline.symbols[0];
+        assert!(class.skeleton);
+        assert!(class.content.starts_with("@dataclass\nclass Config:"));
+        assert!(class.content.contains("verbose: bool"));
+        assert!(!class.content.contains("def load"));
+    }
+
+    #[test]
+    fn test_typescript_exports_and_arrow_functions() {
+        let code = r#"export interface Props { name: string }
+
+export class Widget {
+  handleClick = () => { this.render(); };
+  render(): void {}
+}
+
+const helper = async (x: number) => x * 2;
+
+namespace Util {
+  export function id<T>(v: T): T { return v; }
+}
+"#;
+        assert_eq!(
+            symbols("widget.ts", "typescript", code),
+            vec![
+                sym("interface", "Props", None),
+                sym("method", "handleClick", Some("Widget")),
+                sym("method", "render", Some("Widget")),
+                sym("function", "helper", None),
+                sym("function", "id", Some("Util")),
+            ]
+        );
+
+        let outline = outline(Path::new("widget.ts"), code, "typescript").unwrap();
+        assert!(outline.symbols[0].content.starts_with("export interface"));
+    }
+
+    #[test]
+    fn test_go_methods_use_receiver_scope() {
+        let code = r#"package main
+
+// Server handles requests.
+type Server struct {
+	addr string
+}
+
+func (s *Server) Start() error {
+	return nil
+}
+
+func main() {}
+"#;
+        let outline = outline(Path::new("main.go"), code, "go").unwrap();
+        let found: Vec<_> = outline
+            .symbols
+            .iter()
+            .map(|s| (s.kind, s.name.as_deref(), s.parent.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("struct", Some("Server"), None),
+                ("method", Some("Start"), Some("Server")),
+                ("function", Some("main"), None),
+            ]
+        );
+        assert!(outline.symbols[0].content.starts_with("// Server handles"));
+    }
+
+    #[test]
+    fn test_java_nested_classes() {
+        let code = r#"package demo;
+
+public class Outer {
+    private final int count = 0;
+
+    public Outer() {}
+
+    @Override
+    public String toString() { return "outer"; }
+
+    static class Inner {
+        void run() {}
+    }
+}
+"#;
+        assert_eq!(
+            symbols("Outer.java", "java", code),
+            vec![
+                sym("class", "Outer", None),
+                sym("constructor", "Outer", Some("Outer")),
+                sym("method", "toString", Some("Outer")),
+                sym("method", "run", Some("Outer.Inner")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unsupported_language() {
+        assert!(outline(Path::new("notes.md"), "# Title", "markdown").is_none());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Supports multiple embedding providers:
 //! - OpenAI (text-embedding-3-small/large)
-//! - Local hash-based (for testing/offline)
+//! - Local lexical (offline, CPU only)
 
 use std::collections::HashMap;
 use std::collections::hash_map::DefaultHasher;
@@ -62,10 +62,10 @@ impl Default for EmbedderConfig {
     fn default() -> Self {
         Self {
             provider: "local".to_string(),
-            model: "local-hash".to_string(),
+            model: "local-lexical".to_string(),
             api_key: None,
             endpoint: None,
-            dimensions: 384,
+            dimensions: 1024,
             batch_size: 100,
             cache_enabled: true,
             cache_size: 10000,
@@ -88,12 +88,12 @@ impl EmbedderConfig {
         }
     }
 
-    /// Create config for local hash-based embeddings.
+    /// Create config for local lexical embeddings.
     pub fn local() -> Self {
         Self {
             provider: "local".to_string(),
-            model: "local-hash".to_string(),
-            dimensions: 384,
+            model: "local-lexical".to_string(),
+            dimensions: 1024,
             ..Default::default()
         }
     }
@@ -358,7 +358,33 @@ struct OpenAIEmbeddingData {
     index: usize,
 }
 
-/// Local hash-based embedder for testing/offline use.
+/// Weight of the character trigram block of each word relative to the word itself.
+const NGRAM_WEIGHT: f32 = 0.5;
+
+/// BM25 term frequency saturation parameter.
+const BM25_K1: f32 = 1.2;
+
+/// Weight multiplier for stopwords and very short tokens.
+const STOPWORD_WEIGHT: f32 = 0.1;
+
+/// Common English and code words that carry little meaning on their own.
+const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "do", "does", "for", "from",
+    "has", "have", "how", "i", "if", "in", "into", "is", "it", "its", "me", "my", "no", "not",
+    "of", "on", "or", "our", "so", "that", "the", "their", "then", "there", "these", "this", "to",
+    "was", "we", "what", "when", "where", "which", "who", "why", "will", "with", "you", "your",
+    "fn", "let", "mut", "pub", "self", "def", "var", "const", "return", "new", "use", "import",
+];
+
+/// Local lexical embedder that runs offline on CPU.
+///
+/// Texts are split into words, identifier subwords (`parseConfig` and
+/// `parse_config` both yield `parse` and `config`) and character trigrams, so
+/// texts sharing vocabulary or word stems score as similar. Term frequencies
+/// are saturated BM25-style, stopwords are down-weighted, and features are
+/// hashed with a sign bit into a fixed number of dimensions (the hashing trick)
+/// before L2 normalization. Embeddings are deterministic across runs and
+/// platforms, so stored vectors stay comparable.
 #[derive(Debug)]
 pub struct LocalEmbedder {
     config: EmbedderConfig,
@@ -370,39 +396,135 @@ impl LocalEmbedder {
         Self { config }
     }
 
-    /// Generate deterministic embedding from text hash.
-    fn hash_embed(&self, text: &str) -> Embedding {
-        let mut hasher = DefaultHasher::new();
-        text.hash(&mut hasher);
-        let hash = hasher.finish();
-
-        // Generate pseudo-random embedding from hash
-        let mut rng_state = hash;
-        let embedding: Vec<f32> = (0..self.config.dimensions)
-            .map(|_| {
-                rng_state = rng_state.wrapping_mul(6364136223846793005).wrapping_add(1);
-                (rng_state as f32 / u64::MAX as f32) * 2.0 - 1.0
-            })
-            .collect();
-
-        // Normalize to unit vector
+    /// Generate a normalized lexical embedding for `text`.
+    fn lexical_embed(&self, text: &str) -> Embedding {
+        let dimensions = self.config.dimensions.max(1);
+        let mut embedding = vec![0.0f32; dimensions];
+
+        for (feature, tf) in lexical_features(text) {
+            // BM25 saturation: repeated terms matter, but with diminishing returns.
+            let weight = tf * (BM25_K1 + 1.0) / (tf + BM25_K1);
+            let hash = fnv1a(feature.as_bytes());
+            let index = (hash % dimensions as u64) as usize;
+            let sign = if hash >> 63 == 1 { -1.0 } else { 1.0 };
+            embedding[index] += sign * weight;
+        }
+
         let norm: f32 = embedding.iter().map(|x| x * x).sum::<f32>().sqrt();
         if norm > 0.0 {
-            embedding.into_iter().map(|x| x / norm).collect()
-        } else {
-            embedding
+            for x in &mut embedding {
+                *x /= norm;
+            }
         }
+        embedding
     }
 }
 
+/// Weighted lexical features of `text`, keyed by a namespaced feature string.
+fn lexical_features(text: &str) -> HashMap<String, f32> {
+    let mut features: HashMap<String, f32> = HashMap::new();
+
+    for word in text
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|w| !w.is_empty())
+    {
+        let subwords = split_identifier(word);
+        let whole = normalize_term(&word.to_lowercase());
+
+        *features.entry(format!("w:{}", whole)).or_default() += term_weight(&whole);
+        if subwords.len() > 1 {
+            for subword in &subwords {
+                *features.entry(format!("w:{}", subword)).or_default() += term_weight(subword);
+            }
+        }
+
+        for subword in &subwords {
+            let weight = term_weight(subword);
+            let padded: Vec<char> = format!("^{}$", subword).chars().collect();
+            if padded.len() < 5 {
+                // Short words are fully described by the word feature.
+                continue;
+            }
+            let trigrams = padded.windows(3).count() as f32;
+            for trigram in padded.windows(3) {
+                *features
+                    .entry(format!("g:{}", trigram.iter().collect::<String>()))
+                    .or_default() += weight * NGRAM_WEIGHT / trigrams.sqrt();
+            }
+        }
+    }
+
+    features
+}
+
+/// Split an identifier into lowercase subwords at `_`, case and digit boundaries.
+fn split_identifier(word: &str) -> Vec<String> {
+    let mut subwords = Vec::new();
+    let mut current = String::new();
+    let chars: Vec<char> = word.chars().collect();
+
+    for (i, &c) in chars.iter().enumerate() {
+        if c == '_' {
+            if !current.is_empty() {
+                subwords.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+        if let Some(&prev) = i.checked_sub(1).and_then(|p| chars.get(p)) {
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            let boundary = (prev.is_lowercase() && c.is_uppercase())
+                || (prev.is_uppercase() && c.is_uppercase() && next_lower)
+                || (prev.is_alphabetic() != c.is_alphabetic() && prev != '_');
+            if boundary && !current.is_empty() {
+                subwords.push(std::mem::take(&mut current));
+            }
+        }
+        current.extend(c.to_lowercase());
+    }
+    if !current.is_empty() {
+        subwords.push(current);
+    }
+
+    subwords.iter().map(|s| normalize_term(s)).collect()
+}
+
+/// Light stemming: fold simple plurals so `files` matches `file`.
+fn normalize_term(term: &str) -> String {
+    if term.len() > 3
+        && term.ends_with('s')
+        && !term.ends_with("ss")
+        && term.chars().all(|c| c.is_alphabetic())
+    {
+        term[..term.len() - 1].to_string()
+    } else {
+        term.to_string()
+    }
+}
+
+/// Inverse-document-frequency proxy for a term without corpus statistics.
+fn term_weight(term: &str) -> f32 {
+    if term.chars().count() <= 1 || STOPWORDS.contains(&term) {
+        STOPWORD_WEIGHT
+    } else {
+        1.0
+    }
+}
+
+/// 64-bit FNV-1a, stable across Rust versions unlike `DefaultHasher`.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
 #[async_trait::async_trait]
 impl Embedder for LocalEmbedder {
     async fn embed(&self, text: &str) -> Result<Embedding> {
-        Ok(self.hash_embed(text))
+        Ok(self.lexical_embed(text))
     }
 
     async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Embedding>> {
-        Ok(texts.iter().map(|t| self.hash_embed(t)).collect())
+        Ok(texts.iter().map(|t| self.lexical_embed(t)).collect())
     }
 
     fn dimensions(&self) -> usize {
@@ -436,7 +558,7 @@ mod tests {
         let embedder = LocalEmbedder::new(config);
 
         let embedding = embedder.embed("hello world").await.unwrap();
-        assert_eq!(embedding.len(), 384);
+        assert_eq!(embedding.len(), 1024);
 
         // Same text should give same embedding
         let embedding2 = embedder.embed("hello world").await.unwrap();
@@ -469,10 +591,43 @@ mod tests {
 
         assert_eq!(embeddings.len(), 3);
         for emb in &embeddings {
-            assert_eq!(emb.len(), 384);
+            assert_eq!(emb.len(), 1024);
         }
     }
 
+    #[tokio::test]
+    async fn test_local_embedder_similarity() {
+        use crate::memory::store::cosine_similarity;
+
+        let embedder = LocalEmbedder::new(EmbedderConfig::local());
+        let embed = |t: &'static str| embedder.embed(t);
+
+        let query = embed("parse the configuration file").await.unwrap();
+        let related = embed("fn parseConfigFile(path: &Path) -> Config")
+            .await
+            .unwrap();
+        let typo = embed("parse the configuraton file").await.unwrap();
+        let unrelated = embed("render a progress bar in the terminal")
+            .await
+            .unwrap();
+
+        let related_score = cosine_similarity(&query, &related);
+        let unrelated_score = cosine_similarity(&query, &unrelated);
+        assert!(related_score > 0.3, "related: {related_score}");
+        assert!(unrelated_score < 0.1, "unrelated: {unrelated_score}");
+        assert!(cosine_similarity(&query, &typo) > 0.7);
+    }
+
+    #[test]
+    fn test_split_identifier() {
+        assert_eq!(
+            split_identifier("parseConfigFile"),
+            ["parse", "config", "file"]
+        );
+        assert_eq!(split_identifier("HTTPServer"), ["http", "server"]);
+        assert_eq!(split_identifier("read_files2"), ["read", "file", "2"]);
+    }
+
     #[test]
     fn test_embedding_cache() {
         let mut cache = EmbeddingCache::new(2);
@@ -3,7 +3,7 @@
 //! Provides:
 //! - File watching for incremental updates
 //! - Code chunking by functions/classes
-//! - Language-aware parsing
+//! - Language-aware parsing with tree-sitter
 //! - Metadata extraction
 
 use std::collections::HashMap;
@@ -13,10 +13,17 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+use super::chunker::{self, SymbolChunk};
 use super::embedding::Embedder;
 use super::store::{Memory, MemoryMetadata, MemoryStore, MemoryType};
 use crate::error::Result;
 
+/// Definitions longer than this many `chunk_size`s are split into line windows.
+const MAX_SYMBOL_CHUNKS: usize = 4;
+
+/// Top-level code outside definitions with less non-whitespace text than this is skipped.
+const MIN_GAP_CHARS: usize = 50;
+
 /// Indexer configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct IndexerConfig {
@@ -100,11 +107,26 @@ pub struct CodeChunk {
     pub entity_name: Option<String>,
     /// Parent entity name (e.g., class for a method).
     pub parent_name: Option<String>,
+    /// Start byte offset in the file.
+    #[serde(default)]
+    pub start_byte: usize,
+    /// End byte offset in the file (exclusive).
+    #[serde(default)]
+    pub end_byte: usize,
 }
 
 impl CodeChunk {
     /// Create metadata for this chunk.
     pub fn to_metadata(&self) -> MemoryMetadata {
+        let mut custom = HashMap::new();
+        custom.insert(
+            "byte_range".to_string(),
+            serde_json::json!([self.start_byte, self.end_byte]),
+        );
+        if let Some(parent) = &self.parent_name {
+            custom.insert("parent_scope".to_string(), serde_json::json!(parent));
+        }
+
         MemoryMetadata {
             file_path: Some(self.file_path.clone()),
             line_range: Some((self.start_line, self.end_line)),
@@ -116,7 +138,7 @@ impl CodeChunk {
                     .clone()
                     .unwrap_or_else(|| "chunk".to_string()),
             ],
-            custom: HashMap::new(),
+            custom,
         }
     }
 }
@@ -245,39 +267,67 @@ impl FileIndexer {
 
     /// Chunk by line-based sliding window.
     fn chunk_by_lines(&self, path: &Path, content: &str, language: &str) -> Vec<CodeChunk> {
-        let lines: Vec<&str> = content.lines().collect();
+        self.chunk_range_by_lines(path, content, 0..content.len(), language)
+    }
+
+    /// Chunk a byte range of `content` by line-based sliding window.
+    fn chunk_range_by_lines(
+        &self,
+        path: &Path,
+        content: &str,
+        range: std::ops::Range<usize>,
+        language: &str,
+    ) -> Vec<CodeChunk> {
+        let first_line = content[..range.start].matches('\n').count();
+
+        // (byte offset, text) of each line, line endings excluded
+        let mut offset = range.start;
+        let lines: Vec<(usize, &str)> = content[range]
+            .split_inclusive('\n')
+            .map(|line| {
+                let start = offset;
+                offset += line.len();
+                (start, line.trim_end_matches(['\n', '\r']))
+            })
+            .collect();
         let mut chunks = Vec::new();
 
         if lines.is_empty() {
             return chunks;
         }
 
-        let lines_per_chunk = self.config.chunk_size / 80; // Approximate lines
+        let lines_per_chunk = (self.config.chunk_size / 80).max(1); // Approximate lines
         let overlap_lines = self.config.chunk_overlap / 80;
 
         let mut start = 0;
 
         while start < lines.len() {
             let end = (start + lines_per_chunk).min(lines.len());
-            let chunk_content = lines[start..end].join("\n");
+            let (start_byte, _) = lines[start];
+            let (last_start, last_line) = lines[end - 1];
+            let end_byte = last_start + last_line.len();
+            let start_line = first_line + start + 1;
+            let end_line = first_line + end;
 
             chunks.push(CodeChunk {
-                id: format!("{}:{}:{}", path.display(), start + 1, end),
+                id: format!("{}:{}:{}", path.display(), start_line, end_line),
                 file_path: path.to_path_buf(),
-                content: chunk_content,
-                start_line: start + 1,
-                end_line: end,
+                content: content[start_byte..end_byte].to_string(),
+                start_line,
+                end_line,
                 language: language.to_string(),
                 entity_type: Some("chunk".to_string()),
                 entity_name: None,
                 parent_name: None,
+                start_byte,
+                end_byte,
             });
 
             if end >= lines.len() {
                 break;
             }
 
-            start = if end > overlap_lines {
+            start = if end > overlap_lines && end - overlap_lines > start {
                 end - overlap_lines
             } else {
                 end
@@ -288,334 +338,91 @@ impl FileIndexer {
     }
 
     /// Chunk by code structure (functions, classes, etc).
+    ///
+    /// Supported languages are parsed with tree-sitter and split into one chunk
+    /// per definition; top-level code between definitions is chunked by lines.
+    /// Other languages, and files without any definitions, are chunked by lines.
     fn chunk_by_structure(&self, path: &Path, content: &str, language: &str) -> Vec<CodeChunk> {
-        // Use simple heuristics for now
-        // In production, use tree-sitter for proper parsing
-        match language {
-            "rust" => self.chunk_rust(path, content),
-            "python" => self.chunk_python(path, content),
-            "javascript" | "typescript" => self.chunk_javascript(path, content),
-            _ => self.chunk_by_lines(path, content, language),
-        }
-    }
-
-    /// Chunk Rust code by functions and impl blocks.
-    fn chunk_rust(&self, path: &Path, content: &str) -> Vec<CodeChunk> {
-        let mut chunks = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-
-        let mut current_chunk_start = 0;
-        let mut brace_depth = 0;
-        let mut in_function = false;
-        let mut function_name = None;
-        let mut current_impl = None;
-
-        for (i, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-
-            // Track impl blocks
-            if trimmed.starts_with("impl ") {
-                if let Some(name) = trimmed
-                    .strip_prefix("impl ")
-                    .and_then(|s| s.split_whitespace().next())
-                {
-                    current_impl = Some(name.trim_end_matches('{').to_string());
-                }
-            }
-
-            // Track function definitions
-            if (trimmed.starts_with("pub fn ")
-                || trimmed.starts_with("fn ")
-                || trimmed.starts_with("pub async fn ")
-                || trimmed.starts_with("async fn "))
-                && !in_function
-            {
-                in_function = true;
-                current_chunk_start = i;
-
-                // Extract function name
-                let fn_start = trimmed.find("fn ").unwrap_or(0) + 3;
-                if let Some(end) = trimmed[fn_start..].find('(') {
-                    function_name = Some(trimmed[fn_start..fn_start + end].to_string());
-                }
-            }
-
-            // Track braces
-            brace_depth += line.chars().filter(|c| *c == '{').count() as i32;
-            brace_depth -= line.chars().filter(|c| *c == '}').count() as i32;
-
-            // End of function
-            if in_function && brace_depth == 0 && trimmed.ends_with('}') {
-                let chunk_content = lines[current_chunk_start..=i].join("\n");
-
-                if !chunk_content.trim().is_empty() {
-                    chunks.push(CodeChunk {
-                        id: format!(
-                            "{}:fn:{}",
-                            path.display(),
-                            function_name.as_deref().unwrap_or("unknown")
-                        ),
-                        file_path: path.to_path_buf(),
-                        content: chunk_content,
-                        start_line: current_chunk_start + 1,
-                        end_line: i + 1,
-                        language: "rust".to_string(),
-                        entity_type: Some("function".to_string()),
-                        entity_name: function_name.take(),
-                        parent_name: current_impl.clone(),
-                    });
-                }
-
-                in_function = false;
-                current_chunk_start = i + 1;
-            }
-
-            // Reset impl tracking when leaving impl block
-            if brace_depth == 0 && trimmed == "}" {
-                current_impl = None;
-            }
-        }
-
-        // Handle remaining content
-        if current_chunk_start < lines.len() {
-            let remaining = lines[current_chunk_start..].join("\n");
-            if !remaining.trim().is_empty() && remaining.len() > 50 {
-                // Fall back to line-based chunking for remaining
-                chunks.extend(self.chunk_by_lines(path, &remaining, "rust"));
-            }
-        }
-
-        if chunks.is_empty() {
-            // Fall back to line-based if no functions found
-            return self.chunk_by_lines(path, content, "rust");
+        let Some(outline) = chunker::outline(path, content, language) else {
+            return self.chunk_by_lines(path, content, language);
+        };
+        if outline.symbols.is_empty() {
+            return self.chunk_by_lines(path, content, language);
         }
 
-        chunks
-    }
-
-    /// Chunk Python code by functions and classes.
-    fn chunk_python(&self, path: &Path, content: &str) -> Vec<CodeChunk> {
         let mut chunks = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-
-        let mut current_chunk_start = 0;
-        let mut current_indent = 0;
-        let mut in_definition = false;
-        let mut entity_type = None;
-        let mut entity_name = None;
-        let mut current_class = None;
-
-        for (i, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-            let indent = line.len() - line.trim_start().len();
-
-            // Track class definitions
-            if trimmed.starts_with("class ") {
-                // Save previous chunk
-                if in_definition && i > current_chunk_start {
-                    let chunk_content = lines[current_chunk_start..i].join("\n");
-                    if !chunk_content.trim().is_empty() {
-                        chunks.push(CodeChunk {
-                            id: format!(
-                                "{}:{}:{}",
-                                path.display(),
-                                entity_type.as_deref().unwrap_or("chunk"),
-                                entity_name.as_deref().unwrap_or("unknown")
-                            ),
-                            file_path: path.to_path_buf(),
-                            content: chunk_content,
-                            start_line: current_chunk_start + 1,
-                            end_line: i,
-                            language: "python".to_string(),
-                            entity_type: entity_type.take(),
-                            entity_name: entity_name.take(),
-                            parent_name: current_class.clone(),
-                        });
-                    }
-                }
-
-                current_chunk_start = i;
-                current_indent = indent;
-                in_definition = true;
-                entity_type = Some("class".to_string());
-
-                if let Some(name) = trimmed
-                    .strip_prefix("class ")
-                    .and_then(|s| s.split(['(', ':']).next())
-                {
-                    current_class = Some(name.to_string());
-                    entity_name = Some(name.to_string());
-                }
-            }
-            // Track function definitions
-            else if trimmed.starts_with("def ") || trimmed.starts_with("async def ") {
-                // Save previous chunk if at same or lower indent
-                if in_definition && indent <= current_indent && i > current_chunk_start {
-                    let chunk_content = lines[current_chunk_start..i].join("\n");
-                    if !chunk_content.trim().is_empty() {
-                        chunks.push(CodeChunk {
-                            id: format!(
-                                "{}:{}:{}",
-                                path.display(),
-                                entity_type.as_deref().unwrap_or("chunk"),
-                                entity_name.as_deref().unwrap_or("unknown")
-                            ),
-                            file_path: path.to_path_buf(),
-                            content: chunk_content,
-                            start_line: current_chunk_start + 1,
-                            end_line: i,
-                            language: "python".to_string(),
-                            entity_type: entity_type.take(),
-                            entity_name: entity_name.take(),
-                            parent_name: if indent > 0 {
-                                current_class.clone()
-                            } else {
-                                None
-                            },
-                        });
-                    }
-                }
-
-                current_chunk_start = i;
-                current_indent = indent;
-                in_definition = true;
-                entity_type = Some(if indent > 0 { "method" } else { "function" }.to_string());
-
-                let prefix = if trimmed.starts_with("async def ") {
-                    "async def "
-                } else {
-                    "def "
-                };
-                if let Some(name) = trimmed
-                    .strip_prefix(prefix)
-                    .and_then(|s| s.split('(').next())
-                {
-                    entity_name = Some(name.to_string());
-                }
-
-                // Reset class tracking if at module level
-                if indent == 0 {
-                    current_class = None;
-                }
-            }
+        for symbol in outline.symbols {
+            chunks.extend(self.symbol_chunks(path, content, language, symbol));
         }
-
-        // Handle last chunk
-        if in_definition && current_chunk_start < lines.len() {
-            let chunk_content = lines[current_chunk_start..].join("\n");
-            if !chunk_content.trim().is_empty() {
-                chunks.push(CodeChunk {
-                    id: format!(
-                        "{}:{}:{}",
-                        path.display(),
-                        entity_type.as_deref().unwrap_or("chunk"),
-                        entity_name.as_deref().unwrap_or("unknown")
-                    ),
-                    file_path: path.to_path_buf(),
-                    content: chunk_content,
-                    start_line: current_chunk_start + 1,
-                    end_line: lines.len(),
-                    language: "python".to_string(),
-                    entity_type,
-                    entity_name,
-                    parent_name: current_class,
-                });
+        for gap in outline.gaps {
+            let text = &content[gap.clone()];
+            if text.chars().filter(|c| !c.is_whitespace()).count() < MIN_GAP_CHARS {
+                continue;
             }
+            // Skip the blank lines around the gap.
+            let start = gap.start + (text.len() - text.trim_start().len());
+            let end = gap.start + text.trim_end().len();
+            chunks.extend(self.chunk_range_by_lines(path, content, start..end, language));
         }
 
-        if chunks.is_empty() {
-            return self.chunk_by_lines(path, content, "python");
-        }
-
+        chunks.sort_by_key(|c| c.start_byte);
         chunks
     }
 
-    /// Chunk JavaScript/TypeScript code.
-    fn chunk_javascript(&self, path: &Path, content: &str) -> Vec<CodeChunk> {
-        let mut chunks = Vec::new();
-        let lines: Vec<&str> = content.lines().collect();
-
-        let mut current_chunk_start = 0;
-        let mut brace_depth = 0;
-        let mut in_function = false;
-        let mut function_name = None;
-        let mut current_class = None;
-
-        for (i, line) in lines.iter().enumerate() {
-            let trimmed = line.trim();
-
-            // Track class definitions
-            if trimmed.starts_with("class ") || trimmed.starts_with("export class ") {
-                let prefix = if trimmed.starts_with("export ") {
-                    "export class "
-                } else {
-                    "class "
-                };
-                if let Some(name) = trimmed
-                    .strip_prefix(prefix)
-                    .and_then(|s| s.split_whitespace().next())
-                {
-                    current_class = Some(name.trim_end_matches('{').to_string());
-                }
-            }
-
-            // Track function definitions
-            let is_function = trimmed.starts_with("function ")
-                || trimmed.starts_with("async function ")
-                || trimmed.starts_with("export function ")
-                || trimmed.starts_with("export async function ")
-                || trimmed.contains("=>")
-                || (trimmed.ends_with('{') && trimmed.contains('('));
-
-            if is_function && !in_function {
-                in_function = true;
-                current_chunk_start = i;
-
-                // Extract function name
-                if let Some(fn_idx) = trimmed.find("function ") {
-                    let start = fn_idx + 9;
-                    if let Some(end) = trimmed[start..].find('(') {
-                        function_name = Some(trimmed[start..start + end].to_string());
-                    }
-                }
-            }
-
-            // Track braces
-            brace_depth += line.chars().filter(|c| *c == '{').count() as i32;
-            brace_depth -= line.chars().filter(|c| *c == '}').count() as i32;
-
-            // End of function
-            if in_function && brace_depth == 0 {
-                let chunk_content = lines[current_chunk_start..=i].join("\n");
-
-                if !chunk_content.trim().is_empty() {
-                    chunks.push(CodeChunk {
-                        id: format!(
-                            "{}:fn:{}",
-                            path.display(),
-                            function_name.as_deref().unwrap_or("anonymous")
-                        ),
-                        file_path: path.to_path_buf(),
-                        content: chunk_content,
-                        start_line: current_chunk_start + 1,
-                        end_line: i + 1,
-                        language: self.detect_language(path),
-                        entity_type: Some("function".to_string()),
-                        entity_name: function_name.take(),
-                        parent_name: current_class.clone(),
-                    });
-                }
-
-                in_function = false;
-                current_chunk_start = i + 1;
-            }
-        }
-
-        if chunks.is_empty() {
-            return self.chunk_by_lines(path, content, &self.detect_language(path));
+    /// Convert a definition into chunks, splitting oversized definitions by lines.
+    fn symbol_chunks(
+        &self,
+        path: &Path,
+        content: &str,
+        language: &str,
+        symbol: SymbolChunk,
+    ) -> Vec<CodeChunk> {
+        let line_of = |byte: usize| content[..byte].matches('\n').count() + 1;
+        let start_line = line_of(symbol.range.start);
+        let end_line = line_of(symbol.range.end.saturating_sub(1).max(symbol.range.start));
+
+        let separator = if language == "rust" { "::" } else { "." };
+        let qualified = match (&symbol.parent, &symbol.name) {
+            (Some(parent), Some(name)) => format!("{}{}{}", parent, separator, name),
+            (None, Some(name)) => name.clone(),
+            (_, None) => "anonymous".to_string(),
+        };
+        let id = format!(
+            "{}:{}:{}:{}",
+            path.display(),
+            symbol.kind,
+            qualified,
+            start_line
+        );
+
+        let oversized = symbol.content.len() > self.config.chunk_size * MAX_SYMBOL_CHUNKS;
+        if !oversized || symbol.skeleton {
+            return vec![CodeChunk {
+                id,
+                file_path: path.to_path_buf(),
+                content: symbol.content,
+                start_line,
+                end_line,
+                language: language.to_string(),
+                entity_type: Some(symbol.kind.to_string()),
+                entity_name: symbol.name,
+                parent_name: symbol.parent,
+                start_byte: symbol.range.start,
+                end_byte: symbol.range.end,
+            }];
         }
 
-        chunks
+        self.chunk_range_by_lines(path, content, symbol.range, language)
+            .into_iter()
+            .enumerate()
+            .map(|(part, chunk)| CodeChunk {
+                id: format!("{}#{}", id, part + 1),
+                entity_type: Some(symbol.kind.to_string()),
+                entity_name: symbol.name.clone(),
+                parent_name: symbol.parent.clone(),
+                ..chunk
+            })
+            .collect()
     }
 }
 
@@ -857,7 +664,7 @@ impl Foo {
 }
 "#;
 
-        let chunks = indexer.chunk_rust(Path::new("test.rs"), rust_code);
+        let chunks = indexer.chunk_file(Path::new("test.rs"), rust_code);
         assert!(!chunks.is_empty());
 
         // Should find at least the functions
@@ -867,6 +674,15 @@ impl Foo {
             .collect();
         assert!(fn_names.contains(&&"hello".to_string()));
         assert!(fn_names.contains(&&"world".to_string()));
+
+        let bar = chunks
+            .iter()
+            .find(|c| c.entity_name.as_deref() == Some("bar"))
+            .unwrap();
+        assert_eq!(bar.entity_type.as_deref(), Some("method"));
+        assert_eq!(bar.parent_name.as_deref(), Some("Foo"));
+        assert_eq!(bar.start_line, 11);
+        assert_eq!(&rust_code[bar.start_byte..bar.end_byte], bar.content);
     }
 
     #[test]
@@ -886,8 +702,21 @@ async def async_func():
     pass
 "#;
 
-        let chunks = indexer.chunk_python(Path::new("test.py"), python_code);
+        let chunks = indexer.chunk_file(Path::new("test.py"), python_code);
         assert!(!chunks.is_empty());
+
+        let names: Vec<_> = chunks
+            .iter()
+            .map(|c| (c.entity_type.as_deref(), c.entity_name.as_deref()))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                (Some("function"), Some("hello")),
+                (Some("method"), Some("bar")),
+                (Some("function"), Some("async_func")),
+            ]
+        );
     }
 
     #[test]
@@ -902,11 +731,33 @@ async def async_func():
             entity_type: Some("function".to_string()),
             entity_name: Some("test".to_string()),
             parent_name: None,
+            start_byte: 0,
+            end_byte: 12,
         };
 
         let metadata = chunk.to_metadata();
         assert_eq!(metadata.language, Some("rust".to_string()));
         assert_eq!(metadata.entity_name, Some("test".to_string()));
         assert_eq!(metadata.line_range, Some((1, 1)));
+        assert_eq!(metadata.custom["byte_range"], serde_json::json!([0, 12]));
+    }
+
+    #[test]
+    fn test_chunk_gaps_and_fallback() {
+        let indexer = FileIndexer::new(IndexerConfig::default());
+        let code = "use std::collections::HashMap;\nuse std::sync::Arc;\nuse std::path::PathBuf;\n\nfn run() {}\n";
+
+        let chunks = indexer.chunk_file(Path::new("main.rs"), code);
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].entity_type.as_deref(), Some("chunk"));
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 3));
+        assert_eq!(chunks[1].entity_name.as_deref(), Some("run"));
+        assert_eq!(chunks[1].start_line, 5);
+
+        // Languages without a grammar are chunked by lines.
+        let chunks = indexer.chunk_file(Path::new("notes.md"), "# Notes\n\nSome text.\n");
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].content, "# Notes\n\nSome text.");
+        assert_eq!(chunks[0].end_byte, 19);
     }
 }
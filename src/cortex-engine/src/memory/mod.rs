@@ -8,6 +8,7 @@
 //! - Memory decay and pruning
 //! - Session-specific vs global memories
 
+mod chunker;
 pub mod context;
 pub mod embedding;
 pub mod indexer;
@@ -92,6 +93,8 @@ impl MemorySystem {
             "local" | _ => Arc::new(LocalEmbedder::new(config.embedder.clone())),
         };
 
+        Self::reembed_mismatched(&store, embedder.as_ref(), config.embedder.batch_size).await?;
+
         let retriever = Arc::new(Retriever::new(
             store.clone(),
             embedder.clone(),
@@ -120,6 +123,39 @@ impl MemorySystem {
         })
     }
 
+    /// Re-embed memories stored with a different embedding dimension.
+    ///
+    /// Vectors from another embedder (e.g. before the default dimension
+    /// changed) never match the active embedder's queries, so they are
+    /// replaced when the store is opened.
+    async fn reembed_mismatched(
+        store: &MemoryStore,
+        embedder: &dyn Embedder,
+        batch_size: usize,
+    ) -> Result<usize> {
+        let stale = store.mismatched_embeddings(embedder.dimensions()).await?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+        tracing::info!(
+            count = stale.len(),
+            dimensions = embedder.dimensions(),
+            "Re-embedding memories stored with a different embedding dimension"
+        );
+
+        for batch in stale.chunks(batch_size.max(1)) {
+            let texts: Vec<&str> = batch.iter().map(|m| m.content.as_str()).collect();
+            let embeddings = embedder.embed_batch(&texts).await?;
+            for (memory, embedding) in batch.iter().zip(embeddings) {
+                let mut memory = memory.clone();
+                memory.embedding = embedding;
+                store.update(memory).await?;
+            }
+        }
+        store.save().await?;
+        Ok(stale.len())
+    }
+
     /// Store a new memory.
     pub async fn store_memory(&self, content: &str, memory_type: MemoryType) -> Result<Memory> {
         self.store_with_metadata(content, memory_type, MemoryMetadata::default())
@@ -325,7 +361,6 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Local hash embedder doesn't produce semantically similar embeddings"]
     async fn test_store_and_search() {
         let config = MemorySystemConfig {
             store: MemoryStoreConfig {
@@ -357,4 +392,46 @@ mod tests {
         assert!(!results.is_empty());
         assert!(results[0].score > 0.5);
     }
+
+    #[tokio::test]
+    async fn test_reembeds_memories_with_old_dimension() {
+        let dir = tempfile::tempdir().unwrap();
+        let store_config = MemoryStoreConfig {
+            backend: StorageBackend::Sqlite(dir.path().join("memory.db")),
+            ..Default::default()
+        };
+
+        // A memory written when the local embedder produced 384-dim vectors
+        let old = Memory::new(
+            "The capital of France is Paris",
+            vec![0.05; 384],
+            MemoryType::Fact,
+            MemoryMetadata::default(),
+        );
+        let id = old.id;
+        MemoryStore::new(store_config.clone())
+            .await
+            .unwrap()
+            .insert(old)
+            .await
+            .unwrap();
+
+        let system = MemorySystem::new(MemorySystemConfig {
+            store: store_config,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+
+        let dimensions = system.embedder().dimensions();
+        assert_eq!(dimensions, 1024);
+        let memory = system.memory_store().get(id).await.unwrap().unwrap();
+        assert_eq!(memory.embedding.len(), dimensions);
+
+        let results = system
+            .search("What is the capital of France?", 5)
+            .await
+            .unwrap();
+        assert_eq!(results.first().map(|r| r.id), Some(id));
+    }
 }
@@ -48,7 +48,7 @@ impl Default for RetrieverConfig {
     fn default() -> Self {
         Self {
             top_k: 10,
-            similarity_threshold: 0.5,
+            similarity_threshold: 0.2,
             recency_weight: 0.2,
             relevance_weight: 0.3,
             include_content: true,
@@ -531,7 +531,6 @@ mod tests {
     }
 
     #[tokio::test]
-    #[ignore = "Local hash embedder doesn't produce semantically similar embeddings"]
     async fn test_basic_search() {
         let (store, retriever) = create_test_retriever().await;
         let embedder = Arc::new(LocalEmbedder::new(EmbedderConfig::local()));
@@ -557,7 +556,8 @@ mod tests {
 
         // Search
         let results = retriever.search("programming", 5).await.unwrap();
-        assert!(!results.is_empty());
+        assert_eq!(results.len(), 1);
+        assert!(results[0].content.contains("Rust"));
     }
 
     #[tokio::test]
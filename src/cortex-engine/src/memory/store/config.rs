@@ -13,7 +13,7 @@ pub enum StorageBackend {
     InMemory,
     /// JSON file storage.
     JsonFile(PathBuf),
-    /// SQLite with vector extension.
+    /// SQLite with full-text and vector search.
     Sqlite(PathBuf),
 }
 
@@ -1,7 +1,7 @@
 //! Memory storage backend.
 //!
 //! Provides persistent storage for memories with support for:
-//! - SQLite with vector search (sqlite-vec)
+//! - SQLite with FTS5 keyword search and an LSH vector index
 //! - File-based JSON persistence
 //! - In-memory storage for sessions
 
@@ -172,6 +172,29 @@ impl MemoryStore {
             .search_similar(embedding, limit, filter)
             .await
     }
+
+    /// Memories embedded with a dimension other than `dimensions`.
+    pub async fn mismatched_embeddings(&self, dimensions: usize) -> Result<Vec<Memory>> {
+        self.storage
+            .read()
+            .await
+            .mismatched_embeddings(dimensions)
+            .await
+    }
+
+    /// Search by keywords in memory content.
+    pub async fn search_text(
+        &self,
+        text: &str,
+        limit: usize,
+        filter: Option<MemoryFilter>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.storage
+            .read()
+            .await
+            .search_text(text, limit, filter)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -246,4 +269,25 @@ mod tests {
         storage.delete(id).await.unwrap();
         assert_eq!(storage.count().await.unwrap(), 0);
     }
+
+    #[tokio::test]
+    async fn test_default_search_text() {
+        let mut storage = InMemoryStorage::new();
+        for content in ["parse the config file", "render the config", "unrelated"] {
+            storage
+                .insert(Memory::new(
+                    content,
+                    vec![0.1],
+                    MemoryType::Note,
+                    MemoryMetadata::default(),
+                ))
+                .await
+                .unwrap();
+        }
+
+        let results = storage.search_text("Parse config", 10, None).await.unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0.content, "parse the config file");
+        assert_eq!(results[0].1, 1.0);
+    }
 }
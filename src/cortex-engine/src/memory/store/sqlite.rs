@@ -1,342 +1,1160 @@
-//! SQLite storage implementation with vector search.
+//! SQLite storage implementation with keyword and vector search.
+//!
+//! Memories live in a single SQLite database (WAL mode), so every insert,
+//! update and delete is an incremental write. The schema provides:
+//! - Versioned migrations tracked with `PRAGMA user_version`
+//! - An FTS5 index over memory content for BM25 keyword search
+//! - A random-hyperplane LSH bucket per embedding for approximate nearest
+//!   neighbor search on large stores, with an exact chunked scan for small
+//!   stores or when the probed buckets don't yield enough candidates
+//!
+//! Stores written as JSON by earlier versions are imported on first open and
+//! the original file is kept next to the database as `<name>.json.bak`.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
 
+use chrono::{DateTime, Utc};
+use rusqlite::types::Value as SqlValue;
+use rusqlite::{Connection, OptionalExtension, Row, Transaction, params, params_from_iter};
 use uuid::Uuid;
 
 use crate::error::{CortexError, Result};
 
 use super::query::{MemoryFilter, MemoryQuery};
 use super::traits::MemoryStorage;
-use super::types::{Embedding, Memory, MemoryType};
+use super::types::{Embedding, Memory, MemoryMetadata, MemoryScope, MemoryType};
 use super::utils::cosine_similarity;
 
-/// SQLite storage implementation with vector search.
+/// Microseconds per hour, used to compute memory age in SQL.
+const MICROS_PER_HOUR: i64 = 3_600_000_000;
+
+/// Number of random hyperplanes in an LSH signature (bits per bucket).
+const LSH_BITS: u32 = 16;
+
+/// Maximum Hamming distance probed around the query's bucket.
+const LSH_PROBE_RADIUS: u32 = 2;
+
+/// Stores with fewer embeddings than this are searched exactly.
+const EXACT_SCAN_THRESHOLD: i64 = 20_000;
+
+/// Rows fetched per page during an exact scan.
+const SCAN_CHUNK_SIZE: i64 = 2_048;
+
+/// Columns selected to rebuild a [`Memory`] (see [`row_to_memory`]).
+const MEMORY_COLUMNS: &str = "id, content, memory_type, scope, timestamp, last_accessed, \
+     relevance_score, access_count, metadata, embedding";
+
+/// Schema migrations, applied in order. Migration `i` brings the schema to version `i + 1`.
+const MIGRATIONS: &[&str] = &[
+    // v1: memories with tags and an external-content FTS5 index kept in sync by triggers.
+    r#"
+    CREATE TABLE memories (
+        seq INTEGER PRIMARY KEY,
+        id TEXT NOT NULL UNIQUE,
+        content TEXT NOT NULL,
+        memory_type TEXT NOT NULL,
+        scope TEXT NOT NULL,
+        timestamp INTEGER NOT NULL,
+        last_accessed INTEGER NOT NULL,
+        relevance_score REAL NOT NULL,
+        access_count INTEGER NOT NULL DEFAULT 0,
+        file_path TEXT,
+        metadata TEXT NOT NULL,
+        embedding BLOB NOT NULL,
+        embedding_dim INTEGER NOT NULL,
+        lsh_bucket INTEGER
+    );
+    CREATE INDEX idx_memories_type ON memories(memory_type);
+    CREATE INDEX idx_memories_scope ON memories(scope);
+    CREATE INDEX idx_memories_timestamp ON memories(timestamp);
+    CREATE INDEX idx_memories_relevance ON memories(relevance_score);
+    CREATE INDEX idx_memories_file_path ON memories(file_path);
+    CREATE INDEX idx_memories_lsh ON memories(embedding_dim, lsh_bucket);
+
+    CREATE TABLE memory_tags (
+        memory_id TEXT NOT NULL REFERENCES memories(id) ON DELETE CASCADE ON UPDATE CASCADE,
+        tag TEXT NOT NULL,
+        PRIMARY KEY (memory_id, tag)
+    );
+    CREATE INDEX idx_memory_tags_tag ON memory_tags(tag);
+
+    CREATE VIRTUAL TABLE memories_fts USING fts5(
+        content,
+        content = 'memories',
+        content_rowid = 'seq',
+        tokenize = 'unicode61 remove_diacritics 2'
+    );
+    CREATE TRIGGER memories_fts_insert AFTER INSERT ON memories BEGIN
+        INSERT INTO memories_fts(rowid, content) VALUES (new.seq, new.content);
+    END;
+    CREATE TRIGGER memories_fts_delete AFTER DELETE ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, content)
+            VALUES ('delete', old.seq, old.content);
+    END;
+    CREATE TRIGGER memories_fts_update AFTER UPDATE OF content ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, content)
+            VALUES ('delete', old.seq, old.content);
+        INSERT INTO memories_fts(rowid, content) VALUES (new.seq, new.content);
+    END;
+    "#,
+];
+
+/// SQLite storage implementation with keyword and vector search.
 #[derive(Debug)]
 pub struct SqliteStorage {
     path: PathBuf,
-    memories: HashMap<Uuid, Memory>,
-    dirty: bool,
+    conn: Mutex<Connection>,
 }
 
 impl SqliteStorage {
-    /// Create a new SQLite storage.
+    /// Open (or create) a SQLite memory store at `path`.
     pub async fn new(path: PathBuf) -> Result<Self> {
-        // For now, use JSON serialization for SQLite as well
-        // In production, you'd use rusqlite with sqlite-vec extension
-        let memories = if path.exists() {
-            let content = tokio::fs::read_to_string(&path).await.unwrap_or_default();
-            if content.is_empty() {
-                HashMap::new()
-            } else {
-                let list: Vec<Memory> = serde_json::from_str(&content).unwrap_or_default();
-                list.into_iter().map(|m| (m.id, m)).collect()
-            }
-        } else {
-            HashMap::new()
-        };
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(parent).await?;
+        }
 
-        Ok(Self {
+        let legacy = take_legacy_json(&path).await?;
+
+        let mut conn = Connection::open(&path)?;
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.pragma_update(None, "synchronous", "NORMAL")?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        migrate(&mut conn)?;
+
+        let storage = Self {
             path,
-            memories,
-            dirty: false,
-        })
-    }
+            conn: Mutex::new(conn),
+        };
 
-    /// Check if a memory matches the given filter.
-    fn matches_filter(&self, memory: &Memory, filter: &MemoryFilter) -> bool {
-        if let Some(types) = &filter.types {
-            if !types.contains(&memory.memory_type) {
-                return false;
+        if let Some(memories) = legacy {
+            let count = memories.len();
+            let mut conn = storage.lock();
+            let tx = conn.transaction()?;
+            for memory in &memories {
+                upsert_memory(&tx, memory)?;
             }
+            tx.commit()?;
+            tracing::info!("Imported {} memories from legacy JSON store", count);
         }
-        if let Some(scope) = &filter.scope {
-            if &memory.scope != scope {
-                return false;
-            }
-        }
-        if let Some(min_age) = filter.min_age_hours {
-            if memory.age_hours() < min_age {
-                return false;
-            }
-        }
-        if let Some(max_relevance) = filter.max_relevance {
-            if memory.relevance_score > max_relevance {
-                return false;
-            }
-        }
-        if let Some(tags) = &filter.tags {
-            if !tags.iter().any(|t| memory.metadata.tags.contains(t)) {
-                return false;
-            }
-        }
-        true
+
+        Ok(storage)
     }
 
-    /// Check if a memory matches the given query.
-    fn matches_query(&self, memory: &Memory, query: &MemoryQuery) -> bool {
-        if let Some(types) = &query.types {
-            if !types.contains(&memory.memory_type) {
-                return false;
-            }
-        }
-        if let Some(scope) = &query.scope {
-            if &memory.scope != scope {
-                return false;
-            }
+    /// Current schema version of the database.
+    pub fn schema_version(&self) -> Result<usize> {
+        let version: i64 = self
+            .lock()
+            .query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        Ok(version as usize)
+    }
+
+    /// Keyword search over memory content using the FTS5 index.
+    ///
+    /// Each whitespace-separated word of `text` is matched as a prefix term and
+    /// results are ranked by BM25 (higher score is better).
+    pub fn search_keyword(
+        &self,
+        text: &str,
+        limit: usize,
+        filter: Option<&MemoryFilter>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let Some(fts_query) = fts_query(text) else {
+            return Ok(Vec::new());
+        };
+
+        let mut conditions = SqlConditions::default();
+        conditions.push("memories_fts MATCH ?", vec![SqlValue::Text(fts_query)]);
+        if let Some(filter) = filter {
+            conditions.add_filter(filter, now_micros());
         }
-        if let Some(min_relevance) = query.min_relevance {
-            if memory.relevance_score < min_relevance {
-                return false;
-            }
+        conditions.params.push(SqlValue::Integer(limit as i64));
+
+        let sql = format!(
+            "SELECT {}, bm25(memories_fts) FROM memories_fts \
+             JOIN memories ON memories.seq = memories_fts.rowid{} \
+             ORDER BY bm25(memories_fts) LIMIT ?",
+            qualified_columns(),
+            conditions.where_clause()
+        );
+
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(conditions.params), |row| {
+            let rank: f64 = row.get(10)?;
+            Ok((row_to_memory(row)?, -rank as f32))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        // A panic while holding the lock cannot leave the database inconsistent:
+        // every multi-statement write runs in a transaction.
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Load full memories for the given sequence numbers, keeping their order.
+    fn load_by_seq(conn: &Connection, seqs: &[i64]) -> Result<HashMap<i64, Memory>> {
+        let mut memories = HashMap::with_capacity(seqs.len());
+        if seqs.is_empty() {
+            return Ok(memories);
         }
-        if let Some(max_age) = query.max_age_hours {
-            if memory.age_hours() > max_age {
-                return false;
-            }
+        let placeholders = vec!["?"; seqs.len()].join(", ");
+        let sql = format!(
+            "SELECT {}, seq FROM memories WHERE seq IN ({})",
+            MEMORY_COLUMNS, placeholders
+        );
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(seqs), |row| {
+            Ok((row.get::<_, i64>(10)?, row_to_memory(row)?))
+        })?;
+        for row in rows {
+            let (seq, memory) = row?;
+            memories.insert(seq, memory);
         }
-        if let Some(tags) = &query.tags {
-            if !tags.iter().any(|t| memory.metadata.tags.contains(t)) {
-                return false;
+        Ok(memories)
+    }
+
+    /// Score every embedding matching `conditions` and keep the best `limit`.
+    fn scan_top_k(
+        conn: &Connection,
+        embedding: &Embedding,
+        limit: usize,
+        conditions: &SqlConditions,
+    ) -> Result<Vec<(i64, f32)>> {
+        let mut top = TopK::new(limit);
+        let mut last_seq = i64::MIN;
+
+        let sql = format!(
+            "SELECT seq, embedding FROM memories{} AND seq > ? ORDER BY seq LIMIT ?",
+            conditions.where_clause()
+        );
+        let mut stmt = conn.prepare(&sql)?;
+
+        loop {
+            let mut params = conditions.params.clone();
+            params.push(SqlValue::Integer(last_seq));
+            params.push(SqlValue::Integer(SCAN_CHUNK_SIZE));
+
+            let mut rows = stmt.query(params_from_iter(params))?;
+            let mut fetched = 0;
+            while let Some(row) = rows.next()? {
+                let seq: i64 = row.get(0)?;
+                let blob: Vec<u8> = row.get(1)?;
+                top.push(seq, cosine_similarity(embedding, &decode_embedding(&blob)));
+                last_seq = seq;
+                fetched += 1;
             }
-        }
-        if let Some(prefix) = &query.file_path_prefix {
-            if let Some(path) = &memory.metadata.file_path {
-                if !path.starts_with(prefix) {
-                    return false;
-                }
-            } else {
-                return false;
+            if fetched < SCAN_CHUNK_SIZE {
+                break;
             }
         }
-        true
+
+        Ok(top.into_sorted())
     }
 }
 
 #[async_trait::async_trait]
 impl MemoryStorage for SqliteStorage {
     async fn insert(&mut self, memory: Memory) -> Result<()> {
-        self.memories.insert(memory.id, memory);
-        self.dirty = true;
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        upsert_memory(&tx, &memory)?;
+        tx.commit()?;
         Ok(())
     }
 
     async fn get(&self, id: Uuid) -> Result<Option<Memory>> {
-        Ok(self.memories.get(&id).cloned())
+        let sql = format!("SELECT {} FROM memories WHERE id = ?", MEMORY_COLUMNS);
+        Ok(self
+            .lock()
+            .query_row(&sql, [id.to_string()], row_to_memory)
+            .optional()?)
     }
 
     async fn update(&mut self, memory: Memory) -> Result<()> {
-        if self.memories.contains_key(&memory.id) {
-            self.memories.insert(memory.id, memory);
-            self.dirty = true;
-            Ok(())
-        } else {
-            Err(CortexError::NotFound(format!(
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        let exists = tx
+            .query_row(
+                "SELECT 1 FROM memories WHERE id = ?",
+                [memory.id.to_string()],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some();
+        if !exists {
+            return Err(CortexError::NotFound(format!(
                 "Memory {} not found",
                 memory.id
-            )))
+            )));
         }
+        upsert_memory(&tx, &memory)?;
+        tx.commit()?;
+        Ok(())
     }
 
     async fn delete(&mut self, id: Uuid) -> Result<bool> {
-        let removed = self.memories.remove(&id).is_some();
-        if removed {
-            self.dirty = true;
-        }
-        Ok(removed)
+        let removed = self
+            .lock()
+            .execute("DELETE FROM memories WHERE id = ?", [id.to_string()])?;
+        Ok(removed > 0)
     }
 
     async fn query(&self, query: MemoryQuery) -> Result<Vec<Memory>> {
-        let mut results: Vec<_> = self
-            .memories
-            .values()
-            .filter(|m| self.matches_query(m, &query))
-            .cloned()
-            .collect();
+        let now = now_micros();
+        let mut conditions = SqlConditions::default();
+        conditions.add_query(&query, now);
 
-        results.sort_by(|a, b| {
-            let score_a = a.relevance_score / (a.age_hours() + 1.0);
-            let score_b = b.relevance_score / (b.age_hours() + 1.0);
-            score_b
-                .partial_cmp(&score_a)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
+        // Same ranking as the other backends: relevance / (age in whole hours + 1)
+        let sql = format!(
+            "SELECT {} FROM memories{} \
+             ORDER BY relevance_score / ((({} - timestamp) / {}) + 1.0) DESC LIMIT ? OFFSET ?",
+            MEMORY_COLUMNS,
+            conditions.where_clause(),
+            now,
+            MICROS_PER_HOUR
+        );
+        let mut params = conditions.params;
+        params.push(SqlValue::Integer(query.limit.map_or(-1, |l| l as i64)));
+        params.push(SqlValue::Integer(query.offset.unwrap_or(0) as i64));
 
-        if let Some(offset) = query.offset {
-            results = results.into_iter().skip(offset).collect();
-        }
-        if let Some(limit) = query.limit {
-            results.truncate(limit);
-        }
-
-        Ok(results)
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map(params_from_iter(params), row_to_memory)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
     }
 
     async fn get_all(&self) -> Result<Vec<Memory>> {
-        Ok(self.memories.values().cloned().collect())
+        let sql = format!("SELECT {} FROM memories ORDER BY seq", MEMORY_COLUMNS);
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([], row_to_memory)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
     }
 
     async fn count(&self) -> Result<usize> {
-        Ok(self.memories.len())
+        let count: i64 = self
+            .lock()
+            .query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        Ok(count as usize)
     }
 
     async fn count_by_type(&self) -> Result<HashMap<MemoryType, usize>> {
+        let conn = self.lock();
+        let mut stmt =
+            conn.prepare("SELECT memory_type, COUNT(*) FROM memories GROUP BY memory_type")?;
+        let mut rows = stmt.query([])?;
         let mut counts = HashMap::new();
-        for memory in self.memories.values() {
-            *counts.entry(memory.memory_type).or_insert(0) += 1;
+        while let Some(row) = rows.next()? {
+            let memory_type: String = row.get(0)?;
+            let count: i64 = row.get(1)?;
+            counts.insert(parse_memory_type(&memory_type)?, count as usize);
         }
         Ok(counts)
     }
 
     async fn count_by_scope(&self) -> Result<HashMap<String, usize>> {
-        let mut counts = HashMap::new();
-        for memory in self.memories.values() {
-            *counts.entry(memory.scope.to_string()).or_insert(0) += 1;
-        }
-        Ok(counts)
+        let conn = self.lock();
+        let mut stmt = conn.prepare("SELECT scope, COUNT(*) FROM memories GROUP BY scope")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)? as usize))
+        })?;
+        Ok(rows.collect::<rusqlite::Result<HashMap<_, _>>>()?)
     }
 
     async fn oldest(&self) -> Result<Option<Memory>> {
-        Ok(self.memories.values().min_by_key(|m| m.timestamp).cloned())
+        let sql = format!(
+            "SELECT {} FROM memories ORDER BY timestamp ASC LIMIT 1",
+            MEMORY_COLUMNS
+        );
+        Ok(self.lock().query_row(&sql, [], row_to_memory).optional()?)
     }
 
     async fn newest(&self) -> Result<Option<Memory>> {
-        Ok(self.memories.values().max_by_key(|m| m.timestamp).cloned())
+        let sql = format!(
+            "SELECT {} FROM memories ORDER BY timestamp DESC LIMIT 1",
+            MEMORY_COLUMNS
+        );
+        Ok(self.lock().query_row(&sql, [], row_to_memory).optional()?)
     }
 
     async fn delete_by_filter(&mut self, filter: MemoryFilter) -> Result<usize> {
-        let to_delete: Vec<_> = self
-            .memories
-            .iter()
-            .filter(|(_, m)| self.matches_filter(m, &filter))
-            .map(|(id, _)| *id)
-            .collect();
-
-        let count = to_delete.len();
-        for id in to_delete {
-            self.memories.remove(&id);
-        }
-        if count > 0 {
-            self.dirty = true;
-        }
-        Ok(count)
+        let mut conditions = SqlConditions::default();
+        conditions.add_filter(&filter, now_micros());
+        let sql = format!("DELETE FROM memories{}", conditions.where_clause());
+        Ok(self
+            .lock()
+            .execute(&sql, params_from_iter(conditions.params))?)
     }
 
     async fn apply_decay(&mut self, half_life_hours: f32) -> Result<usize> {
+        let now = now_micros();
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
         let mut count = 0;
-        for memory in self.memories.values_mut() {
-            let old_score = memory.relevance_score;
-            memory.apply_decay(half_life_hours);
-            if (old_score - memory.relevance_score).abs() > 0.001 {
-                count += 1;
+        {
+            let mut select = tx.prepare("SELECT seq, timestamp, relevance_score FROM memories")?;
+            let mut update = tx.prepare("UPDATE memories SET relevance_score = ? WHERE seq = ?")?;
+            let mut rows = select.query([])?;
+            while let Some(row) = rows.next()? {
+                let seq: i64 = row.get(0)?;
+                let timestamp: i64 = row.get(1)?;
+                let old_score = row.get::<_, f64>(2)? as f32;
+
+                // Mirrors `Memory::apply_decay`, which uses whole hours of age.
+                let age_hours = ((now - timestamp) / MICROS_PER_HOUR) as f32;
+                let new_score = old_score * 0.5_f32.powf(age_hours / half_life_hours);
+
+                if new_score != old_score {
+                    update.execute(params![new_score as f64, seq])?;
+                }
+                if (old_score - new_score).abs() > 0.001 {
+                    count += 1;
+                }
             }
         }
-        if count > 0 {
-            self.dirty = true;
-        }
+        tx.commit()?;
         Ok(count)
     }
 
     async fn prune(&mut self, max_count: usize, threshold: f32) -> Result<usize> {
-        let expired: Vec<_> = self
-            .memories
-            .iter()
-            .filter(|(_, m)| m.is_expired(threshold))
-            .map(|(id, _)| *id)
-            .collect();
-
-        for id in &expired {
-            self.memories.remove(id);
-        }
-
-        let mut removed = expired.len();
-        while self.memories.len() > max_count {
-            if let Some((id, _)) = self.memories.iter().min_by(|(_, a), (_, b)| {
-                a.relevance_score
-                    .partial_cmp(&b.relevance_score)
-                    .unwrap_or(std::cmp::Ordering::Equal)
-            }) {
-                let id = *id;
-                self.memories.remove(&id);
-                removed += 1;
-            } else {
-                break;
-            }
-        }
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
 
-        if removed > 0 {
-            self.dirty = true;
+        let mut removed = tx.execute(
+            "DELETE FROM memories WHERE relevance_score < ?",
+            [threshold as f64],
+        )?;
+
+        let remaining: i64 = tx.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        let excess = remaining - max_count as i64;
+        if excess > 0 {
+            removed += tx.execute(
+                "DELETE FROM memories WHERE seq IN \
+                 (SELECT seq FROM memories ORDER BY relevance_score ASC LIMIT ?)",
+                [excess],
+            )?;
         }
+
+        tx.commit()?;
         Ok(removed)
     }
 
     async fn clear(&mut self) -> Result<()> {
-        self.memories.clear();
-        self.dirty = true;
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        tx.execute_batch(
+            "DELETE FROM memory_tags;
+             DELETE FROM memories;
+             INSERT INTO memories_fts(memories_fts) VALUES ('rebuild');",
+        )?;
+        tx.commit()?;
         Ok(())
     }
 
     async fn storage_size(&self) -> Result<u64> {
-        if self.path.exists() {
-            Ok(tokio::fs::metadata(&self.path).await?.len())
-        } else {
-            Ok(0)
+        let mut size = 0;
+        for suffix in ["", "-wal", "-shm"] {
+            let path = PathBuf::from(format!("{}{}", self.path.display(), suffix));
+            if let Ok(meta) = tokio::fs::metadata(&path).await {
+                size += meta.len();
+            }
         }
+        Ok(size)
     }
 
     async fn export(&self) -> Result<Vec<Memory>> {
-        Ok(self.memories.values().cloned().collect())
+        self.get_all().await
     }
 
     async fn import(&mut self, memories: Vec<Memory>) -> Result<usize> {
-        let count = memories.len();
-        for memory in memories {
-            self.memories.insert(memory.id, memory);
+        let mut conn = self.lock();
+        let tx = conn.transaction()?;
+        for memory in &memories {
+            upsert_memory(&tx, memory)?;
         }
-        self.dirty = true;
-        Ok(count)
+        tx.commit()?;
+        Ok(memories.len())
     }
 
-    async fn save(&mut self) -> Result<()> {
-        if self.dirty {
-            let memories: Vec<_> = self.memories.values().cloned().collect();
-            let content = serde_json::to_string_pretty(&memories)?;
-
-            if let Some(parent) = self.path.parent() {
-                tokio::fs::create_dir_all(parent).await?;
-            }
+    async fn mismatched_embeddings(&self, dimensions: usize) -> Result<Vec<Memory>> {
+        let sql = format!(
+            "SELECT {} FROM memories WHERE embedding_dim != 0 AND embedding_dim != ? ORDER BY seq",
+            MEMORY_COLUMNS
+        );
+        let conn = self.lock();
+        let mut stmt = conn.prepare(&sql)?;
+        let rows = stmt.query_map([dimensions as i64], row_to_memory)?;
+        Ok(rows.collect::<rusqlite::Result<Vec<_>>>()?)
+    }
 
-            tokio::fs::write(&self.path, content).await?;
-            self.dirty = false;
-        }
+    async fn save(&mut self) -> Result<()> {
+        // Writes are already durable; fold the WAL back into the main file.
+        self.lock()
+            .query_row("PRAGMA wal_checkpoint(PASSIVE)", [], |_| Ok(()))?;
         Ok(())
     }
 
+    async fn search_text(
+        &self,
+        text: &str,
+        limit: usize,
+        filter: Option<MemoryFilter>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        self.search_keyword(text, limit, filter.as_ref())
+    }
+
     async fn search_similar(
         &self,
         embedding: &Embedding,
         limit: usize,
         filter: Option<MemoryFilter>,
     ) -> Result<Vec<(Memory, f32)>> {
-        let mut results: Vec<_> = self
-            .memories
-            .values()
-            .filter(|m| {
-                filter
-                    .as_ref()
-                    .map(|f| self.matches_filter(m, f))
-                    .unwrap_or(true)
+        if limit == 0 || embedding.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conditions = SqlConditions::default();
+        conditions.push(
+            "embedding_dim = ?",
+            vec![SqlValue::Integer(embedding.len() as i64)],
+        );
+        if let Some(filter) = &filter {
+            conditions.add_filter(filter, now_micros());
+        }
+
+        let conn = self.lock();
+
+        let candidates: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE embedding_dim = ?",
+            [embedding.len() as i64],
+            |row| row.get(0),
+        )?;
+
+        let mut top = Vec::new();
+        if candidates > EXACT_SCAN_THRESHOLD {
+            // Approximate: only score rows whose LSH bucket is near the query's.
+            let buckets = probe_buckets(lsh_bucket(embedding), LSH_PROBE_RADIUS);
+            let mut probe = conditions.clone();
+            probe.push(
+                &format!("lsh_bucket IN ({})", vec!["?"; buckets.len()].join(", ")),
+                buckets.into_iter().map(SqlValue::Integer).collect(),
+            );
+            top = Self::scan_top_k(&conn, embedding, limit, &probe)?;
+        }
+        if top.len() < limit {
+            top = Self::scan_top_k(&conn, embedding, limit, &conditions)?;
+        }
+
+        let seqs: Vec<i64> = top.iter().map(|(seq, _)| *seq).collect();
+        let mut memories = Self::load_by_seq(&conn, &seqs)?;
+        Ok(top
+            .into_iter()
+            .filter_map(|(seq, score)| memories.remove(&seq).map(|m| (m, score)))
+            .collect())
+    }
+}
+
+// =============================================================================
+// SCHEMA AND ROW MAPPING
+// =============================================================================
+
+/// Apply pending migrations, each in its own transaction.
+fn migrate(conn: &mut Connection) -> Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    let version = version as usize;
+    if version > MIGRATIONS.len() {
+        return Err(CortexError::Internal(format!(
+            "Memory database schema version {} is newer than supported version {}",
+            version,
+            MIGRATIONS.len()
+        )));
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(sql)?;
+        tx.pragma_update(None, "user_version", (index + 1) as i64)?;
+        tx.commit()?;
+        tracing::debug!("Migrated memory database to schema version {}", index + 1);
+    }
+    Ok(())
+}
+
+/// If `path` holds a legacy JSON store, move it aside and return its memories.
+async fn take_legacy_json(path: &Path) -> Result<Option<Vec<Memory>>> {
+    const SQLITE_HEADER: &[u8] = b"SQLite format 3\0";
+
+    let bytes = match tokio::fs::read(path).await {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(e.into()),
+    };
+    if bytes.is_empty() || bytes.starts_with(SQLITE_HEADER) {
+        return Ok(None);
+    }
+
+    let memories: Vec<Memory> = serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+        tracing::warn!(
+            "Legacy memory store {} is not valid JSON: {}",
+            path.display(),
+            e
+        );
+        Vec::new()
+    });
+
+    let backup = PathBuf::from(format!("{}.json.bak", path.display()));
+    tokio::fs::rename(path, &backup).await?;
+    tracing::info!(
+        "Migrating legacy JSON memory store to SQLite (backup at {})",
+        backup.display()
+    );
+    Ok(Some(memories))
+}
+
+/// Insert or replace a memory and its tags. Runs inside the caller's transaction.
+fn upsert_memory(tx: &Transaction<'_>, memory: &Memory) -> Result<()> {
+    let id = memory.id.to_string();
+    tx.execute(
+        "INSERT INTO memories (id, content, memory_type, scope, timestamp, last_accessed, \
+         relevance_score, access_count, file_path, metadata, embedding, embedding_dim, lsh_bucket) \
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) \
+         ON CONFLICT(id) DO UPDATE SET content = excluded.content, \
+         memory_type = excluded.memory_type, scope = excluded.scope, \
+         timestamp = excluded.timestamp, last_accessed = excluded.last_accessed, \
+         relevance_score = excluded.relevance_score, access_count = excluded.access_count, \
+         file_path = excluded.file_path, metadata = excluded.metadata, \
+         embedding = excluded.embedding, embedding_dim = excluded.embedding_dim, \
+         lsh_bucket = excluded.lsh_bucket",
+        params![
+            id,
+            memory.content,
+            memory.memory_type.to_string(),
+            memory.scope.to_string(),
+            memory.timestamp.timestamp_micros(),
+            memory.last_accessed.timestamp_micros(),
+            memory.relevance_score as f64,
+            memory.access_count,
+            memory
+                .metadata
+                .file_path
+                .as_ref()
+                .map(|p| p.to_string_lossy().into_owned()),
+            serde_json::to_string(&memory.metadata)?,
+            encode_embedding(&memory.embedding),
+            memory.embedding.len() as i64,
+            (!memory.embedding.is_empty()).then(|| lsh_bucket(&memory.embedding)),
+        ],
+    )?;
+
+    tx.execute("DELETE FROM memory_tags WHERE memory_id = ?", [&id])?;
+    let mut stmt =
+        tx.prepare_cached("INSERT OR IGNORE INTO memory_tags (memory_id, tag) VALUES (?, ?)")?;
+    for tag in &memory.metadata.tags {
+        stmt.execute([&id, tag])?;
+    }
+    Ok(())
+}
+
+/// Rebuild a memory from the first ten columns of `row` (see [`MEMORY_COLUMNS`]).
+fn row_to_memory(row: &Row<'_>) -> rusqlite::Result<Memory> {
+    fn conversion_error(
+        index: usize,
+        e: impl std::error::Error + Send + Sync + 'static,
+    ) -> rusqlite::Error {
+        rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, Box::new(e))
+    }
+
+    let id: String = row.get(0)?;
+    let memory_type: String = row.get(2)?;
+    let scope: String = row.get(3)?;
+    let metadata: String = row.get(8)?;
+    let embedding: Vec<u8> = row.get(9)?;
+
+    Ok(Memory {
+        id: Uuid::parse_str(&id).map_err(|e| conversion_error(0, e))?,
+        content: row.get(1)?,
+        embedding: decode_embedding(&embedding),
+        memory_type: serde_json::from_value(serde_json::Value::String(memory_type))
+            .map_err(|e| conversion_error(2, e))?,
+        timestamp: from_micros(row.get(4)?),
+        last_accessed: from_micros(row.get(5)?),
+        relevance_score: row.get::<_, f64>(6)? as f32,
+        access_count: row.get(7)?,
+        scope: parse_scope(&scope),
+        metadata: serde_json::from_str::<MemoryMetadata>(&metadata)
+            .map_err(|e| conversion_error(8, e))?,
+    })
+}
+
+/// Columns of [`MEMORY_COLUMNS`] qualified with the `memories` table name.
+fn qualified_columns() -> String {
+    MEMORY_COLUMNS
+        .split(", ")
+        .map(|c| format!("memories.{}", c.trim()))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn parse_memory_type(value: &str) -> Result<MemoryType> {
+    Ok(serde_json::from_value(serde_json::Value::String(
+        value.to_string(),
+    ))?)
+}
+
+/// Parse a scope stored in its `Display` form.
+fn parse_scope(value: &str) -> MemoryScope {
+    if let Some(id) = value.strip_prefix("session:") {
+        MemoryScope::Session(id.to_string())
+    } else if let Some(id) = value.strip_prefix("project:") {
+        MemoryScope::Project(id.to_string())
+    } else {
+        MemoryScope::Global
+    }
+}
+
+fn now_micros() -> i64 {
+    Utc::now().timestamp_micros()
+}
+
+fn from_micros(micros: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_micros(micros).unwrap_or_default()
+}
+
+fn encode_embedding(embedding: &Embedding) -> Vec<u8> {
+    embedding.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+fn decode_embedding(bytes: &[u8]) -> Embedding {
+    bytes
+        .chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+/// Build an FTS5 query matching any word of `text` as a prefix.
+fn fts_query(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+    (!terms.is_empty()).then(|| terms.join(" OR "))
+}
+
+// =============================================================================
+// FILTERS
+// =============================================================================
+
+/// SQL `WHERE` conditions with their positional parameters.
+#[derive(Debug, Clone, Default)]
+struct SqlConditions {
+    clauses: Vec<String>,
+    params: Vec<SqlValue>,
+}
+
+impl SqlConditions {
+    fn push(&mut self, clause: &str, params: Vec<SqlValue>) {
+        self.clauses.push(clause.to_string());
+        self.params.extend(params);
+    }
+
+    /// The ` WHERE ...` clause, or ` WHERE 1` if there are no conditions.
+    fn where_clause(&self) -> String {
+        if self.clauses.is_empty() {
+            " WHERE 1".to_string()
+        } else {
+            format!(" WHERE {}", self.clauses.join(" AND "))
+        }
+    }
+
+    fn add_types(&mut self, types: &[MemoryType]) {
+        self.push(
+            &format!(
+                "memories.memory_type IN ({})",
+                vec!["?"; types.len()].join(", ")
+            ),
+            types
+                .iter()
+                .map(|t| SqlValue::Text(t.to_string()))
+                .collect(),
+        );
+    }
+
+    fn add_tags(&mut self, tags: &[String]) {
+        self.push(
+            &format!(
+                "EXISTS (SELECT 1 FROM memory_tags WHERE memory_tags.memory_id = memories.id \
+                 AND memory_tags.tag IN ({}))",
+                vec!["?"; tags.len()].join(", ")
+            ),
+            tags.iter().map(|t| SqlValue::Text(t.clone())).collect(),
+        );
+    }
+
+    /// Age in whole hours, matching `Memory::age_hours`.
+    fn age_hours_expr(now: i64) -> String {
+        format!("(({} - memories.timestamp) / {})", now, MICROS_PER_HOUR)
+    }
+
+    fn add_query(&mut self, query: &MemoryQuery, now: i64) {
+        if let Some(types) = &query.types {
+            self.add_types(types);
+        }
+        if let Some(scope) = &query.scope {
+            self.push(
+                "memories.scope = ?",
+                vec![SqlValue::Text(scope.to_string())],
+            );
+        }
+        if let Some(min_relevance) = query.min_relevance {
+            self.push(
+                "memories.relevance_score >= ?",
+                vec![SqlValue::Real(min_relevance as f64)],
+            );
+        }
+        if let Some(max_age) = query.max_age_hours {
+            self.push(
+                &format!("{} <= ?", Self::age_hours_expr(now)),
+                vec![SqlValue::Real(max_age as f64)],
+            );
+        }
+        if let Some(tags) = &query.tags {
+            self.add_tags(tags);
+        }
+        if let Some(prefix) = &query.file_path_prefix {
+            // Component-wise prefix like `Path::starts_with`.
+            let prefix = prefix.to_string_lossy();
+            let exact = prefix.trim_end_matches('/').to_string();
+            let dir = format!("{}/", exact);
+            self.push(
+                "(memories.file_path = ? OR substr(memories.file_path, 1, ?) = ?)",
+                vec![
+                    SqlValue::Text(exact),
+                    SqlValue::Integer(dir.chars().count() as i64),
+                    SqlValue::Text(dir),
+                ],
+            );
+        }
+    }
+
+    fn add_filter(&mut self, filter: &MemoryFilter, now: i64) {
+        if let Some(types) = &filter.types {
+            self.add_types(types);
+        }
+        if let Some(scope) = &filter.scope {
+            self.push(
+                "memories.scope = ?",
+                vec![SqlValue::Text(scope.to_string())],
+            );
+        }
+        if let Some(min_age) = filter.min_age_hours {
+            self.push(
+                &format!("{} >= ?", Self::age_hours_expr(now)),
+                vec![SqlValue::Real(min_age as f64)],
+            );
+        }
+        if let Some(max_relevance) = filter.max_relevance {
+            self.push(
+                "memories.relevance_score <= ?",
+                vec![SqlValue::Real(max_relevance as f64)],
+            );
+        }
+        if let Some(tags) = &filter.tags {
+            self.add_tags(tags);
+        }
+    }
+}
+
+// =============================================================================
+// VECTOR INDEX
+// =============================================================================
+
+/// Sign of component `dim` of random hyperplane `plane`.
+///
+/// The hyperplanes are never stored: each component is derived from a
+/// SplitMix64 hash of `(plane, dim)`, so signatures are stable across runs
+/// and work for any embedding dimension.
+fn hyperplane_sign(plane: u32, dim: usize) -> bool {
+    let mut z = (((plane as u64) << 32) ^ dim as u64).wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    (z ^ (z >> 31)) & 1 == 1
+}
+
+/// Random-hyperplane LSH signature: bit `i` is set when the embedding lies on
+/// the positive side of hyperplane `i`. Similar vectors share most bits.
+fn lsh_bucket(embedding: &[f32]) -> i64 {
+    let mut signature = 0i64;
+    for plane in 0..LSH_BITS {
+        let projection: f32 = embedding
+            .iter()
+            .enumerate()
+            .map(|(dim, v)| if hyperplane_sign(plane, dim) { *v } else { -*v })
+            .sum();
+        if projection >= 0.0 {
+            signature |= 1 << plane;
+        }
+    }
+    signature
+}
+
+/// All buckets within `radius` bit flips of `bucket`.
+fn probe_buckets(bucket: i64, radius: u32) -> Vec<i64> {
+    let mut buckets = vec![bucket];
+    let mut frontier = vec![(bucket, 0u32)];
+    for _ in 0..radius {
+        let mut next = Vec::new();
+        for (b, min_bit) in frontier {
+            for bit in min_bit..LSH_BITS {
+                let flipped = b ^ (1 << bit);
+                buckets.push(flipped);
+                next.push((flipped, bit + 1));
+            }
+        }
+        frontier = next;
+    }
+    buckets
+}
+
+/// Fixed-size min-heap keeping the `k` highest scores.
+struct TopK {
+    k: usize,
+    heap: BinaryHeap<Scored>,
+}
+
+struct Scored(f32, i64);
+
+impl PartialEq for Scored {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Scored {}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Reversed so the heap's top is the lowest score.
+        other.0.total_cmp(&self.0).then(other.1.cmp(&self.1))
+    }
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        Self {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, seq: i64, score: f32) {
+        if self.heap.len() < self.k {
+            self.heap.push(Scored(score, seq));
+        } else if self.heap.peek().is_some_and(|min| score > min.0) {
+            self.heap.pop();
+            self.heap.push(Scored(score, seq));
+        }
+    }
+
+    /// Entries sorted by descending score.
+    fn into_sorted(self) -> Vec<(i64, f32)> {
+        // `into_sorted_vec` is ascending by `Ord`, i.e. descending by score.
+        self.heap
+            .into_sorted_vec()
+            .into_iter()
+            .map(|Scored(score, seq)| (seq, score))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn memory(content: &str, embedding: Vec<f32>, memory_type: MemoryType) -> Memory {
+        Memory::new(content, embedding, memory_type, MemoryMetadata::default())
+    }
+
+    #[tokio::test]
+    async fn test_incremental_writes_survive_reopen() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+
+        let mut storage = SqliteStorage::new(path.clone()).await.unwrap();
+        assert_eq!(storage.schema_version().unwrap(), MIGRATIONS.len());
+
+        let mut first = memory("first", vec![1.0, 0.0], MemoryType::Note);
+        first.metadata.tags = vec!["rust".to_string()];
+        let second = memory("second", vec![0.0, 1.0], MemoryType::Fact);
+        let id = first.id;
+        storage.insert(first).await.unwrap();
+        storage.insert(second.clone()).await.unwrap();
+        assert!(storage.delete(second.id).await.unwrap());
+        drop(storage);
+
+        let storage = SqliteStorage::new(path).await.unwrap();
+        assert_eq!(storage.count().await.unwrap(), 1);
+        let loaded = storage.get(id).await.unwrap().unwrap();
+        assert_eq!(loaded.content, "first");
+        assert_eq!(loaded.embedding, vec![1.0, 0.0]);
+        assert_eq!(loaded.metadata.tags, vec!["rust"]);
+    }
+
+    #[tokio::test]
+    async fn test_query_filters_and_keyword_search() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = SqliteStorage::new(dir.path().join("memory.db"))
+            .await
+            .unwrap();
+
+        let mut code = memory(
+            "fn parse_config reads the TOML file",
+            vec![1.0, 0.0],
+            MemoryType::Code,
+        );
+        code.metadata.file_path = Some(PathBuf::from("src/config/loader.rs"));
+        code.metadata.tags = vec!["config".to_string()];
+        storage.insert(code).await.unwrap();
+
+        let mut other = memory("unrelated note", vec![0.0, 1.0], MemoryType::Note);
+        other.metadata.file_path = Some(PathBuf::from("src/configuration.rs"));
+        storage.insert(other).await.unwrap();
+
+        let results = storage
+            .query(MemoryQuery {
+                file_path_prefix: Some(PathBuf::from("src/config")),
+                ..Default::default()
             })
-            .map(|m| {
-                let score = cosine_similarity(embedding, &m.embedding);
-                (m.clone(), score)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].memory_type, MemoryType::Code);
+
+        let results = storage
+            .query(MemoryQuery {
+                tags: Some(vec!["config".to_string()]),
+                types: Some(vec![MemoryType::Code]),
+                ..Default::default()
             })
-            .collect();
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        let hits = storage.search_keyword("parse TOML", 10, None).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].0.content.contains("parse_config"));
+        assert!(storage.search_keyword("???", 10, None).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_decay_and_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = SqliteStorage::new(dir.path().join("memory.db"))
+            .await
+            .unwrap();
 
-        results.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
-        results.truncate(limit);
+        let mut old = memory("old", vec![1.0], MemoryType::Note);
+        old.timestamp = Utc::now() - chrono::Duration::hours(168);
+        let fresh = memory("fresh", vec![1.0], MemoryType::Note);
+        let extra = memory("extra", vec![1.0], MemoryType::Note);
+        storage
+            .import(vec![old.clone(), fresh, extra])
+            .await
+            .unwrap();
 
-        Ok(results)
+        assert_eq!(storage.apply_decay(168.0).await.unwrap(), 1);
+        let decayed = storage.get(old.id).await.unwrap().unwrap();
+        assert!(decayed.relevance_score > 0.4 && decayed.relevance_score < 0.6);
+
+        // Nothing is below the threshold, but one memory is over the limit:
+        // the decayed one goes first.
+        assert_eq!(storage.prune(2, 0.1).await.unwrap(), 1);
+        assert!(storage.get(old.id).await.unwrap().is_none());
+        assert_eq!(storage.count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_search_similar_ranks_by_cosine() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut storage = SqliteStorage::new(dir.path().join("memory.db"))
+            .await
+            .unwrap();
+
+        storage
+            .insert(memory("x", vec![1.0, 0.0, 0.0], MemoryType::Note))
+            .await
+            .unwrap();
+        storage
+            .insert(memory("xy", vec![0.7, 0.7, 0.0], MemoryType::Code))
+            .await
+            .unwrap();
+        storage
+            .insert(memory("z", vec![0.0, 0.0, 1.0], MemoryType::Note))
+            .await
+            .unwrap();
+
+        let results = storage
+            .search_similar(&vec![1.0, 0.1, 0.0], 2, None)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].0.content, "x");
+        assert_eq!(results[1].0.content, "xy");
+
+        let filter = MemoryFilter {
+            types: Some(vec![MemoryType::Note]),
+            ..Default::default()
+        };
+        let results = storage
+            .search_similar(&vec![1.0, 0.1, 0.0], 5, Some(filter))
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[1].0.content, "z");
+    }
+
+    #[tokio::test]
+    async fn test_legacy_json_store_is_imported() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memory.db");
+        let legacy = vec![memory("from json", vec![0.5, 0.5], MemoryType::Fact)];
+        std::fs::write(&path, serde_json::to_string(&legacy).unwrap()).unwrap();
+
+        let storage = SqliteStorage::new(path.clone()).await.unwrap();
+        assert_eq!(storage.count().await.unwrap(), 1);
+        assert!(dir.path().join("memory.db.json.bak").exists());
+    }
+
+    #[test]
+    fn test_lsh_buckets_group_similar_vectors() {
+        let a: Vec<f32> = (0..64).map(|i| (i as f32).sin()).collect();
+        let mut b = a.clone();
+        b[3] += 0.01;
+        let c: Vec<f32> = a.iter().map(|v| -v).collect();
+
+        assert_eq!(lsh_bucket(&a), lsh_bucket(&b));
+        assert_eq!((lsh_bucket(&a) ^ lsh_bucket(&c)).count_ones(), LSH_BITS);
+        // 1 + 16 + 120 buckets within Hamming distance 2
+        assert_eq!(probe_buckets(0, 2).len(), 137);
+    }
+
+    #[test]
+    fn test_top_k_keeps_best_scores() {
+        let mut top = TopK::new(2);
+        for (seq, score) in [(1, 0.1), (2, 0.9), (3, 0.5), (4, -1.0)] {
+            top.push(seq, score);
+        }
+        assert_eq!(top.into_sorted(), vec![(2, 0.9), (3, 0.5)]);
     }
 }
@@ -76,4 +76,72 @@ pub trait MemoryStorage: Send + Sync + std::fmt::Debug {
         limit: usize,
         filter: Option<MemoryFilter>,
     ) -> Result<Vec<(Memory, f32)>>;
+
+    /// Memories with an embedding whose dimension differs from `dimensions`.
+    ///
+    /// These were embedded by a different embedder and cannot be compared with
+    /// its vectors. Memories without an embedding are not included.
+    async fn mismatched_embeddings(&self, dimensions: usize) -> Result<Vec<Memory>> {
+        Ok(self
+            .get_all()
+            .await?
+            .into_iter()
+            .filter(|m| !m.embedding.is_empty() && m.embedding.len() != dimensions)
+            .collect())
+    }
+
+    /// Search by keywords in memory content.
+    ///
+    /// Scores are backend-specific; higher is better. The default implementation
+    /// scores each memory by the fraction of query terms its content contains.
+    async fn search_text(
+        &self,
+        text: &str,
+        limit: usize,
+        filter: Option<MemoryFilter>,
+    ) -> Result<Vec<(Memory, f32)>> {
+        let terms: Vec<String> = text
+            .split(|c: char| !c.is_alphanumeric() && c != '_')
+            .filter(|t| !t.is_empty())
+            .map(str::to_lowercase)
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let types = filter.as_ref().and_then(|f| f.types.clone());
+        let scope = filter.as_ref().and_then(|f| f.scope.clone());
+        let mut results: Vec<_> = self
+            .query(MemoryQuery {
+                types,
+                scope,
+                ..Default::default()
+            })
+            .await?
+            .into_iter()
+            .filter(|m| {
+                let Some(filter) = &filter else { return true };
+                filter.min_age_hours.is_none_or(|min| m.age_hours() >= min)
+                    && filter
+                        .max_relevance
+                        .is_none_or(|max| m.relevance_score <= max)
+                    && filter
+                        .tags
+                        .as_ref()
+                        .is_none_or(|tags| tags.iter().any(|t| m.metadata.tags.contains(t)))
+            })
+            .filter_map(|m| {
+                let content = m.content.to_lowercase();
+                let hits = terms
+                    .iter()
+                    .filter(|t| content.contains(t.as_str()))
+                    .count();
+                (hits > 0).then(|| (m, hits as f32 / terms.len() as f32))
+            })
+            .collect();
+
+        results.sort_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+        results.truncate(limit);
+        Ok(results)
+    }
 }
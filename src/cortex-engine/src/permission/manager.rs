@@ -14,6 +14,7 @@ use super::types::{
     Permission, PermissionCheckResult, PermissionContext, PermissionResponse, PermissionScope,
     RiskLevel,
 };
+use crate::audit::{self, AuditEvent, AuditKind};
 use crate::config::PermissionConfig;
 use crate::error::Result;
 
@@ -252,17 +253,25 @@ impl PermissionManager {
     /// Returns the permission response (Allow, Ask, or Deny).
     ///
     /// Permission priority (highest to lowest):
-    /// 1. Runtime stored permissions (session/persistent grants)
-    /// 2. Pattern-based permissions (default safe/dangerous patterns)
-    /// 3. Config-based permissions (from config.toml)
-    /// 4. Auto-approve low risk (if configured)
-    /// 5. Default to Ask
+    /// 1. Managed policy (denied commands cannot be granted)
+    /// 2. Runtime stored permissions (session/persistent grants)
+    /// 3. Pattern-based permissions (default safe/dangerous patterns)
+    /// 4. Config-based permissions (from config.toml)
+    /// 5. Auto-approve low risk (if configured)
+    /// 6. Default to Ask
     pub async fn request_permission(
         &self,
         tool: &str,
         action: &str,
         context: &PermissionContext,
     ) -> PermissionResponse {
+        if (tool == "bash" || tool == "shell")
+            && let Some(ref cmd) = context.command
+            && crate::managed_policy::command_denial(std::slice::from_ref(cmd)).is_some()
+        {
+            return PermissionResponse::Deny;
+        }
+
         // First check if there's an explicit stored permission (highest priority)
         if let Some(result) = self.check_stored_permission(tool, action, context).await {
             return result;
@@ -397,6 +406,9 @@ impl PermissionManager {
     }
 
     /// Request permission with interactive prompt (if callback is set).
+    ///
+    /// Every decision, whether from stored permissions or the prompt, is
+    /// recorded in the audit log.
     pub async fn request_with_prompt(
         &self,
         prompt: PermissionPrompt,
@@ -408,9 +420,11 @@ impl PermissionManager {
 
         match response {
             PermissionResponse::Allow => {
+                audit_decision(&prompt, true, "allowed by stored permission").await;
                 return Ok(PermissionCheckResult::granted(None, true));
             }
             PermissionResponse::Deny => {
+                audit_decision(&prompt, false, "denied by stored permission").await;
                 return Ok(PermissionCheckResult::denied(
                     None,
                     "Denied by stored permission",
@@ -427,6 +441,13 @@ impl PermissionManager {
                 // Process the response
                 let perm_response = response.to_permission_response();
                 let scope = response.to_scope();
+                let granted = perm_response == PermissionResponse::Allow;
+                let outcome = format!(
+                    "{} by user, scope {}",
+                    if granted { "allowed" } else { "denied" },
+                    format!("{scope:?}").to_lowercase()
+                );
+                audit_decision(&prompt, granted, &outcome).await;
 
                 // Store the permission if needed
                 if scope != PermissionScope::Once {
@@ -434,7 +455,7 @@ impl PermissionManager {
                         Permission::new(&prompt.tool, &prompt.pattern, perm_response, scope);
                     self.storage.grant(permission.clone()).await?;
 
-                    if perm_response == PermissionResponse::Allow {
+                    if granted {
                         return Ok(PermissionCheckResult::granted(Some(permission), false));
                     } else {
                         return Ok(PermissionCheckResult::denied(
@@ -445,7 +466,7 @@ impl PermissionManager {
                 }
 
                 // One-time response
-                if perm_response == PermissionResponse::Allow {
+                if granted {
                     return Ok(PermissionCheckResult::granted(None, false));
                 } else {
                     return Ok(PermissionCheckResult::denied(
@@ -637,6 +658,30 @@ impl Clone for PermissionManager {
     }
 }
 
+/// Record a permission decision in the audit log.
+async fn audit_decision(prompt: &PermissionPrompt, granted: bool, outcome: &str) {
+    let target = prompt
+        .context
+        .command
+        .clone()
+        .or_else(|| {
+            prompt
+                .context
+                .file_path
+                .as_ref()
+                .map(|p| p.display().to_string())
+        })
+        .unwrap_or_else(|| prompt.action.clone());
+    let kind = if granted {
+        AuditKind::Approval
+    } else {
+        AuditKind::Denial
+    };
+    let event = AuditEvent::new(kind, "permission", target, outcome)
+        .with_details(serde_json::json!({ "tool": prompt.tool, "pattern": prompt.pattern }));
+    audit::record(event).await;
+}
+
 /// Global permission manager instance.
 static GLOBAL_MANAGER: std::sync::OnceLock<PermissionManager> = std::sync::OnceLock::new();
 
@@ -2,9 +2,11 @@
 //!
 //! Stores conversation history in JSONL format for resumption.
 
+pub mod html;
 pub mod reader;
 pub mod recorder;
 
+pub use html::render_html;
 pub use reader::{RolloutEntry, read_rollout};
 pub use recorder::RolloutRecorder;
 
@@ -34,6 +34,8 @@ pub struct SandboxManager {
     writable_roots: Vec<WritableRoot>,
     /// Protected paths.
     protected_paths: ProtectedPaths,
+    /// Variables routing network traffic through the network proxy.
+    proxy_env: Vec<(String, String)>,
 }
 
 impl SandboxManager {
@@ -49,9 +51,19 @@ impl SandboxManager {
             backend,
             writable_roots,
             protected_paths,
+            proxy_env: Vec::new(),
         }
     }
 
+    /// Route commands' network traffic through a proxy.
+    ///
+    /// `env` is typically `NetworkProxy::env_vars()`; it is added to every
+    /// prepared command and to [`Self::get_sandbox_env`].
+    pub fn with_network_proxy(mut self, env: Vec<(String, String)>) -> Self {
+        self.proxy_env = env;
+        self
+    }
+
     /// Check if sandboxing is available on this platform.
     pub fn is_available(&self) -> bool {
         self.backend
@@ -117,6 +129,15 @@ impl SandboxManager {
     /// Returns a `SandboxedCommand` that wraps the original command with
     /// platform-specific sandboxing.
     pub fn prepare_command(&self, command: &[String]) -> Result<SandboxedCommand> {
+        let mut sandboxed = self.wrap_command(command)?;
+        if !command.is_empty() {
+            sandboxed.env.extend(self.proxy_env.iter().cloned());
+        }
+        Ok(sandboxed)
+    }
+
+    /// Wrap a command with the platform backend, if any.
+    fn wrap_command(&self, command: &[String]) -> Result<SandboxedCommand> {
         if command.is_empty() {
             return Ok(SandboxedCommand::passthrough(&[]));
         }
@@ -172,6 +193,8 @@ impl SandboxManager {
             self.cwd.display().to_string(),
         ));
 
+        env.extend(self.proxy_env.iter().cloned());
+
         env
     }
 
@@ -237,6 +260,19 @@ mod tests {
         )));
     }
 
+    #[test]
+    fn test_network_proxy_env() {
+        let proxy_env = vec![("HTTPS_PROXY".to_string(), "http://127.0.0.1:9".to_string())];
+        let manager = SandboxManager::new(SandboxPolicyType::DangerFullAccess, PathBuf::from("/"))
+            .with_network_proxy(proxy_env.clone());
+
+        let command = manager
+            .prepare_command(&["curl".to_string(), "https://example.com".to_string()])
+            .unwrap();
+        assert_eq!(command.env, proxy_env);
+        assert!(manager.get_sandbox_env().contains(&proxy_env[0]));
+    }
+
     #[test]
     fn test_no_network_policy() {
         let manager = SandboxManager::workspace_write_no_network(PathBuf::from("/workspace"));
@@ -26,6 +26,16 @@ impl Session {
     pub async fn run(&mut self) -> Result<()> {
         use std::path::PathBuf;
 
+        // Tools must not run unproxied when a proxy is configured
+        if let Err(e) = self.start_network_proxy().await {
+            self.emit(EventMsg::Error(ErrorEvent {
+                message: e.to_string(),
+                cortex_error_info: None,
+            }))
+            .await;
+            return Err(e);
+        }
+
         // Emit session configured event
         self.emit(EventMsg::SessionConfigured(Box::new(
             SessionConfiguredEvent {
@@ -302,34 +312,52 @@ impl Session {
                 let args: serde_json::Value = serde_json::from_str(&tool_call.function.arguments)
                     .unwrap_or(serde_json::Value::Null);
 
-                // Check if approval is needed for shell commands
-                let needs_approval = if tool_name == "Execute" {
-                    if let Some(cmd_array) = args.get("command").and_then(|c| c.as_array()) {
-                        let cmd: Vec<String> = cmd_array
-                            .iter()
-                            .filter_map(|v| v.as_str().map(std::string::ToString::to_string))
-                            .collect();
-
-                        let analysis = crate::safety::analyze_command(&cmd, &self.config.cwd);
-                        let requires = crate::safety::requires_approval(
-                            &analysis,
-                            &self.config.approval_policy,
-                        );
-
-                        if requires {
-                            // Emit approval request
-                            self.emit(EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
-                                call_id: tool_call.id.clone(),
-                                turn_id: self.turn_id.to_string(),
-                                command: cmd.clone(),
-                                cwd: self.config.cwd.clone(),
-                                sandbox_assessment: None,
-                            }))
-                            .await;
-                            true
-                        } else {
-                            false
-                        }
+                // Check if approval is needed for shell commands. A PTY
+                // terminal runs its command, or the user's shell, the same way,
+                // and text typed into one is checked as a shell script.
+                let command_args = match tool_name.as_str() {
+                    "Execute" => args.get("command").and_then(|c| c.as_array()).cloned(),
+                    "TerminalOpen" => Some(
+                        args.get("command")
+                            .and_then(|c| c.as_array())
+                            .filter(|c| !c.is_empty())
+                            .cloned()
+                            .unwrap_or_else(|| {
+                                vec![serde_json::Value::String(
+                                    cortex_utils_pty::get_default_shell(),
+                                )]
+                            }),
+                    ),
+                    "TerminalSend" => args.get("keys").and_then(|k| k.as_str()).map(|keys| {
+                        vec![
+                            serde_json::Value::String(cortex_utils_pty::get_default_shell()),
+                            serde_json::Value::String("-c".to_string()),
+                            serde_json::Value::String(crate::terminal::typed_text(keys)),
+                        ]
+                    }),
+                    _ => None,
+                };
+                let needs_approval = if let Some(cmd_array) = command_args {
+                    let cmd: Vec<String> = cmd_array
+                        .iter()
+                        .filter_map(|v| v.as_str().map(std::string::ToString::to_string))
+                        .collect();
+
+                    let analysis = crate::safety::analyze_command(&cmd, &self.config.cwd);
+                    let requires =
+                        crate::safety::requires_approval(&analysis, &self.config.approval_policy);
+
+                    if requires {
+                        // Emit approval request
+                        self.emit(EventMsg::ExecApprovalRequest(ExecApprovalRequestEvent {
+                            call_id: tool_call.id.clone(),
+                            turn_id: self.turn_id.to_string(),
+                            command: cmd.clone(),
+                            cwd: self.config.cwd.clone(),
+                            sandbox_assessment: None,
+                        }))
+                        .await;
+                        true
                     } else {
                         false
                     }
@@ -432,11 +460,13 @@ impl Session {
                 // Execute tool with streaming context
                 let context = ToolContext::new(self.config.cwd.clone())
                     .with_sandbox_policy(self.config.sandbox_policy.clone())
+                    .with_env(self.network_env())
                     .with_turn_id(self.turn_id.to_string())
                     .with_conversation_id(self.conversation_id.to_string())
                     .with_call_id(tool_call.id.clone())
                     .with_output_sender(output_tx)
-                    .with_lsp(self.lsp.clone());
+                    .with_lsp(self.lsp.clone())
+                    .with_client_bridge(self.client_bridge.clone());
 
                 // Clone event sender for the streaming task
                 let event_tx = self.event_tx.clone();
@@ -6,8 +6,9 @@ use chrono::Utc;
 use tracing::info;
 
 use cortex_protocol::{
-    AgentMessageEvent, ErrorEvent, EventMsg, SessionConfiguredEvent, TaskCompleteEvent,
-    TaskStartedEvent, TurnDiffEvent, UserMessageEvent,
+    AgentMessageEvent, ErrorEvent, EventMsg, ExecCommandBeginEvent, ExecCommandEndEvent,
+    ExecCommandSource, ParsedCommand, SessionConfiguredEvent, TaskCompleteEvent, TaskStartedEvent,
+    TurnDiffEvent, UserMessageEvent,
 };
 
 use crate::client::{Message, MessageRole};
@@ -21,6 +22,7 @@ use super::prompt::{
     USE_SKILL_BASED_PROMPT, auto_detect_skills_from_message, build_system_prompt,
     build_system_prompt_with_skills, inject_skills,
 };
+use super::types::PendingToolCall;
 
 impl Session {
     /// Handle an incoming submission.
@@ -86,6 +88,7 @@ impl Session {
                 if let Some(policy) = sandbox_policy {
                     self.config.sandbox_policy = policy;
                 }
+                crate::managed_policy::enforce(&mut self.config)?;
                 if let Some(model) = model {
                     self.config.model = model;
                 }
@@ -631,25 +634,89 @@ impl Session {
         use crate::tools::ToolContext;
 
         if let Some(pending) = self.pending_approvals.remove(call_id) {
+            audit_review(&self.conversation_id.to_string(), &pending, decision).await;
+
             match decision {
                 ReviewDecision::Approved | ReviewDecision::ApprovedForSession => {
                     // Execute the approved tool
                     let context = ToolContext::new(self.config.cwd.clone())
                         .with_sandbox_policy(self.config.sandbox_policy.clone())
+                        .with_env(self.network_env())
                         .with_turn_id(self.turn_id.to_string())
                         .with_conversation_id(self.conversation_id.to_string())
-                        .with_lsp(self.lsp.clone());
+                        .with_call_id(pending.tool_call_id.clone())
+                        .with_lsp(self.lsp.clone())
+                        .with_client_bridge(self.client_bridge.clone());
+
+                    // Report an approved command like any other, so clients
+                    // and the rollout see its output
+                    let exec = SHELL_TOOLS.contains(&pending.tool_name.as_str()).then(|| {
+                        let command: Vec<String> = pending
+                            .arguments
+                            .get("command")
+                            .and_then(|c| c.as_array())
+                            .map(|parts| {
+                                parts
+                                    .iter()
+                                    .filter_map(|p| p.as_str().map(String::from))
+                                    .collect()
+                            })
+                            .unwrap_or_else(|| vec![pending.tool_name.clone()]);
+                        let parsed_cmd = vec![ParsedCommand {
+                            program: command.first().cloned().unwrap_or_default(),
+                            args: command.iter().skip(1).cloned().collect(),
+                        }];
+                        (command, parsed_cmd)
+                    });
+                    if let Some((command, parsed_cmd)) = &exec {
+                        self.emit(EventMsg::ExecCommandBegin(ExecCommandBeginEvent {
+                            call_id: pending.tool_call_id.clone(),
+                            turn_id: self.turn_id.to_string(),
+                            command: command.clone(),
+                            cwd: self.config.cwd.clone(),
+                            parsed_cmd: parsed_cmd.clone(),
+                            source: ExecCommandSource::Agent,
+                            interaction_input: None,
+                            tool_name: Some(pending.tool_name.clone()),
+                            tool_arguments: Some(pending.arguments.clone()),
+                        }))
+                        .await;
+                    }
 
+                    let exec_start = std::time::Instant::now();
                     let result = self
                         .tool_router
                         .execute(&pending.tool_name, pending.arguments, &context)
                         .await;
 
-                    let result_text = match result {
-                        Ok(r) => r.output,
-                        Err(e) => format!("Error: {e}"),
+                    let (result_text, exit_code, metadata) = match result {
+                        Ok(r) => {
+                            let meta = r.metadata.and_then(|m| m.data);
+                            (r.output, if r.success { 0 } else { 1 }, meta)
+                        }
+                        Err(e) => (format!("Error: {e}"), 1, None),
                     };
 
+                    if let Some((command, parsed_cmd)) = exec {
+                        self.emit(EventMsg::ExecCommandEnd(Box::new(ExecCommandEndEvent {
+                            call_id: pending.tool_call_id.clone(),
+                            turn_id: self.turn_id.to_string(),
+                            command,
+                            cwd: self.config.cwd.clone(),
+                            parsed_cmd,
+                            source: ExecCommandSource::Agent,
+                            interaction_input: None,
+                            stdout: result_text.clone(),
+                            stderr: String::new(),
+                            aggregated_output: result_text.clone(),
+                            exit_code,
+                            duration_ms: exec_start.elapsed().as_millis() as u64,
+                            formatted_output: result_text.clone(),
+                            metadata,
+                        })))
+                        .await;
+                    }
+
                     // Add tool result to messages
                     self.messages
                         .push(Message::tool_result(&pending.tool_call_id, &result_text));
@@ -657,15 +724,64 @@ impl Session {
                     // Continue the agent loop
                     self.run_agent_loop(&self.turn_id.to_string()).await?;
                 }
-                ReviewDecision::Denied | ReviewDecision::Abort => {
+                ReviewDecision::Denied => {
                     // Add rejection message as tool result
                     self.messages.push(Message::tool_result(
                         &pending.tool_call_id,
                         "Command was rejected by user.",
                     ));
+
+                    // Let the model respond so the turn completes
+                    self.run_agent_loop(&self.turn_id.to_string()).await?;
+                }
+                ReviewDecision::Abort => {
+                    self.messages.push(Message::tool_result(
+                        &pending.tool_call_id,
+                        "Command was rejected by user.",
+                    ));
+
+                    // End the turn without another model call
+                    self.emit(EventMsg::TaskComplete(TaskCompleteEvent {
+                        last_agent_message: None,
+                    }))
+                    .await;
                 }
             }
         }
         Ok(())
     }
 }
+
+/// Tools whose approved calls are reported as command executions.
+const SHELL_TOOLS: &[&str] = &["Execute", "TerminalOpen", "TerminalSend"];
+
+/// Record the user's decision on a pending tool call in the audit log.
+async fn audit_review(
+    session: &str,
+    pending: &PendingToolCall,
+    decision: cortex_protocol::ReviewDecision,
+) {
+    use crate::audit::{self, AuditEvent, AuditKind};
+    use cortex_protocol::ReviewDecision;
+
+    let (kind, outcome) = match decision {
+        ReviewDecision::Approved => (AuditKind::Approval, "approved by user"),
+        ReviewDecision::ApprovedForSession => (AuditKind::Approval, "approved by user for session"),
+        ReviewDecision::Denied => (AuditKind::Denial, "denied by user"),
+        ReviewDecision::Abort => (AuditKind::Denial, "aborted by user"),
+    };
+    let target = pending
+        .arguments
+        .get("command")
+        .and_then(|c| c.as_array())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|p| p.as_str())
+                .collect::<Vec<_>>()
+                .join(" ")
+        })
+        .unwrap_or_else(|| pending.tool_name.clone());
+    let event = AuditEvent::new(kind, &pending.tool_name, target, outcome);
+    audit::in_session(session, audit::record(event)).await;
+}
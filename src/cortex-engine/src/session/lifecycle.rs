@@ -12,7 +12,7 @@ use cortex_protocol::{
     AgentMessageEvent, ConversationId, Event, EventMsg, TokenUsage, UserMessageEvent,
 };
 
-use crate::client::{Message, create_client};
+use crate::client::{Message, ModelClient, create_client_from_config};
 use crate::config::Config;
 use crate::error::Result;
 use crate::rollout::reader::{RolloutItem, get_events, get_session_meta};
@@ -27,23 +27,26 @@ use super::types::{SessionHandle, SessionInfo, TokenCounter};
 impl Session {
     /// Create a new session with channels.
     pub fn new(config: Config) -> Result<(Self, SessionHandle)> {
+        // Custom providers are called directly; everything else goes through Cortex auth
+        let client = create_client_from_config(&config)?;
+        Self::with_client(config, client)
+    }
+
+    /// Create a new session that talks to `client`.
+    ///
+    /// Used to drive sessions from a fake or replaying model, e.g.
+    /// [`CassetteClient`](crate::client::CassetteClient).
+    pub fn with_client(
+        mut config: Config,
+        client: Box<dyn ModelClient>,
+    ) -> Result<(Self, SessionHandle)> {
+        crate::managed_policy::enforce(&mut config)?;
         let (submission_tx, submission_rx) = unbounded();
         let (event_tx, event_rx) = unbounded();
 
         let conversation_id = ConversationId::new();
         let cancelled = Arc::new(AtomicBool::new(false));
 
-        // Get API key using centralized auth module
-        let api_key = crate::auth_token::get_auth_token(None)
-            .map_err(|e| anyhow::anyhow!("Authentication required: {}", e))?;
-
-        let client = create_client(
-            &config.model_provider_id,
-            &config.model,
-            &api_key,
-            Some(config.model_provider.base_url.as_str()),
-        )?;
-
         let mut tool_router = ToolRouter::new();
 
         // Initialize rollout recorder
@@ -116,6 +119,8 @@ impl Session {
             current_undo_actions: Vec::new(),
             share_service: crate::share_service::ShareService::new(),
             lsp,
+            client_bridge: None,
+            network_proxy: None,
         };
 
         let handle = SessionHandle {
@@ -130,25 +135,18 @@ impl Session {
 
     /// Resume a session from a rollout file.
     pub fn resume(
-        config: Config,
+        mut config: Config,
         conversation_id: ConversationId,
     ) -> Result<(Self, SessionHandle)> {
+        crate::managed_policy::enforce(&mut config)?;
         let (submission_tx, submission_rx) = unbounded();
         let (event_tx, event_rx) = unbounded();
 
         let rollout_path = get_rollout_path(&config.cortex_home, &conversation_id);
         let entries = read_rollout(&rollout_path)?;
 
-        // Get API key using centralized auth module
-        let api_key = crate::auth_token::get_auth_token(None)
-            .map_err(|e| anyhow::anyhow!("Authentication required: {}", e))?;
-
-        let client = create_client(
-            &config.model_provider_id,
-            &config.model,
-            &api_key,
-            Some(config.model_provider.base_url.as_str()),
-        )?;
+        // Custom providers are called directly; everything else goes through Cortex auth
+        let client = create_client_from_config(&config)?;
 
         let mut tool_router = ToolRouter::new();
 
@@ -172,20 +170,18 @@ impl Session {
                 EventMsg::AgentMessage(e) => {
                     messages.push(Message::assistant(&e.message));
                 }
-                EventMsg::UndoCompleted(e) => {
-                    if e.success {
-                        // Replicate handle_undo logic
-                        while let Some(msg) = messages.last() {
-                            if matches!(msg.role, crate::client::MessageRole::User) {
-                                break;
-                            }
-                            messages.pop();
-                        }
-                        if let Some(msg) = messages.last()
-                            && matches!(msg.role, crate::client::MessageRole::User)
-                        {
-                            messages.pop();
+                EventMsg::UndoCompleted(e) if e.success => {
+                    // Replicate handle_undo logic
+                    while let Some(msg) = messages.last() {
+                        if matches!(msg.role, crate::client::MessageRole::User) {
+                            break;
                         }
+                        messages.pop();
+                    }
+                    if let Some(msg) = messages.last()
+                        && matches!(msg.role, crate::client::MessageRole::User)
+                    {
+                        messages.pop();
                     }
                 }
                 _ => {}
@@ -231,6 +227,8 @@ impl Session {
             current_undo_actions: Vec::new(),
             share_service: crate::share_service::ShareService::new(),
             lsp,
+            client_bridge: None,
+            network_proxy: None,
         };
 
         let handle = SessionHandle {
@@ -245,25 +243,18 @@ impl Session {
 
     /// Fork a session from an existing conversation.
     pub fn fork(
-        config: Config,
+        mut config: Config,
         original_conversation_id: ConversationId,
         message_index: usize,
     ) -> Result<(Self, SessionHandle)> {
+        crate::managed_policy::enforce(&mut config)?;
         let (submission_tx, submission_rx) = unbounded();
         let (event_tx, event_rx) = unbounded();
 
         let new_conversation_id = ConversationId::new();
 
-        // Get API key using centralized auth module
-        let api_key = crate::auth_token::get_auth_token(None)
-            .map_err(|e| anyhow::anyhow!("Authentication required: {}", e))?;
-
-        let client = create_client(
-            &config.model_provider_id,
-            &config.model,
-            &api_key,
-            Some(config.model_provider.base_url.as_str()),
-        )?;
+        // Custom providers are called directly; everything else goes through Cortex auth
+        let client = create_client_from_config(&config)?;
 
         let mut tool_router = ToolRouter::new();
 
@@ -399,6 +390,8 @@ impl Session {
             current_undo_actions: Vec::new(),
             share_service: crate::share_service::ShareService::new(),
             lsp,
+            client_bridge: None,
+            network_proxy: None,
         };
 
         let handle = SessionHandle {
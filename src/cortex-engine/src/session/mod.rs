@@ -14,10 +14,12 @@ use std::sync::atomic::AtomicBool;
 
 use async_channel::{Receiver, Sender};
 
+use cortex_network_proxy::NetworkProxy;
 use cortex_protocol::{ConversationId, Event, Submission, TokenUsage};
 
 use crate::client::{Message, ModelClient};
 use crate::config::Config;
+use crate::error::{CortexError, Result};
 use crate::rollout::RolloutRecorder;
 use crate::tools::ToolRouter;
 
@@ -72,9 +74,45 @@ pub struct Session {
     pub(crate) share_service: crate::share_service::ShareService,
     /// LSP integration.
     pub(crate) lsp: Arc<crate::integrations::LspIntegration>,
+    /// Client-side file and terminal access (ACP editors).
+    pub(crate) client_bridge: Option<Arc<dyn crate::tools::ClientBridge>>,
+    /// Proxy tool processes reach the network through, when configured.
+    pub(crate) network_proxy: Option<NetworkProxy>,
 }
 
 impl Session {
+    /// Route file and shell tools through the connected client.
+    pub fn set_client_bridge(&mut self, bridge: Arc<dyn crate::tools::ClientBridge>) {
+        self.client_bridge = Some(bridge);
+    }
+
+    /// Start the network proxy if `[network_proxy]` is enabled, which the
+    /// managed policy's `[network]` forces. Tools never run without a
+    /// configured proxy, so failing to start it is an error.
+    pub(crate) async fn start_network_proxy(&mut self) -> Result<()> {
+        if self.network_proxy.is_some() {
+            return Ok(());
+        }
+        let Some(config) = self.config.network_proxy.clone().filter(|c| c.enabled) else {
+            return Ok(());
+        };
+
+        let proxy = NetworkProxy::start(config)
+            .await
+            .map_err(|e| CortexError::Config(format!("Failed to start network proxy: {e}")))?;
+        tracing::info!("Network proxy listening on {}", proxy.addr());
+        self.network_proxy = Some(proxy);
+        Ok(())
+    }
+
+    /// Variables routing tool processes through the network proxy.
+    pub(crate) fn network_env(&self) -> Vec<(String, String)> {
+        self.network_proxy
+            .as_ref()
+            .map(NetworkProxy::env_vars)
+            .unwrap_or_default()
+    }
+
     /// Emit an event to the event channel and optionally record it.
     pub(crate) async fn emit(&mut self, msg: cortex_protocol::EventMsg) {
         // Skip rollout recording for delta events (too frequent, causes latency)
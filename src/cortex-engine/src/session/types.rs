@@ -1,20 +1,23 @@
 //! Session types - TokenCounter, PendingToolCall, SessionHandle, SessionInfo.
 
+use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
+use std::sync::{Arc, Mutex};
 
 use async_channel::{Receiver, Sender};
 
 use cortex_protocol::{ConversationId, Event, Submission};
 
-use crate::client::types::MessageContent;
 use crate::client::{Message, ToolDefinition as ClientToolDefinition};
 use crate::error::Result;
+use crate::tokenizer::{self, TokenizerType};
 
-/// Simple token counter for session context tracking.
-/// Uses approximation-based counting (4 chars per token).
-pub struct TokenCounter;
+/// Token counter for session context tracking.
+/// Counts with the BPE vocabulary of the session's model, caching per tokenizer.
+pub struct TokenCounter {
+    counters: Mutex<HashMap<TokenizerType, tokenizer::TokenCounter>>,
+}
 
 impl Default for TokenCounter {
     fn default() -> Self {
@@ -25,51 +28,27 @@ impl Default for TokenCounter {
 impl TokenCounter {
     /// Create a new token counter.
     pub fn new() -> Self {
-        Self
+        Self {
+            counters: Mutex::new(HashMap::new()),
+        }
     }
 
-    /// Count tokens in messages (approximate).
-    pub async fn count_messages(&self, _model: &str, messages: &[Message]) -> Result<usize> {
-        let mut total = 0usize;
-        for msg in messages {
-            // Base overhead per message (~4 tokens)
-            total += 4;
-            // Count content tokens
-            let text = match &msg.content {
-                MessageContent::Text(t) => t.len(),
-                MessageContent::Parts(parts) => parts
-                    .iter()
-                    .map(|p| match p {
-                        crate::client::types::ContentPart::Text { text, .. } => text.len(),
-                        _ => 85, // Image tokens approximation
-                    })
-                    .sum(),
-                MessageContent::ToolResult { content, .. } => content.len(),
-                MessageContent::ToolCalls(calls) => {
-                    calls.iter().map(|c| c.name.len() + c.arguments.len()).sum()
-                }
-            };
-            // Approximate: 4 chars per token
-            total += (text as f64 / 4.0).ceil() as usize;
-        }
-        // Message separator overhead
-        total += messages.len() * 3;
-        Ok(total)
+    /// Count tokens in messages, including images and tool calls.
+    pub async fn count_messages(&self, model: &str, messages: &[Message]) -> Result<usize> {
+        Ok(self.with_counter(model, |counter| counter.count_conversation(messages)) as usize)
     }
 
-    /// Count tokens in tool definitions (approximate).
-    pub async fn count_tools(&self, _model: &str, tools: &[ClientToolDefinition]) -> Result<usize> {
-        let mut total = 0usize;
-        for tool in tools {
-            let json = serde_json::to_string(tool).unwrap_or_default();
-            // Approximate: 4 chars per token + 10 overhead
-            total += (json.len() as f64 / 4.0).ceil() as usize + 10;
-        }
-        // Base overhead for having tools
-        if !tools.is_empty() {
-            total += 9;
-        }
-        Ok(total)
+    /// Count tokens in tool definitions, including their JSON schemas.
+    pub async fn count_tools(&self, model: &str, tools: &[ClientToolDefinition]) -> Result<usize> {
+        Ok(self.with_counter(model, |counter| counter.count_tools(tools)) as usize)
+    }
+
+    fn with_counter<T>(&self, model: &str, f: impl FnOnce(&mut tokenizer::TokenCounter) -> T) -> T {
+        let tokenizer = TokenizerType::for_model(model);
+        let mut counters = self.counters.lock().unwrap_or_else(|e| e.into_inner());
+        f(counters
+            .entry(tokenizer)
+            .or_insert_with(|| tokenizer::TokenCounter::new(tokenizer)))
     }
 }
 
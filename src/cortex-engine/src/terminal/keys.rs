@@ -0,0 +1,187 @@
+//! Keystroke notation for PTY terminals.
+//!
+//! Text is sent as-is, except for key names in angle brackets such as
+//! `<Enter>`, `<Up>`, `<C-c>` or `<M-x>`. Names are case-insensitive. Use
+//! `<lt>` for a literal `<`; anything else in brackets that is not a key
+//! name is also sent literally.
+
+/// Encode keystroke notation into the bytes a terminal would send.
+///
+/// `application_cursor` selects the cursor key encoding programs such as
+/// `vim` and `less` switch the terminal into.
+pub fn encode_keys(input: &str, application_cursor: bool) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(open) = rest.find('<') {
+        bytes.extend_from_slice(&rest.as_bytes()[..open]);
+        let after = &rest[open + 1..];
+        let key = after
+            .find('>')
+            .map(|close| (&after[..close], &after[close + 1..]))
+            .and_then(|(name, tail)| encode_key(name, application_cursor).map(|k| (k, tail)));
+        match key {
+            Some((encoded, tail)) => {
+                bytes.extend_from_slice(&encoded);
+                rest = tail;
+            }
+            None => {
+                bytes.push(b'<');
+                rest = after;
+            }
+        }
+    }
+    bytes.extend_from_slice(rest.as_bytes());
+    bytes
+}
+
+/// The text keystroke notation types, for checking it like a command line.
+///
+/// `<Enter>` becomes a newline and other named keys that do not type a
+/// printable character become spaces.
+pub fn typed_text(input: &str) -> String {
+    let mut text = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(open) = rest.find('<') {
+        text.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let key = after
+            .find('>')
+            .map(|close| (&after[..close], &after[close + 1..]))
+            .and_then(|(name, tail)| encode_key(name, false).map(|k| (k, tail)));
+        match key {
+            Some((encoded, tail)) => {
+                text.push(match encoded.as_slice() {
+                    [b'\r'] => '\n',
+                    [b] if b.is_ascii_graphic() => *b as char,
+                    _ => ' ',
+                });
+                rest = tail;
+            }
+            None => {
+                text.push('<');
+                rest = after;
+            }
+        }
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Encode a single key name, without the angle brackets.
+fn encode_key(name: &str, application_cursor: bool) -> Option<Vec<u8>> {
+    if let Some((modifier, key)) = name.split_once('-').filter(|(_, key)| !key.is_empty()) {
+        return match modifier.to_ascii_lowercase().as_str() {
+            "c" | "ctrl" => control(key).map(|b| vec![b]),
+            "m" | "alt" | "meta" => {
+                let mut encoded = vec![0x1b];
+                match encode_key(key, application_cursor) {
+                    Some(named) => encoded.extend(named),
+                    None if key.chars().count() == 1 => encoded.extend_from_slice(key.as_bytes()),
+                    None => return None,
+                }
+                Some(encoded)
+            }
+            _ => None,
+        };
+    }
+
+    let cursor = |c: u8| {
+        if application_cursor {
+            vec![0x1b, b'O', c]
+        } else {
+            vec![0x1b, b'[', c]
+        }
+    };
+    let tilde = |n: &str| format!("\x1b[{n}~").into_bytes();
+
+    let encoded = match name.to_ascii_lowercase().as_str() {
+        "enter" | "cr" | "return" => vec![b'\r'],
+        "tab" => vec![b'\t'],
+        "esc" | "escape" => vec![0x1b],
+        "backspace" | "bs" => vec![0x7f],
+        "space" => vec![b' '],
+        "lt" => vec![b'<'],
+        "up" => cursor(b'A'),
+        "down" => cursor(b'B'),
+        "right" => cursor(b'C'),
+        "left" => cursor(b'D'),
+        "home" => cursor(b'H'),
+        "end" => cursor(b'F'),
+        "insert" | "ins" => tilde("2"),
+        "delete" | "del" => tilde("3"),
+        "pageup" | "pgup" => tilde("5"),
+        "pagedown" | "pgdn" => tilde("6"),
+        "f1" => b"\x1bOP".to_vec(),
+        "f2" => b"\x1bOQ".to_vec(),
+        "f3" => b"\x1bOR".to_vec(),
+        "f4" => b"\x1bOS".to_vec(),
+        "f5" => tilde("15"),
+        "f6" => tilde("17"),
+        "f7" => tilde("18"),
+        "f8" => tilde("19"),
+        "f9" => tilde("20"),
+        "f10" => tilde("21"),
+        "f11" => tilde("23"),
+        "f12" => tilde("24"),
+        _ => return None,
+    };
+    Some(encoded)
+}
+
+/// The control character for `Ctrl` plus `key`.
+fn control(key: &str) -> Option<u8> {
+    let mut chars = key.chars();
+    let c = chars.next()?;
+    if chars.next().is_some() {
+        return match key.to_ascii_lowercase().as_str() {
+            "space" => Some(0),
+            _ => None,
+        };
+    }
+    match c.to_ascii_uppercase() {
+        c @ ('@'..='_') => Some(c as u8 & 0x1f),
+        '?' => Some(0x7f),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_named_keys() {
+        assert_eq!(encode_keys("ls -la<Enter>", false), b"ls -la\r");
+        assert_eq!(
+            encode_keys("<C-c><ctrl-D><Esc>:wq<CR>", false),
+            b"\x03\x04\x1b:wq\r"
+        );
+        assert_eq!(encode_keys("<M-x><Alt-Left>", false), b"\x1bx\x1b\x1b[D");
+        assert_eq!(encode_keys("<F5><PageDown>", false), b"\x1b[15~\x1b[6~");
+    }
+
+    #[test]
+    fn test_cursor_keys_follow_mode() {
+        assert_eq!(encode_keys("<Up><End>", false), b"\x1b[A\x1b[F");
+        assert_eq!(encode_keys("<Up><End>", true), b"\x1bOA\x1bOF");
+    }
+
+    #[test]
+    fn test_unknown_names_are_literal() {
+        assert_eq!(encode_keys("a <b> c", false), b"a <b> c");
+        assert_eq!(encode_keys("<lt>Enter>", false), b"<Enter>");
+        assert_eq!(encode_keys("x < y <", false), b"x < y <");
+        assert_eq!(encode_keys("<C-foo>", false), b"<C-foo>");
+    }
+
+    #[test]
+    fn test_typed_text() {
+        assert_eq!(
+            typed_text("cd src<Enter>rm -rf<Space>build<Tab><C-c>"),
+            "cd src\nrm -rf build  "
+        );
+        assert_eq!(typed_text("<lt>b> <Up>"), "<b>  ");
+    }
+}
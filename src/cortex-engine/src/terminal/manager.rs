@@ -2,11 +2,13 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{RwLock, mpsc};
 use uuid::Uuid;
 
 use super::process::{BackgroundTerminal, LogLine, LogStream};
+use super::pty::{PtyTerminal, ScreenSnapshot, WaitFor, WaitOutcome};
 
 /// Terminal status.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -17,6 +19,17 @@ pub enum TerminalStatus {
     Error,
 }
 
+/// How a terminal is connected to its program.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TerminalKind {
+    /// A shell with piped, line-based output.
+    #[default]
+    Background,
+    /// A program in a pseudo-terminal, with an emulated screen.
+    Pty,
+}
+
 /// Terminal information.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TerminalInfo {
@@ -26,6 +39,48 @@ pub struct TerminalInfo {
     pub status: TerminalStatus,
     pub created_at: u64,
     pub exit_code: Option<i32>,
+    #[serde(default)]
+    pub kind: TerminalKind,
+    /// Command line of a PTY terminal.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl TerminalInfo {
+    fn from_background(t: &mut BackgroundTerminal) -> Self {
+        t.check_status();
+        Self {
+            id: t.id.clone(),
+            name: t.name.clone(),
+            cwd: t.cwd.clone(),
+            status: if t.running {
+                TerminalStatus::Running
+            } else {
+                TerminalStatus::Stopped
+            },
+            created_at: t.created_at,
+            exit_code: t.exit_code,
+            kind: TerminalKind::Background,
+            command: None,
+        }
+    }
+
+    fn from_pty(t: &PtyTerminal) -> Self {
+        Self {
+            id: t.id.clone(),
+            name: t.name.clone(),
+            cwd: t.cwd.clone(),
+            status: if t.check_status() {
+                TerminalStatus::Running
+            } else {
+                TerminalStatus::Stopped
+            },
+            created_at: t.created_at,
+            exit_code: t.exit_code(),
+            kind: TerminalKind::Pty,
+            command: Some(t.command.clone()),
+        }
+    }
 }
 
 /// Terminal output event for streaming.
@@ -52,9 +107,17 @@ impl From<(String, LogLine)> for TerminalOutput {
     }
 }
 
+static GLOBAL_MANAGER: OnceLock<TerminalManager> = OnceLock::new();
+
+/// The terminal manager shared by the agent tools and the app server.
+pub fn global_manager() -> &'static TerminalManager {
+    GLOBAL_MANAGER.get_or_init(TerminalManager::new)
+}
+
 /// Manages multiple background terminals.
 pub struct TerminalManager {
     terminals: Arc<RwLock<HashMap<String, BackgroundTerminal>>>,
+    ptys: Arc<RwLock<HashMap<String, Arc<PtyTerminal>>>>,
     /// Channel for terminal output events.
     output_tx: mpsc::UnboundedSender<TerminalOutput>,
     output_rx: Arc<RwLock<Option<mpsc::UnboundedReceiver<TerminalOutput>>>>,
@@ -66,6 +129,7 @@ impl TerminalManager {
         let (tx, rx) = mpsc::unbounded_channel();
         Self {
             terminals: Arc::new(RwLock::new(HashMap::new())),
+            ptys: Arc::new(RwLock::new(HashMap::new())),
             output_tx: tx,
             output_rx: Arc::new(RwLock::new(Some(rx))),
         }
@@ -96,6 +160,8 @@ impl TerminalManager {
             status: TerminalStatus::Running,
             created_at: terminal.created_at,
             exit_code: None,
+            kind: TerminalKind::Background,
+            command: None,
         };
 
         // Forward output to manager's channel
@@ -115,8 +181,74 @@ impl TerminalManager {
         Ok(info)
     }
 
+    /// Start `command` (the default shell if empty) in a new PTY terminal
+    /// of `cols` by `rows`, with environment `env` if given.
+    pub async fn create_pty_terminal(
+        &self,
+        name: String,
+        cwd: String,
+        command: Vec<String>,
+        env: Option<HashMap<String, String>>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<TerminalInfo, String> {
+        let id = Uuid::new_v4().to_string();
+        let terminal =
+            PtyTerminal::spawn(id.clone(), name, cwd, &command, env.as_ref(), cols, rows)?;
+        let info = TerminalInfo::from_pty(&terminal);
+        self.ptys.write().await.insert(id, Arc::new(terminal));
+        Ok(info)
+    }
+
+    async fn pty(&self, terminal_id: &str) -> Result<Arc<PtyTerminal>, String> {
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            return Ok(terminal.clone());
+        }
+        if self.terminals.read().await.contains_key(terminal_id) {
+            Err(format!("Terminal {} is not a PTY terminal", terminal_id))
+        } else {
+            Err(format!("Terminal not found: {}", terminal_id))
+        }
+    }
+
+    /// Send keystrokes to a PTY terminal, in
+    /// [`encode_keys`](super::encode_keys) notation.
+    pub async fn send_keys(&self, terminal_id: &str, keys: &str) -> Result<(), String> {
+        self.pty(terminal_id).await?.send_keys(keys)
+    }
+
+    /// Wait for a condition in a PTY terminal.
+    pub async fn wait_for(
+        &self,
+        terminal_id: &str,
+        condition: &WaitFor,
+        timeout: Duration,
+    ) -> Result<WaitOutcome, String> {
+        let terminal = self.pty(terminal_id).await?;
+        Ok(terminal.wait(condition, timeout).await)
+    }
+
+    /// Get the rendered screen of a PTY terminal.
+    pub async fn screen(&self, terminal_id: &str) -> Result<ScreenSnapshot, String> {
+        Ok(self.pty(terminal_id).await?.snapshot())
+    }
+
+    /// Resize a PTY terminal.
+    pub async fn resize_terminal(
+        &self,
+        terminal_id: &str,
+        cols: u16,
+        rows: u16,
+    ) -> Result<(), String> {
+        self.pty(terminal_id).await?.resize(cols, rows)
+    }
+
     /// Run a command in a terminal.
     pub async fn run_command(&self, terminal_id: &str, command: &str) -> Result<(), String> {
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            return terminal.send_bytes(format!("{}\r", command).as_bytes());
+        }
+
         let mut terminals = self.terminals.write().await;
         let terminal = terminals
             .get_mut(terminal_id)
@@ -131,12 +263,30 @@ impl TerminalManager {
         terminal_id: &str,
         tail: Option<usize>,
     ) -> Result<Vec<TerminalOutput>, String> {
+        let tail = tail.unwrap_or(100);
+
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            let timestamp = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64;
+            return Ok(terminal
+                .history(tail)
+                .into_iter()
+                .map(|content| TerminalOutput {
+                    terminal_id: terminal_id.to_string(),
+                    timestamp,
+                    content,
+                    stream: "stdout".to_string(),
+                })
+                .collect());
+        }
+
         let terminals = self.terminals.read().await;
         let terminal = terminals
             .get(terminal_id)
             .ok_or_else(|| format!("Terminal not found: {}", terminal_id))?;
 
-        let tail = tail.unwrap_or(100);
         let logs = terminal.get_logs(tail);
 
         Ok(logs
@@ -145,53 +295,44 @@ impl TerminalManager {
             .collect())
     }
 
-    /// List all terminals.
+    /// List all terminals, oldest first.
     pub async fn list_terminals(&self) -> Vec<TerminalInfo> {
-        let mut terminals = self.terminals.write().await;
-
-        terminals
+        let mut infos: Vec<TerminalInfo> = self
+            .terminals
+            .write()
+            .await
             .values_mut()
-            .map(|t| {
-                t.check_status();
-                TerminalInfo {
-                    id: t.id.clone(),
-                    name: t.name.clone(),
-                    cwd: t.cwd.clone(),
-                    status: if t.running {
-                        TerminalStatus::Running
-                    } else {
-                        TerminalStatus::Stopped
-                    },
-                    created_at: t.created_at,
-                    exit_code: t.exit_code,
-                }
-            })
-            .collect()
+            .map(TerminalInfo::from_background)
+            .collect();
+        infos.extend(
+            self.ptys
+                .read()
+                .await
+                .values()
+                .map(|t| TerminalInfo::from_pty(t)),
+        );
+        infos.sort_by_key(|info| info.created_at);
+        infos
     }
 
     /// Get terminal info.
     pub async fn get_terminal(&self, terminal_id: &str) -> Option<TerminalInfo> {
-        let mut terminals = self.terminals.write().await;
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            return Some(TerminalInfo::from_pty(terminal));
+        }
 
-        terminals.get_mut(terminal_id).map(|t| {
-            t.check_status();
-            TerminalInfo {
-                id: t.id.clone(),
-                name: t.name.clone(),
-                cwd: t.cwd.clone(),
-                status: if t.running {
-                    TerminalStatus::Running
-                } else {
-                    TerminalStatus::Stopped
-                },
-                created_at: t.created_at,
-                exit_code: t.exit_code,
-            }
-        })
+        let mut terminals = self.terminals.write().await;
+        terminals
+            .get_mut(terminal_id)
+            .map(TerminalInfo::from_background)
     }
 
     /// Kill a terminal.
     pub async fn kill_terminal(&self, terminal_id: &str) -> Result<(), String> {
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            return terminal.kill();
+        }
+
         let mut terminals = self.terminals.write().await;
         let terminal = terminals
             .get_mut(terminal_id)
@@ -202,6 +343,10 @@ impl TerminalManager {
 
     /// Send SIGINT (Ctrl+C) to a terminal to interrupt the running process.
     pub async fn interrupt_terminal(&self, terminal_id: &str) -> Result<(), String> {
+        if let Some(terminal) = self.ptys.read().await.get(terminal_id) {
+            return terminal.send_bytes(&[0x03]);
+        }
+
         let mut terminals = self.terminals.write().await;
         let terminal = terminals
             .get_mut(terminal_id)
@@ -212,6 +357,17 @@ impl TerminalManager {
 
     /// Remove a terminal (must be stopped first).
     pub async fn remove_terminal(&self, terminal_id: &str) -> Result<(), String> {
+        {
+            let mut ptys = self.ptys.write().await;
+            if let Some(terminal) = ptys.get(terminal_id) {
+                if terminal.check_status() {
+                    return Err("Cannot remove running terminal. Kill it first.".to_string());
+                }
+                ptys.remove(terminal_id);
+                return Ok(());
+            }
+        }
+
         let mut terminals = self.terminals.write().await;
 
         // Check if stopped
@@ -236,6 +392,12 @@ impl TerminalManager {
             }
         }
         terminals.clear();
+
+        let mut ptys = self.ptys.write().await;
+        for terminal in ptys.values() {
+            let _ = terminal.kill();
+        }
+        ptys.clear();
     }
 }
 
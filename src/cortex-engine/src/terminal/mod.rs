@@ -2,9 +2,20 @@
 //!
 //! This module provides functionality for creating and managing background terminals
 //! that can run long-running processes and be monitored by agents.
+//!
+//! Terminals come in two kinds. Background terminals run a shell with piped,
+//! line-based output. PTY terminals run a program in a pseudo-terminal behind
+//! a terminal emulator, so interactive programs (REPLs, editors, prompts,
+//! TUI installers) can be driven with keystrokes and their screen read back.
 
+mod keys;
 mod manager;
 mod process;
+mod pty;
 
-pub use manager::{TerminalInfo, TerminalManager, TerminalStatus};
+pub use keys::{encode_keys, typed_text};
+pub use manager::{
+    TerminalInfo, TerminalKind, TerminalManager, TerminalOutput, TerminalStatus, global_manager,
+};
 pub use process::BackgroundTerminal;
+pub use pty::{PtyTerminal, ScreenSnapshot, WaitFor, WaitOutcome};
@@ -0,0 +1,590 @@
+//! PTY-backed terminals with a terminal emulator.
+//!
+//! Unlike [`BackgroundTerminal`](super::BackgroundTerminal), which reads
+//! piped output line by line, a PTY terminal gives the program a real
+//! terminal. REPLs, editors, pagers and TUI installers behave as they would
+//! for a user, and their output is fed through a VT100 emulator so the
+//! rendered screen can be read back at any time.
+
+use std::collections::HashMap;
+use std::io::{Read, Write};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use cortex_utils_pty::{Child, CommandBuilder, MasterPty, PtySize, native_pty_system};
+use regex::Regex;
+use serde::Serialize;
+use tokio::sync::Notify;
+
+use super::keys::encode_keys;
+
+/// Lines of scrollback kept by the emulator.
+const SCROLLBACK_LINES: usize = 5000;
+
+/// Bytes of plain-text output kept for [`WaitFor::Pattern`].
+const MAX_TRANSCRIPT_BYTES: usize = 256 * 1024;
+
+/// Condition to wait for in a PTY terminal.
+#[derive(Debug, Clone)]
+pub enum WaitFor {
+    /// Output received since the last input matches the pattern.
+    Pattern(Regex),
+    /// No output has been received for this long.
+    Idle(Duration),
+}
+
+/// How a wait ended.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum WaitOutcome {
+    /// The pattern matched this text.
+    Matched { text: String },
+    /// The terminal went quiet.
+    Idle,
+    /// The program exited before the condition was met.
+    Exited,
+    /// The timeout elapsed first.
+    TimedOut,
+}
+
+/// Rendered state of a PTY terminal's screen.
+#[derive(Debug, Clone, Serialize)]
+pub struct ScreenSnapshot {
+    /// Screen height.
+    pub rows: u16,
+    /// Screen width.
+    pub cols: u16,
+    /// Cursor position as (row, column), 0-based.
+    pub cursor: (u16, u16),
+    /// Whether the cursor is hidden.
+    pub cursor_hidden: bool,
+    /// Window title set by the program, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Whether a full-screen program has switched to the alternate screen.
+    pub alternate_screen: bool,
+    /// Visible lines, trailing whitespace removed.
+    pub lines: Vec<String>,
+}
+
+impl ScreenSnapshot {
+    /// Render the screen as text, dropping trailing blank lines.
+    pub fn text(&self) -> String {
+        let end = self
+            .lines
+            .iter()
+            .rposition(|line| !line.is_empty())
+            .map_or(0, |i| i + 1);
+        self.lines[..end].join("\n")
+    }
+}
+
+/// Emulator callbacks: remembers the window title and answers the status
+/// queries programs send to find out where the cursor is.
+#[derive(Default)]
+struct Responder {
+    title: Option<String>,
+    replies: Vec<u8>,
+}
+
+impl vt100::Callbacks for Responder {
+    fn set_window_title(&mut self, _: &mut vt100::Screen, title: &[u8]) {
+        self.title = Some(String::from_utf8_lossy(title).into_owned());
+    }
+
+    fn unhandled_csi(
+        &mut self,
+        screen: &mut vt100::Screen,
+        i1: Option<u8>,
+        _i2: Option<u8>,
+        params: &[&[u16]],
+        c: char,
+    ) {
+        let first = params.first().and_then(|p| p.first()).copied();
+        match (i1, c, first) {
+            // Device status report
+            (None, 'n', Some(5)) => self.replies.extend_from_slice(b"\x1b[0n"),
+            // Cursor position report
+            (None, 'n', Some(6)) => {
+                let (row, col) = screen.cursor_position();
+                self.replies
+                    .extend_from_slice(format!("\x1b[{};{}R", row + 1, col + 1).as_bytes());
+            }
+            // Primary device attributes: a VT100 with advanced video
+            (None, 'c', None | Some(0)) => self.replies.extend_from_slice(b"\x1b[?1;2c"),
+            _ => {}
+        }
+    }
+}
+
+/// State shared with the reader thread.
+struct Shared {
+    parser: vt100::Parser<Responder>,
+    /// Plain-text output, escape sequences removed.
+    transcript: String,
+    /// Start of the output not yet consumed by a pattern wait or
+    /// superseded by input.
+    mark: usize,
+    last_output: Instant,
+    eof: bool,
+}
+
+impl Shared {
+    fn append_transcript(&mut self, bytes: &[u8]) {
+        let text = cortex_common::strip_ansi_codes(&String::from_utf8_lossy(bytes));
+        self.transcript.extend(
+            text.chars()
+                .filter(|c| *c == '\n' || *c == '\t' || !c.is_control()),
+        );
+
+        if self.transcript.len() > MAX_TRANSCRIPT_BYTES {
+            let mut cut = self.transcript.len() - MAX_TRANSCRIPT_BYTES;
+            while !self.transcript.is_char_boundary(cut) {
+                cut += 1;
+            }
+            self.transcript.drain(..cut);
+            self.mark = self.mark.saturating_sub(cut);
+        }
+    }
+}
+
+/// A program running in a pseudo-terminal.
+pub struct PtyTerminal {
+    /// Terminal ID.
+    pub id: String,
+    /// Terminal name/description.
+    pub name: String,
+    /// Working directory.
+    pub cwd: String,
+    /// Command line the terminal runs.
+    pub command: String,
+    /// Creation timestamp.
+    pub created_at: u64,
+    process: Mutex<Process>,
+    shared: Arc<Mutex<Shared>>,
+    notify: Arc<Notify>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    master: Mutex<Box<dyn MasterPty + Send>>,
+}
+
+/// The program and what is known about its exit.
+struct Process {
+    child: Box<dyn Child + Send + Sync>,
+    running: bool,
+    exit_code: Option<i32>,
+}
+
+impl Process {
+    fn check_status(&mut self) -> bool {
+        if !self.running {
+            return false;
+        }
+        match self.child.try_wait() {
+            Ok(Some(status)) => {
+                self.exit_code = Some(status.exit_code() as i32);
+                self.running = false;
+            }
+            Ok(None) => {}
+            Err(_) => self.running = false,
+        }
+        self.running
+    }
+}
+
+impl PtyTerminal {
+    /// Start `command` (the default shell if empty) in a new PTY.
+    ///
+    /// With `env`, the program gets exactly that environment instead of
+    /// inheriting Cortex's.
+    pub fn spawn(
+        id: String,
+        name: String,
+        cwd: String,
+        command: &[String],
+        env: Option<&HashMap<String, String>>,
+        cols: u16,
+        rows: u16,
+    ) -> Result<Self, String> {
+        let pair = native_pty_system()
+            .openpty(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to open PTY: {}", e))?;
+
+        let argv = if command.is_empty() {
+            vec![cortex_utils_pty::get_default_shell()]
+        } else {
+            command.to_vec()
+        };
+        let mut cmd = CommandBuilder::new(&argv[0]);
+        cmd.args(&argv[1..]);
+        cmd.cwd(&cwd);
+        if let Some(env) = env {
+            cmd.env_clear();
+            for (key, value) in env {
+                cmd.env(key, value);
+            }
+        }
+        cmd.env("TERM", "xterm-256color");
+        cmd.env("COLUMNS", cols.to_string());
+        cmd.env("LINES", rows.to_string());
+
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| format!("Failed to spawn '{}': {}", argv[0], e))?;
+        // Only the child holds the slave side, so reads see EOF when it exits.
+        drop(pair.slave);
+
+        let reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| format!("Failed to read from PTY: {}", e))?;
+        let writer = pair
+            .master
+            .take_writer()
+            .map_err(|e| format!("Failed to write to PTY: {}", e))?;
+
+        let shared = Arc::new(Mutex::new(Shared {
+            parser: vt100::Parser::new_with_callbacks(
+                rows,
+                cols,
+                SCROLLBACK_LINES,
+                Responder::default(),
+            ),
+            transcript: String::new(),
+            mark: 0,
+            last_output: Instant::now(),
+            eof: false,
+        }));
+        let notify = Arc::new(Notify::new());
+        let writer = Arc::new(Mutex::new(writer));
+
+        spawn_reader(reader, shared.clone(), notify.clone(), writer.clone());
+
+        Ok(Self {
+            id,
+            name,
+            cwd,
+            command: shlex::try_join(argv.iter().map(String::as_str))
+                .unwrap_or_else(|_| argv.join(" ")),
+            created_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_millis() as u64,
+            process: Mutex::new(Process {
+                child,
+                running: true,
+                exit_code: None,
+            }),
+            shared,
+            notify,
+            writer,
+            master: Mutex::new(pair.master),
+        })
+    }
+
+    /// Send keystrokes in [`encode_keys`] notation.
+    ///
+    /// Output received before the keystrokes no longer counts for
+    /// [`WaitFor::Pattern`].
+    pub fn send_keys(&self, keys: &str) -> Result<(), String> {
+        let bytes = {
+            let mut shared = lock(&self.shared);
+            shared.mark = shared.transcript.len();
+            encode_keys(keys, shared.parser.screen().application_cursor())
+        };
+        self.send_bytes(&bytes)
+    }
+
+    /// Send raw bytes.
+    pub fn send_bytes(&self, bytes: &[u8]) -> Result<(), String> {
+        if !self.check_status() {
+            return Err("Terminal not running".to_string());
+        }
+        let mut writer = lock(&self.writer);
+        writer
+            .write_all(bytes)
+            .and_then(|()| writer.flush())
+            .map_err(|e| format!("Failed to write to terminal: {}", e))
+    }
+
+    /// Wait until `condition` holds, the program exits or `timeout` elapses.
+    ///
+    /// A matched pattern consumes the output up to the end of the match, so
+    /// waiting for the same prompt twice waits for it to appear again.
+    pub async fn wait(&self, condition: &WaitFor, timeout: Duration) -> WaitOutcome {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            let wake = {
+                let mut shared = lock(&self.shared);
+                let wake = match condition {
+                    WaitFor::Pattern(pattern) => {
+                        let mark = shared.mark;
+                        if let Some(found) = pattern.find(&shared.transcript[mark..]) {
+                            let text = found.as_str().to_string();
+                            shared.mark = mark + found.end();
+                            return WaitOutcome::Matched { text };
+                        }
+                        deadline
+                    }
+                    WaitFor::Idle(quiet) => {
+                        let idle_at = shared.last_output + *quiet;
+                        if Instant::now() >= idle_at {
+                            return WaitOutcome::Idle;
+                        }
+                        idle_at.min(deadline)
+                    }
+                };
+                if shared.eof {
+                    return WaitOutcome::Exited;
+                }
+                wake
+            };
+            if Instant::now() >= deadline {
+                return WaitOutcome::TimedOut;
+            }
+
+            tokio::select! {
+                _ = &mut notified => {}
+                _ = tokio::time::sleep_until(tokio::time::Instant::from_std(wake)) => {}
+            }
+        }
+    }
+
+    /// The rendered screen.
+    pub fn snapshot(&self) -> ScreenSnapshot {
+        let shared = lock(&self.shared);
+        let screen = shared.parser.screen();
+        let (rows, cols) = screen.size();
+        ScreenSnapshot {
+            rows,
+            cols,
+            cursor: screen.cursor_position(),
+            cursor_hidden: screen.hide_cursor(),
+            title: shared.parser.callbacks().title.clone(),
+            alternate_screen: screen.alternate_screen(),
+            lines: screen
+                .rows(0, cols)
+                .map(|line| line.trim_end().to_string())
+                .collect(),
+        }
+    }
+
+    /// The last `tail` lines of scrollback and screen, oldest first.
+    pub fn history(&self, tail: usize) -> Vec<String> {
+        let mut shared = lock(&self.shared);
+        let screen = shared.parser.screen_mut();
+        let (rows, cols) = screen.size();
+
+        // The emulator only exposes scrollback a screenful at a time: scrolled
+        // up by `offset` lines, the top row is the `offset`-th line from the
+        // bottom of the scrollback.
+        screen.set_scrollback(usize::MAX);
+        let mut offset = screen.scrollback();
+        let mut lines = Vec::with_capacity(offset + rows as usize);
+        while offset > 0 {
+            screen.set_scrollback(offset);
+            let page = offset.min(rows as usize);
+            lines.extend(screen.rows(0, cols).take(page));
+            offset -= page;
+        }
+        screen.set_scrollback(0);
+        lines.extend(screen.rows(0, cols));
+
+        let mut lines: Vec<String> = lines
+            .into_iter()
+            .map(|line| line.trim_end().to_string())
+            .collect();
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+        let start = lines.len().saturating_sub(tail);
+        lines.split_off(start)
+    }
+
+    /// Resize the terminal and its emulator.
+    pub fn resize(&self, cols: u16, rows: u16) -> Result<(), String> {
+        lock(&self.master)
+            .resize(PtySize {
+                rows,
+                cols,
+                pixel_width: 0,
+                pixel_height: 0,
+            })
+            .map_err(|e| format!("Failed to resize terminal: {}", e))?;
+        lock(&self.shared).parser.screen_mut().set_size(rows, cols);
+        Ok(())
+    }
+
+    /// Kill the program.
+    pub fn kill(&self) -> Result<(), String> {
+        let mut process = lock(&self.process);
+        if !process.check_status() {
+            return Ok(());
+        }
+        process
+            .child
+            .kill()
+            .map_err(|e| format!("Failed to kill terminal: {}", e))?;
+        let status = process
+            .child
+            .wait()
+            .map_err(|e| format!("Failed to wait for terminal: {}", e))?;
+        process.exit_code = Some(status.exit_code() as i32);
+        process.running = false;
+        Ok(())
+    }
+
+    /// Check if the program is still running.
+    pub fn check_status(&self) -> bool {
+        lock(&self.process).check_status()
+    }
+
+    /// Exit code, once the program has exited.
+    pub fn exit_code(&self) -> Option<i32> {
+        let mut process = lock(&self.process);
+        process.check_status();
+        process.exit_code
+    }
+}
+
+impl Drop for PtyTerminal {
+    fn drop(&mut self) {
+        let _ = self.kill();
+    }
+}
+
+/// Feed PTY output to the emulator until the program closes the terminal.
+fn spawn_reader(
+    mut reader: Box<dyn Read + Send>,
+    shared: Arc<Mutex<Shared>>,
+    notify: Arc<Notify>,
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+) {
+    thread::spawn(move || {
+        let mut buf = [0u8; 8192];
+        loop {
+            let n = match reader.read(&mut buf) {
+                Ok(0) | Err(_) => break,
+                Ok(n) => n,
+            };
+            let replies = {
+                let mut shared = lock(&shared);
+                shared.parser.process(&buf[..n]);
+                shared.append_transcript(&buf[..n]);
+                shared.last_output = Instant::now();
+                std::mem::take(&mut shared.parser.callbacks_mut().replies)
+            };
+            if !replies.is_empty() {
+                let mut writer = lock(&writer);
+                let _ = writer.write_all(&replies).and_then(|()| writer.flush());
+            }
+            notify.notify_waiters();
+        }
+        lock(&shared).eof = true;
+        notify.notify_waiters();
+    });
+}
+
+/// Lock a mutex, recovering the data if a holder panicked.
+fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn spawn(command: &[&str]) -> PtyTerminal {
+        let command: Vec<String> = command.iter().map(|s| s.to_string()).collect();
+        PtyTerminal::spawn(
+            "t1".to_string(),
+            "test".to_string(),
+            std::env::temp_dir().display().to_string(),
+            &command,
+            None,
+            80,
+            24,
+        )
+        .expect("spawn pty")
+    }
+
+    fn pattern(p: &str) -> WaitFor {
+        WaitFor::Pattern(Regex::new(p).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_drives_interactive_program() {
+        let term = spawn(&[
+            "sh",
+            "-c",
+            "printf 'name? '; read name; echo \"hi $name\"; sleep 5",
+        ]);
+        let timeout = Duration::from_secs(10);
+
+        assert_eq!(
+            term.wait(&pattern(r"name\? $"), timeout).await,
+            WaitOutcome::Matched {
+                text: "name? ".to_string()
+            }
+        );
+        term.send_keys("cortex<Enter>").unwrap();
+        assert_eq!(
+            term.wait(&pattern(r"hi \w+"), timeout).await,
+            WaitOutcome::Matched {
+                text: "hi cortex".to_string()
+            }
+        );
+
+        let snapshot = term.snapshot();
+        assert_eq!((snapshot.rows, snapshot.cols), (24, 80));
+        assert_eq!(snapshot.lines[0], "name? cortex");
+        assert_eq!(snapshot.text(), "name? cortex\nhi cortex");
+
+        term.kill().unwrap();
+        assert!(!term.check_status());
+        assert!(term.exit_code().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_wait_reports_exit_and_timeout() {
+        let term = spawn(&["sh", "-c", "echo done"]);
+        let outcome = term
+            .wait(&pattern("never printed"), Duration::from_secs(10))
+            .await;
+        assert_eq!(outcome, WaitOutcome::Exited);
+        assert_eq!(term.history(10), vec!["done".to_string()]);
+
+        let term = spawn(&["sh", "-c", "sleep 5"]);
+        let outcome = term
+            .wait(&pattern("never printed"), Duration::from_millis(100))
+            .await;
+        assert_eq!(outcome, WaitOutcome::TimedOut);
+        let outcome = term
+            .wait(
+                &WaitFor::Idle(Duration::from_millis(50)),
+                Duration::from_secs(10),
+            )
+            .await;
+        assert_eq!(outcome, WaitOutcome::Idle);
+    }
+
+    #[test]
+    fn test_responder_answers_cursor_queries() {
+        let mut parser = vt100::Parser::new_with_callbacks(24, 80, 0, Responder::default());
+        parser.process(b"ab\x1b[6n\x1b]2;my title\x07\x1b[c");
+        assert_eq!(parser.callbacks().replies, b"\x1b[1;3R\x1b[?1;2c");
+        assert_eq!(parser.callbacks().title.as_deref(), Some("my title"));
+    }
+}
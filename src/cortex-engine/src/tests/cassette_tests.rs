@@ -0,0 +1,184 @@
+//! End-to-end agent turns recorded and replayed through `CassetteClient`.
+
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use cortex_protocol::{EventMsg, Op, Submission, UserInput};
+
+use crate::client::{
+    CassetteClient, CompletionRequest, CompletionResponse, FinishReason, MessageRole,
+    ModelCapabilities, ModelClient, ResponseEvent, ResponseStream, ToolCallEvent,
+};
+use crate::config::Config;
+use crate::session::Session;
+
+/// Model that reads `notes.txt`, then repeats what the tool returned.
+struct ScriptedClient {
+    capabilities: ModelCapabilities,
+}
+
+#[async_trait]
+impl ModelClient for ScriptedClient {
+    fn model(&self) -> &str {
+        "scripted-model"
+    }
+
+    fn provider(&self) -> &str {
+        "scripted"
+    }
+
+    fn capabilities(&self) -> &ModelCapabilities {
+        &self.capabilities
+    }
+
+    async fn complete(&self, request: CompletionRequest) -> crate::error::Result<ResponseStream> {
+        let last = request.messages.last().unwrap();
+
+        let events = if last.role == MessageRole::Tool {
+            let output = last.content.as_text().unwrap_or_default();
+            let answer = if output.contains("remember the milk") {
+                "The notes say to remember the milk."
+            } else {
+                "I could not read the notes."
+            };
+            vec![
+                ResponseEvent::Delta(answer.to_string()),
+                ResponseEvent::Done(CompletionResponse::default()),
+            ]
+        } else {
+            vec![
+                ResponseEvent::ToolCall(ToolCallEvent {
+                    id: "call_read".to_string(),
+                    name: "Read".to_string(),
+                    arguments: r#"{"file_path":"notes.txt"}"#.to_string(),
+                }),
+                ResponseEvent::Done(CompletionResponse {
+                    finish_reason: FinishReason::ToolCalls,
+                    ..Default::default()
+                }),
+            ]
+        };
+        Ok(Box::pin(futures::stream::iter(events.into_iter().map(Ok))))
+    }
+
+    async fn complete_sync(
+        &self,
+        _request: CompletionRequest,
+    ) -> crate::error::Result<CompletionResponse> {
+        unimplemented!()
+    }
+}
+
+/// Run one user turn and return the agent's final message or error.
+async fn run_turn(workdir: PathBuf, client: Box<dyn ModelClient>) -> Result<String, String> {
+    let config = Config {
+        model: "scripted-model".to_string(),
+        cortex_home: workdir.join(".cortex"),
+        cwd: workdir,
+        ..Config::default()
+    };
+    let (mut session, handle) = Session::with_client(config, client).unwrap();
+    let task = tokio::spawn(async move { session.run().await });
+
+    handle
+        .submission_tx
+        .send(Submission {
+            id: "1".to_string(),
+            op: Op::UserInput {
+                items: vec![UserInput::Text {
+                    text: "What do my notes say?".to_string(),
+                }],
+            },
+        })
+        .await
+        .unwrap();
+
+    let mut outcome = Err("agent sent no message".to_string());
+    loop {
+        match handle.event_rx.recv().await.unwrap().msg {
+            EventMsg::AgentMessage(event) => outcome = Ok(event.message),
+            EventMsg::Error(event) => outcome = Err(event.message),
+            EventMsg::TaskComplete(_) => break,
+            _ => {}
+        }
+    }
+
+    handle
+        .submission_tx
+        .send(Submission {
+            id: "2".to_string(),
+            op: Op::Shutdown,
+        })
+        .await
+        .unwrap();
+    task.await.unwrap().unwrap();
+
+    outcome
+}
+
+fn workdir(notes: &str) -> tempfile::TempDir {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("notes.txt"), notes).unwrap();
+    dir
+}
+
+#[tokio::test]
+async fn test_agent_turn_replays_offline() {
+    let cassettes = tempfile::tempdir().unwrap();
+    let cassette = cassettes.path().join("read_notes.json");
+
+    // Record against the scripted model
+    let recording = workdir("remember the milk\n");
+    let client = CassetteClient::record(
+        Box::new(ScriptedClient {
+            capabilities: ModelCapabilities::default(),
+        }),
+        &cassette,
+    )
+    .redact(recording.path().display().to_string(), "<workdir>");
+    let recorded = run_turn(recording.path().to_path_buf(), Box::new(client))
+        .await
+        .unwrap();
+    assert_eq!(recorded, "The notes say to remember the milk.");
+
+    // Replay in another directory, with no model at all
+    let replaying = workdir("remember the milk\n");
+    let client = CassetteClient::replay(&cassette)
+        .unwrap()
+        .redact(replaying.path().display().to_string(), "<workdir>");
+    let replayed = run_turn(replaying.path().to_path_buf(), Box::new(client))
+        .await
+        .unwrap();
+    assert_eq!(replayed, recorded);
+}
+
+#[tokio::test]
+async fn test_agent_turn_mismatch_is_reported() {
+    let cassettes = tempfile::tempdir().unwrap();
+    let cassette = cassettes.path().join("read_notes.json");
+
+    let recording = workdir("remember the milk\n");
+    let client = CassetteClient::record(
+        Box::new(ScriptedClient {
+            capabilities: ModelCapabilities::default(),
+        }),
+        &cassette,
+    );
+    run_turn(recording.path().to_path_buf(), Box::new(client))
+        .await
+        .unwrap();
+
+    // The tool now returns something else, so the second request differs
+    let replaying = workdir("buy bread\n");
+    let client = CassetteClient::replay(&cassette).unwrap();
+    let error = run_turn(replaying.path().to_path_buf(), Box::new(client))
+        .await
+        .unwrap_err();
+    assert!(error.contains("recording #2"), "{error}");
+    assert!(
+        error
+            .lines()
+            .any(|line| line.starts_with('+') && line.contains("buy bread")),
+        "{error}"
+    );
+}
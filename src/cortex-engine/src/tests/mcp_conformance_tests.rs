@@ -0,0 +1,188 @@
+//! MCP conformance tests.
+//!
+//! Runs the same scenarios against an in-process `cortex-mcp-server` over
+//! each transport the engine supports.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use cortex_mcp_client::{StreamTransport, StreamableHttpTransport, Transport};
+use cortex_mcp_server::{FnToolHandler, McpServer, McpServerBuilder, StaticPromptProvider};
+use cortex_mcp_server::{StaticResourceProvider, ToolHandler};
+use cortex_mcp_types::{
+    CallToolResult, Content, GetPromptParams, GetPromptResult, InitializeParams, Prompt,
+    PromptMessage, Resource, Tool,
+};
+use serde_json::json;
+
+use crate::mcp::{ConnectionState, McpConnectionManager, McpServerConfig};
+
+/// Build the server every scenario runs against.
+async fn conformance_server() -> Arc<McpServer> {
+    let mut resources = StaticResourceProvider::new();
+    resources.add_text(Resource::new("file:///notes.txt", "Notes"), "remember");
+
+    let mut prompts = StaticPromptProvider::new();
+    prompts.add(Prompt::new("greet"), |arguments| {
+        let name = arguments
+            .and_then(|a| a.get("name").cloned())
+            .unwrap_or_default();
+        GetPromptResult::new(vec![PromptMessage::user(Content::text(format!(
+            "Hello, {name}"
+        )))])
+    });
+
+    let server = McpServerBuilder::new("conformance", "1.0.0")
+        .with_tools_capability()
+        .resource_provider(Arc::new(resources))
+        .prompt_provider(Arc::new(prompts))
+        .build()
+        .unwrap();
+
+    let echo: Arc<dyn ToolHandler> = Arc::new(FnToolHandler::new(
+        Tool::new("echo", "Echo the message"),
+        |args| {
+            let message = args.get("message").and_then(|v| v.as_str()).unwrap_or("");
+            Ok(CallToolResult::text(message))
+        },
+    ));
+    let fail: Arc<dyn ToolHandler> = Arc::new(FnToolHandler::new(
+        Tool::new("fail", "Always fails"),
+        |_| Err(anyhow!("tool exploded")),
+    ));
+    server.register_tools(vec![echo, fail]).await;
+
+    server
+}
+
+/// Serve the conformance server over an in-memory pipe.
+async fn in_memory_transport() -> Arc<dyn Transport> {
+    let server = conformance_server().await;
+    let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+    let (server_read, server_write) = tokio::io::split(server_io);
+    tokio::spawn(server.serve(server_read, server_write));
+
+    let (client_read, client_write) = tokio::io::split(client_io);
+    Arc::new(StreamTransport::new(client_read, client_write))
+}
+
+/// Serve the conformance server over HTTP on a local port.
+async fn http_url() -> String {
+    let server = conformance_server().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/", listener.local_addr().unwrap());
+    tokio::spawn(server.serve_http(listener));
+    url
+}
+
+/// Scenarios exercised through the connection manager.
+async fn check_manager(manager: &McpConnectionManager) {
+    manager.connect("conf").await.unwrap();
+    assert_eq!(
+        manager.status().await.get("conf"),
+        Some(&ConnectionState::Connected)
+    );
+
+    // Tools are discovered on connect
+    let tools = manager.list_all_tools().await;
+    assert!(tools.contains_key("mcp__conf__echo"));
+    assert!(tools.contains_key("mcp__conf__fail"));
+
+    let result = manager
+        .call_tool("mcp__conf__echo", Some(json!({"message": "hi"})))
+        .await
+        .unwrap();
+    assert!(!result.is_error());
+    assert_eq!(result.content[0].as_text(), Some("hi"));
+
+    // Tool failures are results, not protocol errors
+    let result = manager.call_tool("mcp__conf__fail", None).await.unwrap();
+    assert!(result.is_error());
+
+    let error = manager
+        .call_tool("mcp__conf__missing", None)
+        .await
+        .unwrap_err();
+    assert!(format!("{error:#}").contains("Unknown tool"), "{error:#}");
+
+    // Resources
+    let resources = manager.list_all_resources().await;
+    assert!(resources.contains_key("conf://file:///notes.txt"));
+
+    let contents = manager
+        .read_resource("conf://file:///notes.txt")
+        .await
+        .unwrap();
+    assert_eq!(contents.contents[0].text.as_deref(), Some("remember"));
+    assert!(manager.read_resource("conf://file:///nope").await.is_err());
+
+    manager.disconnect("conf").await.unwrap();
+    assert_eq!(
+        manager.status().await.get("conf"),
+        Some(&ConnectionState::Disconnected)
+    );
+}
+
+/// Scenarios only reachable through the transport itself.
+async fn check_transport(transport: &dyn Transport) {
+    let info = transport
+        .initialize(InitializeParams::default())
+        .await
+        .unwrap();
+    assert_eq!(info.server_info.name, "conformance");
+    transport.send_initialized().await.unwrap();
+
+    transport.ping().await.unwrap();
+
+    let prompts = transport.list_prompts().await.unwrap();
+    assert_eq!(prompts.prompts[0].name, "greet");
+
+    let prompt = transport
+        .get_prompt(GetPromptParams {
+            name: "greet".to_string(),
+            arguments: Some(HashMap::from([("name".to_string(), "Ada".to_string())])),
+        })
+        .await
+        .unwrap();
+    assert_eq!(prompt.messages.len(), 1);
+
+    let error = transport.request("no/such/method", None).await.unwrap_err();
+    assert!(error.to_string().contains("MCP error"), "{error:#}");
+
+    transport.close().await.unwrap();
+}
+
+#[tokio::test]
+async fn test_manager_in_memory() {
+    let manager = McpConnectionManager::new();
+    manager
+        .add_server_with_transport(
+            McpServerConfig::new("conf", "unused"),
+            in_memory_transport().await,
+        )
+        .await;
+
+    check_manager(&manager).await;
+}
+
+#[tokio::test]
+async fn test_manager_streamable_http() {
+    let manager = McpConnectionManager::new();
+    manager
+        .add_server(McpServerConfig::new_http("conf", http_url().await))
+        .await;
+
+    check_manager(&manager).await;
+}
+
+#[tokio::test]
+async fn test_transport_in_memory() {
+    check_transport(in_memory_transport().await.as_ref()).await;
+}
+
+#[tokio::test]
+async fn test_transport_streamable_http() {
+    let url = url::Url::parse(&http_url().await).unwrap();
+    check_transport(&StreamableHttpTransport::new(url)).await;
+}
@@ -1,8 +1,10 @@
 //! Comprehensive tests for cortex-core modules.
 
+mod cassette_tests;
 mod diff_tests;
 mod error_tests;
 mod json_utils_tests;
+mod mcp_conformance_tests;
 mod session_lifecycle_tests;
 mod shell_tests;
 mod text_utils_tests;
@@ -2,8 +2,12 @@ use crate::client::{
     CompletionRequest, CompletionResponse, ModelCapabilities, ModelClient, ResponseEvent,
     ResponseStream,
 };
+use crate::config::{Config, ConfigOverrides};
 use crate::error::Result;
+use crate::session::Session;
 use async_trait::async_trait;
+use cortex_network_proxy::NetworkProxyConfig;
+use cortex_protocol::EventMsg;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::Duration;
@@ -53,3 +57,67 @@ async fn test_session_basic_setup() -> Result<()> {
     // We'll skip complex mocking for now as Session::new is tightly coupled to actual providers
     Ok(())
 }
+
+#[tokio::test]
+async fn test_session_fails_closed_when_proxy_cannot_start() {
+    let dir = tempfile::tempdir().unwrap();
+    let config = Config {
+        cortex_home: dir.path().join(".cortex"),
+        cwd: dir.path().to_path_buf(),
+        network_proxy: Some(NetworkProxyConfig {
+            enabled: true,
+            allowed_domains: vec!["*.".to_string()],
+            ..Default::default()
+        }),
+        ..Config::default()
+    };
+    let client = MockModelClient {
+        should_hang: Arc::new(AtomicBool::new(false)),
+        capabilities: ModelCapabilities::default(),
+    };
+    let (mut session, handle) = Session::with_client(config, Box::new(client)).unwrap();
+
+    assert!(session.run().await.is_err());
+    let event = handle.event_rx.recv().await.unwrap();
+    assert!(
+        matches!(&event.msg, EventMsg::Error(e) if e.message.contains("network proxy")),
+        "{:?}",
+        event.msg
+    );
+}
+
+#[tokio::test]
+async fn test_network_proxy_from_config_toml_routes_tools() {
+    let dir = tempfile::tempdir().unwrap();
+    let cortex_home = dir.path().join(".cortex");
+    std::fs::create_dir_all(&cortex_home).unwrap();
+    std::fs::write(
+        cortex_home.join("config.toml"),
+        r#"
+[network_proxy]
+enabled = true
+allowed_domains = ["example.com"]
+"#,
+    )
+    .unwrap();
+
+    let config = Config::load_with_home(
+        cortex_home,
+        ConfigOverrides {
+            cwd: Some(dir.path().to_path_buf()),
+            ..Default::default()
+        },
+    )
+    .await
+    .unwrap();
+    let client = MockModelClient {
+        should_hang: Arc::new(AtomicBool::new(false)),
+        capabilities: ModelCapabilities::default(),
+    };
+    let (mut session, _handle) = Session::with_client(config, Box::new(client)).unwrap();
+    assert!(session.network_env().is_empty());
+
+    session.start_network_proxy().await.unwrap();
+    let env = session.network_env();
+    assert!(env.iter().any(|(key, _)| key == "HTTP_PROXY"), "{env:?}");
+}
@@ -1,24 +1,56 @@
 //! Tokenization utilities.
 //!
 //! Provides token counting and text tokenization for various models.
+//!
+//! Counting uses the BPE vocabularies bundled with `tiktoken-rs`, so it works
+//! offline. Claude's tokenizer is not public; its counts are approximated from
+//! cl100k_base.
 
 use std::collections::HashMap;
 
+use base64::Engine as _;
 use serde::{Deserialize, Serialize};
+use tiktoken_rs::CoreBPE;
+
+use crate::client::types::{ContentPart, Message, MessageContent, ToolDefinition};
+use crate::model_family::ModelFamily;
+
+/// Tokens added per message for role and delimiters.
+const MESSAGE_OVERHEAD: u32 = 3;
+
+/// Tokens that prime the assistant reply.
+const REPLY_PRIMING: u32 = 3;
+
+/// Tokens added per tool call for its id and framing.
+const TOOL_CALL_OVERHEAD: u32 = 8;
+
+/// Tokens added per tool definition.
+const TOOL_DEFINITION_OVERHEAD: u32 = 8;
+
+/// Size assumed for images that are not inlined (remote URLs).
+const DEFAULT_IMAGE_SIZE: (u32, u32) = (1024, 1024);
+
+/// Tokens per PDF page (text plus the rendered page image).
+const PDF_PAGE_TOKENS: u32 = 1_500;
+
+/// Claude produces roughly 10% more tokens than cl100k_base.
+const CLAUDE_SCALE: (u32, u32) = (11, 10);
 
 /// Tokenizer type.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 #[derive(Default)]
 pub enum TokenizerType {
     /// OpenAI tokenizer (cl100k_base).
     #[default]
     Cl100kBase,
-    /// GPT-2 tokenizer.
+    /// OpenAI tokenizer (o200k_base), used by GPT-4o and later.
+    O200kBase,
+    /// GPT-2 tokenizer (r50k_base).
     Gpt2,
-    /// Claude tokenizer.
+    /// Claude approximation, scaled from cl100k_base.
     Claude,
-    /// Llama tokenizer.
+    /// Llama tokenizer, approximated with cl100k_base.
     Llama,
     /// Simple word-based approximation.
     Simple,
@@ -26,19 +58,50 @@ pub enum TokenizerType {
 
 impl TokenizerType {
     /// Get tokenizer for model.
+    ///
+    /// Aliases are resolved through the model presets, then the tokenizer is
+    /// picked from the model family, falling back to the preset's provider.
     pub fn for_model(model: &str) -> Self {
-        let model_lower = model.to_lowercase();
-
-        if model_lower.contains("gpt-4") || model_lower.contains("gpt-3.5") {
-            Self::Cl100kBase
-        } else if model_lower.contains("claude") {
-            Self::Claude
-        } else if model_lower.contains("llama") {
-            Self::Llama
-        } else if model_lower.contains("gpt2") {
-            Self::Gpt2
-        } else {
-            Self::Simple
+        let resolved = cortex_common::resolve_model_alias(model);
+        let (prefix, name) = match resolved.split_once('/') {
+            Some((prefix, name)) => (Some(prefix), name),
+            None => (None, resolved),
+        };
+        let name_lower = name.to_lowercase();
+
+        if name_lower.contains("gpt2") {
+            return Self::Gpt2;
+        }
+        if ["gpt-4.1", "gpt-4.5", "gpt-5", "o4-"]
+            .iter()
+            .any(|p| name_lower.contains(p))
+        {
+            return Self::O200kBase;
+        }
+        if name_lower.contains("claude") {
+            return Self::Claude;
+        }
+
+        match ModelFamily::from_model_name(name) {
+            ModelFamily::Gpt4o | ModelFamily::O1 | ModelFamily::O3 => return Self::O200kBase,
+            ModelFamily::Gpt4 | ModelFamily::Gpt4Turbo | ModelFamily::Gpt35 => {
+                return Self::Cl100kBase;
+            }
+            family if family.provider() == "anthropic" => return Self::Claude,
+            family if family.provider() == "meta" => return Self::Llama,
+            _ => {}
+        }
+        if name_lower.contains("llama") {
+            return Self::Llama;
+        }
+
+        let provider = cortex_common::get_model_preset(name)
+            .map(|preset| preset.provider)
+            .or(prefix);
+        match provider {
+            Some("anthropic") => Self::Claude,
+            Some("openai") => Self::O200kBase,
+            _ => Self::Cl100kBase,
         }
     }
 
@@ -46,12 +109,60 @@ impl TokenizerType {
     pub fn chars_per_token(&self) -> f32 {
         match self {
             Self::Cl100kBase => 4.0,
+            Self::O200kBase => 4.2,
             Self::Gpt2 => 4.0,
             Self::Claude => 3.5,
             Self::Llama => 3.8,
             Self::Simple => 4.0,
         }
     }
+
+    /// Bundled BPE vocabulary for this tokenizer, if it uses one.
+    fn bpe(&self) -> Option<&'static CoreBPE> {
+        match self {
+            Self::Cl100kBase | Self::Claude | Self::Llama => {
+                Some(tiktoken_rs::cl100k_base_singleton())
+            }
+            Self::O200kBase => Some(tiktoken_rs::o200k_base_singleton()),
+            Self::Gpt2 => Some(tiktoken_rs::r50k_base_singleton()),
+            Self::Simple => None,
+        }
+    }
+
+    /// Tokens for an image of the given pixel size.
+    ///
+    /// Claude bills `width * height / 750` after downscaling; OpenAI-style
+    /// models bill 170 per 512px tile plus 85, or a flat 85 at low detail.
+    pub fn image_tokens(&self, width: u32, height: u32, detail: Option<&str>) -> u32 {
+        let (width, height) = (width.max(1) as f64, height.max(1) as f64);
+
+        if *self == Self::Claude {
+            let scale = (1568.0 / width.max(height)).min(1.0);
+            let tokens = (width * scale) * (height * scale) / 750.0;
+            return (tokens.ceil() as u32).clamp(1, 1600);
+        }
+
+        if detail == Some("low") {
+            return 85;
+        }
+        let fit = (2048.0 / width.max(height)).min(1.0);
+        let (width, height) = (width * fit, height * fit);
+        let shortest = (768.0 / width.min(height)).min(1.0);
+        let (width, height) = (width * shortest, height * shortest);
+        let tiles = (width / 512.0).ceil() * (height / 512.0).ceil();
+        85 + 170 * tiles as u32
+    }
+
+    /// Fixed tokens added to a request that declares tools.
+    ///
+    /// Anthropic injects a tool-use system prompt; OpenAI-style models wrap
+    /// the definitions in a short namespace header.
+    pub fn tools_overhead(&self) -> u32 {
+        match self {
+            Self::Claude => 346,
+            _ => 12,
+        }
+    }
 }
 
 /// Token counter.
@@ -76,6 +187,11 @@ impl TokenCounter {
         Self::new(TokenizerType::for_model(model))
     }
 
+    /// Get the tokenizer type.
+    pub fn tokenizer(&self) -> TokenizerType {
+        self.tokenizer
+    }
+
     /// Count tokens in text.
     pub fn count(&mut self, text: &str) -> u32 {
         let hash = hash_text(text);
@@ -91,9 +207,15 @@ impl TokenCounter {
 
     /// Count tokens without caching.
     fn count_uncached(&self, text: &str) -> u32 {
-        match self.tokenizer {
-            TokenizerType::Simple => self.count_simple(text),
-            _ => self.count_approximate(text),
+        let Some(bpe) = self.tokenizer.bpe() else {
+            return self.count_simple(text);
+        };
+
+        let count = bpe.encode_ordinary(text).len() as u32;
+        if self.tokenizer == TokenizerType::Claude {
+            (count * CLAUDE_SCALE.0).div_ceil(CLAUDE_SCALE.1)
+        } else {
+            count
         }
     }
 
@@ -124,12 +246,6 @@ impl TokenCounter {
         count
     }
 
-    /// Approximate token count based on characters.
-    fn count_approximate(&self, text: &str) -> u32 {
-        let chars = text.len() as f32;
-        (chars / self.tokenizer.chars_per_token()).ceil() as u32
-    }
-
     /// Count tokens in messages.
     pub fn count_messages(&mut self, messages: &[ChatMessage]) -> u32 {
         let mut total = 0u32;
@@ -152,6 +268,102 @@ impl TokenCounter {
         total
     }
 
+    /// Count tokens in a model request's messages, including images and tool calls.
+    pub fn count_conversation(&mut self, messages: &[Message]) -> u32 {
+        if messages.is_empty() {
+            return 0;
+        }
+        messages
+            .iter()
+            .map(|message| self.count_message(message))
+            .sum::<u32>()
+            + REPLY_PRIMING
+    }
+
+    /// Count tokens in a single model message.
+    pub fn count_message(&mut self, message: &Message) -> u32 {
+        let mut total = MESSAGE_OVERHEAD;
+
+        total += match &message.content {
+            MessageContent::Text(text) => self.count(text),
+            MessageContent::Parts(parts) => parts.iter().map(|part| self.count_part(part)).sum(),
+            MessageContent::ToolResult { content, .. } => self.count(content),
+            MessageContent::ToolCalls(calls) => calls
+                .iter()
+                .map(|call| self.count_tool_call(&call.name, &call.arguments))
+                .sum(),
+        };
+
+        if let Some(calls) = &message.tool_calls {
+            for call in calls {
+                total += self.count_tool_call(&call.function.name, &call.function.arguments);
+            }
+        }
+        if message.tool_call_id.is_some() {
+            total += TOOL_CALL_OVERHEAD;
+        }
+
+        total
+    }
+
+    /// Count tokens in tool definitions sent with a request.
+    pub fn count_tools(&mut self, tools: &[ToolDefinition]) -> u32 {
+        if tools.is_empty() {
+            return 0;
+        }
+
+        let mut total = self.tokenizer.tools_overhead();
+        for tool in tools {
+            let schema = serde_json::to_string(&tool.function.parameters).unwrap_or_default();
+            total += TOOL_DEFINITION_OVERHEAD
+                + self.count(&tool.function.name)
+                + self.count(&tool.function.description)
+                + self.count(&schema);
+        }
+        total
+    }
+
+    /// Count tokens for an image, reading its size from inline data URLs.
+    pub fn count_image(&self, url: &str, detail: Option<&str>) -> u32 {
+        let (width, height) = inline_data(url)
+            .and_then(|data| imagesize::blob_size(&data).ok())
+            .map(|size| (size.width as u32, size.height as u32))
+            .unwrap_or(DEFAULT_IMAGE_SIZE);
+        self.tokenizer.image_tokens(width, height, detail)
+    }
+
+    /// Count tokens in one content part.
+    fn count_part(&mut self, part: &ContentPart) -> u32 {
+        match part {
+            ContentPart::Text { text, .. } => self.count(text),
+            ContentPart::ImageUrl { image_url } => {
+                self.count_image(&image_url.url, image_url.detail.as_deref())
+            }
+            ContentPart::Image { url, detail } => self.count_image(url, detail.as_deref()),
+            ContentPart::Document {
+                data,
+                mime_type,
+                name,
+            } => {
+                let name_tokens = name.as_deref().map_or(0, |name| self.count(name));
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(data)
+                    .unwrap_or_default();
+                let body = if mime_type.starts_with("text/") || mime_type.ends_with("json") {
+                    self.count(&String::from_utf8_lossy(&bytes))
+                } else {
+                    pdf_page_count(&bytes).max(1) * PDF_PAGE_TOKENS
+                };
+                name_tokens + body
+            }
+        }
+    }
+
+    /// Count a tool call's name and JSON arguments.
+    fn count_tool_call(&mut self, name: &str, arguments: &str) -> u32 {
+        TOOL_CALL_OVERHEAD + self.count(name) + self.count(arguments)
+    }
+
     /// Clear cache.
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -164,6 +376,33 @@ impl Default for TokenCounter {
     }
 }
 
+/// Count tokens in text with the default tokenizer, without caching.
+pub fn count_tokens(text: &str) -> u32 {
+    TokenCounter::default().count_uncached(text)
+}
+
+/// Decode the payload of a base64 `data:` URL.
+fn inline_data(url: &str) -> Option<Vec<u8>> {
+    let (header, data) = url.strip_prefix("data:")?.split_once(',')?;
+    if !header.ends_with(";base64") {
+        return None;
+    }
+    base64::engine::general_purpose::STANDARD.decode(data).ok()
+}
+
+/// Count pages in a PDF by its `/Type /Page` objects.
+fn pdf_page_count(bytes: &[u8]) -> u32 {
+    [&b"/Type /Page"[..], &b"/Type/Page"[..]]
+        .iter()
+        .map(|needle| {
+            bytes
+                .windows(needle.len() + 1)
+                .filter(|window| window.starts_with(needle) && window[needle.len()] != b's')
+                .count() as u32
+        })
+        .sum()
+}
+
 /// Chat message for token counting.
 #[derive(Debug, Clone)]
 pub struct ChatMessage {
@@ -468,6 +707,136 @@ mod tests {
         assert_eq!(TokenizerType::for_model("llama-3"), TokenizerType::Llama);
     }
 
+    #[test]
+    fn test_tokenizer_for_model_presets() {
+        assert_eq!(TokenizerType::for_model("gpt-4o"), TokenizerType::O200kBase);
+        assert_eq!(
+            TokenizerType::for_model("o1-mini"),
+            TokenizerType::O200kBase
+        );
+        assert_eq!(
+            TokenizerType::for_model("gpt-3.5-turbo"),
+            TokenizerType::Cl100kBase
+        );
+        // Aliases resolve to provider-prefixed ids
+        assert_eq!(TokenizerType::for_model("sonnet"), TokenizerType::Claude);
+        assert_eq!(TokenizerType::for_model("gpt4"), TokenizerType::O200kBase);
+        assert_eq!(
+            TokenizerType::for_model("anthropic/claude-opus-4.5"),
+            TokenizerType::Claude
+        );
+        assert_eq!(
+            TokenizerType::for_model("meta-llama/llama-3.1-70b"),
+            TokenizerType::Llama
+        );
+        assert_eq!(
+            TokenizerType::for_model("some-local-model"),
+            TokenizerType::Cl100kBase
+        );
+    }
+
+    #[test]
+    fn test_bpe_counts() {
+        let mut cl100k = TokenCounter::new(TokenizerType::Cl100kBase);
+        let mut o200k = TokenCounter::new(TokenizerType::O200kBase);
+        let mut claude = TokenCounter::new(TokenizerType::Claude);
+
+        assert_eq!(cl100k.count("Hello world"), 2);
+        assert_eq!(cl100k.count(""), 0);
+
+        // o200k has far better coverage of non-Latin scripts
+        let text = "这是一个关于分词器的测试。";
+        assert!(o200k.count(text) < cl100k.count(text));
+
+        // Special tokens are counted as ordinary text
+        assert!(cl100k.count("<|endoftext|>") > 1);
+
+        let code = "fn main() {\n    println!(\"{}\", 42);\n}\n";
+        assert!(claude.count(code) > cl100k.count(code));
+    }
+
+    #[test]
+    fn test_image_tokens() {
+        let openai = TokenizerType::O200kBase;
+        assert_eq!(openai.image_tokens(1024, 1024, None), 765);
+        assert_eq!(openai.image_tokens(4096, 4096, Some("low")), 85);
+        assert_eq!(openai.image_tokens(2048, 4096, None), 1105);
+
+        let claude = TokenizerType::Claude;
+        assert_eq!(claude.image_tokens(1000, 1000, None), 1334);
+        assert_eq!(claude.image_tokens(8000, 8000, None), 1600);
+    }
+
+    #[test]
+    fn test_count_image_reads_inline_size() {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR".to_vec();
+        png.extend_from_slice(&200u32.to_be_bytes());
+        png.extend_from_slice(&100u32.to_be_bytes());
+        png.extend_from_slice(&[8, 6, 0, 0, 0, 0, 0, 0, 0]);
+        let url = format!(
+            "data:image/png;base64,{}",
+            base64::engine::general_purpose::STANDARD.encode(&png)
+        );
+
+        let counter = TokenCounter::new(TokenizerType::Claude);
+        assert_eq!(counter.count_image(&url, None), 27);
+        assert_eq!(
+            counter.count_image("https://example.com/cat.png", None),
+            TokenizerType::Claude.image_tokens(1024, 1024, None)
+        );
+    }
+
+    #[test]
+    fn test_count_conversation_and_tools() {
+        use crate::client::types::ImageUrl;
+
+        let mut counter = TokenCounter::new(TokenizerType::O200kBase);
+        let text_only = vec![Message::user("Describe this image")];
+        let with_image = vec![Message {
+            content: MessageContent::Parts(vec![
+                ContentPart::Text {
+                    text: "Describe this image".to_string(),
+                    cache_control: None,
+                },
+                ContentPart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: "https://example.com/cat.png".to_string(),
+                        detail: Some("low".to_string()),
+                    },
+                },
+            ]),
+            ..Message::user("")
+        }];
+        assert_eq!(
+            counter.count_conversation(&with_image),
+            counter.count_conversation(&text_only) + 85
+        );
+
+        assert_eq!(counter.count_tools(&[]), 0);
+        let small = [ToolDefinition::function(
+            "Read",
+            "Read a file",
+            serde_json::json!({}),
+        )];
+        let large = ToolDefinition::function(
+            "Read",
+            "Read a file",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "file_path": {"type": "string", "description": "Absolute path to read"},
+                    "offset": {"type": "integer", "description": "Line to start from"}
+                },
+                "required": ["file_path"]
+            }),
+        );
+        assert!(counter.count_tools(&[large]) > counter.count_tools(&small) + 20);
+        assert!(
+            TokenCounter::new(TokenizerType::Claude).count_tools(&small)
+                > counter.count_tools(&small) + 300
+        );
+    }
+
     #[test]
     fn test_token_counter_simple() {
         let mut counter = TokenCounter::new(TokenizerType::Simple);
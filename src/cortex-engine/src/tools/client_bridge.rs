@@ -0,0 +1,47 @@
+//! Client-side file and process access.
+//!
+//! Editors connected over ACP can serve file reads and writes from their open
+//! buffers and run commands in their own terminals. Tools look for a bridge on
+//! their [`ToolContext`](super::ToolContext) and fall back to local access when
+//! the client doesn't advertise the capability.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::error::Result;
+use crate::exec::ExecOutput;
+
+/// File and terminal access provided by the connected client.
+#[async_trait]
+pub trait ClientBridge: Send + Sync {
+    /// Whether the client serves file reads.
+    fn can_read_files(&self) -> bool;
+
+    /// Whether the client serves file writes.
+    fn can_write_files(&self) -> bool;
+
+    /// Whether the client runs commands in its terminals.
+    fn can_run_commands(&self) -> bool;
+
+    /// Read a text file, including unsaved changes in the editor.
+    async fn read_text_file(&self, path: &Path) -> Result<String>;
+
+    /// Write a text file through the editor.
+    async fn write_text_file(&self, path: &Path, content: &str) -> Result<()>;
+
+    /// Run a command in a client terminal and wait for it to finish.
+    ///
+    /// `call_id` is the tool call the terminal belongs to, so the client can
+    /// show its output inline. Output is returned in `stdout`/`aggregated`.
+    async fn run_command(
+        &self,
+        call_id: &str,
+        command: &[String],
+        cwd: &Path,
+        env: &HashMap<String, String>,
+        timeout: Duration,
+    ) -> Result<ExecOutput>;
+}
@@ -8,6 +8,7 @@ use cortex_common::normalize_path as normalize_path_util;
 use cortex_protocol::SandboxPolicy;
 use tokio::sync::mpsc;
 
+use super::client_bridge::ClientBridge;
 use crate::integrations::LspIntegration;
 
 /// Output chunk from tool execution
@@ -38,6 +39,8 @@ pub struct ToolContext {
     pub output_sender: Option<mpsc::Sender<(String, ToolOutputChunk)>>,
     /// LSP integration.
     pub lsp: Option<Arc<LspIntegration>>,
+    /// Client-side file and terminal access (ACP editors).
+    pub client_bridge: Option<Arc<dyn ClientBridge>>,
 }
 
 impl std::fmt::Debug for ToolContext {
@@ -51,6 +54,7 @@ impl std::fmt::Debug for ToolContext {
             .field("auto_approve", &self.auto_approve)
             .field("call_id", &self.call_id)
             .field("has_output_sender", &self.output_sender.is_some())
+            .field("has_client_bridge", &self.client_bridge.is_some())
             .finish()
     }
 }
@@ -85,6 +89,7 @@ impl ToolContext {
             call_id: String::new(),
             output_sender: None,
             lsp: None,
+            client_bridge: None,
         }
     }
 
@@ -94,6 +99,49 @@ impl ToolContext {
         self
     }
 
+    /// Notify the language server that a tool wrote a file.
+    ///
+    /// Every tool that writes file content calls this after a successful
+    /// write, so LSP diagnostics never describe stale content.
+    pub async fn notify_file_written(&self, path: &Path, content: &str) {
+        if let Some(lsp) = &self.lsp {
+            lsp.did_write(path, content).await;
+        }
+    }
+
+    /// Set client-side file and terminal access.
+    pub fn with_client_bridge(mut self, bridge: Option<Arc<dyn ClientBridge>>) -> Self {
+        self.client_bridge = bridge;
+        self
+    }
+
+    /// Client bridge, if the client serves file reads.
+    pub fn client_reader(&self) -> Option<&Arc<dyn ClientBridge>> {
+        self.client_bridge.as_ref().filter(|b| b.can_read_files())
+    }
+
+    /// Client bridge, if the client serves file writes.
+    pub fn client_writer(&self) -> Option<&Arc<dyn ClientBridge>> {
+        self.client_bridge.as_ref().filter(|b| b.can_write_files())
+    }
+
+    /// Client bridge, if the client runs commands.
+    ///
+    /// Client terminals run outside any sandbox, so they are only used when
+    /// the policy is `DangerFullAccess`.
+    pub fn client_terminal(&self) -> Option<&Arc<dyn ClientBridge>> {
+        if self.sandbox_policy != SandboxPolicy::DangerFullAccess {
+            return None;
+        }
+        self.client_bridge.as_ref().filter(|b| b.can_run_commands())
+    }
+
+    /// Add environment variables for processes tools start.
+    pub fn with_env(mut self, env: impl IntoIterator<Item = (String, String)>) -> Self {
+        self.env.extend(env);
+        self
+    }
+
     /// Set the sandbox policy.
     pub fn with_sandbox_policy(mut self, policy: SandboxPolicy) -> Self {
         self.sandbox_policy = policy;
@@ -40,7 +40,7 @@ static EDIT_LOCKS: Lazy<
 > = Lazy::new(|| Mutex::new(HashMap::new()));
 
 /// Get or create a lock for a specific file path.
-fn get_file_lock(path: &Path) -> std::sync::Arc<tokio::sync::Mutex<()>> {
+pub(crate) fn get_file_lock(path: &Path) -> std::sync::Arc<tokio::sync::Mutex<()>> {
     let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
     let mut locks = EDIT_LOCKS.lock().unwrap();
     locks
@@ -116,6 +116,40 @@ fn atomic_write_file(path: &Path, content: &str) -> std::io::Result<()> {
     Ok(())
 }
 
+/// Read a file for editing.
+///
+/// Goes through the client when it serves reads, so unsaved editor buffers
+/// are what gets edited.
+pub(crate) async fn read_file_content(
+    context: &ToolContext,
+    path: &Path,
+) -> std::result::Result<String, String> {
+    match context.client_reader() {
+        Some(client) => client.read_text_file(path).await.map_err(|e| e.to_string()),
+        None => fs::read_to_string(path).map_err(|e| e.to_string()),
+    }
+}
+
+/// Write edited content back.
+///
+/// Editors apply the write to their buffer; otherwise the file is replaced
+/// atomically (write to temp, then rename) so readers always see complete content.
+pub(crate) async fn write_file_content(
+    context: &ToolContext,
+    path: &Path,
+    content: &str,
+) -> std::result::Result<(), String> {
+    match context.client_writer() {
+        Some(client) => client
+            .write_text_file(path, content)
+            .await
+            .map_err(|e| e.to_string())?,
+        None => atomic_write_file(path, content).map_err(|e| e.to_string())?,
+    }
+    context.notify_file_written(path, content).await;
+    Ok(())
+}
+
 /// Handler for Patch tool with fuzzy matching.
 pub struct PatchHandler;
 
@@ -157,7 +191,7 @@ impl ToolHandler for PatchHandler {
             }
         };
 
-        if !path.exists() {
+        if context.client_reader().is_none() && !path.exists() {
             return Ok(ToolResult::error(format!(
                 "File not found: {}",
                 path.display()
@@ -169,7 +203,7 @@ impl ToolHandler for PatchHandler {
         let _guard = file_lock.lock().await;
 
         // Read file content while holding the lock
-        let content = match fs::read_to_string(&path) {
+        let content = match read_file_content(context, &path).await {
             Ok(c) => c,
             Err(e) => {
                 return Ok(ToolResult::error(format!("Failed to read file: {e}")));
@@ -203,9 +237,7 @@ impl ToolHandler for PatchHandler {
                     );
                 }
 
-                // Write the new content atomically (write to temp, then rename)
-                // This prevents partial writes and ensures readers always see complete content
-                match atomic_write_file(&path, &cascade_result.content) {
+                match write_file_content(context, &path, &cascade_result.content).await {
                     Ok(_) => {
                         let filename = path
                             .file_name()
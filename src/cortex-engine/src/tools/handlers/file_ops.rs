@@ -54,8 +54,10 @@ impl ToolHandler for ReadFileHandler {
             crate::error::CortexError::InvalidInput("file_path is required".into())
         })?;
         let path = context.resolve_path(&file_path);
+        // Editors may hold unsaved buffers for files not yet on disk
+        let client = context.client_reader();
 
-        if !path.exists() {
+        if client.is_none() && !path.exists() {
             return Ok(ToolResult::error(format!(
                 "File not found: {}",
                 path.display()
@@ -117,7 +119,14 @@ impl ToolHandler for ReadFileHandler {
         }
 
         // Handle text files
-        let content = match fs::read_to_string(&path) {
+        let content = match client {
+            Some(client) => client
+                .read_text_file(&path)
+                .await
+                .map_err(|e| e.to_string()),
+            None => fs::read_to_string(&path).map_err(|e| e.to_string()),
+        };
+        let content = match content {
             Ok(c) => c,
             Err(e) => {
                 return Ok(ToolResult::error(format!("Failed to read file: {e}")));
@@ -224,8 +233,17 @@ impl ToolHandler for WriteFileHandler {
             )));
         }
 
-        match fs::write(&path, &args.content) {
+        let written = match context.client_writer() {
+            Some(client) => client
+                .write_text_file(&path, &args.content)
+                .await
+                .map_err(|e| e.to_string()),
+            None => fs::write(&path, &args.content).map_err(|e| e.to_string()),
+        };
+
+        match written {
             Ok(_) => {
+                context.notify_file_written(&path, &args.content).await;
                 let filename = path
                     .file_name()
                     .and_then(|n| n.to_str())
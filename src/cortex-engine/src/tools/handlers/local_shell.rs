@@ -9,6 +9,7 @@
 //! - Non-interactive mode enforcement (CI=true, TERM=dumb, etc.)
 //! - Proper timeout handling with process group killing
 
+use std::collections::HashMap;
 use std::time::Duration;
 
 use async_trait::async_trait;
@@ -17,8 +18,9 @@ use serde_json::Value;
 use tokio::sync::mpsc;
 
 use super::{ToolContext, ToolHandler, ToolResult};
+use crate::audit::{self, AuditEvent, AuditKind};
 use crate::error::Result;
-use crate::exec::{ExecOptions, ExecOutput, OutputChunk, execute_command_streaming};
+use crate::exec::{ExecOptions, ExecOutput, OutputChunk, audit_command, execute_command_streaming};
 use crate::tools::context::ToolOutputChunk;
 use crate::tools::spec::ToolMetadata;
 
@@ -155,6 +157,17 @@ impl ToolHandler for LocalShellHandler {
             return Ok(ToolResult::error("Empty command"));
         }
 
+        if let Some(reason) = crate::managed_policy::command_denial(&args.command) {
+            let event = AuditEvent::new(
+                AuditKind::Denial,
+                "managed-policy",
+                args.command.join(" "),
+                audit::summarize(&reason),
+            );
+            audit::record(event).await;
+            return Ok(ToolResult::error(reason));
+        }
+
         // Build the command (handles shell metacharacters)
         let command = Self::build_command(&args);
 
@@ -170,6 +183,26 @@ impl ToolHandler for LocalShellHandler {
             .map(Duration::from_millis)
             .unwrap_or(Duration::from_secs(60));
 
+        // Editors that advertise terminals run the command where the user can see it,
+        // unless the sandbox policy requires the local runner
+        if let Some(client) = context.client_terminal() {
+            // Only pass variables Cortex set; the client terminal has its own environment
+            let env: HashMap<String, String> = context
+                .env
+                .iter()
+                .filter(|(key, value)| std::env::var(key).ok().as_ref() != Some(*value))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect();
+            let result = client
+                .run_command(&context.call_id, &command, &cwd, &env, timeout)
+                .await;
+            audit_command(&command, &cwd, &result).await;
+            return match result {
+                Ok(output) => Ok(exec_output_to_tool_result(output)),
+                Err(e) => Ok(ToolResult::error(format!("Execution failed: {e}"))),
+            };
+        }
+
         let options = ExecOptions {
             cwd,
             timeout,
@@ -16,6 +16,8 @@ use std::sync::Arc;
 
 use crate::error::Result;
 use crate::integrations::LspIntegration;
+use crate::integrations::lsp_integration::{Position, Range};
+use crate::tools::handlers::workspace_edit::{apply_workspace_edit, edited_files_result};
 use crate::tools::{ToolContext, ToolDefinition, ToolResult};
 
 /// LSP operations available.
@@ -218,6 +220,7 @@ pub async fn execute_lsp(
     // Convert to 0-based indices for LSP
     let line = params.line.saturating_sub(1);
     let character = params.character.saturating_sub(1);
+    let position = Position::new(line, character);
 
     let rel_path = file_path
         .strip_prefix(&context.cwd)
@@ -250,7 +253,7 @@ pub async fn execute_lsp(
         },
         "workspaceSymbol" => {
             let query = params.query.as_deref().unwrap_or("");
-            match lsp.workspace_symbols(query).await {
+            match lsp.workspace_symbols(query, Some(&file_path)).await {
                 Ok(symbols) => json!(symbols),
                 Err(e) => return Ok(ToolResult::error(format!("workspaceSymbol failed: {}", e))),
             }
@@ -308,12 +311,25 @@ pub async fn execute_lsp(
                 Some(name) => name,
                 None => return Ok(ToolResult::error("rename requires 'newName' parameter")),
             };
-            match lsp.rename(&file_path_str, line, character, &new_name).await {
-                Ok(edits) => json!(edits),
+            // Apply the edit through the normal edit path rather than returning it.
+            let edit = match lsp.rename(&file_path_str, line, character, &new_name).await {
+                Ok(Some(edit)) => edit,
+                Ok(None) => {
+                    return Ok(ToolResult::error("rename is not possible at this position"));
+                }
                 Err(e) => return Ok(ToolResult::error(format!("rename failed: {}", e))),
-            }
+            };
+            return Ok(match apply_workspace_edit(&edit, context).await {
+                Ok(files) => {
+                    edited_files_result(&format!("Renamed to '{new_name}'"), &files, &context.cwd)
+                }
+                Err(e) => ToolResult::error(format!("rename failed: {e}")),
+            });
         }
-        "codeActions" => match lsp.code_actions(&file_path_str, line, character).await {
+        "codeActions" => match lsp
+            .code_actions(&file_path_str, Range::new(position, position))
+            .await
+        {
             Ok(actions) => json!(actions),
             Err(e) => return Ok(ToolResult::error(format!("codeActions failed: {}", e))),
         },
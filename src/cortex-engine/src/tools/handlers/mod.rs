@@ -19,6 +19,7 @@ pub mod subagent;
 pub mod task;
 mod todo;
 mod web_search;
+pub mod workspace_edit;
 
 pub use apply_patch::ApplyPatchHandler;
 pub use batch::{
@@ -65,6 +66,7 @@ pub use propose::ProposeHandler;
 pub use questions::QuestionsHandler;
 pub use todo::{TodoItem, TodoPriority, TodoReadHandler, TodoStatus, TodoWriteHandler};
 pub use web_search::WebSearchHandler;
+pub use workspace_edit::{EditedFile, apply_workspace_edit, edited_files_result};
 
 // Skill exports
 pub use skill::{
@@ -646,7 +646,7 @@ impl SubagentExecutor {
                 name: "research".to_string(),
                 description: "Read-only research agent for investigation. Use for understanding code, finding patterns, and gathering information. Cannot modify files.".to_string(),
                 allowed_tools: Some(vec!["Read", "Grep", "Glob", "LS", "FetchUrl", "WebSearch"].into_iter().map(String::from).collect()),
-                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "Execute"].into_iter().map(String::from).collect(),
+                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "LspRename", "LspCodeAction", "Execute", "TerminalOpen", "TerminalSend"].into_iter().map(String::from).collect(),
             },
             SubagentTypeInfo {
                 name: "refactor".to_string(),
@@ -676,13 +676,13 @@ impl SubagentExecutor {
                 name: "architect".to_string(),
                 description: "Architecture planning agent. Use for designing systems, planning refactors, and making technical decisions. Cannot modify files.".to_string(),
                 allowed_tools: Some(vec!["Read", "Grep", "Glob", "LS", "WebSearch"].into_iter().map(String::from).collect()),
-                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "Execute"].into_iter().map(String::from).collect(),
+                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "LspRename", "LspCodeAction", "Execute", "TerminalOpen", "TerminalSend"].into_iter().map(String::from).collect(),
             },
             SubagentTypeInfo {
                 name: "reviewer".to_string(),
                 description: "Code review agent. Use for reviewing changes, finding bugs, and suggesting improvements. Cannot modify files.".to_string(),
                 allowed_tools: Some(vec!["Read", "Grep", "Glob", "LS"].into_iter().map(String::from).collect()),
-                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "Execute"].into_iter().map(String::from).collect(),
+                denied_tools: vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "LspRename", "LspCodeAction", "Execute", "TerminalOpen", "TerminalSend"].into_iter().map(String::from).collect(),
             },
         ]
     }
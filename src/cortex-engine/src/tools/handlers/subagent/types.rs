@@ -253,7 +253,15 @@ This summary will be sent to the orchestrator to coordinate with other agents."#
     pub fn denied_tools(&self) -> Vec<&'static str> {
         match self {
             Self::Research | Self::Reviewer | Self::Architect => {
-                vec!["Create", "Edit", "ApplyPatch", "MultiEdit", "Execute"]
+                vec![
+                    "Create",
+                    "Edit",
+                    "ApplyPatch",
+                    "MultiEdit",
+                    "LspRename",
+                    "LspCodeAction",
+                    "Execute",
+                ]
             }
             _ => vec![],
         }
@@ -0,0 +1,381 @@
+//! Applying LSP workspace edits.
+//!
+//! Renames and code actions come back from language servers as a
+//! `WorkspaceEdit`. This module applies them through the same read/lock/write
+//! path as the Patch tool, so client buffers, path validation and atomic
+//! writes behave exactly as for a manual edit.
+//!
+//! All files are read and edited in memory before anything is written, so an
+//! edit that does not apply cleanly leaves the workspace untouched.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use cortex_lsp::lsp_types::{
+    DocumentChangeOperation, DocumentChanges, OneOf, Position, TextEdit, WorkspaceEdit,
+};
+use serde_json::json;
+
+use super::ToolContext;
+use super::edit_file::{get_file_lock, read_file_content, write_file_content};
+use crate::tools::spec::{ToolMetadata, ToolResult};
+
+/// A file changed by an applied workspace edit.
+#[derive(Debug, Clone)]
+pub struct EditedFile {
+    /// Absolute path of the file.
+    pub path: PathBuf,
+    /// Number of text edits applied to the file.
+    pub edits: usize,
+    /// Unified diff of the change.
+    pub diff: String,
+}
+
+/// Convert a 1-based line and character column into an LSP position.
+///
+/// LSP columns count UTF-16 code units, so the column is converted using the
+/// text of the line.
+pub fn to_lsp_position(content: &str, line: u32, column: u32) -> Position {
+    let line = line.saturating_sub(1);
+    let chars = column.saturating_sub(1) as usize;
+    let character = content
+        .split('\n')
+        .nth(line as usize)
+        .map(|text| text.chars().take(chars).map(|c| c.len_utf16() as u32).sum())
+        .unwrap_or(chars as u32);
+    Position { line, character }
+}
+
+/// Apply text edits to a document.
+///
+/// Ranges refer to the original document. Edits may not overlap; inserts at
+/// the same position are applied in the order given.
+pub fn apply_text_edits(content: &str, edits: &[TextEdit]) -> Result<String, String> {
+    let line_starts = line_starts(content);
+
+    let mut spans = Vec::with_capacity(edits.len());
+    for edit in edits {
+        let start = byte_offset(content, &line_starts, edit.range.start);
+        let end = byte_offset(content, &line_starts, edit.range.end);
+        if end < start {
+            return Err(format!(
+                "Edit range ends before it starts at line {}",
+                edit.range.start.line + 1
+            ));
+        }
+        spans.push((start, end, edit.new_text.as_str()));
+    }
+    // Stable sort keeps same-position inserts in their original order.
+    spans.sort_by_key(|&(start, end, _)| (start, end));
+
+    let mut result = String::with_capacity(content.len());
+    let mut last = 0;
+    for (start, end, new_text) in spans {
+        if start < last {
+            return Err("Edits overlap and cannot be applied".to_string());
+        }
+        result.push_str(&content[last..start]);
+        result.push_str(new_text);
+        last = end;
+    }
+    result.push_str(&content[last..]);
+    Ok(result)
+}
+
+/// Apply a workspace edit to the files it touches.
+///
+/// Only text edits are supported; edits that create, rename or delete files
+/// are rejected before any file is changed.
+pub async fn apply_workspace_edit(
+    edit: &WorkspaceEdit,
+    context: &ToolContext,
+) -> Result<Vec<EditedFile>, String> {
+    let mut by_file: BTreeMap<PathBuf, Vec<TextEdit>> = BTreeMap::new();
+    let mut add = |uri: &cortex_lsp::lsp_types::Url, edits: Vec<TextEdit>| {
+        let path = uri
+            .to_file_path()
+            .map_err(|_| format!("Edit targets a non-file URI: {uri}"))?;
+        by_file.entry(path).or_default().extend(edits);
+        Ok::<_, String>(())
+    };
+
+    // documentChanges takes precedence over changes when both are present.
+    match &edit.document_changes {
+        Some(DocumentChanges::Edits(documents)) => {
+            for document in documents {
+                add(&document.text_document.uri, text_edits(&document.edits))?;
+            }
+        }
+        Some(DocumentChanges::Operations(operations)) => {
+            for operation in operations {
+                match operation {
+                    DocumentChangeOperation::Edit(document) => {
+                        add(&document.text_document.uri, text_edits(&document.edits))?;
+                    }
+                    DocumentChangeOperation::Op(_) => {
+                        return Err(
+                            "Edit creates, renames or deletes files, which is not supported"
+                                .to_string(),
+                        );
+                    }
+                }
+            }
+        }
+        None => {
+            for (uri, edits) in edit.changes.iter().flatten() {
+                add(uri, edits.clone())?;
+            }
+        }
+    }
+
+    let mut files = Vec::with_capacity(by_file.len());
+    for (path, edits) in by_file {
+        let path = context.resolve_and_validate_path(&path.to_string_lossy())?;
+        files.push((path, edits));
+    }
+
+    // Lock every file up front, in path order, so nothing changes between
+    // computing the edits and writing them.
+    let mut guards = Vec::with_capacity(files.len());
+    for (path, _) in &files {
+        guards.push(get_file_lock(path).lock_owned().await);
+    }
+
+    let mut pending = Vec::with_capacity(files.len());
+    for (path, edits) in files {
+        let original = read_file_content(context, &path)
+            .await
+            .map_err(|e| format!("Failed to read {}: {e}", path.display()))?;
+        let updated = apply_text_edits(&original, &edits)
+            .map_err(|e| format!("Failed to apply edit to {}: {e}", path.display()))?;
+        if updated != original {
+            pending.push((path, edits.len(), original, updated));
+        }
+    }
+
+    let mut edited = Vec::with_capacity(pending.len());
+    for (path, edits, original, updated) in pending {
+        if let Err(e) = write_file_content(context, &path, &updated).await {
+            let written: Vec<_> = edited
+                .iter()
+                .map(|f: &EditedFile| f.path.display().to_string())
+                .collect();
+            return Err(if written.is_empty() {
+                format!("Failed to write {}: {e}", path.display())
+            } else {
+                format!(
+                    "Failed to write {}: {e} (already written: {})",
+                    path.display(),
+                    written.join(", ")
+                )
+            });
+        }
+
+        let name = display_path(&path, &context.cwd);
+        let diff = similar::TextDiff::from_lines(&original, &updated)
+            .unified_diff()
+            .header(&format!("a/{name}"), &format!("b/{name}"))
+            .to_string();
+        edited.push(EditedFile { path, edits, diff });
+    }
+
+    Ok(edited)
+}
+
+/// Build the tool result reporting an applied workspace edit.
+pub fn edited_files_result(summary: &str, files: &[EditedFile], cwd: &Path) -> ToolResult {
+    if files.is_empty() {
+        return ToolResult::success(format!("{summary}: no files changed"));
+    }
+
+    let mut output = format!("{summary}: {} file(s) changed\n", files.len());
+    for file in files {
+        output.push_str(&format!(
+            "\n{} ({} edit(s))\n{}",
+            display_path(&file.path, cwd),
+            file.edits,
+            file.diff
+        ));
+    }
+
+    let files_modified: Vec<String> = files.iter().map(|f| f.path.display().to_string()).collect();
+    let metadata = ToolMetadata {
+        duration_ms: 0,
+        exit_code: Some(0),
+        files_modified: files_modified.clone(),
+        data: Some(json!({
+            "files": files_modified,
+            "diff": files.iter().map(|f| f.diff.as_str()).collect::<String>(),
+        })),
+    };
+
+    ToolResult::success(output.trim_end().to_string()).with_metadata(metadata)
+}
+
+/// Display a path relative to the working directory when it is inside it.
+pub fn display_path(path: &Path, cwd: &Path) -> String {
+    let canonical_cwd = cwd.canonicalize().ok();
+    path.strip_prefix(cwd)
+        .ok()
+        .or_else(|| path.strip_prefix(canonical_cwd.as_deref()?).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string()
+}
+
+fn text_edits(
+    edits: &[OneOf<TextEdit, cortex_lsp::lsp_types::AnnotatedTextEdit>],
+) -> Vec<TextEdit> {
+    edits
+        .iter()
+        .map(|edit| match edit {
+            OneOf::Left(edit) => edit.clone(),
+            OneOf::Right(annotated) => annotated.text_edit.clone(),
+        })
+        .collect()
+}
+
+fn line_starts(content: &str) -> Vec<usize> {
+    std::iter::once(0)
+        .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+        .collect()
+}
+
+/// Byte offset of an LSP position, clamping past-the-end lines and columns.
+fn byte_offset(content: &str, line_starts: &[usize], position: Position) -> usize {
+    let Some(&start) = line_starts.get(position.line as usize) else {
+        return content.len();
+    };
+    let end = line_starts
+        .get(position.line as usize + 1)
+        .copied()
+        .unwrap_or(content.len());
+    let text = content[start..end].trim_end_matches(['\n', '\r']);
+
+    let mut units = 0;
+    for (i, c) in text.char_indices() {
+        if units >= position.character {
+            return start + i;
+        }
+        units += c.len_utf16() as u32;
+    }
+    start + text.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_lsp::lsp_types::{
+        CreateFile, OptionalVersionedTextDocumentIdentifier, Range, ResourceOp, TextDocumentEdit,
+        Url,
+    };
+    use std::collections::HashMap;
+    use tempfile::TempDir;
+
+    fn edit(sl: u32, sc: u32, el: u32, ec: u32, text: &str) -> TextEdit {
+        TextEdit::new(
+            Range::new(Position::new(sl, sc), Position::new(el, ec)),
+            text.to_string(),
+        )
+    }
+
+    #[test]
+    fn test_apply_text_edits_uses_original_ranges() {
+        let content = "fn old() {}\nfn main() { old(); }\n";
+        let edits = vec![edit(1, 12, 1, 15, "new"), edit(0, 3, 0, 6, "new")];
+
+        let updated = apply_text_edits(content, &edits).unwrap();
+        assert_eq!(updated, "fn new() {}\nfn main() { new(); }\n");
+    }
+
+    #[test]
+    fn test_apply_text_edits_counts_utf16_columns() {
+        // The emoji is two UTF-16 code units wide.
+        let content = "let s = \"😀\"; let x = 1;\r\nx\n";
+        let edits = vec![edit(0, 18, 0, 19, "y"), edit(0, 30, 0, 40, "!")];
+
+        let updated = apply_text_edits(content, &edits).unwrap();
+        assert_eq!(updated, "let s = \"😀\"; let y = 1;!\r\nx\n");
+    }
+
+    #[test]
+    fn test_apply_text_edits_rejects_overlap() {
+        let edits = vec![edit(0, 0, 0, 5, "a"), edit(0, 3, 0, 6, "b")];
+        assert!(apply_text_edits("abcdefgh", &edits).is_err());
+    }
+
+    #[test]
+    fn test_to_lsp_position_converts_columns() {
+        let content = "a\n😀b\n";
+        assert_eq!(to_lsp_position(content, 2, 2), Position::new(1, 2));
+        assert_eq!(to_lsp_position(content, 1, 1), Position::new(0, 0));
+    }
+
+    #[tokio::test]
+    async fn test_apply_workspace_edit_writes_all_files() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.rs");
+        let b = dir.path().join("b.rs");
+        std::fs::write(&a, "fn old() {}\n").unwrap();
+        std::fs::write(&b, "use crate::old;\n").unwrap();
+
+        let mut changes = HashMap::new();
+        changes.insert(
+            Url::from_file_path(&a).unwrap(),
+            vec![edit(0, 3, 0, 6, "new")],
+        );
+        changes.insert(
+            Url::from_file_path(&b).unwrap(),
+            vec![edit(0, 11, 0, 14, "new")],
+        );
+        let workspace_edit = WorkspaceEdit::new(changes);
+
+        let context = ToolContext::new(dir.path().to_path_buf());
+        let edited = apply_workspace_edit(&workspace_edit, &context)
+            .await
+            .unwrap();
+
+        assert_eq!(edited.len(), 2);
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "fn new() {}\n");
+        assert_eq!(std::fs::read_to_string(&b).unwrap(), "use crate::new;\n");
+        assert!(edited[0].diff.contains("+fn new() {}"));
+
+        let result = edited_files_result("Renamed", &edited, dir.path());
+        assert!(result.success);
+        assert!(result.output.contains("a.rs (1 edit(s))"));
+    }
+
+    #[tokio::test]
+    async fn test_apply_workspace_edit_rejects_resource_operations() {
+        let dir = TempDir::new().unwrap();
+        let a = dir.path().join("a.rs");
+        std::fs::write(&a, "fn old() {}\n").unwrap();
+        let uri = Url::from_file_path(&a).unwrap();
+
+        let workspace_edit = WorkspaceEdit {
+            document_changes: Some(DocumentChanges::Operations(vec![
+                DocumentChangeOperation::Edit(TextDocumentEdit {
+                    text_document: OptionalVersionedTextDocumentIdentifier {
+                        uri: uri.clone(),
+                        version: None,
+                    },
+                    edits: vec![OneOf::Left(edit(0, 3, 0, 6, "new"))],
+                }),
+                DocumentChangeOperation::Op(ResourceOp::Create(CreateFile {
+                    uri: Url::from_file_path(dir.path().join("c.rs")).unwrap(),
+                    options: None,
+                    annotation_id: None,
+                })),
+            ])),
+            ..Default::default()
+        };
+
+        let context = ToolContext::new(dir.path().to_path_buf());
+        assert!(
+            apply_workspace_edit(&workspace_edit, &context)
+                .await
+                .is_err()
+        );
+        assert_eq!(std::fs::read_to_string(&a).unwrap(), "fn old() {}\n");
+    }
+}
@@ -27,6 +27,7 @@
 //! See [`artifacts`] module for configuration and usage.
 
 pub mod artifacts;
+pub mod client_bridge;
 pub mod context;
 pub mod handlers;
 pub mod registry;
@@ -42,6 +43,7 @@ pub use artifacts::{
     DEFAULT_TRUNCATE_THRESHOLD, cleanup_old_artifacts, cleanup_session_artifacts, process_output,
     process_tool_result,
 };
+pub use client_bridge::ClientBridge;
 pub use context::ToolContext;
 pub use handlers::*;
 pub use registry::{PluginTool, ToolRegistry};
@@ -6,7 +6,9 @@ use serde_json::json;
 
 use super::ToolRegistry;
 use crate::agent::tools::{
-    LspDiagnosticsTool, LspHoverTool, MultiEditTool, PatchTool, WebSearchTool,
+    LspCallHierarchyTool, LspCodeActionTool, LspDefinitionTool, LspDiagnosticsTool, LspHoverTool,
+    LspReferencesTool, LspRenameTool, MultiEditTool, PatchTool, TerminalCloseTool,
+    TerminalOpenTool, TerminalScreenTool, TerminalSendTool, TerminalWaitTool, WebSearchTool,
 };
 use crate::tools::handlers::LocalShellHandler;
 use crate::tools::spec::ToolDefinition;
@@ -29,6 +31,7 @@ impl ToolRegistry {
         self.register_multi_edit_tool();
         self.register_task_tools();
         self.register_lsp_tools();
+        self.register_terminal_tools();
         self.register_plan_tool();
         self.register_questions_tool();
         self.register_skill_tool();
@@ -520,6 +523,10 @@ impl ToolRegistry {
                             "type": "string",
                             "enum": ["error", "warning", "all"],
                             "description": "Filter by severity level (default: all)"
+                        },
+                        "timeout_ms": {
+                            "type": "integer",
+                            "description": "With a path, how long to wait for diagnostics on the file's latest content (default: 5000)"
                         }
                     },
                     "required": []
@@ -554,9 +561,164 @@ impl ToolRegistry {
             Arc::new(LspHoverTool::new()),
         );
 
+        self.register_with_handler(
+            ToolDefinition::new(
+                "LspDefinition",
+                "Go to the definition of the symbol at a position, or list its implementations. Returns file:line:column locations with the source line.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "File path containing the symbol"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "Line number (1-based)"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column number (1-based)"
+                        },
+                        "implementation": {
+                            "type": "boolean",
+                            "description": "Find implementations of the trait or interface instead of the definition (default: false)"
+                        }
+                    },
+                    "required": ["file", "line", "column"]
+                }),
+            ),
+            Arc::new(LspDefinitionTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "LspReferences",
+                "Find all references to the symbol at a position across the workspace, including its declaration.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "File path containing the symbol"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "Line number (1-based)"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column number (1-based)"
+                        }
+                    },
+                    "required": ["file", "line", "column"]
+                }),
+            ),
+            Arc::new(LspReferencesTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "LspCallHierarchy",
+                "List the callers (incoming) or callees (outgoing) of the function at a position, with call sites.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "File path containing the symbol"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "Line number (1-based)"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column number (1-based)"
+                        },
+                        "direction": {
+                            "type": "string",
+                            "enum": ["incoming", "outgoing"],
+                            "description": "incoming lists callers, outgoing lists called functions (default: incoming)"
+                        }
+                    },
+                    "required": ["file", "line", "column"]
+                }),
+            ),
+            Arc::new(LspCallHierarchyTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "LspRename",
+                "Rename the symbol at a position everywhere it is used, using the language server. Edits are written to disk and a diff is returned.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "File path containing the symbol"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "Line number (1-based)"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column number (1-based)"
+                        },
+                        "new_name": {
+                            "type": "string",
+                            "description": "The new name for the symbol"
+                        }
+                    },
+                    "required": ["file", "line", "column", "new_name"]
+                }),
+            ),
+            Arc::new(LspRenameTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "LspCodeAction",
+                "List the quick fixes and refactorings the language server offers at a position or range, or apply one of them by number or title.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "file": {
+                            "type": "string",
+                            "description": "File path containing the symbol"
+                        },
+                        "line": {
+                            "type": "integer",
+                            "description": "Line number (1-based)"
+                        },
+                        "column": {
+                            "type": "integer",
+                            "description": "Column number (1-based)"
+                        },
+                        "end_line": {
+                            "type": "integer",
+                            "description": "End line of the range (1-based, default: same as line)"
+                        },
+                        "end_column": {
+                            "type": "integer",
+                            "description": "End column of the range (1-based, default: same as column)"
+                        },
+                        "apply": {
+                            "type": ["integer", "string"],
+                            "description": "Number (from the listing) or exact title of the action to apply. Omit to list actions."
+                        }
+                    },
+                    "required": ["file", "line", "column"]
+                }),
+            ),
+            Arc::new(LspCodeActionTool::new()),
+        );
+
         self.register(ToolDefinition::new(
             "LspSymbols",
-            "Search for symbols (functions, classes, types, etc.) in the workspace. Use for navigating large codebases.",
+            "Search for symbols (functions, classes, types, etc.) in the workspace. Uses the language server when one is running, otherwise a text search. Use for navigating large codebases.",
             json!({
                 "type": "object",
                 "properties": {
@@ -566,7 +728,7 @@ impl ToolRegistry {
                     },
                     "path": {
                         "type": "string",
-                        "description": "Directory to search in (default: current directory)"
+                        "description": "Directory to search in, or a file whose language server to ask (default: current directory)"
                     }
                 },
                 "required": ["query"]
@@ -574,6 +736,132 @@ impl ToolRegistry {
         ));
     }
 
+    fn register_terminal_tools(&mut self) {
+        let terminal_id = json!({
+            "type": "string",
+            "description": "ID returned by TerminalOpen"
+        });
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "TerminalOpen",
+                "Start a program in an interactive terminal (PTY) and show its first screen. Use for REPLs, editors, \
+                interactive prompts and installers that Execute cannot drive. The terminal stays open until TerminalClose.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "command": {
+                            "type": "array",
+                            "items": { "type": "string" },
+                            "description": "Program and arguments to run (default: the user's shell)"
+                        },
+                        "workdir": {
+                            "type": "string",
+                            "description": "Working directory (optional, defaults to cwd)"
+                        },
+                        "name": {
+                            "type": "string",
+                            "description": "Short name for the terminal"
+                        },
+                        "cols": {
+                            "type": "integer",
+                            "description": "Terminal width in columns (default: 120)"
+                        },
+                        "rows": {
+                            "type": "integer",
+                            "description": "Terminal height in rows (default: 30)"
+                        }
+                    },
+                    "required": []
+                }),
+            ),
+            Arc::new(TerminalOpenTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "TerminalSend",
+                "Type keystrokes into a terminal. Text is sent as-is; write keys in angle brackets: <Enter>, <Tab>, <Esc>, \
+                <Backspace>, <Up>, <Down>, <Left>, <Right>, <Home>, <End>, <PageUp>, <PageDown>, <Delete>, <F1>-<F12>, \
+                <C-c> for Ctrl+C, <M-x> for Alt+X, and <lt> for a literal '<'. Follow with TerminalWait to see the response.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "terminal_id": terminal_id.clone(),
+                        "keys": {
+                            "type": "string",
+                            "description": "Keystrokes to send, e.g. \"print(1 + 1)<Enter>\" or \"<Esc>:wq<Enter>\""
+                        }
+                    },
+                    "required": ["terminal_id", "keys"]
+                }),
+            ),
+            Arc::new(TerminalSendTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "TerminalWait",
+                "Wait until output since the last keystrokes matches a regex, or until the terminal has been quiet for a while, \
+                then show the screen. Also returns when the program exits or the timeout elapses.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "terminal_id": terminal_id.clone(),
+                        "pattern": {
+                            "type": "string",
+                            "description": "Regex to wait for, e.g. a prompt like \"\\$ $\" or \">>> $\""
+                        },
+                        "idle_ms": {
+                            "type": "integer",
+                            "description": "Without a pattern, wait for this many milliseconds without output (default: 500)"
+                        },
+                        "timeout_ms": {
+                            "type": "integer",
+                            "description": "Maximum time to wait in milliseconds (default: 30000)"
+                        }
+                    },
+                    "required": ["terminal_id"]
+                }),
+            ),
+            Arc::new(TerminalWaitTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "TerminalScreen",
+                "Show the current screen of a terminal, as a user would see it, or its last lines of scrollback.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "terminal_id": terminal_id.clone(),
+                        "scrollback": {
+                            "type": "integer",
+                            "description": "Show this many lines of history and screen instead of the screen"
+                        }
+                    },
+                    "required": ["terminal_id"]
+                }),
+            ),
+            Arc::new(TerminalScreenTool::new()),
+        );
+
+        self.register_with_handler(
+            ToolDefinition::new(
+                "TerminalClose",
+                "Stop the program in a terminal and close it.",
+                json!({
+                    "type": "object",
+                    "properties": {
+                        "terminal_id": terminal_id
+                    },
+                    "required": ["terminal_id"]
+                }),
+            ),
+            Arc::new(TerminalCloseTool::new()),
+        );
+    }
+
     fn register_plan_tool(&mut self) {
         self.register(ToolDefinition::new(
             "Plan",
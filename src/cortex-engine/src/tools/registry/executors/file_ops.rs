@@ -1,5 +1,7 @@
 //! File operation tool executors (read, write, list, search, edit).
 
+use std::path::Path;
+
 use serde_json::Value;
 
 use crate::error::Result;
@@ -38,11 +40,14 @@ impl ToolRegistry {
             .ok_or_else(|| crate::error::CortexError::InvalidInput("content is required".into()))?;
 
         match tokio::fs::write(path, content).await {
-            Ok(_) => Ok(ToolResult::success(format!(
-                "Wrote {} bytes to {}",
-                content.len(),
-                path
-            ))),
+            Ok(_) => {
+                self.notify_file_written(path, content).await;
+                Ok(ToolResult::success(format!(
+                    "Wrote {} bytes to {}",
+                    content.len(),
+                    path
+                )))
+            }
             Err(e) => Ok(ToolResult::error(format!("Failed to write file: {e}"))),
         }
     }
@@ -149,11 +154,21 @@ impl ToolRegistry {
             content.replacen(old_str, new_str, 1)
         };
 
-        match tokio::fs::write(file_path, new_content).await {
-            Ok(_) => Ok(ToolResult::success(format!(
-                "Successfully edited {file_path}"
-            ))),
+        match tokio::fs::write(file_path, &new_content).await {
+            Ok(_) => {
+                self.notify_file_written(file_path, &new_content).await;
+                Ok(ToolResult::success(format!(
+                    "Successfully edited {file_path}"
+                )))
+            }
             Err(e) => Ok(ToolResult::error(format!("Failed to write file: {e}"))),
         }
     }
+
+    /// Keep the language server in sync with a file written by a tool.
+    async fn notify_file_written(&self, path: &str, content: &str) {
+        if let Some(lsp) = &self.lsp {
+            lsp.did_write(Path::new(path), content).await;
+        }
+    }
 }
@@ -1,11 +1,18 @@
 //! LSP-related tool executors (diagnostics, hover, symbols).
 
+use std::path::Path;
+
 use serde_json::Value;
+use tracing::debug;
 
 use crate::error::Result;
+use crate::tools::handlers::workspace_edit::display_path;
 use crate::tools::registry::ToolRegistry;
 use crate::tools::spec::ToolResult;
 
+/// Maximum number of symbols listed in a search result.
+const MAX_SYMBOLS: usize = 50;
+
 impl ToolRegistry {
     pub(crate) async fn execute_lsp_diagnostics(&self, args: Value) -> Result<ToolResult> {
         let path = args.get("path").and_then(|p| p.as_str());
@@ -210,6 +217,10 @@ impl ToolRegistry {
         let query = args.get("query").and_then(|q| q.as_str()).unwrap_or("");
         let path = args.get("path").and_then(|p| p.as_str()).unwrap_or(".");
 
+        if let Some(result) = self.lsp_workspace_symbols(query, path).await {
+            return Ok(result);
+        }
+
         // Use ctags or similar to find symbols
         let output = tokio::process::Command::new("grep")
             .args([
@@ -235,7 +246,7 @@ impl ToolRegistry {
         match output {
             Ok(output) => {
                 let stdout = String::from_utf8_lossy(&output.stdout);
-                let lines: Vec<&str> = stdout.lines().take(50).collect();
+                let lines: Vec<&str> = stdout.lines().take(MAX_SYMBOLS).collect();
 
                 if lines.is_empty() {
                     return Ok(ToolResult::success(format!(
@@ -254,4 +265,50 @@ impl ToolRegistry {
             Err(e) => Ok(ToolResult::error(format!("Symbol search failed: {e}"))),
         }
     }
+
+    /// Search symbols through the running language servers.
+    ///
+    /// Returns `None` when no server is available or nothing matched, so the
+    /// caller can fall back to a text search.
+    async fn lsp_workspace_symbols(&self, query: &str, path: &str) -> Option<ToolResult> {
+        let lsp = self.lsp.as_ref()?;
+        if !lsp.is_running().await {
+            return None;
+        }
+
+        // A file selects its language server; directories ask every running server.
+        let file = Path::new(path);
+        let server_file = file.is_file().then_some(file);
+        let symbols = match lsp.workspace_symbols(query, server_file).await {
+            Ok(symbols) if !symbols.is_empty() => symbols,
+            Ok(_) => return None,
+            Err(e) => {
+                debug!("LSP workspace symbol search failed, falling back to grep: {e:#}");
+                return None;
+            }
+        };
+
+        let cwd = std::env::current_dir().unwrap_or_default();
+        let mut result = format!(
+            "Found {} symbol(s) matching '{}':\n\n",
+            symbols.len().min(MAX_SYMBOLS),
+            query
+        );
+        for symbol in symbols.iter().take(MAX_SYMBOLS) {
+            let location = match symbol.location.uri.to_file_path() {
+                Ok(file) => format!(
+                    "{}:{}",
+                    display_path(&file, &cwd),
+                    symbol.location.range.start.line + 1
+                ),
+                Err(_) => symbol.location.uri.to_string(),
+            };
+            result.push_str(&format!("> {} {:?} {}", location, symbol.kind, symbol.name));
+            if let Some(container) = &symbol.container_name {
+                result.push_str(&format!(" (in {container})"));
+            }
+            result.push('\n');
+        }
+        Some(ToolResult::success(result))
+    }
 }
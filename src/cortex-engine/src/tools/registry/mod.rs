@@ -23,6 +23,8 @@ pub struct ToolRegistry {
     pub(crate) handlers: HashMap<String, Arc<dyn ToolHandler>>,
     /// LSP integration.
     pub(crate) lsp: Option<Arc<crate::integrations::LspIntegration>>,
+    /// Extra environment for tool processes (e.g. network proxy variables).
+    pub(crate) env: HashMap<String, String>,
 }
 
 impl std::fmt::Debug for ToolRegistry {
@@ -84,6 +86,11 @@ impl ToolRegistry {
         self.lsp = Some(lsp);
     }
 
+    /// Set extra environment variables for tool processes.
+    pub fn set_env(&mut self, env: impl IntoIterator<Item = (String, String)>) {
+        self.env = env.into_iter().collect();
+    }
+
     /// Get a tool definition.
     pub fn get(&self, name: &str) -> Option<&ToolDefinition> {
         self.tools.get(name)
@@ -121,8 +128,12 @@ impl ToolRegistry {
         &self,
         name: &str,
         arguments: Value,
-        context: super::context::ToolContext,
+        mut context: super::context::ToolContext,
     ) -> Result<ToolResult> {
+        context
+            .env
+            .extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+
         if !self.has(name) {
             return Ok(ToolResult::error(format!("Unknown tool: {name}")));
         }
@@ -11,6 +11,7 @@ use super::handlers::batch::{BatchToolExecutor, BatchToolHandler, batch_tool_def
 use super::handlers::*;
 use super::registry::ToolRegistry;
 use super::spec::{ToolDefinition, ToolHandler, ToolResult};
+use crate::audit::{self, AuditEvent, AuditKind};
 use crate::error::{CortexError, Result};
 
 /// Routes tool calls to appropriate handlers.
@@ -75,6 +76,46 @@ impl RouterExecutor {
             "LspDiagnostics".to_string(),
             Box::new(crate::agent::tools::LspDiagnosticsTool::new_handler()),
         );
+        new_handlers.insert(
+            "LspDefinition".to_string(),
+            Box::new(crate::agent::tools::LspDefinitionTool::new()),
+        );
+        new_handlers.insert(
+            "LspReferences".to_string(),
+            Box::new(crate::agent::tools::LspReferencesTool::new()),
+        );
+        new_handlers.insert(
+            "LspCallHierarchy".to_string(),
+            Box::new(crate::agent::tools::LspCallHierarchyTool::new()),
+        );
+        new_handlers.insert(
+            "LspRename".to_string(),
+            Box::new(crate::agent::tools::LspRenameTool::new()),
+        );
+        new_handlers.insert(
+            "LspCodeAction".to_string(),
+            Box::new(crate::agent::tools::LspCodeActionTool::new()),
+        );
+        new_handlers.insert(
+            "TerminalOpen".to_string(),
+            Box::new(crate::agent::tools::TerminalOpenTool::new()),
+        );
+        new_handlers.insert(
+            "TerminalSend".to_string(),
+            Box::new(crate::agent::tools::TerminalSendTool::new()),
+        );
+        new_handlers.insert(
+            "TerminalWait".to_string(),
+            Box::new(crate::agent::tools::TerminalWaitTool::new()),
+        );
+        new_handlers.insert(
+            "TerminalScreen".to_string(),
+            Box::new(crate::agent::tools::TerminalScreenTool::new()),
+        );
+        new_handlers.insert(
+            "TerminalClose".to_string(),
+            Box::new(crate::agent::tools::TerminalCloseTool::new()),
+        );
 
         // Also include any custom handlers that were registered
         for name in handlers.keys() {
@@ -106,7 +147,7 @@ impl BatchToolExecutor for RouterExecutor {
                 name: name.to_string(),
             })?;
 
-        handler.execute(arguments, context).await
+        execute_audited(handler.as_ref(), name, arguments, context).await
     }
 
     fn has_tool(&self, name: &str) -> bool {
@@ -165,6 +206,46 @@ impl ToolRouter {
             "LspDiagnostics".to_string(),
             Box::new(crate::agent::tools::LspDiagnosticsTool::new_handler()),
         );
+        handlers.insert(
+            "LspDefinition".to_string(),
+            Box::new(crate::agent::tools::LspDefinitionTool::new()),
+        );
+        handlers.insert(
+            "LspReferences".to_string(),
+            Box::new(crate::agent::tools::LspReferencesTool::new()),
+        );
+        handlers.insert(
+            "LspCallHierarchy".to_string(),
+            Box::new(crate::agent::tools::LspCallHierarchyTool::new()),
+        );
+        handlers.insert(
+            "LspRename".to_string(),
+            Box::new(crate::agent::tools::LspRenameTool::new()),
+        );
+        handlers.insert(
+            "LspCodeAction".to_string(),
+            Box::new(crate::agent::tools::LspCodeActionTool::new()),
+        );
+        handlers.insert(
+            "TerminalOpen".to_string(),
+            Box::new(crate::agent::tools::TerminalOpenTool::new()),
+        );
+        handlers.insert(
+            "TerminalSend".to_string(),
+            Box::new(crate::agent::tools::TerminalSendTool::new()),
+        );
+        handlers.insert(
+            "TerminalWait".to_string(),
+            Box::new(crate::agent::tools::TerminalWaitTool::new()),
+        );
+        handlers.insert(
+            "TerminalScreen".to_string(),
+            Box::new(crate::agent::tools::TerminalScreenTool::new()),
+        );
+        handlers.insert(
+            "TerminalClose".to_string(),
+            Box::new(crate::agent::tools::TerminalCloseTool::new()),
+        );
 
         // Create the Batch tool handler with a RouterExecutor
         let router_executor = Arc::new(RouterExecutor::new(&handlers));
@@ -191,7 +272,11 @@ impl ToolRouter {
                 name: tool_name.to_string(),
             })?;
 
-        handler.execute(arguments, context).await
+        audit::in_session(
+            &context.conversation_id,
+            execute_audited(handler.as_ref(), tool_name, arguments, context),
+        )
+        .await
     }
 
     /// Get tool definitions for the model.
@@ -227,6 +312,111 @@ impl Default for ToolRouter {
     }
 }
 
+/// Execute a tool call, recording it in the audit log if it writes files,
+/// reaches the network or calls an MCP server. Commands are recorded by the
+/// command runner, which sees their exit status.
+async fn execute_audited(
+    handler: &dyn ToolHandler,
+    tool_name: &str,
+    arguments: Value,
+    context: &ToolContext,
+) -> Result<ToolResult> {
+    let audited = audit_kind(tool_name).map(|kind| {
+        (
+            kind,
+            audit_target(kind, tool_name, &arguments),
+            audit_details(kind, &arguments),
+        )
+    });
+
+    let result = handler.execute(arguments, context).await;
+
+    if let Some((kind, target, details)) = audited {
+        let outcome = match &result {
+            Ok(r) if r.success => "ok".to_string(),
+            Ok(r) => format!(
+                "failed: {}",
+                audit::summarize(r.error.as_deref().unwrap_or(&r.output))
+            ),
+            Err(e) => format!("error: {}", audit::summarize(&e.to_string())),
+        };
+        let event = AuditEvent::new(kind, tool_name, target, outcome).with_details(details);
+        audit::record(event).await;
+    }
+    result
+}
+
+/// Kind of audit entry a tool call produces, if any.
+fn audit_kind(tool_name: &str) -> Option<AuditKind> {
+    match tool_name {
+        "Create" | "Patch" | "ApplyPatch" | "MultiEdit" | "LspRename" | "LspCodeAction" => {
+            Some(AuditKind::FileWrite)
+        }
+        "FetchUrl" | "WebFetch" | "WebSearch" => Some(AuditKind::NetworkFetch),
+        "TerminalOpen" | "TerminalSend" => Some(AuditKind::Command),
+        name if name.starts_with("mcp__") => Some(AuditKind::McpCall),
+        _ => None,
+    }
+}
+
+/// What a tool call acts on: the files written, the URL or query fetched,
+/// the command run or keys typed, or the MCP tool called.
+fn audit_target(kind: AuditKind, tool_name: &str, arguments: &Value) -> String {
+    let str_arg = |key: &str| arguments.get(key).and_then(Value::as_str);
+    match kind {
+        AuditKind::McpCall => tool_name.to_string(),
+        AuditKind::Command => match arguments.get("command").and_then(Value::as_array) {
+            Some(command) => command
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => str_arg("keys").unwrap_or_default().to_string(),
+        },
+        AuditKind::NetworkFetch => str_arg("url")
+            .or_else(|| str_arg("query"))
+            .unwrap_or_default()
+            .to_string(),
+        _ => {
+            let mut paths: Vec<String> = if let Some(patch) = str_arg("patch") {
+                apply_patch::parse_unified_diff(patch)
+                    .unwrap_or_default()
+                    .into_iter()
+                    .filter_map(|change| change.new_path.or(change.old_path))
+                    .map(|path| path.display().to_string())
+                    .collect()
+            } else if let Some(edits) = arguments.get("edits").and_then(Value::as_array) {
+                edits
+                    .iter()
+                    .filter_map(|edit| edit.get("file_path").and_then(Value::as_str))
+                    .map(str::to_string)
+                    .collect()
+            } else {
+                str_arg("file_path")
+                    .or_else(|| str_arg("path"))
+                    .or_else(|| str_arg("file"))
+                    .map(str::to_string)
+                    .into_iter()
+                    .collect()
+            };
+            paths.dedup();
+            paths.join(", ")
+        }
+    }
+}
+
+/// Details worth keeping for a tool call: MCP arguments, and whether a
+/// patch was only a dry run.
+fn audit_details(kind: AuditKind, arguments: &Value) -> Value {
+    match kind {
+        AuditKind::McpCall => arguments.clone(),
+        AuditKind::FileWrite if arguments.get("dry_run").and_then(Value::as_bool) == Some(true) => {
+            serde_json::json!({ "dry_run": true })
+        }
+        _ => Value::Null,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -329,4 +519,60 @@ mod tests {
             error_msg
         );
     }
+
+    #[test]
+    fn test_audit_target() {
+        let target = |tool: &str, args: Value| audit_target(audit_kind(tool).unwrap(), tool, &args);
+
+        assert_eq!(
+            target("Create", serde_json::json!({ "file_path": "src/lib.rs" })),
+            "src/lib.rs"
+        );
+        assert_eq!(
+            target(
+                "MultiEdit",
+                serde_json::json!({ "edits": [{ "file_path": "a.rs" }, { "file_path": "a.rs" }, { "file_path": "b.rs" }] })
+            ),
+            "a.rs, b.rs"
+        );
+        assert_eq!(
+            target(
+                "ApplyPatch",
+                serde_json::json!({ "patch": "--- a/x.txt\n+++ b/x.txt\n@@ -1 +1 @@\n-a\n+b\n" })
+            ),
+            "x.txt"
+        );
+        assert_eq!(
+            target("WebSearch", serde_json::json!({ "query": "rust audit" })),
+            "rust audit"
+        );
+        assert_eq!(
+            target("mcp__github__list_repos", serde_json::json!({})),
+            "mcp__github__list_repos"
+        );
+        assert_eq!(
+            target(
+                "TerminalOpen",
+                serde_json::json!({ "command": ["python3", "-q"] })
+            ),
+            "python3 -q"
+        );
+        assert_eq!(
+            target(
+                "TerminalSend",
+                serde_json::json!({ "terminal_id": "t1", "keys": "ls<Enter>" })
+            ),
+            "ls<Enter>"
+        );
+        assert_eq!(
+            target(
+                "LspRename",
+                serde_json::json!({ "file": "src/lib.rs", "line": 3, "column": 8, "new_name": "y" })
+            ),
+            "src/lib.rs"
+        );
+        assert_eq!(audit_kind("LspCodeAction"), Some(AuditKind::FileWrite));
+        assert_eq!(audit_kind("Execute"), None);
+        assert_eq!(audit_kind("Read"), None);
+    }
 }
@@ -351,3 +351,85 @@ async fn test_grep_handler_search_in_temp() {
         assert!(result.content().contains("Hello") || result.content().contains("test.txt"));
     }
 }
+
+/// Editor buffers standing in for an ACP client.
+#[derive(Default)]
+struct BufferBridge {
+    buffers: std::sync::Mutex<std::collections::HashMap<PathBuf, String>>,
+}
+
+#[async_trait::async_trait]
+impl crate::tools::ClientBridge for BufferBridge {
+    fn can_read_files(&self) -> bool {
+        true
+    }
+
+    fn can_write_files(&self) -> bool {
+        true
+    }
+
+    fn can_run_commands(&self) -> bool {
+        false
+    }
+
+    async fn read_text_file(&self, path: &std::path::Path) -> crate::error::Result<String> {
+        let buffers = self.buffers.lock().unwrap();
+        buffers
+            .get(path)
+            .cloned()
+            .ok_or_else(|| crate::error::CortexError::NotFound(path.display().to_string()))
+    }
+
+    async fn write_text_file(
+        &self,
+        path: &std::path::Path,
+        content: &str,
+    ) -> crate::error::Result<()> {
+        let mut buffers = self.buffers.lock().unwrap();
+        buffers.insert(path.to_path_buf(), content.to_string());
+        Ok(())
+    }
+
+    async fn run_command(
+        &self,
+        _call_id: &str,
+        _command: &[String],
+        _cwd: &std::path::Path,
+        _env: &std::collections::HashMap<String, String>,
+        _timeout: std::time::Duration,
+    ) -> crate::error::Result<crate::exec::ExecOutput> {
+        unimplemented!()
+    }
+}
+
+#[tokio::test]
+async fn test_patch_handler_edits_client_buffer() {
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    let temp = tempdir().expect("create temp dir");
+    let file_path = temp.path().join("unsaved.txt");
+
+    // The file only exists as an unsaved editor buffer
+    let bridge = Arc::new(BufferBridge::default());
+    bridge
+        .buffers
+        .lock()
+        .unwrap()
+        .insert(file_path.clone(), "Hello world".to_string());
+
+    let handler = PatchHandler::new();
+    let ctx = ToolContext::new(temp.path().to_path_buf()).with_client_bridge(Some(bridge.clone()));
+
+    let args = serde_json::json!({
+        "file_path": file_path.to_str().unwrap(),
+        "old_str": "world",
+        "new_str": "Rust"
+    });
+
+    let result = handler.execute(args, &ctx).await.expect("execute");
+    assert!(result.success, "Edit should succeed: {}", result.content());
+
+    assert_eq!(bridge.buffers.lock().unwrap()[&file_path], "Hello Rust");
+    assert!(!file_path.exists());
+}
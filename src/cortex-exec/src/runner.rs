@@ -19,6 +19,7 @@ use cortex_engine::{
     client::{
         CompletionRequest, CompletionResponse, FinishReason, Message, ModelClient, ResponseEvent,
         ToolCall, ToolDefinition as ClientToolDefinition, create_client,
+        create_client_for_provider,
     },
     tools::{ToolContext, ToolRouter},
 };
@@ -176,13 +177,21 @@ impl ExecRunner {
                 .unwrap_or(&self.config.model)
                 .clone();
 
-            // Create client using cortex-core's factory
-            let client = create_client(
-                &self.config.model_provider_id,
-                &model,
-                "", // API key resolved from environment by the client
-                None,
-            )?;
+            // Custom providers from the `[providers]` table are called directly
+            let client = match self.config.providers.get(&self.config.model_provider_id) {
+                Some(provider) => create_client_for_provider(
+                    &self.config.model_provider_id,
+                    provider,
+                    &model,
+                    None,
+                )?,
+                None => create_client(
+                    &self.config.model_provider_id,
+                    &model,
+                    "", // API key resolved from environment by the client
+                    None,
+                )?,
+            };
 
             self.client = Some(client);
         }
@@ -40,6 +40,23 @@ impl LMStudioClient {
         Self::new(DEFAULT_LMSTUDIO_URL).await
     }
 
+    /// Create a client for the given base URL without checking server connectivity
+    ///
+    /// Use this when the server may not be running yet; requests will fail
+    /// with a connection error until it is.
+    pub fn with_base_url(base_url: impl Into<String>) -> Self {
+        let client = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(5))
+            .timeout(Duration::from_secs(300))
+            .build()
+            .unwrap_or_else(|_| reqwest::Client::new());
+
+        Self {
+            client,
+            base_url: base_url.into(),
+        }
+    }
+
     /// Create a client without checking server connectivity (for testing)
     #[cfg(test)]
     pub(crate) fn from_host_root(host_root: impl Into<String>) -> Self {
@@ -1,6 +1,8 @@
 //! LSP server capabilities caching.
 
-use lsp_types::InitializeResult;
+use lsp_types::{
+    InitializeResult, TextDocumentSyncCapability, TextDocumentSyncKind, TextDocumentSyncSaveOptions,
+};
 
 /// Server capabilities cached from initialization.
 #[derive(Debug, Clone, Default)]
@@ -29,12 +31,30 @@ pub struct CachedServerCapabilities {
     pub call_hierarchy: bool,
     /// Whether the server supports code actions.
     pub code_action: bool,
+    /// How the server wants document changes sent, if it advertised it.
+    pub text_document_sync: Option<TextDocumentSyncKind>,
+    /// Whether the server wants `didSave`, and if so whether to include the text.
+    pub save_include_text: Option<bool>,
 }
 
 impl CachedServerCapabilities {
     /// Parse capabilities from InitializeResult.
     pub fn from_initialize_result(result: &InitializeResult) -> Self {
         let caps = &result.capabilities;
+        let (text_document_sync, save_include_text) = match &caps.text_document_sync {
+            Some(TextDocumentSyncCapability::Kind(kind)) => (Some(*kind), None),
+            Some(TextDocumentSyncCapability::Options(options)) => {
+                let save = match &options.save {
+                    Some(TextDocumentSyncSaveOptions::Supported(true)) => Some(false),
+                    Some(TextDocumentSyncSaveOptions::SaveOptions(save)) => {
+                        Some(save.include_text.unwrap_or(false))
+                    }
+                    _ => None,
+                };
+                (options.change, save)
+            }
+            None => (None, None),
+        };
         Self {
             hover: caps.hover_provider.is_some(),
             goto_definition: caps.definition_provider.is_some(),
@@ -48,6 +68,8 @@ impl CachedServerCapabilities {
             implementation: caps.implementation_provider.is_some(),
             call_hierarchy: caps.call_hierarchy_provider.is_some(),
             code_action: caps.code_action_provider.is_some(),
+            text_document_sync,
+            save_include_text,
         }
     }
 }
@@ -4,10 +4,13 @@ mod capabilities;
 mod config;
 mod process;
 mod requests;
+mod sync;
 
 pub use capabilities::CachedServerCapabilities;
 pub use config::LspClientConfig;
 
+use sync::{DiagnosticPublications, OpenDocument};
+
 use crate::{Diagnostic, LspError, LspServerConfig, Result};
 use lsp_types::*;
 use serde::{Deserialize, Serialize};
@@ -37,6 +40,10 @@ pub struct LspClient {
     pub(crate) capabilities: RwLock<Option<CachedServerCapabilities>>,
     /// Shutdown signal sender for the response reader task.
     pub(crate) shutdown_tx: Mutex<Option<mpsc::Sender<()>>>,
+    /// Documents opened in the server, with their synced content.
+    pub(crate) documents: RwLock<HashMap<PathBuf, OpenDocument>>,
+    /// When diagnostics were last published, per file.
+    pub(crate) publications: Arc<DiagnosticPublications>,
 }
 
 impl LspClient {
@@ -60,6 +67,8 @@ impl LspClient {
             server_alive: Arc::new(AtomicBool::new(false)),
             capabilities: RwLock::new(None),
             shutdown_tx: Mutex::new(None),
+            documents: RwLock::new(HashMap::new()),
+            publications: Arc::new(DiagnosticPublications::default()),
         }
     }
 
@@ -15,6 +15,7 @@ use tokio::sync::{mpsc, RwLock};
 use tracing::{debug, error, info, warn};
 
 use super::capabilities::CachedServerCapabilities;
+use super::sync::DiagnosticPublications;
 use super::LspClient;
 
 impl LspClient {
@@ -63,6 +64,7 @@ impl LspClient {
         // Start reading responses in background
         let pending = self.pending_requests.clone();
         let diagnostics = self.diagnostics.clone();
+        let publications = self.publications.clone();
         let server_alive = self.server_alive.clone();
         let read_timeout = self.client_config.read_timeout;
         let max_content_length = self.client_config.max_content_length;
@@ -72,6 +74,7 @@ impl LspClient {
                 stdout,
                 pending,
                 diagnostics,
+                publications,
                 server_alive.clone(),
                 shutdown_rx,
                 read_timeout,
@@ -170,15 +173,63 @@ impl LspClient {
                     }),
                     publish_diagnostics: Some(PublishDiagnosticsClientCapabilities {
                         related_information: Some(true),
+                        version_support: Some(true),
                         ..Default::default()
                     }),
+                    definition: Some(GotoCapability {
+                        dynamic_registration: Some(false),
+                        link_support: Some(true),
+                    }),
                     implementation: Some(GotoCapability {
                         dynamic_registration: Some(false),
                         link_support: Some(true),
                     }),
+                    references: Some(DynamicRegistrationClientCapabilities {
+                        dynamic_registration: Some(false),
+                    }),
+                    document_symbol: Some(DocumentSymbolClientCapabilities {
+                        dynamic_registration: Some(false),
+                        hierarchical_document_symbol_support: Some(true),
+                        ..Default::default()
+                    }),
                     call_hierarchy: Some(CallHierarchyClientCapabilities {
                         dynamic_registration: Some(false),
                     }),
+                    rename: Some(RenameClientCapabilities {
+                        dynamic_registration: Some(false),
+                        prepare_support: Some(false),
+                        ..Default::default()
+                    }),
+                    code_action: Some(CodeActionClientCapabilities {
+                        dynamic_registration: Some(false),
+                        code_action_literal_support: Some(CodeActionLiteralSupport {
+                            code_action_kind: CodeActionKindLiteralSupport {
+                                value_set: vec![
+                                    CodeActionKind::QUICKFIX.as_str().to_string(),
+                                    CodeActionKind::REFACTOR.as_str().to_string(),
+                                    CodeActionKind::REFACTOR_EXTRACT.as_str().to_string(),
+                                    CodeActionKind::REFACTOR_INLINE.as_str().to_string(),
+                                    CodeActionKind::REFACTOR_REWRITE.as_str().to_string(),
+                                    CodeActionKind::SOURCE.as_str().to_string(),
+                                    CodeActionKind::SOURCE_ORGANIZE_IMPORTS.as_str().to_string(),
+                                ],
+                            },
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                workspace: Some(WorkspaceClientCapabilities {
+                    // Edits are applied by the host; file create/rename/delete
+                    // operations are not supported.
+                    workspace_edit: Some(WorkspaceEditClientCapabilities {
+                        document_changes: Some(true),
+                        ..Default::default()
+                    }),
+                    symbol: Some(WorkspaceSymbolClientCapabilities {
+                        dynamic_registration: Some(false),
+                        ..Default::default()
+                    }),
                     ..Default::default()
                 }),
                 ..Default::default()
@@ -269,10 +320,12 @@ impl LspClient {
     }
 
     /// Read responses from the LSP server stdout.
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn read_responses(
         stdout: tokio::process::ChildStdout,
         pending: Arc<RwLock<HashMap<u64, mpsc::Sender<Value>>>>,
         diagnostics: Arc<RwLock<HashMap<PathBuf, Vec<Diagnostic>>>>,
+        publications: Arc<DiagnosticPublications>,
         server_alive: Arc<std::sync::atomic::AtomicBool>,
         mut shutdown_rx: mpsc::Receiver<()>,
         read_timeout: Duration,
@@ -303,7 +356,7 @@ impl LspClient {
                             else if let Some(method) = message.get("method").and_then(|m| m.as_str()) {
                                 if method == "textDocument/publishDiagnostics" {
                                     if let Some(params) = message.get("params") {
-                                        Self::handle_diagnostics(params, &diagnostics).await;
+                                        Self::handle_diagnostics(params, &diagnostics, &publications).await;
                                     }
                                 }
                             }
@@ -416,6 +469,7 @@ impl LspClient {
     pub(super) async fn handle_diagnostics(
         params: &Value,
         diagnostics: &RwLock<HashMap<PathBuf, Vec<Diagnostic>>>,
+        publications: &DiagnosticPublications,
     ) {
         let uri = params.get("uri").and_then(|u| u.as_str());
         let diags = params.get("diagnostics").and_then(|d| d.as_array());
@@ -444,6 +498,16 @@ impl LspClient {
 
                             let mut diag = Diagnostic::new(path.clone(), line, column, message)
                                 .with_severity(severity);
+                            if let Some(end) = range.get("end") {
+                                diag.end_line = end
+                                    .get("line")
+                                    .and_then(|l| l.as_u64())
+                                    .map(|l| l as u32 + 1);
+                                diag.end_column = end
+                                    .get("character")
+                                    .and_then(|c| c.as_u64())
+                                    .map(|c| c as u32 + 1);
+                            }
 
                             if let Some(source) = d.get("source").and_then(|s| s.as_str()) {
                                 diag = diag.with_source(source);
@@ -466,7 +530,12 @@ impl LspClient {
                         })
                         .collect();
 
-                    diagnostics.write().await.insert(path, converted);
+                    diagnostics.write().await.insert(path.clone(), converted);
+                    let version = params
+                        .get("version")
+                        .and_then(|v| v.as_i64())
+                        .map(|v| v as i32);
+                    publications.record(path, version).await;
                 }
             }
         }
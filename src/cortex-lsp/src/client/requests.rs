@@ -5,6 +5,7 @@ use lsp_types::request::{GotoImplementationParams, GotoImplementationResponse};
 use lsp_types::*;
 use std::path::Path;
 
+use super::sync::OpenDocument;
 use super::LspClient;
 
 impl LspClient {
@@ -22,7 +23,16 @@ impl LspClient {
             },
         };
 
-        self.notify("textDocument/didOpen", params).await
+        self.notify("textDocument/didOpen", params).await?;
+        self.documents.write().await.insert(
+            path.to_path_buf(),
+            OpenDocument {
+                version: 1,
+                text: text.to_string(),
+                synced_at: self.publications.sequence(),
+            },
+        );
+        Ok(())
     }
 
     /// Close a document.
@@ -34,7 +44,9 @@ impl LspClient {
             text_document: TextDocumentIdentifier { uri },
         };
 
-        self.notify("textDocument/didClose", params).await
+        self.notify("textDocument/didClose", params).await?;
+        self.documents.write().await.remove(path);
+        Ok(())
     }
 
     /// Get hover information.
@@ -124,7 +136,7 @@ impl LspClient {
     }
 
     /// Search for symbols across the workspace.
-    pub async fn workspace_symbols(&self, query: &str) -> Result<Option<Vec<SymbolInformation>>> {
+    pub async fn workspace_symbols(&self, query: &str) -> Result<Option<WorkspaceSymbolResponse>> {
         self.check_capability("workspaceSymbol").await?;
 
         let params = WorkspaceSymbolParams {
@@ -308,6 +320,9 @@ impl LspClient {
     }
 
     /// Get code actions (quick fixes, refactorings) for a range.
+    ///
+    /// `diagnostics` are the diagnostics overlapping the range; servers use
+    /// them to offer quick fixes.
     pub async fn code_actions(
         &self,
         path: &Path,
@@ -315,6 +330,7 @@ impl LspClient {
         start_char: u32,
         end_line: u32,
         end_char: u32,
+        diagnostics: Vec<lsp_types::Diagnostic>,
     ) -> Result<Option<CodeActionResponse>> {
         self.check_capability("codeAction").await?;
 
@@ -334,7 +350,7 @@ impl LspClient {
                 },
             },
             context: CodeActionContext {
-                diagnostics: Vec::new(),
+                diagnostics,
                 only: None,
                 trigger_kind: None,
             },
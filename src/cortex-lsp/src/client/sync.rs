@@ -0,0 +1,334 @@
+//! Text document synchronization.
+//!
+//! The client keeps the text and version of every document it has opened, so
+//! edits made on disk can be sent to the server as versioned `didChange`
+//! notifications (incremental when the server supports it) followed by
+//! `didSave`. Diagnostics published after the latest change are tracked so
+//! callers can wait for results that describe the current content.
+
+use crate::{Diagnostic, LspError, Result};
+use lsp_types::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::{Notify, RwLock};
+
+use super::LspClient;
+
+/// A document opened in the server.
+#[derive(Debug, Clone)]
+pub(crate) struct OpenDocument {
+    /// Version of the last content sent to the server.
+    pub version: i32,
+    /// Content as last sent to the server.
+    pub text: String,
+    /// Publication counter at the time of the last change. Diagnostics
+    /// published after this point describe the current content.
+    pub synced_at: u64,
+}
+
+/// A `publishDiagnostics` notification, as far as freshness is concerned.
+#[derive(Debug, Clone, Copy)]
+struct Publication {
+    /// Value of the publication counter for this notification.
+    sequence: u64,
+    /// Document version the diagnostics refer to, if the server sent one.
+    version: Option<i32>,
+}
+
+/// Tracks when diagnostics were last published for each file.
+#[derive(Debug, Default)]
+pub(crate) struct DiagnosticPublications {
+    counter: AtomicU64,
+    latest: RwLock<HashMap<PathBuf, Publication>>,
+    notify: Notify,
+}
+
+impl DiagnosticPublications {
+    /// Current value of the publication counter.
+    pub fn sequence(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
+
+    /// Record a publication for a file and wake waiters.
+    pub async fn record(&self, path: PathBuf, version: Option<i32>) {
+        let sequence = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+        self.latest
+            .write()
+            .await
+            .insert(path, Publication { sequence, version });
+        self.notify.notify_waiters();
+    }
+
+    /// Whether diagnostics for `path` were published after `synced_at` and,
+    /// when the server reports versions, for at least `version`.
+    async fn is_fresh(&self, path: &Path, synced_at: u64, version: i32) -> bool {
+        self.latest
+            .read()
+            .await
+            .get(path)
+            .is_some_and(|p| p.sequence > synced_at && p.version.is_none_or(|v| v >= version))
+    }
+}
+
+impl LspClient {
+    /// Version of an open document, or `None` if it is not open.
+    pub async fn document_version(&self, path: &Path) -> Option<i32> {
+        self.documents.read().await.get(path).map(|d| d.version)
+    }
+
+    /// Send new content for an open document.
+    ///
+    /// Sends an incremental change covering only the edited region when the
+    /// server supports it, the full text otherwise. Does nothing when the
+    /// content is unchanged or the server does not want change notifications.
+    pub async fn did_change(&self, path: &Path, text: &str) -> Result<()> {
+        let sync_kind = self
+            .capabilities
+            .read()
+            .await
+            .as_ref()
+            .and_then(|c| c.text_document_sync)
+            .unwrap_or(TextDocumentSyncKind::FULL);
+
+        let mut documents = self.documents.write().await;
+        let document = documents
+            .get_mut(path)
+            .ok_or_else(|| LspError::Communication(format!("{} is not open", path.display())))?;
+        if document.text == text {
+            return Ok(());
+        }
+
+        let change = if sync_kind == TextDocumentSyncKind::INCREMENTAL {
+            incremental_change(&document.text, text)
+        } else if sync_kind == TextDocumentSyncKind::FULL {
+            TextDocumentContentChangeEvent {
+                range: None,
+                range_length: None,
+                text: text.to_string(),
+            }
+        } else {
+            // The server does not track document content.
+            document.text = text.to_string();
+            return Ok(());
+        };
+
+        let uri = Url::from_file_path(path)
+            .map_err(|_| LspError::Communication("Invalid path".into()))?;
+        let version = document.version + 1;
+        let params = DidChangeTextDocumentParams {
+            text_document: VersionedTextDocumentIdentifier { uri, version },
+            content_changes: vec![change],
+        };
+        self.notify("textDocument/didChange", params).await?;
+
+        document.version = version;
+        document.text = text.to_string();
+        document.synced_at = self.publications.sequence();
+        Ok(())
+    }
+
+    /// Tell the server an open document was saved.
+    ///
+    /// Servers that run their checks on save (e.g. `cargo check`) only
+    /// refresh diagnostics after this. Does nothing when the server did not
+    /// ask for save notifications.
+    pub async fn did_save(&self, path: &Path) -> Result<()> {
+        let Some(include_text) = self
+            .capabilities
+            .read()
+            .await
+            .as_ref()
+            .and_then(|c| c.save_include_text)
+        else {
+            return Ok(());
+        };
+
+        let text = if include_text {
+            let documents = self.documents.read().await;
+            let document = documents.get(path).ok_or_else(|| {
+                LspError::Communication(format!("{} is not open", path.display()))
+            })?;
+            Some(document.text.clone())
+        } else {
+            None
+        };
+
+        let uri = Url::from_file_path(path)
+            .map_err(|_| LspError::Communication("Invalid path".into()))?;
+        let params = DidSaveTextDocumentParams {
+            text_document: TextDocumentIdentifier { uri },
+            text,
+        };
+        self.notify("textDocument/didSave", params).await
+    }
+
+    /// Wait until diagnostics for the current content of `path` are published.
+    ///
+    /// Returns the diagnostics, or [`LspError::Timeout`] if the server did not
+    /// publish any for the latest change within `timeout`.
+    pub async fn wait_for_diagnostics(
+        &self,
+        path: &Path,
+        timeout: Duration,
+    ) -> Result<Vec<Diagnostic>> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register for wakeups before checking so a publication between
+            // the check and the wait is not missed.
+            let notified = self.publications.notify.notified();
+
+            let (synced_at, version) = {
+                let documents = self.documents.read().await;
+                let document = documents.get(path).ok_or_else(|| {
+                    LspError::Communication(format!("{} is not open", path.display()))
+                })?;
+                (document.synced_at, document.version)
+            };
+            if self.publications.is_fresh(path, synced_at, version).await {
+                return Ok(self.get_diagnostics(path).await);
+            }
+
+            if tokio::time::timeout_at(deadline, notified).await.is_err() {
+                return Err(LspError::Timeout);
+            }
+        }
+    }
+}
+
+/// Build a change event replacing only the region where `old` and `new` differ.
+pub(crate) fn incremental_change(old: &str, new: &str) -> TextDocumentContentChangeEvent {
+    let (old_bytes, new_bytes) = (old.as_bytes(), new.as_bytes());
+
+    let mut prefix = old_bytes
+        .iter()
+        .zip(new_bytes)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(prefix) || !new.is_char_boundary(prefix) {
+        prefix -= 1;
+    }
+
+    let max_suffix = old.len().min(new.len()) - prefix;
+    let mut suffix = old_bytes
+        .iter()
+        .rev()
+        .zip(new_bytes.iter().rev())
+        .take(max_suffix)
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !old.is_char_boundary(old.len() - suffix) || !new.is_char_boundary(new.len() - suffix) {
+        suffix -= 1;
+    }
+
+    TextDocumentContentChangeEvent {
+        range: Some(Range::new(
+            position_at(old, prefix),
+            position_at(old, old.len() - suffix),
+        )),
+        range_length: None,
+        text: new[prefix..new.len() - suffix].to_string(),
+    }
+}
+
+/// LSP position (UTF-16 columns) of a byte offset.
+fn position_at(text: &str, offset: usize) -> Position {
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Position::new(
+        before.matches('\n').count() as u32,
+        before[line_start..].encode_utf16().count() as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(old: &str, change: &TextDocumentContentChangeEvent) -> String {
+        let range = change.range.unwrap();
+        let offset = |p: Position| {
+            let line_start: usize = old
+                .split_inclusive('\n')
+                .take(p.line as usize)
+                .map(str::len)
+                .sum();
+            let line = &old[line_start..];
+            let mut units = 0;
+            for (i, c) in line.char_indices() {
+                if units >= p.character {
+                    return line_start + i;
+                }
+                units += c.len_utf16() as u32;
+            }
+            old.len()
+        };
+        let (start, end) = (offset(range.start), offset(range.end));
+        format!("{}{}{}", &old[..start], change.text, &old[end..])
+    }
+
+    #[test]
+    fn test_incremental_change_covers_edited_region() {
+        let old = "fn main() {\n    let x = 1;\n}\n";
+        let new = "fn main() {\n    let y = 2;\n}\n";
+        let change = incremental_change(old, new);
+
+        assert_eq!(
+            change.range,
+            Some(Range::new(Position::new(1, 8), Position::new(1, 13)))
+        );
+        assert_eq!(change.text, "y = 2");
+        assert_eq!(apply(old, &change), new);
+    }
+
+    #[test]
+    fn test_incremental_change_insert_and_delete() {
+        let old = "a\nb\n";
+        let inserted = "a\nnew\nb\n";
+        let change = incremental_change(old, inserted);
+        assert_eq!(change.text, "new\n");
+        assert_eq!(apply(old, &change), inserted);
+
+        let change = incremental_change(inserted, old);
+        assert_eq!(change.text, "");
+        assert_eq!(apply(inserted, &change), old);
+    }
+
+    #[test]
+    fn test_incremental_change_respects_char_boundaries() {
+        // "é" and "è" share their first UTF-8 byte.
+        let old = "let s = \"😀é\";";
+        let new = "let s = \"😀è\";";
+        let change = incremental_change(old, new);
+
+        assert_eq!(change.text, "è");
+        // The emoji counts as two UTF-16 code units.
+        assert_eq!(
+            change.range,
+            Some(Range::new(Position::new(0, 11), Position::new(0, 12)))
+        );
+        assert_eq!(apply(old, &change), new);
+    }
+
+    #[tokio::test]
+    async fn test_publications_freshness() {
+        let publications = DiagnosticPublications::default();
+        let path = PathBuf::from("/tmp/lib.rs");
+
+        let synced_at = publications.sequence();
+        assert!(!publications.is_fresh(&path, synced_at, 2).await);
+
+        // A publication for an older version is not fresh.
+        publications.record(path.clone(), Some(1)).await;
+        assert!(!publications.is_fresh(&path, synced_at, 2).await);
+
+        publications.record(path.clone(), Some(2)).await;
+        assert!(publications.is_fresh(&path, synced_at, 2).await);
+
+        // Servers that omit versions are fresh once they publish after the change.
+        let synced_at = publications.sequence();
+        publications.record(path.clone(), None).await;
+        assert!(publications.is_fresh(&path, synced_at, 3).await);
+    }
+}
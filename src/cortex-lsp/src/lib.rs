@@ -20,6 +20,8 @@ pub mod root_detection;
 pub mod server_config;
 pub mod workspace;
 
+pub use lsp_types;
+
 pub use client::{CachedServerCapabilities, LspClient, LspClientConfig};
 pub use diagnostics::{Diagnostic, DiagnosticSeverity};
 pub use downloader::{DownloadableServer, InstallMethod, LspDownloader, ProgressCallback};
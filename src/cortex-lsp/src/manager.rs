@@ -6,8 +6,15 @@
 
 use crate::root_detection::detect_root;
 use crate::workspace::WorkspaceManager;
-use crate::{Diagnostic, LspClient, LspError, LspServerConfig, Result, BUILTIN_SERVERS};
-use std::collections::HashMap;
+use crate::{
+    Diagnostic, DiagnosticSeverity, LspClient, LspError, LspServerConfig, Result, BUILTIN_SERVERS,
+};
+use lsp_types::{
+    CallHierarchyIncomingCall, CallHierarchyItem, CallHierarchyOutgoingCall, CodeActionOrCommand,
+    DocumentSymbol, DocumentSymbolResponse, GotoDefinitionResponse, Location, OneOf, Position,
+    Range, SymbolInformation, Url, WorkspaceEdit, WorkspaceSymbolResponse,
+};
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -29,6 +36,8 @@ pub struct LspManager {
     workspace_manager: Option<WorkspaceManager>,
     /// Whether to use multi-root mode.
     multi_root_enabled: bool,
+    /// Documents that have been opened in their language server.
+    open_documents: RwLock<HashSet<PathBuf>>,
 }
 
 impl LspManager {
@@ -41,6 +50,7 @@ impl LspManager {
             disabled_servers: RwLock::new(Vec::new()),
             workspace_manager: None,
             multi_root_enabled: false,
+            open_documents: RwLock::new(HashSet::new()),
         }
     }
 
@@ -54,6 +64,7 @@ impl LspManager {
             disabled_servers: RwLock::new(Vec::new()),
             workspace_manager: Some(WorkspaceManager::with_fallback_root(fallback)),
             multi_root_enabled: true,
+            open_documents: RwLock::new(HashSet::new()),
         }
     }
 
@@ -220,6 +231,7 @@ impl LspManager {
         if let Some(client) = self.get_client_for_file(path).await? {
             let lang_id = self.get_language_id(path).unwrap_or("plaintext");
             client.did_open(path, lang_id, content).await?;
+            self.open_documents.write().await.insert(path.to_path_buf());
         }
         Ok(())
     }
@@ -228,10 +240,268 @@ impl LspManager {
     pub async fn did_close(&self, path: &Path) -> Result<()> {
         if let Some(client) = self.get_client_for_file(path).await? {
             client.did_close(path).await?;
+            self.open_documents.write().await.remove(path);
         }
         Ok(())
     }
 
+    /// Sync a file that was just written to disk with its language server.
+    ///
+    /// Open documents get a versioned change followed by a save notification,
+    /// so diagnostics are recomputed for the new content. Files the server has
+    /// not opened are left alone; it reads them from disk when needed.
+    pub async fn did_write(&self, path: &Path, content: &str) -> Result<()> {
+        if !self.open_documents.read().await.contains(path) {
+            return Ok(());
+        }
+        let Some(client) = self.get_client_for_file(path).await? else {
+            return Ok(());
+        };
+
+        if client.document_version(path).await.is_none() {
+            // The server was restarted since the file was opened.
+            let lang_id = self.get_language_id(path).unwrap_or("plaintext");
+            return client.did_open(path, lang_id, content).await;
+        }
+        client.did_change(path, content).await?;
+        client.did_save(path).await
+    }
+
+    /// Wait for diagnostics that describe the current content of a file.
+    ///
+    /// The file is opened (starting its server if needed) and synced with
+    /// what is on disk first. Returns [`LspError::Timeout`] if the server does
+    /// not publish diagnostics for that content within `timeout`.
+    pub async fn wait_for_diagnostics(
+        &self,
+        path: &Path,
+        timeout: std::time::Duration,
+    ) -> Result<Vec<Diagnostic>> {
+        let client = self.client_with_document(path).await?;
+
+        let content = tokio::fs::read_to_string(path).await?;
+        let version = client.document_version(path).await;
+        client.did_change(path, &content).await?;
+        if client.document_version(path).await != version {
+            client.did_save(path).await?;
+        }
+
+        client.wait_for_diagnostics(path, timeout).await
+    }
+
+    /// Get the client for a file and make sure the file is open in it.
+    ///
+    /// Navigation requests need the document to be known to the server, so
+    /// files that were never opened are read from disk and opened first.
+    async fn client_with_document(&self, path: &Path) -> Result<Arc<LspClient>> {
+        let client = self.get_client_for_file(path).await?.ok_or_else(|| {
+            LspError::ServerNotFound(format!("no language server handles {}", path.display()))
+        })?;
+
+        if client.document_version(path).await.is_none() {
+            let content = tokio::fs::read_to_string(path).await?;
+            let lang_id = self.get_language_id(path).unwrap_or("plaintext");
+            client.did_open(path, lang_id, &content).await?;
+            self.open_documents.write().await.insert(path.to_path_buf());
+        }
+
+        Ok(client)
+    }
+
+    /// Find where the symbol at a position is defined.
+    pub async fn goto_definition(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<Location>> {
+        let client = self.client_with_document(path).await?;
+        let response = client.goto_definition(path, line, character).await?;
+        Ok(flatten_locations(response))
+    }
+
+    /// Find implementations of the trait or interface at a position.
+    pub async fn goto_implementation(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<Location>> {
+        let client = self.client_with_document(path).await?;
+        let response = client.goto_implementation(path, line, character).await?;
+        Ok(flatten_locations(response))
+    }
+
+    /// Find all references to the symbol at a position.
+    pub async fn find_references(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+        include_declaration: bool,
+    ) -> Result<Vec<Location>> {
+        let client = self.client_with_document(path).await?;
+        Ok(client
+            .find_references(path, line, character, include_declaration)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// List the symbols of a document, flattened in document order.
+    pub async fn document_symbols(&self, path: &Path) -> Result<Vec<SymbolInformation>> {
+        let client = self.client_with_document(path).await?;
+        let uri = Url::from_file_path(path)
+            .map_err(|_| LspError::Communication("Invalid path".into()))?;
+        Ok(match client.document_symbols(path).await? {
+            Some(DocumentSymbolResponse::Flat(symbols)) => symbols,
+            Some(DocumentSymbolResponse::Nested(symbols)) => {
+                let mut flat = Vec::new();
+                flatten_document_symbols(&uri, symbols, None, &mut flat);
+                flat
+            }
+            None => Vec::new(),
+        })
+    }
+
+    /// Search for symbols across the workspace.
+    ///
+    /// When `path` is given the query goes to the server for that file,
+    /// otherwise it goes to every running server.
+    pub async fn workspace_symbols(
+        &self,
+        query: &str,
+        path: Option<&Path>,
+    ) -> Result<Vec<SymbolInformation>> {
+        let clients = match path {
+            Some(path) => vec![self.client_with_document(path).await?],
+            None => self.running_clients().await,
+        };
+        if clients.is_empty() {
+            return Err(LspError::ServerNotFound(
+                "no language server is running".to_string(),
+            ));
+        }
+
+        let mut symbols = Vec::new();
+        for client in clients {
+            match client.workspace_symbols(query).await {
+                Ok(Some(response)) => symbols.extend(workspace_symbols_to_information(response)),
+                Ok(None) => {}
+                Err(e) => debug!("workspace/symbol failed: {}", e),
+            }
+        }
+        Ok(symbols)
+    }
+
+    /// Resolve the call hierarchy items at a position.
+    pub async fn prepare_call_hierarchy(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<CallHierarchyItem>> {
+        let client = self.client_with_document(path).await?;
+        Ok(client
+            .prepare_call_hierarchy(path, line, character)
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// Find the callers of the function at a position.
+    pub async fn incoming_calls(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<CallHierarchyIncomingCall>> {
+        let client = self.client_with_document(path).await?;
+        let items = client
+            .prepare_call_hierarchy(path, line, character)
+            .await?
+            .unwrap_or_default();
+
+        let mut calls = Vec::new();
+        for item in &items {
+            calls.extend(client.incoming_calls(item).await?.unwrap_or_default());
+        }
+        Ok(calls)
+    }
+
+    /// Find the functions called by the function at a position.
+    pub async fn outgoing_calls(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+    ) -> Result<Vec<CallHierarchyOutgoingCall>> {
+        let client = self.client_with_document(path).await?;
+        let items = client
+            .prepare_call_hierarchy(path, line, character)
+            .await?
+            .unwrap_or_default();
+
+        let mut calls = Vec::new();
+        for item in &items {
+            calls.extend(client.outgoing_calls(item).await?.unwrap_or_default());
+        }
+        Ok(calls)
+    }
+
+    /// Compute the workspace edit that renames the symbol at a position.
+    ///
+    /// The edit is returned, not applied.
+    pub async fn rename(
+        &self,
+        path: &Path,
+        line: u32,
+        character: u32,
+        new_name: &str,
+    ) -> Result<Option<WorkspaceEdit>> {
+        let client = self.client_with_document(path).await?;
+        client.rename(path, line, character, new_name).await
+    }
+
+    /// Get the code actions available for a range.
+    ///
+    /// Diagnostics published for the range are sent along so the server can
+    /// offer quick fixes for them.
+    pub async fn code_actions(
+        &self,
+        path: &Path,
+        range: Range,
+    ) -> Result<Vec<CodeActionOrCommand>> {
+        let client = self.client_with_document(path).await?;
+        let diagnostics = client
+            .get_diagnostics(path)
+            .await
+            .iter()
+            .map(to_lsp_diagnostic)
+            .filter(|d| ranges_overlap(&d.range, &range))
+            .collect();
+
+        Ok(client
+            .code_actions(
+                path,
+                range.start.line,
+                range.start.character,
+                range.end.line,
+                range.end.character,
+                diagnostics,
+            )
+            .await?
+            .unwrap_or_default())
+    }
+
+    /// All clients that are currently running, in either mode.
+    async fn running_clients(&self) -> Vec<Arc<LspClient>> {
+        let mut clients: Vec<_> = self.clients.read().await.values().cloned().collect();
+        if let Some(workspace) = self.workspace_manager() {
+            clients.extend(workspace.get_all_clients().await);
+        }
+        clients.retain(|c| c.is_server_alive());
+        clients
+    }
+
     /// Get hover information for a position.
     pub async fn hover(&self, path: &Path, line: u32, column: u32) -> Result<Option<String>> {
         if let Some(client) = self.get_client_for_file(path).await? {
@@ -436,9 +706,173 @@ impl LspManager {
     }
 }
 
+/// Normalize the three shapes of a goto response into plain locations.
+fn flatten_locations(response: Option<GotoDefinitionResponse>) -> Vec<Location> {
+    match response {
+        Some(GotoDefinitionResponse::Scalar(location)) => vec![location],
+        Some(GotoDefinitionResponse::Array(locations)) => locations,
+        Some(GotoDefinitionResponse::Link(links)) => links
+            .into_iter()
+            .map(|link| Location::new(link.target_uri, link.target_selection_range))
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+/// Flatten a document symbol tree, recording each symbol's parent as its container.
+#[allow(deprecated)] // SymbolInformation::deprecated must still be initialized
+fn flatten_document_symbols(
+    uri: &Url,
+    symbols: Vec<DocumentSymbol>,
+    container: Option<&str>,
+    out: &mut Vec<SymbolInformation>,
+) {
+    for symbol in symbols {
+        out.push(SymbolInformation {
+            name: symbol.name.clone(),
+            kind: symbol.kind,
+            tags: symbol.tags.clone(),
+            deprecated: None,
+            location: Location::new(uri.clone(), symbol.selection_range),
+            container_name: container.map(str::to_string),
+        });
+        if let Some(children) = symbol.children {
+            flatten_document_symbols(uri, children, Some(&symbol.name), out);
+        }
+    }
+}
+
+/// Convert a workspace symbol response to `SymbolInformation`.
+///
+/// Symbols that only carry a URI (no range) point at the start of the file.
+#[allow(deprecated)] // SymbolInformation::deprecated must still be initialized
+fn workspace_symbols_to_information(response: WorkspaceSymbolResponse) -> Vec<SymbolInformation> {
+    match response {
+        WorkspaceSymbolResponse::Flat(symbols) => symbols,
+        WorkspaceSymbolResponse::Nested(symbols) => symbols
+            .into_iter()
+            .map(|symbol| SymbolInformation {
+                name: symbol.name,
+                kind: symbol.kind,
+                tags: symbol.tags,
+                deprecated: None,
+                location: match symbol.location {
+                    OneOf::Left(location) => location,
+                    OneOf::Right(location) => Location::new(location.uri, Range::default()),
+                },
+                container_name: symbol.container_name,
+            })
+            .collect(),
+    }
+}
+
+/// Convert a stored diagnostic back to its protocol form.
+fn to_lsp_diagnostic(diagnostic: &Diagnostic) -> lsp_types::Diagnostic {
+    let start = Position::new(
+        diagnostic.line.saturating_sub(1),
+        diagnostic.column.saturating_sub(1),
+    );
+    let end = match (diagnostic.end_line, diagnostic.end_column) {
+        (Some(line), Some(column)) => {
+            Position::new(line.saturating_sub(1), column.saturating_sub(1))
+        }
+        _ => start,
+    };
+    let severity = match diagnostic.severity {
+        DiagnosticSeverity::Error => lsp_types::DiagnosticSeverity::ERROR,
+        DiagnosticSeverity::Warning => lsp_types::DiagnosticSeverity::WARNING,
+        DiagnosticSeverity::Information => lsp_types::DiagnosticSeverity::INFORMATION,
+        DiagnosticSeverity::Hint => lsp_types::DiagnosticSeverity::HINT,
+    };
+
+    lsp_types::Diagnostic {
+        range: Range::new(start, end),
+        severity: Some(severity),
+        code: diagnostic
+            .code
+            .clone()
+            .map(lsp_types::NumberOrString::String),
+        source: diagnostic.source.clone(),
+        message: diagnostic.message.clone(),
+        ..Default::default()
+    }
+}
+
+fn ranges_overlap(a: &Range, b: &Range) -> bool {
+    a.start <= b.end && b.start <= a.end
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use lsp_types::{LocationLink, SymbolKind};
+
+    fn range(sl: u32, sc: u32, el: u32, ec: u32) -> Range {
+        Range::new(Position::new(sl, sc), Position::new(el, ec))
+    }
+
+    #[test]
+    fn test_flatten_locations_uses_link_selection_range() {
+        let uri = Url::parse("file:///tmp/lib.rs").unwrap();
+        let links = GotoDefinitionResponse::Link(vec![LocationLink {
+            origin_selection_range: None,
+            target_uri: uri.clone(),
+            target_range: range(1, 0, 5, 1),
+            target_selection_range: range(1, 7, 1, 10),
+        }]);
+
+        let locations = flatten_locations(Some(links));
+        assert_eq!(locations, vec![Location::new(uri, range(1, 7, 1, 10))]);
+        assert!(flatten_locations(None).is_empty());
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_flatten_document_symbols_sets_container() {
+        let uri = Url::parse("file:///tmp/lib.rs").unwrap();
+        let method = DocumentSymbol {
+            name: "run".to_string(),
+            detail: None,
+            kind: SymbolKind::METHOD,
+            tags: None,
+            deprecated: None,
+            range: range(2, 4, 4, 5),
+            selection_range: range(2, 7, 2, 10),
+            children: None,
+        };
+        let parent = DocumentSymbol {
+            name: "Runner".to_string(),
+            detail: None,
+            kind: SymbolKind::STRUCT,
+            tags: None,
+            deprecated: None,
+            range: range(0, 0, 5, 1),
+            selection_range: range(0, 5, 0, 11),
+            children: Some(vec![method]),
+        };
+
+        let mut flat = Vec::new();
+        flatten_document_symbols(&uri, vec![parent], None, &mut flat);
+
+        assert_eq!(flat.len(), 2);
+        assert_eq!(flat[0].name, "Runner");
+        assert_eq!(flat[0].container_name, None);
+        assert_eq!(flat[1].name, "run");
+        assert_eq!(flat[1].container_name.as_deref(), Some("Runner"));
+        assert_eq!(flat[1].location.range, range(2, 7, 2, 10));
+    }
+
+    #[test]
+    fn test_to_lsp_diagnostic_is_zero_based() {
+        let mut diagnostic = Diagnostic::new(PathBuf::from("/tmp/lib.rs"), 3, 5, "bad".into());
+        diagnostic.end_line = Some(3);
+        diagnostic.end_column = Some(9);
+
+        let converted = to_lsp_diagnostic(&diagnostic);
+        assert_eq!(converted.range, range(2, 4, 2, 8));
+        assert!(ranges_overlap(&converted.range, &range(2, 6, 2, 6)));
+        assert!(!ranges_overlap(&converted.range, &range(3, 0, 3, 1)));
+    }
 
     #[tokio::test]
     async fn test_lsp_manager_creation() {
@@ -0,0 +1,36 @@
+//! Authorization for remote transports.
+//!
+//! Remote MCP servers are usually protected with OAuth 2.0 bearer tokens.
+//! Token storage and the authorization flow live with the caller; transports
+//! only ask an [`AuthProvider`] for the token to send and for a new one when
+//! the server rejects it.
+
+use anyhow::Result;
+use async_trait::async_trait;
+
+/// Supplies bearer tokens to remote transports.
+#[async_trait]
+pub trait AuthProvider: Send + Sync {
+    /// Token for the next request, or `None` to send no `Authorization`
+    /// header.
+    async fn token(&self) -> Result<Option<String>>;
+
+    /// Obtain a new token after the server answered `401 Unauthorized`.
+    ///
+    /// Returns `None` when no replacement is available, in which case the
+    /// request fails.
+    async fn refresh(&self) -> Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Provider for a fixed token, e.g. an API key from configuration.
+#[derive(Debug, Clone)]
+pub struct StaticToken(pub String);
+
+#[async_trait]
+impl AuthProvider for StaticToken {
+    async fn token(&self) -> Result<Option<String>> {
+        Ok(Some(self.0.clone()))
+    }
+}
@@ -14,7 +14,7 @@ impl McpClient {
     pub async fn connect(&self) -> Result<()> {
         let params = InitializeParams {
             protocol_version: cortex_mcp_types::PROTOCOL_VERSION.to_string(),
-            capabilities: cortex_mcp_types::ClientCapabilities::default(),
+            capabilities: self.transport.client_capabilities(),
             client_info: Implementation::default(),
         };
 
@@ -0,0 +1,203 @@
+//! Server-initiated requests.
+//!
+//! MCP servers may call back into the client while a request of their own is
+//! in flight: `sampling/createMessage` to borrow the client's model,
+//! `roots/list` to learn the workspace, and `elicitation/create` to ask the
+//! user for input. Transports hand such requests to a [`ServerRequestHandler`]
+//! through [`dispatch`] and write the returned response back to the server.
+
+use async_trait::async_trait;
+use cortex_mcp_types::{
+    ClientCapabilities, ElicitRequest, ElicitResult, JsonRpcError, JsonRpcNotification,
+    JsonRpcRequest, JsonRpcResponse, ListRootsResult, SamplingRequest, SamplingResult, methods,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Answers requests sent by an MCP server to the client.
+///
+/// Every method defaults to "method not found", so implementors only
+/// override what they support and advertise it in [`capabilities`].
+///
+/// [`capabilities`]: ServerRequestHandler::capabilities
+#[async_trait]
+pub trait ServerRequestHandler: Send + Sync {
+    /// Capabilities advertised to the server during initialization.
+    fn capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities::default()
+    }
+
+    /// Handle `sampling/createMessage`.
+    async fn create_message(
+        &self,
+        _request: SamplingRequest,
+    ) -> Result<SamplingResult, JsonRpcError> {
+        Err(JsonRpcError::method_not_found(
+            methods::SAMPLING_CREATE_MESSAGE,
+        ))
+    }
+
+    /// Handle `roots/list`.
+    async fn list_roots(&self) -> Result<ListRootsResult, JsonRpcError> {
+        Err(JsonRpcError::method_not_found(methods::ROOTS_LIST))
+    }
+
+    /// Handle `elicitation/create`.
+    async fn elicit(&self, _request: ElicitRequest) -> Result<ElicitResult, JsonRpcError> {
+        Err(JsonRpcError::method_not_found(methods::ELICITATION_CREATE))
+    }
+}
+
+/// Handler that supports no server-initiated requests.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoServerRequests;
+
+impl ServerRequestHandler for NoServerRequests {}
+
+/// Answer a server-initiated request.
+pub async fn dispatch(
+    handler: &dyn ServerRequestHandler,
+    request: JsonRpcRequest,
+) -> JsonRpcResponse {
+    let id = request.id.clone();
+    let result = match request.method.as_str() {
+        methods::PING => Ok(Value::Object(Default::default())),
+        methods::SAMPLING_CREATE_MESSAGE => match parse_params(request.params) {
+            Ok(params) => handler.create_message(params).await.and_then(to_value),
+            Err(e) => Err(e),
+        },
+        methods::ROOTS_LIST => handler.list_roots().await.and_then(to_value),
+        methods::ELICITATION_CREATE => match parse_params(request.params) {
+            Ok(params) => handler.elicit(params).await.and_then(to_value),
+            Err(e) => Err(e),
+        },
+        other => Err(JsonRpcError::method_not_found(other)),
+    };
+
+    match result {
+        Ok(value) => JsonRpcResponse::success(id, value),
+        Err(error) => JsonRpcResponse::error(id, error),
+    }
+}
+
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, JsonRpcError> {
+    serde_json::from_value(params.unwrap_or(Value::Null))
+        .map_err(|e| JsonRpcError::invalid_params(e.to_string()))
+}
+
+fn to_value<T: serde::Serialize>(result: T) -> Result<Value, JsonRpcError> {
+    serde_json::to_value(result).map_err(|e| JsonRpcError::internal_error(e.to_string()))
+}
+
+/// A message read from an MCP server.
+#[derive(Debug, Clone)]
+pub enum IncomingMessage {
+    /// Response to one of the client's requests.
+    Response(JsonRpcResponse),
+    /// Request the client must answer.
+    Request(JsonRpcRequest),
+    /// Notification from the server.
+    Notification(JsonRpcNotification),
+}
+
+impl IncomingMessage {
+    /// Classify a JSON-RPC message by its `method` and `id` members.
+    ///
+    /// Responses and requests cannot be told apart by deserializing alone,
+    /// since every request also deserializes as a response.
+    pub fn parse(text: &str) -> serde_json::Result<Self> {
+        let value: Value = serde_json::from_str(text)?;
+        let has_method = value.get("method").is_some();
+        let has_id = value.get("id").is_some_and(|id| !id.is_null());
+
+        Ok(match (has_method, has_id) {
+            (true, true) => Self::Request(serde_json::from_value(value)?),
+            (true, false) => Self::Notification(serde_json::from_value(value)?),
+            (false, _) => Self::Response(serde_json::from_value(value)?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_mcp_types::{Content, ErrorCode, Role, Root};
+    use serde_json::json;
+
+    struct Workspace;
+
+    #[async_trait]
+    impl ServerRequestHandler for Workspace {
+        async fn list_roots(&self) -> Result<ListRootsResult, JsonRpcError> {
+            Ok(ListRootsResult {
+                roots: vec![Root::new("file:///work").with_name("work")],
+            })
+        }
+
+        async fn create_message(
+            &self,
+            request: SamplingRequest,
+        ) -> Result<SamplingResult, JsonRpcError> {
+            let prompt = request.messages[0].content.as_text().unwrap_or_default();
+            Ok(SamplingResult {
+                model: "echo".to_string(),
+                stop_reason: None,
+                role: Role::Assistant,
+                content: Content::text(prompt.to_uppercase()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dispatch() {
+        let roots = dispatch(&Workspace, JsonRpcRequest::new(7, methods::ROOTS_LIST)).await;
+        assert_eq!(roots.id, 7.into());
+        assert_eq!(roots.result.unwrap()["roots"][0]["uri"], "file:///work");
+
+        let sampled = dispatch(
+            &Workspace,
+            JsonRpcRequest::new("s1", methods::SAMPLING_CREATE_MESSAGE).with_params(json!({
+                "messages": [{"role": "user", "content": {"type": "text", "text": "hi"}}],
+                "includeContext": "thisServer",
+                "maxTokens": 10
+            })),
+        )
+        .await;
+        let result = sampled.result.unwrap();
+        assert_eq!(result["content"]["text"], "HI");
+        assert_eq!(result["role"], "assistant");
+
+        let invalid = dispatch(
+            &Workspace,
+            JsonRpcRequest::new(8, methods::SAMPLING_CREATE_MESSAGE).with_params(json!({})),
+        )
+        .await;
+        assert_eq!(invalid.error.unwrap().code, ErrorCode::INVALID_PARAMS);
+
+        let unsupported = dispatch(
+            &Workspace,
+            JsonRpcRequest::new(9, methods::ELICITATION_CREATE)
+                .with_params(json!({"message": "Name?"})),
+        )
+        .await;
+        assert_eq!(unsupported.error.unwrap().code, ErrorCode::METHOD_NOT_FOUND);
+
+        let ping = dispatch(&NoServerRequests, JsonRpcRequest::new(10, methods::PING)).await;
+        assert!(ping.is_success());
+    }
+
+    #[test]
+    fn test_parse_incoming() {
+        let request = IncomingMessage::parse(r#"{"jsonrpc":"2.0","id":1,"method":"roots/list"}"#);
+        assert!(matches!(request, Ok(IncomingMessage::Request(r)) if r.method == "roots/list"));
+
+        let response = IncomingMessage::parse(r#"{"jsonrpc":"2.0","id":1,"result":{}}"#);
+        assert!(matches!(response, Ok(IncomingMessage::Response(_))));
+
+        let notification =
+            IncomingMessage::parse(r#"{"jsonrpc":"2.0","method":"notifications/message"}"#);
+        assert!(matches!(notification, Ok(IncomingMessage::Notification(_))));
+
+        assert!(IncomingMessage::parse("not json").is_err());
+    }
+}
@@ -1,9 +1,19 @@
 //! MCP Client implementation for Cortex
 
+pub mod auth;
 pub mod client;
 pub mod discovery;
+pub mod handler;
+pub mod stream;
+pub mod streamable_http;
 pub mod transport;
+pub mod websocket;
 
+pub use auth::{AuthProvider, StaticToken};
 pub use client::McpClient;
 pub use discovery::ToolDiscovery;
-pub use transport::Transport;
+pub use handler::{IncomingMessage, NoServerRequests, ServerRequestHandler, dispatch};
+pub use stream::StreamTransport;
+pub use streamable_http::StreamableHttpTransport;
+pub use transport::{HttpTransport, ReconnectConfig, StdioTransport, Transport};
+pub use websocket::WebSocketTransport;
@@ -0,0 +1,238 @@
+//! Line-delimited JSON-RPC over a byte stream.
+//!
+//! [`StreamTransport`] speaks the stdio framing (one JSON message per line)
+//! over any reader/writer pair. [`StdioTransport`] runs it over a subprocess'
+//! pipes; it is also how in-process servers are connected, e.g. through
+//! [`tokio::io::duplex`].
+//!
+//! [`StdioTransport`]: crate::transport::StdioTransport
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use cortex_mcp_types::{
+    ClientCapabilities, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId,
+};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::sync::{Mutex, RwLock, oneshot};
+use tracing::{debug, warn};
+
+use crate::handler::{IncomingMessage, NoServerRequests, ServerRequestHandler, dispatch};
+use crate::transport::{Transport, into_result};
+
+type Reader = Box<dyn AsyncRead + Send + Unpin>;
+type Writer = Arc<Mutex<Box<dyn AsyncWrite + Send + Unpin>>>;
+type PendingResponses = Arc<RwLock<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>;
+
+/// How long to wait for a response before giving up on a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Transport over a reader/writer pair, one JSON-RPC message per line.
+pub struct StreamTransport {
+    /// Read half, until the read loop takes it.
+    reader: Mutex<Option<Reader>>,
+    /// Write half.
+    writer: Writer,
+    /// Pending responses.
+    pending_responses: PendingResponses,
+    /// Request ID counter.
+    request_id: AtomicU64,
+    /// Whether the stream is open.
+    connected: Arc<AtomicBool>,
+    /// Handler for requests initiated by the server.
+    request_handler: Arc<dyn ServerRequestHandler>,
+    /// Number of server-initiated requests being answered.
+    active_server_requests: Arc<AtomicUsize>,
+}
+
+impl StreamTransport {
+    /// Create a transport reading from `reader` and writing to `writer`.
+    pub fn new(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+    ) -> Self {
+        Self {
+            reader: Mutex::new(Some(Box::new(reader))),
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+            pending_responses: Arc::new(RwLock::new(HashMap::new())),
+            request_id: AtomicU64::new(1),
+            connected: Arc::new(AtomicBool::new(true)),
+            request_handler: Arc::new(NoServerRequests),
+            active_server_requests: Arc::new(AtomicUsize::new(0)),
+        }
+    }
+
+    /// Set the handler for sampling, roots and elicitation requests.
+    pub fn with_request_handler(mut self, handler: Arc<dyn ServerRequestHandler>) -> Self {
+        self.request_handler = handler;
+        self
+    }
+
+    /// Start the read loop on first use.
+    async fn start(&self) {
+        let Some(reader) = self.reader.lock().await.take() else {
+            return;
+        };
+
+        tokio::spawn(read_loop(
+            reader,
+            self.writer.clone(),
+            self.pending_responses.clone(),
+            self.connected.clone(),
+            self.request_handler.clone(),
+            self.active_server_requests.clone(),
+        ));
+    }
+
+    /// Send a request and wait for its response.
+    async fn send_request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(anyhow!("MCP server closed the connection"));
+        }
+        self.start().await;
+
+        let request_id = request.id.to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending_responses
+            .write()
+            .await
+            .insert(request_id.clone(), tx);
+
+        if let Err(e) = write_line(&self.writer, &request).await {
+            self.pending_responses.write().await.remove(&request_id);
+            return Err(e);
+        }
+
+        tokio::pin!(rx);
+        loop {
+            tokio::select! {
+                response = &mut rx => {
+                    return response.context("MCP server closed the connection");
+                }
+                _ = tokio::time::sleep(REQUEST_TIMEOUT) => {
+                    // The server may be waiting on us (e.g. for the user to
+                    // approve a sampling request), so keep the clock stopped.
+                    if self.active_server_requests.load(Ordering::SeqCst) > 0 {
+                        continue;
+                    }
+                    // Forget the request so a late response is dropped
+                    self.pending_responses.write().await.remove(&request_id);
+                    return Err(anyhow!(
+                        "MCP request timed out after {}s. The in-flight request has been cancelled.",
+                        REQUEST_TIMEOUT.as_secs()
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Generate next request ID.
+    fn next_request_id(&self) -> RequestId {
+        RequestId::Number(self.request_id.fetch_add(1, Ordering::SeqCst) as i64)
+    }
+}
+
+#[async_trait]
+impl Transport for StreamTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let mut request = JsonRpcRequest::new(self.next_request_id(), method);
+        request.params = params;
+        into_result(self.send_request(request).await?)
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        if !self.connected.load(Ordering::SeqCst) {
+            return Err(anyhow!("MCP server closed the connection"));
+        }
+        self.start().await;
+
+        let mut notification = JsonRpcNotification::new(method);
+        notification.params = params;
+        write_line(&self.writer, &notification).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.connected.store(false, Ordering::SeqCst);
+        self.pending_responses.write().await.clear();
+        let _ = self.writer.lock().await.shutdown().await;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn client_capabilities(&self) -> ClientCapabilities {
+        self.request_handler.capabilities()
+    }
+}
+
+/// Read messages until the stream ends.
+async fn read_loop(
+    reader: Reader,
+    writer: Writer,
+    pending_responses: PendingResponses,
+    connected: Arc<AtomicBool>,
+    handler: Arc<dyn ServerRequestHandler>,
+    active_server_requests: Arc<AtomicUsize>,
+) {
+    let mut lines = BufReader::new(reader).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        match IncomingMessage::parse(trimmed) {
+            Ok(IncomingMessage::Response(response)) => {
+                let id = response.id.to_string();
+                if let Some(sender) = pending_responses.write().await.remove(&id) {
+                    let _ = sender.send(response);
+                }
+            }
+            Ok(IncomingMessage::Request(request)) => {
+                // Answer on a separate task: handlers may wait for the
+                // user, and the server may keep sending meanwhile.
+                debug!(method = %request.method, "Received server request");
+                let writer = writer.clone();
+                let handler = handler.clone();
+                let active = active_server_requests.clone();
+                active.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let response = dispatch(handler.as_ref(), request).await;
+                    if let Err(e) = write_line(&writer, &response).await {
+                        warn!(error = %e, "Failed to answer server request");
+                    }
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Ok(IncomingMessage::Notification(notification)) => {
+                debug!(method = %notification.method, "Received notification");
+            }
+            Err(e) => {
+                debug!(error = %e, "Ignoring malformed message from MCP server");
+            }
+        }
+    }
+
+    connected.store(false, Ordering::SeqCst);
+    // Dropping the senders fails every request still waiting
+    pending_responses.write().await.clear();
+    debug!("MCP stream closed");
+}
+
+/// Write a JSON-RPC message followed by a newline.
+async fn write_line<T: serde::Serialize>(writer: &Writer, message: &T) -> Result<()> {
+    let json = serde_json::to_string(message)?;
+    let mut writer = writer.lock().await;
+    writer.write_all(json.as_bytes()).await?;
+    writer.write_all(b"\n").await?;
+    writer.flush().await?;
+    Ok(())
+}
@@ -0,0 +1,593 @@
+//! Streamable HTTP transport.
+//!
+//! Every message is POSTed to a single endpoint. The server answers a request
+//! either with a JSON body or with an SSE stream that may carry server
+//! requests and notifications before the response. The transport:
+//!
+//! - keeps the `Mcp-Session-Id` assigned at initialization and starts a new
+//!   session when the server reports it expired
+//! - resumes interrupted streams with `Last-Event-ID`
+//! - answers server requests by POSTing the response back
+//!
+//! The optional standalone GET stream for unsolicited server messages is not
+//! opened.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+use anyhow::{Context, Result, anyhow, bail};
+use async_trait::async_trait;
+use cortex_common::create_streaming_client;
+use cortex_mcp_types::{
+    ClientCapabilities, InitializeResult, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse,
+    RequestId, methods,
+};
+use eventsource_stream::Eventsource;
+use futures::StreamExt;
+use reqwest::header::{ACCEPT, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::{Response, StatusCode};
+use serde_json::Value;
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::auth::AuthProvider;
+use crate::handler::{IncomingMessage, NoServerRequests, ServerRequestHandler, dispatch};
+use crate::transport::{ReconnectConfig, Transport, into_result};
+
+/// Header carrying the session ID.
+pub const SESSION_ID_HEADER: &str = "Mcp-Session-Id";
+/// Header carrying the negotiated protocol version.
+pub const PROTOCOL_VERSION_HEADER: &str = "MCP-Protocol-Version";
+/// Header used to resume an SSE stream.
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Streamable HTTP transport for remote MCP servers.
+pub struct StreamableHttpTransport {
+    /// HTTP client.
+    client: reqwest::Client,
+    /// MCP endpoint.
+    url: url::Url,
+    /// Custom headers.
+    headers: HashMap<String, String>,
+    /// Bearer token source.
+    auth: Option<Arc<dyn AuthProvider>>,
+    /// Session assigned by the server.
+    session_id: RwLock<Option<String>>,
+    /// Protocol version negotiated at initialization.
+    protocol_version: RwLock<Option<String>>,
+    /// Parameters of the last `initialize`, replayed for a new session.
+    init_params: RwLock<Option<Value>>,
+    /// Request ID counter.
+    request_id: AtomicU64,
+    /// Whether a session is established.
+    connected: AtomicBool,
+    /// Reconnection settings.
+    reconnect_config: ReconnectConfig,
+    /// Handler for requests initiated by the server.
+    request_handler: Arc<dyn ServerRequestHandler>,
+}
+
+impl StreamableHttpTransport {
+    /// Create a new Streamable HTTP transport.
+    pub fn new(url: url::Url) -> Self {
+        Self {
+            client: create_streaming_client().expect("Failed to create HTTP client"),
+            url,
+            headers: HashMap::new(),
+            auth: None,
+            session_id: RwLock::new(None),
+            protocol_version: RwLock::new(None),
+            init_params: RwLock::new(None),
+            request_id: AtomicU64::new(1),
+            connected: AtomicBool::new(false),
+            reconnect_config: ReconnectConfig::default(),
+            request_handler: Arc::new(NoServerRequests),
+        }
+    }
+
+    /// Add custom header.
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    /// Authorize requests with bearer tokens from `auth`.
+    pub fn with_auth(mut self, auth: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Set reconnection configuration.
+    pub fn with_reconnect(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect_config = config;
+        self
+    }
+
+    /// Set the handler for sampling, roots and elicitation requests.
+    pub fn with_request_handler(mut self, handler: Arc<dyn ServerRequestHandler>) -> Self {
+        self.request_handler = handler;
+        self
+    }
+
+    /// Session ID assigned by the server, if any.
+    pub async fn session_id(&self) -> Option<String> {
+        self.session_id.read().await.clone()
+    }
+
+    /// Build a request carrying the session and authorization headers.
+    async fn build(&self, method: reqwest::Method, token: Option<&str>) -> reqwest::RequestBuilder {
+        let mut req = self
+            .client
+            .request(method, self.url.clone())
+            .header(ACCEPT, "application/json, text/event-stream");
+
+        for (key, value) in &self.headers {
+            req = req.header(key, value);
+        }
+        if let Some(session_id) = self.session_id.read().await.as_deref() {
+            req = req.header(SESSION_ID_HEADER, session_id);
+        }
+        if let Some(version) = self.protocol_version.read().await.as_deref() {
+            req = req.header(PROTOCOL_VERSION_HEADER, version);
+        }
+        if let Some(token) = token {
+            req = req.header(AUTHORIZATION, format!("Bearer {token}"));
+        }
+        req
+    }
+
+    /// Send a request, refreshing the token once if the server rejects it.
+    async fn send(
+        &self,
+        method: reqwest::Method,
+        body: Option<&Value>,
+        extra_header: Option<(&str, &str)>,
+    ) -> Result<Response> {
+        let mut token = match &self.auth {
+            Some(auth) => auth.token().await?,
+            None => None,
+        };
+
+        let mut refreshed = false;
+        loop {
+            let mut req = self.build(method.clone(), token.as_deref()).await;
+            if let Some(body) = body {
+                req = req.header(CONTENT_TYPE, "application/json").json(body);
+            }
+            if let Some((key, value)) = extra_header {
+                req = req.header(key, value);
+            }
+
+            let response = req.send().await.context("HTTP request failed")?;
+            if response.status() != StatusCode::UNAUTHORIZED || refreshed {
+                return Ok(response);
+            }
+
+            let Some(auth) = &self.auth else {
+                bail!("MCP server requires authorization");
+            };
+            token = Some(
+                auth.refresh()
+                    .await?
+                    .ok_or_else(|| anyhow!("MCP server rejected the access token"))?,
+            );
+            refreshed = true;
+        }
+    }
+
+    /// POST a request and wait for its response.
+    async fn send_request(&self, request: &JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let body = serde_json::to_value(request)?;
+        let mut response = self.send(reqwest::Method::POST, Some(&body), None).await?;
+
+        // The server forgot our session: start a new one and try again.
+        if response.status() == StatusCode::NOT_FOUND
+            && request.method != methods::INITIALIZE
+            && self.session_id.read().await.is_some()
+        {
+            info!(url = %self.url, "MCP session expired, starting a new one");
+            self.restart_session().await?;
+            response = self.send(reqwest::Method::POST, Some(&body), None).await?;
+        }
+
+        if !response.status().is_success() {
+            bail!("HTTP request failed with status: {}", response.status());
+        }
+
+        if let Some(session_id) = response
+            .headers()
+            .get(SESSION_ID_HEADER)
+            .and_then(|v| v.to_str().ok())
+        {
+            *self.session_id.write().await = Some(session_id.to_string());
+        }
+
+        if is_event_stream(&response) {
+            self.read_stream(response, &request.id).await
+        } else {
+            response.json().await.context("Failed to parse response")
+        }
+    }
+
+    /// Read an SSE stream until the response to `id` arrives, resuming it
+    /// when the connection drops.
+    async fn read_stream(&self, response: Response, id: &RequestId) -> Result<JsonRpcResponse> {
+        let mut response = response;
+        let mut last_event_id: Option<String> = None;
+
+        loop {
+            let mut events = response.bytes_stream().eventsource();
+            while let Some(event) = events.next().await {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        warn!(error = %e, "MCP event stream interrupted");
+                        break;
+                    }
+                };
+
+                if !event.id.is_empty() {
+                    last_event_id = Some(event.id.clone());
+                }
+                if event.data.trim().is_empty() {
+                    continue;
+                }
+                if let Some(response) = self.handle_message(&event.data, id).await {
+                    return Ok(response);
+                }
+            }
+
+            // Without an event ID the server cannot replay what we missed.
+            let Some(event_id) = last_event_id.clone() else {
+                bail!("Event stream closed before the response arrived");
+            };
+            response = self
+                .reconnect_config
+                .retry("event stream resumption", || self.resume(&event_id))
+                .await?;
+        }
+    }
+
+    /// Reopen a stream after `event_id`.
+    async fn resume(&self, event_id: &str) -> Result<Response> {
+        let response = self
+            .send(
+                reqwest::Method::GET,
+                None,
+                Some((LAST_EVENT_ID_HEADER, event_id)),
+            )
+            .await?;
+
+        if !response.status().is_success() {
+            bail!(
+                "Stream resumption failed with status: {}",
+                response.status()
+            );
+        }
+        if !is_event_stream(&response) {
+            bail!("Stream resumption did not return an event stream");
+        }
+        Ok(response)
+    }
+
+    /// Handle one message from a stream, returning it if it answers `id`.
+    async fn handle_message(&self, data: &str, id: &RequestId) -> Option<JsonRpcResponse> {
+        match IncomingMessage::parse(data) {
+            Ok(IncomingMessage::Response(response)) if &response.id == id => Some(response),
+            Ok(IncomingMessage::Response(response)) => {
+                warn!(id = %response.id, "Ignoring response to unknown request");
+                None
+            }
+            Ok(IncomingMessage::Request(request)) => {
+                debug!(method = %request.method, "Received server request");
+                let response = dispatch(self.request_handler.as_ref(), request).await;
+                if let Err(e) = self.post_message(&response).await {
+                    warn!(error = %e, "Failed to answer server request");
+                }
+                None
+            }
+            Ok(IncomingMessage::Notification(notification)) => {
+                debug!(method = %notification.method, "Received notification");
+                None
+            }
+            Err(e) => {
+                debug!(error = %e, "Ignoring malformed message from MCP server");
+                None
+            }
+        }
+    }
+
+    /// POST a notification or response, which the server acknowledges
+    /// without a body.
+    async fn post_message<T: serde::Serialize>(&self, message: &T) -> Result<()> {
+        let body = serde_json::to_value(message)?;
+        let response = self.send(reqwest::Method::POST, Some(&body), None).await?;
+        if !response.status().is_success() {
+            bail!("HTTP request failed with status: {}", response.status());
+        }
+        Ok(())
+    }
+
+    /// Run the `initialize` handshake again to get a new session.
+    async fn restart_session(&self) -> Result<()> {
+        let params = self
+            .init_params
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| anyhow!("MCP session expired before initialization"))?;
+        *self.session_id.write().await = None;
+        self.connected.store(false, Ordering::SeqCst);
+
+        self.reconnect_config
+            .retry("MCP session restart", || async {
+                let request = JsonRpcRequest::new(self.next_request_id(), methods::INITIALIZE)
+                    .with_params(params.clone());
+                // Boxed: `send_request` is what called us.
+                into_result(Box::pin(self.send_request(&request)).await?)?;
+                self.post_message(&JsonRpcNotification::new(methods::INITIALIZED))
+                    .await
+            })
+            .await?;
+
+        self.connected.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Generate next request ID.
+    fn next_request_id(&self) -> RequestId {
+        RequestId::Number(self.request_id.fetch_add(1, Ordering::SeqCst) as i64)
+    }
+}
+
+#[async_trait]
+impl Transport for StreamableHttpTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let mut request = JsonRpcRequest::new(self.next_request_id(), method);
+        request.params = params.clone();
+        let result = into_result(self.send_request(&request).await?)?;
+
+        if method == methods::INITIALIZE {
+            if let Ok(init) = serde_json::from_value::<InitializeResult>(result.clone()) {
+                *self.protocol_version.write().await = Some(init.protocol_version);
+            }
+            *self.init_params.write().await = params;
+            self.connected.store(true, Ordering::SeqCst);
+        }
+        Ok(result)
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        let mut notification = JsonRpcNotification::new(method);
+        notification.params = params;
+        self.post_message(&notification).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        self.connected.store(false, Ordering::SeqCst);
+        *self.init_params.write().await = None;
+
+        // Tell the server it can drop the session; servers may refuse.
+        if self.session_id.read().await.is_some() {
+            match self.send(reqwest::Method::DELETE, None, None).await {
+                Ok(response) if response.status() == StatusCode::METHOD_NOT_ALLOWED => {}
+                Ok(response) if !response.status().is_success() => {
+                    warn!(status = %response.status(), "Failed to end MCP session");
+                }
+                Ok(_) => {}
+                Err(e) => warn!(error = %e, "Failed to end MCP session"),
+            }
+        }
+        *self.session_id.write().await = None;
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn client_capabilities(&self) -> ClientCapabilities {
+        self.request_handler.capabilities()
+    }
+}
+
+/// Whether the response body is an SSE stream.
+fn is_event_stream(response: &Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.starts_with("text/event-stream"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::StaticToken;
+    use cortex_mcp_types::{InitializeParams, JsonRpcError, ListRootsResult, Root};
+    use serde_json::json;
+    use std::time::Duration;
+    use wiremock::matchers::{body_partial_json, header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn initialize_result() -> Value {
+        json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "result": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {"tools": {}},
+                "serverInfo": {"name": "remote", "version": "1"}
+            }
+        })
+    }
+
+    fn sse(events: &[(&str, Value)]) -> ResponseTemplate {
+        let body: String = events
+            .iter()
+            .map(|(id, data)| format!("id: {id}\ndata: {data}\n\n"))
+            .collect();
+        ResponseTemplate::new(200).set_body_raw(body, "text/event-stream")
+    }
+
+    async fn initialized(server: &MockServer) -> StreamableHttpTransport {
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "initialize"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header(SESSION_ID_HEADER, "session-1")
+                    .set_body_json(initialize_result()),
+            )
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "notifications/initialized"}),
+            ))
+            .respond_with(ResponseTemplate::new(202))
+            .mount(server)
+            .await;
+
+        let transport = StreamableHttpTransport::new(server.uri().parse().unwrap())
+            .with_auth(Arc::new(StaticToken("secret".to_string())))
+            .with_reconnect(ReconnectConfig::new().with_initial_delay(Duration::from_millis(10)));
+        transport
+            .initialize(InitializeParams::default())
+            .await
+            .unwrap();
+        transport.send_initialized().await.unwrap();
+        transport
+    }
+
+    #[tokio::test]
+    async fn test_session_and_event_stream() {
+        let server = MockServer::start().await;
+        let transport = initialized(&server).await;
+        assert_eq!(transport.session_id().await.as_deref(), Some("session-1"));
+
+        Mock::given(method("POST"))
+            .and(header(SESSION_ID_HEADER, "session-1"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_partial_json(json!({"method": "tools/list"})))
+            .respond_with(sse(&[
+                (
+                    "1",
+                    json!({"jsonrpc": "2.0", "method": "notifications/message", "params": {}}),
+                ),
+                (
+                    "2",
+                    json!({"jsonrpc": "2.0", "id": 2, "result": {"tools": [
+                        {"name": "search", "inputSchema": {"type": "object"}}
+                    ]}}),
+                ),
+            ]))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let tools = transport.list_tools().await.unwrap();
+        assert_eq!(tools.tools[0].name, "search");
+
+        Mock::given(method("DELETE"))
+            .and(header(SESSION_ID_HEADER, "session-1"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        transport.close().await.unwrap();
+        assert!(!transport.is_connected());
+    }
+
+    struct Roots;
+
+    #[async_trait]
+    impl ServerRequestHandler for Roots {
+        async fn list_roots(&self) -> Result<ListRootsResult, JsonRpcError> {
+            Ok(ListRootsResult {
+                roots: vec![Root::new("file:///work")],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resumes_stream_and_answers_server_requests() {
+        let server = MockServer::start().await;
+        let transport = initialized(&server)
+            .await
+            .with_request_handler(Arc::new(Roots));
+
+        // The stream asks for roots, then drops before the result.
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "tools/call"})))
+            .respond_with(sse(&[(
+                "7",
+                json!({"jsonrpc": "2.0", "id": "r1", "method": "roots/list"}),
+            )]))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"id": "r1", "result": {"roots": [{"uri": "file:///work"}]}}),
+            ))
+            .respond_with(ResponseTemplate::new(202))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(header(LAST_EVENT_ID_HEADER, "7"))
+            .and(header(SESSION_ID_HEADER, "session-1"))
+            .respond_with(sse(&[(
+                "8",
+                json!({"jsonrpc": "2.0", "id": 2, "result": {
+                    "content": [{"type": "text", "text": "done"}]
+                }}),
+            )]))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = transport
+            .call_tool(cortex_mcp_types::CallToolParams {
+                name: "index".to_string(),
+                arguments: None,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.content[0].as_text(), Some("done"));
+    }
+
+    #[tokio::test]
+    async fn test_expired_session_is_restarted() {
+        let server = MockServer::start().await;
+        let transport = initialized(&server).await;
+
+        // The first ping hits an expired session; the retry succeeds.
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "ping"})))
+            .respond_with(ResponseTemplate::new(404))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "ping"})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(json!({"jsonrpc": "2.0", "id": 2, "result": {}})),
+            )
+            .mount(&server)
+            .await;
+
+        transport.ping().await.unwrap();
+        let initializes = server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .filter(|r| String::from_utf8_lossy(&r.body).contains("\"initialize\""))
+            .count();
+        assert_eq!(initializes, 2);
+    }
+}
@@ -1,6 +1,8 @@
 //! MCP Client Transport Layer
 //!
-//! Provides stdio and HTTP/SSE transports for MCP client communication.
+//! Provides the transport trait, the stdio and legacy HTTP transports, and
+//! the reconnection settings shared by all transports. WebSocket and
+//! Streamable HTTP transports live in their own modules.
 
 use std::collections::HashMap;
 use std::process::Stdio;
@@ -12,56 +14,124 @@ use anyhow::{Context, Result, anyhow};
 use async_trait::async_trait;
 use cortex_common::create_default_client;
 use cortex_mcp_types::{
-    CallToolParams, CallToolResult, GetPromptParams, GetPromptResult, InitializeParams,
-    InitializeResult, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, ListPromptsResult,
-    ListResourcesResult, ListToolsResult, ReadResourceParams, ReadResourceResult, RequestId,
-    methods,
+    CallToolParams, CallToolResult, ClientCapabilities, GetPromptParams, GetPromptResult,
+    InitializeParams, InitializeResult, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse,
+    ListPromptsResult, ListResourcesResult, ListToolsResult, ReadResourceParams,
+    ReadResourceResult, RequestId, methods,
 };
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use serde_json::Value;
+use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::process::{Child, Command};
 use tokio::sync::{Mutex, RwLock};
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
+use crate::auth::AuthProvider;
+use crate::handler::{NoServerRequests, ServerRequestHandler};
+use crate::stream::StreamTransport;
+
 // ============================================================================
 // Transport Trait
 // ============================================================================
 
 /// Transport layer for MCP client communication.
+///
+/// Transports implement [`request`](Transport::request) and
+/// [`notify`](Transport::notify); the typed methods are built on top of them.
 #[async_trait]
 pub trait Transport: Send + Sync {
+    /// Send a request and return its result.
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value>;
+
+    /// Send a notification (no response expected).
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()>;
+
     /// Initialize the connection.
-    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult>;
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let result = self
+            .request(methods::INITIALIZE, Some(serde_json::to_value(params)?))
+            .await
+            .context("Initialize failed")?;
+        serde_json::from_value(result).context("Failed to parse initialize result")
+    }
 
     /// Send initialized notification.
-    async fn send_initialized(&self) -> Result<()>;
+    async fn send_initialized(&self) -> Result<()> {
+        self.notify(methods::INITIALIZED, None).await
+    }
 
     /// List available tools.
-    async fn list_tools(&self) -> Result<ListToolsResult>;
+    async fn list_tools(&self) -> Result<ListToolsResult> {
+        let result = self
+            .request(methods::TOOLS_LIST, None)
+            .await
+            .context("List tools failed")?;
+        serde_json::from_value(result).context("Failed to parse list tools result")
+    }
 
     /// Execute a tool.
-    async fn call_tool(&self, params: CallToolParams) -> Result<CallToolResult>;
+    async fn call_tool(&self, params: CallToolParams) -> Result<CallToolResult> {
+        let result = self
+            .request(methods::TOOLS_CALL, Some(serde_json::to_value(params)?))
+            .await
+            .context("Call tool failed")?;
+        serde_json::from_value(result).context("Failed to parse call tool result")
+    }
 
     /// List available resources.
-    async fn list_resources(&self) -> Result<ListResourcesResult>;
+    async fn list_resources(&self) -> Result<ListResourcesResult> {
+        let result = self
+            .request(methods::RESOURCES_LIST, None)
+            .await
+            .context("List resources failed")?;
+        serde_json::from_value(result).context("Failed to parse list resources result")
+    }
 
     /// Read a resource.
-    async fn read_resource(&self, params: ReadResourceParams) -> Result<ReadResourceResult>;
+    async fn read_resource(&self, params: ReadResourceParams) -> Result<ReadResourceResult> {
+        let result = self
+            .request(methods::RESOURCES_READ, Some(serde_json::to_value(params)?))
+            .await
+            .context("Read resource failed")?;
+        serde_json::from_value(result).context("Failed to parse read resource result")
+    }
 
     /// List available prompts.
-    async fn list_prompts(&self) -> Result<ListPromptsResult>;
+    async fn list_prompts(&self) -> Result<ListPromptsResult> {
+        let result = self
+            .request(methods::PROMPTS_LIST, None)
+            .await
+            .context("List prompts failed")?;
+        serde_json::from_value(result).context("Failed to parse list prompts result")
+    }
 
     /// Get a prompt.
-    async fn get_prompt(&self, params: GetPromptParams) -> Result<GetPromptResult>;
+    async fn get_prompt(&self, params: GetPromptParams) -> Result<GetPromptResult> {
+        let result = self
+            .request(methods::PROMPTS_GET, Some(serde_json::to_value(params)?))
+            .await
+            .context("Get prompt failed")?;
+        serde_json::from_value(result).context("Failed to parse get prompt result")
+    }
 
     /// Send a ping.
-    async fn ping(&self) -> Result<()>;
+    async fn ping(&self) -> Result<()> {
+        self.request(methods::PING, None)
+            .await
+            .context("Ping failed")?;
+        Ok(())
+    }
 
     /// Close the connection.
     async fn close(&self) -> Result<()>;
 
     /// Check if the transport is connected.
     fn is_connected(&self) -> bool;
+
+    /// Capabilities to advertise for server-initiated requests.
+    fn client_capabilities(&self) -> ClientCapabilities {
+        ClientCapabilities::default()
+    }
 }
 
 // ============================================================================
@@ -69,13 +139,15 @@ pub trait Transport: Send + Sync {
 // ============================================================================
 
 /// Stdio transport using subprocess communication.
+///
+/// Messages are exchanged with [`StreamTransport`] over the subprocess'
+/// stdin and stdout. If the subprocess exits after initialization, the next
+/// request respawns it and replays the handshake.
 pub struct StdioTransport {
     /// Child process.
-    process: Arc<Mutex<Option<Child>>>,
-    /// Request ID counter.
-    request_id: AtomicU64,
-    /// Whether the transport is connected.
-    connected: AtomicBool,
+    process: Mutex<Option<Child>>,
+    /// Stream over the child's pipes.
+    stream: std::sync::RwLock<Option<Arc<StreamTransport>>>,
     /// Command to execute.
     command: String,
     /// Command arguments.
@@ -84,28 +156,39 @@ pub struct StdioTransport {
     cwd: Option<String>,
     /// Environment variables.
     env: HashMap<String, String>,
-    /// Pending responses.
-    pending_responses: Arc<RwLock<HashMap<String, tokio::sync::oneshot::Sender<JsonRpcResponse>>>>,
+    /// Whether to start from an empty environment.
+    clear_env: bool,
     /// Reconnection settings.
     reconnect_config: ReconnectConfig,
+    /// Handler for requests initiated by the server.
+    request_handler: Arc<dyn ServerRequestHandler>,
+    /// Parameters of the last initialize request, replayed on reconnect.
+    init_params: RwLock<Option<Value>>,
 }
 
 impl StdioTransport {
     /// Create a new stdio transport.
     pub fn new(command: impl Into<String>, args: Vec<String>) -> Self {
         Self {
-            process: Arc::new(Mutex::new(None)),
-            request_id: AtomicU64::new(1),
-            connected: AtomicBool::new(false),
+            process: Mutex::new(None),
+            stream: std::sync::RwLock::new(None),
             command: command.into(),
             args,
             cwd: None,
             env: HashMap::new(),
-            pending_responses: Arc::new(RwLock::new(HashMap::new())),
+            clear_env: false,
             reconnect_config: ReconnectConfig::default(),
+            request_handler: Arc::new(NoServerRequests),
+            init_params: RwLock::new(None),
         }
     }
 
+    /// Set the handler for sampling, roots and elicitation requests.
+    pub fn with_request_handler(mut self, handler: Arc<dyn ServerRequestHandler>) -> Self {
+        self.request_handler = handler;
+        self
+    }
+
     /// Set working directory.
     pub fn with_cwd(mut self, cwd: impl Into<String>) -> Self {
         self.cwd = Some(cwd.into());
@@ -118,18 +201,37 @@ impl StdioTransport {
         self
     }
 
+    /// Don't inherit the parent's environment; only variables added with
+    /// [`with_env`](Self::with_env) are passed to the subprocess.
+    pub fn with_clean_env(mut self) -> Self {
+        self.clear_env = true;
+        self
+    }
+
     /// Set reconnection configuration.
     pub fn with_reconnect(mut self, config: ReconnectConfig) -> Self {
         self.reconnect_config = config;
         self
     }
 
-    /// Connect to the subprocess.
-    async fn connect(&self) -> Result<()> {
+    /// The current stream, if the subprocess is running.
+    fn stream(&self) -> Option<Arc<StreamTransport>> {
+        self.stream
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    fn set_stream(&self, stream: Option<Arc<StreamTransport>>) {
+        *self.stream.write().unwrap_or_else(|e| e.into_inner()) = stream;
+    }
+
+    /// Spawn the subprocess.
+    async fn connect(&self) -> Result<Arc<StreamTransport>> {
         let mut process_guard = self.process.lock().await;
 
-        if process_guard.is_some() {
-            return Ok(());
+        if let Some(stream) = self.stream().filter(|s| s.is_connected()) {
+            return Ok(stream);
         }
 
         debug!(command = %self.command, args = ?self.args, "Starting MCP subprocess");
@@ -145,40 +247,20 @@ impl StdioTransport {
             cmd.current_dir(cwd);
         }
 
+        if self.clear_env {
+            cmd.env_clear();
+        }
         for (key, value) in &self.env {
             cmd.env(key, value);
         }
 
         let mut child = cmd.spawn().context("Failed to spawn subprocess")?;
 
-        // Start reading stdout in background
         let stdout = child.stdout.take().context("Failed to get stdout")?;
-        let pending_responses = self.pending_responses.clone();
-        tokio::spawn(async move {
-            let reader = BufReader::new(stdout);
-            let mut lines = reader.lines();
-
-            while let Ok(Some(line)) = lines.next_line().await {
-                let trimmed = line.trim();
-                if trimmed.is_empty() {
-                    continue;
-                }
-
-                // Try to parse as response
-                if let Ok(response) = serde_json::from_str::<JsonRpcResponse>(trimmed) {
-                    let id = response.id.to_string();
-                    if let Some(sender) = pending_responses.write().await.remove(&id) {
-                        let _ = sender.send(response);
-                    }
-                }
-                // Try to parse as notification
-                else if let Ok(notification) =
-                    serde_json::from_str::<JsonRpcNotification>(trimmed)
-                {
-                    debug!(method = %notification.method, "Received notification");
-                }
-            }
-        });
+        let stdin = child.stdin.take().context("Failed to get stdin")?;
+        let stream = Arc::new(
+            StreamTransport::new(stdout, stdin).with_request_handler(self.request_handler.clone()),
+        );
 
         // Start reading stderr in background
         if let Some(stderr) = child.stderr.take() {
@@ -193,257 +275,102 @@ impl StdioTransport {
         }
 
         *process_guard = Some(child);
-        self.connected.store(true, Ordering::SeqCst);
+        self.set_stream(Some(stream.clone()));
 
         info!(command = %self.command, "MCP subprocess connected");
-        Ok(())
+        Ok(stream)
     }
 
-    /// Reconnect with exponential backoff.
+    /// Respawn the subprocess and replay the handshake, with exponential
+    /// backoff.
     ///
     /// Properly cleans up existing connections before each attempt to prevent
     /// file descriptor leaks (#2198).
-    #[allow(dead_code)]
-    async fn reconnect(&self) -> Result<()> {
-        if !self.reconnect_config.enabled {
-            return Err(anyhow!("Reconnection disabled"));
-        }
-
-        let mut attempt = 0;
-        let mut delay = self.reconnect_config.initial_delay;
-
-        while attempt < self.reconnect_config.max_attempts {
-            attempt += 1;
-            info!(
-                attempt,
-                max = self.reconnect_config.max_attempts,
-                "Attempting reconnection"
-            );
-
-            // Clean up any existing connection before attempting reconnect
-            // This prevents file descriptor leaks on repeated failures (#2198)
-            {
-                let mut process_guard = self.process.lock().await;
-                if let Some(mut child) = process_guard.take() {
-                    // Kill the process and wait for it to clean up
-                    let _ = child.kill().await;
-                    // Wait a short time for resources to be released
-                    drop(child);
-                }
-                self.connected.store(false, Ordering::SeqCst);
-            }
-
-            // Clear any stale pending responses
-            self.pending_responses.write().await.clear();
-
-            match self.connect().await {
-                Ok(()) => {
-                    info!("Reconnection successful");
-                    return Ok(());
-                }
-                Err(e) => {
-                    error!(error = %e, attempt, "Reconnection failed");
-                    if attempt < self.reconnect_config.max_attempts {
-                        sleep(delay).await;
-                        delay = (delay * 2).min(self.reconnect_config.max_delay);
-                    }
-                }
-            }
-        }
-
-        Err(anyhow!("Failed to reconnect after {} attempts", attempt))
-    }
-
-    /// Send a request and wait for response.
-    async fn send_request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
-        // Ensure connected
-        if !self.connected.load(Ordering::SeqCst) {
-            self.connect().await?;
-        }
-
-        let request_id = request.id.to_string();
-        let (tx, rx) = tokio::sync::oneshot::channel();
-
-        // Register pending response
-        self.pending_responses
-            .write()
+    async fn reconnect(&self, init_params: Value) -> Result<Arc<StreamTransport>> {
+        self.reconnect_config
+            .retry("MCP subprocess reconnection", || async {
+                // Clean up any existing connection before attempting reconnect
+                // This prevents file descriptor leaks on repeated failures (#2198)
+                self.kill().await;
+
+                let stream = self.connect().await?;
+                stream
+                    .request(methods::INITIALIZE, Some(init_params.clone()))
+                    .await?;
+                stream.notify(methods::INITIALIZED, None).await?;
+                info!("Reconnection successful");
+                Ok(stream)
+            })
             .await
-            .insert(request_id.clone(), tx);
-
-        // Send request
-        let request_json = serde_json::to_string(&request)?;
-        let mut process_guard = self.process.lock().await;
+    }
 
-        if let Some(ref mut child) = *process_guard {
-            if let Some(ref mut stdin) = child.stdin {
-                stdin.write_all(request_json.as_bytes()).await?;
-                stdin.write_all(b"\n").await?;
-                stdin.flush().await?;
-            } else {
-                return Err(anyhow!("Subprocess stdin not available"));
-            }
-        } else {
-            return Err(anyhow!("Subprocess not running"));
+    /// Get a live stream, spawning or respawning the subprocess as needed.
+    async fn ensure_connected(&self) -> Result<Arc<StreamTransport>> {
+        if let Some(stream) = self.stream().filter(|s| s.is_connected()) {
+            return Ok(stream);
         }
 
-        drop(process_guard);
-
-        // Wait for response with timeout
-        // Use tokio::select! to properly cancel the pending request on timeout
-        let timeout_future = tokio::time::sleep(Duration::from_secs(30));
-        tokio::pin!(timeout_future);
-
-        tokio::select! {
-            response = rx => {
-                response.context("Response channel closed")
-            }
-            _ = &mut timeout_future => {
-                // On timeout, remove the pending request to prevent orphaned operations
-                // This ensures the request handler won't try to send a response later
-                self.pending_responses.write().await.remove(&request_id);
-                Err(anyhow!("MCP tool request timed out after 30s. The in-flight request has been cancelled."))
-            }
+        match self.init_params.read().await.clone() {
+            // The subprocess went away after the handshake
+            Some(params) => self.reconnect(params).await,
+            None => self.connect().await,
         }
     }
 
-    /// Send a notification (no response expected).
-    async fn send_notification(&self, notification: JsonRpcNotification) -> Result<()> {
-        // Ensure connected
-        if !self.connected.load(Ordering::SeqCst) {
-            self.connect().await?;
+    /// Kill the subprocess, if any.
+    async fn kill(&self) {
+        let stream = self
+            .stream
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .take();
+        if let Some(stream) = stream {
+            // Fails any request still waiting on the old process
+            let _ = stream.close().await;
         }
-
-        let notification_json = serde_json::to_string(&notification)?;
-        let mut process_guard = self.process.lock().await;
-
-        if let Some(ref mut child) = *process_guard
-            && let Some(ref mut stdin) = child.stdin
-        {
-            stdin.write_all(notification_json.as_bytes()).await?;
-            stdin.write_all(b"\n").await?;
-            stdin.flush().await?;
+        if let Some(mut child) = self.process.lock().await.take() {
+            let _ = child.kill().await;
+            info!("MCP subprocess terminated");
         }
-
-        Ok(())
-    }
-
-    /// Generate next request ID.
-    fn next_request_id(&self) -> RequestId {
-        RequestId::Number(self.request_id.fetch_add(1, Ordering::SeqCst) as i64)
     }
 }
 
 #[async_trait]
 impl Transport for StdioTransport {
-    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::INITIALIZE)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Initialize failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse initialize result")
-    }
-
-    async fn send_initialized(&self) -> Result<()> {
-        let notification = JsonRpcNotification::new(methods::INITIALIZED);
-        self.send_notification(notification).await
-    }
-
-    async fn list_tools(&self) -> Result<ListToolsResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::TOOLS_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List tools failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list tools result")
-    }
-
-    async fn call_tool(&self, params: CallToolParams) -> Result<CallToolResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::TOOLS_CALL)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Call tool failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse call tool result")
-    }
-
-    async fn list_resources(&self) -> Result<ListResourcesResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::RESOURCES_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List resources failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list resources result")
-    }
-
-    async fn read_resource(&self, params: ReadResourceParams) -> Result<ReadResourceResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::RESOURCES_READ)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Read resource failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse read resource result")
-    }
-
-    async fn list_prompts(&self) -> Result<ListPromptsResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PROMPTS_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List prompts failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list prompts result")
-    }
-
-    async fn get_prompt(&self, params: GetPromptParams) -> Result<GetPromptResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PROMPTS_GET)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Get prompt failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse get prompt result")
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let stream = self.ensure_connected().await?;
+        if method == methods::INITIALIZE {
+            *self.init_params.write().await = params.clone();
+        }
+        stream.request(method, params).await
     }
 
-    async fn ping(&self) -> Result<()> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PING);
-        let response = self.send_request(request).await?;
-        response
-            .into_result()
-            .map_err(|e| anyhow!("Ping failed: {}", e))?;
-        Ok(())
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        self.ensure_connected().await?.notify(method, params).await
     }
 
     async fn close(&self) -> Result<()> {
-        self.connected.store(false, Ordering::SeqCst);
-        let mut process_guard = self.process.lock().await;
-
-        if let Some(mut child) = process_guard.take() {
-            child.kill().await.context("Failed to kill subprocess")?;
-            info!("MCP subprocess terminated");
-        }
-
+        *self.init_params.write().await = None;
+        self.kill().await;
         Ok(())
     }
 
     fn is_connected(&self) -> bool {
-        self.connected.load(Ordering::SeqCst)
+        self.stream().is_some_and(|s| s.is_connected())
+    }
+
+    fn client_capabilities(&self) -> ClientCapabilities {
+        self.request_handler.capabilities()
     }
 }
 
+/// Turn a JSON-RPC response into its result, or an error for error responses.
+pub(crate) fn into_result(response: JsonRpcResponse) -> Result<Value> {
+    response
+        .into_result()
+        .map_err(|e| anyhow!("MCP error: {e}"))
+}
+
 // ============================================================================
 // HTTP/SSE Transport
 // ============================================================================
@@ -462,6 +389,8 @@ pub struct HttpTransport {
     reconnect_config: ReconnectConfig,
     /// Custom headers.
     headers: HashMap<String, String>,
+    /// Source of bearer tokens.
+    auth: Option<Arc<dyn AuthProvider>>,
 }
 
 impl HttpTransport {
@@ -474,6 +403,7 @@ impl HttpTransport {
             connected: AtomicBool::new(false),
             reconnect_config: ReconnectConfig::default(),
             headers: HashMap::new(),
+            auth: None,
         }
     }
 
@@ -489,16 +419,52 @@ impl HttpTransport {
         self
     }
 
-    /// Send a JSON-RPC request over HTTP.
-    async fn send_request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
-        let mut req = self.client.post(self.base_url.clone()).json(&request);
+    /// Authorize requests with bearer tokens from `auth`.
+    pub fn with_auth(mut self, auth: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// POST a JSON-RPC message, refreshing the token once if the server
+    /// rejects it.
+    async fn post<T: serde::Serialize>(&self, message: &T) -> Result<reqwest::Response> {
+        let mut token = match &self.auth {
+            Some(auth) => auth.token().await?,
+            None => None,
+        };
+
+        let mut refreshed = false;
+        loop {
+            let mut req = self.client.post(self.base_url.clone()).json(message);
+
+            // Add custom headers
+            for (key, value) in &self.headers {
+                req = req.header(key, value);
+            }
+            if let Some(ref token) = token {
+                req = req.bearer_auth(token);
+            }
 
-        // Add custom headers
-        for (key, value) in &self.headers {
-            req = req.header(key, value);
+            let response = req.send().await.context("HTTP request failed")?;
+            if response.status() != reqwest::StatusCode::UNAUTHORIZED || refreshed {
+                return Ok(response);
+            }
+
+            let Some(auth) = &self.auth else {
+                return Err(anyhow!("MCP server requires authorization"));
+            };
+            token = Some(
+                auth.refresh()
+                    .await?
+                    .ok_or_else(|| anyhow!("MCP server rejected the access token"))?,
+            );
+            refreshed = true;
         }
+    }
 
-        let response = req.send().await.context("HTTP request failed")?;
+    /// Send a JSON-RPC request over HTTP.
+    async fn send_request(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let response = self.post(&request).await?;
 
         if !response.status().is_success() {
             return Err(anyhow!(
@@ -565,96 +531,27 @@ impl HttpTransport {
 
 #[async_trait]
 impl Transport for HttpTransport {
-    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::INITIALIZE)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Initialize failed: {}", e))?;
-
-        self.connected.store(true, Ordering::SeqCst);
-        serde_json::from_value(result).context("Failed to parse initialize result")
-    }
-
-    async fn send_initialized(&self) -> Result<()> {
-        // HTTP transport doesn't need to send initialized notification
-        Ok(())
-    }
-
-    async fn list_tools(&self) -> Result<ListToolsResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::TOOLS_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List tools failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list tools result")
-    }
-
-    async fn call_tool(&self, params: CallToolParams) -> Result<CallToolResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::TOOLS_CALL)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Call tool failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse call tool result")
-    }
-
-    async fn list_resources(&self) -> Result<ListResourcesResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::RESOURCES_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List resources failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list resources result")
-    }
-
-    async fn read_resource(&self, params: ReadResourceParams) -> Result<ReadResourceResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::RESOURCES_READ)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Read resource failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse read resource result")
-    }
-
-    async fn list_prompts(&self) -> Result<ListPromptsResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PROMPTS_LIST);
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("List prompts failed: {}", e))?;
-
-        serde_json::from_value(result).context("Failed to parse list prompts result")
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        let mut request = JsonRpcRequest::new(self.next_request_id(), method);
+        request.params = params;
+        let result = into_result(self.send_request(request).await?)?;
+        if method == methods::INITIALIZE {
+            self.connected.store(true, Ordering::SeqCst);
+        }
+        Ok(result)
     }
 
-    async fn get_prompt(&self, params: GetPromptParams) -> Result<GetPromptResult> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PROMPTS_GET)
-            .with_params(serde_json::to_value(params)?);
-
-        let response = self.send_request(request).await?;
-        let result = response
-            .into_result()
-            .map_err(|e| anyhow!("Get prompt failed: {}", e))?;
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        let mut notification = JsonRpcNotification::new(method);
+        notification.params = params;
 
-        serde_json::from_value(result).context("Failed to parse get prompt result")
-    }
-
-    async fn ping(&self) -> Result<()> {
-        let request = JsonRpcRequest::new(self.next_request_id(), methods::PING);
-        let response = self.send_request(request).await?;
-        response
-            .into_result()
-            .map_err(|e| anyhow!("Ping failed: {}", e))?;
+        let response = self.post(&notification).await?;
+        if !response.status().is_success() {
+            return Err(anyhow!(
+                "HTTP notification failed with status: {}",
+                response.status()
+            ));
+        }
         Ok(())
     }
 
@@ -727,6 +624,39 @@ impl ReconnectConfig {
         self.max_delay = delay;
         self
     }
+
+    /// Run `attempt` with exponential backoff until it succeeds or the
+    /// attempts run out.
+    pub(crate) async fn retry<T, F, Fut>(&self, what: &str, mut attempt: F) -> Result<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        if !self.enabled {
+            return Err(anyhow!("Reconnection disabled"));
+        }
+
+        let mut delay = self.initial_delay;
+        let mut count = 0;
+        loop {
+            count += 1;
+            info!(
+                attempt = count,
+                max = self.max_attempts,
+                "Attempting {what}"
+            );
+
+            match attempt().await {
+                Ok(value) => return Ok(value),
+                Err(e) if count < self.max_attempts => {
+                    error!(error = %e, attempt = count, "{what} failed");
+                    sleep(delay).await;
+                    delay = (delay * 2).min(self.max_delay);
+                }
+                Err(e) => return Err(e.context(format!("{what} failed after {count} attempts"))),
+            }
+        }
+    }
 }
 
 // ============================================================================
@@ -755,4 +685,44 @@ mod tests {
         let config = ReconnectConfig::disabled();
         assert!(!config.enabled);
     }
+
+    struct Roots;
+
+    #[async_trait]
+    impl ServerRequestHandler for Roots {
+        fn capabilities(&self) -> ClientCapabilities {
+            ClientCapabilities {
+                roots: Some(Default::default()),
+                ..Default::default()
+            }
+        }
+
+        async fn list_roots(
+            &self,
+        ) -> Result<cortex_mcp_types::ListRootsResult, cortex_mcp_types::JsonRpcError> {
+            Ok(cortex_mcp_types::ListRootsResult {
+                roots: vec![cortex_mcp_types::Root::new("file:///work")],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stdio_answers_server_requests() {
+        // The server asks for roots before answering, then echoes our answer.
+        let script = r#"read request
+echo '{"jsonrpc":"2.0","id":"srv-1","method":"roots/list"}'
+read answer
+printf '{"jsonrpc":"2.0","id":1,"result":{"answer":%s}}\n' "$answer"
+"#;
+        let transport = StdioTransport::new("sh", vec!["-c".to_string(), script.to_string()])
+            .with_request_handler(Arc::new(Roots));
+        assert!(transport.client_capabilities().roots.is_some());
+
+        let result = transport.request(methods::TOOLS_LIST, None).await.unwrap();
+        let answer = &result["answer"];
+        assert_eq!(answer["id"], "srv-1");
+        assert_eq!(answer["result"]["roots"][0]["uri"], "file:///work");
+
+        transport.close().await.unwrap();
+    }
 }
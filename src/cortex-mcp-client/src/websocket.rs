@@ -0,0 +1,474 @@
+//! WebSocket transport.
+//!
+//! Every JSON-RPC message travels as one text frame. A background task reads
+//! frames, completes pending requests and answers server-initiated requests
+//! on the same socket. When the socket drops after initialization, the next
+//! request reconnects with backoff and replays the `initialize` handshake.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use async_trait::async_trait;
+use cortex_mcp_types::{
+    ClientCapabilities, JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, RequestId, methods,
+};
+use futures::stream::{SplitSink, SplitStream};
+use futures::{SinkExt, StreamExt};
+use serde_json::Value;
+use tokio::net::TcpStream;
+use tokio::sync::{Mutex, RwLock, oneshot};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::http::{HeaderName, HeaderValue, StatusCode, header};
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use tracing::{debug, info, warn};
+
+use crate::auth::AuthProvider;
+use crate::handler::{IncomingMessage, NoServerRequests, ServerRequestHandler, dispatch};
+use crate::transport::{ReconnectConfig, Transport, into_result};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+type Sink = Arc<Mutex<Option<SplitSink<Socket, Message>>>>;
+type PendingResponses = Arc<RwLock<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>;
+
+/// How long to wait for a response before giving up on a request.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// WebSocket transport for remote MCP servers.
+pub struct WebSocketTransport {
+    /// Server URL (`ws://` or `wss://`).
+    url: url::Url,
+    /// Custom headers sent with the handshake.
+    headers: HashMap<String, String>,
+    /// Bearer token source.
+    auth: Option<Arc<dyn AuthProvider>>,
+    /// Write half of the socket.
+    sink: Sink,
+    /// Pending responses.
+    pending_responses: PendingResponses,
+    /// Request ID counter.
+    request_id: AtomicU64,
+    /// Whether the socket is open.
+    connected: Arc<AtomicBool>,
+    /// Reconnection settings.
+    reconnect_config: ReconnectConfig,
+    /// Handler for requests initiated by the server.
+    request_handler: Arc<dyn ServerRequestHandler>,
+    /// Number of server-initiated requests being answered.
+    active_server_requests: Arc<AtomicUsize>,
+    /// Parameters of the last `initialize`, replayed after reconnecting.
+    init_params: RwLock<Option<Value>>,
+}
+
+impl WebSocketTransport {
+    /// Create a new WebSocket transport.
+    pub fn new(url: url::Url) -> Self {
+        Self {
+            url,
+            headers: HashMap::new(),
+            auth: None,
+            sink: Arc::new(Mutex::new(None)),
+            pending_responses: Arc::new(RwLock::new(HashMap::new())),
+            request_id: AtomicU64::new(1),
+            connected: Arc::new(AtomicBool::new(false)),
+            reconnect_config: ReconnectConfig::default(),
+            request_handler: Arc::new(NoServerRequests),
+            active_server_requests: Arc::new(AtomicUsize::new(0)),
+            init_params: RwLock::new(None),
+        }
+    }
+
+    /// Add custom header.
+    pub fn with_header(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.insert(key.into(), value.into());
+        self
+    }
+
+    /// Authorize the handshake with bearer tokens from `auth`.
+    pub fn with_auth(mut self, auth: Arc<dyn AuthProvider>) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Set reconnection configuration.
+    pub fn with_reconnect(mut self, config: ReconnectConfig) -> Self {
+        self.reconnect_config = config;
+        self
+    }
+
+    /// Set the handler for sampling, roots and elicitation requests.
+    pub fn with_request_handler(mut self, handler: Arc<dyn ServerRequestHandler>) -> Self {
+        self.request_handler = handler;
+        self
+    }
+
+    /// Open the socket and start reading from it.
+    async fn connect(&self) -> Result<()> {
+        let token = match &self.auth {
+            Some(auth) => auth.token().await?,
+            None => None,
+        };
+
+        let socket = match self.handshake(token.as_deref()).await {
+            Err(WsError::Http(response)) if response.status() == StatusCode::UNAUTHORIZED => {
+                let auth = self
+                    .auth
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("MCP server requires authorization"))?;
+                let token = auth
+                    .refresh()
+                    .await?
+                    .ok_or_else(|| anyhow!("MCP server rejected the access token"))?;
+                self.handshake(Some(&token)).await
+            }
+            other => other,
+        }
+        .with_context(|| format!("WebSocket connection to {} failed", self.url))?;
+
+        let (sink, stream) = socket.split();
+        *self.sink.lock().await = Some(sink);
+        self.connected.store(true, Ordering::SeqCst);
+
+        tokio::spawn(read_loop(
+            stream,
+            self.sink.clone(),
+            self.pending_responses.clone(),
+            self.connected.clone(),
+            self.request_handler.clone(),
+            self.active_server_requests.clone(),
+        ));
+
+        info!(url = %self.url, "MCP WebSocket connected");
+        Ok(())
+    }
+
+    /// Perform the WebSocket handshake.
+    async fn handshake(&self, token: Option<&str>) -> Result<Socket, WsError> {
+        let mut request = self.url.as_str().into_client_request()?;
+        let headers = request.headers_mut();
+        for (key, value) in &self.headers {
+            match (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                (Ok(name), Ok(value)) => {
+                    headers.insert(name, value);
+                }
+                _ => warn!(header = %key, "Skipping invalid WebSocket header"),
+            }
+        }
+        if let Some(token) = token
+            && let Ok(value) = HeaderValue::from_str(&format!("Bearer {token}"))
+        {
+            headers.insert(header::AUTHORIZATION, value);
+        }
+
+        let (socket, _) = tokio_tungstenite::connect_async(request).await?;
+        Ok(socket)
+    }
+
+    /// Reconnect and replay the `initialize` handshake.
+    async fn reconnect(&self, init_params: Value) -> Result<()> {
+        self.reconnect_config
+            .retry("WebSocket reconnection", || async {
+                self.connect().await?;
+                let request = JsonRpcRequest::new(self.next_request_id(), methods::INITIALIZE)
+                    .with_params(init_params.clone());
+                into_result(self.roundtrip(request).await?)?;
+                self.send_message(&JsonRpcNotification::new(methods::INITIALIZED))
+                    .await
+            })
+            .await
+    }
+
+    /// Make sure the socket is open, reconnecting if it dropped.
+    async fn ensure_connected(&self) -> Result<()> {
+        if self.connected.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let init_params = self.init_params.read().await.clone();
+        match init_params {
+            Some(params) => self.reconnect(params).await,
+            None => self.connect().await,
+        }
+    }
+
+    /// Send a request and wait for its response.
+    async fn roundtrip(&self, request: JsonRpcRequest) -> Result<JsonRpcResponse> {
+        let request_id = request.id.to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending_responses
+            .write()
+            .await
+            .insert(request_id.clone(), tx);
+
+        if let Err(e) = self.send_message(&request).await {
+            self.pending_responses.write().await.remove(&request_id);
+            return Err(e);
+        }
+
+        tokio::pin!(rx);
+        loop {
+            tokio::select! {
+                response = &mut rx => {
+                    return response.context("WebSocket closed before the response arrived");
+                }
+                _ = tokio::time::sleep(REQUEST_TIMEOUT) => {
+                    // The server may be waiting on us, e.g. for the user to
+                    // approve a sampling request.
+                    if self.active_server_requests.load(Ordering::SeqCst) > 0 {
+                        continue;
+                    }
+                    self.pending_responses.write().await.remove(&request_id);
+                    return Err(anyhow!(
+                        "MCP request timed out after {}s",
+                        REQUEST_TIMEOUT.as_secs()
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Write one message as a text frame.
+    async fn send_message<T: serde::Serialize>(&self, message: &T) -> Result<()> {
+        write_frame(&self.sink, message).await
+    }
+
+    /// Generate next request ID.
+    fn next_request_id(&self) -> RequestId {
+        RequestId::Number(self.request_id.fetch_add(1, Ordering::SeqCst) as i64)
+    }
+}
+
+#[async_trait]
+impl Transport for WebSocketTransport {
+    async fn request(&self, method: &str, params: Option<Value>) -> Result<Value> {
+        self.ensure_connected().await?;
+
+        let mut request = JsonRpcRequest::new(self.next_request_id(), method);
+        request.params = params.clone();
+        let result = into_result(self.roundtrip(request).await?)?;
+
+        if method == methods::INITIALIZE {
+            *self.init_params.write().await = params;
+        }
+        Ok(result)
+    }
+
+    async fn notify(&self, method: &str, params: Option<Value>) -> Result<()> {
+        self.ensure_connected().await?;
+
+        let mut notification = JsonRpcNotification::new(method);
+        notification.params = params;
+        self.send_message(&notification).await
+    }
+
+    async fn close(&self) -> Result<()> {
+        // Forget the handshake so nothing reconnects behind our back
+        *self.init_params.write().await = None;
+        self.connected.store(false, Ordering::SeqCst);
+
+        if let Some(mut sink) = self.sink.lock().await.take() {
+            let _ = sink.send(Message::Close(None)).await;
+            let _ = sink.close().await;
+            info!(url = %self.url, "MCP WebSocket closed");
+        }
+        Ok(())
+    }
+
+    fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::SeqCst)
+    }
+
+    fn client_capabilities(&self) -> ClientCapabilities {
+        self.request_handler.capabilities()
+    }
+}
+
+/// Read frames until the socket closes.
+async fn read_loop(
+    mut stream: SplitStream<Socket>,
+    sink: Sink,
+    pending_responses: PendingResponses,
+    connected: Arc<AtomicBool>,
+    handler: Arc<dyn ServerRequestHandler>,
+    active_server_requests: Arc<AtomicUsize>,
+) {
+    while let Some(frame) = stream.next().await {
+        let text = match frame {
+            Ok(Message::Text(text)) => text,
+            Ok(Message::Close(_)) => break,
+            Ok(_) => continue,
+            Err(e) => {
+                warn!(error = %e, "MCP WebSocket read failed");
+                break;
+            }
+        };
+
+        match IncomingMessage::parse(&text) {
+            Ok(IncomingMessage::Response(response)) => {
+                let id = response.id.to_string();
+                if let Some(sender) = pending_responses.write().await.remove(&id) {
+                    let _ = sender.send(response);
+                }
+            }
+            Ok(IncomingMessage::Request(request)) => {
+                debug!(method = %request.method, "Received server request");
+                let sink = sink.clone();
+                let handler = handler.clone();
+                let active = active_server_requests.clone();
+                active.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let response = dispatch(handler.as_ref(), request).await;
+                    if let Err(e) = write_frame(&sink, &response).await {
+                        warn!(error = %e, "Failed to answer server request");
+                    }
+                    active.fetch_sub(1, Ordering::SeqCst);
+                });
+            }
+            Ok(IncomingMessage::Notification(notification)) => {
+                debug!(method = %notification.method, "Received notification");
+            }
+            Err(e) => {
+                debug!(error = %e, "Ignoring malformed message from MCP server");
+            }
+        }
+    }
+
+    connected.store(false, Ordering::SeqCst);
+    sink.lock().await.take();
+    // Dropping the senders fails every request still waiting
+    pending_responses.write().await.clear();
+    info!("MCP WebSocket disconnected");
+}
+
+/// Write a JSON-RPC message as a text frame.
+async fn write_frame<T: serde::Serialize>(sink: &Sink, message: &T) -> Result<()> {
+    let json = serde_json::to_string(message)?;
+    let mut sink = sink.lock().await;
+    sink.as_mut()
+        .ok_or_else(|| anyhow!("WebSocket not connected"))?
+        .send(Message::Text(json.into()))
+        .await
+        .context("Failed to write to WebSocket")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cortex_mcp_types::{InitializeParams, ListRootsResult, Root};
+    use serde_json::json;
+    use tokio::net::TcpListener;
+
+    struct Roots;
+
+    #[async_trait]
+    impl ServerRequestHandler for Roots {
+        async fn list_roots(&self) -> Result<ListRootsResult, cortex_mcp_types::JsonRpcError> {
+            Ok(ListRootsResult {
+                roots: vec![Root::new("file:///work")],
+            })
+        }
+    }
+
+    async fn next_message(socket: &mut WebSocketStream<TcpStream>) -> Value {
+        loop {
+            match socket.next().await.unwrap().unwrap() {
+                Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+                _ => continue,
+            }
+        }
+    }
+
+    async fn reply(socket: &mut WebSocketStream<TcpStream>, message: Value) {
+        socket
+            .send(Message::Text(message.to_string().into()))
+            .await
+            .unwrap();
+    }
+
+    async fn handshake(socket: &mut WebSocketStream<TcpStream>) {
+        let init = next_message(socket).await;
+        assert_eq!(init["method"], "initialize");
+        reply(
+            socket,
+            json!({"jsonrpc": "2.0", "id": init["id"], "result": {
+                "protocolVersion": "2024-11-05",
+                "capabilities": {},
+                "serverInfo": {"name": "ws", "version": "1"}
+            }}),
+        )
+        .await;
+        assert_eq!(
+            next_message(socket).await["method"],
+            "notifications/initialized"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_websocket_reconnects_and_answers_server_requests() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = url::Url::parse(&format!("ws://{}", listener.local_addr().unwrap())).unwrap();
+
+        let server = tokio::spawn(async move {
+            // First connection: handshake, then drop the socket.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            handshake(&mut socket).await;
+            drop(socket);
+
+            // Second connection: the handshake is replayed, then the server
+            // asks for roots before answering tools/list.
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+            handshake(&mut socket).await;
+
+            let list = next_message(&mut socket).await;
+            assert_eq!(list["method"], "tools/list");
+            reply(
+                &mut socket,
+                json!({"jsonrpc": "2.0", "id": "r1", "method": "roots/list"}),
+            )
+            .await;
+            let roots = next_message(&mut socket).await;
+            assert_eq!(roots["id"], "r1");
+            let root = roots["result"]["roots"][0]["uri"].clone();
+            reply(
+                &mut socket,
+                json!({"jsonrpc": "2.0", "id": list["id"], "result": {
+                    "tools": [{"name": "open", "description": root, "inputSchema": {"type": "object"}}]
+                }}),
+            )
+            .await;
+        });
+
+        let transport = WebSocketTransport::new(url)
+            .with_request_handler(Arc::new(Roots))
+            .with_reconnect(ReconnectConfig::new().with_initial_delay(Duration::from_millis(10)));
+
+        transport
+            .initialize(InitializeParams::default())
+            .await
+            .unwrap();
+        transport.send_initialized().await.unwrap();
+
+        // Wait for the server to drop the first socket.
+        for _ in 0..100 {
+            if !transport.is_connected() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert!(!transport.is_connected());
+
+        let tools = transport.list_tools().await.unwrap();
+        assert_eq!(tools.tools[0].description.as_deref(), Some("file:///work"));
+
+        server.await.unwrap();
+        transport.close().await.unwrap();
+    }
+}
@@ -7,7 +7,7 @@ use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
 use anyhow::{Context, Result};
 use serde_json::{Value, json};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
 use tokio::sync::{RwLock, oneshot};
 use tracing::{debug, error, info, warn};
 
@@ -402,13 +402,20 @@ impl McpServer {
     /// Run the server with stdio transport.
     pub async fn run_stdio(self: Arc<Self>) -> Result<()> {
         info!(server = %self.info.name, "Starting MCP server with stdio transport");
-        self.running.store(true, Ordering::SeqCst);
+        self.serve(tokio::io::stdin(), tokio::io::stdout()).await
+    }
 
-        let stdin = tokio::io::stdin();
-        let stdout = tokio::io::stdout();
+    /// Serve newline-delimited JSON-RPC over any byte stream, such as an
+    /// in-process pipe.
+    pub async fn serve<R, W>(self: Arc<Self>, reader: R, writer: W) -> Result<()>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        self.running.store(true, Ordering::SeqCst);
 
-        let mut reader = BufReader::new(stdin);
-        let mut stdout = stdout;
+        let mut reader = BufReader::new(reader);
+        let mut stdout = writer;
 
         let mut line = String::new();
 
@@ -458,7 +465,7 @@ impl McpServer {
                     }
                 }
                 Err(e) => {
-                    error!(error = %e, "Error reading from stdin");
+                    error!(error = %e, "Error reading from input");
                     break;
                 }
             }
@@ -539,24 +546,47 @@ impl McpServer {
     /// Run the server with HTTP transport on the given address.
     #[cfg(feature = "http")]
     pub async fn run_http(self: Arc<Self>, addr: std::net::SocketAddr) -> Result<()> {
+        info!(server = %self.info.name, addr = %addr, "Starting MCP server with HTTP transport");
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        self.serve_http(listener).await
+    }
+
+    /// Serve HTTP on an already bound listener.
+    ///
+    /// Requests are answered with a JSON body; notifications are
+    /// acknowledged with `202 Accepted`.
+    #[cfg(feature = "http")]
+    pub async fn serve_http(self: Arc<Self>, listener: tokio::net::TcpListener) -> Result<()> {
+        use axum::response::{IntoResponse, Response};
         use axum::{Json, Router, extract::State, http::StatusCode, routing::post};
 
-        info!(server = %self.info.name, addr = %addr, "Starting MCP server with HTTP transport");
         self.running.store(true, Ordering::SeqCst);
 
         async fn handle_json_rpc(
             State(server): State<Arc<McpServer>>,
-            Json(request): Json<JsonRpcRequest>,
-        ) -> (StatusCode, Json<JsonRpcResponse>) {
-            let response = server.handle_request(request).await;
-            (StatusCode::OK, Json(response))
+            Json(message): Json<Value>,
+        ) -> Response {
+            let has_id = message.get("id").is_some_and(|id| !id.is_null());
+            if has_id && let Ok(request) = serde_json::from_value::<JsonRpcRequest>(message.clone())
+            {
+                let response = server.handle_request(request).await;
+                return (StatusCode::OK, Json(response)).into_response();
+            }
+            if let Ok(notification) = serde_json::from_value::<JsonRpcNotification>(message) {
+                server.handle_notification(notification).await;
+                return StatusCode::ACCEPTED.into_response();
+            }
+            let error = JsonRpcResponse::error(
+                RequestId::Number(0),
+                JsonRpcError::parse_error("Invalid JSON-RPC message"),
+            );
+            (StatusCode::BAD_REQUEST, Json(error)).into_response()
         }
 
         let app = Router::new()
             .route("/", post(handle_json_rpc))
             .with_state(self.clone());
 
-        let listener = tokio::net::TcpListener::bind(addr).await?;
         axum::serve(listener, app).await?;
 
         *self.state.write().await = ServerState::Stopped;
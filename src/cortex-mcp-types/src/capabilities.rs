@@ -19,6 +19,9 @@ pub struct ClientCapabilities {
     /// Roots capability.
     #[serde(skip_serializing_if = "Option::is_none")]
     pub roots: Option<RootsCapability>,
+    /// Elicitation capability.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub elicitation: Option<ElicitationCapability>,
 }
 
 /// Server capabilities.
@@ -81,6 +84,10 @@ pub struct RootsCapability {
     pub list_changed: Option<bool>,
 }
 
+/// Elicitation capability (client).
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ElicitationCapability {}
+
 /// Logging capability (server).
 #[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
 pub struct LoggingCapability {}
@@ -0,0 +1,111 @@
+//! Elicitation types for MCP protocol.
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Elicitation request: the server asks the user for structured input.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ElicitRequest {
+    /// Message to show to the user.
+    pub message: String,
+    /// Flat object schema describing the requested fields.
+    #[serde(default = "empty_object_schema")]
+    pub requested_schema: Value,
+}
+
+fn empty_object_schema() -> Value {
+    serde_json::json!({ "type": "object", "properties": {} })
+}
+
+impl ElicitRequest {
+    /// Create a new elicitation request.
+    pub fn new(message: impl Into<String>, requested_schema: Value) -> Self {
+        Self {
+            message: message.into(),
+            requested_schema,
+        }
+    }
+}
+
+/// How the user responded to an elicitation request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ElicitAction {
+    /// The user submitted the requested data.
+    Accept,
+    /// The user explicitly declined.
+    Decline,
+    /// The user dismissed the request without choosing.
+    Cancel,
+}
+
+/// Elicitation result.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ElicitResult {
+    /// User action.
+    pub action: ElicitAction,
+    /// Submitted data (only with [`ElicitAction::Accept`]).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<Map<String, Value>>,
+}
+
+impl ElicitResult {
+    /// Create an accepted result with the submitted data.
+    pub fn accept(content: Map<String, Value>) -> Self {
+        Self {
+            action: ElicitAction::Accept,
+            content: Some(content),
+        }
+    }
+
+    /// Create a declined result.
+    pub fn decline() -> Self {
+        Self {
+            action: ElicitAction::Decline,
+            content: None,
+        }
+    }
+
+    /// Create a cancelled result.
+    pub fn cancel() -> Self {
+        Self {
+            action: ElicitAction::Cancel,
+            content: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_elicitation_serialization() {
+        let request: ElicitRequest = serde_json::from_value(json!({
+            "message": "Which branch?",
+            "requestedSchema": {
+                "type": "object",
+                "properties": {"branch": {"type": "string"}},
+                "required": ["branch"]
+            }
+        }))
+        .unwrap();
+        assert_eq!(request.message, "Which branch?");
+        assert_eq!(request.requested_schema["required"][0], "branch");
+
+        let mut content = Map::new();
+        content.insert("branch".to_string(), json!("main"));
+        let accepted = serde_json::to_value(ElicitResult::accept(content)).unwrap();
+        assert_eq!(
+            accepted,
+            json!({"action": "accept", "content": {"branch": "main"}})
+        );
+        assert_eq!(
+            serde_json::to_value(ElicitResult::cancel()).unwrap(),
+            json!({"action": "cancel"})
+        );
+    }
+}
@@ -25,6 +25,7 @@
 
 mod capabilities;
 mod content;
+mod elicitation;
 mod initialization;
 mod jsonrpc;
 mod logging;
@@ -63,8 +64,8 @@ pub use initialization::{Implementation, InitializeParams, InitializeResult};
 
 // Capability types
 pub use capabilities::{
-    ClientCapabilities, LoggingCapability, PromptsCapability, ResourcesCapability, RootsCapability,
-    SamplingCapability, ServerCapabilities, ToolsCapability,
+    ClientCapabilities, ElicitationCapability, LoggingCapability, PromptsCapability,
+    ResourcesCapability, RootsCapability, SamplingCapability, ServerCapabilities, ToolsCapability,
 };
 
 // Tool types
@@ -101,5 +102,8 @@ pub use sampling::{
 // Root types
 pub use roots::{ListRootsResult, Root};
 
+// Elicitation types
+pub use elicitation::{ElicitAction, ElicitRequest, ElicitResult};
+
 // Notification types
 pub use notifications::{CancelledNotification, ProgressNotification, ProgressToken};
@@ -60,6 +60,10 @@ pub const SAMPLING_CREATE_MESSAGE: &str = "sampling/createMessage";
 /// List roots method.
 pub const ROOTS_LIST: &str = "roots/list";
 
+// Elicitation
+/// Create elicitation method.
+pub const ELICITATION_CREATE: &str = "elicitation/create";
+
 // Ping
 /// Ping method.
 pub const PING: &str = "ping";
@@ -75,7 +75,7 @@ pub struct ModelHint {
 
 /// Include context option.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "camelCase")]
 pub enum IncludeContext {
     /// No context.
     None,
@@ -5,6 +5,8 @@
 //! - SSRF protection (blocks private/local IPs)
 //! - Network mode control (Full/Limited/Disabled)
 //! - Request logging and metrics
+//! - A local HTTP/HTTPS (`CONNECT`) forwarding proxy that enforces all of
+//!   the above for child processes
 //!
 //! # Architecture
 //!
@@ -51,6 +53,7 @@ pub mod host;
 pub mod ip_validation;
 pub mod pattern;
 pub mod policy;
+pub mod proxy;
 pub mod state;
 
 pub use config::{NetworkMode, NetworkProxyConfig, NetworkProxyConfigBuilder};
@@ -64,7 +67,8 @@ pub use ip_validation::{
 };
 pub use pattern::{DomainPattern, compile_patterns};
 pub use policy::{HostBlockDecision, HostBlockReason, PolicyEngine};
-pub use state::{NetworkProxyState, RequestMetrics};
+pub use proxy::NetworkProxy;
+pub use state::{NetworkProxyState, ProxyRequestLog, RequestMetrics, RequestOutcome};
 
 use thiserror::Error;
 
@@ -111,6 +111,11 @@ impl PolicyEngine {
         self.mode
     }
 
+    /// Whether connections to local/private IPs are allowed.
+    pub fn allows_local_binding(&self) -> bool {
+        self.allow_local_binding
+    }
+
     /// Check if a method is allowed.
     pub fn check_method(&self, method: &str) -> bool {
         self.mode.allows_method(method)
@@ -0,0 +1,606 @@
+//! Local forwarding proxy.
+//!
+//! [`NetworkProxy`] listens on a loopback port and forwards plain HTTP
+//! requests and HTTPS `CONNECT` tunnels that the [`PolicyEngine`] allows.
+//! Child processes are pointed at it with [`NetworkProxy::env_vars`].
+//!
+//! Plain HTTP requests are checked by method and host. Tunnels carry
+//! encrypted traffic, so only their destination is checked. Each client
+//! connection carries a single request: the destination is asked to close
+//! the connection after responding.
+
+use std::fmt::Write as _;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt, copy_bidirectional};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tracing::{debug, warn};
+
+use super::{
+    NetworkProxyConfig, NetworkProxyError, NetworkProxyState, PolicyEngine, ProxyRequestLog,
+    RequestOutcome, Result, safe_connect_with_timeout,
+};
+
+/// Largest request head accepted from a client.
+const MAX_HEAD_SIZE: usize = 64 * 1024;
+
+/// How long to wait for the destination to accept a connection.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Headers addressed to the proxy rather than the destination.
+const HOP_BY_HOP_HEADERS: &[&str] = &[
+    "connection",
+    "keep-alive",
+    "proxy-connection",
+    "proxy-authorization",
+    "proxy-authenticate",
+    "te",
+    "trailer",
+    "upgrade",
+];
+
+/// Variables that point HTTP clients at a proxy.
+const PROXY_ENV_VARS: &[&str] = &["HTTP_PROXY", "HTTPS_PROXY", "http_proxy", "https_proxy"];
+
+/// Variables that exempt hosts from the proxy.
+const NO_PROXY_ENV_VARS: &[&str] = &["NO_PROXY", "no_proxy"];
+
+/// A running forwarding proxy.
+///
+/// The listener stops when the proxy is dropped.
+pub struct NetworkProxy {
+    /// Address the proxy listens on.
+    addr: SocketAddr,
+
+    /// Policy, metrics and request log.
+    state: Arc<NetworkProxyState>,
+
+    /// Accept loop.
+    task: JoinHandle<()>,
+}
+
+impl NetworkProxy {
+    /// Start a proxy on an ephemeral loopback port.
+    pub async fn start(config: NetworkProxyConfig) -> Result<Self> {
+        Self::bind(config, SocketAddr::from(([127, 0, 0, 1], 0))).await
+    }
+
+    /// Start a proxy on `addr`.
+    ///
+    /// Non-loopback addresses are refused unless
+    /// `dangerously_allow_non_loopback_proxy` is set.
+    pub async fn bind(config: NetworkProxyConfig, addr: SocketAddr) -> Result<Self> {
+        if !addr.ip().is_loopback() && !config.dangerously_allow_non_loopback_proxy {
+            return Err(NetworkProxyError::ConfigError(format!(
+                "refusing to listen on non-loopback address {addr}"
+            )));
+        }
+
+        let state = Arc::new(NetworkProxyState::new(PolicyEngine::new(config)?));
+        let listener = TcpListener::bind(addr)
+            .await
+            .map_err(|e| NetworkProxyError::Internal(format!("Failed to bind {addr}: {e}")))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| NetworkProxyError::Internal(format!("Failed to get address: {e}")))?;
+
+        debug!(%addr, "Network proxy listening");
+        let task = tokio::spawn(accept_loop(listener, state.clone()));
+
+        Ok(Self { addr, state, task })
+    }
+
+    /// Address the proxy listens on.
+    pub fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Proxy URL, e.g. `http://127.0.0.1:41234`.
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Environment variables that route a child process through the proxy.
+    ///
+    /// `NO_PROXY` is cleared so no host bypasses the policy.
+    pub fn env_vars(&self) -> Vec<(String, String)> {
+        let url = self.url();
+        PROXY_ENV_VARS
+            .iter()
+            .map(|key| (key.to_string(), url.clone()))
+            .chain(
+                NO_PROXY_ENV_VARS
+                    .iter()
+                    .map(|key| (key.to_string(), String::new())),
+            )
+            .collect()
+    }
+
+    /// Policy, metrics and request log.
+    pub fn state(&self) -> &Arc<NetworkProxyState> {
+        &self.state
+    }
+}
+
+impl Drop for NetworkProxy {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+/// Accept clients until the task is aborted.
+async fn accept_loop(listener: TcpListener, state: Arc<NetworkProxyState>) {
+    loop {
+        match listener.accept().await {
+            Ok((stream, peer)) => {
+                let state = state.clone();
+                tokio::spawn(async move {
+                    if let Err(e) = handle_connection(stream, &state).await {
+                        debug!(%peer, error = %e, "Proxy connection failed");
+                    }
+                });
+            }
+            Err(e) => {
+                warn!(error = %e, "Proxy failed to accept a connection");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+        }
+    }
+}
+
+/// Serve one client connection.
+async fn handle_connection(mut client: TcpStream, state: &NetworkProxyState) -> io::Result<()> {
+    let Some((head, rest)) = read_head(&mut client).await? else {
+        return Ok(());
+    };
+    let Some(request) = RequestHead::parse(&head) else {
+        return respond(&mut client, 400, "Bad Request", "Malformed request\n").await;
+    };
+
+    if request.method.eq_ignore_ascii_case("CONNECT") {
+        tunnel(client, request, rest, state).await
+    } else {
+        forward(client, request, rest, state).await
+    }
+}
+
+/// Open a tunnel for `CONNECT host:port`.
+async fn tunnel(
+    mut client: TcpStream,
+    request: RequestHead,
+    rest: Vec<u8>,
+    state: &NetworkProxyState,
+) -> io::Result<()> {
+    let Some((host, port)) = split_authority(&request.target, 443) else {
+        return respond(&mut client, 400, "Bad Request", "Invalid CONNECT target\n").await;
+    };
+
+    if let Err(e) = state.check_tunnel_and_record(&host, port).await {
+        return refuse(&mut client, state, "CONNECT", &host, port, e).await;
+    }
+
+    let mut upstream = match connect(state, &host, port).await {
+        Ok(stream) => stream,
+        Err(e) => return fail(&mut client, state, "CONNECT", &host, port, e).await,
+    };
+
+    client
+        .write_all(b"HTTP/1.1 200 Connection Established\r\n\r\n")
+        .await?;
+    upstream.write_all(&rest).await?;
+
+    let bytes = copy_bidirectional(&mut client, &mut upstream)
+        .await
+        .map(|(sent, received)| sent + received)
+        .unwrap_or(0)
+        + rest.len() as u64;
+    state.record_success(bytes);
+    state.log_request(
+        ProxyRequestLog::new("CONNECT", host, port, RequestOutcome::Allowed).with_bytes(bytes),
+    );
+    Ok(())
+}
+
+/// Forward a plain HTTP request given in absolute form.
+async fn forward(
+    mut client: TcpStream,
+    request: RequestHead,
+    rest: Vec<u8>,
+    state: &NetworkProxyState,
+) -> io::Result<()> {
+    let Some(url) = strip_prefix_ignore_case(&request.target, "http://") else {
+        return respond(
+            &mut client,
+            400,
+            "Bad Request",
+            "Only absolute http:// URLs can be proxied\n",
+        )
+        .await;
+    };
+
+    let (authority, path) = match url.find(['/', '?']) {
+        Some(i) => (&url[..i], &url[i..]),
+        None => (url, "/"),
+    };
+    let path = if path.starts_with('?') {
+        format!("/{path}")
+    } else {
+        path.to_string()
+    };
+    let Some((host, port)) = split_authority(authority, 80) else {
+        return respond(&mut client, 400, "Bad Request", "Invalid request URL\n").await;
+    };
+
+    let method = request.method.clone();
+    if let Err(e) = state.check_and_record(&method, &host, port).await {
+        return refuse(&mut client, state, &method, &host, port, e).await;
+    }
+
+    let mut upstream = match connect(state, &host, port).await {
+        Ok(stream) => stream,
+        Err(e) => return fail(&mut client, state, &method, &host, port, e).await,
+    };
+
+    let head = request.to_origin_form(&path);
+    upstream.write_all(&head).await?;
+    upstream.write_all(&rest).await?;
+
+    let bytes = copy_bidirectional(&mut client, &mut upstream)
+        .await
+        .map(|(sent, received)| sent + received)
+        .unwrap_or(0)
+        + (head.len() + rest.len()) as u64;
+    state.record_success(bytes);
+    state.log_request(
+        ProxyRequestLog::new(method, host, port, RequestOutcome::Allowed).with_bytes(bytes),
+    );
+    Ok(())
+}
+
+/// Connect to the destination, re-checking its address unless local
+/// destinations are allowed.
+async fn connect(state: &NetworkProxyState, host: &str, port: u16) -> Result<TcpStream> {
+    if !state.policy().allows_local_binding() {
+        return safe_connect_with_timeout(host, port, CONNECT_TIMEOUT).await;
+    }
+
+    tokio::time::timeout(CONNECT_TIMEOUT, TcpStream::connect((host, port)))
+        .await
+        .map_err(|_| NetworkProxyError::Internal(format!("Connection to {host}:{port} timed out")))?
+        .map_err(|e| NetworkProxyError::Internal(format!("Connection failed: {e}")))
+}
+
+/// Answer a request the policy refused.
+async fn refuse(
+    client: &mut TcpStream,
+    state: &NetworkProxyState,
+    method: &str,
+    host: &str,
+    port: u16,
+    error: NetworkProxyError,
+) -> io::Result<()> {
+    let reason = match error {
+        NetworkProxyError::HostBlocked(_, reason) => reason.to_string(),
+        NetworkProxyError::MethodNotAllowed(method) => format!(
+            "{method} not allowed in {} network mode",
+            state.policy().mode()
+        ),
+        other => other.to_string(),
+    };
+    debug!(%method, %host, port, %reason, "Proxy request blocked");
+
+    let body = format!("Blocked by Cortex network policy: {method} {host}:{port} ({reason})\n");
+    state.log_request(ProxyRequestLog::new(
+        method,
+        host,
+        port,
+        RequestOutcome::Blocked(reason),
+    ));
+    respond(client, 403, "Forbidden", &body).await
+}
+
+/// Answer a request whose destination could not be reached.
+async fn fail(
+    client: &mut TcpStream,
+    state: &NetworkProxyState,
+    method: &str,
+    host: &str,
+    port: u16,
+    error: NetworkProxyError,
+) -> io::Result<()> {
+    let body = format!("Could not reach {host}:{port}: {error}\n");
+    state.log_request(ProxyRequestLog::new(
+        method,
+        host,
+        port,
+        RequestOutcome::Failed(error.to_string()),
+    ));
+    respond(client, 502, "Bad Gateway", &body).await
+}
+
+/// Send a plain-text response and close the connection.
+async fn respond(client: &mut TcpStream, status: u16, reason: &str, body: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {status} {reason}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    client.write_all(response.as_bytes()).await?;
+    client.shutdown().await
+}
+
+/// Read up to the end of the request head.
+///
+/// Returns the head and any bytes read past it, or `None` if the client
+/// closed the connection first.
+async fn read_head(stream: &mut TcpStream) -> io::Result<Option<(Vec<u8>, Vec<u8>)>> {
+    let mut buf = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            let rest = buf.split_off(end + 4);
+            return Ok(Some((buf, rest)));
+        }
+        if buf.len() > MAX_HEAD_SIZE {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "request head too large",
+            ));
+        }
+
+        let n = stream.read(&mut chunk).await?;
+        if n == 0 {
+            return Ok(None);
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+}
+
+/// Request line and headers of a client request.
+#[derive(Debug)]
+struct RequestHead {
+    method: String,
+    target: String,
+    version: String,
+    headers: Vec<(String, String)>,
+}
+
+impl RequestHead {
+    /// Parse a request head, including its final blank line.
+    fn parse(head: &[u8]) -> Option<Self> {
+        let text = std::str::from_utf8(head).ok()?;
+        let mut lines = text.split("\r\n");
+
+        let mut parts = lines.next()?.split_whitespace();
+        let method = parts.next()?.to_string();
+        let target = parts.next()?.to_string();
+        let version = parts.next()?.to_string();
+
+        let headers = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| {
+                let (name, value) = line.split_once(':')?;
+                Some((name.trim().to_string(), value.trim().to_string()))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self {
+            method,
+            target,
+            version,
+            headers,
+        })
+    }
+
+    /// Rewrite the head for the destination server.
+    fn to_origin_form(&self, path: &str) -> Vec<u8> {
+        let mut head = format!("{} {} {}\r\n", self.method, path, self.version);
+        for (name, value) in &self.headers {
+            if HOP_BY_HOP_HEADERS
+                .iter()
+                .any(|header| name.eq_ignore_ascii_case(header))
+            {
+                continue;
+            }
+            let _ = write!(head, "{name}: {value}\r\n");
+        }
+        head.push_str("Connection: close\r\n\r\n");
+        head.into_bytes()
+    }
+}
+
+/// Strip an ASCII prefix, ignoring case.
+fn strip_prefix_ignore_case<'a>(s: &'a str, prefix: &str) -> Option<&'a str> {
+    s.get(..prefix.len())
+        .filter(|start| start.eq_ignore_ascii_case(prefix))
+        .map(|_| &s[prefix.len()..])
+}
+
+/// Split `host[:port]` (with optional userinfo and IPv6 brackets).
+fn split_authority(authority: &str, default_port: u16) -> Option<(String, u16)> {
+    let authority = authority
+        .rsplit_once('@')
+        .map_or(authority, |(_, host)| host);
+
+    let (host, port) = if let Some(rest) = authority.strip_prefix('[') {
+        let (host, after) = rest.split_once(']')?;
+        let port = match after.strip_prefix(':') {
+            Some(port) => port.parse().ok()?,
+            None if after.is_empty() => default_port,
+            None => return None,
+        };
+        (host, port)
+    } else {
+        match authority.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (authority, default_port),
+        }
+    };
+
+    if host.is_empty() {
+        return None;
+    }
+    Some((host.to_string(), port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{NetworkMode, NetworkProxyConfigBuilder};
+
+    /// Start a server that answers one request and reports its head.
+    async fn upstream() -> (u16, tokio::sync::oneshot::Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let (head, _) = read_head(&mut stream).await.unwrap().unwrap();
+            let _ = tx.send(String::from_utf8(head).unwrap());
+            stream
+                .write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\nConnection: close\r\n\r\nok")
+                .await
+                .unwrap();
+        });
+        (port, rx)
+    }
+
+    /// Send a raw request through the proxy and return the whole response.
+    async fn send(proxy: &NetworkProxy, request: &str) -> String {
+        let mut stream = TcpStream::connect(proxy.addr()).await.unwrap();
+        stream.write_all(request.as_bytes()).await.unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        response
+    }
+
+    fn local_config() -> NetworkProxyConfigBuilder {
+        NetworkProxyConfig::builder()
+            .allow_local_binding(true)
+            .allow_domain("127.0.0.1")
+    }
+
+    #[tokio::test]
+    async fn test_forwards_allowed_request() {
+        let proxy = NetworkProxy::start(local_config().build()).await.unwrap();
+        let mut rx = proxy.state().subscribe();
+        let (port, head) = upstream().await;
+
+        let response = send(
+            &proxy,
+            &format!(
+                "GET http://127.0.0.1:{port}/path?q=1 HTTP/1.1\r\nHost: 127.0.0.1\r\nProxy-Connection: keep-alive\r\n\r\n"
+            ),
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 200 OK"), "{response}");
+        assert!(response.ends_with("ok"));
+
+        let head = head.await.unwrap();
+        assert!(head.starts_with("GET /path?q=1 HTTP/1.1\r\n"), "{head}");
+        assert!(!head.to_lowercase().contains("proxy-connection"));
+        assert!(head.contains("Connection: close"));
+
+        // Logged once the connection closes
+        let entry = rx.recv().await.unwrap();
+        assert_eq!(entry.outcome, RequestOutcome::Allowed);
+        assert_eq!(entry.port, port);
+        assert_eq!(proxy.state().metrics().allowed(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_unsafe_method_in_limited_mode() {
+        let proxy = NetworkProxy::start(local_config().mode(NetworkMode::Limited).build())
+            .await
+            .unwrap();
+        let mut rx = proxy.state().subscribe();
+
+        let response = send(
+            &proxy,
+            "POST http://127.0.0.1:1/upload HTTP/1.1\r\nContent-Length: 0\r\n\r\n",
+        )
+        .await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+
+        let entry = rx.recv().await.unwrap();
+        assert_eq!(entry.method, "POST");
+        assert!(matches!(entry.outcome, RequestOutcome::Blocked(_)));
+        assert_eq!(proxy.state().metrics().blocked(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_blocks_host_outside_allowlist() {
+        let proxy = NetworkProxy::start(local_config().build()).await.unwrap();
+
+        let response = send(&proxy, "CONNECT evil.example:443 HTTP/1.1\r\n\r\n").await;
+        assert!(response.starts_with("HTTP/1.1 403"), "{response}");
+        assert!(response.contains("not in allowlist"));
+
+        let log = proxy.state().recent_requests();
+        assert_eq!(log[0].host, "evil.example");
+        assert_eq!(
+            log[0].outcome,
+            RequestOutcome::Blocked("not in allowlist".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tunnels_connect() {
+        let proxy = NetworkProxy::start(local_config().mode(NetworkMode::Limited).build())
+            .await
+            .unwrap();
+
+        // Echo server
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let (mut read, mut write) = stream.split();
+            tokio::io::copy(&mut read, &mut write).await.unwrap();
+        });
+
+        let mut stream = TcpStream::connect(proxy.addr()).await.unwrap();
+        stream
+            .write_all(format!("CONNECT 127.0.0.1:{port} HTTP/1.1\r\n\r\n").as_bytes())
+            .await
+            .unwrap();
+        let (head, _) = read_head(&mut stream).await.unwrap().unwrap();
+        assert!(head.starts_with(b"HTTP/1.1 200"));
+
+        stream.write_all(b"ping").await.unwrap();
+        let mut echoed = [0u8; 4];
+        stream.read_exact(&mut echoed).await.unwrap();
+        assert_eq!(&echoed, b"ping");
+    }
+
+    #[tokio::test]
+    async fn test_refuses_non_loopback_listener() {
+        let result = NetworkProxy::bind(
+            NetworkProxyConfig::default(),
+            SocketAddr::from(([0, 0, 0, 0], 0)),
+        )
+        .await;
+        assert!(matches!(result, Err(NetworkProxyError::ConfigError(_))));
+    }
+
+    #[test]
+    fn test_split_authority() {
+        assert_eq!(
+            split_authority("example.com", 80),
+            Some(("example.com".to_string(), 80))
+        );
+        assert_eq!(
+            split_authority("user:pw@example.com:8080", 80),
+            Some(("example.com".to_string(), 8080))
+        );
+        assert_eq!(
+            split_authority("[::1]:443", 80),
+            Some(("::1".to_string(), 443))
+        );
+        assert_eq!(split_authority(":443", 80), None);
+        assert_eq!(split_authority("example.com:http", 80), None);
+    }
+}
@@ -1,7 +1,14 @@
 //! Network proxy state and metrics.
 
-use std::sync::Arc;
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+
+use tokio::sync::broadcast;
+
+/// Number of requests kept in the request log.
+const REQUEST_LOG_CAPACITY: usize = 500;
 
 /// Metrics for network proxy requests.
 #[derive(Debug, Default)]
@@ -78,6 +85,77 @@ pub struct MetricsSnapshot {
     pub bytes_transferred: u64,
 }
 
+/// What happened to a proxied request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RequestOutcome {
+    /// Forwarded to the destination.
+    Allowed,
+
+    /// Refused by the policy.
+    Blocked(String),
+
+    /// Allowed, but the destination could not be reached.
+    Failed(String),
+}
+
+/// A request seen by the proxy.
+#[derive(Debug, Clone)]
+pub struct ProxyRequestLog {
+    /// When the request was received.
+    pub timestamp: SystemTime,
+
+    /// HTTP method (`CONNECT` for tunnels).
+    pub method: String,
+
+    /// Destination host.
+    pub host: String,
+
+    /// Destination port.
+    pub port: u16,
+
+    /// What happened to the request.
+    pub outcome: RequestOutcome,
+
+    /// Bytes transferred in both directions.
+    pub bytes: u64,
+}
+
+impl ProxyRequestLog {
+    /// Create a log entry timestamped now.
+    pub fn new(
+        method: impl Into<String>,
+        host: impl Into<String>,
+        port: u16,
+        outcome: RequestOutcome,
+    ) -> Self {
+        Self {
+            timestamp: SystemTime::now(),
+            method: method.into(),
+            host: host.into(),
+            port,
+            outcome,
+            bytes: 0,
+        }
+    }
+
+    /// Set the bytes transferred.
+    pub fn with_bytes(mut self, bytes: u64) -> Self {
+        self.bytes = bytes;
+        self
+    }
+}
+
+impl std::fmt::Display for ProxyRequestLog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}:{}", self.method, self.host, self.port)?;
+        match &self.outcome {
+            RequestOutcome::Allowed => write!(f, " ({} bytes)", self.bytes),
+            RequestOutcome::Blocked(reason) => write!(f, " blocked: {reason}"),
+            RequestOutcome::Failed(error) => write!(f, " failed: {error}"),
+        }
+    }
+}
+
 /// State for the network proxy.
 pub struct NetworkProxyState {
     /// Policy engine.
@@ -88,6 +166,12 @@ pub struct NetworkProxyState {
 
     /// Whether the proxy is active.
     active: std::sync::atomic::AtomicBool,
+
+    /// Most recent requests, oldest first.
+    log: Mutex<VecDeque<ProxyRequestLog>>,
+
+    /// Live feed of requests.
+    log_tx: broadcast::Sender<ProxyRequestLog>,
 }
 
 impl NetworkProxyState {
@@ -97,6 +181,8 @@ impl NetworkProxyState {
             policy,
             metrics: Arc::new(RequestMetrics::new()),
             active: std::sync::atomic::AtomicBool::new(true),
+            log: Mutex::new(VecDeque::new()),
+            log_tx: broadcast::channel(64).0,
         }
     }
 
@@ -141,10 +227,61 @@ impl NetworkProxyState {
         }
     }
 
+    /// Check a tunnel to `host:port` and record metrics.
+    ///
+    /// Tunnels carry encrypted traffic, so only the destination is checked.
+    pub async fn check_tunnel_and_record(&self, host: &str, port: u16) -> super::Result<()> {
+        if !self.is_active() {
+            self.metrics.record_blocked();
+            return Err(super::NetworkProxyError::Internal(
+                "Proxy is not active".to_string(),
+            ));
+        }
+
+        match self.policy.check_host(host, port).await {
+            super::HostBlockDecision::Allowed => Ok(()),
+            super::HostBlockDecision::Blocked(reason) => {
+                self.metrics.record_blocked();
+                Err(super::NetworkProxyError::HostBlocked(
+                    host.to_string(),
+                    reason,
+                ))
+            }
+        }
+    }
+
     /// Record successful transfer.
     pub fn record_success(&self, bytes: u64) {
         self.metrics.record_allowed(bytes);
     }
+
+    /// Add a request to the log and notify subscribers.
+    pub fn log_request(&self, entry: ProxyRequestLog) {
+        {
+            let mut log = self.log.lock().unwrap_or_else(|e| e.into_inner());
+            if log.len() == REQUEST_LOG_CAPACITY {
+                log.pop_front();
+            }
+            log.push_back(entry.clone());
+        }
+        // No subscribers is fine
+        let _ = self.log_tx.send(entry);
+    }
+
+    /// Most recent requests, oldest first.
+    pub fn recent_requests(&self) -> Vec<ProxyRequestLog> {
+        self.log
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .cloned()
+            .collect()
+    }
+
+    /// Subscribe to requests as they are logged.
+    pub fn subscribe(&self) -> broadcast::Receiver<ProxyRequestLog> {
+        self.log_tx.subscribe()
+    }
 }
 
 #[cfg(test)]
@@ -164,4 +301,22 @@ mod tests {
         assert_eq!(metrics.blocked(), 1);
         assert_eq!(metrics.bytes(), 1500);
     }
+
+    #[test]
+    fn test_request_log_is_bounded() {
+        let state = NetworkProxyState::new(crate::PolicyEngine::permissive());
+
+        for port in 0..(REQUEST_LOG_CAPACITY as u16 + 10) {
+            state.log_request(ProxyRequestLog::new(
+                "GET",
+                "example.com",
+                port,
+                RequestOutcome::Allowed,
+            ));
+        }
+
+        let log = state.recent_requests();
+        assert_eq!(log.len(), REQUEST_LOG_CAPACITY);
+        assert_eq!(log[0].port, 10);
+    }
 }
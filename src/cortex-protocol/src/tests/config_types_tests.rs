@@ -18,7 +18,7 @@ fn test_reasoning_effort_all_variants() {
 
     let expected_json = vec!["\"low\"", "\"medium\"", "\"high\""];
 
-    for (variant, expected) in variants.into_iter().zip(expected_json.into_iter()) {
+    for (variant, expected) in variants.into_iter().zip(expected_json) {
         let json = serde_json::to_string(&variant).expect("serialize");
         assert_eq!(json, expected);
 
@@ -44,7 +44,7 @@ fn test_reasoning_summary_all_variants() {
 
     let expected_json = vec!["\"none\"", "\"brief\"", "\"detailed\"", "\"auto\""];
 
-    for (variant, expected) in variants.into_iter().zip(expected_json.into_iter()) {
+    for (variant, expected) in variants.into_iter().zip(expected_json) {
         let json = serde_json::to_string(&variant).expect("serialize");
         assert_eq!(json, expected);
 
@@ -74,7 +74,7 @@ fn test_sandbox_mode_all_variants() {
         "\"workspace-write\"",
     ];
 
-    for (variant, expected) in variants.into_iter().zip(expected_json.into_iter()) {
+    for (variant, expected) in variants.into_iter().zip(expected_json) {
         let json = serde_json::to_string(&variant).expect("serialize");
         assert_eq!(json, expected);
 
@@ -26,6 +26,10 @@ pub enum StorageError {
     #[error("Could not determine home/data directory")]
     HomeDirNotFound,
 
+    /// Could not lock a file shared with other processes.
+    #[error("Lock error: {0}")]
+    Lock(String),
+
     /// Storage not initialized.
     #[error("Storage not initialized")]
     NotInitialized,
@@ -11,6 +11,7 @@
 //! - Automatic OS detection for storage paths
 //! - Session metadata persistence (JSON)
 //! - Message history (JSONL for efficient appending)
+//! - Full-text search over message history
 //! - Both async and sync APIs
 //!
 //! # Usage
@@ -48,6 +49,6 @@ pub mod sessions;
 pub use error::{Result, StorageError};
 pub use paths::{cortex_config_dir, cortex_data_dir, CortexPaths};
 pub use sessions::{
-    SessionQuery, SessionSort, SessionStorage, SessionSummary, ShareInfo, StoredMessage,
-    StoredSession, StoredToolCall,
+    SearchField, SearchHit, SearchIndex, SessionQuery, SessionSort, SessionStorage, SessionSummary,
+    ShareInfo, StoredMessage, StoredSession, StoredToolCall,
 };
@@ -52,6 +52,7 @@ pub const APP_NAME: &str = "Cortex";
 /// Subdirectory names.
 pub const SESSIONS_DIR: &str = "sessions";
 pub const HISTORY_DIR: &str = "history";
+pub const INDEX_DIR: &str = "index";
 pub const CACHE_DIR: &str = "cache";
 pub const LOGS_DIR: &str = "logs";
 pub const CONFIG_FILE: &str = "config.toml";
@@ -65,6 +66,8 @@ pub struct CortexPaths {
     pub sessions_dir: PathBuf,
     /// Message history directory.
     pub history_dir: PathBuf,
+    /// Full-text search index directory.
+    pub index_dir: PathBuf,
     /// Cache directory.
     pub cache_dir: PathBuf,
     /// Logs directory.
@@ -83,6 +86,7 @@ impl CortexPaths {
         Self {
             sessions_dir: data_dir.join(SESSIONS_DIR),
             history_dir: data_dir.join(HISTORY_DIR),
+            index_dir: data_dir.join(INDEX_DIR),
             cache_dir: data_dir.join(CACHE_DIR),
             logs_dir: data_dir.join(LOGS_DIR),
             data_dir,
@@ -10,8 +10,10 @@
 //! - [`types`] - Core data structures (StoredSession, StoredMessage, etc.)
 //! - [`query`] - Query system for filtering and sorting sessions
 //! - [`storage`] - Storage operations (CRUD, history, sharing)
+//! - [`search`] - Full-text search index over message history
 
 mod query;
+mod search;
 mod storage;
 #[cfg(test)]
 mod tests;
@@ -19,5 +21,6 @@ mod types;
 
 // Re-export all public types for backwards compatibility
 pub use query::{SessionQuery, SessionSort};
+pub use search::{SearchField, SearchHit, SearchIndex};
 pub use storage::SessionStorage;
 pub use types::{SessionSummary, ShareInfo, StoredMessage, StoredSession, StoredToolCall};
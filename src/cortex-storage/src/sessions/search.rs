@@ -0,0 +1,636 @@
+//! Full-text search over session message history.
+//!
+//! `SearchIndex` is an on-disk inverted index over message contents, the
+//! names of tools called, file paths touched and commands executed. It is
+//! append-only so that it can be updated as each message is stored:
+//!
+//! - `docs.jsonl` - one line per indexed message (session, timestamp, length)
+//! - `postings/NN.jsonl` - `(term, document, field, frequency)` lines, sharded
+//!   by a stable hash of the term so a query only reads the shards it needs
+//! - `removed.jsonl` - sessions deleted or rewritten since the last
+//!   compaction, with the number of documents indexed at the time; earlier
+//!   documents of the session are ignored
+//!
+//! Once removed documents make up a quarter of the index they are compacted
+//! away. Writers hold an exclusive lock on `<dir>.lock` and searches a shared
+//! one, so several Cortex processes can share an index.
+//!
+//! Results are ranked with BM25, with matches on tool names, paths and
+//! commands weighted above matches in message text.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use cortex_common::{acquire_lock, FileLockGuard, LockConfig, LockMode};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::error::{Result, StorageError};
+
+use super::types::StoredMessage;
+
+/// Number of posting shards.
+const SHARDS: u64 = 32;
+/// Longest term that is indexed.
+const MAX_TERM_LEN: usize = 64;
+/// BM25 term frequency saturation.
+const BM25_K1: f64 = 1.2;
+/// BM25 length normalization.
+const BM25_B: f64 = 0.75;
+/// Characters of context on each side of a match in a snippet.
+const SNIPPET_CONTEXT: usize = 60;
+/// Compact once removed documents are this fraction (1/N) of the index.
+const COMPACT_RATIO: usize = 4;
+
+/// Part of a message a term was found in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SearchField {
+    /// Message text.
+    #[serde(rename = "c")]
+    Content,
+    /// Name of a tool called.
+    #[serde(rename = "t")]
+    Tool,
+    /// File path touched by a tool call.
+    #[serde(rename = "p")]
+    Path,
+    /// Command executed.
+    #[serde(rename = "x")]
+    Command,
+}
+
+impl SearchField {
+    fn weight(self) -> f64 {
+        match self {
+            Self::Content => 1.0,
+            Self::Tool => 1.5,
+            Self::Path | Self::Command => 2.0,
+        }
+    }
+}
+
+/// A session matching a search, with its best matching message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    /// Session ID.
+    pub session_id: String,
+    /// Best matching message in the session.
+    pub message_id: String,
+    /// Role of the best matching message.
+    pub role: String,
+    /// Timestamp of the best matching message (Unix seconds).
+    pub timestamp: i64,
+    /// Relevance score; higher is better.
+    pub score: f64,
+    /// Number of messages in the session that match.
+    pub matches: usize,
+    /// Query terms as indexed.
+    pub terms: Vec<String>,
+    /// Excerpt around the match, when the message was available.
+    #[serde(default)]
+    pub snippet: Option<String>,
+}
+
+impl SearchHit {
+    /// Fill in the snippet from the session's messages.
+    pub fn with_snippet(mut self, messages: &[StoredMessage]) -> Self {
+        if let Some(message) = messages.iter().find(|m| m.id == self.message_id) {
+            self.snippet = Some(snippet(message, &self.terms));
+        }
+        self
+    }
+}
+
+/// Indexed message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DocEntry {
+    /// Document key referenced by postings.
+    #[serde(rename = "d")]
+    key: String,
+    #[serde(rename = "s")]
+    session_id: String,
+    #[serde(rename = "m")]
+    message_id: String,
+    #[serde(rename = "r")]
+    role: String,
+    #[serde(rename = "t")]
+    timestamp: i64,
+    /// Number of indexed terms.
+    #[serde(rename = "n")]
+    length: usize,
+}
+
+/// Occurrences of a term in one field of a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Posting {
+    term: String,
+    #[serde(rename = "d")]
+    key: String,
+    #[serde(rename = "f")]
+    field: SearchField,
+    tf: u32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Removed {
+    #[serde(rename = "s")]
+    session_id: String,
+    /// Documents indexed before the removal.
+    #[serde(rename = "n")]
+    before: usize,
+}
+
+/// On-disk inverted index over session messages.
+#[derive(Debug, Clone)]
+pub struct SearchIndex {
+    dir: PathBuf,
+}
+
+impl SearchIndex {
+    /// Open (or lazily create) the index in `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Directory holding the index.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether the index has been built.
+    pub fn exists(&self) -> bool {
+        self.docs_path().exists()
+    }
+
+    fn docs_path(&self) -> PathBuf {
+        self.dir.join("docs.jsonl")
+    }
+
+    fn removed_path(&self) -> PathBuf {
+        self.dir.join("removed.jsonl")
+    }
+
+    fn lock_path(&self) -> PathBuf {
+        self.dir.with_extension("lock")
+    }
+
+    fn postings_dir(&self) -> PathBuf {
+        self.dir.join("postings")
+    }
+
+    fn shard_path(&self, term: &str) -> PathBuf {
+        self.postings_dir()
+            .join(format!("{:02}.jsonl", fnv1a(term) % SHARDS))
+    }
+
+    /// Lock the index against other processes until the guard is dropped.
+    ///
+    /// The lock file sits next to the index directory so that a rebuild can
+    /// remove the directory while holding it.
+    fn lock(&self, mode: LockMode) -> Result<FileLockGuard> {
+        if let Some(parent) = self.dir.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let path = self.lock_path();
+        // Shared locks do not create the file
+        OpenOptions::new().create(true).append(true).open(&path)?;
+        acquire_lock(&path, mode, &LockConfig::default())
+            .map_err(|e| StorageError::Lock(format!("Failed to lock {}: {e}", path.display())))
+    }
+
+    /// Add a message to the index.
+    pub fn add_message(&self, session_id: &str, message: &StoredMessage) -> Result<()> {
+        let _lock = self.lock(LockMode::Exclusive)?;
+        self.write_message(session_id, message)
+    }
+
+    fn write_message(&self, session_id: &str, message: &StoredMessage) -> Result<()> {
+        fs::create_dir_all(self.postings_dir())?;
+
+        let key = Uuid::new_v4().simple().to_string();
+        let fields = message_terms(message);
+        let mut by_shard: HashMap<PathBuf, Vec<Posting>> = HashMap::new();
+        let mut length = 0;
+        for (field, terms) in &fields {
+            length += terms.values().sum::<u32>() as usize;
+            for (term, tf) in terms {
+                by_shard
+                    .entry(self.shard_path(term))
+                    .or_default()
+                    .push(Posting {
+                        term: term.clone(),
+                        key: key.clone(),
+                        field: *field,
+                        tf: *tf,
+                    });
+            }
+        }
+
+        for (path, postings) in by_shard {
+            append_lines(&path, &postings)?;
+        }
+        // The document line goes last so a message is only counted once its
+        // postings are on disk.
+        append_lines(
+            &self.docs_path(),
+            &[DocEntry {
+                key,
+                session_id: session_id.to_string(),
+                message_id: message.id.clone(),
+                role: message.role.clone(),
+                timestamp: message.timestamp,
+                length,
+            }],
+        )?;
+        debug!(session_id = %session_id, message_id = %message.id, "Message indexed");
+        Ok(())
+    }
+
+    /// Exclude a deleted session's messages from results.
+    pub fn remove_session(&self, session_id: &str) -> Result<()> {
+        let _lock = self.lock(LockMode::Exclusive)?;
+        self.write_removal(session_id)
+    }
+
+    /// Replace a session's messages, e.g. after its history was rewritten.
+    pub fn reindex_session(&self, session_id: &str, messages: &[StoredMessage]) -> Result<()> {
+        let _lock = self.lock(LockMode::Exclusive)?;
+        self.write_removal(session_id)?;
+        for message in messages {
+            self.write_message(session_id, message)?;
+        }
+        Ok(())
+    }
+
+    fn write_removal(&self, session_id: &str) -> Result<()> {
+        if !self.exists() {
+            return Ok(());
+        }
+        let before = read_lines::<DocEntry>(&self.docs_path())?.len();
+        append_lines(
+            &self.removed_path(),
+            &[Removed {
+                session_id: session_id.to_string(),
+                before,
+            }],
+        )?;
+        self.compact_if_needed()
+    }
+
+    /// Drop removed documents from the index files once they are a large
+    /// enough share of it.
+    fn compact_if_needed(&self) -> Result<()> {
+        let (live, removed) = self.read_docs()?;
+        if removed.is_empty() || removed.len() * COMPACT_RATIO < live.len() + removed.len() {
+            return Ok(());
+        }
+
+        let removed: HashSet<String> = removed.into_iter().map(|doc| doc.key).collect();
+        if self.postings_dir().exists() {
+            for entry in fs::read_dir(self.postings_dir())? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "jsonl") {
+                    let postings: Vec<Posting> = read_lines::<Posting>(&path)?
+                        .into_iter()
+                        .filter(|posting| !removed.contains(&posting.key))
+                        .collect();
+                    replace_lines(&path, &postings)?;
+                }
+            }
+        }
+        // Live documents move to new positions, so the removals must go before
+        // the documents are rewritten. Removed documents left behind by an
+        // interruption here have no postings and never match.
+        if self.removed_path().exists() {
+            fs::remove_file(self.removed_path())?;
+        }
+        replace_lines(&self.docs_path(), &live)?;
+        debug!(dir = %self.dir.display(), removed = removed.len(), "Search index compacted");
+        Ok(())
+    }
+
+    /// Indexed documents, split into live ones and those of removed sessions.
+    fn read_docs(&self) -> Result<(Vec<DocEntry>, Vec<DocEntry>)> {
+        let mut removed: HashMap<String, usize> = HashMap::new();
+        for entry in read_lines::<Removed>(&self.removed_path())? {
+            let before = removed.entry(entry.session_id).or_default();
+            *before = (*before).max(entry.before);
+        }
+        let mut live = Vec::new();
+        let mut dead = Vec::new();
+        for (position, doc) in read_lines::<DocEntry>(&self.docs_path())?
+            .into_iter()
+            .enumerate()
+        {
+            if removed
+                .get(&doc.session_id)
+                .is_none_or(|before| position >= *before)
+            {
+                live.push(doc);
+            } else {
+                dead.push(doc);
+            }
+        }
+        Ok((live, dead))
+    }
+
+    /// Replace the index with the given sessions' messages.
+    pub fn rebuild<'a, I>(&self, sessions: I) -> Result<usize>
+    where
+        I: IntoIterator<Item = (&'a str, &'a [StoredMessage])>,
+    {
+        let _lock = self.lock(LockMode::Exclusive)?;
+        if self.dir.exists() {
+            fs::remove_dir_all(&self.dir)?;
+        }
+        fs::create_dir_all(&self.dir)?;
+        File::create(self.docs_path())?;
+
+        let mut indexed = 0;
+        for (session_id, messages) in sessions {
+            for message in messages {
+                self.write_message(session_id, message)?;
+                indexed += 1;
+            }
+        }
+        debug!(dir = %self.dir.display(), messages = indexed, "Search index rebuilt");
+        Ok(indexed)
+    }
+
+    /// Find the sessions best matching `query`, most relevant first.
+    ///
+    /// Every query term must occur in the same message. Hits carry no
+    /// snippet; see [`SearchHit::with_snippet`].
+    pub fn search(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let mut terms: Vec<String> = tokenize(query).collect();
+        terms.sort();
+        terms.dedup();
+        if terms.is_empty() || !self.exists() {
+            return Ok(Vec::new());
+        }
+
+        let _lock = self.lock(LockMode::Shared)?;
+        let docs: HashMap<String, DocEntry> = self
+            .read_docs()?
+            .0
+            .into_iter()
+            .map(|doc| (doc.key.clone(), doc))
+            .collect();
+        if docs.is_empty() {
+            return Ok(Vec::new());
+        }
+        let doc_count = docs.len() as f64;
+        let avg_length =
+            (docs.values().map(|d| d.length).sum::<usize>() as f64 / doc_count).max(1.0);
+
+        // Weighted frequency of each term in each message
+        let mut scores: Option<HashMap<String, f64>> = None;
+        for term in &terms {
+            let mut frequencies: HashMap<String, f64> = HashMap::new();
+            for posting in read_lines::<Posting>(&self.shard_path(term))? {
+                if &posting.term == term && docs.contains_key(&posting.key) {
+                    *frequencies.entry(posting.key).or_default() +=
+                        f64::from(posting.tf) * posting.field.weight();
+                }
+            }
+
+            let df = frequencies.len() as f64;
+            let idf = ((doc_count - df + 0.5) / (df + 0.5) + 1.0).ln();
+            let previous = scores.take();
+            let mut next = HashMap::new();
+            for (key, tf) in frequencies {
+                let base = match &previous {
+                    Some(previous) => match previous.get(&key) {
+                        Some(score) => *score,
+                        None => continue,
+                    },
+                    None => 0.0,
+                };
+                let length = docs[&key].length as f64;
+                let norm = BM25_K1 * (1.0 - BM25_B + BM25_B * length / avg_length);
+                next.insert(key, base + idf * tf * (BM25_K1 + 1.0) / (tf + norm));
+            }
+            if next.is_empty() {
+                return Ok(Vec::new());
+            }
+            scores = Some(next);
+        }
+
+        // Best message per session; sessions with more matching messages rank
+        // slightly higher.
+        let mut sessions: HashMap<String, SearchHit> = HashMap::new();
+        for (key, score) in scores.unwrap_or_default() {
+            let doc = &docs[&key];
+            let hit = sessions
+                .entry(doc.session_id.clone())
+                .or_insert_with(|| SearchHit {
+                    session_id: doc.session_id.clone(),
+                    message_id: doc.message_id.clone(),
+                    role: doc.role.clone(),
+                    timestamp: doc.timestamp,
+                    score,
+                    matches: 0,
+                    terms: terms.clone(),
+                    snippet: None,
+                });
+            hit.matches += 1;
+            if score > hit.score || (score == hit.score && doc.timestamp > hit.timestamp) {
+                hit.message_id = doc.message_id.clone();
+                hit.role = doc.role.clone();
+                hit.timestamp = doc.timestamp;
+                hit.score = score;
+            }
+        }
+
+        let mut hits: Vec<SearchHit> = sessions
+            .into_values()
+            .map(|mut hit| {
+                hit.score *= 1.0 + 0.1 * (hit.matches as f64).ln();
+                hit
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then(b.timestamp.cmp(&a.timestamp))
+        });
+        hits.truncate(limit);
+        Ok(hits)
+    }
+}
+
+/// Terms of each searchable part of a message, with their frequencies.
+fn message_terms(message: &StoredMessage) -> Vec<(SearchField, HashMap<String, u32>)> {
+    let mut content = HashMap::new();
+    let mut tools = HashMap::new();
+    let mut paths = HashMap::new();
+    let mut commands = HashMap::new();
+
+    for term in tokenize(&message.content) {
+        *content.entry(term).or_insert(0) += 1;
+    }
+    for call in &message.tool_calls {
+        for term in tokenize(&call.name) {
+            *tools.entry(term).or_insert(0) += 1;
+        }
+        for path in tool_paths(&call.input) {
+            for term in tokenize(&path) {
+                *paths.entry(term).or_insert(0) += 1;
+            }
+        }
+        if let Some(command) = tool_command(&call.input) {
+            for term in tokenize(&command) {
+                *commands.entry(term).or_insert(0) += 1;
+            }
+        }
+    }
+
+    [
+        (SearchField::Content, content),
+        (SearchField::Tool, tools),
+        (SearchField::Path, paths),
+        (SearchField::Command, commands),
+    ]
+    .into_iter()
+    .filter(|(_, terms)| !terms.is_empty())
+    .collect()
+}
+
+/// File paths named in a tool call's arguments.
+fn tool_paths(input: &Value) -> Vec<String> {
+    const KEYS: [&str; 4] = ["file_path", "path", "directory_path", "directory"];
+    let mut paths: Vec<String> = KEYS
+        .iter()
+        .filter_map(|key| input.get(*key).and_then(Value::as_str))
+        .map(String::from)
+        .collect();
+    if let Some(edits) = input.get("edits").and_then(Value::as_array) {
+        paths.extend(
+            edits
+                .iter()
+                .filter_map(|edit| edit.get("file_path").and_then(Value::as_str))
+                .map(String::from),
+        );
+    }
+    paths
+}
+
+/// Command executed by a tool call, as a single line.
+fn tool_command(input: &Value) -> Option<String> {
+    match input.get("command")? {
+        Value::String(command) => Some(command.clone()),
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(Value::as_str)
+                .collect::<Vec<_>>()
+                .join(" "),
+        ),
+        _ => None,
+    }
+}
+
+/// Split text into lowercase alphanumeric terms.
+pub fn tokenize(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| !word.is_empty() && word.len() <= MAX_TERM_LEN)
+        .map(str::to_lowercase)
+}
+
+/// Excerpt of a message around the first query term found.
+///
+/// Looks in the message text first, then in its tool calls' commands and
+/// paths, so a session found by a command it ran still shows why.
+pub fn snippet(message: &StoredMessage, terms: &[String]) -> String {
+    let mut sources = vec![message.content.clone()];
+    for call in &message.tool_calls {
+        if let Some(command) = tool_command(&call.input) {
+            sources.push(format!("{}: {}", call.name, command));
+        }
+        for path in tool_paths(&call.input) {
+            sources.push(format!("{}: {}", call.name, path));
+        }
+    }
+
+    for source in &sources {
+        let lower = source.to_lowercase();
+        let found = terms
+            .iter()
+            .filter_map(|term| lower.find(term.as_str()))
+            .min();
+        if let Some(position) = found {
+            return excerpt(source, position);
+        }
+    }
+    excerpt(&message.content, 0)
+}
+
+/// Single-line excerpt around a byte position.
+fn excerpt(text: &str, position: usize) -> String {
+    let chars: Vec<(usize, char)> = text.char_indices().collect();
+    let center = chars
+        .iter()
+        .position(|(i, _)| *i >= position)
+        .unwrap_or(chars.len());
+    let start = center.saturating_sub(SNIPPET_CONTEXT);
+    let end = (center + SNIPPET_CONTEXT * 2).min(chars.len());
+
+    let mut out: String = chars[start..end].iter().map(|(_, c)| *c).collect();
+    out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    if start > 0 {
+        out.insert_str(0, "...");
+    }
+    if end < chars.len() {
+        out.push_str("...");
+    }
+    out
+}
+
+/// Stable 64-bit FNV-1a hash, used to pick a term's shard.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn append_lines<T: Serialize>(path: &Path, items: &[T]) -> Result<()> {
+    let mut buffer = String::new();
+    for item in items {
+        buffer.push_str(&serde_json::to_string(item)?);
+        buffer.push('\n');
+    }
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    file.write_all(buffer.as_bytes())?;
+    Ok(())
+}
+
+/// Rewrite a file with the given lines, atomically.
+fn replace_lines<T: Serialize>(path: &Path, items: &[T]) -> Result<()> {
+    let tmp = path.with_extension("jsonl.tmp");
+    File::create(&tmp)?;
+    append_lines(&tmp, items)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn read_lines<T: for<'de> Deserialize<'de>>(path: &Path) -> Result<Vec<T>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let mut items = Vec::new();
+    for line in BufReader::new(File::open(path)?).lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line) {
+            Ok(item) => items.push(item),
+            Err(e) => warn!(path = %path.display(), error = %e, "Skipping bad index line"),
+        }
+    }
+    Ok(items)
+}
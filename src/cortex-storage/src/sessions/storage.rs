@@ -15,6 +15,7 @@ use crate::error::{Result, StorageError};
 use crate::paths::CortexPaths;
 
 use super::query::SessionQuery;
+use super::search::{SearchHit, SearchIndex};
 use super::types::{SessionSummary, ShareInfo, StoredMessage, StoredSession};
 
 /// Centralized session storage manager.
@@ -153,6 +154,11 @@ impl SessionStorage {
         if history_path.exists() {
             fs::remove_file(&history_path).await?;
         }
+        let index = self.search_index();
+        let session_id = id.to_string();
+        if let Err(e) = blocking(move || index.remove_session(&session_id)).await {
+            warn!(session_id = %id, error = %e, "Failed to remove session from search index");
+        }
 
         info!(session_id = %id, "Session deleted");
         Ok(())
@@ -169,6 +175,9 @@ impl SessionStorage {
         if history_path.exists() {
             std::fs::remove_file(&history_path)?;
         }
+        if let Err(e) = self.search_index().remove_session(id) {
+            warn!(session_id = %id, error = %e, "Failed to remove session from search index");
+        }
 
         info!(session_id = %id, "Session deleted");
         Ok(())
@@ -218,6 +227,16 @@ impl SessionStorage {
         // Ensure data is durably written to disk (fsync) to prevent data loss on crash
         file.sync_all().await?;
 
+        // The index is updated with blocking file I/O under a file lock
+        let storage = self.clone();
+        let id = session_id.to_string();
+        let indexed = message.clone();
+        if let Err(e) =
+            tokio::task::spawn_blocking(move || storage.index_message(&id, &indexed)).await
+        {
+            warn!(session_id = %session_id, error = %e, "Search index update panicked");
+        }
+
         debug!(session_id = %session_id, message_id = %message.id, "Message appended");
         Ok(())
     }
@@ -238,6 +257,7 @@ impl SessionStorage {
 
         // Ensure data is durably written to disk (fsync) to prevent data loss on crash
         file.sync_all()?;
+        self.index_message(session_id, message);
 
         debug!(session_id = %session_id, message_id = %message.id, "Message appended");
         Ok(())
@@ -318,6 +338,92 @@ impl SessionStorage {
         Ok(query.apply_pagination(filtered))
     }
 
+    // ========================================================================
+    // Full-text search
+    // ========================================================================
+
+    /// Get the full-text search index over message history.
+    pub fn search_index(&self) -> SearchIndex {
+        SearchIndex::new(&self.paths.index_dir)
+    }
+
+    /// Search message contents, tool names, paths and commands across all
+    /// sessions, returning the best matching sessions with snippets.
+    pub async fn search_history(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let storage = self.clone();
+        let query = query.to_string();
+        let hits = blocking(move || storage.search_hits(&query, limit)).await?;
+        let mut results = Vec::with_capacity(hits.len());
+        for hit in hits {
+            let history = self.get_history(&hit.session_id).await?;
+            results.push(hit.with_snippet(&history));
+        }
+        Ok(results)
+    }
+
+    /// Search history synchronously.
+    pub fn search_history_sync(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        self.search_hits(query, limit)?
+            .into_iter()
+            .map(|hit| {
+                let history = self.get_history_sync(&hit.session_id)?;
+                Ok(hit.with_snippet(&history))
+            })
+            .collect()
+    }
+
+    /// Rebuild the search index from all stored history.
+    pub fn rebuild_search_index(&self) -> Result<usize> {
+        let mut histories = Vec::new();
+        if self.paths.history_dir.exists() {
+            for entry in std::fs::read_dir(&self.paths.history_dir)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|ext| ext == "jsonl") {
+                    if let Some(id) = path.file_stem().and_then(|s| s.to_str()) {
+                        histories.push((id.to_string(), self.get_history_sync(id)?));
+                    }
+                }
+            }
+        }
+
+        let indexed = self.search_index().rebuild(
+            histories
+                .iter()
+                .map(|(id, messages)| (id.as_str(), messages.as_slice())),
+        )?;
+        info!(
+            sessions = histories.len(),
+            messages = indexed,
+            "Search index rebuilt"
+        );
+        Ok(indexed)
+    }
+
+    fn search_hits(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let index = self.search_index();
+        if !index.exists() {
+            self.rebuild_search_index()?;
+        }
+        index.search(query, limit)
+    }
+
+    /// Add an appended message to the search index.
+    ///
+    /// The history file is the source of truth, so indexing failures are
+    /// logged rather than failing the append. An index that was never built
+    /// is built from scratch, which picks up the new message too.
+    fn index_message(&self, session_id: &str, message: &StoredMessage) {
+        let index = self.search_index();
+        let result = if index.exists() {
+            index.add_message(session_id, message)
+        } else {
+            self.rebuild_search_index().map(|_| ())
+        };
+        if let Err(e) = result {
+            warn!(session_id = %session_id, error = %e, "Failed to update search index");
+        }
+    }
+
     // ========================================================================
     // Favorites, tags, and sharing operations
     // ========================================================================
@@ -447,3 +553,14 @@ fn generate_share_token() -> String {
     // Use UUID v4 which provides cryptographic randomness
     Uuid::new_v4().to_string().replace("-", "")
 }
+
+/// Run blocking search index work off the async runtime.
+async fn blocking<T, F>(work: F) -> Result<T>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T> + Send + 'static,
+{
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| StorageError::Io(std::io::Error::other(e)))?
+}
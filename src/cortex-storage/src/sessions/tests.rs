@@ -3,7 +3,10 @@
 use tempfile::tempdir;
 
 use crate::paths::CortexPaths;
-use crate::sessions::{SessionQuery, SessionStorage, SessionSummary, StoredMessage, StoredSession};
+use crate::sessions::{
+    SearchIndex, SessionQuery, SessionStorage, SessionSummary, StoredMessage, StoredSession,
+    StoredToolCall,
+};
 use chrono::Utc;
 use std::time::Duration;
 
@@ -303,3 +306,179 @@ async fn test_query_sessions() {
         .unwrap();
     assert_eq!(limited.len(), 1);
 }
+
+fn tool_call(name: &str, input: serde_json::Value) -> StoredToolCall {
+    StoredToolCall {
+        id: format!("call-{}", name),
+        name: name.to_string(),
+        input,
+        output: None,
+        success: true,
+        duration_ms: None,
+    }
+}
+
+#[tokio::test]
+async fn test_search_history() {
+    let dir = tempdir().unwrap();
+    let paths = CortexPaths::from_root(dir.path().to_path_buf());
+    let storage = SessionStorage::with_paths(paths);
+    storage.init().await.unwrap();
+
+    storage
+        .append_message("s1", &StoredMessage::user("Why does the parser panic?"))
+        .await
+        .unwrap();
+    storage
+        .append_message(
+            "s1",
+            &StoredMessage::assistant("Running the tests.").with_tool_call(tool_call(
+                "Execute",
+                serde_json::json!({"command": ["cargo", "test", "-p", "tokenizer"]}),
+            )),
+        )
+        .await
+        .unwrap();
+    storage
+        .append_message(
+            "s2",
+            &StoredMessage::assistant("Updated the config loader.").with_tool_call(tool_call(
+                "Edit",
+                serde_json::json!({"file_path": "src/config/loader.rs"}),
+            )),
+        )
+        .await
+        .unwrap();
+    storage
+        .append_message(
+            "s3",
+            &StoredMessage::user("The parser is slow on big files"),
+        )
+        .await
+        .unwrap();
+
+    // Message text
+    let hits = storage.search_history("parser", 10).await.unwrap();
+    let ids: Vec<_> = hits.iter().map(|h| h.session_id.as_str()).collect();
+    assert_eq!(ids.len(), 2);
+    assert!(ids.contains(&"s1") && ids.contains(&"s3"));
+
+    // Commands run, with a snippet showing the command
+    let hits = storage.search_history("cargo tokenizer", 10).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].session_id, "s1");
+    assert!(hits[0].snippet.as_deref().unwrap().contains("cargo test"));
+
+    // File paths and tool names
+    let hits = storage.search_history_sync("loader.rs", 10).unwrap();
+    assert_eq!(hits[0].session_id, "s2");
+    let hits = storage.search_history_sync("edit", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+
+    // All terms must match in one message
+    assert!(storage
+        .search_history("parser loader", 10)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Deleted sessions drop out of results
+    storage.delete_session("s3").await.unwrap();
+    let hits = storage.search_history("parser", 10).await.unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].session_id, "s1");
+}
+
+#[test]
+fn test_search_index_rebuilt_from_history() {
+    let dir = tempdir().unwrap();
+    let paths = CortexPaths::from_root(dir.path().to_path_buf());
+    let storage = SessionStorage::with_paths(paths);
+    storage.init_sync().unwrap();
+
+    storage
+        .append_message_sync("old", &StoredMessage::user("migrate the database schema"))
+        .unwrap();
+    // Simulate history written before the index existed
+    std::fs::remove_dir_all(storage.search_index().dir()).unwrap();
+
+    storage
+        .append_message_sync("new", &StoredMessage::user("schema docs"))
+        .unwrap();
+    let hits = storage.search_history_sync("schema", 10).unwrap();
+    assert_eq!(hits.len(), 2);
+
+    // Rarer and repeated terms rank higher
+    storage
+        .append_message_sync("many", &StoredMessage::user("schema schema schema"))
+        .unwrap();
+    let hits = storage.search_history_sync("schema", 10).unwrap();
+    assert_eq!(hits[0].session_id, "many");
+    assert!(hits.iter().all(|h| h.snippet.is_some()));
+}
+
+#[test]
+fn test_search_index_reindex_session() {
+    let dir = tempdir().unwrap();
+    let index = SearchIndex::new(dir.path().join("index"));
+
+    let first = StoredMessage::user("rename the widget factory");
+    let second = StoredMessage::assistant("done renaming the widget");
+    index.add_message("s1", &first).unwrap();
+    index.add_message("s1", &second).unwrap();
+    index
+        .add_message("s2", &StoredMessage::user("widget colors"))
+        .unwrap();
+    assert_eq!(index.search("widget", 10).unwrap().len(), 2);
+
+    // Undo drops the second message; the session is re-indexed in place
+    index
+        .reindex_session("s1", std::slice::from_ref(&first))
+        .unwrap();
+    assert!(index.search("renaming", 10).unwrap().is_empty());
+    let hits = index.search("factory", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].message_id, first.id);
+    assert_eq!(hits[0].matches, 1);
+
+    let hit = hits[0].clone().with_snippet(&[first]);
+    assert_eq!(hit.snippet.as_deref(), Some("rename the widget factory"));
+}
+
+#[test]
+fn test_search_index_compacts_removed_sessions() {
+    let dir = tempdir().unwrap();
+    let index = SearchIndex::new(dir.path().join("index"));
+
+    for i in 0..3 {
+        index
+            .add_message("gone", &StoredMessage::user(format!("old draft {i}")))
+            .unwrap();
+    }
+    index
+        .add_message("kept", &StoredMessage::user("final draft"))
+        .unwrap();
+    assert!(dir.path().join("index.lock").exists());
+
+    index.remove_session("gone").unwrap();
+    let docs = std::fs::read_to_string(dir.path().join("index/docs.jsonl")).unwrap();
+    assert_eq!(docs.lines().count(), 1);
+    assert!(!dir.path().join("index/removed.jsonl").exists());
+    for shard in std::fs::read_dir(dir.path().join("index/postings")).unwrap() {
+        let postings = std::fs::read_to_string(shard.unwrap().path()).unwrap();
+        assert!(!postings.contains("\"old\""));
+    }
+
+    let hits = index.search("draft", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].session_id, "kept");
+
+    // Removals after compaction still apply to the rewritten documents
+    index
+        .add_message("later", &StoredMessage::user("draft notes"))
+        .unwrap();
+    index.remove_session("kept").unwrap();
+    let hits = index.search("draft", 10).unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].session_id, "later");
+}
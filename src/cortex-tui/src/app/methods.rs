@@ -255,7 +255,21 @@ impl AppState {
 impl AppState {
     /// Cycle to the next permission mode
     pub fn cycle_permission_mode(&mut self) {
-        self.permission_mode = self.permission_mode.cycle_next();
+        self.set_permission_mode(self.permission_mode.cycle_next());
+    }
+
+    /// Switch permission mode, lowered to what the managed policy allows.
+    /// The user is told when the requested mode is refused.
+    pub fn set_permission_mode(&mut self, mode: PermissionMode) {
+        let allowed = mode.clamp_to_managed();
+        if allowed != mode {
+            self.toasts.warning(format!(
+                "Permission mode {} is not allowed by the managed policy; using {}",
+                mode.display_name(),
+                allowed.display_name()
+            ));
+        }
+        self.permission_mode = allowed;
     }
 
     /// Set the thinking budget level
@@ -597,11 +611,11 @@ impl AppState {
 
     /// Sets the approval mode
     pub fn set_approval_mode(&mut self, mode: ApprovalMode) {
-        self.permission_mode = match mode {
+        self.set_permission_mode(match mode {
             ApprovalMode::Ask => PermissionMode::High,
             ApprovalMode::AllowSession => PermissionMode::Medium,
             ApprovalMode::AllowAlways => PermissionMode::Yolo,
-        };
+        });
     }
 
     /// Toggle compact mode
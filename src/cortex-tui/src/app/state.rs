@@ -280,7 +280,7 @@ impl AppState {
             model_picker: crate::widgets::ModelPickerState::new(),
             text_selection: TextSelection::new(),
             toasts: ToastManager::new().with_position(ToastPosition::BottomLeft),
-            permission_mode: PermissionMode::default(),
+            permission_mode: PermissionMode::default().clamp_to_managed(),
             tool_calls: Vec::new(),
             pending_tool_results: Vec::new(),
             thinking_budget: None,
@@ -108,6 +108,7 @@ impl CommandExecutor {
             "logs" | "log" => self.cmd_logs(cmd),
             "dump" => self.cmd_dump(cmd),
             "metrics" | "perf" => CommandResult::Async("metrics".to_string()),
+            "network" | "net" => CommandResult::Async("network".to_string()),
 
             // Hidden commands
             "crash" => CommandResult::Error("Crash test triggered".to_string()),
@@ -657,6 +657,15 @@ pub fn register_builtin_commands(registry: &mut CommandRegistry) {
         false,
     ));
 
+    registry.register(CommandDef::new(
+        "network",
+        &["net"],
+        "Show network requests made by tools",
+        "/network",
+        CommandCategory::Debug,
+        false,
+    ));
+
     // ========================================
     // DEVELOPMENT & TOOLS COMMANDS
     // ========================================
@@ -8,6 +8,7 @@ use ratatui::widgets::Widget;
 
 use cortex_core::style::{SURFACE_0, TEXT_MUTED};
 
+use crate::session::SessionStorage;
 use crate::widgets::ActionBar;
 
 use super::super::{CancelBehavior, Modal, ModalAction, ModalResult};
@@ -18,6 +19,12 @@ use super::rendering::{
 use super::session_action::SessionAction;
 use super::session_info::SessionInfo;
 
+/// Shortest query that also searches message history.
+const MIN_HISTORY_QUERY_LEN: usize = 2;
+
+/// Maximum number of history search results.
+const HISTORY_SEARCH_LIMIT: usize = 20;
+
 /// A modal for managing sessions.
 pub struct SessionsModal {
     /// Session information list.
@@ -34,6 +41,8 @@ pub struct SessionsModal {
     filtered_indices: Vec<usize>,
     /// Current action mode (for confirmations).
     action_mode: SessionAction,
+    /// Storage used to search message history, if enabled.
+    history: Option<SessionStorage>,
 }
 
 impl SessionsModal {
@@ -56,9 +65,19 @@ impl SessionsModal {
             search_query: String::new(),
             filtered_indices,
             action_mode: SessionAction::None,
+            history: None,
         }
     }
 
+    /// Also matches sessions by their message history, tool calls and
+    /// commands, ranked by relevance and shown with a snippet.
+    ///
+    /// Matching sessions outside the initial list are added to it.
+    pub fn with_history_search(mut self, storage: SessionStorage) -> Self {
+        self.history = Some(storage);
+        self
+    }
+
     /// Gets the actual session index from the selected index.
     /// Returns None if "New Session" is selected (idx 0 maps to None).
     fn selected_session_index(&self) -> Option<usize> {
@@ -120,7 +139,14 @@ impl SessionsModal {
     }
 
     /// Apply search filter.
+    ///
+    /// Sessions whose name matches come first, followed by sessions whose
+    /// history matches, best match first.
     fn apply_filter(&mut self) {
+        for session in &mut self.sessions {
+            session.snippet = None;
+        }
+
         if self.search_query.is_empty() {
             self.filtered_indices = (0..self.sessions.len()).collect();
         } else {
@@ -132,12 +158,58 @@ impl SessionsModal {
                 .filter(|(_, s)| s.name.to_lowercase().contains(&query_lower))
                 .map(|(i, _)| i)
                 .collect();
+            for idx in self.search_history() {
+                if !self.filtered_indices.contains(&idx) {
+                    self.filtered_indices.push(idx);
+                }
+            }
         }
         // Reset selection to New Session
         self.selected_idx = 0;
         self.scroll_offset = 0;
     }
 
+    /// Search message history, returning indices of matching sessions.
+    fn search_history(&mut self) -> Vec<usize> {
+        let Some(storage) = &self.history else {
+            return Vec::new();
+        };
+        if self.search_query.trim().len() < MIN_HISTORY_QUERY_LEN {
+            return Vec::new();
+        }
+        let hits = match storage.search_history(&self.search_query, HISTORY_SEARCH_LIMIT) {
+            Ok(hits) => hits,
+            Err(e) => {
+                tracing::warn!("Session history search failed: {}", e);
+                return Vec::new();
+            }
+        };
+
+        let mut indices = Vec::new();
+        for hit in hits {
+            let path = std::path::PathBuf::from(&hit.session_id);
+            let idx = match self.sessions.iter().position(|s| s.path == path) {
+                Some(idx) => idx,
+                None => match storage.load_meta(&hit.session_id) {
+                    Ok(meta) => {
+                        self.sessions.push(SessionInfo::new(
+                            path,
+                            meta.display_title(),
+                            meta.model.clone(),
+                            meta.created_at,
+                            meta.message_count as usize,
+                        ));
+                        self.sessions.len() - 1
+                    }
+                    Err(_) => continue,
+                },
+            };
+            self.sessions[idx].snippet = hit.snippet;
+            indices.push(idx);
+        }
+        indices
+    }
+
     /// Build contextual action bar.
     fn build_action_bar(&self) -> ActionBar {
         match &self.action_mode {
@@ -102,12 +102,26 @@ pub fn render_session_row(session: &SessionInfo, is_selected: bool, area: Rect,
     // Session name (left-aligned)
     let name_style = Style::default().fg(fg).bg(bg);
 
-    // Build metadata: "2h ago   15 msgs   claude-opus"
-    let time_ago = session.relative_time();
-    let msg_count = format!("{} msgs", session.message_count);
-    let model = session.short_model();
-    let meta = format!("{}   {}   {}", time_ago, msg_count, model);
-    let meta_len = meta.len();
+    // Build metadata: "2h ago   15 msgs   claude-opus", or the matching
+    // excerpt when found by a history search
+    let meta = match &session.snippet {
+        Some(snippet) => {
+            let max_len = (area.width as usize / 2).max(10);
+            if snippet.chars().count() > max_len {
+                let truncated: String = snippet.chars().take(max_len - 3).collect();
+                format!("{}...", truncated)
+            } else {
+                snippet.clone()
+            }
+        }
+        None => {
+            let time_ago = session.relative_time();
+            let msg_count = format!("{} msgs", session.message_count);
+            let model = session.short_model();
+            format!("{}   {}   {}", time_ago, msg_count, model)
+        }
+    };
+    let meta_len = meta.chars().count();
 
     // Calculate max name length
     let available_width = (area.width as usize).saturating_sub(4); // prefix + padding
@@ -17,6 +17,8 @@ pub struct SessionInfo {
     pub created_at: DateTime<Utc>,
     /// Number of messages in the session.
     pub message_count: usize,
+    /// Excerpt of the message matching the current history search.
+    pub snippet: Option<String>,
 }
 
 impl SessionInfo {
@@ -34,6 +36,7 @@ impl SessionInfo {
             model: model.into(),
             created_at,
             message_count,
+            snippet: None,
         }
     }
 
@@ -3,10 +3,12 @@
 use std::path::PathBuf;
 
 use chrono::{Duration, Utc};
+use crossterm::event::{KeyCode, KeyEvent};
 
 use super::modal_impl::SessionsModal;
 use super::session_info::SessionInfo;
-use crate::modal::Modal;
+use crate::modal::{Modal, ModalAction, ModalResult};
+use crate::session::{SessionMeta, SessionStorage, StoredMessage};
 
 fn create_test_sessions() -> Vec<SessionInfo> {
     let now = Utc::now();
@@ -162,3 +164,27 @@ fn test_empty_sessions() {
     let modal = SessionsModal::new(vec![]);
     assert_eq!(modal.desired_height(20, 80), 6); // Minimum height
 }
+
+#[test]
+fn test_history_search_finds_unlisted_session() {
+    let temp = tempfile::TempDir::new().unwrap();
+    let storage = SessionStorage::with_dir(temp.path().to_path_buf());
+    let meta = SessionMeta::new("cortex", "test-model");
+    storage.save_meta(&meta).unwrap();
+    storage
+        .append_message(&meta.id, &StoredMessage::user("flaky migration in CI"))
+        .unwrap();
+
+    let mut modal = SessionsModal::new(create_test_sessions()).with_history_search(storage);
+    for c in "migration".chars() {
+        modal.handle_key(KeyEvent::from(KeyCode::Char(c)));
+    }
+    modal.handle_key(KeyEvent::from(KeyCode::Down));
+
+    match modal.handle_key(KeyEvent::from(KeyCode::Enter)) {
+        ModalResult::Action(ModalAction::SelectSession(path)) => {
+            assert_eq!(path, PathBuf::from(&meta.id));
+        }
+        _ => panic!("expected the matching session to be selected"),
+    }
+}
@@ -3,6 +3,8 @@
 //! This module provides a comprehensive permission system that controls
 //! which tools can be executed automatically and which require user approval.
 
+use cortex_engine::autonomy::AutonomyLevel;
+use cortex_engine::managed_policy::ManagedPolicy;
 use ratatui::style::Color;
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
@@ -65,6 +67,43 @@ impl PermissionMode {
             PermissionMode::High => PermissionMode::Yolo,
         }
     }
+
+    /// How much runs without asking, from least to most.
+    fn rank(self) -> u8 {
+        match self {
+            PermissionMode::High => 0,
+            PermissionMode::Medium => 1,
+            PermissionMode::Low => 2,
+            PermissionMode::Yolo => 3,
+        }
+    }
+
+    /// Most permissive mode a managed policy allows, if it limits autonomy.
+    pub fn managed_max(policy: &ManagedPolicy) -> Option<PermissionMode> {
+        policy.max_autonomy.map(|level| match level {
+            AutonomyLevel::Manual => PermissionMode::High,
+            AutonomyLevel::Low => PermissionMode::Medium,
+            AutonomyLevel::Medium => PermissionMode::Low,
+            AutonomyLevel::High | AutonomyLevel::SkipPermissionsUnsafe => PermissionMode::Yolo,
+        })
+    }
+
+    /// This mode, lowered to what `policy` allows.
+    pub fn clamp_to(self, policy: &ManagedPolicy) -> PermissionMode {
+        match Self::managed_max(policy) {
+            Some(max) if self.rank() > max.rank() => max,
+            _ => self,
+        }
+    }
+
+    /// This mode, lowered to what the system managed policy allows. A
+    /// policy that cannot be loaded allows only `High`.
+    pub fn clamp_to_managed(self) -> PermissionMode {
+        match ManagedPolicy::system() {
+            Ok(policy) => self.clamp_to(policy),
+            Err(_) => PermissionMode::High,
+        }
+    }
 }
 
 /// Risk level associated with a tool.
@@ -124,7 +163,7 @@ impl PermissionManager {
     /// Creates a new permission manager with default settings.
     pub fn new() -> Self {
         Self {
-            mode: PermissionMode::default(),
+            mode: PermissionMode::default().clamp_to_managed(),
             session_allowed: HashSet::new(),
             always_allowed: HashSet::new(),
         }
@@ -192,7 +231,8 @@ impl PermissionManager {
             "medium" => PermissionMode::Medium,
             "high" => PermissionMode::High,
             _ => PermissionMode::Medium,
-        };
+        }
+        .clamp_to_managed();
     }
 }
 
@@ -244,6 +284,27 @@ mod tests {
         assert_eq!(PermissionMode::High.cycle_next(), PermissionMode::Yolo);
     }
 
+    #[test]
+    fn test_permission_mode_clamped_to_managed_policy() {
+        let unmanaged = ManagedPolicy::default();
+        assert_eq!(
+            PermissionMode::Yolo.clamp_to(&unmanaged),
+            PermissionMode::Yolo
+        );
+
+        let low = ManagedPolicy {
+            max_autonomy: Some(AutonomyLevel::Low),
+            ..ManagedPolicy::default()
+        };
+        assert_eq!(PermissionMode::Yolo.clamp_to(&low), PermissionMode::Medium);
+        assert_eq!(PermissionMode::Low.clamp_to(&low), PermissionMode::Medium);
+        assert_eq!(
+            PermissionMode::Medium.clamp_to(&low),
+            PermissionMode::Medium
+        );
+        assert_eq!(PermissionMode::High.clamp_to(&low), PermissionMode::High);
+    }
+
     #[test]
     fn test_tool_risk_ordering() {
         assert!(ToolRisk::Safe < ToolRisk::Low);
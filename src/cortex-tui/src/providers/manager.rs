@@ -3,11 +3,15 @@
 //! Manages the connection to Cortex backend and handles completion requests.
 //! Requires authentication - all requests go through the Cortex API.
 
+use std::collections::HashMap;
+
 use anyhow::{Result, anyhow};
 use cortex_engine::client::{
     CompletionRequest, CompletionResponse, CortexClient, CortexModel, Message, ModelCapabilities,
-    ModelClient, ResponseStream, ToolDefinition, create_client,
+    ModelClient, ResponseStream, ToolDefinition, create_client, create_client_for_provider,
+    is_local_provider,
 };
+use cortex_engine::config::CustomProviderConfig;
 
 use super::config::CortexConfig;
 use super::models::{ModelInfo, get_models_for_provider, get_popular_models};
@@ -33,6 +37,8 @@ pub struct ProviderManager {
     client: Option<Box<dyn ModelClient>>,
     /// Cached models from backend.
     cached_models: Option<Vec<CortexModel>>,
+    /// Custom providers from the `[providers]` table of the engine config.
+    custom_providers: HashMap<String, CustomProviderConfig>,
 }
 
 impl ProviderManager {
@@ -70,6 +76,7 @@ impl ProviderManager {
             auth_token: None,
             client: None,
             cached_models: None,
+            custom_providers: HashMap::new(),
         }
     }
 
@@ -125,7 +132,41 @@ impl ProviderManager {
 
     /// Gets the display name of the current provider.
     pub fn current_provider_name(&self) -> &str {
-        "Cortex"
+        match self.current_provider.to_ascii_lowercase().as_str() {
+            "lmstudio" => "LM Studio",
+            "ollama" => "Ollama",
+            _ => "Cortex",
+        }
+    }
+
+    /// Sets the custom providers defined in the `[providers]` table of config.toml.
+    pub fn set_custom_providers(&mut self, providers: HashMap<String, CustomProviderConfig>) {
+        self.custom_providers = providers;
+        self.client = None;
+    }
+
+    /// Checks if the current provider is a custom provider from config.toml.
+    pub fn is_custom(&self) -> bool {
+        self.custom_providers.contains_key(&self.current_provider)
+    }
+
+    /// Checks if the current provider is a local inference server (LM Studio, Ollama).
+    pub fn is_local(&self) -> bool {
+        is_local_provider(&self.current_provider)
+    }
+
+    /// Gets the configured base URL for a local provider, if any.
+    ///
+    /// Returns `None` for the Cortex backend and when no override is configured,
+    /// in which case the local server's default URL is used.
+    pub fn local_base_url(&self) -> Option<String> {
+        if !self.is_local() {
+            return None;
+        }
+        self.config
+            .providers
+            .get(&self.current_provider)
+            .and_then(|p| p.base_url.clone())
     }
 
     /// Gets the display name of the current model.
@@ -217,9 +258,11 @@ impl ProviderManager {
         get_popular_models()
     }
 
-    /// Checks if the provider is available (authenticated).
+    /// Checks if the provider is available (authenticated or local).
     pub fn is_available(&self) -> bool {
-        self.is_authenticated()
+        self.is_local()
+            || self.is_custom()
+            || self.is_authenticated()
             || std::env::var("CORTEX_AUTH_TOKEN").is_ok()
             || cortex_login::has_valid_auth()
     }
@@ -403,6 +446,27 @@ impl ProviderManager {
             validate_chutes_model(&self.current_model).map_err(|e| anyhow::anyhow!("{}", e))?;
         }
 
+        if self.is_local() {
+            let base_url = self.local_base_url();
+            self.client = Some(create_client(
+                &self.current_provider,
+                &self.current_model,
+                "",
+                base_url.as_deref(),
+            )?);
+            return Ok(());
+        }
+
+        if let Some(provider) = self.custom_providers.get(&self.current_provider) {
+            self.client = Some(create_client_for_provider(
+                &self.current_provider,
+                provider,
+                &self.current_model,
+                None,
+            )?);
+            return Ok(());
+        }
+
         let token = self.get_token()?;
         self.client = Some(create_client("cortex", &self.current_model, &token, None)?);
         Ok(())
@@ -548,7 +612,11 @@ impl ProviderManager {
 
     /// Formats provider information for display.
     pub fn format_provider_info(&self) -> String {
-        format!("Cortex / {}", self.format_short_model())
+        format!(
+            "{} / {}",
+            self.current_provider_name(),
+            self.format_short_model()
+        )
     }
 
     /// Formats a short model identifier for status bar.
@@ -612,4 +680,41 @@ mod tests {
         manager.current_model = "anthropic/claude-opus-4-20250514".to_string();
         assert_eq!(manager.format_short_model(), "Opus 4");
     }
+
+    #[test]
+    fn test_local_provider_needs_no_auth() {
+        let mut manager = ProviderManager::new(CortexConfig::default());
+        manager.set_provider("lmstudio").unwrap();
+        manager.set_model("openai/gpt-oss-20b").unwrap();
+
+        assert!(manager.is_local());
+        assert!(manager.is_available());
+        assert_eq!(manager.current_provider_name(), "LM Studio");
+
+        manager.ensure_client().unwrap();
+        assert_eq!(manager.client.as_ref().unwrap().provider(), "lmstudio");
+    }
+
+    #[test]
+    fn test_custom_provider_client() {
+        let provider: CustomProviderConfig = toml::from_str(
+            r#"
+            name = "Local vLLM"
+            base_url = "http://localhost:8000/v1"
+            api_type = "openai-compatible"
+            "#,
+        )
+        .unwrap();
+
+        let mut manager = ProviderManager::new(CortexConfig::default());
+        manager.set_custom_providers(HashMap::from([("vllm".to_string(), provider)]));
+        manager.set_provider("vllm").unwrap();
+        manager.set_model("qwen3-coder").unwrap();
+
+        assert!(manager.is_custom());
+        assert!(manager.is_available());
+
+        manager.ensure_client().unwrap();
+        assert_eq!(manager.client.as_ref().unwrap().provider(), "vllm");
+    }
 }
@@ -7,8 +7,9 @@
 //! - Keyboard navigation (↑↓, 1-9, Enter, Esc)
 //! - Tab navigation for multiple questions
 
+use cortex_mcp_types::{ElicitRequest, SamplingRequest};
 use serde::{Deserialize, Serialize};
-use serde_json::Value;
+use serde_json::{Map, Value};
 
 // ============================================================
 // QUESTION TYPES
@@ -150,6 +151,135 @@ impl QuestionRequest {
     }
 }
 
+// ============================================================
+// MCP SERVER REQUESTS
+// ============================================================
+
+/// Option value for approving a sampling request.
+pub const SAMPLING_ALLOW: &str = "allow";
+
+impl QuestionRequest {
+    /// Build a form for an MCP elicitation request.
+    ///
+    /// Each property of the requested schema becomes one question: enums and
+    /// booleans as single choice, numbers as number input, the rest as text.
+    pub fn from_elicitation(id: &str, server: &str, request: &ElicitRequest) -> Self {
+        let schema = &request.requested_schema;
+        let required: Vec<&str> = schema
+            .get("required")
+            .and_then(|r| r.as_array())
+            .map(|r| r.iter().filter_map(|v| v.as_str()).collect())
+            .unwrap_or_default();
+
+        let mut questions = Vec::new();
+        if let Some(properties) = schema.get("properties").and_then(|p| p.as_object()) {
+            for (name, property) in properties {
+                let text = property
+                    .get("title")
+                    .or_else(|| property.get("description"))
+                    .and_then(|t| t.as_str())
+                    .unwrap_or(name)
+                    .to_string();
+                let mut question = Question {
+                    id: name.clone(),
+                    question: text,
+                    question_type: QuestionType::Text,
+                    options: Vec::new(),
+                    placeholder: property
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .map(String::from),
+                    required: required.contains(&name.as_str()),
+                    allow_custom: true,
+                };
+
+                match property.get("type").and_then(|t| t.as_str()) {
+                    Some("boolean") => {
+                        question.question_type = QuestionType::Single;
+                        question.options =
+                            vec![option("true", "Yes", None), option("false", "No", None)];
+                        question.allow_custom = false;
+                    }
+                    Some("number") | Some("integer") => {
+                        question.question_type = QuestionType::Number;
+                    }
+                    _ => {
+                        if let Some(values) = property.get("enum").and_then(|e| e.as_array()) {
+                            let labels = property.get("enumNames").and_then(|n| n.as_array());
+                            question.question_type = QuestionType::Single;
+                            question.allow_custom = false;
+                            question.options = values
+                                .iter()
+                                .enumerate()
+                                .filter_map(|(i, value)| {
+                                    let value = value.as_str()?;
+                                    let label = labels
+                                        .and_then(|l| l.get(i))
+                                        .and_then(|l| l.as_str())
+                                        .unwrap_or(value);
+                                    Some(option(value, label, None))
+                                })
+                                .collect();
+                        }
+                    }
+                }
+                questions.push(question);
+            }
+        }
+
+        QuestionRequest {
+            id: id.to_string(),
+            title: format!("{server} asks"),
+            description: Some(request.message.clone()),
+            questions,
+        }
+    }
+
+    /// Build an allow/deny prompt for an MCP sampling request.
+    pub fn for_sampling(id: &str, server: &str, request: &SamplingRequest) -> Self {
+        let preview = request
+            .messages
+            .last()
+            .and_then(|m| m.content.as_text())
+            .unwrap_or("(non-text content)");
+        let preview: String = preview.chars().take(200).collect();
+
+        QuestionRequest {
+            id: id.to_string(),
+            title: format!("{server} wants to use the model"),
+            description: Some(format!(
+                "{} message(s); last: {preview}",
+                request.messages.len()
+            )),
+            questions: vec![Question {
+                id: "decision".to_string(),
+                question: "Allow this sampling request?".to_string(),
+                question_type: QuestionType::Single,
+                options: vec![
+                    option(
+                        SAMPLING_ALLOW,
+                        "Allow",
+                        Some("Send the messages to the current model"),
+                    ),
+                    option("deny", "Deny", Some("Reject the request")),
+                ],
+                placeholder: None,
+                required: true,
+                allow_custom: false,
+            }],
+        }
+    }
+}
+
+fn option(value: &str, label: &str, description: Option<&str>) -> QuestionOption {
+    QuestionOption {
+        value: value.to_string(),
+        label: label.to_string(),
+        description: description.map(String::from),
+        selected: false,
+    }
+}
+
 // ============================================================
 // QUESTION STATE
 // ============================================================
@@ -443,6 +573,58 @@ impl QuestionState {
         Value::Object(result)
     }
 
+    /// Whether the user allowed a sampling request (see
+    /// [`QuestionRequest::for_sampling`]).
+    pub fn sampling_allowed(&self) -> bool {
+        self.elicitation_content(&Value::Null)
+            .get("decision")
+            .and_then(|v| v.as_str())
+            == Some(SAMPLING_ALLOW)
+    }
+
+    /// Get the answers as option values, typed according to an elicitation
+    /// schema. Unanswered questions are left out.
+    pub fn elicitation_content(&self, schema: &Value) -> Map<String, Value> {
+        let mut content = Map::new();
+
+        for (i, q) in self.request.questions.iter().enumerate() {
+            let Some(answer) = self.answers[i].first() else {
+                continue;
+            };
+            let answer = q
+                .options
+                .iter()
+                .find(|o| &o.label == answer)
+                .map(|o| o.value.as_str())
+                .unwrap_or(answer);
+
+            let kind = schema
+                .get("properties")
+                .and_then(|p| p.get(&q.id))
+                .and_then(|p| p.get("type"))
+                .and_then(|t| t.as_str());
+            let value = match kind {
+                Some("boolean") => Value::Bool(answer == "true"),
+                Some("integer") => answer
+                    .trim()
+                    .parse::<i64>()
+                    .map(Value::from)
+                    .unwrap_or_else(|_| Value::String(answer.to_string())),
+                Some("number") => answer
+                    .trim()
+                    .parse::<f64>()
+                    .ok()
+                    .and_then(serde_json::Number::from_f64)
+                    .map(Value::Number)
+                    .unwrap_or_else(|| Value::String(answer.to_string())),
+                _ => Value::String(answer.to_string()),
+            };
+            content.insert(q.id.clone(), value);
+        }
+
+        content
+    }
+
     /// Check if all required questions are answered
     pub fn is_complete(&self) -> bool {
         for (i, q) in self.request.questions.iter().enumerate() {
@@ -546,4 +728,60 @@ mod tests {
         state.move_down();
         assert_eq!(state.selected_index[0], 0); // wrap around
     }
+
+    #[test]
+    fn test_elicitation_form() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "env": {"type": "string", "title": "Environment", "enum": ["prod", "dev"], "enumNames": ["Production", "Development"]},
+                "replicas": {"type": "integer"},
+                "confirm": {"type": "boolean"},
+                "note": {"type": "string", "description": "Optional note"}
+            },
+            "required": ["env", "replicas"]
+        });
+        let request = QuestionRequest::from_elicitation(
+            "mcp-1",
+            "deploy",
+            &ElicitRequest::new("Deploy settings", schema.clone()),
+        );
+        assert_eq!(request.description.as_deref(), Some("Deploy settings"));
+        assert_eq!(request.questions.len(), 4);
+
+        let env = request.questions.iter().find(|q| q.id == "env").unwrap();
+        assert_eq!(env.question, "Environment");
+        assert_eq!(env.options[0].label, "Production");
+        assert!(env.required && !env.allow_custom);
+        let replicas = request
+            .questions
+            .iter()
+            .position(|q| q.id == "replicas")
+            .unwrap();
+        assert_eq!(
+            request.questions[replicas].question_type,
+            QuestionType::Number
+        );
+        let note = request.questions.iter().find(|q| q.id == "note").unwrap();
+        assert!(!note.required);
+
+        let mut state = QuestionState::new(request);
+        let index = |id: &str| {
+            state
+                .request
+                .questions
+                .iter()
+                .position(|q| q.id == id)
+                .unwrap()
+        };
+        let (env, replicas, confirm) = (index("env"), index("replicas"), index("confirm"));
+        state.answers[env] = vec!["Production".to_string()];
+        state.answers[replicas] = vec!["3".to_string()];
+        state.answers[confirm] = vec!["Yes".to_string()];
+
+        assert_eq!(
+            Value::Object(state.elicitation_content(&schema)),
+            json!({"env": "prod", "replicas": 3, "confirm": true})
+        );
+    }
 }
@@ -12,8 +12,9 @@ use crate::runner::terminal::{CortexTerminal, TerminalOptions};
 use crate::session::CortexSession;
 
 use anyhow::Result;
-use cortex_engine::Config;
+use cortex_engine::{Config, ManagedPolicy};
 use cortex_login::{CredentialsStoreMode, load_auth, logout_with_fallback};
+use cortex_network_proxy::NetworkProxy;
 use cortex_protocol::ConversationId;
 use cortex_update::UpdateManager;
 use std::path::PathBuf;
@@ -295,9 +296,14 @@ impl AppRunner {
     /// let exit_info = AppRunner::new(config).run().await?;
     /// println!("Exited: {:?}", exit_info.exit_reason);
     /// ```
-    pub async fn run(self) -> Result<AppExitInfo> {
+    pub async fn run(mut self) -> Result<AppExitInfo> {
         tracing::info!("Starting Cortex TUI");
 
+        // The managed policy wins over whatever config the caller built, before
+        // the network proxy and tool registry are set up from it
+        let policy = ManagedPolicy::system().map_err(|e| anyhow::anyhow!("{}", e))?;
+        self.apply_managed_policy(policy);
+
         // Use direct provider mode if enabled
         if self.use_direct_provider {
             return self.run_direct_provider().await;
@@ -354,6 +360,19 @@ impl AppRunner {
             ProviderManager::new(Default::default())
         });
 
+        // Local providers (`--oss`, LM Studio / Ollama) and custom `[providers]` entries
+        // are selected on the engine config
+        let provider_id = &self.config.model_provider_id;
+        provider_manager.set_custom_providers(self.config.providers.clone());
+        if cortex_engine::client::is_local_provider(provider_id)
+            || self.config.providers.contains_key(provider_id)
+        {
+            provider_manager.set_provider(provider_id)?;
+            if !self.config.model.is_empty() {
+                provider_manager.set_model(&self.config.model)?;
+            }
+        }
+
         // Try to load auth token from keyring and set it on the provider manager
         if let Some(token) = cortex_login::get_auth_token() {
             tracing::debug!("Loaded auth token from keyring");
@@ -363,6 +382,10 @@ impl AppRunner {
         // Check if user is authenticated (OAuth/API key login) or has API keys configured
         // This is a fast local check - no network calls
         let auth_status = match load_auth(&cortex_home, CredentialsStoreMode::default()) {
+            // Local inference servers and custom providers need no Cortex login
+            _ if provider_manager.is_local() || provider_manager.is_custom() => {
+                AuthStatus::Authenticated
+            }
             Ok(Some(auth)) if !auth.is_expired() => {
                 tracing::info!("User authenticated via {}", auth.mode);
                 AuthStatus::Authenticated
@@ -693,7 +716,9 @@ impl AppRunner {
         }
 
         // Extract and apply models if we got them from background task
-        if let Ok(Ok((_, Some(models)))) = validation_result {
+        if let Ok(Ok((_, Some(models)))) = validation_result
+            && !provider_manager.is_local()
+        {
             provider_manager.set_cached_models(models);
         }
 
@@ -706,11 +731,19 @@ impl AppRunner {
             // Get auth token using the centralized auth module
             // This properly handles: instance token → env var → keyring
             // Previous bug: only checked CORTEX_AUTH_TOKEN env var, missing keyring auth
-            let api_key = cortex_engine::auth_token::get_auth_token(None).ok();
-            let base_url = provider_manager.config().get_base_url(&provider);
+            // Local providers run without a key
+            let is_local = provider_manager.is_local();
+            let (api_key, base_url) = if is_local {
+                (Some(String::new()), provider_manager.local_base_url())
+            } else {
+                (
+                    cortex_engine::auth_token::get_auth_token(None).ok(),
+                    provider_manager.config().get_base_url(&provider),
+                )
+            };
 
             match api_key {
-                Some(api_key) if !api_key.is_empty() => {
+                Some(api_key) if is_local || !api_key.is_empty() => {
                     tracing::debug!(
                         "Using API key for UnifiedToolExecutor (length: {})",
                         api_key.len()
@@ -757,12 +790,18 @@ impl AppRunner {
             }
         };
 
+        // Start the network proxy before any tool can run
+        let network_proxy = Self::start_network_proxy(&self.config).await?;
+
         // Create tool registry for executing tools
         let tool_registry = {
             use cortex_engine::tools::ToolRegistry;
             use std::sync::Arc;
 
-            let registry = ToolRegistry::new();
+            let mut registry = ToolRegistry::new();
+            if let Some(proxy) = &network_proxy {
+                registry.set_env(proxy.env_vars());
+            }
             tracing::info!("Initialized ToolRegistry");
             Arc::new(registry)
         };
@@ -773,6 +812,10 @@ impl AppRunner {
             .with_cortex_session(cortex_session)
             .with_tool_registry(tool_registry);
 
+        if let Some(proxy) = network_proxy {
+            event_loop = event_loop.with_network_proxy(proxy);
+        }
+
         // Add unified executor if available
         if let Some(executor) = unified_executor {
             event_loop = event_loop.with_unified_executor(executor);
@@ -886,12 +929,18 @@ impl AppRunner {
             );
         }
 
+        // Start the network proxy before any tool can run
+        let network_proxy = Self::start_network_proxy(&self.config).await?;
+
         // Create tool registry for executing tools
         let tool_registry = {
             use cortex_engine::tools::ToolRegistry;
             use std::sync::Arc;
 
-            let registry = ToolRegistry::new();
+            let mut registry = ToolRegistry::new();
+            if let Some(proxy) = &network_proxy {
+                registry.set_env(proxy.env_vars());
+            }
             tracing::info!("Initialized ToolRegistry (legacy mode)");
             Arc::new(registry)
         };
@@ -901,6 +950,10 @@ impl AppRunner {
             .with_session(session_bridge)
             .with_tool_registry(tool_registry);
 
+        if let Some(proxy) = network_proxy {
+            event_loop = event_loop.with_network_proxy(proxy);
+        }
+
         // Load persisted MCP server configurations
         event_loop.load_mcp_servers();
 
@@ -1006,6 +1059,28 @@ impl AppRunner {
             }
         }
     }
+
+    /// Clamp the session config to a managed policy.
+    fn apply_managed_policy(&mut self, policy: &ManagedPolicy) {
+        policy.apply(&mut self.config);
+    }
+
+    /// Start the network proxy if `[network_proxy]` is enabled, which the
+    /// managed policy's `[network]` forces.
+    ///
+    /// Failing to start is fatal: tools never run without a configured
+    /// proxy.
+    async fn start_network_proxy(config: &Config) -> Result<Option<std::sync::Arc<NetworkProxy>>> {
+        let Some(config) = config.network_proxy.clone().filter(|c| c.enabled) else {
+            return Ok(None);
+        };
+
+        let proxy = NetworkProxy::start(config)
+            .await
+            .map_err(|e| anyhow::anyhow!("Failed to start network proxy: {}", e))?;
+        tracing::info!("Network proxy listening on {}", proxy.addr());
+        Ok(Some(std::sync::Arc::new(proxy)))
+    }
 }
 
 // ============================================================================
@@ -1017,6 +1092,32 @@ mod tests {
     use super::*;
     use crate::runner::terminal::TerminalOptions;
 
+    #[tokio::test]
+    async fn test_managed_network_policy_starts_proxy() {
+        let mut runner = AppRunner::new(Config::default());
+        assert!(
+            AppRunner::start_network_proxy(&runner.config)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let policy = ManagedPolicy {
+            network: Some(cortex_engine::managed_policy::ManagedNetwork {
+                allowed_domains: vec!["example.com".to_string()],
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        runner.apply_managed_policy(&policy);
+
+        let proxy = AppRunner::start_network_proxy(&runner.config)
+            .await
+            .unwrap()
+            .expect("managed [network] should start the proxy");
+        assert!(!proxy.env_vars().is_empty());
+    }
+
     #[test]
     fn test_app_runner_builder() {
         let config = Config::default();
@@ -151,12 +151,20 @@ impl EventLoop {
                     crate::permissions::PermissionMode::Yolo
                 );
                 if is_yolo {
-                    self.app_state.permission_mode = crate::permissions::PermissionMode::High;
+                    self.app_state
+                        .set_permission_mode(crate::permissions::PermissionMode::High);
                     self.app_state.toasts.info("Auto-approve: OFF");
                 } else {
-                    self.app_state.permission_mode = crate::permissions::PermissionMode::Yolo;
-                    self.app_state.toasts.success("Auto-approve: ON");
+                    self.app_state
+                        .set_permission_mode(crate::permissions::PermissionMode::Yolo);
+                    if matches!(
+                        self.app_state.permission_mode,
+                        crate::permissions::PermissionMode::Yolo
+                    ) {
+                        self.app_state.toasts.success("Auto-approve: ON");
+                    }
                 }
+                self.sync_permission_mode();
             }
             _ => {
                 self.app_state
@@ -392,6 +400,9 @@ impl EventLoop {
             "history" => {
                 self.handle_history();
             }
+            "network" => {
+                self.handle_network_log();
+            }
             "models:fetch-and-pick" => {
                 // First, fetch models from the backend to populate the cache
                 if let Some(pm) = &self.provider_manager {
@@ -1,11 +1,12 @@
 //! Core EventLoop struct definition and main run loop.
 
+use std::collections::VecDeque;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::time::{Duration, Instant};
 
 use anyhow::Result;
-use tokio::sync::mpsc;
+use tokio::sync::{broadcast, mpsc};
 use tokio::task::JoinHandle;
 
 use crate::actions::{ActionContext, ActionMapper};
@@ -23,8 +24,10 @@ use crate::session::CortexSession;
 
 use crate::capture::TuiCapture;
 use cortex_core::EngineEvent;
+use cortex_engine::mcp::McpUserRequest;
 use cortex_engine::streaming::StreamEvent;
 use cortex_engine::tools::{ToolRegistry, UnifiedToolExecutor};
+use cortex_network_proxy::{NetworkProxy, ProxyRequestLog};
 
 // ============================================================================
 // ERROR MESSAGE HELPERS
@@ -155,6 +158,21 @@ pub struct EventLoop {
     pub(super) tool_event_tx: mpsc::Sender<ToolEvent>,
     pub(super) tool_event_rx: Option<mpsc::Receiver<ToolEvent>>,
 
+    /// Channel for MCP server requests that need the user.
+    pub(super) mcp_request_tx: mpsc::UnboundedSender<McpUserRequest>,
+    pub(super) mcp_request_rx: Option<mpsc::UnboundedReceiver<McpUserRequest>>,
+    /// MCP requests waiting for the question prompt.
+    pub(super) mcp_request_queue: VecDeque<McpUserRequest>,
+    /// MCP request currently shown, with its question prompt ID.
+    pub(super) active_mcp_request: Option<(String, McpUserRequest)>,
+    /// Counter for MCP question prompt IDs.
+    pub(super) next_mcp_request_id: u64,
+
+    /// Proxy tool processes reach the network through.
+    pub(super) network_proxy: Option<Arc<NetworkProxy>>,
+    /// Requests handled by the network proxy.
+    pub(super) network_log_rx: Option<broadcast::Receiver<ProxyRequestLog>>,
+
     /// Whether the current streaming is a continuation after tool results.
     /// When true, tool calls should NOT be cleared on StreamEvent::Done.
     pub(super) is_continuation: bool,
@@ -172,6 +190,7 @@ impl EventLoop {
     pub fn new(app_state: AppState) -> Self {
         // Create channel for tool execution events
         let (tool_event_tx, tool_event_rx) = mpsc::channel::<ToolEvent>(100);
+        let (mcp_request_tx, mcp_request_rx) = mpsc::unbounded_channel();
 
         // Initialize TUI capture with terminal size from app state
         let (width, height) = app_state.terminal_size;
@@ -208,6 +227,13 @@ impl EventLoop {
             tool_execution_started: false,
             tool_event_tx,
             tool_event_rx: Some(tool_event_rx),
+            mcp_request_tx,
+            mcp_request_rx: Some(mcp_request_rx),
+            mcp_request_queue: VecDeque::new(),
+            active_mcp_request: None,
+            next_mcp_request_id: 0,
+            network_proxy: None,
+            network_log_rx: None,
             is_continuation: false,
             _undo_stack: Vec::new(),
             tui_capture,
@@ -365,6 +391,41 @@ impl EventLoop {
                         tracing::error!("Error rendering after tool event: {}", e);
                     }
                 }
+
+                // Branch 4: MCP server requests that need the user
+                Some(mcp_request) = async {
+                    match self.mcp_request_rx.as_mut() {
+                        Some(rx) => rx.recv().await,
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    self.handle_mcp_user_request(mcp_request);
+                    if let Err(e) = self.render(terminal) {
+                        tracing::error!("Error rendering after MCP request: {}", e);
+                    }
+                }
+
+                // Branch 5: Requests handled by the network proxy
+                entry = async {
+                    match self.network_log_rx.as_mut() {
+                        Some(rx) => loop {
+                            match rx.recv().await {
+                                Ok(entry) => break entry,
+                                // Only the toast for skipped entries is lost
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => {
+                                    std::future::pending().await
+                                }
+                            }
+                        },
+                        None => std::future::pending().await,
+                    }
+                } => {
+                    self.handle_network_request(entry);
+                    if let Err(e) = self.render(terminal) {
+                        tracing::error!("Error rendering after network request: {}", e);
+                    }
+                }
             }
         }
 
@@ -388,16 +449,10 @@ impl EventLoop {
         Ok(())
     }
 
-    /// Syncs the permission mode between app state and permission manager.
+    /// Syncs the permission mode between app state and permission manager,
+    /// within what the managed policy allows.
     pub(super) fn sync_permission_mode(&mut self) {
-        self.permission_manager.mode = match self.app_state.permission_mode {
-            crate::permissions::PermissionMode::Yolo => crate::permissions::PermissionMode::Yolo,
-            crate::permissions::PermissionMode::Low => crate::permissions::PermissionMode::Low,
-            crate::permissions::PermissionMode::Medium => {
-                crate::permissions::PermissionMode::Medium
-            }
-            crate::permissions::PermissionMode::High => crate::permissions::PermissionMode::High,
-        };
+        self.permission_manager.mode = self.app_state.permission_mode.clamp_to_managed();
     }
 
     /// Updates the session's model metadata and persists it.
@@ -0,0 +1,98 @@
+//! MCP server requests that need the user.
+//!
+//! Sampling approvals and elicitation requests arrive from connected MCP
+//! servers and are shown one at a time as question prompts. The server
+//! waits until the prompt is answered or dismissed.
+
+use cortex_engine::mcp::McpUserRequest;
+use cortex_mcp_types::ElicitResult;
+use tokio::sync::mpsc;
+
+use crate::question::{QuestionRequest, QuestionState};
+
+use super::core::EventLoop;
+
+impl EventLoop {
+    /// Sender for MCP server requests that need the user.
+    ///
+    /// Pass it to `McpServerRequests::with_user_channel` when connecting
+    /// MCP servers.
+    pub fn mcp_user_requests(&self) -> mpsc::UnboundedSender<McpUserRequest> {
+        self.mcp_request_tx.clone()
+    }
+
+    /// Queues a request and shows it when no other prompt is open.
+    pub(super) fn handle_mcp_user_request(&mut self, request: McpUserRequest) {
+        self.mcp_request_queue.push_back(request);
+        self.show_next_mcp_request();
+    }
+
+    /// Shows the next queued request as a question prompt.
+    pub(super) fn show_next_mcp_request(&mut self) {
+        if self.active_mcp_request.is_some() || self.app_state.has_question_prompt() {
+            return;
+        }
+
+        while let Some(request) = self.mcp_request_queue.pop_front() {
+            self.next_mcp_request_id += 1;
+            let id = format!("mcp-request-{}", self.next_mcp_request_id);
+            let question = match &request {
+                // The server stopped waiting, e.g. because it was disconnected.
+                McpUserRequest::Sampling { respond, .. } if respond.is_closed() => continue,
+                McpUserRequest::Elicitation { respond, .. } if respond.is_closed() => continue,
+                McpUserRequest::Sampling {
+                    server, request, ..
+                } => QuestionRequest::for_sampling(&id, server, request),
+                McpUserRequest::Elicitation {
+                    server, request, ..
+                } => QuestionRequest::from_elicitation(&id, server, request),
+            };
+
+            self.app_state
+                .start_question_prompt(QuestionState::new(question));
+            self.active_mcp_request = Some((id, request));
+            return;
+        }
+    }
+
+    /// Whether the question prompt with this ID belongs to an MCP request.
+    pub(super) fn is_mcp_request_prompt(&self, id: &str) -> bool {
+        self.active_mcp_request
+            .as_ref()
+            .is_some_and(|(active, _)| active == id)
+    }
+
+    /// Answers the active MCP request, `None` meaning the prompt was dismissed.
+    pub(super) fn resolve_mcp_request(&mut self, answers: Option<&QuestionState>) {
+        let Some((_, request)) = self.active_mcp_request.take() else {
+            return;
+        };
+
+        match request {
+            McpUserRequest::Sampling { respond, .. } => {
+                let _ = respond.send(answers.is_some_and(QuestionState::sampling_allowed));
+            }
+            McpUserRequest::Elicitation {
+                request, respond, ..
+            } => {
+                let result = match answers {
+                    Some(state) => {
+                        ElicitResult::accept(state.elicitation_content(&request.requested_schema))
+                    }
+                    None => ElicitResult::cancel(),
+                };
+                let _ = respond.send(result);
+            }
+        }
+
+        self.show_next_mcp_request();
+    }
+
+    /// Puts the active MCP request back in the queue when another question
+    /// prompt takes over the screen.
+    pub(super) fn requeue_mcp_request(&mut self) {
+        if let Some((_, request)) = self.active_mcp_request.take() {
+            self.mcp_request_queue.push_front(request);
+        }
+    }
+}
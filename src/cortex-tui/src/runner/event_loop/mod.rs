@@ -31,8 +31,10 @@ mod auth;
 mod commands;
 mod core;
 mod input;
+mod mcp;
 mod modal;
 mod mouse;
+mod network;
 mod rendering;
 mod streaming;
 mod subagent;
@@ -441,10 +441,14 @@ impl EventLoop {
                         model: s.model,
                         created_at: s.created_at,
                         message_count: s.message_count as usize,
+                        snippet: None,
                     })
                     .collect();
-                self.modal_stack
-                    .push(Box::new(SessionsModal::new(session_infos)));
+                let mut modal = SessionsModal::new(session_infos);
+                if let Ok(storage) = crate::session::SessionStorage::new() {
+                    modal = modal.with_history_search(storage);
+                }
+                self.modal_stack.push(Box::new(modal));
             }
             Err(e) => {
                 self.add_system_message(&format!("Failed to list sessions: {}", e));
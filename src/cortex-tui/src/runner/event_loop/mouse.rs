@@ -500,6 +500,12 @@ impl EventLoop {
         };
 
         let tool_call_id = q_state.request.id.clone();
+        if self.is_mcp_request_prompt(&tool_call_id) {
+            let q_state = q_state.clone();
+            self.app_state.complete_question_prompt();
+            self.resolve_mcp_request(Some(&q_state));
+            return Ok(());
+        }
         let answers = q_state.get_formatted_answers();
 
         let formatted_answers = serde_json::to_string_pretty(&answers).unwrap_or_default();
@@ -513,6 +519,7 @@ impl EventLoop {
         );
 
         self.app_state.complete_question_prompt();
+        self.show_next_mcp_request();
 
         self.app_state
             .add_pending_tool_result(tool_call_id, "Questions".to_string(), output, true);
@@ -529,6 +536,11 @@ impl EventLoop {
         let Some(tool_call_id) = self.app_state.cancel_question_prompt() else {
             return Ok(());
         };
+        if self.is_mcp_request_prompt(&tool_call_id) {
+            self.resolve_mcp_request(None);
+            return Ok(());
+        }
+        self.show_next_mcp_request();
 
         let output = "User dismissed the questions without answering.".to_string();
 
@@ -0,0 +1,68 @@
+//! Requests seen by the network proxy.
+//!
+//! When `[network_proxy]` is configured, tool processes reach the network
+//! through a local proxy. Blocked requests raise a toast as they happen and
+//! `/network` lists the session's recent requests.
+
+use std::sync::Arc;
+
+use cortex_network_proxy::{NetworkProxy, ProxyRequestLog, RequestOutcome};
+
+use super::core::EventLoop;
+
+/// Number of requests listed by `/network`.
+const NETWORK_LOG_LINES: usize = 30;
+
+impl EventLoop {
+    /// Sets the network proxy tool processes are routed through.
+    pub fn with_network_proxy(mut self, proxy: Arc<NetworkProxy>) -> Self {
+        self.network_log_rx = Some(proxy.state().subscribe());
+        self.network_proxy = Some(proxy);
+        self
+    }
+
+    /// Surfaces a request the proxy just handled.
+    pub(super) fn handle_network_request(&mut self, entry: ProxyRequestLog) {
+        if let RequestOutcome::Blocked(reason) = &entry.outcome {
+            self.app_state.toasts.warning(format!(
+                "Blocked {} {}:{} ({})",
+                entry.method, entry.host, entry.port, reason
+            ));
+        }
+    }
+
+    /// Lists the session's recent network requests.
+    pub(super) fn handle_network_log(&mut self) {
+        let Some(proxy) = &self.network_proxy else {
+            self.add_system_message(
+                "Network proxy is not enabled. Add a [network_proxy] section to config.toml.",
+            );
+            return;
+        };
+
+        let policy = proxy.state().policy();
+        let metrics = proxy.state().metrics();
+        let mut output = format!(
+            "Network proxy on {} ({} mode)\n  Allowed: {}  Blocked: {}  Bytes: {}\n",
+            proxy.addr(),
+            policy.mode(),
+            metrics.allowed(),
+            metrics.blocked(),
+            metrics.bytes()
+        );
+
+        let requests = proxy.state().recent_requests();
+        if requests.is_empty() {
+            output.push_str("\nNo requests yet.");
+        } else {
+            output.push('\n');
+            let skip = requests.len().saturating_sub(NETWORK_LOG_LINES);
+            for entry in &requests[skip..] {
+                let time = chrono::DateTime::<chrono::Local>::from(entry.timestamp);
+                output.push_str(&format!("  {} {}\n", time.format("%H:%M:%S"), entry));
+            }
+        }
+
+        self.add_system_message(&output);
+    }
+}
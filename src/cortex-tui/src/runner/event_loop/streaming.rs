@@ -548,6 +548,7 @@ impl EventLoop {
             && let Some(request) = QuestionRequest::from_tool_args(&id, &arguments)
         {
             let state = QuestionState::new(request);
+            self.requeue_mcp_request();
             self.app_state.start_question_prompt(state);
             self.app_state.update_tool_status(&id, ToolStatus::Running);
             // Don't execute the tool yet - wait for user answers
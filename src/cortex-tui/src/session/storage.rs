@@ -4,6 +4,7 @@
 //! Uses a directory-per-session structure with atomic writes for safety.
 
 use anyhow::{Context, Result};
+use cortex_storage::{SearchHit, SearchIndex};
 use std::fs::{self, File};
 use std::io::{BufRead, BufReader, BufWriter, Write};
 use std::path::{Path, PathBuf};
@@ -21,6 +22,9 @@ const META_FILE: &str = "meta.json";
 /// History file name.
 const HISTORY_FILE: &str = "history.jsonl";
 
+/// Search index directory name (skipped when listing sessions).
+const INDEX_DIR: &str = ".index";
+
 // ============================================================
 // SESSION STORAGE
 // ============================================================
@@ -58,6 +62,11 @@ impl SessionStorage {
         self.session_dir(session_id).join(META_FILE)
     }
 
+    /// Gets the full-text search index over all sessions' history.
+    pub fn search_index(&self) -> SearchIndex {
+        SearchIndex::new(self.base_dir.join(INDEX_DIR))
+    }
+
     /// Gets the history file path for a session.
     pub fn history_path(&self, session_id: &str) -> PathBuf {
         self.session_dir(session_id).join(HISTORY_FILE)
@@ -147,6 +156,9 @@ impl SessionStorage {
             .sync_all()
             .with_context(|| format!("Failed to sync history file to disk: {:?}", path))?;
 
+        self.update_index(session_id, |index| {
+            index.add_message(session_id, &message.into())
+        });
         Ok(())
     }
 
@@ -212,6 +224,10 @@ impl SessionStorage {
         fs::rename(&temp_path, &path)
             .with_context(|| format!("Failed to rename history file: {:?}", path))?;
 
+        self.update_index(session_id, |index| {
+            let messages: Vec<_> = messages.iter().map(Into::into).collect();
+            index.reindex_session(session_id, &messages)
+        });
         Ok(())
     }
 
@@ -264,9 +280,76 @@ impl SessionStorage {
             fs::remove_dir_all(&dir)
                 .with_context(|| format!("Failed to delete session directory: {:?}", dir))?;
         }
+        self.update_index(session_id, |index| index.remove_session(session_id));
         Ok(())
     }
 
+    // ========================================================================
+    // SESSION SEARCH
+    // ========================================================================
+
+    /// Searches message contents, tool names, paths and commands across all
+    /// sessions, returning the best matching sessions with snippets.
+    pub fn search_history(&self, query: &str, limit: usize) -> Result<Vec<SearchHit>> {
+        let index = self.search_index();
+        if !index.exists() {
+            self.rebuild_search_index()?;
+        }
+        let hits = index.search(query, limit)?;
+        hits.into_iter()
+            .map(|hit| {
+                let messages: Vec<_> = self
+                    .load_messages(&hit.session_id)?
+                    .iter()
+                    .map(Into::into)
+                    .collect();
+                Ok(hit.with_snippet(&messages))
+            })
+            .collect()
+    }
+
+    /// Rebuilds the search index from every session's history.
+    pub fn rebuild_search_index(&self) -> Result<usize> {
+        let mut histories = Vec::new();
+        for summary in self.list_sessions()? {
+            let messages: Vec<cortex_storage::StoredMessage> = self
+                .load_messages(&summary.id)?
+                .iter()
+                .map(Into::into)
+                .collect();
+            histories.push((summary.id, messages));
+        }
+        let indexed = self.search_index().rebuild(
+            histories
+                .iter()
+                .map(|(id, messages)| (id.as_str(), messages.as_slice())),
+        )?;
+        Ok(indexed)
+    }
+
+    /// Applies a change to the search index, building it first if needed.
+    ///
+    /// History files are the source of truth, so failures are only logged.
+    fn update_index(
+        &self,
+        session_id: &str,
+        update: impl FnOnce(&SearchIndex) -> cortex_storage::Result<()>,
+    ) {
+        let index = self.search_index();
+        let result = if index.exists() {
+            update(&index).map_err(anyhow::Error::from)
+        } else {
+            self.rebuild_search_index().map(|_| ())
+        };
+        if let Err(e) = result {
+            tracing::warn!(
+                "Failed to update search index for session {}: {}",
+                session_id,
+                e
+            );
+        }
+    }
+
     /// Archives a session (sets archived flag in metadata).
     pub fn archive_session(&self, session_id: &str) -> Result<()> {
         let mut meta = self.load_meta(session_id)?;
@@ -334,6 +417,7 @@ impl Default for SessionStorage {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::session::types::StoredToolCall;
     use tempfile::TempDir;
 
     fn create_test_storage() -> (SessionStorage, TempDir) {
@@ -429,4 +513,47 @@ mod tests {
         let loaded = storage.load_meta(&session_id).unwrap();
         assert!(loaded.archived);
     }
+
+    #[test]
+    fn test_search_history() {
+        let (storage, _temp) = create_test_storage();
+        let meta = SessionMeta::new("cortex", "test-model");
+        let session_id = meta.id.clone();
+        storage.save_meta(&meta).unwrap();
+
+        let question = StoredMessage::user("Why is the login page blank?");
+        let mut answer = StoredMessage::assistant("Checking the bundle.");
+        answer.tool_calls.push(StoredToolCall::new(
+            "call-1",
+            "Execute",
+            serde_json::json!({"command": ["npm", "run", "build"]}),
+        ));
+        storage.append_message(&session_id, &question).unwrap();
+        storage.append_message(&session_id, &answer).unwrap();
+
+        let hits = storage.search_history("npm build", 10).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].session_id, session_id);
+        assert_eq!(hits[0].message_id, answer.id);
+        assert!(
+            hits[0]
+                .snippet
+                .as_deref()
+                .unwrap()
+                .contains("npm run build")
+        );
+
+        // Rewriting history (undo) re-indexes the session
+        storage
+            .rewrite_history(&session_id, std::slice::from_ref(&question))
+            .unwrap();
+        assert!(storage.search_history("npm", 10).unwrap().is_empty());
+        assert_eq!(storage.search_history("login", 10).unwrap().len(), 1);
+
+        // The index directory is not listed as a session
+        assert_eq!(storage.list_sessions().unwrap().len(), 1);
+
+        storage.delete_session(&session_id).unwrap();
+        assert!(storage.search_history("login", 10).unwrap().is_empty());
+    }
 }
@@ -357,6 +357,30 @@ impl StoredToolCall {
     }
 }
 
+impl From<&StoredMessage> for cortex_storage::StoredMessage {
+    /// Converts to the shared storage format used by the search index.
+    fn from(message: &StoredMessage) -> Self {
+        Self {
+            id: message.id.clone(),
+            role: message.role.clone(),
+            content: message.content.clone(),
+            timestamp: message.timestamp.timestamp(),
+            tool_calls: message
+                .tool_calls
+                .iter()
+                .map(|call| cortex_storage::StoredToolCall {
+                    id: call.id.clone(),
+                    name: call.name.clone(),
+                    input: call.input.clone(),
+                    output: call.output.clone(),
+                    success: call.success,
+                    duration_ms: call.duration_ms,
+                })
+                .collect(),
+        }
+    }
+}
+
 // ============================================================
 // SESSION SUMMARY
 // ============================================================
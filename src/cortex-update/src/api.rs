@@ -1,4 +1,14 @@
 //! Cortex Foundation Software API client.
+//!
+//! Besides the distribution API, the client reads self-hosted mirrors that
+//! serve the same release manifests as static files:
+//!
+//! ```text
+//! <mirror>/channels/<channel>.json   latest release of a channel
+//! <mirror>/releases/<version>.json   a specific release
+//! ```
+//!
+//! Asset and signature URLs in mirror manifests may be relative to the mirror.
 
 use std::collections::HashMap;
 use std::path::Path;
@@ -73,11 +83,22 @@ pub struct ChangelogEntry {
     pub changes: Vec<String>,
 }
 
+/// How releases are looked up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Layout {
+    /// The distribution API (`/v1/releases/...`).
+    Api,
+    /// Static manifest files, as served by a self-hosted mirror.
+    Mirror,
+}
+
 /// Client for the Cortex Foundation Software Distribution API.
 #[derive(Clone)]
 pub struct CortexSoftwareClient {
     client: Client,
     base_url: String,
+    layout: Layout,
+    allow_http: bool,
 }
 
 impl CortexSoftwareClient {
@@ -101,12 +122,30 @@ impl CortexSoftwareClient {
     /// Returns `UpdateError::InsecureUrl` if the URL does not use HTTPS
     /// (except for localhost/127.0.0.1 which are allowed for development).
     pub fn with_url(base_url: String) -> UpdateResult<Self> {
+        Self::build(base_url, Layout::Api, false)
+    }
+
+    /// Create a client for a self-hosted mirror: a local directory, a
+    /// `file://` URL or an HTTP(S) base URL.
+    ///
+    /// # Errors
+    ///
+    /// Returns `UpdateError::InsecureUrl` for plain `http://` mirrors other than
+    /// localhost unless `allow_http` is set, which callers should only do when
+    /// release signatures are verified.
+    pub fn mirror(location: &str, allow_http: bool) -> UpdateResult<Self> {
+        let base_url = if location.contains("://") {
+            location.trim_end_matches('/').to_string()
+        } else {
+            let dir = std::path::absolute(location)?;
+            format!("file://{}", dir.display())
+        };
+        Self::build(base_url, Layout::Mirror, allow_http)
+    }
+
+    fn build(base_url: String, layout: Layout, allow_http: bool) -> UpdateResult<Self> {
         // Validate URL uses HTTPS for security (allow http for localhost development only)
-        let url_lower = base_url.to_lowercase();
-        if !url_lower.starts_with("https://")
-            && !url_lower.starts_with("http://localhost")
-            && !url_lower.starts_with("http://127.0.0.1")
-        {
+        if !is_allowed_url(&base_url, layout, allow_http) {
             return Err(UpdateError::InsecureUrl { url: base_url });
         }
 
@@ -114,11 +153,33 @@ impl CortexSoftwareClient {
             .build()
             .unwrap_or_else(|_| Client::new());
 
-        Ok(Self { client, base_url })
+        Ok(Self {
+            client,
+            base_url,
+            layout,
+            allow_http,
+        })
+    }
+
+    /// Whether this client reads a self-hosted mirror.
+    pub fn is_mirror(&self) -> bool {
+        self.layout == Layout::Mirror
     }
 
     /// Get the latest release for a channel.
     pub async fn get_latest(&self, channel: ReleaseChannel) -> UpdateResult<ReleaseInfo> {
+        if self.is_mirror() {
+            let url = format!("{}/channels/{}.json", self.base_url, channel.as_str());
+            let bytes = self
+                .fetch(&url)
+                .await?
+                .ok_or_else(|| UpdateError::ServerError {
+                    status: 404,
+                    message: format!("No release manifest at {}", url),
+                })?;
+            return Ok(self.resolve(serde_json::from_slice(&bytes)?));
+        }
+
         let url = format!(
             "{}/v1/releases/latest?channel={}",
             self.base_url,
@@ -159,6 +220,17 @@ impl CortexSoftwareClient {
 
     /// Get a specific release by version.
     pub async fn get_release(&self, version: &str) -> UpdateResult<ReleaseInfo> {
+        if self.is_mirror() {
+            let url = format!("{}/releases/{}.json", self.base_url, version);
+            let bytes = self
+                .fetch(&url)
+                .await?
+                .ok_or_else(|| UpdateError::VersionNotFound {
+                    version: version.to_string(),
+                })?;
+            return Ok(self.resolve(serde_json::from_slice(&bytes)?));
+        }
+
         let url = format!("{}/v1/releases/{}", self.base_url, version);
 
         let response =
@@ -187,7 +259,13 @@ impl CortexSoftwareClient {
     }
 
     /// Get changelog entries since a version.
+    ///
+    /// Mirrors do not serve changelogs, so they always return no entries.
     pub async fn get_changelog(&self, since: &str) -> UpdateResult<Vec<ChangelogEntry>> {
+        if self.is_mirror() {
+            return Ok(Vec::new());
+        }
+
         let url = format!("{}/v1/changelog?since={}", self.base_url, since);
 
         let response =
@@ -220,16 +298,23 @@ impl CortexSoftwareClient {
         F: FnMut(u64, u64), // (downloaded, total)
     {
         // Validate asset URL uses HTTPS (allow localhost for development)
-        let url_lower = asset.url.to_lowercase();
-        if !url_lower.starts_with("https://")
-            && !url_lower.starts_with("http://localhost")
-            && !url_lower.starts_with("http://127.0.0.1")
-        {
+        if !is_allowed_url(&asset.url, self.layout, self.allow_http) {
             return Err(UpdateError::InsecureUrl {
                 url: asset.url.clone(),
             });
         }
 
+        if let Some(path) = file_path(&asset.url) {
+            let copied =
+                tokio::fs::copy(path, dest)
+                    .await
+                    .map_err(|e| UpdateError::DownloadFailed {
+                        message: format!("{}: {}", path, e),
+                    })?;
+            on_progress(copied, asset.size.max(copied));
+            return Ok(());
+        }
+
         let response =
             self.client
                 .get(&asset.url)
@@ -269,6 +354,20 @@ impl CortexSoftwareClient {
 
     /// Download a signature file.
     pub async fn download_signature(&self, url: &str) -> UpdateResult<Vec<u8>> {
+        if !is_allowed_url(url, self.layout, self.allow_http) {
+            return Err(UpdateError::InsecureUrl {
+                url: url.to_string(),
+            });
+        }
+
+        if let Some(path) = file_path(url) {
+            return tokio::fs::read(path)
+                .await
+                .map_err(|e| UpdateError::DownloadFailed {
+                    message: format!("Failed to read signature {}: {}", path, e),
+                });
+        }
+
         let response =
             self.client
                 .get(url)
@@ -287,6 +386,68 @@ impl CortexSoftwareClient {
         let bytes = response.bytes().await?;
         Ok(bytes.to_vec())
     }
+
+    /// Fetch a mirror file; `None` if it does not exist.
+    async fn fetch(&self, url: &str) -> UpdateResult<Option<Vec<u8>>> {
+        if let Some(path) = file_path(url) {
+            return match tokio::fs::read(path).await {
+                Ok(bytes) => Ok(Some(bytes)),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+                Err(e) => Err(e.into()),
+            };
+        }
+
+        let response =
+            self.client
+                .get(url)
+                .send()
+                .await
+                .map_err(|e| UpdateError::ConnectionFailed {
+                    message: e.to_string(),
+                })?;
+
+        if response.status().as_u16() == 404 {
+            return Ok(None);
+        }
+
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = response.text().await.unwrap_or_default();
+            return Err(UpdateError::ServerError { status, message });
+        }
+
+        Ok(Some(response.bytes().await?.to_vec()))
+    }
+
+    /// Make relative asset and signature URLs of a mirror manifest absolute.
+    fn resolve(&self, mut info: ReleaseInfo) -> ReleaseInfo {
+        let resolve = |url: &mut String| {
+            if !url.contains("://") {
+                *url = format!("{}/{}", self.base_url, url.trim_start_matches('/'));
+            }
+        };
+        info.assets
+            .values_mut()
+            .for_each(|asset| resolve(&mut asset.url));
+        info.signatures.values_mut().for_each(resolve);
+        info
+    }
+}
+
+/// Whether a URL may be used: HTTPS, HTTP on localhost, `file://` for mirrors
+/// and any HTTP when explicitly allowed.
+fn is_allowed_url(url: &str, layout: Layout, allow_http: bool) -> bool {
+    let url_lower = url.to_lowercase();
+    url_lower.starts_with("https://")
+        || url_lower.starts_with("http://localhost")
+        || url_lower.starts_with("http://127.0.0.1")
+        || (allow_http && url_lower.starts_with("http://"))
+        || (layout == Layout::Mirror && url_lower.starts_with("file://"))
+}
+
+/// Local path of a `file://` URL.
+fn file_path(url: &str) -> Option<&str> {
+    url.strip_prefix("file://")
 }
 
 impl Default for CortexSoftwareClient {
@@ -324,6 +485,75 @@ pub fn platform_key() -> String {
 mod tests {
     use super::*;
 
+    fn release(asset_url: &str) -> String {
+        serde_json::json!({
+            "version": "9.9.9",
+            "channel": "stable",
+            "released_at": "2026-01-01T00:00:00Z",
+            "assets": {
+                platform_key(): {"url": asset_url, "sha256": "00", "size": 7}
+            },
+            "signatures": {
+                platform_key(): format!("{asset_url}.minisig")
+            }
+        })
+        .to_string()
+    }
+
+    #[tokio::test]
+    async fn test_directory_mirror() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir_all(dir.path().join("channels")).unwrap();
+        std::fs::write(
+            dir.path().join("channels/stable.json"),
+            release("files/cortex.tar.gz"),
+        )
+        .unwrap();
+        std::fs::create_dir_all(dir.path().join("files")).unwrap();
+        std::fs::write(dir.path().join("files/cortex.tar.gz"), b"archive").unwrap();
+        std::fs::write(dir.path().join("files/cortex.tar.gz.minisig"), b"sig").unwrap();
+
+        let client = CortexSoftwareClient::mirror(dir.path().to_str().unwrap(), false).unwrap();
+        assert!(client.is_mirror());
+        let latest = client.get_latest(ReleaseChannel::Stable).await.unwrap();
+        assert_eq!(latest.version, "9.9.9");
+        let asset = latest.asset_for_current_platform().unwrap();
+        assert!(asset.url.starts_with("file://"));
+        assert!(asset.url.ends_with("/files/cortex.tar.gz"));
+
+        let dest = dir.path().join("download");
+        let mut progress = (0, 0);
+        client
+            .download(asset, &dest, |done, total| progress = (done, total))
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&dest).unwrap(), b"archive");
+        assert_eq!(progress, (7, 7));
+
+        let signature_url = latest.signature_for_current_platform().unwrap();
+        let signature = client.download_signature(signature_url).await.unwrap();
+        assert_eq!(signature, b"sig");
+
+        assert!(matches!(
+            client.get_release("1.0.0").await,
+            Err(UpdateError::VersionNotFound { .. })
+        ));
+        assert!(matches!(
+            client.get_latest(ReleaseChannel::Beta).await,
+            Err(UpdateError::ServerError { status: 404, .. })
+        ));
+    }
+
+    #[test]
+    fn test_url_policy() {
+        assert!(CortexSoftwareClient::with_url("http://updates.corp".to_string()).is_err());
+        assert!(CortexSoftwareClient::mirror("http://updates.corp", false).is_err());
+        assert!(CortexSoftwareClient::mirror("http://updates.corp/", true).is_ok());
+        assert!(CortexSoftwareClient::mirror("https://updates.corp", false).is_ok());
+        // Only mirrors read local files.
+        assert!(!is_allowed_url("file:///tmp/cortex", Layout::Api, true));
+    }
+
     #[test]
     fn test_platform_key() {
         let key = platform_key();
@@ -75,6 +75,20 @@ pub struct UpdateConfig {
     /// Custom software distribution URL (for testing/enterprise)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub custom_url: Option<String>,
+
+    /// Self-hosted update channel: a local directory, `file://` URL or HTTP(S)
+    /// base URL serving release manifests as static files (overrides `custom_url`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mirror: Option<String>,
+
+    /// Verify release signatures against the trusted public keys
+    #[serde(default = "default_true")]
+    pub verify_signatures: bool,
+
+    /// Minisign public keys trusted to sign releases, in addition to the
+    /// release key built into this binary
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_keys: Vec<String>,
 }
 
 fn default_true() -> bool {
@@ -95,6 +109,9 @@ impl Default for UpdateConfig {
             skip_version: None,
             last_notified_version: None,
             custom_url: None,
+            mirror: None,
+            verify_signatures: true,
+            trusted_keys: Vec::new(),
         }
     }
 }
@@ -148,4 +165,33 @@ impl UpdateConfig {
     pub fn is_version_skipped(&self, version: &str) -> bool {
         self.skip_version.as_deref() == Some(version)
     }
+
+    /// Public keys release signatures are checked against: the pinned
+    /// release key followed by the configured keys.
+    pub fn trusted_keys(&self) -> Vec<String> {
+        crate::RELEASE_PUBLIC_KEY
+            .into_iter()
+            .map(str::to_string)
+            .chain(self.trusted_keys.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_self_hosted_config() {
+        let config: UpdateConfig = serde_json::from_str(
+            r#"{"mirror": "/srv/cortex-updates", "trusted_keys": ["RWQ-internal-key"]}"#,
+        )
+        .unwrap();
+        assert_eq!(config.mirror.as_deref(), Some("/srv/cortex-updates"));
+        assert!(config.verify_signatures);
+        assert_eq!(
+            config.trusted_keys().last().map(String::as_str),
+            Some("RWQ-internal-key")
+        );
+    }
 }
@@ -40,8 +40,22 @@ pub enum UpdateError {
     #[error("SHA256 verification failed: expected {expected}, got {actual}")]
     ChecksumMismatch { expected: String, actual: String },
 
-    #[error("Signature verification failed")]
-    SignatureInvalid,
+    #[error("Signature verification failed: {reason}")]
+    SignatureInvalid { reason: String },
+
+    #[error("No release signature published for platform {platform}")]
+    SignatureMissing { platform: String },
+
+    #[error("Release signature was made with a key that is not trusted")]
+    UntrustedKey,
+
+    #[error("Invalid trusted public key {key}")]
+    InvalidPublicKey { key: String },
+
+    #[error(
+        "No trusted release keys configured; add one to `trusted_keys` in ~/.cortex/update.json"
+    )]
+    NoTrustedKeys,
 
     // Installation errors
     #[error("Installation failed: {message}")]
@@ -62,6 +76,9 @@ pub enum UpdateError {
     #[error("Update requires restart: {message}")]
     RequiresRestart { message: String },
 
+    #[error("No previous version to roll back to")]
+    NoPreviousVersion,
+
     // Archive errors
     #[error("Failed to extract archive: {message}")]
     ExtractionFailed { message: String },
@@ -3,8 +3,10 @@
 use std::path::{Path, PathBuf};
 use tokio::process::Command;
 
+use crate::CURRENT_VERSION;
 use crate::error::{UpdateError, UpdateResult};
 use crate::method::InstallMethod;
+use crate::rollback::RollbackStore;
 
 /// A downloaded and verified update ready for installation.
 #[derive(Debug)]
@@ -36,12 +38,22 @@ impl DownloadedUpdate {
 /// Installer for Cortex CLI updates.
 pub struct Installer {
     method: InstallMethod,
+    rollback: Option<RollbackStore>,
 }
 
 impl Installer {
     /// Create a new installer.
     pub fn new(method: InstallMethod) -> Self {
-        Self { method }
+        Self {
+            method,
+            rollback: None,
+        }
+    }
+
+    /// Keep the replaced binary in `store` so the update can be rolled back.
+    pub fn with_rollback(mut self, store: RollbackStore) -> Self {
+        self.rollback = Some(store);
+        self
     }
 
     /// Install the update.
@@ -98,12 +110,31 @@ impl Installer {
             .extract_archive(&download.archive_path, temp_dir.path())
             .await?;
 
-        // 3. Replace current binary
+        // 3. Keep the current binary for rollback
+        if let Some(store) = &self.rollback {
+            let current = std::env::current_exe()?;
+            store.keep(&current, CURRENT_VERSION)?;
+        }
+
+        // 4. Replace current binary
         self.replace_binary(&binary_path).await?;
 
         Ok(())
     }
 
+    /// Put a previously kept binary back in place of the current one.
+    pub async fn restore(&self, binary: &Path) -> UpdateResult<()> {
+        if self.method.uses_package_manager() {
+            return Err(UpdateError::UnsupportedMethod {
+                method: format!(
+                    "{} (reinstall the previous version with the package manager)",
+                    self.method.description()
+                ),
+            });
+        }
+        self.replace_binary(binary).await
+    }
+
     /// Extract the archive and return path to the binary.
     async fn extract_archive(&self, archive: &Path, dest: &Path) -> UpdateResult<PathBuf> {
         let archive_str = archive.to_string_lossy().to_lowercase();
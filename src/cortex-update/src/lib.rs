@@ -2,8 +2,11 @@
 //!
 //! Provides automatic update checking and installation via:
 //! - Cortex Foundation software distribution API
+//! - Self-hosted mirrors serving the same release manifests from a local
+//!   directory or internal HTTP server
+//! - Minisign signature verification against pinned public keys
 //! - Multiple installation method detection (npm, brew, choco, etc.)
-//! - Cross-platform binary replacement
+//! - Cross-platform binary replacement, keeping the previous binary for rollback
 //!
 //! # Example
 //!
@@ -17,8 +20,8 @@
 //!     println!("Update available: {} -> {}", info.current_version, info.latest_version);
 //!     
 //!     // Download and install
-//!     let download = manager.download_update(&info).await?;
-//!     manager.verify(&download)?;
+//!     let mut download = manager.download_update(&info, |_| {}).await?;
+//!     manager.verify(&mut download, &info).await?;
 //!     manager.install(&download).await?;
 //! }
 //! ```
@@ -30,6 +33,7 @@ mod error;
 mod install;
 mod manager;
 mod method;
+mod rollback;
 mod verify;
 mod version;
 
@@ -40,6 +44,7 @@ pub use error::{UpdateError, UpdateResult};
 pub use install::DownloadedUpdate;
 pub use manager::{UpdateInfo, UpdateManager, UpdateOutcome};
 pub use method::InstallMethod;
+pub use rollback::{PreviousVersion, RollbackStore};
 pub use version::VersionCache;
 
 /// Current version of Cortex CLI (set at compile time)
@@ -47,3 +52,7 @@ pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 /// Default software distribution URL
 pub const SOFTWARE_URL: &str = "https://software.cortex.foundation";
+
+/// Minisign public key official releases are signed with, pinned at build time
+/// through `CORTEX_RELEASE_PUBLIC_KEY`
+pub const RELEASE_PUBLIC_KEY: Option<&str> = option_env!("CORTEX_RELEASE_PUBLIC_KEY");
@@ -1,13 +1,14 @@
 //! Update manager - main API for update operations.
 
 use crate::CURRENT_VERSION;
-use crate::api::{CortexSoftwareClient, ReleaseAsset, ReleaseInfo};
+use crate::api::{CortexSoftwareClient, ReleaseAsset, ReleaseInfo, platform_key};
 use crate::config::{ReleaseChannel, UpdateConfig};
 use crate::download::{DownloadProgress, Downloader};
 use crate::error::{UpdateError, UpdateResult};
 use crate::install::{DownloadedUpdate, Installer};
 use crate::method::InstallMethod;
-use crate::verify::verify_sha256;
+use crate::rollback::{PreviousVersion, RollbackStore};
+use crate::verify::{check_signed_file, verify_sha256, verify_signature};
 use crate::version::{VersionCache, VersionComparison, compare_versions};
 
 /// Information about an available update.
@@ -25,6 +26,8 @@ pub struct UpdateInfo {
     pub release_notes: Option<String>,
     /// Asset for current platform
     pub asset: ReleaseAsset,
+    /// Signature URL for the asset
+    pub signature_url: Option<String>,
     /// Detected installation method
     pub install_method: InstallMethod,
 }
@@ -40,6 +43,8 @@ pub enum UpdateOutcome {
     Skipped,
     /// Requires restart to complete
     RequiresRestart,
+    /// Previous binary restored
+    RolledBack { from: String, to: String },
 }
 
 /// Manager for update operations.
@@ -47,6 +52,7 @@ pub struct UpdateManager {
     client: CortexSoftwareClient,
     config: UpdateConfig,
     install_method: InstallMethod,
+    rollback: Option<RollbackStore>,
 }
 
 impl UpdateManager {
@@ -58,21 +64,30 @@ impl UpdateManager {
 
     /// Create with a specific config.
     pub fn with_config(config: UpdateConfig) -> UpdateResult<Self> {
-        let client = if let Some(url) = &config.custom_url {
+        let client = if let Some(mirror) = &config.mirror {
+            CortexSoftwareClient::mirror(mirror, config.verify_signatures)?
+        } else if let Some(url) = &config.custom_url {
             CortexSoftwareClient::with_url(url.clone())?
         } else {
             CortexSoftwareClient::new()
         };
 
         let install_method = InstallMethod::detect();
+        let rollback = RollbackStore::default_dir().map(RollbackStore::new);
 
         Ok(Self {
             client,
             config,
             install_method,
+            rollback,
         })
     }
 
+    /// Where releases are looked up: the API URL or the mirror.
+    pub fn update_source(&self) -> &str {
+        self.client.base_url()
+    }
+
     /// Get the current configuration.
     pub fn config(&self) -> &UpdateConfig {
         &self.config
@@ -123,12 +138,18 @@ impl UpdateManager {
         }
     }
 
+    /// Look up a specific release.
+    pub async fn get_version(&self, version: &str) -> UpdateResult<UpdateInfo> {
+        let release = self.client.get_release(version).await?;
+        self.build_update_info(&release)
+    }
+
     /// Build UpdateInfo from ReleaseInfo.
     fn build_update_info(&self, release: &ReleaseInfo) -> UpdateResult<UpdateInfo> {
         let asset = release
             .asset_for_current_platform()
             .ok_or_else(|| UpdateError::NoPlatformAsset {
-                platform: crate::api::platform_key(),
+                platform: platform_key(),
             })?
             .clone();
 
@@ -139,6 +160,7 @@ impl UpdateManager {
             changelog_url: release.changelog_url.clone(),
             release_notes: release.release_notes.clone(),
             asset,
+            signature_url: release.signature_for_current_platform().cloned(),
             install_method: self.install_method,
         })
     }
@@ -160,20 +182,51 @@ impl UpdateManager {
         Ok(DownloadedUpdate::new(path, info.latest_version.clone()))
     }
 
-    /// Verify a downloaded update.
+    /// Verify a downloaded update: its SHA256 checksum and, unless disabled in
+    /// the config, its signature against the trusted public keys.
     pub async fn verify(
         &self,
         download: &mut DownloadedUpdate,
-        expected_sha256: &str,
+        info: &UpdateInfo,
     ) -> UpdateResult<()> {
-        verify_sha256(&download.archive_path, expected_sha256).await?;
+        verify_sha256(&download.archive_path, &info.asset.sha256).await?;
+
+        if self.config.verify_signatures {
+            let url =
+                info.signature_url
+                    .as_deref()
+                    .ok_or_else(|| UpdateError::SignatureMissing {
+                        platform: platform_key(),
+                    })?;
+            let signature = self.client.download_signature(url).await?;
+            let signature =
+                String::from_utf8(signature).map_err(|_| UpdateError::SignatureInvalid {
+                    reason: "signature file is not text".to_string(),
+                })?;
+            let trusted_comment = verify_signature(
+                &download.archive_path,
+                &signature,
+                &self.config.trusted_keys(),
+            )
+            .await?;
+            let file_name = info.asset.url.rsplit('/').next().unwrap_or_default();
+            check_signed_file(&trusted_comment, file_name)?;
+        } else {
+            tracing::warn!(
+                "Release signature verification is disabled; only the checksum was checked"
+            );
+        }
+
         download.mark_verified();
         Ok(())
     }
 
     /// Install a verified update.
     pub async fn install(&self, download: &DownloadedUpdate) -> UpdateResult<UpdateOutcome> {
-        let installer = Installer::new(self.install_method);
+        let mut installer = Installer::new(self.install_method);
+        if let Some(store) = &self.rollback {
+            installer = installer.with_rollback(store.clone());
+        }
         installer.install(download).await?;
 
         Ok(UpdateOutcome::Updated {
@@ -197,12 +250,39 @@ impl UpdateManager {
         let mut download = self.download_update(&info, on_progress).await?;
 
         // Verify
-        self.verify(&mut download, &info.asset.sha256).await?;
+        self.verify(&mut download, &info).await?;
 
         // Install
         self.install(&download).await
     }
 
+    /// The binary kept by the last upgrade, if any.
+    pub fn previous_version(&self) -> Option<PreviousVersion> {
+        self.rollback.as_ref()?.previous()
+    }
+
+    /// Restore the binary replaced by the last upgrade.
+    ///
+    /// The version rolled back from is skipped so it is not offered again.
+    pub async fn rollback(&mut self) -> UpdateResult<UpdateOutcome> {
+        let store = self
+            .rollback
+            .clone()
+            .ok_or(UpdateError::NoPreviousVersion)?;
+        let previous = store.previous().ok_or(UpdateError::NoPreviousVersion)?;
+
+        Installer::new(self.install_method)
+            .restore(&previous.binary)
+            .await?;
+        store.clear()?;
+        self.skip_version(CURRENT_VERSION)?;
+
+        Ok(UpdateOutcome::RolledBack {
+            from: CURRENT_VERSION.to_string(),
+            to: previous.version,
+        })
+    }
+
     /// Skip a version (don't prompt for it again).
     pub fn skip_version(&mut self, version: &str) -> UpdateResult<()> {
         self.config.skip_version = Some(version.to_string());
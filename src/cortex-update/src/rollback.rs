@@ -0,0 +1,109 @@
+//! Keeping the replaced binary for `cortex upgrade --rollback`.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::error::{UpdateError, UpdateResult};
+
+/// The binary replaced by the last self-update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PreviousVersion {
+    /// Version of the kept binary
+    pub version: String,
+    /// Where the kept binary is stored
+    pub binary: PathBuf,
+    /// When it was replaced
+    pub replaced_at: DateTime<Utc>,
+}
+
+/// Directory holding the previous binary and its metadata.
+#[derive(Debug, Clone)]
+pub struct RollbackStore {
+    dir: PathBuf,
+}
+
+impl RollbackStore {
+    /// Use `dir` to keep the previous binary.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// The standard location (~/.cortex/rollback).
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::home_dir().map(|h| h.join(".cortex").join("rollback"))
+    }
+
+    fn metadata_path(&self) -> PathBuf {
+        self.dir.join("previous.json")
+    }
+
+    /// Copy `binary` into the store as the previous version, replacing any
+    /// binary kept before.
+    pub fn keep(&self, binary: &Path, version: &str) -> UpdateResult<PreviousVersion> {
+        self.clear()?;
+        std::fs::create_dir_all(&self.dir)?;
+
+        let file_name = match binary.extension() {
+            Some(ext) => format!("cortex-{}.{}", version, ext.to_string_lossy()),
+            None => format!("cortex-{}", version),
+        };
+        let kept = self.dir.join(file_name);
+        // std::fs::copy carries the permissions over, so the copy stays executable.
+        std::fs::copy(binary, &kept).map_err(|e| UpdateError::InstallFailed {
+            message: format!("Failed to keep previous binary {}: {}", binary.display(), e),
+        })?;
+
+        let previous = PreviousVersion {
+            version: version.to_string(),
+            binary: kept,
+            replaced_at: Utc::now(),
+        };
+        std::fs::write(
+            self.metadata_path(),
+            serde_json::to_string_pretty(&previous)?,
+        )?;
+        Ok(previous)
+    }
+
+    /// The kept binary, if there is one and it still exists.
+    pub fn previous(&self) -> Option<PreviousVersion> {
+        let content = std::fs::read_to_string(self.metadata_path()).ok()?;
+        let previous: PreviousVersion = serde_json::from_str(&content).ok()?;
+        previous.binary.exists().then_some(previous)
+    }
+
+    /// Remove the kept binary.
+    pub fn clear(&self) -> UpdateResult<()> {
+        if self.dir.exists() {
+            std::fs::remove_dir_all(&self.dir)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keep_replaces_previous() {
+        let dir = tempfile::tempdir().unwrap();
+        let binary = dir.path().join("cortex");
+        std::fs::write(&binary, b"v1").unwrap();
+        let store = RollbackStore::new(dir.path().join("rollback"));
+        assert!(store.previous().is_none());
+
+        store.keep(&binary, "0.1.0").unwrap();
+        std::fs::write(&binary, b"v2").unwrap();
+        store.keep(&binary, "0.2.0").unwrap();
+
+        let previous = store.previous().unwrap();
+        assert_eq!(previous.version, "0.2.0");
+        assert_eq!(std::fs::read(&previous.binary).unwrap(), b"v2");
+        assert!(!dir.path().join("rollback/cortex-0.1.0").exists());
+
+        store.clear().unwrap();
+        assert!(store.previous().is_none());
+    }
+}
@@ -1,5 +1,6 @@
-//! SHA256 verification for downloaded files.
+//! SHA256 and signature verification for downloaded files.
 
+use minisign_verify::{PublicKey, Signature};
 use sha2::{Digest, Sha256};
 use std::path::Path;
 use tokio::io::AsyncReadExt;
@@ -68,6 +69,99 @@ pub async fn calculate_sha256(path: &Path) -> UpdateResult<String> {
     Ok(hex::encode(result))
 }
 
+/// Verify a detached minisign signature of a file against trusted public keys.
+///
+/// Only prehashed signatures (the minisign default) are accepted. Keys that
+/// fail to parse are skipped with a warning. Returns the signature's trusted
+/// comment.
+pub async fn verify_signature(
+    path: &Path,
+    signature: &str,
+    trusted_keys: &[String],
+) -> UpdateResult<String> {
+    let signature =
+        Signature::decode(signature.trim()).map_err(|e| UpdateError::SignatureInvalid {
+            reason: e.to_string(),
+        })?;
+    let keys: Vec<PublicKey> = trusted_keys
+        .iter()
+        .filter_map(|key| match parse_public_key(key) {
+            Ok(key) => Some(key),
+            Err(e) => {
+                tracing::warn!("Ignoring trusted key: {}", e);
+                None
+            }
+        })
+        .collect();
+    if keys.is_empty() {
+        return Err(UpdateError::NoTrustedKeys);
+    }
+
+    for key in &keys {
+        let mut verifier = match key.verify_stream(&signature) {
+            Ok(verifier) => verifier,
+            Err(minisign_verify::Error::UnexpectedKeyId) => continue,
+            Err(e) => {
+                return Err(UpdateError::SignatureInvalid {
+                    reason: e.to_string(),
+                });
+            }
+        };
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let mut buffer = vec![0u8; 8192];
+        loop {
+            let n = file.read(&mut buffer).await?;
+            if n == 0 {
+                break;
+            }
+            verifier.update(&buffer[..n]);
+        }
+        verifier
+            .finalize()
+            .map_err(|e| UpdateError::SignatureInvalid {
+                reason: e.to_string(),
+            })?;
+        return Ok(signature.trusted_comment().to_string());
+    }
+
+    Err(UpdateError::UntrustedKey)
+}
+
+/// Check that a signature's trusted comment names the file it was checked against.
+///
+/// The trusted comment is covered by the signature, so this stops a mirror from
+/// serving an older signed release, or another asset, under a release's name.
+/// Comments without a `file:` field are rejected.
+pub fn check_signed_file(trusted_comment: &str, file_name: &str) -> UpdateResult<()> {
+    let signed = trusted_comment
+        .split('\t')
+        .find_map(|field| field.strip_prefix("file:"));
+    match signed {
+        Some(signed) if signed == file_name => Ok(()),
+        Some(signed) => Err(UpdateError::SignatureInvalid {
+            reason: format!("signature is for {signed}, not {file_name}"),
+        }),
+        None => Err(UpdateError::SignatureInvalid {
+            reason: format!("signature does not name the signed file; expected {file_name}"),
+        }),
+    }
+}
+
+/// Parse a minisign public key, either the bare base64 key or the contents of
+/// a `minisign.pub` file.
+fn parse_public_key(key: &str) -> UpdateResult<PublicKey> {
+    let key = key.trim();
+    let parsed = if key.contains('\n') {
+        PublicKey::decode(key)
+    } else {
+        PublicKey::from_base64(key)
+    };
+    parsed.map_err(|_| UpdateError::InvalidPublicKey {
+        key: key.to_string(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -96,6 +190,68 @@ mod tests {
         assert!(matches!(result, Err(UpdateError::ChecksumMismatch { .. })));
     }
 
+    // Test vector from minisign: the prehashed signature of "test".
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RUQf6LRCGA9i559r3g7V1qNyJDApGip8MfqcadIgT9CuhV3EMhHoN1mGTkUidF/z7SrlQgXdy8ofjb7bNJJylDOocrCo8KLzZwo=
+trusted comment: timestamp:1556193335\tfile:test
+y/rUw2y8/hOUYjZU71eHp/Wo1KZ40fGy2VJEDl34XMJM+TX48Ss/17u3IvIfbVR1FkZZSNCisQbuQY+bHwhEBg==
+";
+
+    #[tokio::test]
+    async fn test_verify_signature() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "test").unwrap();
+
+        let comment = verify_signature(file.path(), SIGNATURE, &[PUBLIC_KEY.to_string()])
+            .await
+            .unwrap();
+        assert_eq!(comment, "timestamp:1556193335\tfile:test");
+        check_signed_file(&comment, "test").unwrap();
+        assert!(matches!(
+            check_signed_file(&comment, "cortex-0.2.0.tar.gz"),
+            Err(UpdateError::SignatureInvalid { .. })
+        ));
+        assert!(matches!(
+            check_signed_file("timestamp:1556193335", "test"),
+            Err(UpdateError::SignatureInvalid { .. })
+        ));
+
+        let pub_file = format!("untrusted comment: minisign public key\n{PUBLIC_KEY}\n");
+        verify_signature(file.path(), SIGNATURE, &[pub_file])
+            .await
+            .unwrap();
+
+        // An unparsable key does not stop the others from being tried.
+        let keys = ["not a key".to_string(), PUBLIC_KEY.to_string()];
+        verify_signature(file.path(), SIGNATURE, &keys)
+            .await
+            .unwrap();
+        let result = verify_signature(file.path(), SIGNATURE, &["not a key".to_string()]).await;
+        assert!(matches!(result, Err(UpdateError::NoTrustedKeys)));
+    }
+
+    #[tokio::test]
+    async fn test_verify_signature_rejects() {
+        let mut file = NamedTempFile::new().unwrap();
+        write!(file, "tampered").unwrap();
+        let trusted = [PUBLIC_KEY.to_string()];
+
+        let result = verify_signature(file.path(), SIGNATURE, &trusted).await;
+        assert!(matches!(result, Err(UpdateError::SignatureInvalid { .. })));
+
+        // A well-formed key with a different key id is not trusted.
+        let other = "RWQAAAAAAAAAAHmlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3".to_string();
+        let result = verify_signature(file.path(), SIGNATURE, &[other]).await;
+        assert!(matches!(result, Err(UpdateError::UntrustedKey)));
+
+        let result = verify_signature(file.path(), SIGNATURE, &[]).await;
+        assert!(matches!(result, Err(UpdateError::NoTrustedKeys)));
+
+        let result = verify_signature(file.path(), "not a signature", &trusted).await;
+        assert!(matches!(result, Err(UpdateError::SignatureInvalid { .. })));
+    }
+
     #[tokio::test]
     async fn test_calculate_sha256() {
         let mut file = NamedTempFile::new().unwrap();